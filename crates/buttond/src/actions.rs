@@ -0,0 +1,211 @@
+//! What each kind of press does. `buttond.actions` maps `single`, `double`,
+//! and `long` presses to one of a fixed set of verbs. Entries left out keep
+//! the original wiring: a single or long press toggles sleep and a double
+//! press shuts the frame down.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Action, CommandConfig, CommandSpec};
+
+/// A verb as written in `buttond.actions`: a bare name, or
+/// `custom-command:` with a command block.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum VerbConfig {
+    ToggleSleep,
+    Wake,
+    Sleep,
+    Shutdown,
+    NextPhoto,
+    CustomCommand(CommandConfig),
+}
+
+impl VerbConfig {
+    fn into_verb(self, press: Action) -> Verb {
+        match self {
+            Self::ToggleSleep => Verb::ToggleSleep,
+            Self::Wake => Verb::Wake,
+            Self::Sleep => Verb::Sleep,
+            Self::Shutdown => Verb::Shutdown,
+            Self::NextPhoto => Verb::NextPhoto,
+            Self::CustomCommand(command) => {
+                Verb::CustomCommand(command.into_spec(&format!("{}-press", press.as_str())))
+            }
+        }
+    }
+}
+
+/// The `buttond.actions` block.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct ActionsConfig {
+    #[serde(
+        default = "ActionsConfig::default_single",
+        with = "serde_yaml::with::singleton_map"
+    )]
+    single: VerbConfig,
+    #[serde(
+        default = "ActionsConfig::default_double",
+        with = "serde_yaml::with::singleton_map"
+    )]
+    double: VerbConfig,
+    /// A press held past `single-window-ms`.
+    #[serde(
+        default = "ActionsConfig::default_long",
+        with = "serde_yaml::with::singleton_map"
+    )]
+    long: VerbConfig,
+}
+
+impl ActionsConfig {
+    const fn default_single() -> VerbConfig {
+        VerbConfig::ToggleSleep
+    }
+
+    const fn default_double() -> VerbConfig {
+        VerbConfig::Shutdown
+    }
+
+    const fn default_long() -> VerbConfig {
+        VerbConfig::ToggleSleep
+    }
+
+    pub(crate) fn into_actions(self) -> ButtonActions {
+        ButtonActions {
+            single: self.single.into_verb(Action::Single),
+            double: self.double.into_verb(Action::Double),
+            long: self.long.into_verb(Action::Long),
+        }
+    }
+}
+
+impl Default for ActionsConfig {
+    fn default() -> Self {
+        Self {
+            single: Self::default_single(),
+            double: Self::default_double(),
+            long: Self::default_long(),
+        }
+    }
+}
+
+/// What `Runtime` does for a press.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Verb {
+    ToggleSleep,
+    Wake,
+    Sleep,
+    /// Runs `buttond.shutdown-command`.
+    Shutdown,
+    /// Sends `{"command":"next"}` to the viewer's control socket.
+    NextPhoto,
+    CustomCommand(CommandSpec),
+}
+
+impl Verb {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Self::ToggleSleep => "toggle-sleep",
+            Self::Wake => "wake",
+            Self::Sleep => "sleep",
+            Self::Shutdown => "shutdown",
+            Self::NextPhoto => "next-photo",
+            Self::CustomCommand(_) => "custom-command",
+        }
+    }
+}
+
+/// The verb bound to each kind of press.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ButtonActions {
+    single: Verb,
+    double: Verb,
+    long: Verb,
+}
+
+impl ButtonActions {
+    pub(crate) fn verb_for(&self, press: Action) -> &Verb {
+        match press {
+            Action::Single => &self.single,
+            Action::Double => &self.double,
+            Action::Long => &self.long,
+        }
+    }
+}
+
+impl Default for ButtonActions {
+    fn default() -> Self {
+        ActionsConfig::default().into_actions()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn missing_entries_keep_the_original_wiring() {
+        let actions = serde_yaml::from_str::<ActionsConfig>("{}")
+            .unwrap()
+            .into_actions();
+        assert_eq!(actions, ButtonActions::default());
+        assert_eq!(actions.verb_for(Action::Single), &Verb::ToggleSleep);
+        assert_eq!(actions.verb_for(Action::Double), &Verb::Shutdown);
+        assert_eq!(actions.verb_for(Action::Long), &Verb::ToggleSleep);
+
+        let actions = serde_yaml::from_str::<ActionsConfig>("single: next-photo\n")
+            .unwrap()
+            .into_actions();
+        assert_eq!(actions.verb_for(Action::Single), &Verb::NextPhoto);
+        assert_eq!(actions.verb_for(Action::Double), &Verb::Shutdown);
+    }
+
+    #[test]
+    fn every_verb_parses_and_custom_commands_get_a_label() {
+        let yaml = r#"
+single: next-photo
+double: toggle-sleep
+long:
+  custom-command:
+    program: /usr/local/bin/chime
+    args: [--loud]
+"#;
+        let actions = serde_yaml::from_str::<ActionsConfig>(yaml)
+            .unwrap()
+            .into_actions();
+        assert_eq!(actions.verb_for(Action::Double), &Verb::ToggleSleep);
+        assert_eq!(
+            actions.verb_for(Action::Long),
+            &Verb::CustomCommand(CommandSpec {
+                label: "long-press".into(),
+                program: PathBuf::from("/usr/local/bin/chime"),
+                args: vec!["--loud".into()],
+            })
+        );
+        for verb in ["wake", "sleep", "shutdown"] {
+            let parsed = serde_yaml::from_str::<ActionsConfig>(&format!("single: {verb}\n"))
+                .unwrap()
+                .into_actions();
+            assert_eq!(parsed.verb_for(Action::Single).as_str(), verb);
+        }
+    }
+
+    #[test]
+    fn unknown_verbs_and_presses_are_rejected() {
+        let err = serde_yaml::from_str::<ActionsConfig>("single: reboot\n")
+            .unwrap_err()
+            .to_string();
+        for allowed in [
+            "toggle-sleep",
+            "wake",
+            "sleep",
+            "shutdown",
+            "next-photo",
+            "custom-command",
+        ] {
+            assert!(err.contains(allowed), "{err}");
+        }
+        assert!(serde_yaml::from_str::<ActionsConfig>("triple: wake\n").is_err());
+    }
+}
@@ -0,0 +1,281 @@
+//! Where button presses come from: an evdev device advertising `KEY_POWER`, or
+//! a momentary switch wired to a GPIO line. Both backends hand the same
+//! press/release edges to `ButtonTracker`, so debounce and single/double
+//! detection behave identically whichever one is in use.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Instant;
+
+use anyhow::{Context, Result, anyhow};
+use evdev::{Device, EventSummary, KeyCode};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+/// Which backend `buttond.input` selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum InputBackend {
+    Evdev,
+    Gpio,
+}
+
+/// The `buttond.gpio` block.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct GpioConfig {
+    #[serde(default = "GpioConfig::default_chip")]
+    pub(crate) chip: PathBuf,
+    /// Line offset on the chip, as listed by `gpioinfo`.
+    pub(crate) line: u32,
+    #[serde(default = "GpioConfig::default_active")]
+    pub(crate) active: ActiveLevel,
+    #[serde(default = "GpioConfig::default_pull")]
+    pub(crate) pull: Pull,
+}
+
+impl GpioConfig {
+    fn default_chip() -> PathBuf {
+        PathBuf::from("/dev/gpiochip0")
+    }
+
+    const fn default_active() -> ActiveLevel {
+        ActiveLevel::Low
+    }
+
+    const fn default_pull() -> Pull {
+        Pull::Up
+    }
+}
+
+/// The level the line sits at while the button is held. A switch to ground
+/// with the internal pull-up is active low.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ActiveLevel {
+    Low,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum Pull {
+    Up,
+    Down,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Edge {
+    Pressed,
+    Released,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ButtonEvent {
+    pub(crate) edge: Edge,
+    pub(crate) at: Instant,
+}
+
+/// A non-blocking supply of button edges.
+pub(crate) trait ButtonSource {
+    /// Edges that arrived since the last call, oldest first; empty when the
+    /// button has not moved.
+    fn poll(&mut self) -> Result<Vec<ButtonEvent>>;
+}
+
+pub(crate) struct EvdevSource {
+    device: Device,
+}
+
+impl EvdevSource {
+    /// Takes a device already opened non-blocking.
+    pub(crate) fn new(device: Device) -> Self {
+        Self { device }
+    }
+}
+
+impl ButtonSource for EvdevSource {
+    fn poll(&mut self) -> Result<Vec<ButtonEvent>> {
+        let events = match self.device.fetch_events() {
+            Ok(events) => events,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => return Ok(Vec::new()),
+            Err(err) => return Err(err).context("failed reading input events"),
+        };
+        let at = Instant::now();
+        Ok(events
+            .filter_map(|event| match event.destructure() {
+                EventSummary::Key(_, KeyCode::KEY_POWER, 1) => Some(Edge::Pressed),
+                EventSummary::Key(_, KeyCode::KEY_POWER, 0) => Some(Edge::Released),
+                _ => None,
+            })
+            .map(|edge| ButtonEvent { edge, at })
+            .collect())
+    }
+}
+
+/// Edge events from one GPIO line. The kernel only delivers them through a
+/// blocking read, so a thread waits on the line and forwards each edge.
+pub(crate) struct GpioSource {
+    events: Receiver<io::Result<ButtonEvent>>,
+}
+
+impl GpioSource {
+    pub(crate) fn open(config: &GpioConfig) -> Result<Self> {
+        let options = gpiod::Options::input([config.line])
+            .edge(gpiod::EdgeDetect::Both)
+            .active(match config.active {
+                ActiveLevel::Low => gpiod::Active::Low,
+                ActiveLevel::High => gpiod::Active::High,
+            })
+            .bias(match config.pull {
+                Pull::Up => gpiod::Bias::PullUp,
+                Pull::Down => gpiod::Bias::PullDown,
+                Pull::None => gpiod::Bias::Disable,
+            })
+            .consumer("buttond");
+        let chip = gpiod::Chip::new(&config.chip)
+            .with_context(|| format!("failed to open {}", config.chip.display()))?;
+        let mut lines = chip.request_lines(options).with_context(|| {
+            format!(
+                "failed to request line {} on {}",
+                config.line,
+                config.chip.display()
+            )
+        })?;
+
+        let (tx, events) = mpsc::channel();
+        thread::Builder::new()
+            .name("buttond-gpio".into())
+            .spawn(move || {
+                loop {
+                    // With the line configured active-low the kernel reports the
+                    // press as the rising edge, so edges map the same either way.
+                    let event = lines.read_event().map(|event| ButtonEvent {
+                        edge: match event.edge {
+                            gpiod::Edge::Rising => Edge::Pressed,
+                            gpiod::Edge::Falling => Edge::Released,
+                        },
+                        at: Instant::now(),
+                    });
+                    let failed = event.is_err();
+                    if tx.send(event).is_err() || failed {
+                        break;
+                    }
+                }
+            })
+            .context("failed to spawn GPIO reader thread")?;
+        Ok(Self { events })
+    }
+}
+
+impl ButtonSource for GpioSource {
+    fn poll(&mut self) -> Result<Vec<ButtonEvent>> {
+        let mut edges = Vec::new();
+        loop {
+            match self.events.try_recv() {
+                Ok(Ok(event)) => edges.push(event),
+                Ok(Err(err)) => return Err(err).context("failed reading GPIO edge events"),
+                Err(TryRecvError::Empty) => return Ok(edges),
+                Err(TryRecvError::Disconnected) => {
+                    return Err(anyhow!("GPIO reader thread exited"));
+                }
+            }
+        }
+    }
+}
+
+/// Opens the configured backend. Without `buttond.input`, evdev is tried
+/// first and a configured GPIO line is the fallback when no device
+/// advertises `KEY_POWER`.
+pub(crate) fn open_source(
+    backend: Option<InputBackend>,
+    gpio: Option<&GpioConfig>,
+    open_evdev: impl FnOnce() -> Result<(Device, PathBuf)>,
+) -> Result<(Box<dyn ButtonSource>, String)> {
+    let open_gpio = |config: &GpioConfig| -> Result<(Box<dyn ButtonSource>, String)> {
+        let source = GpioSource::open(config)?;
+        Ok((Box::new(source), describe_gpio(config)))
+    };
+    match (backend, gpio) {
+        (Some(InputBackend::Gpio), Some(config)) => open_gpio(config),
+        (Some(InputBackend::Gpio), None) => Err(anyhow!(
+            "buttond.input is gpio but no buttond.gpio section is configured"
+        )),
+        (Some(InputBackend::Evdev), _) | (None, None) => {
+            let (device, path) = open_evdev()?;
+            Ok((Box::new(EvdevSource::new(device)), describe_evdev(&path)))
+        }
+        (None, Some(config)) => match open_evdev() {
+            Ok((device, path)) => Ok((Box::new(EvdevSource::new(device)), describe_evdev(&path))),
+            Err(err) => {
+                debug!(?err, "no evdev power button; falling back to GPIO");
+                warn!(
+                    line = config.line,
+                    chip = %config.chip.display(),
+                    "no KEY_POWER input device found; using the configured GPIO line"
+                );
+                open_gpio(config)
+            }
+        },
+    }
+}
+
+fn describe_evdev(path: &Path) -> String {
+    path.display().to_string()
+}
+
+fn describe_gpio(config: &GpioConfig) -> String {
+    format!("{} line {}", config.chip.display(), config.line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gpio_block_defaults_to_an_active_low_pulled_up_line() {
+        let config: GpioConfig = serde_yaml::from_str("line: 17\n").unwrap();
+        assert_eq!(
+            config,
+            GpioConfig {
+                chip: PathBuf::from("/dev/gpiochip0"),
+                line: 17,
+                active: ActiveLevel::Low,
+                pull: Pull::Up,
+            }
+        );
+        let config: GpioConfig =
+            serde_yaml::from_str("chip: /dev/gpiochip4\nline: 3\nactive: high\npull: none\n")
+                .unwrap();
+        assert_eq!(config.active, ActiveLevel::High);
+        assert_eq!(config.pull, Pull::None);
+        assert!(serde_yaml::from_str::<GpioConfig>("line: 3\nedge: both\n").is_err());
+    }
+
+    #[test]
+    fn gpio_backend_needs_a_gpio_section() {
+        let err = open_source(Some(InputBackend::Gpio), None, || unreachable!())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("buttond.gpio"), "{err}");
+    }
+
+    #[test]
+    fn explicit_evdev_ignores_the_gpio_section() {
+        let gpio = GpioConfig {
+            chip: PathBuf::from("/nonexistent/gpiochip"),
+            line: 3,
+            active: ActiveLevel::Low,
+            pull: Pull::Up,
+        };
+        let err = open_source(Some(InputBackend::Evdev), Some(&gpio), || {
+            Err(anyhow!("no input devices advertising KEY_POWER found"))
+        })
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("KEY_POWER"), "{err}");
+    }
+}
@@ -0,0 +1,76 @@
+//! Resume notifications from systemd-logind (the `logind` feature).
+//!
+//! logind broadcasts `PrepareForSleep(true)` on the system bus just before a
+//! suspend and `PrepareForSleep(false)` once the system is back. The
+//! scheduler only needs the second: it re-evaluates the schedule and
+//! re-reads the panel the moment the frame resumes, instead of whenever its
+//! own clock check next runs.
+
+use std::sync::mpsc;
+use std::thread;
+
+use anyhow::{Context, Result};
+use tracing::{debug, info, warn};
+use zbus::blocking::{Connection, Proxy};
+
+const DESTINATION: &str = "org.freedesktop.login1";
+const PATH: &str = "/org/freedesktop/login1";
+const INTERFACE: &str = "org.freedesktop.login1.Manager";
+const SIGNAL: &str = "PrepareForSleep";
+
+/// Starts a listener thread that sends `()` each time the system resumes.
+/// `None` when the system bus or logind can't be reached; the scheduler
+/// then falls back to noticing the clock jump.
+pub fn watch_resume() -> Option<mpsc::Receiver<()>> {
+    let proxy = match connect() {
+        Ok(proxy) => proxy,
+        Err(err) => {
+            warn!(
+                ?err,
+                "not watching logind for resume; falling back to clock checks"
+            );
+            return None;
+        }
+    };
+    let (tx, rx) = mpsc::channel();
+    let builder = thread::Builder::new().name(String::from("buttond-logind"));
+    match builder.spawn(move || listen(&proxy, &tx)) {
+        Ok(_) => Some(rx),
+        Err(err) => {
+            warn!(?err, "failed to spawn logind listener thread");
+            None
+        }
+    }
+}
+
+fn connect() -> Result<Proxy<'static>> {
+    let connection = Connection::system().context("failed to connect to the system bus")?;
+    Proxy::new(&connection, DESTINATION, PATH, INTERFACE)
+        .context("failed to reach org.freedesktop.login1")
+}
+
+fn listen(proxy: &Proxy<'static>, tx: &mpsc::Sender<()>) {
+    let signals = match proxy.receive_signal(SIGNAL) {
+        Ok(signals) => signals,
+        Err(err) => {
+            warn!(?err, "failed to subscribe to logind {SIGNAL}");
+            return;
+        }
+    };
+    info!("watching logind for suspend and resume");
+    for message in signals {
+        let body = message.body();
+        match body.deserialize::<bool>() {
+            Ok(true) => debug!("system is about to sleep"),
+            Ok(false) => {
+                info!("system resumed from sleep");
+                if tx.send(()).is_err() {
+                    debug!("logind listener exiting after scheduler closed");
+                    return;
+                }
+            }
+            Err(err) => debug!(?err, "ignoring malformed {SIGNAL} signal"),
+        }
+    }
+    warn!("logind signal stream ended; falling back to clock checks");
+}
@@ -1,28 +1,41 @@
+use std::collections::VecDeque;
 use std::env;
 use std::fmt;
 use std::fs;
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
+use std::net::Shutdown;
 use std::os::fd::AsFd;
 use std::os::unix::fs::{FileTypeExt, MetadataExt};
 use std::os::unix::net::UnixStream;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, Output};
 use std::sync::mpsc::{self, TryRecvError};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result, anyhow, bail};
-use chrono::{Duration as ChronoDuration, Utc};
+use chrono::{DateTime, Duration as ChronoDuration, SecondsFormat, Utc};
+use chrono_tz::Tz;
 use clap::Parser;
-use config_model::{AwakeScheduleConfig, GreetingScreenConfig};
-use evdev::{Device, EventSummary, KeyCode};
+use config_model::{
+    AwakeScheduleConfig, GreetingScreenConfig, ScheduleEvaluation, ScheduledState,
+    SleepScreenConfig,
+};
+use evdev::{Device, KeyCode};
 use humantime::format_duration;
 use nix::fcntl::{FcntlArg, OFlag, fcntl};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use tracing::{debug, error, info, warn};
-use tracing_subscriber::EnvFilter;
+
+mod actions;
+mod input;
+#[cfg(feature = "logind")]
+mod logind;
+
+use actions::{ActionsConfig, ButtonActions, Verb};
+use input::{ButtonSource, Edge, GpioConfig, InputBackend, open_source};
 
 #[derive(Debug, Clone, Parser)]
 #[command(
@@ -38,32 +51,66 @@ struct Args {
     #[arg(long)]
     device: Option<PathBuf>,
 
-    /// Logging level (error|warn|info|debug|trace).
-    #[arg(long, default_value = "info")]
-    log_level: String,
+    /// Logging level (error|warn|info|debug|trace); overrides `logging.level`.
+    #[arg(long)]
+    log_level: Option<String>,
+
+    /// Log screen power and shutdown commands instead of running them.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Validate the configuration, print the effective settings, and exit.
+    #[arg(long)]
+    check_config: bool,
+
+    /// Print what the awake schedule says at `--at` (default now) and exit.
+    #[arg(long)]
+    explain_schedule: bool,
+
+    /// Instant for `--explain-schedule`, e.g. 2024-03-10T01:30:00-05:00.
+    #[arg(long, value_name = "RFC3339", requires = "explain_schedule")]
+    at: Option<String>,
+
+    /// Print `--explain-schedule` as JSON.
+    #[arg(long, requires = "explain_schedule")]
+    json: bool,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
-    init_tracing(&args.log_level)?;
+    if args.check_config {
+        print!("{}", check_config(&args.config, args.device.clone())?);
+        return Ok(());
+    }
+    if args.explain_schedule {
+        print!(
+            "{}",
+            explain_schedule(&args.config, args.at.as_deref(), args.json)?
+        );
+        return Ok(());
+    }
+    log_setup::init_from_file(&args.config, args.log_level.as_deref())?;
 
-    let settings = ButtondSettings::load(&args.config, args.device.clone()).with_context(|| {
-        format!(
-            "failed to load configuration from {}",
-            args.config.display()
-        )
-    })?;
+    let settings = load_settings(&args.config, args.device.clone())?;
     let device_override = settings.device.clone();
+    let input = settings.input;
+    let gpio = settings.gpio.clone();
     let durations = settings.durations;
-    let (mut runtime, scheduler_config) = settings.into_runtime()?;
+    if args.dry_run {
+        info!("dry-run enabled: screen power and shutdown commands will only be logged");
+    }
+    let (mut runtime, scheduler_config) = settings.into_runtime(args.dry_run)?;
 
     let mut scheduler_rx =
         scheduler_config.and_then(|config| spawn_scheduler(config, runtime.shared_state()));
 
-    let (mut device, path) = open_device(device_override.as_ref())?;
-    set_nonblocking(&device)
-        .with_context(|| format!("failed to set {} non-blocking", path.display()))?;
-    info!(device = %path.display(), "listening for power button events");
+    let (mut source, description) = open_source(input, gpio.as_ref(), || {
+        let (device, path) = open_device(device_override.as_ref())?;
+        set_nonblocking(&device)
+            .with_context(|| format!("failed to set {} non-blocking", path.display()))?;
+        Ok((device, path))
+    })?;
+    info!(input = %description, "listening for power button events");
 
     let mut tracker = ButtonTracker::new(durations);
 
@@ -82,6 +129,16 @@ fn main() -> Result<()> {
                             error!(?err, "failed to process scheduled sleep");
                         }
                     }
+                    Ok(SchedulerCommand::EnterAmbient) => {
+                        if let Err(err) = runtime.enter_ambient(TransitionSource::Scheduled) {
+                            error!(?err, "failed to process scheduled ambient");
+                        }
+                    }
+                    Ok(SchedulerCommand::WarnSleep { remaining }) => {
+                        if let Err(err) = runtime.warn_of_sleep(remaining) {
+                            error!(?err, "failed to show the sleep warning");
+                        }
+                    }
                     Err(TryRecvError::Empty) => break,
                     Err(TryRecvError::Disconnected) => {
                         warn!("scheduler channel disconnected");
@@ -105,29 +162,14 @@ fn main() -> Result<()> {
             continue;
         }
 
-        let idle = match device.fetch_events() {
-            Ok(events) => {
-                let mut handled = false;
-                for event in events {
-                    handled = true;
-                    if let EventSummary::Key(_, KeyCode::KEY_POWER, value) = event.destructure() {
-                        match value {
-                            1 => {
-                                tracker.on_press(Instant::now());
-                            }
-                            0 => {
-                                if let Some(action) = tracker.on_release(Instant::now()) {
-                                    perform_action(action, &mut runtime);
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
+        let idle = match pump_source(source.as_mut(), &mut tracker)? {
+            Some(actions) => {
+                for action in actions {
+                    perform_action(action, &mut runtime);
                 }
-                !handled
+                false
             }
-            Err(err) if err.kind() == io::ErrorKind::WouldBlock => true,
-            Err(err) => return Err(err).with_context(|| "failed reading input events"),
+            None => true,
         };
 
         if idle {
@@ -145,8 +187,11 @@ fn main() -> Result<()> {
     }
 }
 
+#[derive(Debug)]
 struct ButtondSettings {
+    input: Option<InputBackend>,
     device: Option<PathBuf>,
+    gpio: Option<GpioConfig>,
     durations: Durations,
     control_socket_path: PathBuf,
     shutdown_command: CommandSpec,
@@ -156,6 +201,7 @@ struct ButtondSettings {
     screen_display_name: Option<String>,
     greeting_screen_delay: Duration,
     awake_schedule: Option<AwakeScheduleConfig>,
+    actions: ButtonActions,
 }
 
 const FORCE_SHUTDOWN_FLAG: &str = "-i";
@@ -203,33 +249,189 @@ fn configure_shutdown_flag(args: &mut Vec<String>, flag: &str, enabled: bool) {
     }
 }
 
+fn load_settings(config_path: &Path, device_override: Option<PathBuf>) -> Result<ButtondSettings> {
+    let file_config = load_file_config(config_path, device_override)?;
+    ButtondSettings::from_file(file_config)
+        .with_context(|| format!("invalid configuration in {}", config_path.display()))
+}
+
+/// The shared config file as buttond reads it, with `--device` in place of
+/// `buttond.device`.
+fn load_file_config(config_path: &Path, device_override: Option<PathBuf>) -> Result<FileConfig> {
+    let mut file_config = FileConfig::from_path(config_path).with_context(|| {
+        format!(
+            "failed to load configuration from {}",
+            config_path.display()
+        )
+    })?;
+    if device_override.is_some() {
+        file_config.buttond.device = device_override;
+    }
+    Ok(file_config)
+}
+
+/// `--check-config`: loads and validates the shared config file exactly as
+/// startup does and prints the settings it resolves to as YAML. Opens no
+/// input device and does not talk to sway or the viewer.
+fn check_config(config_path: &Path, device_override: Option<PathBuf>) -> Result<String> {
+    let file_config = load_file_config(config_path, device_override)?;
+    ButtondSettings::from_file(file_config.clone())
+        .with_context(|| format!("invalid configuration in {}", config_path.display()))?;
+    config_model::check_report(config_path, &[], &file_config)
+}
+
+/// `--explain-schedule`: evaluates the awake schedule at `at` (RFC 3339,
+/// default now) the way the scheduler does, and says what would hold the
+/// change off: the greeting on a fresh start, or a press during the sleep
+/// warning.
+fn explain_schedule(config_path: &Path, at: Option<&str>, json: bool) -> Result<String> {
+    let config = load_file_config(config_path, None)?;
+    let Some(schedule) = config.awake_schedule else {
+        return Ok(if json {
+            format!("{}\n", json!({ "schedule": null }))
+        } else {
+            String::from("no awake-schedule configured; the frame stays awake\n")
+        });
+    };
+    let timezone = schedule.timezone();
+    let at = match at {
+        Some(raw) => DateTime::parse_from_rfc3339(raw)
+            .with_context(|| format!("invalid --at instant {raw:?}"))?
+            .with_timezone(&timezone),
+        None => Utc::now().with_timezone(&timezone),
+    };
+    let evaluation = schedule.evaluate(at);
+    let greeting_delay = config.greeting_screen.effective_duration();
+    Ok(if json {
+        format!("{}\n", explanation_json(&evaluation, greeting_delay))
+    } else {
+        explanation_text(&evaluation, greeting_delay)
+    })
+}
+
+/// Until when a wake would wait for the greeting had buttond started at the
+/// evaluated instant, as the scheduler holds its first wake or ambient.
+fn greeting_hold(evaluation: &ScheduleEvaluation, delay: Duration) -> Option<DateTime<Tz>> {
+    let ready_at = evaluation.at + ChronoDuration::from_std(delay).ok()?;
+    let wake_due = evaluation.state != ScheduledState::Asleep
+        || evaluation
+            .next_change
+            .is_some_and(|(at, state)| state != ScheduledState::Asleep && at < ready_at);
+    (wake_due && ready_at > evaluation.at).then_some(ready_at)
+}
+
+fn rfc3339(instant: DateTime<Tz>) -> String {
+    instant.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+fn explanation_text(evaluation: &ScheduleEvaluation, greeting_delay: Duration) -> String {
+    let at = evaluation.at;
+    let mut lines = vec![
+        format!(
+            "at: {} ({}, {})",
+            rfc3339(at),
+            at.format("%A"),
+            at.timezone().name()
+        ),
+        format!("rules: {}", evaluation.rule_source.label()),
+        format!("state: {}", evaluation.state.label()),
+    ];
+    lines.push(match evaluation.window {
+        Some(window) => format!(
+            "window: {} to {} from {}",
+            rfc3339(window.start),
+            rfc3339(window.end),
+            window.source.label()
+        ),
+        None => String::from("window: none"),
+    });
+    lines.push(match evaluation.next_change {
+        Some((at, state)) => format!("next: {} at {}", state.label(), rfc3339(at)),
+        None => String::from("next: none within a week"),
+    });
+    lines.push(match greeting_hold(evaluation, greeting_delay) {
+        Some(until) => format!(
+            "greeting: a start now holds the wake until {}",
+            rfc3339(until)
+        ),
+        None => String::from("greeting: no wake to hold"),
+    });
+    lines.push(match evaluation.sleep_warning {
+        Some((warn_at, sleep_at)) if warn_at <= at => format!(
+            "sleep-warning: showing; a press now keeps the frame awake past {}",
+            rfc3339(sleep_at)
+        ),
+        Some((warn_at, sleep_at)) => format!(
+            "sleep-warning: from {} for the sleep at {}",
+            rfc3339(warn_at),
+            rfc3339(sleep_at)
+        ),
+        None => String::from("sleep-warning: none"),
+    });
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+fn explanation_json(
+    evaluation: &ScheduleEvaluation,
+    greeting_delay: Duration,
+) -> serde_json::Value {
+    let at = evaluation.at;
+    json!({
+        "at": rfc3339(at),
+        "timezone": at.timezone().name(),
+        "rules": evaluation.rule_source.label(),
+        "state": evaluation.state.label(),
+        "window": evaluation.window.map(|window| json!({
+            "start": rfc3339(window.start),
+            "end": rfc3339(window.end),
+            "rules": window.source.label(),
+        })),
+        "next_change": evaluation.next_change.map(|(at, state)| json!({
+            "at": rfc3339(at),
+            "state": state.label(),
+        })),
+        "greeting_hold_until": greeting_hold(evaluation, greeting_delay).map(rfc3339),
+        "sleep_warning": evaluation.sleep_warning.map(|(warn_at, sleep_at)| json!({
+            "warn_at": rfc3339(warn_at),
+            "sleep_at": rfc3339(sleep_at),
+            "showing": warn_at <= at,
+        })),
+    })
+}
+
 #[derive(Clone)]
 struct SchedulerConfig {
     schedule: AwakeScheduleConfig,
     greeting_delay: Duration,
+    /// Re-reads the panel's power state after a resume from suspend.
+    screen: ScreenProbe,
 }
 
 impl ButtondSettings {
-    fn load(config_path: &Path, device_override: Option<PathBuf>) -> Result<Self> {
-        let file_config = FileConfig::from_path(config_path)?;
+    fn from_file(file_config: FileConfig) -> Result<Self> {
         let FileConfig {
             control_socket_path,
             buttond,
             greeting_screen,
+            sleep_screen,
             awake_schedule,
         } = file_config;
         let ButtondFileConfig {
+            input,
             device,
+            gpio,
             single_window_ms,
             double_window_ms,
             debounce_ms,
             shutdown_command,
             screen,
             force_shutdown,
+            actions,
         } = buttond;
 
         let durations = Durations::from_millis(debounce_ms, single_window_ms, double_window_ms);
-        let device = device_override.or(device);
         let mut shutdown_command = shutdown_command.into_spec("shutdown");
         configure_shutdown_args(
             &shutdown_command.program,
@@ -242,7 +444,10 @@ impl ButtondSettings {
             off_command,
             display_name,
         } = screen;
-        let screen_off_delay = Duration::from_millis(off_delay_ms);
+        let screen_off_delay = effective_screen_off_delay(
+            Duration::from_millis(off_delay_ms),
+            sleep_screen.effective_transition(),
+        );
         let greeting_screen_delay = greeting_screen.effective_duration();
 
         let mut screen_on_command = on_command.into_spec("screen-on");
@@ -252,8 +457,14 @@ impl ButtondSettings {
             screen_off_command.args.push(name.clone());
         }
 
+        if input == Some(InputBackend::Gpio) && gpio.is_none() {
+            bail!("buttond.input is gpio but no buttond.gpio section is configured");
+        }
+
         Ok(Self {
+            input,
             device,
+            gpio,
             durations,
             control_socket_path,
             shutdown_command,
@@ -263,17 +474,25 @@ impl ButtondSettings {
             screen_display_name: display_name,
             greeting_screen_delay,
             awake_schedule,
+            actions: actions.into_actions(),
         })
     }
 
-    fn into_runtime(self) -> Result<(Runtime, Option<SchedulerConfig>)> {
+    fn into_runtime(self, dry_run: bool) -> Result<(Runtime, Option<SchedulerConfig>)> {
         let sway_env = Arc::new(SwayEnvironment::prepare()?);
-        let executor: Arc<dyn CommandExecutor> =
-            Arc::new(SwayCommandExecutor::new(sway_env.clone()));
+        let executor: Arc<dyn CommandExecutor> = if dry_run {
+            Arc::new(DryRunExecutor::new())
+        } else {
+            Arc::new(SwayCommandExecutor::new(sway_env.clone()))
+        };
         let powerctl_program =
             detect_powerctl_program(&self.screen_on_command, &self.screen_off_command);
         let detector: Arc<dyn ScreenDetector> =
             Arc::new(SwayScreenDetector::new(sway_env, powerctl_program));
+        let probe = ScreenProbe {
+            detector: detector.clone(),
+            display_name: self.screen_display_name.clone(),
+        };
 
         let screen = ScreenRuntime::new(
             self.screen_on_command,
@@ -303,23 +522,44 @@ impl ButtondSettings {
         let control_socket: Arc<dyn ControlSocket> =
             Arc::new(UnixControlSocket::new(self.control_socket_path.clone()));
 
+        let manual_override = self
+            .awake_schedule
+            .as_ref()
+            .and_then(AwakeScheduleConfig::manual_override);
         let runtime = Runtime::new(
             control_socket,
             self.shutdown_command,
             screen,
             executor,
             initial_state,
-        );
+        )
+        .with_manual_override(manual_override)
+        .with_actions(self.actions);
 
         let scheduler = self.awake_schedule.map(|schedule| SchedulerConfig {
             schedule,
             greeting_delay: self.greeting_screen_delay,
+            screen: probe,
         });
 
         Ok((runtime, scheduler))
     }
 }
 
+/// The viewer fades to black before showing the sleep screen; powering the panel off
+/// before that finishes would cut the fade short, so the off-delay never undercuts it.
+fn effective_screen_off_delay(configured: Duration, sleep_transition: Duration) -> Duration {
+    if configured < sleep_transition {
+        warn!(
+            configured_ms = configured.as_millis() as u64,
+            sleep_transition_ms = sleep_transition.as_millis() as u64,
+            "screen off-delay-ms is shorter than the sleep transition; extending it"
+        );
+        return sleep_transition;
+    }
+    configured
+}
+
 fn detect_powerctl_program(on: &CommandSpec, off: &CommandSpec) -> Option<PathBuf> {
     for command in [on, off] {
         if command
@@ -336,7 +576,7 @@ fn detect_powerctl_program(on: &CommandSpec, off: &CommandSpec) -> Option<PathBu
     None
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 struct FileConfig {
     #[serde(default = "FileConfig::default_control_socket_path")]
@@ -346,15 +586,14 @@ struct FileConfig {
     #[serde(default)]
     greeting_screen: GreetingScreenConfig,
     #[serde(default)]
+    sleep_screen: SleepScreenConfig,
+    #[serde(default)]
     awake_schedule: Option<AwakeScheduleConfig>,
 }
 
 impl FileConfig {
     fn from_path(path: &Path) -> Result<Self> {
-        let raw = fs::read_to_string(path)
-            .with_context(|| format!("failed to read {}", path.display()))?;
-        let mut parsed: Self = serde_yaml::from_str(&raw)
-            .with_context(|| format!("failed to parse {}", path.display()))?;
+        let mut parsed: Self = config_model::read_yaml_file(path)?;
         if parsed.control_socket_path.as_os_str().is_empty() {
             bail!("control-socket-path must not be empty");
         }
@@ -365,6 +604,10 @@ impl FileConfig {
             .greeting_screen
             .validate()
             .context("invalid greeting screen configuration")?;
+        parsed
+            .sleep_screen
+            .validate()
+            .context("invalid sleep screen configuration")?;
         if let Some(schedule) = parsed.awake_schedule.as_mut() {
             schedule
                 .validate()
@@ -378,11 +621,16 @@ impl FileConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 struct ButtondFileConfig {
+    /// `evdev` or `gpio`; auto-detects, preferring evdev, when omitted.
+    #[serde(default)]
+    input: Option<InputBackend>,
     #[serde(default)]
     device: Option<PathBuf>,
+    #[serde(default)]
+    gpio: Option<GpioConfig>,
     #[serde(default = "ButtondFileConfig::default_single_window_ms")]
     single_window_ms: u64,
     #[serde(default = "ButtondFileConfig::default_double_window_ms")]
@@ -395,6 +643,9 @@ struct ButtondFileConfig {
     shutdown_command: CommandConfig,
     #[serde(default)]
     screen: ScreenConfig,
+    /// What single, double, and long presses do.
+    #[serde(default)]
+    actions: ActionsConfig,
 }
 
 impl ButtondFileConfig {
@@ -426,18 +677,21 @@ impl ButtondFileConfig {
 impl Default for ButtondFileConfig {
     fn default() -> Self {
         Self {
+            input: None,
             device: None,
+            gpio: None,
             single_window_ms: Self::default_single_window_ms(),
             double_window_ms: Self::default_double_window_ms(),
             debounce_ms: Self::default_debounce_ms(),
             force_shutdown: Self::default_force_shutdown(),
             shutdown_command: Self::default_shutdown_command(),
             screen: ScreenConfig::default(),
+            actions: ActionsConfig::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 struct ScreenConfig {
     #[serde(default = "ScreenConfig::default_off_delay_ms")]
@@ -483,7 +737,7 @@ impl ScreenConfig {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 struct CommandConfig {
     #[serde(default)]
@@ -508,7 +762,7 @@ impl CommandConfig {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct CommandSpec {
     label: String,
     program: PathBuf,
@@ -537,11 +791,13 @@ impl CommandExecutor for SwayCommandExecutor {
             label = %command.label,
             "running command",
         );
-        let mut os_command = Command::new(&command.program);
-        os_command.args(&command.args);
-        self.env.configure(&mut os_command);
-        let output = os_command
-            .output()
+        let output = self
+            .env
+            .output_with_refresh(|| {
+                let mut os_command = Command::new(&command.program);
+                os_command.args(&command.args);
+                os_command
+            })
             .with_context(|| format!("failed to execute {}", command.program.display()))?;
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -565,8 +821,67 @@ impl CommandExecutor for SwayCommandExecutor {
     }
 }
 
+/// Executor used by `--dry-run`: logs each command instead of spawning it so a
+/// new configuration can be exercised on hardware without powering the panel
+/// off or shutting the frame down. Keeps a bounded history of recent calls.
+struct DryRunExecutor {
+    calls: Mutex<VecDeque<CommandSpec>>,
+}
+
+impl DryRunExecutor {
+    const HISTORY_LIMIT: usize = 64;
+
+    fn new() -> Self {
+        Self {
+            calls: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    #[cfg(test)]
+    fn calls(&self) -> Vec<CommandSpec> {
+        let guard = self.calls.lock().expect("dry-run executor poisoned");
+        guard.iter().cloned().collect()
+    }
+}
+
+impl CommandExecutor for DryRunExecutor {
+    fn execute(&self, command: &CommandSpec) -> Result<()> {
+        info!(
+            program = %command.program.display(),
+            args = ?command.args,
+            label = %command.label,
+            "dry-run: skipping command",
+        );
+        let mut guard = self.calls.lock().expect("dry-run executor poisoned");
+        if guard.len() == Self::HISTORY_LIMIT {
+            guard.pop_front();
+        }
+        guard.push_back(command.clone());
+        Ok(())
+    }
+}
+
+/// What the viewer made of a `set-state` command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SetStateOutcome {
+    Applied,
+    /// A manual override on the viewer refused a scheduled change; it holds
+    /// for this much longer.
+    HeldOff(Duration),
+}
+
 trait ControlSocket: Send + Sync {
-    fn send_set_state(&self, state: ViewerMode) -> Result<()>;
+    fn send_set_state(
+        &self,
+        state: ViewerMode,
+        source: TransitionSource,
+    ) -> Result<SetStateOutcome>;
+
+    /// Asks the viewer to move on to the next photo.
+    fn send_next(&self) -> Result<()>;
+
+    /// Asks the viewer to show `text` in a banner for `duration`.
+    fn send_notice(&self, text: &str, duration: Duration) -> Result<()>;
 }
 
 struct UnixControlSocket {
@@ -579,21 +894,61 @@ impl UnixControlSocket {
     }
 }
 
-impl ControlSocket for UnixControlSocket {
-    fn send_set_state(&self, state: ViewerMode) -> Result<()> {
+impl UnixControlSocket {
+    /// Reads the viewer's answer to a command already written to `stream`. The
+    /// viewer only replies when it refuses; silence or an unreadable reply
+    /// counts as applied, which is also how older viewers behave.
+    fn read_outcome(&self, stream: &mut UnixStream) -> Result<SetStateOutcome> {
+        const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+        #[derive(Deserialize)]
+        struct Reply {
+            status: String,
+            #[serde(default)]
+            error: Option<String>,
+            #[serde(default)]
+            override_remaining_seconds: Option<u64>,
+        }
+
+        let mut reply = String::new();
+        let read = stream
+            .shutdown(Shutdown::Write)
+            .and_then(|()| stream.set_read_timeout(Some(REPLY_TIMEOUT)))
+            .and_then(|()| stream.read_to_string(&mut reply));
+        if let Err(err) = read {
+            debug!(path = %self.path.display(), ?err, "no reply to set-state");
+            return Ok(SetStateOutcome::Applied);
+        }
+        let Ok(reply) = serde_json::from_str::<Reply>(reply.trim()) else {
+            return Ok(SetStateOutcome::Applied);
+        };
+        if reply.status != "error" {
+            return Ok(SetStateOutcome::Applied);
+        }
+        match reply.override_remaining_seconds {
+            Some(seconds) => Ok(SetStateOutcome::HeldOff(Duration::from_secs(seconds))),
+            None => bail!(
+                "viewer refused set-state: {}",
+                reply.error.as_deref().unwrap_or("no reason given")
+            ),
+        }
+    }
+}
+
+impl UnixControlSocket {
+    /// Writes one command, retrying briefly while the viewer is not
+    /// listening, and returns the stream so the caller can read a reply.
+    fn send(&self, command: serde_json::Value) -> Result<UnixStream> {
         const MAX_ATTEMPTS: usize = 3;
         const RETRY_DELAY: Duration = Duration::from_millis(150);
 
-        let payload = serde_json::to_vec(&json!({
-            "command": "set-state",
-            "state": state.as_str(),
-        }))
-        .context("failed to serialize control payload")?;
+        let payload =
+            serde_json::to_vec(&command).context("failed to serialize control payload")?;
 
         let mut last_error: Option<anyhow::Error> = None;
 
         for attempt in 1..=MAX_ATTEMPTS {
-            match UnixStream::connect(&self.path) {
+            match config_model::connect_control_socket(&self.path) {
                 Ok(mut stream) => {
                     if let Err(err) = stream.write_all(&payload) {
                         warn!(
@@ -604,17 +959,16 @@ impl ControlSocket for UnixControlSocket {
                         );
                         last_error = Some(err.into());
                     } else {
-                        return Ok(());
+                        return Ok(stream);
                     }
                 }
                 Err(err) => {
                     warn!(
                         attempt,
-                        path = %self.path.display(),
-                        ?err,
+                        error = %format!("{err:#}"),
                         "failed to connect to control socket",
                     );
-                    last_error = Some(err.into());
+                    last_error = Some(err);
                 }
             }
 
@@ -627,6 +981,35 @@ impl ControlSocket for UnixControlSocket {
     }
 }
 
+impl ControlSocket for UnixControlSocket {
+    fn send_set_state(
+        &self,
+        state: ViewerMode,
+        source: TransitionSource,
+    ) -> Result<SetStateOutcome> {
+        let mut stream = self.send(json!({
+            "command": "set-state",
+            "state": state.as_str(),
+            "source": source.as_str(),
+        }))?;
+        self.read_outcome(&mut stream)
+    }
+
+    fn send_next(&self) -> Result<()> {
+        // The viewer answers `next` with silence; there is nothing to read.
+        self.send(json!({ "command": "next" })).map(drop)
+    }
+
+    fn send_notice(&self, text: &str, duration: Duration) -> Result<()> {
+        self.send(json!({
+            "command": "notice",
+            "text": text,
+            "seconds": duration.as_secs().max(1),
+        }))
+        .map(drop)
+    }
+}
+
 /// A panel power-off that has been scheduled but not yet fired. The viewer is
 /// already in the Asleep state; only the physical power-off is deferred, so the
 /// event loop keeps servicing button presses during the screen-off delay.
@@ -647,6 +1030,7 @@ struct Runtime {
     state: Arc<Mutex<FrameState>>,
     /// Deferred panel power-off for an in-flight sleep transition, if any.
     pending_power_off: Option<PendingPowerOff>,
+    actions: ButtonActions,
 }
 
 impl Runtime {
@@ -665,18 +1049,57 @@ impl Runtime {
             executor,
             state,
             pending_power_off: None,
+            actions: ButtonActions::default(),
         }
     }
 
+    /// What each kind of press does; see `buttond.actions`.
+    fn with_actions(mut self, actions: ButtonActions) -> Self {
+        self.actions = actions;
+        self
+    }
+
+    /// How long a manual wake or sleep holds against the schedule; see
+    /// `awake-schedule.manual-override-minutes`.
+    fn with_manual_override(self, ttl: Option<Duration>) -> Self {
+        self.state
+            .lock()
+            .expect("frame state poisoned")
+            .override_ttl = ttl;
+        self
+    }
+
     fn shared_state(&self) -> Arc<Mutex<FrameState>> {
         Arc::clone(&self.state)
     }
 
+    /// Carries out the verb bound to `press`.
+    fn run_verb(&mut self, press: Action) -> Result<()> {
+        let verb = self.actions.verb_for(press).clone();
+        if matches!(verb, Verb::ToggleSleep | Verb::Wake) && self.stay_awake_if_warned()? {
+            return Ok(());
+        }
+        match verb {
+            Verb::ToggleSleep => self.handle_manual_toggle(),
+            Verb::Wake => self.wake_up(TransitionSource::Manual),
+            Verb::Sleep => self.go_to_sleep(TransitionSource::Manual),
+            Verb::Shutdown => self.shutdown(),
+            Verb::NextPhoto => self.control_socket.send_next(),
+            Verb::CustomCommand(command) => self.executor.execute(&command),
+        }
+    }
+
     fn handle_manual_toggle(&mut self) -> Result<()> {
         // If a sleep is mid-flight (the panel power-off is still pending), a
         // press cancels it and wakes — the user clearly wants the frame on.
         if self.pending_power_off.is_some() {
-            info!("single press during sleep delay → cancelling pending sleep and waking");
+            info!("press during sleep delay → cancelling pending sleep and waking");
+            return self.wake_up(TransitionSource::Manual);
+        }
+        // The panel is on while ambient, so detection would read it as awake;
+        // a press from ambient means "fully on".
+        if self.current_viewer_mode() == ViewerMode::Ambient {
+            info!("toggle → waking frame from ambient");
             return self.wake_up(TransitionSource::Manual);
         }
         // Prefer the physically detected screen state, but never let a detection
@@ -700,11 +1123,11 @@ impl Runtime {
 
         match current {
             ViewerMode::Awake => {
-                info!("single press → putting frame to sleep");
+                info!("toggle → putting frame to sleep");
                 self.go_to_sleep(TransitionSource::Manual)?;
             }
-            ViewerMode::Asleep => {
-                info!("single press → waking frame");
+            ViewerMode::Asleep | ViewerMode::Ambient => {
+                info!("toggle → waking frame");
                 self.wake_up(TransitionSource::Manual)?;
             }
         }
@@ -712,7 +1135,43 @@ impl Runtime {
         Ok(())
     }
 
-    fn handle_double(&self) -> Result<()> {
+    /// Puts up the scheduler's warning of a sleep `remaining` from now.
+    fn warn_of_sleep(&mut self, remaining: Duration) -> Result<()> {
+        self.state
+            .lock()
+            .expect("frame state poisoned")
+            .warn_of_sleep(wall_deadline(Utc::now(), remaining));
+        let minutes = remaining.as_secs().div_ceil(60).max(1);
+        let plural = if minutes == 1 { "" } else { "s" };
+        info!(
+            remaining = %format_duration(remaining),
+            "warning of the scheduled sleep"
+        );
+        self.control_socket.send_notice(
+            &format!("Going to sleep in {minutes} minute{plural} — press the button to stay awake"),
+            remaining,
+        )
+    }
+
+    /// A press while the sleep warning is up keeps the frame awake through
+    /// the warned sleep. Returns whether the press was taken that way.
+    fn stay_awake_if_warned(&mut self) -> Result<bool> {
+        const STAYING_AWAKE_NOTICE: Duration = Duration::from_secs(5);
+
+        {
+            let mut guard = self.state.lock().expect("frame state poisoned");
+            if !guard.sleep_warning_active(Utc::now()) {
+                return Ok(false);
+            }
+            guard.stay_awake();
+        }
+        info!("press during the sleep warning → staying awake through the scheduled sleep");
+        self.control_socket
+            .send_notice("Staying awake", STAYING_AWAKE_NOTICE)?;
+        Ok(true)
+    }
+
+    fn shutdown(&self) -> Result<()> {
         self.executor.execute(&self.shutdown_command)
     }
 
@@ -749,7 +1208,17 @@ impl Runtime {
         }
 
         self.screen.power_on()?;
-        self.control_socket.send_set_state(ViewerMode::Awake)?;
+        let outcome = self
+            .control_socket
+            .send_set_state(ViewerMode::Awake, source)?;
+        if let SetStateOutcome::HeldOff(remaining) = outcome {
+            // The viewer is staying asleep, so the panel goes back off.
+            if let Err(err) = self.screen.power_off() {
+                warn!(?err, "failed to power screen back off after a refused wake");
+            }
+            self.hold_viewer_override(ViewerMode::Asleep, remaining);
+            return Ok(());
+        }
         info!(reason = source.as_str(), "frame wake request completed");
         self.record_state(ViewerMode::Awake, source);
         Ok(())
@@ -781,7 +1250,13 @@ impl Runtime {
             }
         }
 
-        self.control_socket.send_set_state(ViewerMode::Asleep)?;
+        let outcome = self
+            .control_socket
+            .send_set_state(ViewerMode::Asleep, source)?;
+        if let SetStateOutcome::HeldOff(remaining) = outcome {
+            self.hold_viewer_override(ViewerMode::Awake, remaining);
+            return Ok(());
+        }
         // Record the asleep mode immediately so the scheduler (which reconciles
         // against the tracked viewer mode) stops re-sending sleep commands while
         // the panel power-off below is deferred.
@@ -805,6 +1280,34 @@ impl Runtime {
         Ok(())
     }
 
+    /// Dims the frame for an ambient window: the panel stays (or comes back)
+    /// on and the viewer shows its ambient screen.
+    fn enter_ambient(&mut self, source: TransitionSource) -> Result<()> {
+        // Like a wake, this wins over an in-flight sleep's deferred power-off.
+        self.pending_power_off = None;
+        let previous = self.current_viewer_mode();
+
+        self.screen.power_on()?;
+        let outcome = self
+            .control_socket
+            .send_set_state(ViewerMode::Ambient, source)?;
+        if let SetStateOutcome::HeldOff(remaining) = outcome {
+            if previous == ViewerMode::Asleep
+                && let Err(err) = self.screen.power_off()
+            {
+                warn!(
+                    ?err,
+                    "failed to power screen back off after a refused ambient"
+                );
+            }
+            self.hold_viewer_override(previous, remaining);
+            return Ok(());
+        }
+        info!(reason = source.as_str(), "frame ambient request completed");
+        self.record_state(ViewerMode::Ambient, source);
+        Ok(())
+    }
+
     /// Power the panel off immediately and refresh the tracked state. Used when
     /// the configured off-delay is zero.
     fn power_off_now(&mut self, source: TransitionSource) {
@@ -890,6 +1393,19 @@ impl Runtime {
         let mut guard = self.state.lock().expect("frame state poisoned");
         guard.update(mode, source);
     }
+
+    /// The viewer refused a scheduled change because someone set it to `mode`
+    /// by hand (through the control socket, not this button). Adopt that as
+    /// our own override so the scheduler waits it out instead of retrying.
+    fn hold_viewer_override(&self, mode: ViewerMode, remaining: Duration) {
+        info!(
+            mode = mode.as_str(),
+            remaining = %format_duration(remaining),
+            "viewer is holding a manual override; deferring the scheduled transition"
+        );
+        let mut guard = self.state.lock().expect("frame state poisoned");
+        guard.hold(mode, wall_deadline(Utc::now(), remaining));
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -910,12 +1426,15 @@ impl TransitionSource {
 /// Manual override applied by a button press. It supersedes the schedule until
 /// the schedule itself would produce the same state (see
 /// `FrameState::reconcile_override`), at which point it clears so the frame
-/// resumes following the schedule automatically.
+/// resumes following the schedule automatically. With
+/// `awake-schedule.manual-override-minutes` set it also runs out after that
+/// long.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Override {
     Unset,
     ForceWake,
     ForceSleep,
+    ForceAmbient,
 }
 
 impl Override {
@@ -925,6 +1444,7 @@ impl Override {
             Override::Unset => None,
             Override::ForceWake => Some(ViewerMode::Awake),
             Override::ForceSleep => Some(ViewerMode::Asleep),
+            Override::ForceAmbient => Some(ViewerMode::Ambient),
         }
     }
 
@@ -933,6 +1453,7 @@ impl Override {
         match mode {
             ViewerMode::Awake => Override::ForceWake,
             ViewerMode::Asleep => Override::ForceSleep,
+            ViewerMode::Ambient => Override::ForceAmbient,
         }
     }
 }
@@ -940,6 +1461,19 @@ impl Override {
 struct FrameState {
     mode: ViewerMode,
     override_state: Override,
+    /// Lifetime given to each manual override, when limited.
+    override_ttl: Option<Duration>,
+    /// When the current override runs out, if it is time-limited. Kept in
+    /// wall-clock time, which (unlike `Instant`) runs on while the system
+    /// is suspended.
+    override_until: Option<DateTime<Utc>>,
+    /// The schedule has wanted the other state since the override began.
+    override_contested: bool,
+    /// The override began while the schedule agreed with it, so it clears
+    /// on agreement only once the schedule has wanted the other state.
+    override_awaits_boundary: bool,
+    /// When the scheduled sleep the viewer was last warned about falls.
+    sleep_warned_for: Option<DateTime<Utc>>,
     greeting_complete: bool,
 }
 
@@ -948,6 +1482,11 @@ impl FrameState {
         Self {
             mode,
             override_state: Override::Unset,
+            override_ttl: None,
+            override_until: None,
+            override_contested: false,
+            override_awaits_boundary: false,
+            sleep_warned_for: None,
             greeting_complete: mode == ViewerMode::Awake,
         }
     }
@@ -960,23 +1499,88 @@ impl FrameState {
         // agrees with it.
         if let TransitionSource::Manual = source {
             self.override_state = Override::for_manual(mode);
+            self.override_until = self.override_ttl.map(|ttl| wall_deadline(Utc::now(), ttl));
+            self.override_contested = false;
+            self.override_awaits_boundary = self.override_until.is_some();
         }
 
-        if mode == ViewerMode::Awake {
+        if mode != ViewerMode::Asleep {
             self.greeting_complete = true;
         }
     }
 
+    /// Keeps the frame in `mode`, which the viewer already is in, until `until`
+    /// even though the schedule currently wants the other state.
+    fn hold(&mut self, mode: ViewerMode, until: DateTime<Utc>) {
+        self.mode = mode;
+        self.override_state = Override::for_manual(mode);
+        self.override_until = Some(until);
+        self.override_contested = true;
+        self.override_awaits_boundary = true;
+    }
+
+    fn warn_of_sleep(&mut self, sleep_at: DateTime<Utc>) {
+        self.sleep_warned_for = Some(sleep_at);
+    }
+
+    /// Whether the frame is awake with a warned sleep still ahead.
+    fn sleep_warning_active(&self, now: DateTime<Utc>) -> bool {
+        self.mode == ViewerMode::Awake && self.sleep_warned_for.is_some_and(|at| now < at)
+    }
+
+    /// Cancels the warned sleep with a manual wake that, unlike a wake made
+    /// while the schedule already agrees, holds across the coming boundary:
+    /// until the schedule wakes the frame again or the override runs out.
+    fn stay_awake(&mut self) {
+        self.update(ViewerMode::Awake, TransitionSource::Manual);
+        self.override_awaits_boundary = true;
+        self.sleep_warned_for = None;
+    }
+
     /// Clears the override once the schedule wants the same state the override is
     /// forcing (equivalent to "reset at the next schedule boundary", but
-    /// stateless). Returns the resulting override and the current mode.
-    fn reconcile_override(&mut self, schedule_desired: ViewerMode) -> (Override, ViewerMode) {
-        if self.override_state.target() == Some(schedule_desired) {
-            self.override_state = Override::Unset;
+    /// stateless), or once a time-limited override runs out. A time-limited
+    /// override made while the schedule already agreed with it holds through
+    /// the next boundary, so it only clears on agreement after the schedule
+    /// has wanted the other state. Returns the resulting override and the
+    /// current mode.
+    fn reconcile_override(
+        &mut self,
+        schedule_desired: ViewerMode,
+        now: DateTime<Utc>,
+    ) -> (Override, ViewerMode) {
+        if let Some(target) = self.override_state.target() {
+            let expired = self.override_until.is_some_and(|until| now >= until);
+            let caught_up = target == schedule_desired
+                && (!self.override_awaits_boundary || self.override_contested);
+            if expired || caught_up {
+                self.override_state = Override::Unset;
+                self.override_until = None;
+            } else if target != schedule_desired {
+                self.override_contested = true;
+            }
         }
         (self.override_state, self.mode)
     }
 
+    /// When a time-limited override runs out, if one is active.
+    fn override_until(&self) -> Option<DateTime<Utc>> {
+        self.override_until
+            .filter(|_| self.override_state != Override::Unset)
+    }
+
+    /// Adopts the panel's power state when it no longer matches the tracked
+    /// mode, as after a resume from suspend that left the panel on or off
+    /// behind buttond's back. The scheduler then drives the frame back to
+    /// the mode it wants. Returns whether the tracked mode changed.
+    fn resync(&mut self, panel: ScreenState) -> bool {
+        if ScreenState::from(self.mode) == panel {
+            return false;
+        }
+        self.mode = ViewerMode::from(panel);
+        true
+    }
+
     fn greeting_complete(&self) -> bool {
         self.greeting_complete
     }
@@ -1027,6 +1631,19 @@ impl ScreenRuntime {
     }
 }
 
+/// Read-only access to the panel state, for the scheduler thread.
+#[derive(Clone)]
+struct ScreenProbe {
+    detector: Arc<dyn ScreenDetector>,
+    display_name: Option<String>,
+}
+
+impl ScreenProbe {
+    fn detect_state(&self) -> Result<ScreenDetection> {
+        self.detector.detect(self.display_name.as_deref())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ScreenState {
     On,
@@ -1046,6 +1663,8 @@ impl ScreenState {
 enum ViewerMode {
     Awake,
     Asleep,
+    /// Panel on, viewer dimmed; only the schedule puts the frame here.
+    Ambient,
 }
 
 impl ViewerMode {
@@ -1053,6 +1672,17 @@ impl ViewerMode {
         match self {
             ViewerMode::Awake => "awake",
             ViewerMode::Asleep => "asleep",
+            ViewerMode::Ambient => "ambient",
+        }
+    }
+}
+
+impl From<ScheduledState> for ViewerMode {
+    fn from(state: ScheduledState) -> Self {
+        match state {
+            ScheduledState::Awake => ViewerMode::Awake,
+            ScheduledState::Ambient => ViewerMode::Ambient,
+            ScheduledState::Asleep => ViewerMode::Asleep,
         }
     }
 }
@@ -1069,7 +1699,7 @@ impl From<ScreenState> for ViewerMode {
 impl From<ViewerMode> for ScreenState {
     fn from(mode: ViewerMode) -> Self {
         match mode {
-            ViewerMode::Awake => ScreenState::On,
+            ViewerMode::Awake | ViewerMode::Ambient => ScreenState::On,
             ViewerMode::Asleep => ScreenState::Off,
         }
     }
@@ -1102,15 +1732,16 @@ impl SwayScreenDetector {
         program: &Path,
         display_name: Option<&str>,
     ) -> Result<ScreenDetection> {
-        let mut command = Command::new(program);
-        command.arg("state");
-        if let Some(name) = display_name {
-            command.arg(name);
-        }
-        self.env.configure(&mut command);
-
-        let output = command
-            .output()
+        let output = self
+            .env
+            .output_with_refresh(|| {
+                let mut command = Command::new(program);
+                command.arg("state");
+                if let Some(name) = display_name {
+                    command.arg(name);
+                }
+                command
+            })
             .with_context(|| format!("failed to execute {} state probe", program.display()))?;
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -1141,12 +1772,13 @@ impl SwayScreenDetector {
     }
 
     fn detect_via_swaymsg(&self, display_name: Option<&str>) -> Result<ScreenDetection> {
-        let mut command = Command::new("swaymsg");
-        self.env.configure(&mut command);
-        command.arg("-t").arg("get_outputs").arg("--raw");
-
-        let output = command
-            .output()
+        let output = self
+            .env
+            .output_with_refresh(|| {
+                let mut command = Command::new("swaymsg");
+                command.arg("-t").arg("get_outputs").arg("--raw");
+                command
+            })
             .context("failed to execute swaymsg for output detection")?;
         if !output.status.success() {
             bail!(
@@ -1186,9 +1818,20 @@ struct SwayEnvironment {
     /// back to live discovery if it disappears, so a pinned-but-dead socket
     /// never strands us.
     explicit_socket: Option<PathBuf>,
+    /// Socket last handed to a child command. Reused while the file exists and
+    /// replaced when a command reports it could not connect through it.
+    cached_socket: Mutex<Option<PathBuf>>,
 }
 
 impl SwayEnvironment {
+    fn new(runtime_dir: PathBuf, explicit_socket: Option<PathBuf>) -> Self {
+        Self {
+            runtime_dir,
+            explicit_socket,
+            cached_socket: Mutex::new(None),
+        }
+    }
+
     fn prepare() -> Result<Self> {
         let runtime_dir = match env::var_os("XDG_RUNTIME_DIR") {
             Some(value) if !value.is_empty() => PathBuf::from(value),
@@ -1217,31 +1860,92 @@ impl SwayEnvironment {
             _ => None,
         };
 
-        Ok(Self {
-            runtime_dir,
-            explicit_socket,
-        })
+        Ok(Self::new(runtime_dir, explicit_socket))
     }
 
-    /// Resolve the *live* sway IPC socket. This re-discovers on every call so
-    /// that a sway restart (new PID → new socket path) does not leave buttond
-    /// pinned to a dead endpoint for the rest of its lifetime. The compositor is
-    /// launched by greetd independently of this long-lived system service, so it
-    /// can and does cycle underneath us.
+    /// Resolve the *live* sway IPC socket. The cached path is only reused while
+    /// its file still exists, so a sway restart (new PID → new socket path) does
+    /// not leave buttond pinned to a dead endpoint for the rest of its lifetime.
+    /// The compositor is launched by greetd independently of this long-lived
+    /// system service, so it can and does cycle underneath us.
     fn resolve_socket(&self) -> Result<PathBuf> {
+        let mut cached = self
+            .cached_socket
+            .lock()
+            .expect("sway socket cache poisoned");
+        if let Some(sock) = cached.as_ref()
+            && is_socket(sock).unwrap_or(false)
+        {
+            return Ok(sock.clone());
+        }
+        let fresh = self.discover_socket(None)?;
+        *cached = Some(fresh.clone());
+        Ok(fresh)
+    }
+
+    /// Forgets `stale` and discovers again, skipping it even if a crashed sway
+    /// left its socket file behind.
+    fn refresh_socket(&self, stale: &Path) -> Result<PathBuf> {
+        let mut cached = self
+            .cached_socket
+            .lock()
+            .expect("sway socket cache poisoned");
+        let fresh = self.discover_socket(Some(stale));
+        *cached = fresh.as_ref().ok().cloned();
+        fresh
+    }
+
+    fn discover_socket(&self, skip: Option<&Path>) -> Result<PathBuf> {
         if let Some(sock) = &self.explicit_socket
+            && Some(sock.as_path()) != skip
             && is_socket(sock).unwrap_or(false)
         {
             return Ok(sock.clone());
         }
-        find_sway_socket(&self.runtime_dir)
+        let found = find_sway_socket(&self.runtime_dir)?;
+        if Some(found.as_path()) == skip {
+            bail!(
+                "sway IPC socket {} refused the connection and no other socket was found in {}",
+                found.display(),
+                self.runtime_dir.display()
+            );
+        }
+        Ok(found)
+    }
+
+    /// Runs the command built by `build` with the sway environment applied. When
+    /// the child reports it could not reach sway (the compositor restarted),
+    /// re-resolves the socket and runs a fresh command once more.
+    fn output_with_refresh(&self, build: impl Fn() -> Command) -> Result<Output> {
+        let mut command = build();
+        let used = self.configure(&mut command);
+        let output = command.output()?;
+        if output.status.success() || !is_sway_connection_error(&output.stderr) {
+            return Ok(output);
+        }
+        let fresh = match &used {
+            Some(stale) => self.refresh_socket(stale),
+            None => self.resolve_socket(),
+        }
+        .context("sway IPC socket is unavailable; is sway running?")?;
+        info!(
+            stale = ?used,
+            socket = %fresh.display(),
+            "sway IPC socket changed; retrying command",
+        );
+        let mut retry = build();
+        self.configure(&mut retry);
+        Ok(retry.output()?)
     }
 
-    fn configure(&self, command: &mut Command) {
+    /// Points `command` at the runtime dir and live socket, returning the socket
+    /// it was given (if any).
+    fn configure(&self, command: &mut Command) -> Option<PathBuf> {
         command.env("XDG_RUNTIME_DIR", &self.runtime_dir);
         match self.resolve_socket() {
             Ok(socket_path) => {
-                command.env("SWAYSOCK", socket_path);
+                command.env("SWAYSOCK", &socket_path);
+                Some(socket_path)
             }
             Err(err) => {
                 // Never forward a stale SWAYSOCK: a crashed sway often leaves its
@@ -1253,11 +1957,25 @@ impl SwayEnvironment {
                     "no live sway IPC socket; clearing SWAYSOCK for child command"
                 );
                 command.env_remove("SWAYSOCK");
+                None
             }
         }
     }
 }
 
+/// Whether a sway client's stderr says it could not reach the IPC socket, as
+/// opposed to sway rejecting the command itself.
+fn is_sway_connection_error(stderr: &[u8]) -> bool {
+    let stderr = String::from_utf8_lossy(stderr).to_ascii_lowercase();
+    [
+        "unable to connect",
+        "connection refused",
+        "unable to retrieve socket path",
+    ]
+    .iter()
+    .any(|marker| stderr.contains(marker))
+}
+
 fn is_socket(path: &Path) -> Result<bool> {
     match fs::metadata(path) {
         Ok(metadata) => Ok(metadata.file_type().is_socket()),
@@ -1496,19 +2214,10 @@ impl<'de> Deserialize<'de> for SwayPower {
 }
 
 fn perform_action(action: Action, runtime: &mut Runtime) {
-    match action {
-        Action::Single => {
-            info!("single press → toggle frame state");
-            if let Err(err) = runtime.handle_manual_toggle() {
-                error!(?err, "failed to process single press");
-            }
-        }
-        Action::Double => {
-            info!("double press → shutdown");
-            if let Err(err) = runtime.handle_double() {
-                error!(?err, "failed to run shutdown command");
-            }
-        }
+    let verb = runtime.actions.verb_for(action).as_str();
+    info!("{} press → {verb}", action.as_str());
+    if let Err(err) = runtime.run_verb(action) {
+        error!(?err, "failed to process {} press ({verb})", action.as_str());
     }
 }
 
@@ -1516,6 +2225,12 @@ fn perform_action(action: Action, runtime: &mut Runtime) {
 enum SchedulerCommand {
     WakeUp,
     GoToSleep,
+    EnterAmbient,
+    /// `awake-schedule.sleep-warning-minutes` before a scheduled sleep that
+    /// is `remaining` away.
+    WarnSleep {
+        remaining: Duration,
+    },
 }
 
 impl SchedulerCommand {
@@ -1523,6 +2238,9 @@ impl SchedulerCommand {
         match self {
             SchedulerCommand::WakeUp => ViewerMode::Awake,
             SchedulerCommand::GoToSleep => ViewerMode::Asleep,
+            SchedulerCommand::EnterAmbient => ViewerMode::Ambient,
+            // Only sent while awake, and never left pending.
+            SchedulerCommand::WarnSleep { .. } => ViewerMode::Awake,
         }
     }
 }
@@ -1532,8 +2250,9 @@ fn spawn_scheduler(
     shared_state: Arc<Mutex<FrameState>>,
 ) -> Option<mpsc::Receiver<SchedulerCommand>> {
     let (tx, rx) = mpsc::channel();
+    let resume = resume_signals();
     let builder = thread::Builder::new().name(String::from("buttond-scheduler"));
-    match builder.spawn(move || scheduler_loop(config, shared_state, tx)) {
+    match builder.spawn(move || scheduler_loop(config, shared_state, tx, resume)) {
         Ok(_) => Some(rx),
         Err(err) => {
             error!(?err, "failed to spawn scheduler thread");
@@ -1542,38 +2261,123 @@ fn spawn_scheduler(
     }
 }
 
-fn scheduler_loop(
-    config: SchedulerConfig,
-    shared_state: Arc<Mutex<FrameState>>,
-    tx: mpsc::Sender<SchedulerCommand>,
-) {
-    const MAX_SLEEP: Duration = Duration::from_secs(60);
-    const COMMAND_SETTLE: Duration = Duration::from_millis(100);
-    let greeting_ready_at = Instant::now() + config.greeting_delay;
-    // Tracks the command we're trying to apply, when it was last dispatched, and
-    // how many times we've sent it without it taking effect. The attempt count
-    // drives exponential backoff so a persistently failing transition (e.g. the
-    // compositor is down) can't spin in a tight 1s retry loop.
-    let mut pending_command: Option<(SchedulerCommand, Instant, u32)> = None;
+/// Resume notifications from systemd-logind, when built with `logind`.
+#[cfg(feature = "logind")]
+fn resume_signals() -> Option<mpsc::Receiver<()>> {
+    logind::watch_resume()
+}
+
+/// Without the `logind` feature a resume is only noticed by [`ClockWatch`],
+/// within a minute of it.
+#[cfg(not(feature = "logind"))]
+fn resume_signals() -> Option<mpsc::Receiver<()>> {
+    None
+}
+
+/// Notices the wall clock moving away from the monotonic one between two
+/// scheduler passes. `Instant` stands still while the system is suspended,
+/// so a suspend shows up as a jump; so does a clock step (NTP syncing on a
+/// board without an RTC, say). Either way the schedule needs a fresh look.
+#[derive(Debug, Default)]
+struct ClockWatch {
+    last: Option<(Instant, DateTime<Utc>)>,
+}
+
+impl ClockWatch {
+    /// Drift below this is ordinary clock slewing or a slow pass.
+    const THRESHOLD: Duration = Duration::from_secs(10);
+
+    /// Records a pass at `monotonic`/`wall` and returns how far the wall
+    /// clock moved beyond the monotonic one since the last pass, when that
+    /// crosses the threshold (negative for a step backwards).
+    fn observe(&mut self, monotonic: Instant, wall: DateTime<Utc>) -> Option<ChronoDuration> {
+        let (last_monotonic, last_wall) = self.last.replace((monotonic, wall))?;
+        let elapsed =
+            ChronoDuration::from_std(monotonic.saturating_duration_since(last_monotonic)).ok()?;
+        let jump = wall.signed_duration_since(last_wall) - elapsed;
+        let threshold = ChronoDuration::from_std(Self::THRESHOLD).ok()?;
+        (jump.abs() >= threshold).then_some(jump)
+    }
+}
+
+/// Re-reads the panel after a resume, so a panel that came back in a
+/// different state than buttond left it is put right by the next pass.
+fn resync_screen(probe: &ScreenProbe, shared_state: &Mutex<FrameState>) {
+    match probe.detect_state() {
+        Ok(detected) => {
+            let mut guard = shared_state.lock().expect("frame state poisoned");
+            let tracked = guard.mode;
+            if guard.resync(detected.state) {
+                info!(
+                    output = %detected.name,
+                    state = detected.state.as_str(),
+                    tracked = tracked.as_str(),
+                    "screen state changed while suspended"
+                );
+            }
+        }
+        Err(err) => warn!(?err, "could not re-detect the screen state after resume"),
+    }
+}
+
+fn scheduler_loop(
+    config: SchedulerConfig,
+    shared_state: Arc<Mutex<FrameState>>,
+    tx: mpsc::Sender<SchedulerCommand>,
+    resume: Option<mpsc::Receiver<()>>,
+) {
+    const MAX_SLEEP: Duration = Duration::from_secs(60);
+    const COMMAND_SETTLE: Duration = Duration::from_millis(100);
+    let resume = resume.as_ref();
+    let mut greeting_ready_at = Instant::now() + config.greeting_delay;
+    // Tracks the command we're trying to apply, when it was last dispatched, and
+    // how many times we've sent it without it taking effect. The attempt count
+    // drives exponential backoff so a persistently failing transition (e.g. the
+    // compositor is down) can't spin in a tight 1s retry loop.
+    let mut pending_command: Option<(SchedulerCommand, Instant, u32)> = None;
+    // The scheduled sleep the last warning was about, so each is warned once.
+    let mut warned_sleep = None;
+    let mut clock = ClockWatch::default();
+    // Set when logind reported a resume while the loop was waiting.
+    let mut resumed = false;
 
     loop {
         let now_instant = Instant::now();
+        let now_utc = Utc::now();
+        if let Some(jump) = clock.observe(now_instant, now_utc) {
+            info!(
+                jump_secs = jump.num_seconds(),
+                "wall clock jumped (suspend or clock change); re-evaluating the schedule"
+            );
+            resumed = true;
+        }
+        if std::mem::take(&mut resumed) {
+            // The greeting delay and retry backoff run on `Instant`, which
+            // stood still while suspended; neither should hold up the first
+            // pass after it.
+            greeting_ready_at = now_instant;
+            pending_command = None;
+            resync_screen(&config.screen, &shared_state);
+        }
         let timezone = config.schedule.timezone();
-        let now = Utc::now().with_timezone(&timezone);
-        let schedule_desired = if config.schedule.is_awake_at(now) {
-            ViewerMode::Awake
-        } else {
-            ViewerMode::Asleep
-        };
+        let now = now_utc.with_timezone(&timezone);
+        let evaluation = config.schedule.evaluate(now);
+        let schedule_desired = ViewerMode::from(evaluation.state);
 
         // Reconcile clears a manual override once the schedule agrees with it,
         // so an override holds only until the next schedule boundary. The
         // resulting desired mode is the override target when set, otherwise the
         // schedule's desired mode.
-        let (override_state, current_mode, greeting_complete) = {
+        let (override_state, current_mode, greeting_complete, override_until) = {
             let mut guard = shared_state.lock().expect("frame state poisoned");
-            let (override_state, current_mode) = guard.reconcile_override(schedule_desired);
-            (override_state, current_mode, guard.greeting_complete())
+            let (override_state, current_mode) =
+                guard.reconcile_override(schedule_desired, now_utc);
+            (
+                override_state,
+                current_mode,
+                guard.greeting_complete(),
+                guard.override_until(),
+            )
         };
         let desired_mode = override_state.target().unwrap_or(schedule_desired);
 
@@ -1589,18 +2393,21 @@ fn scheduler_loop(
             let command = match desired_mode {
                 ViewerMode::Awake => SchedulerCommand::WakeUp,
                 ViewerMode::Asleep => SchedulerCommand::GoToSleep,
+                ViewerMode::Ambient => SchedulerCommand::EnterAmbient,
             };
 
-            // Hold the initial wake until the greeting screen has had time to
-            // display, so the viewer doesn't flash straight past it on boot.
-            let waiting_for_greeting = command == SchedulerCommand::WakeUp
+            // Hold the initial wake (or ambient) until the greeting screen has
+            // had time to display, so the viewer doesn't flash straight past it
+            // on boot.
+            let waiting_for_greeting = command != SchedulerCommand::GoToSleep
                 && !greeting_complete
                 && now_instant < greeting_ready_at;
 
             if waiting_for_greeting {
-                sleep_for(
+                resumed = sleep_for(
                     greeting_ready_at.saturating_duration_since(now_instant),
                     MAX_SLEEP,
+                    resume,
                 );
                 continue;
             }
@@ -1615,7 +2422,7 @@ fn scheduler_loop(
                     let backoff = retry_backoff(*attempts);
                     let elapsed = now_instant.saturating_duration_since(*last_sent);
                     if elapsed < backoff {
-                        sleep_for(backoff - elapsed, MAX_SLEEP);
+                        resumed = sleep_for(backoff - elapsed, MAX_SLEEP, resume);
                         continue;
                     }
                     *attempts
@@ -1635,7 +2442,7 @@ fn scheduler_loop(
                         );
                     }
                     pending_command = Some((command, Instant::now(), attempts));
-                    sleep_for(COMMAND_SETTLE, MAX_SLEEP);
+                    resumed = sleep_for(COMMAND_SETTLE, MAX_SLEEP, resume);
                     continue;
                 }
                 Err(_) => {
@@ -1647,7 +2454,28 @@ fn scheduler_loop(
 
         let mut next_check = now_instant + MAX_SLEEP;
 
-        if let Some((transition, _)) = config.schedule.next_transition_after(now)
+        // Warn ahead of the scheduled sleep, unless an override is keeping the
+        // frame where it is anyway.
+        if current_mode == ViewerMode::Awake
+            && override_state == Override::Unset
+            && let Some((warn_at, sleep_at)) = evaluation.sleep_warning
+        {
+            match chrono_duration_to_std(warn_at.signed_duration_since(now)) {
+                Some(until_warning) => next_check = next_check.min(now_instant + until_warning),
+                None if warned_sleep != Some(sleep_at) => {
+                    let remaining = chrono_duration_to_std(sleep_at.signed_duration_since(now))
+                        .unwrap_or_default();
+                    if tx.send(SchedulerCommand::WarnSleep { remaining }).is_err() {
+                        debug!("scheduler exiting after receiver closed");
+                        break;
+                    }
+                    warned_sleep = Some(sleep_at);
+                }
+                None => {}
+            }
+        }
+
+        if let Some((transition, _)) = evaluation.next_change
             && let Some(duration) = chrono_duration_to_std(transition.signed_duration_since(now))
         {
             let candidate = now_instant + duration;
@@ -1660,8 +2488,17 @@ fn scheduler_loop(
             next_check = greeting_ready_at;
         }
 
+        // Wake up when a time-limited override runs out, not up to a minute later.
+        if let Some(until) = override_until {
+            let candidate = now_instant
+                + chrono_duration_to_std(until.signed_duration_since(now_utc)).unwrap_or_default();
+            if candidate < next_check {
+                next_check = candidate;
+            }
+        }
+
         let sleep_duration = next_check.saturating_duration_since(Instant::now());
-        sleep_for(sleep_duration, MAX_SLEEP);
+        resumed = sleep_for(sleep_duration, MAX_SLEEP, resume);
     }
 }
 
@@ -1677,12 +2514,31 @@ fn retry_backoff(attempts: u32) -> Duration {
     BASE.saturating_mul(1u32 << shift).min(MAX)
 }
 
-fn sleep_for(duration: Duration, max_sleep: Duration) {
+/// Sleeps the scheduler for `duration` (at most `max_sleep`), cut short by a
+/// resume signal. Returns whether one arrived.
+fn sleep_for(duration: Duration, max_sleep: Duration, resume: Option<&mpsc::Receiver<()>>) -> bool {
     if duration.is_zero() {
         thread::yield_now();
-        return;
+        return false;
+    }
+    let duration = duration.min(max_sleep);
+    match resume.map(|signals| signals.recv_timeout(duration)) {
+        Some(Ok(())) => true,
+        Some(Err(mpsc::RecvTimeoutError::Timeout)) => false,
+        // No listener, or it has gone away: sleep out the interval.
+        None | Some(Err(mpsc::RecvTimeoutError::Disconnected)) => {
+            thread::sleep(duration);
+            false
+        }
     }
-    thread::sleep(duration.min(max_sleep));
+}
+
+/// The wall-clock time `after` past `now`, saturating far in the future.
+fn wall_deadline(now: DateTime<Utc>, after: Duration) -> DateTime<Utc> {
+    ChronoDuration::from_std(after)
+        .ok()
+        .and_then(|after| now.checked_add_signed(after))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
 }
 
 fn chrono_duration_to_std(duration: ChronoDuration) -> Option<Duration> {
@@ -1701,14 +2557,6 @@ fn set_nonblocking(device: &Device) -> Result<()> {
     Ok(())
 }
 
-fn init_tracing(level: &str) -> Result<()> {
-    let filter = EnvFilter::builder()
-        .parse(level)
-        .with_context(|| format!("invalid log level '{level}'"))?;
-    tracing_subscriber::fmt().with_env_filter(filter).init();
-    Ok(())
-}
-
 fn open_device(device_override: Option<&PathBuf>) -> Result<(Device, PathBuf)> {
     if let Some(path) = device_override {
         let device =
@@ -1800,7 +2648,7 @@ fn ensure_power_key(device: &Device, path: &Path) -> Result<()> {
     Ok(())
 }
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 struct Durations {
     debounce: Duration,
     single_window: Duration,
@@ -1821,6 +2669,18 @@ impl Durations {
 enum Action {
     Single,
     Double,
+    /// A press held past `single-window-ms`.
+    Long,
+}
+
+impl Action {
+    fn as_str(self) -> &'static str {
+        match self {
+            Action::Single => "single",
+            Action::Double => "double",
+            Action::Long => "long",
+        }
+    }
 }
 
 struct ButtonTracker {
@@ -1908,11 +2768,11 @@ impl ButtonTracker {
                     return Some(Action::Double);
                 }
                 if held > self.durations.single_window {
-                    // A deliberate long press is unambiguously a single action;
+                    // A deliberate long press is never the start of a double;
                     // fire it immediately rather than waiting for a second tap
                     // (and never silently drop it).
-                    debug!(duration = ?held, "long press → single action");
-                    return Some(Action::Single);
+                    debug!(duration = ?held, "long press");
+                    return Some(Action::Long);
                 }
                 self.state = State::WaitingForSecond {
                     deadline: now + self.durations.double_window,
@@ -1962,21 +2822,48 @@ impl ButtonTracker {
     }
 }
 
+/// Feeds the edges waiting at `source` to the tracker. Returns the actions
+/// they completed, or `None` when the button has not moved.
+fn pump_source(
+    source: &mut dyn ButtonSource,
+    tracker: &mut ButtonTracker,
+) -> Result<Option<Vec<Action>>> {
+    let events = source.poll()?;
+    if events.is_empty() {
+        return Ok(None);
+    }
+    let mut actions = Vec::new();
+    for event in events {
+        match event.edge {
+            Edge::Pressed => tracker.on_press(event.at),
+            Edge::Released => actions.extend(tracker.on_release(event.at)),
+        }
+    }
+    Ok(Some(actions))
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        Action, ButtonTracker, CommandExecutor, CommandSpec, ControlSocket, Durations,
-        FORCE_SHUTDOWN_FLAG, FrameState, NO_ASK_PASSWORD_FLAG, Override, Runtime, SchedulerCommand,
-        SchedulerConfig, ScreenDetection, ScreenDetector, ScreenRuntime, ScreenState,
-        SwayEnvironment, SwayScreenDetector, TransitionSource, UnixControlSocket, ViewerMode,
-        configure_shutdown_args, find_sway_socket_with_proc_root, override_proc_root,
-        parse_sway_outputs, retry_backoff, scheduler_loop,
+        Action, ButtonTracker, ClockWatch, CommandExecutor, CommandSpec, ControlSocket,
+        DryRunExecutor, Durations, FORCE_SHUTDOWN_FLAG, FileConfig, FrameState,
+        NO_ASK_PASSWORD_FLAG, Override, Runtime, SchedulerCommand, SchedulerConfig,
+        ScreenDetection, ScreenDetector, ScreenProbe, ScreenRuntime, ScreenState, SetStateOutcome,
+        SwayCommandExecutor, SwayEnvironment, SwayScreenDetector, TransitionSource,
+        UnixControlSocket, ViewerMode, check_config, configure_shutdown_args,
+        effective_screen_off_delay, explain_schedule, find_sway_socket_with_proc_root,
+        override_proc_root, parse_sway_outputs, perform_action, pump_source, retry_backoff,
+        scheduler_loop, wall_deadline,
     };
+    use crate::actions::{ActionsConfig, ButtonActions};
+    use crate::input::{ButtonEvent, ButtonSource, Edge};
+    use chrono::{Duration as ChronoDuration, Utc};
     use config_model::AwakeScheduleConfig;
     use serde_yaml::from_str;
+    use std::collections::VecDeque;
     use std::ffi::{OsStr, OsString};
     use std::fs;
-    use std::io::Read;
+    use std::io::{Read, Write};
     use std::os::unix::fs::{MetadataExt, PermissionsExt};
     use std::os::unix::net::UnixListener;
     use std::path::{Path, PathBuf};
@@ -2076,6 +2963,13 @@ awake-scheduled: {}
         schedule
     }
 
+    fn probe(state: ScreenState) -> ScreenProbe {
+        ScreenProbe {
+            detector: Arc::new(StaticDetector::new(state)),
+            display_name: None,
+        }
+    }
+
     #[test]
     fn configure_shutdown_args_adds_force_flags() {
         let mut args = vec![String::from("poweroff")];
@@ -2136,6 +3030,10 @@ awake-scheduled: {}
     #[derive(Default, Clone)]
     struct RecordingControlSocket {
         events: Arc<Mutex<Vec<(ViewerMode, Instant)>>>,
+        /// Answer scheduled commands as a viewer holding a manual override.
+        held_off: Option<Duration>,
+        nexts: Arc<Mutex<u32>>,
+        notices: Arc<Mutex<Vec<String>>>,
     }
 
     impl RecordingControlSocket {
@@ -2146,14 +3044,51 @@ awake-scheduled: {}
         fn events(&self) -> Arc<Mutex<Vec<(ViewerMode, Instant)>>> {
             Arc::clone(&self.events)
         }
+
+        fn nexts(&self) -> u32 {
+            *self
+                .nexts
+                .lock()
+                .expect("recording control socket poisoned")
+        }
+
+        fn notices(&self) -> Vec<String> {
+            self.notices
+                .lock()
+                .expect("recording control socket poisoned")
+                .clone()
+        }
     }
 
     impl ControlSocket for RecordingControlSocket {
-        fn send_set_state(&self, state: ViewerMode) -> super::Result<()> {
+        fn send_set_state(
+            &self,
+            state: ViewerMode,
+            source: TransitionSource,
+        ) -> super::Result<SetStateOutcome> {
+            if let (Some(remaining), TransitionSource::Scheduled) = (self.held_off, source) {
+                return Ok(SetStateOutcome::HeldOff(remaining));
+            }
             self.events
                 .lock()
                 .expect("recording control socket poisoned")
                 .push((state, Instant::now()));
+            Ok(SetStateOutcome::Applied)
+        }
+
+        fn send_next(&self) -> super::Result<()> {
+            *self
+                .nexts
+                .lock()
+                .expect("recording control socket poisoned") += 1;
+            Ok(())
+        }
+
+        fn send_notice(&self, text: &str, _duration: Duration) -> super::Result<()> {
+            self.notices
+                .lock()
+                .expect("recording control socket poisoned")
+                .push(text.to_owned());
             Ok(())
         }
     }
@@ -2219,6 +3154,169 @@ awake-scheduled: {}
         }
     }
 
+    #[test]
+    fn manual_wake_before_scheduled_sleep_holds_for_the_override_window() {
+        // Awake window, two minutes before the sleep boundary: a press to wake
+        // (the frame was already awake) starts a 30-minute override.
+        let window = Duration::from_secs(30 * 60);
+        let mut state = FrameState::new(ViewerMode::Awake);
+        state.override_ttl = Some(window);
+        let pressed = Utc::now();
+        state.update(ViewerMode::Awake, TransitionSource::Manual);
+
+        // Still awake by schedule: the override holds through it.
+        let (ov, _) = state.reconcile_override(ViewerMode::Awake, pressed);
+        assert_eq!(ov, Override::ForceWake);
+
+        // The sleep boundary passes, and keeps being re-evaluated for the
+        // rest of the window; the frame stays awake throughout.
+        for minutes in [2, 10, 29] {
+            let now = pressed + ChronoDuration::minutes(minutes);
+            let (ov, mode) = state.reconcile_override(ViewerMode::Asleep, now);
+            assert_eq!(ov, Override::ForceWake, "{minutes} min after the press");
+            assert_eq!(mode, ViewerMode::Awake);
+        }
+        assert!(state.override_until().is_some());
+
+        // Once the window runs out the schedule wins.
+        let (ov, _) =
+            state.reconcile_override(ViewerMode::Asleep, wall_deadline(Utc::now(), window));
+        assert_eq!(ov, Override::Unset);
+        assert!(state.override_until().is_none());
+    }
+
+    #[test]
+    fn limited_override_clears_when_the_schedule_comes_round() {
+        let mut state = FrameState::new(ViewerMode::Awake);
+        state.override_ttl = Some(Duration::from_secs(3600));
+        state.update(ViewerMode::Asleep, TransitionSource::Manual);
+        let now = Utc::now();
+        let (ov, _) = state.reconcile_override(ViewerMode::Awake, now);
+        assert_eq!(ov, Override::ForceSleep);
+        // The schedule reaches its own sleep boundary within the hour.
+        let (ov, _) = state.reconcile_override(ViewerMode::Asleep, now);
+        assert_eq!(ov, Override::Unset);
+    }
+
+    #[test]
+    fn staying_awake_through_a_warned_sleep_holds_until_the_next_wake() {
+        let mut state = FrameState::new(ViewerMode::Awake);
+        let now = Utc::now();
+        state.warn_of_sleep(now + ChronoDuration::minutes(5));
+        assert!(state.sleep_warning_active(now));
+        assert!(!state.sleep_warning_active(now + ChronoDuration::minutes(5)));
+
+        state.stay_awake();
+        assert!(!state.sleep_warning_active(now));
+        // Unlike a plain wake while the schedule agrees, this one holds until
+        // the schedule has come round to awake again.
+        let (ov, _) = state.reconcile_override(ViewerMode::Awake, now);
+        assert_eq!(ov, Override::ForceWake);
+        let (ov, mode) = state.reconcile_override(ViewerMode::Asleep, now);
+        assert_eq!((ov, mode), (Override::ForceWake, ViewerMode::Awake));
+        let (ov, _) = state.reconcile_override(ViewerMode::Awake, now);
+        assert_eq!(ov, Override::Unset);
+    }
+
+    #[test]
+    fn override_runs_out_across_a_suspend() {
+        let mut state = FrameState::new(ViewerMode::Awake);
+        state.override_ttl = Some(Duration::from_secs(30 * 60));
+        state.update(ViewerMode::Asleep, TransitionSource::Manual);
+        let pressed = Utc::now();
+
+        // The system slept for two hours right after the press. `Instant`
+        // barely moved, but the override is measured on the wall clock.
+        let (ov, _) =
+            state.reconcile_override(ViewerMode::Awake, pressed + ChronoDuration::hours(2));
+        assert_eq!(ov, Override::Unset);
+    }
+
+    #[test]
+    fn clock_watch_flags_a_suspend_but_not_ordinary_passes() {
+        let mut clock = ClockWatch::default();
+        let start = Instant::now();
+        let wall = Utc::now();
+        assert_eq!(clock.observe(start, wall), None);
+        // A minute's sleep with a little drift.
+        let drifted = wall + ChronoDuration::seconds(60) + ChronoDuration::milliseconds(5);
+        assert_eq!(
+            clock.observe(start + Duration::from_secs(60), drifted),
+            None
+        );
+
+        // Suspended for two hours partway through a 30s sleep.
+        let resumed = drifted + ChronoDuration::hours(2) + ChronoDuration::seconds(30);
+        let jump = clock.observe(start + Duration::from_secs(90), resumed);
+        assert_eq!(jump, Some(ChronoDuration::hours(2)));
+
+        // NTP stepping the clock back an hour is a jump too.
+        let jump = clock.observe(
+            start + Duration::from_secs(91),
+            resumed + ChronoDuration::seconds(1) - ChronoDuration::hours(1),
+        );
+        assert_eq!(jump, Some(-ChronoDuration::hours(1)));
+    }
+
+    #[test]
+    fn resync_adopts_the_panel_state() {
+        let mut state = FrameState::new(ViewerMode::Asleep);
+        assert!(!state.resync(ScreenState::Off));
+        assert!(state.resync(ScreenState::On));
+        assert_eq!(state.mode, ViewerMode::Awake);
+
+        let mut ambient = FrameState::new(ViewerMode::Ambient);
+        assert!(
+            !ambient.resync(ScreenState::On),
+            "the panel stays on in ambient"
+        );
+        assert!(ambient.resync(ScreenState::Off));
+        assert_eq!(ambient.mode, ViewerMode::Asleep);
+    }
+
+    #[test]
+    fn scheduled_sleep_refused_by_the_viewer_is_deferred() {
+        let executor = RecordingExecutor::new();
+        let detector = StaticDetector::new(ScreenState::On);
+        let screen = ScreenRuntime::new(
+            command("screen-on"),
+            command("screen-off"),
+            Duration::ZERO,
+            Some("HDMI-A-1".into()),
+            Arc::new(executor.clone()),
+            Arc::new(detector),
+        );
+        let control = RecordingControlSocket {
+            held_off: Some(Duration::from_secs(600)),
+            ..RecordingControlSocket::new()
+        };
+        let mut runtime = Runtime::new(
+            Arc::new(control),
+            command("shutdown"),
+            screen,
+            Arc::new(executor.clone()),
+            ViewerMode::Awake,
+        );
+
+        runtime
+            .go_to_sleep(TransitionSource::Scheduled)
+            .expect("a held-off sleep is not an error");
+
+        assert!(
+            executor.calls().lock().unwrap().is_empty(),
+            "panel stays on"
+        );
+        let state = runtime.shared_state();
+        let mut guard = state.lock().unwrap();
+        assert_eq!(guard.mode, ViewerMode::Awake);
+        let (ov, _) = guard.reconcile_override(ViewerMode::Asleep, Utc::now());
+        assert_eq!(
+            ov,
+            Override::ForceWake,
+            "the scheduler waits the override out"
+        );
+    }
+
     #[test]
     fn power_off_never_gives_up_and_retries_with_backoff() {
         // Fail far more times than the old 30-attempt cap would have tolerated.
@@ -2272,6 +3370,48 @@ awake-scheduled: {}
         assert_eq!(executor.screen_off_calls(), 41);
     }
 
+    #[test]
+    fn dry_run_executor_records_without_spawning() {
+        let dir = tempdir().expect("tempdir");
+        let marker = dir.path().join("spawned");
+        let executor = Arc::new(DryRunExecutor::new());
+        let detector = StaticDetector::new(ScreenState::Off);
+        let touch = |label: &str| CommandSpec {
+            label: label.to_string(),
+            program: PathBuf::from("/usr/bin/touch"),
+            args: vec![marker.display().to_string()],
+        };
+        let screen = ScreenRuntime::new(
+            touch("screen-on"),
+            touch("screen-off"),
+            Duration::from_millis(0),
+            None,
+            executor.clone(),
+            Arc::new(detector),
+        );
+        let control: Arc<dyn ControlSocket> = Arc::new(RecordingControlSocket::new());
+        let mut runtime = Runtime::new(
+            control,
+            touch("shutdown"),
+            screen,
+            executor.clone(),
+            ViewerMode::Asleep,
+        );
+
+        runtime
+            .wake_up(TransitionSource::Manual)
+            .expect("dry-run wake succeeds");
+        runtime.shutdown().expect("dry-run shutdown succeeds");
+
+        let labels: Vec<String> = executor
+            .calls()
+            .into_iter()
+            .map(|call| call.label)
+            .collect();
+        assert_eq!(labels, vec!["screen-on", "shutdown"]);
+        assert!(!marker.exists(), "dry-run must never spawn the command");
+    }
+
     #[test]
     fn single_press_triggers_single_action() {
         let mut tracker = ButtonTracker::new(durations());
@@ -2290,15 +3430,20 @@ awake-scheduled: {}
     }
 
     #[test]
-    fn long_press_fires_single_immediately() {
+    fn long_press_fires_immediately() {
         // Regression: a press held longer than single_window must not be
-        // silently dropped — it fires a Single action on release.
+        // silently dropped — it fires a Long action on release, which the
+        // default actions map to the same toggle as a single press.
         let mut tracker = ButtonTracker::new(durations());
         let start = Instant::now();
 
         tracker.on_press(start);
         let held = durations().single_window + Duration::from_millis(500);
-        assert_eq!(tracker.on_release(start + held), Some(Action::Single));
+        assert_eq!(tracker.on_release(start + held), Some(Action::Long));
+        assert_eq!(
+            ButtonActions::default().verb_for(Action::Long),
+            ButtonActions::default().verb_for(Action::Single)
+        );
     }
 
     #[test]
@@ -2310,12 +3455,12 @@ awake-scheduled: {}
         assert_eq!(state.override_state, Override::ForceSleep);
 
         // Schedule still wants awake → override holds, mode stays asleep.
-        let (ov, mode) = state.reconcile_override(ViewerMode::Awake);
+        let (ov, mode) = state.reconcile_override(ViewerMode::Awake, Utc::now());
         assert_eq!(ov, Override::ForceSleep);
         assert_eq!(mode, ViewerMode::Asleep);
 
         // Schedule now wants asleep → override clears.
-        let (ov, _) = state.reconcile_override(ViewerMode::Asleep);
+        let (ov, _) = state.reconcile_override(ViewerMode::Asleep, Utc::now());
         assert_eq!(ov, Override::Unset);
     }
 
@@ -2331,7 +3476,7 @@ awake-scheduled: {}
         // User presses again → manual sleep. Override is now ForceSleep, which
         // agrees with the asleep schedule and clears on the next reconcile.
         state.update(ViewerMode::Asleep, TransitionSource::Manual);
-        let (ov, _) = state.reconcile_override(ViewerMode::Asleep);
+        let (ov, _) = state.reconcile_override(ViewerMode::Asleep, Utc::now());
         assert_eq!(ov, Override::Unset);
     }
 
@@ -2434,6 +3579,111 @@ awake-scheduled: {}
         assert_eq!(env.resolve_socket().expect("resolve new"), new_socket);
     }
 
+    /// Fake sway client: refuses sockets marked `<sock>.dead` (a crashed sway that
+    /// left its socket file behind) and logs the socket of every successful call.
+    fn fake_sway_client(dir: &Path) -> (PathBuf, PathBuf) {
+        let log = dir.join("calls.log");
+        let script = dir.join("fake-swaymsg");
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\nif [ -e \"$SWAYSOCK.dead\" ]; then\n  echo \"Unable to connect to $SWAYSOCK\" >&2\n  exit 1\nfi\necho \"$SWAYSOCK\" >> {}\n",
+                log.display()
+            ),
+        )
+        .expect("write script");
+        let mut perms = fs::metadata(&script).expect("metadata").permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&script, perms).expect("set perms");
+        (script, log)
+    }
+
+    #[test]
+    fn command_retries_on_refreshed_socket_after_sway_restart() {
+        let runtime = tempdir().expect("tempdir");
+        let runtime_path = runtime.path().to_path_buf();
+        let uid = fs::metadata(&runtime_path).expect("runtime metadata").uid();
+        let procfs = tempdir().expect("procfs");
+        let _proc_guard = override_proc_root(procfs.path());
+        let scripts = tempdir().expect("scripts");
+        let (script, log) = fake_sway_client(scripts.path());
+
+        let old_pid = 31313;
+        let old_socket = runtime_path.join(format!("sway-ipc.{uid}.{old_pid}.sock"));
+        let _old_listener = UnixListener::bind(&old_socket).expect("old socket");
+        write_proc_status(procfs.path(), old_pid, "sway", uid);
+
+        let env = Arc::new(SwayEnvironment::new(runtime_path.clone(), None));
+        let executor = SwayCommandExecutor::new(env.clone());
+        let spec = CommandSpec {
+            label: "screen-on".into(),
+            program: script,
+            args: Vec::new(),
+        };
+        executor.execute(&spec).expect("first call");
+
+        // Sway crashes and restarts: the old socket file lingers but refuses
+        // connections, and a new PID owns a new socket.
+        fs::write(
+            runtime_path.join(format!("sway-ipc.{uid}.{old_pid}.sock.dead")),
+            b"",
+        )
+        .expect("mark dead");
+        fs::remove_dir_all(procfs.path().join(old_pid.to_string())).expect("remove old proc");
+        let new_pid = 32323;
+        let new_socket = runtime_path.join(format!("sway-ipc.{uid}.{new_pid}.sock"));
+        let _new_listener = UnixListener::bind(&new_socket).expect("new socket");
+        write_proc_status(procfs.path(), new_pid, "sway", uid);
+
+        executor.execute(&spec).expect("retried on the new socket");
+        let calls = fs::read_to_string(&log).expect("read log");
+        let calls: Vec<&str> = calls.lines().collect();
+        assert_eq!(
+            calls,
+            [old_socket.to_str().unwrap(), new_socket.to_str().unwrap()]
+        );
+        assert_eq!(env.resolve_socket().expect("cached socket"), new_socket);
+    }
+
+    #[test]
+    fn command_fails_clearly_when_sway_does_not_come_back() {
+        let runtime = tempdir().expect("tempdir");
+        let runtime_path = runtime.path().to_path_buf();
+        let uid = fs::metadata(&runtime_path).expect("runtime metadata").uid();
+        let procfs = tempdir().expect("procfs");
+        let _proc_guard = override_proc_root(procfs.path());
+        let scripts = tempdir().expect("scripts");
+        let (script, _log) = fake_sway_client(scripts.path());
+
+        let pid = 41414;
+        let socket = runtime_path.join(format!("sway-ipc.{uid}.{pid}.sock"));
+        let _listener = UnixListener::bind(&socket).expect("socket");
+        write_proc_status(procfs.path(), pid, "sway", uid);
+        let env = Arc::new(SwayEnvironment::new(runtime_path.clone(), None));
+        let executor = SwayCommandExecutor::new(env);
+        let spec = CommandSpec {
+            label: "screen-on".into(),
+            program: script,
+            args: Vec::new(),
+        };
+        executor.execute(&spec).expect("first call");
+
+        fs::write(
+            runtime_path.join(format!("sway-ipc.{uid}.{pid}.sock.dead")),
+            b"",
+        )
+        .expect("mark dead");
+        fs::remove_dir_all(procfs.path().join(pid.to_string())).expect("remove proc");
+
+        let err = executor
+            .execute(&spec)
+            .expect_err("no sway to retry against");
+        assert!(
+            format!("{err:#}").contains("sway IPC socket is unavailable"),
+            "unexpected error: {err:#}"
+        );
+    }
+
     #[test]
     fn find_sway_socket_prefers_active_pid_socket() {
         let runtime = tempdir().expect("runtime dir");
@@ -2499,6 +3749,83 @@ awake-scheduled: {}
         assert_eq!(env.resolve_socket().expect("discover socket"), socket_path);
     }
 
+    /// Stands in for an evdev device or GPIO line: each poll hands over the
+    /// next scripted batch of edges.
+    struct ScriptedSource {
+        batches: VecDeque<Vec<ButtonEvent>>,
+    }
+
+    impl ScriptedSource {
+        fn new(start: Instant, batches: &[&[(Edge, u64)]]) -> Self {
+            let batches = batches
+                .iter()
+                .map(|batch| {
+                    batch
+                        .iter()
+                        .map(|&(edge, ms)| ButtonEvent {
+                            edge,
+                            at: start + Duration::from_millis(ms),
+                        })
+                        .collect()
+                })
+                .collect();
+            Self { batches }
+        }
+    }
+
+    impl ButtonSource for ScriptedSource {
+        fn poll(&mut self) -> anyhow::Result<Vec<ButtonEvent>> {
+            Ok(self.batches.pop_front().unwrap_or_default())
+        }
+    }
+
+    #[test]
+    fn source_edges_drive_the_tracker() {
+        use Edge::{Pressed, Released};
+        let start = Instant::now();
+        let mut tracker = ButtonTracker::new(durations());
+
+        // A double tap arriving in one batch, as a GPIO line delivers it.
+        let mut source = ScriptedSource::new(
+            start,
+            &[&[
+                (Pressed, 0),
+                (Released, 100),
+                (Pressed, 220),
+                (Released, 300),
+            ]],
+        );
+        assert_eq!(
+            pump_source(&mut source, &mut tracker).unwrap(),
+            Some(vec![Action::Double])
+        );
+        assert_eq!(pump_source(&mut source, &mut tracker).unwrap(), None);
+
+        // A single tap whose contacts bounce on release, spread over polls.
+        let start = start + Duration::from_secs(5);
+        let mut source = ScriptedSource::new(
+            start,
+            &[
+                &[(Pressed, 0)],
+                &[(Released, 90), (Pressed, 95), (Released, 105)],
+                &[],
+            ],
+        );
+        assert_eq!(
+            pump_source(&mut source, &mut tracker).unwrap(),
+            Some(vec![])
+        );
+        assert_eq!(
+            pump_source(&mut source, &mut tracker).unwrap(),
+            Some(vec![])
+        );
+        assert_eq!(pump_source(&mut source, &mut tracker).unwrap(), None);
+        assert_eq!(
+            tracker.handle_timeout(start + Duration::from_millis(600)),
+            Some(Action::Single)
+        );
+    }
+
     #[test]
     fn bouncing_release_does_not_trigger_double() {
         let mut tracker = ButtonTracker::new(durations());
@@ -2560,10 +3887,7 @@ awake-scheduled: {}
         perms.set_mode(0o755);
         fs::set_permissions(&script_path, perms).expect("set perms");
 
-        let env = Arc::new(SwayEnvironment {
-            runtime_dir: dir.path().to_path_buf(),
-            explicit_socket: None,
-        });
+        let env = Arc::new(SwayEnvironment::new(dir.path().to_path_buf(), None));
 
         let detector = SwayScreenDetector::new(env, Some(script_path));
         let detection = detector
@@ -2583,10 +3907,7 @@ awake-scheduled: {}
         perms.set_mode(0o755);
         fs::set_permissions(&script_path, perms).expect("set perms");
 
-        let env = Arc::new(SwayEnvironment {
-            runtime_dir: dir.path().to_path_buf(),
-            explicit_socket: None,
-        });
+        let env = Arc::new(SwayEnvironment::new(dir.path().to_path_buf(), None));
 
         let detector = SwayScreenDetector::new(env, Some(script_path));
         let detection = detector.detect(None).expect("powerctl state detection");
@@ -2692,6 +4013,258 @@ awake-scheduled: {}
         assert!(runtime.time_until_power_off(Instant::now()).is_none());
     }
 
+    /// A runtime that has just been put to sleep with the default 3.5 s off-delay.
+    fn runtime_in_off_delay(
+        executor: &RecordingExecutor,
+        control: &RecordingControlSocket,
+    ) -> (Runtime, Duration) {
+        let off_delay = Duration::from_millis(3500);
+        let screen = ScreenRuntime::new(
+            command("screen-on"),
+            command("screen-off"),
+            off_delay,
+            Some("HDMI-A-1".into()),
+            Arc::new(executor.clone()),
+            Arc::new(StaticDetector::new(ScreenState::On)),
+        );
+        let runtime_control: Arc<dyn ControlSocket> = Arc::new(control.clone());
+        let mut runtime = Runtime::new(
+            runtime_control,
+            command("shutdown"),
+            screen,
+            Arc::new(executor.clone()),
+            ViewerMode::Awake,
+        );
+        runtime
+            .go_to_sleep(TransitionSource::Manual)
+            .expect("sleep should succeed");
+        (runtime, off_delay)
+    }
+
+    fn executed(executor: &RecordingExecutor) -> Vec<String> {
+        let calls = executor.calls();
+        let guard = calls.lock().expect("executor calls poisoned");
+        guard.iter().map(|(label, _)| label.clone()).collect()
+    }
+
+    #[test]
+    fn press_during_off_delay_wakes_and_skips_the_power_off() {
+        let executor = RecordingExecutor::new();
+        let control = RecordingControlSocket::new();
+        let start = Instant::now();
+        let (mut runtime, off_delay) = runtime_in_off_delay(&executor, &control);
+
+        // A press half a second into the delay is seen right away, not after it.
+        let mut tracker = ButtonTracker::new(durations());
+        let press = start + Duration::from_millis(500);
+        tracker.on_press(press);
+        assert!(
+            tracker
+                .on_release(press + Duration::from_millis(100))
+                .is_none()
+        );
+        let action = tracker
+            .handle_timeout(press + Duration::from_millis(600))
+            .expect("single press decided inside the delay window");
+        assert!(press + Duration::from_millis(600) < start + off_delay);
+        perform_action(action, &mut runtime);
+
+        assert_eq!(executed(&executor), ["screen-on"]);
+        {
+            let events = control.events();
+            let guard = events.lock().expect("control events poisoned");
+            let modes: Vec<_> = guard.iter().map(|(mode, _)| *mode).collect();
+            assert_eq!(modes, [ViewerMode::Asleep, ViewerMode::Awake]);
+        }
+
+        // The superseded power-off never runs, even once its deadline passes.
+        assert!(runtime.time_until_power_off(start).is_none());
+        runtime.poll_pending_power_off(start + off_delay + Duration::from_secs(1));
+        assert_eq!(executed(&executor), ["screen-on"]);
+    }
+
+    #[test]
+    fn double_press_during_off_delay_still_shuts_down() {
+        let executor = RecordingExecutor::new();
+        let control = RecordingControlSocket::new();
+        let start = Instant::now();
+        let (mut runtime, off_delay) = runtime_in_off_delay(&executor, &control);
+
+        let mut tracker = ButtonTracker::new(durations());
+        let first = start + Duration::from_millis(200);
+        tracker.on_press(first);
+        assert!(
+            tracker
+                .on_release(first + Duration::from_millis(100))
+                .is_none()
+        );
+        let second = first + Duration::from_millis(250);
+        tracker.on_press(second);
+        let action = tracker
+            .on_release(second + Duration::from_millis(80))
+            .expect("double press recognised during the delay");
+        assert_eq!(action, Action::Double);
+        perform_action(action, &mut runtime);
+
+        assert!(second < start + off_delay);
+        assert_eq!(executed(&executor), ["shutdown"]);
+    }
+
+    fn awake_runtime(executor: &RecordingExecutor, control: &RecordingControlSocket) -> Runtime {
+        let screen = ScreenRuntime::new(
+            command("screen-on"),
+            command("screen-off"),
+            Duration::ZERO,
+            None,
+            Arc::new(executor.clone()),
+            Arc::new(StaticDetector::new(ScreenState::On)),
+        );
+        Runtime::new(
+            Arc::new(control.clone()),
+            command("shutdown"),
+            screen,
+            Arc::new(executor.clone()),
+            ViewerMode::Awake,
+        )
+    }
+
+    fn modes(control: &RecordingControlSocket) -> Vec<ViewerMode> {
+        let events = control.events();
+        let guard = events.lock().expect("control events poisoned");
+        guard.iter().map(|(mode, _)| *mode).collect()
+    }
+
+    #[test]
+    fn a_press_during_the_sleep_warning_keeps_the_frame_awake() {
+        let executor = RecordingExecutor::new();
+        let control = RecordingControlSocket::new();
+        let mut runtime = awake_runtime(&executor, &control);
+
+        runtime
+            .warn_of_sleep(Duration::from_secs(5 * 60))
+            .expect("warning sent");
+        perform_action(Action::Single, &mut runtime);
+
+        assert!(modes(&control).is_empty(), "the press did not sleep");
+        assert_eq!(
+            control.notices(),
+            [
+                "Going to sleep in 5 minutes — press the button to stay awake",
+                "Staying awake",
+            ]
+        );
+        let (ov, _) = runtime
+            .shared_state()
+            .lock()
+            .unwrap()
+            .reconcile_override(ViewerMode::Asleep, Utc::now());
+        assert_eq!(ov, Override::ForceWake);
+
+        // Once the warning has been answered the button toggles as usual.
+        perform_action(Action::Single, &mut runtime);
+        assert_eq!(modes(&control), [ViewerMode::Asleep]);
+    }
+
+    #[test]
+    fn each_verb_dispatches_through_the_runtime() {
+        let executor = RecordingExecutor::new();
+        let control = RecordingControlSocket::new();
+        let actions = from_str::<ActionsConfig>(
+            "single: next-photo
+double: sleep
+long:
+  custom-command:
+    program: /bin/true
+",
+        )
+        .unwrap()
+        .into_actions();
+        let mut runtime = awake_runtime(&executor, &control).with_actions(actions);
+
+        perform_action(Action::Single, &mut runtime);
+        assert_eq!(control.nexts(), 1);
+        assert!(modes(&control).is_empty());
+
+        perform_action(Action::Double, &mut runtime);
+        assert_eq!(modes(&control), [ViewerMode::Asleep]);
+
+        perform_action(Action::Long, &mut runtime);
+        assert_eq!(executed(&executor), ["screen-off", "long-press"]);
+
+        let actions = from_str::<ActionsConfig>(
+            "single: wake
+double: toggle-sleep
+long: shutdown
+",
+        )
+        .unwrap()
+        .into_actions();
+        let mut runtime = runtime.with_actions(actions);
+        perform_action(Action::Single, &mut runtime);
+        perform_action(Action::Double, &mut runtime);
+        assert_eq!(
+            modes(&control),
+            [ViewerMode::Asleep, ViewerMode::Awake, ViewerMode::Asleep]
+        );
+        perform_action(Action::Long, &mut runtime);
+        assert_eq!(
+            executed(&executor).last().map(String::as_str),
+            Some("shutdown")
+        );
+    }
+
+    #[test]
+    fn a_press_from_ambient_wakes_the_frame_fully() {
+        let executor = RecordingExecutor::new();
+        let control = RecordingControlSocket::new();
+        let mut runtime = awake_runtime(&executor, &control);
+        runtime
+            .enter_ambient(TransitionSource::Scheduled)
+            .expect("enter ambient");
+        assert_eq!(modes(&control), [ViewerMode::Ambient]);
+        assert_eq!(executed(&executor), ["screen-on"]);
+
+        // The panel reads as on, but the press wakes rather than sleeps.
+        perform_action(Action::Single, &mut runtime);
+        assert_eq!(modes(&control), [ViewerMode::Ambient, ViewerMode::Awake]);
+        assert_eq!(runtime.current_viewer_mode(), ViewerMode::Awake);
+    }
+
+    #[test]
+    fn default_actions_toggle_on_single_and_long_and_shut_down_on_double() {
+        let executor = RecordingExecutor::new();
+        let control = RecordingControlSocket::new();
+        let mut runtime = awake_runtime(&executor, &control);
+        perform_action(Action::Single, &mut runtime);
+        assert_eq!(modes(&control), [ViewerMode::Asleep]);
+
+        // A long press toggles too: mid-sleep, it wakes the frame.
+        let control = RecordingControlSocket::new();
+        let (mut runtime, _) = runtime_in_off_delay(&executor, &control);
+        perform_action(Action::Long, &mut runtime);
+        assert_eq!(modes(&control), [ViewerMode::Asleep, ViewerMode::Awake]);
+        assert_eq!(control.nexts(), 0);
+
+        perform_action(Action::Double, &mut runtime);
+        assert_eq!(
+            executed(&executor).last().map(String::as_str),
+            Some("shutdown")
+        );
+    }
+
+    #[test]
+    fn screen_off_delay_covers_sleep_transition() {
+        let transition = Duration::from_millis(1500);
+        assert_eq!(
+            effective_screen_off_delay(Duration::from_millis(3500), transition),
+            Duration::from_millis(3500)
+        );
+        assert_eq!(
+            effective_screen_off_delay(Duration::from_millis(500), transition),
+            transition
+        );
+    }
+
     #[test]
     fn go_to_sleep_enforces_power_off_when_viewer_thinks_asleep() {
         let executor = RecordingExecutor::new();
@@ -2742,12 +4315,41 @@ awake-scheduled: {}
             String::from_utf8(buf).expect("utf8 payload")
         });
 
-        socket
-            .send_set_state(ViewerMode::Awake)
+        let outcome = socket
+            .send_set_state(ViewerMode::Awake, TransitionSource::Manual)
             .expect("send payload");
 
         let payload = handle.join().expect("server thread");
-        assert_eq!(payload, r#"{"command":"set-state","state":"awake"}"#);
+        assert_eq!(
+            payload,
+            r#"{"command":"set-state","source":"manual","state":"awake"}"#
+        );
+        assert_eq!(outcome, SetStateOutcome::Applied);
+    }
+
+    #[test]
+    fn control_socket_reports_a_held_off_transition() {
+        let dir = tempdir().expect("tempdir");
+        let socket_path = dir.path().join("control.sock");
+        let listener = UnixListener::bind(&socket_path).expect("bind control socket");
+
+        let socket = UnixControlSocket::new(socket_path.clone());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept connection");
+            let mut buf = Vec::new();
+            stream.read_to_end(&mut buf).expect("read payload");
+            let reply = r#"{"status":"error","error":"manual override active","override_remaining_seconds":1680}"#;
+            stream.write_all(reply.as_bytes()).expect("write reply");
+            String::from_utf8(buf).expect("utf8 payload")
+        });
+
+        let outcome = socket
+            .send_set_state(ViewerMode::Asleep, TransitionSource::Scheduled)
+            .expect("send payload");
+
+        let payload = handle.join().expect("server thread");
+        assert!(payload.contains(r#""source":"scheduled""#), "{payload}");
+        assert_eq!(outcome, SetStateOutcome::HeldOff(Duration::from_secs(1680)));
     }
 
     #[test]
@@ -2755,13 +4357,14 @@ awake-scheduled: {}
         let config = SchedulerConfig {
             schedule: always_awake_schedule(),
             greeting_delay: Duration::from_millis(60),
+            screen: probe(ScreenState::On),
         };
         let state = Arc::new(Mutex::new(FrameState::new(ViewerMode::Asleep)));
         let (tx, rx) = mpsc::channel();
         let handle = thread::spawn({
             let config = config.clone();
             let state = Arc::clone(&state);
-            move || scheduler_loop(config, state, tx)
+            move || scheduler_loop(config, state, tx, None)
         });
 
         let start = Instant::now();
@@ -2775,6 +4378,40 @@ awake-scheduled: {}
         handle.join().expect("scheduler thread");
     }
 
+    #[test]
+    fn scheduler_enters_ambient_inside_an_ambient_window() {
+        let mut schedule: AwakeScheduleConfig = from_str(
+            r#"
+timezone: "UTC"
+awake-scheduled:
+  ambient:
+    daily:
+      - ["00:00", "23:59"]
+"#,
+        )
+        .expect("valid schedule yaml");
+        schedule.validate().expect("valid schedule");
+        let config = SchedulerConfig {
+            schedule,
+            greeting_delay: Duration::from_millis(0),
+            screen: probe(ScreenState::On),
+        };
+        let state = Arc::new(Mutex::new(FrameState::new(ViewerMode::Asleep)));
+        let (tx, rx) = mpsc::channel();
+        let handle = thread::spawn({
+            let state = Arc::clone(&state);
+            move || scheduler_loop(config, state, tx, None)
+        });
+
+        let command = rx
+            .recv_timeout(Duration::from_secs(1))
+            .expect("scheduler ambient");
+        assert_eq!(command, SchedulerCommand::EnterAmbient);
+
+        drop(rx);
+        drop(handle);
+    }
+
     #[test]
     fn scheduler_does_not_rewake_after_manual_sleep_in_wake_window() {
         // Regression: a manual sleep during a scheduled wake window must stick.
@@ -2783,6 +4420,7 @@ awake-scheduled: {}
         let config = SchedulerConfig {
             schedule: always_awake_schedule(),
             greeting_delay: Duration::from_millis(0),
+            screen: probe(ScreenState::On),
         };
         let state = Arc::new(Mutex::new(FrameState::new(ViewerMode::Awake)));
         {
@@ -2795,7 +4433,7 @@ awake-scheduled: {}
         let handle = thread::spawn({
             let config = config.clone();
             let state = Arc::clone(&state);
-            move || scheduler_loop(config, state, tx)
+            move || scheduler_loop(config, state, tx, None)
         });
 
         // No wake command should arrive: the override keeps it asleep.
@@ -2814,6 +4452,7 @@ awake-scheduled: {}
         let config = SchedulerConfig {
             schedule: always_asleep_schedule(),
             greeting_delay: Duration::from_millis(0),
+            screen: probe(ScreenState::On),
         };
         let state = Arc::new(Mutex::new(FrameState::new(ViewerMode::Asleep)));
         {
@@ -2825,7 +4464,7 @@ awake-scheduled: {}
         let handle = thread::spawn({
             let config = config.clone();
             let state = Arc::clone(&state);
-            move || scheduler_loop(config, state, tx)
+            move || scheduler_loop(config, state, tx, None)
         });
 
         // No sleep command should arrive: the force-wake override holds.
@@ -2842,6 +4481,7 @@ awake-scheduled: {}
         let config = SchedulerConfig {
             schedule: always_awake_schedule(),
             greeting_delay: Duration::from_millis(0),
+            screen: probe(ScreenState::On),
         };
         let state = Arc::new(Mutex::new(FrameState::new(ViewerMode::Asleep)));
 
@@ -2849,7 +4489,7 @@ awake-scheduled: {}
         let handle = thread::spawn({
             let config = config.clone();
             let state = Arc::clone(&state);
-            move || scheduler_loop(config, state, tx)
+            move || scheduler_loop(config, state, tx, None)
         });
 
         let command = rx
@@ -2864,6 +4504,67 @@ awake-scheduled: {}
         handle.join().expect("scheduler thread");
     }
 
+    #[test]
+    fn scheduler_reevaluates_at_once_on_resume() {
+        // The panel came back from suspend switched off while buttond still
+        // tracks the frame as awake.
+        let config = SchedulerConfig {
+            schedule: always_awake_schedule(),
+            greeting_delay: Duration::from_millis(0),
+            screen: probe(ScreenState::Off),
+        };
+        let state = Arc::new(Mutex::new(FrameState::new(ViewerMode::Awake)));
+        let (tx, rx) = mpsc::channel();
+        let (resume_tx, resume_rx) = mpsc::channel();
+        let handle = thread::spawn(move || scheduler_loop(config, state, tx, Some(resume_rx)));
+
+        // Awake as scheduled: the scheduler settles into a long sleep.
+        assert!(rx.recv_timeout(Duration::from_millis(150)).is_err());
+
+        resume_tx.send(()).expect("scheduler listening");
+        let command = rx
+            .recv_timeout(Duration::from_secs(1))
+            .expect("wake right after the resume");
+        assert_eq!(command, SchedulerCommand::WakeUp);
+
+        drop(rx);
+        drop(handle);
+    }
+
+    #[test]
+    fn scheduler_warns_once_ahead_of_a_scheduled_sleep() {
+        // Awake since an hour ago, asleep in a minute or two: well inside a
+        // five-minute warning.
+        let now = Utc::now();
+        let start = (now - ChronoDuration::hours(1)).format("%H:%M");
+        let end = (now + ChronoDuration::minutes(2)).format("%H:%M");
+        let mut schedule: AwakeScheduleConfig = from_str(&format!(
+            "timezone: UTC\nsleep-warning-minutes: 5\nawake-scheduled:\n  daily:\n    - [\"{start}\", \"{end}\"]\n"
+        ))
+        .expect("valid schedule yaml");
+        schedule.validate().expect("valid schedule");
+        let config = SchedulerConfig {
+            schedule,
+            greeting_delay: Duration::from_millis(0),
+            screen: probe(ScreenState::On),
+        };
+        let state = Arc::new(Mutex::new(FrameState::new(ViewerMode::Awake)));
+        let (tx, rx) = mpsc::channel();
+        let handle = thread::spawn(move || scheduler_loop(config, state, tx, None));
+
+        let command = rx
+            .recv_timeout(Duration::from_secs(1))
+            .expect("sleep warning");
+        let SchedulerCommand::WarnSleep { remaining } = command else {
+            panic!("expected a warning, got {command:?}");
+        };
+        assert!(remaining <= Duration::from_secs(120), "{remaining:?}");
+        assert!(rx.recv_timeout(Duration::from_millis(150)).is_err());
+
+        drop(rx);
+        drop(handle);
+    }
+
     #[test]
     fn retry_backoff_grows_then_caps() {
         // attempts == 0 (no send yet) and the first send both use the base delay.
@@ -2879,4 +4580,149 @@ awake-scheduled: {}
         assert_eq!(retry_backoff(100), Duration::from_secs(60));
         assert_eq!(retry_backoff(u32::MAX), Duration::from_secs(60));
     }
+
+    #[test]
+    fn check_config_reports_the_effective_settings() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(
+            &path,
+            "photo-library-path: /photos\nbuttond:\n  debounce-ms: 40\n",
+        )
+        .unwrap();
+
+        let report = check_config(&path, None).expect("valid config");
+
+        assert!(report.starts_with(&format!("# {}: configuration is valid", path.display())));
+        assert!(report.contains("debounce-ms: 40"), "{report}");
+        assert!(report.contains("off-delay-ms: 3500"), "{report}");
+        // The report reads back as the same settings.
+        let reread: FileConfig = from_str(&report).expect("report parses");
+        assert_eq!(reread.buttond.debounce_ms, 40);
+    }
+
+    const DST_SCHEDULE: &str = r#"
+greeting-screen:
+  duration-seconds: 30
+awake-schedule:
+  timezone: "America/New_York"
+  sleep-warning-minutes: 10
+  awake-scheduled:
+    daily:
+      - ["07:00", "22:00"]
+    saturday:
+      - ["21:00", "02:30"]
+"#;
+
+    #[test]
+    fn explain_schedule_walks_an_overnight_window_into_spring_forward() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(&path, DST_SCHEDULE).unwrap();
+
+        let report =
+            explain_schedule(&path, Some("2024-03-10T06:25:00Z"), false).expect("explanation");
+        let err = explain_schedule(&path, Some("tomorrow"), false).unwrap_err();
+        assert!(format!("{err:#}").contains("--at"), "{err:#}");
+
+        assert_eq!(
+            report,
+            "at: 2024-03-10T01:25:00-05:00 (Sunday, America/New_York)\n\
+             rules: daily\n\
+             state: awake\n\
+             window: 2024-03-09T21:00:00-05:00 to 2024-03-10T03:00:00-04:00 from saturday\n\
+             next: asleep at 2024-03-10T03:00:00-04:00\n\
+             greeting: a start now holds the wake until 2024-03-10T01:25:30-05:00\n\
+             sleep-warning: from 2024-03-10T01:50:00-05:00 for the sleep at 2024-03-10T03:00:00-04:00\n"
+        );
+    }
+
+    #[test]
+    fn explain_schedule_reports_json_across_fall_back() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(&path, DST_SCHEDULE).unwrap();
+
+        // Fifteen seconds before Sunday's wake, back on standard time: a start
+        // now would hold that wake for the greeting.
+        let report = explain_schedule(&path, Some("2024-11-03T11:59:45Z"), true).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+
+        assert_eq!(report["at"], "2024-11-03T06:59:45-05:00");
+        assert_eq!(report["rules"], "daily");
+        assert_eq!(report["state"], "asleep");
+        assert_eq!(report["window"], serde_json::Value::Null);
+        assert_eq!(report["next_change"]["at"], "2024-11-03T07:00:00-05:00");
+        assert_eq!(report["next_change"]["state"], "awake");
+        assert_eq!(report["greeting_hold_until"], "2024-11-03T07:00:15-05:00");
+        assert_eq!(report["sleep_warning"], serde_json::Value::Null);
+
+        // Saturday's window runs past the repeated hour to 02:30 EST, and its
+        // warning is up.
+        let report = explain_schedule(&path, Some("2024-11-03T02:25:00-05:00"), true).unwrap();
+        let report: serde_json::Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(report["window"]["rules"], "saturday");
+        assert_eq!(report["sleep_warning"]["showing"], true);
+        assert_eq!(
+            report["sleep_warning"]["sleep_at"],
+            "2024-11-03T02:30:00-05:00"
+        );
+    }
+
+    #[test]
+    fn explain_schedule_without_a_schedule_says_so() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(&path, "photo-library-path: /photos\n").unwrap();
+
+        let report = explain_schedule(&path, None, false).unwrap();
+        assert_eq!(
+            report,
+            "no awake-schedule configured; the frame stays awake\n"
+        );
+    }
+
+    #[test]
+    fn check_config_names_the_bad_field() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(&path, "buttond:\n  debounce-ms: soon\n").unwrap();
+
+        let err = format!("{:#}", check_config(&path, None).unwrap_err());
+
+        assert!(err.contains("buttond.debounce-ms"), "{err}");
+    }
+
+    #[test]
+    fn check_config_accepts_a_gpio_button() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(
+            &path,
+            "buttond:\n  input: gpio\n  gpio:\n    line: 17\n    pull: none\n",
+        )
+        .unwrap();
+
+        let report = check_config(&path, None).expect("valid config");
+        assert!(report.contains("input: gpio"), "{report}");
+        assert!(report.contains("line: 17"), "{report}");
+
+        fs::write(&path, "buttond:\n  input: gpio\n").unwrap();
+        let err = format!("{:#}", check_config(&path, None).unwrap_err());
+        assert!(err.contains("no buttond.gpio section"), "{err}");
+    }
+
+    #[test]
+    fn check_config_reports_button_actions() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(&path, "buttond:\n  actions:\n    single: next-photo\n").unwrap();
+
+        let report = check_config(&path, None).expect("valid config");
+        assert!(report.contains("single: next-photo"), "{report}");
+
+        fs::write(&path, "buttond:\n  actions:\n    double: reboot\n").unwrap();
+        let err = format!("{:#}", check_config(&path, None).unwrap_err());
+        assert!(err.contains("next-photo"), "{err}");
+    }
 }
@@ -1,68 +1,238 @@
 use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-use anyhow::{Result, ensure};
+use anyhow::{Context, Result, bail, ensure};
+use chrono::format::{Item, StrftimeItems};
 use chrono::{
-    DateTime, Datelike, Duration as ChronoDuration, LocalResult, NaiveDate, NaiveDateTime,
-    NaiveTime, TimeZone, Weekday,
+    DateTime, Datelike, Duration as ChronoDuration, FixedOffset, Local, LocalResult, NaiveDate,
+    NaiveDateTime, NaiveTime, TimeZone, Utc, Weekday,
 };
 use chrono_tz::Tz;
-use serde::Deserialize;
 use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
 
-pub use awake::{AwakeScheduleConfig, AwakeScheduleRules, AwakeTimeRange};
+pub use awake::{
+    AwakeScheduleConfig, AwakeScheduleRules, AwakeTimeRange, RuleSource, ScheduleEvaluation,
+    ScheduleWindow, ScheduledState,
+};
+#[cfg(unix)]
+pub use control_socket::{connect_control_socket, fallback_control_socket_path};
+pub use file::{check_report, expand_path, from_yaml_str, read_yaml_file};
 pub use greeting::{
-    GreetingScreenColorsConfig, GreetingScreenConfig, ScreenMessageConfig, SleepScreenConfig,
+    EmptyLibraryScreenConfig, GreetingScreenColorsConfig, GreetingScreenConfig, LibraryState,
+    MessageContext, ScreenMessageConfig, SleepScreenConfig, SleepTransitionStyle,
 };
 pub use showcase::ShowcaseConfig;
 
 mod greeting {
     use super::*;
 
-    #[derive(Debug, Clone, Deserialize, Default)]
-    #[serde(rename_all = "kebab-case", default)]
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
     pub struct GreetingScreenColorsConfig {
         pub background: Option<String>,
         pub font: Option<String>,
         pub accent: Option<String>,
     }
 
-    #[derive(Debug, Clone, Deserialize, Default)]
-    #[serde(rename_all = "kebab-case", default)]
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
     pub struct ScreenMessageConfig {
         pub message: Option<String>,
         pub font: Option<String>,
         pub stroke_width: Option<f32>,
         pub corner_radius: Option<f32>,
-        #[serde(default)]
+        pub fade_ms: Option<u64>,
+        pub max_width_percent: Option<f32>,
         pub colors: GreetingScreenColorsConfig,
     }
 
-    #[derive(Debug, Clone, Deserialize, Default)]
-    #[serde(rename_all = "kebab-case", default)]
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "kebab-case", from = "GreetingScreenFields")]
     pub struct GreetingScreenConfig {
         #[serde(flatten)]
         pub screen: ScreenMessageConfig,
+        /// Longest the greeting stays up while no photo is ready.
         pub duration_seconds: Option<f32>,
+        /// Shortest the greeting stays up, even when a photo is ready sooner.
+        pub min_duration_seconds: Option<f32>,
     }
 
-    #[derive(Debug, Clone, Deserialize)]
-    #[serde(rename_all = "kebab-case", default)]
+    #[derive(Debug, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "kebab-case", from = "SleepScreenFields")]
     pub struct SleepScreenConfig {
         #[serde(flatten)]
         pub screen: ScreenMessageConfig,
+        pub transition_seconds: Option<f32>,
+        pub transition_style: SleepTransitionStyle,
+        pub show_clock: bool,
+        pub clock_format: Option<String>,
+    }
+
+    /// How the slideshow leaves the screen on sleep and returns on wake.
+    #[derive(Debug, Clone, Copy, Deserialize, Serialize, Default, PartialEq, Eq)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum SleepTransitionStyle {
+        /// The photo fades to black.
+        #[default]
+        Fade,
+        /// An iris closes over the photo, and opens onto it again on wake.
+        Iris,
+    }
+
+    /// Card shown in place of the slideshow while the library holds no photos.
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
+    #[serde(rename_all = "kebab-case", from = "ScreenMessageConfig")]
+    pub struct EmptyLibraryScreenConfig {
+        #[serde(flatten)]
+        pub screen: ScreenMessageConfig,
+    }
+
+    impl From<ScreenMessageConfig> for EmptyLibraryScreenConfig {
+        fn from(screen: ScreenMessageConfig) -> Self {
+            Self { screen }
+        }
+    }
+
+    /// `greeting-screen` as written. The screen keys are spelled out again
+    /// rather than flattened, which would let a misspelled key through.
+    #[derive(Deserialize, Default)]
+    #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+    struct GreetingScreenFields {
+        message: Option<String>,
+        font: Option<String>,
+        stroke_width: Option<f32>,
+        corner_radius: Option<f32>,
+        fade_ms: Option<u64>,
+        max_width_percent: Option<f32>,
+        colors: GreetingScreenColorsConfig,
+        duration_seconds: Option<f32>,
+        min_duration_seconds: Option<f32>,
+    }
+
+    impl From<GreetingScreenFields> for GreetingScreenConfig {
+        fn from(fields: GreetingScreenFields) -> Self {
+            Self {
+                screen: ScreenMessageConfig {
+                    message: fields.message,
+                    font: fields.font,
+                    stroke_width: fields.stroke_width,
+                    corner_radius: fields.corner_radius,
+                    fade_ms: fields.fade_ms,
+                    max_width_percent: fields.max_width_percent,
+                    colors: fields.colors,
+                },
+                duration_seconds: fields.duration_seconds,
+                min_duration_seconds: fields.min_duration_seconds,
+            }
+        }
+    }
+
+    /// `sleep-screen` as written; unset keys take [`SleepScreenConfig`]'s
+    /// defaults.
+    #[derive(Deserialize, Default)]
+    #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+    struct SleepScreenFields {
+        message: Option<String>,
+        font: Option<String>,
+        stroke_width: Option<f32>,
+        corner_radius: Option<f32>,
+        fade_ms: Option<u64>,
+        max_width_percent: Option<f32>,
+        colors: GreetingScreenColorsConfig,
+        transition_seconds: Option<f32>,
+        transition_style: Option<SleepTransitionStyle>,
+        show_clock: Option<bool>,
+        clock_format: Option<String>,
+    }
+
+    impl From<SleepScreenFields> for SleepScreenConfig {
+        fn from(fields: SleepScreenFields) -> Self {
+            let defaults = Self::default();
+            Self {
+                screen: ScreenMessageConfig {
+                    message: fields.message.or(defaults.screen.message),
+                    font: fields.font,
+                    stroke_width: fields.stroke_width,
+                    corner_radius: fields.corner_radius,
+                    fade_ms: fields.fade_ms,
+                    max_width_percent: fields.max_width_percent,
+                    colors: fields.colors,
+                },
+                transition_seconds: fields.transition_seconds,
+                transition_style: fields.transition_style.unwrap_or(defaults.transition_style),
+                show_clock: fields.show_clock.unwrap_or(defaults.show_clock),
+                clock_format: fields.clock_format,
+            }
+        }
+    }
+
+    /// Where the photo library is in its startup scan, shown by `{state}`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum LibraryState {
+        Scanning,
+        Ready,
+    }
+
+    impl LibraryState {
+        pub fn label(self) -> &'static str {
+            match self {
+                Self::Scanning => "scanning library",
+                Self::Ready => "ready",
+            }
+        }
+    }
+
+    /// Values substituted for `{date}`, `{time}`, `{photo_count}`, and `{state}` in screen
+    /// messages.
+    #[derive(Debug, Clone, Copy)]
+    pub struct MessageContext {
+        pub now: DateTime<FixedOffset>,
+        pub photo_count: usize,
+        pub state: LibraryState,
+    }
+
+    impl MessageContext {
+        /// Captures the current wall-clock time in `timezone`, or the host's local zone.
+        pub fn current(timezone: Option<Tz>, photo_count: usize, state: LibraryState) -> Self {
+            let now = match timezone {
+                Some(tz) => Utc::now().with_timezone(&tz).fixed_offset(),
+                None => Local::now().fixed_offset(),
+            };
+            Self {
+                now,
+                photo_count,
+                state,
+            }
+        }
     }
 
     impl ScreenMessageConfig {
         const DEFAULT_STROKE_WIDTH_DIP: f32 = 16.0;
 
-        pub fn message_or_default(&self) -> Cow<'_, str> {
+        /// Returns the configured message (or the built-in default) with placeholders expanded.
+        pub fn message_or_default(&self, ctx: &MessageContext) -> Cow<'_, str> {
             match &self.message {
-                Some(msg) if !msg.trim().is_empty() => Cow::Borrowed(msg.as_str()),
+                Some(msg) if !msg.trim().is_empty() => expand_placeholders(msg, ctx),
                 _ => Cow::Borrowed("Initializing…"),
             }
         }
 
+        /// How long the card takes to fade in on entry and out on exit; zero snaps.
+        pub fn effective_fade(&self) -> Duration {
+            Duration::from_millis(self.fade_ms.unwrap_or(0))
+        }
+
+        /// Width of the text wrap box as a fraction of the space inside the frame.
+        pub fn effective_max_width_fraction(&self) -> f32 {
+            self.max_width_percent
+                .filter(|value| value.is_finite() && *value > 0.0 && *value <= 100.0)
+                .unwrap_or(100.0)
+                / 100.0
+        }
+
         pub fn effective_stroke_width_dip(&self) -> f32 {
             let width = self
                 .stroke_width
@@ -100,6 +270,13 @@ mod greeting {
                     prefix
                 );
             }
+            if let Some(percent) = self.max_width_percent {
+                ensure!(
+                    percent.is_finite() && percent > 0.0 && percent <= 100.0,
+                    "{}.max-width-percent must be within (0, 100]",
+                    prefix
+                );
+            }
             if let Some(font_name) = &self.font {
                 ensure!(
                     !font_name.trim().is_empty(),
@@ -125,9 +302,55 @@ mod greeting {
         }
     }
 
+    /// Expands `{date}`, `{time}`, `{photo_count}` (or `{photo-count}`), and `{state}`.
+    /// `{{` and `}}` produce literal braces;
+    /// unknown or unterminated placeholders are kept verbatim so typos stay visible on screen.
+    fn expand_placeholders<'a>(template: &'a str, ctx: &MessageContext) -> Cow<'a, str> {
+        if !template.contains(['{', '}']) {
+            return Cow::Borrowed(template);
+        }
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(idx) = rest.find(['{', '}']) {
+            out.push_str(&rest[..idx]);
+            let tail = &rest[idx..];
+            if let Some(after) = tail.strip_prefix("{{") {
+                out.push('{');
+                rest = after;
+                continue;
+            }
+            if let Some(after) = tail.strip_prefix("}}") {
+                out.push('}');
+                rest = after;
+                continue;
+            }
+            if let Some(body) = tail.strip_prefix('{')
+                && let Some(end) = body.find(['{', '}'])
+                && body[end..].starts_with('}')
+            {
+                let name = &body[..end];
+                match name {
+                    "date" => out.push_str(&ctx.now.format("%A, %B %-d").to_string()),
+                    "time" => out.push_str(&ctx.now.format("%H:%M").to_string()),
+                    "photo_count" | "photo-count" => out.push_str(&ctx.photo_count.to_string()),
+                    "state" => out.push_str(ctx.state.label()),
+                    _ => out.push_str(&tail[..end + 2]),
+                }
+                rest = &body[end + 1..];
+                continue;
+            }
+            out.push_str(&tail[..1]);
+            rest = &tail[1..];
+        }
+        out.push_str(rest);
+        Cow::Owned(out)
+    }
+
     impl GreetingScreenConfig {
         const DEFAULT_DURATION_SECONDS: f32 = 4.0;
+        const DEFAULT_MIN_DURATION_SECONDS: f32 = 1.0;
 
+        /// The cap: how long the greeting waits for a first photo.
         pub fn effective_duration(&self) -> Duration {
             let seconds = self
                 .duration_seconds
@@ -137,6 +360,15 @@ mod greeting {
             Duration::from_secs_f32(seconds)
         }
 
+        /// The floor, never longer than [`Self::effective_duration`].
+        pub fn effective_min_duration(&self) -> Duration {
+            let seconds = self
+                .min_duration_seconds
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(Self::DEFAULT_MIN_DURATION_SECONDS);
+            Duration::from_secs_f32(seconds).min(self.effective_duration())
+        }
+
         pub fn validate(&self) -> Result<()> {
             self.screen.validate("greeting-screen")?;
             if let Some(duration) = self.duration_seconds {
@@ -145,6 +377,16 @@ mod greeting {
                     "greeting-screen.duration-seconds must be non-negative"
                 );
             }
+            if let Some(min) = self.min_duration_seconds {
+                ensure!(
+                    min.is_finite() && min >= 0.0,
+                    "greeting-screen.min-duration-seconds must be non-negative"
+                );
+                ensure!(
+                    Duration::from_secs_f32(min) <= self.effective_duration(),
+                    "greeting-screen.min-duration-seconds must not exceed duration-seconds"
+                );
+            }
             Ok(())
         }
 
@@ -153,9 +395,87 @@ mod greeting {
         }
     }
 
+    impl EmptyLibraryScreenConfig {
+        /// The configured message, or a hint naming the library roots when none is set.
+        pub fn message_for(&self, ctx: &MessageContext, library_paths: &[PathBuf]) -> String {
+            match &self.screen.message {
+                Some(msg) if !msg.trim().is_empty() => expand_placeholders(msg, ctx).into_owned(),
+                _ => {
+                    let roots: Vec<String> = library_paths
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect();
+                    format!("No photos yet\nAdd photos to {}", roots.join(" or "))
+                }
+            }
+        }
+
+        pub fn validate(&self) -> Result<()> {
+            self.screen.validate("empty-library-screen")
+        }
+
+        pub fn screen(&self) -> &ScreenMessageConfig {
+            &self.screen
+        }
+    }
+
     impl SleepScreenConfig {
+        const DEFAULT_TRANSITION_SECONDS: f32 = 1.5;
+        const DEFAULT_CLOCK_FORMAT: &'static str = "%H:%M";
+
+        /// Total time to fade the slideshow to black and the sleep message in.
+        pub fn effective_transition(&self) -> Duration {
+            let seconds = self
+                .transition_seconds
+                .filter(|value| value.is_finite() && *value >= 0.0)
+                .unwrap_or(Self::DEFAULT_TRANSITION_SECONDS)
+                .max(0.0);
+            Duration::from_secs_f32(seconds)
+        }
+
+        /// strftime pattern for the sleep clock.
+        pub fn effective_clock_format(&self) -> &str {
+            self.clock_format
+                .as_deref()
+                .filter(|format| !format.trim().is_empty())
+                .unwrap_or(Self::DEFAULT_CLOCK_FORMAT)
+        }
+
+        /// The clock line for `ctx`, or `None` when `show-clock` is off.
+        pub fn clock_text(&self, ctx: &MessageContext) -> Option<String> {
+            self.show_clock
+                .then(|| ctx.now.format(self.effective_clock_format()).to_string())
+        }
+
+        /// The sleep card's text: the message, with the clock on its own line below it.
+        pub fn message_with_clock(&self, ctx: &MessageContext) -> String {
+            let message = self.screen.message_or_default(ctx);
+            match self.clock_text(ctx) {
+                Some(clock) => format!("{message}\n{clock}"),
+                None => message.into_owned(),
+            }
+        }
+
         pub fn validate(&self) -> Result<()> {
-            self.screen.validate("sleep-screen")
+            self.screen.validate("sleep-screen")?;
+            if let Some(format) = &self.clock_format {
+                ensure!(
+                    !format.trim().is_empty(),
+                    "sleep-screen.clock-format must not be blank when provided"
+                );
+                ensure!(
+                    !StrftimeItems::new(format).any(|item| matches!(item, Item::Error)),
+                    "sleep-screen.clock-format {:?} is not a valid strftime pattern",
+                    format
+                );
+            }
+            if let Some(transition) = self.transition_seconds {
+                ensure!(
+                    transition.is_finite() && transition >= 0.0,
+                    "sleep-screen.transition-seconds must be non-negative"
+                );
+            }
+            Ok(())
         }
 
         pub fn screen(&self) -> &ScreenMessageConfig {
@@ -170,16 +490,20 @@ mod greeting {
                     message: Some("Going to Sleep".to_string()),
                     ..ScreenMessageConfig::default()
                 },
+                transition_seconds: None,
+                transition_style: SleepTransitionStyle::Fade,
+                show_clock: false,
+                clock_format: None,
             }
         }
     }
 }
 
 mod showcase {
-    use serde::Deserialize;
+    use serde::{Deserialize, Serialize};
     use std::path::PathBuf;
 
-    #[derive(Debug, Clone, Deserialize, Default)]
+    #[derive(Debug, Clone, Deserialize, Serialize, Default)]
     #[serde(rename_all = "kebab-case", default)]
     pub struct ShowcaseConfig {
         pub enabled: bool,
@@ -199,35 +523,549 @@ mod showcase {
     }
 }
 
+#[cfg(unix)]
+mod control_socket {
+    use super::*;
+    use std::ffi::OsStr;
+    use std::os::unix::net::UnixStream;
+
+    /// Where photoframe binds the control socket when `control-socket-path` is
+    /// refused for lack of permission: `$XDG_RUNTIME_DIR/photo-frame/control.sock`.
+    pub fn fallback_control_socket_path(runtime_dir: Option<&OsStr>) -> Option<PathBuf> {
+        let runtime_dir = runtime_dir.filter(|dir| !dir.is_empty())?;
+        Some(Path::new(runtime_dir).join("photo-frame/control.sock"))
+    }
+
+    /// Connects to the frame's control socket at `configured`, or at this
+    /// user's fallback path when the frame had to bind there instead.
+    pub fn connect_control_socket(configured: &Path) -> Result<UnixStream> {
+        connect_with_fallback(configured, std::env::var_os("XDG_RUNTIME_DIR").as_deref())
+    }
+
+    pub(crate) fn connect_with_fallback(
+        configured: &Path,
+        runtime_dir: Option<&OsStr>,
+    ) -> Result<UnixStream> {
+        let err = match UnixStream::connect(configured) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => err,
+        };
+        let fallback =
+            fallback_control_socket_path(runtime_dir).filter(|fallback| fallback != configured);
+        match fallback {
+            Some(fallback) => match UnixStream::connect(&fallback) {
+                Ok(stream) => Ok(stream),
+                Err(_) => Err(err).with_context(|| {
+                    format!(
+                        "failed to connect to the control socket at {} or {}",
+                        configured.display(),
+                        fallback.display()
+                    )
+                }),
+            },
+            None => Err(err).with_context(|| {
+                format!(
+                    "failed to connect to the control socket at {}",
+                    configured.display()
+                )
+            }),
+        }
+    }
+}
+
+mod file {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use serde_yaml::Value as YamlValue;
+
+    /// Top-level key listing further config files to merge over the one naming it.
+    const INCLUDE_KEY: &str = "include";
+
+    /// Reads the config file at `path` into `T`. The files listed under
+    /// `include:` are merged over it first; errors are explained as by
+    /// [`from_yaml_str`].
+    pub fn read_yaml_file<T: DeserializeOwned>(path: &Path) -> Result<T> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read config file {}", path.display()))?;
+        if !has_includes(&text) {
+            // Parsed as written, so error positions match the file.
+            return from_yaml_str(&text);
+        }
+        let merged = read_yaml_with_includes(path, &mut Vec::new())?;
+        let merged = serde_yaml::to_string(&merged).context("re-serialize merged config")?;
+        from_yaml_str(&merged)
+    }
+
+    /// Parses a YAML document. A misspelled key or kind is reported with its
+    /// full key path and, when one is close enough, the name it was probably
+    /// meant to be.
+    pub fn from_yaml_str<T: DeserializeOwned>(yaml: &str) -> Result<T> {
+        serde_yaml::from_str(yaml).map_err(explain_yaml_error)
+    }
+
+    /// The `--check-config` report: a header naming `path`, one comment per
+    /// warning, then `effective` as YAML with every default filled in.
+    pub fn check_report<T: Serialize>(
+        path: &Path,
+        warnings: &[String],
+        effective: &T,
+    ) -> Result<String> {
+        let mut report = format!("# {}: configuration is valid\n", path.display());
+        for warning in warnings {
+            report.push_str(&format!("# warning: {warning}\n"));
+        }
+        report.push_str(&serde_yaml::to_string(effective).context("render configuration")?);
+        Ok(report)
+    }
+
+    /// Whether the YAML document `yaml` has an `include:` key.
+    fn has_includes(yaml: &str) -> bool {
+        serde_yaml::from_str::<YamlValue>(yaml).is_ok_and(|value| value.get(INCLUDE_KEY).is_some())
+    }
+
+    /// The YAML file at `path` with each file it includes merged over it in
+    /// turn, so later files override earlier ones. Included files may include
+    /// others; relative paths are taken from the including file's folder.
+    /// `chain` holds the files being read, to catch an include cycle.
+    fn read_yaml_with_includes(path: &Path, chain: &mut Vec<PathBuf>) -> Result<YamlValue> {
+        let canonical = std::fs::canonicalize(path)
+            .with_context(|| format!("cannot read config file {}", path.display()))?;
+        if let Some(start) = chain.iter().position(|seen| *seen == canonical) {
+            let cycle: Vec<String> = chain[start..]
+                .iter()
+                .chain([&canonical])
+                .map(|file| file.display().to_string())
+                .collect();
+            bail!("config files include each other: {}", cycle.join(" -> "));
+        }
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read config file {}", path.display()))?;
+        let mut value: YamlValue = serde_yaml::from_str(&text)
+            .with_context(|| format!("cannot parse config file {}", path.display()))?;
+        let includes = match value
+            .as_mapping_mut()
+            .and_then(|map| map.remove(INCLUDE_KEY))
+        {
+            None | Some(YamlValue::Null) => Vec::new(),
+            Some(YamlValue::String(include)) => vec![include],
+            Some(YamlValue::Sequence(includes)) => includes
+                .into_iter()
+                .map(|include| match include {
+                    YamlValue::String(include) => Ok(include),
+                    _ => bail!("{}: include entries must be paths", path.display()),
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => bail!(
+                "{}: include must be a path or a list of paths",
+                path.display()
+            ),
+        };
+        let dir = path.parent().unwrap_or(Path::new(""));
+        chain.push(canonical);
+        for include in includes {
+            let include = dir.join(expand_path(Path::new(&include)).context("include")?);
+            let overlay = read_yaml_with_includes(&include, chain)?;
+            // An empty file includes nothing.
+            if !overlay.is_null() {
+                merge_yaml(&mut value, overlay);
+            }
+        }
+        chain.pop();
+        Ok(value)
+    }
+
+    /// Merges `overlay` into `base`: mappings key by key, all the way down;
+    /// anything else, lists included, is replaced.
+    fn merge_yaml(base: &mut YamlValue, overlay: YamlValue) {
+        match (base, overlay) {
+            (YamlValue::Mapping(base), YamlValue::Mapping(overlay)) => {
+                for (key, value) in overlay {
+                    match base.get_mut(&key) {
+                        Some(existing) => merge_yaml(existing, value),
+                        None => {
+                            base.insert(key, value);
+                        }
+                    }
+                }
+            }
+            (base, overlay) => *base = overlay,
+        }
+    }
+
+    /// `path` with a leading `~` replaced by `$HOME` and each `${VAR}` by that
+    /// variable's value. Paths that are not UTF-8 are left as they are.
+    pub fn expand_path(path: &Path) -> Result<PathBuf> {
+        let Some(text) = path.to_str() else {
+            return Ok(path.to_path_buf());
+        };
+        let mut expanded = String::with_capacity(text.len());
+        let mut rest = text;
+        if rest == "~" || rest.starts_with("~/") {
+            expanded.push_str(&env_var("HOME", text)?);
+            rest = &rest[1..];
+        }
+        while let Some(start) = rest.find("${") {
+            expanded.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after
+                .find('}')
+                .with_context(|| format!("`{text}` has a `${{` without a closing `}}`"))?;
+            expanded.push_str(&env_var(&after[..end], text)?);
+            rest = &after[end + 1..];
+        }
+        expanded.push_str(rest);
+        Ok(PathBuf::from(expanded))
+    }
+
+    fn env_var(name: &str, path: &str) -> Result<String> {
+        match std::env::var(name) {
+            Ok(value) => Ok(value),
+            Err(std::env::VarError::NotPresent) => Err(anyhow::anyhow!(
+                "environment variable `{name}` in `{path}` is not set"
+            )),
+            Err(std::env::VarError::NotUnicode(_)) => Err(anyhow::anyhow!(
+                "environment variable `{name}` in `{path}` is not valid UTF-8"
+            )),
+        }
+    }
+
+    /// Rewrites serde's `unknown field`/`unknown variant` errors, which serde_yaml
+    /// renders as `matting: active[0].bevle-width-px: unknown field ..., expected
+    /// one of ...`, into `matting.active[0].bevle-width-px: unknown field ...; did
+    /// you mean 'bevel-width-px'?`. Other errors pass through untouched. The
+    /// misspelled-key tests pin serde_yaml's wording, so a change to it fails
+    /// them instead of quietly dropping the suggestions.
+    fn explain_yaml_error(err: serde_yaml::Error) -> anyhow::Error {
+        let rendered = err.to_string();
+        let mut message = rendered.as_str();
+        if let Some(location) = err.location() {
+            let suffix = format!(" at line {} column {}", location.line(), location.column());
+            message = message.strip_suffix(&suffix).unwrap_or(message);
+        }
+
+        let mut path: Vec<&str> = Vec::new();
+        let (kind, rest) = loop {
+            if let Some(rest) = message.strip_prefix("unknown field ") {
+                break ("field", rest);
+            }
+            if let Some(rest) = message.strip_prefix("unknown variant ") {
+                break ("variant", rest);
+            }
+            match message.split_once(": ") {
+                Some((segment, rest)) if !segment.contains(' ') => {
+                    path.push(segment);
+                    message = rest;
+                }
+                _ => return err.into(),
+            }
+        };
+        let Some((name, expected)) = rest.strip_prefix('`').and_then(|rest| rest.split_once('`'))
+        else {
+            return err.into();
+        };
+        // Inline matting and transition fields already name the key in the path.
+        let named = |last: &&str| *last == name || last.ends_with(&format!(".{name}"));
+        if kind == "field" && !path.last().is_some_and(named) {
+            path.push(name);
+        }
+
+        let candidates: Vec<&str> = expected.split('`').skip(1).step_by(2).collect();
+        let mut report = format!("{}: unknown {kind} `{name}`", path.join("."));
+        match closest_name(name, &candidates) {
+            Some(suggestion) => report.push_str(&format!("; did you mean '{suggestion}'?")),
+            None if !candidates.is_empty() => {
+                report.push_str(&format!("; expected one of: {}", candidates.join(", ")))
+            }
+            None => {}
+        }
+        if let Some(location) = err.location() {
+            report.push_str(&format!(
+                " (line {}, column {})",
+                location.line(),
+                location.column()
+            ));
+        }
+        anyhow::anyhow!(report)
+    }
+
+    /// The candidate nearest to `name` by edit distance, if it is close enough to
+    /// be a plausible typo: at most a third of the name's length, and at least 2.
+    fn closest_name<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+        let limit = (name.chars().count() / 3).max(2);
+        candidates
+            .iter()
+            .map(|candidate| (edit_distance(name, candidate), *candidate))
+            .filter(|(distance, _)| *distance <= limit)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, candidate)| candidate)
+    }
+
+    /// Levenshtein distance over chars.
+    fn edit_distance(a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut row: Vec<usize> = (0..=b.len()).collect();
+        for (i, ca) in a.chars().enumerate() {
+            let mut diagonal = row[0];
+            row[0] = i + 1;
+            for (j, cb) in b.iter().enumerate() {
+                let substitution = diagonal + usize::from(ca != *cb);
+                diagonal = row[j + 1];
+                row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+            }
+        }
+        row[b.len()]
+    }
+}
+
 mod awake {
     use super::*;
+    use chrono::Timelike;
+
+    /// What the schedule wants the frame to be doing at a given moment.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ScheduledState {
+        Awake,
+        /// Inside an `awake-scheduled.ambient` window: panel on, dimmed.
+        Ambient,
+        Asleep,
+    }
+
+    impl ScheduledState {
+        pub fn label(self) -> &'static str {
+            match self {
+                Self::Awake => "awake",
+                Self::Ambient => "ambient",
+                Self::Asleep => "asleep",
+            }
+        }
+    }
+
+    /// Which key of `awake-scheduled` decides a day's windows.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum RuleSource {
+        Daily,
+        Weekdays,
+        Weekend,
+        /// A day key such as `friday`.
+        Day(Weekday),
+    }
+
+    impl RuleSource {
+        pub fn label(self) -> &'static str {
+            match self {
+                Self::Daily => "daily",
+                Self::Weekdays => "weekdays",
+                Self::Weekend => "weekend",
+                Self::Day(Weekday::Mon) => "monday",
+                Self::Day(Weekday::Tue) => "tuesday",
+                Self::Day(Weekday::Wed) => "wednesday",
+                Self::Day(Weekday::Thu) => "thursday",
+                Self::Day(Weekday::Fri) => "friday",
+                Self::Day(Weekday::Sat) => "saturday",
+                Self::Day(Weekday::Sun) => "sunday",
+            }
+        }
+    }
+
+    /// One awake or ambient window, resolved to instants, and the key it
+    /// came from. An overnight window keeps the key of the day it began.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ScheduleWindow {
+        pub start: DateTime<Tz>,
+        pub end: DateTime<Tz>,
+        pub source: RuleSource,
+    }
 
-    #[derive(Debug, Clone, Deserialize)]
+    /// What the schedule says about one instant. buttond's scheduler and the
+    /// viewer's startup state both read it, and `buttond --explain-schedule`
+    /// prints it.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct ScheduleEvaluation {
+        pub at: DateTime<Tz>,
+        /// The key that decides the awake windows of `at`'s own day.
+        pub rule_source: RuleSource,
+        pub state: ScheduledState,
+        /// The window `at` falls in; `None` while asleep.
+        pub window: Option<ScheduleWindow>,
+        /// The next moment the state changes, and the state it changes to.
+        pub next_change: Option<(DateTime<Tz>, ScheduledState)>,
+        /// When the warning of the coming sleep goes up, and when the sleep
+        /// falls; see [`AwakeScheduleConfig::next_sleep_warning`].
+        pub sleep_warning: Option<(DateTime<Tz>, DateTime<Tz>)>,
+    }
+
+    #[derive(Debug, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "kebab-case")]
     pub struct AwakeScheduleConfig {
         pub timezone: Tz,
         #[serde(rename = "awake-scheduled", default)]
         schedule: AwakeScheduleRules,
+        /// How long a manual wake or sleep holds against the schedule. Unset,
+        /// it holds until the schedule itself comes round to the same state.
+        #[serde(default)]
+        manual_override_minutes: Option<u32>,
+        /// How long before a scheduled sleep the viewer shows a warning
+        /// banner. Unset, the frame goes to sleep unannounced.
+        #[serde(default)]
+        sleep_warning_minutes: Option<u32>,
     }
 
     impl AwakeScheduleConfig {
         pub fn validate(&mut self) -> Result<()> {
-            self.schedule.validate()
+            ensure!(
+                self.manual_override_minutes != Some(0),
+                "awake-schedule.manual-override-minutes must be at least 1"
+            );
+            ensure!(
+                self.sleep_warning_minutes != Some(0),
+                "awake-schedule.sleep-warning-minutes must be at least 1"
+            );
+            self.schedule.validate()?;
+            if let Some(ambient) = self.schedule.ambient.as_mut() {
+                ensure!(
+                    ambient.ambient.is_none(),
+                    "awake-schedule.awake-scheduled.ambient cannot contain its own ambient block"
+                );
+                ambient.validate_at("awake-schedule.awake-scheduled.ambient")?;
+            }
+            Ok(())
         }
 
         pub fn timezone(&self) -> Tz {
             self.timezone
         }
 
+        /// `manual-override-minutes` as a duration, when set.
+        pub fn manual_override(&self) -> Option<Duration> {
+            self.manual_override_minutes
+                .map(|minutes| Duration::from_secs(u64::from(minutes) * 60))
+        }
+
+        /// `sleep-warning-minutes` as a duration, when set.
+        pub fn sleep_warning(&self) -> Option<Duration> {
+            self.sleep_warning_minutes
+                .map(|minutes| Duration::from_secs(u64::from(minutes) * 60))
+        }
+
+        /// When to warn about the sleep that ends the awake stretch `from` is
+        /// in, and when that sleep falls. `None` when no warning is set, when
+        /// the frame is not scheduled awake at `from`, when the stretch ends in
+        /// ambient rather than sleep, or when it began less than the warning
+        /// lead before the sleep.
+        pub fn next_sleep_warning(
+            &self,
+            from: DateTime<Tz>,
+        ) -> Option<(DateTime<Tz>, DateTime<Tz>)> {
+            let lead = ChronoDuration::from_std(self.sleep_warning()?).ok()?;
+            if self.state_at(from) != ScheduledState::Awake {
+                return None;
+            }
+            let (sleep_at, state) = self.next_state_change_after(from)?;
+            if state != ScheduledState::Asleep {
+                return None;
+            }
+            let warn_at = sleep_at - lead;
+            // The whole lead has to be one awake stretch: a frame that wakes
+            // two minutes before a sleep gets no five-minute warning.
+            let covered = warn_at >= from
+                || (self.state_at(warn_at) == ScheduledState::Awake
+                    && self.next_state_change_after(warn_at)
+                        == Some((sleep_at, ScheduledState::Asleep)));
+            covered.then_some((warn_at, sleep_at))
+        }
+
+        pub fn evaluate(&self, at: DateTime<Tz>) -> ScheduleEvaluation {
+            let state = self.state_at(at);
+            let window = match state {
+                ScheduledState::Awake => self.window_at(&self.schedule, at),
+                ScheduledState::Ambient => self
+                    .schedule
+                    .ambient
+                    .as_deref()
+                    .and_then(|ambient| self.window_at(ambient, at)),
+                ScheduledState::Asleep => None,
+            };
+            ScheduleEvaluation {
+                at,
+                rule_source: self.schedule.rule_source(at.weekday()),
+                state,
+                window,
+                next_change: self.next_state_change_after(at),
+                sleep_warning: self.next_sleep_warning(at),
+            }
+        }
+
         pub fn is_awake_at(&self, instant: DateTime<Tz>) -> bool {
+            self.rules_cover(&self.schedule, instant)
+        }
+
+        /// Awake windows win over ambient ones; outside both the frame sleeps.
+        /// Schedules without an `ambient` block only ever say awake or asleep.
+        pub fn state_at(&self, instant: DateTime<Tz>) -> ScheduledState {
+            if self.is_awake_at(instant) {
+                ScheduledState::Awake
+            } else if self
+                .schedule
+                .ambient
+                .as_deref()
+                .is_some_and(|ambient| self.rules_cover(ambient, instant))
+            {
+                ScheduledState::Ambient
+            } else {
+                ScheduledState::Asleep
+            }
+        }
+
+        /// The next moment [`Self::state_at`] changes, and the state it
+        /// changes to. Unlike [`Self::next_transition_after`] this accounts
+        /// for ambient windows.
+        pub fn next_state_change_after(
+            &self,
+            from: DateTime<Tz>,
+        ) -> Option<(DateTime<Tz>, ScheduledState)> {
+            let start_date = from.date_naive();
+            let mut boundaries = Vec::new();
+            for rules in std::iter::once(&self.schedule).chain(self.schedule.ambient.as_deref()) {
+                for offset in -1..=7 {
+                    let date = start_date + ChronoDuration::days(offset);
+                    for interval in self.intervals_for_date(rules, date) {
+                        boundaries.extend([interval.start, interval.end]);
+                    }
+                }
+            }
+            boundaries.retain(|boundary| *boundary > from);
+            boundaries.sort_unstable();
+            boundaries.dedup();
+            let current = self.state_at(from);
+            boundaries
+                .into_iter()
+                .map(|boundary| (boundary, self.state_at(boundary)))
+                .find(|(_, state)| *state != current)
+        }
+
+        fn rules_cover(&self, rules: &AwakeScheduleRules, instant: DateTime<Tz>) -> bool {
+            self.window_at(rules, instant).is_some()
+        }
+
+        fn window_at(
+            &self,
+            rules: &AwakeScheduleRules,
+            instant: DateTime<Tz>,
+        ) -> Option<ScheduleWindow> {
             let date = instant.date_naive();
             // Also consider the previous day so an overnight window that began
             // yesterday (e.g. 21:00 -> 07:00) still counts this morning.
             let previous = date - ChronoDuration::days(1);
-            self.intervals_for_date(previous)
-                .into_iter()
-                .chain(self.intervals_for_date(date))
-                .any(|interval| interval.contains(instant))
+            [previous, date].into_iter().find_map(|date| {
+                self.intervals_for_date(rules, date)
+                    .into_iter()
+                    .find(|interval| interval.contains(instant))
+                    .map(|interval| ScheduleWindow {
+                        start: interval.start,
+                        end: interval.end,
+                        source: rules.rule_source(date.weekday()),
+                    })
+            })
         }
 
         pub fn next_transition_after(&self, from: DateTime<Tz>) -> Option<(DateTime<Tz>, bool)> {
@@ -237,7 +1075,7 @@ mod awake {
             for offset in -1..=7 {
                 let offset_days = i64::from(offset);
                 let date = start_date + ChronoDuration::days(offset_days);
-                for interval in self.intervals_for_date(date) {
+                for interval in self.intervals_for_date(&self.schedule, date) {
                     if interval.start > from {
                         return Some((interval.start, true));
                     }
@@ -249,9 +1087,13 @@ mod awake {
             None
         }
 
-        fn intervals_for_date(&self, date: NaiveDate) -> Vec<ResolvedAwakeInterval> {
+        fn intervals_for_date(
+            &self,
+            rules: &AwakeScheduleRules,
+            date: NaiveDate,
+        ) -> Vec<ResolvedAwakeInterval> {
             let mut intervals = Vec::new();
-            for range in self.schedule.resolved_ranges_for(date.weekday()) {
+            for range in rules.resolved_ranges_for(date.weekday()) {
                 let start =
                     resolve_local_datetime(self.timezone, date, range.start(), Boundary::Start);
                 // A range whose end is not strictly after its start wraps past
@@ -272,7 +1114,7 @@ mod awake {
         }
     }
 
-    #[derive(Debug, Clone, Default, Deserialize)]
+    #[derive(Debug, Clone, Default, Deserialize, Serialize)]
     #[serde(rename_all = "kebab-case", default)]
     pub struct AwakeScheduleRules {
         #[serde(default)]
@@ -286,16 +1128,23 @@ mod awake {
         friday: Option<Vec<AwakeTimeRange>>,
         saturday: Option<Vec<AwakeTimeRange>>,
         sunday: Option<Vec<AwakeTimeRange>>,
+        /// Windows, in the same shape, during which the frame stays on but
+        /// dimmed instead of sleeping. Awake windows take precedence.
+        ambient: Option<Box<AwakeScheduleRules>>,
     }
 
     impl AwakeScheduleRules {
         pub fn validate(&mut self) -> Result<()> {
-            Self::validate_ranges(&mut self.daily, "awake-schedule.awake-scheduled.daily")?;
+            self.validate_at("awake-schedule.awake-scheduled")
+        }
+
+        fn validate_at(&mut self, prefix: &str) -> Result<()> {
+            Self::validate_ranges(&mut self.daily, &format!("{prefix}.daily"))?;
             if let Some(ranges) = self.weekdays.as_mut() {
-                Self::validate_ranges(ranges, "awake-schedule.awake-scheduled.weekdays")?;
+                Self::validate_ranges(ranges, &format!("{prefix}.weekdays"))?;
             }
             if let Some(ranges) = self.weekend.as_mut() {
-                Self::validate_ranges(ranges, "awake-schedule.awake-scheduled.weekend")?;
+                Self::validate_ranges(ranges, &format!("{prefix}.weekend"))?;
             }
             for (label, ranges) in [
                 ("monday", &mut self.monday),
@@ -307,10 +1156,7 @@ mod awake {
                 ("sunday", &mut self.sunday),
             ] {
                 if let Some(ranges) = ranges {
-                    Self::validate_ranges(
-                        ranges,
-                        &format!("awake-schedule.awake-scheduled.{label}"),
-                    )?;
+                    Self::validate_ranges(ranges, &format!("{prefix}.{label}"))?;
                 }
             }
             Ok(())
@@ -333,14 +1179,24 @@ mod awake {
         }
 
         fn resolved_ranges_for(&self, weekday: Weekday) -> Vec<AwakeTimeRange> {
-            if let Some(overrides) = self.day_specific(weekday) {
-                return overrides.clone();
+            match self.rule_source(weekday) {
+                RuleSource::Day(day) => self.day_specific(day).cloned().unwrap_or_default(),
+                RuleSource::Weekend => self.weekend.clone().unwrap_or_default(),
+                RuleSource::Weekdays => self.weekdays.clone().unwrap_or_default(),
+                RuleSource::Daily => self.daily.clone(),
+            }
+        }
+
+        /// A day key wins over `weekdays`/`weekend`, which win over `daily`.
+        fn rule_source(&self, weekday: Weekday) -> RuleSource {
+            if self.day_specific(weekday).is_some() {
+                return RuleSource::Day(weekday);
             }
             match weekday {
-                Weekday::Sat | Weekday::Sun => {
-                    self.weekend.clone().unwrap_or_else(|| self.daily.clone())
-                }
-                _ => self.weekdays.clone().unwrap_or_else(|| self.daily.clone()),
+                Weekday::Sat | Weekday::Sun if self.weekend.is_some() => RuleSource::Weekend,
+                Weekday::Sat | Weekday::Sun => RuleSource::Daily,
+                _ if self.weekdays.is_some() => RuleSource::Weekdays,
+                _ => RuleSource::Daily,
             }
         }
 
@@ -400,6 +1256,23 @@ mod awake {
         }
     }
 
+    /// Written back as `["HH:MM", "HH:MM"]`, with seconds only when set.
+    impl Serialize for AwakeTimeRange {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let format = |time: NaiveTime| {
+                if time.second() == 0 {
+                    time.format("%H:%M").to_string()
+                } else {
+                    time.format("%H:%M:%S").to_string()
+                }
+            };
+            (format(self.start), format(self.end)).serialize(serializer)
+        }
+    }
+
     #[derive(Debug, Clone)]
     struct ResolvedAwakeInterval {
         start: DateTime<Tz>,
@@ -448,6 +1321,170 @@ mod awake {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Timelike;
+
+    fn message_context() -> MessageContext {
+        let now = FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2025, 3, 14, 9, 5, 0)
+            .unwrap();
+        MessageContext {
+            now,
+            photo_count: 1234,
+            state: LibraryState::Ready,
+        }
+    }
+
+    fn screen_with_message(message: &str) -> ScreenMessageConfig {
+        ScreenMessageConfig {
+            message: Some(message.to_string()),
+            ..ScreenMessageConfig::default()
+        }
+    }
+
+    #[test]
+    fn sleep_clock_changes_across_minute_boundary() {
+        let sleep = SleepScreenConfig {
+            show_clock: true,
+            ..SleepScreenConfig::default()
+        };
+        let mut ctx = message_context();
+        ctx.now = ctx.now.with_second(59).unwrap();
+        let before = sleep.message_with_clock(&ctx);
+        ctx.now += ChronoDuration::seconds(1);
+        let after = sleep.message_with_clock(&ctx);
+        assert_eq!(before, "Going to Sleep\n09:05");
+        assert_eq!(after, "Going to Sleep\n09:06");
+
+        let custom = SleepScreenConfig {
+            show_clock: true,
+            clock_format: Some("%-I:%M %p".to_string()),
+            ..SleepScreenConfig::default()
+        };
+        assert_eq!(custom.clock_text(&ctx).as_deref(), Some("9:06 AM"));
+        assert_eq!(SleepScreenConfig::default().clock_text(&ctx), None);
+    }
+
+    #[test]
+    fn sleep_transition_style_defaults_to_fade() {
+        let sleep: SleepScreenConfig = serde_yaml::from_str("message: Night").unwrap();
+        assert_eq!(sleep.transition_style, SleepTransitionStyle::Fade);
+        let sleep: SleepScreenConfig = serde_yaml::from_str("transition-style: iris").unwrap();
+        assert_eq!(sleep.transition_style, SleepTransitionStyle::Iris);
+        assert!(serde_yaml::from_str::<SleepScreenConfig>("transition-style: wipe").is_err());
+    }
+
+    #[test]
+    fn greeting_min_duration_defaults_below_the_cap() {
+        let greeting = GreetingScreenConfig::default();
+        assert_eq!(greeting.effective_min_duration(), Duration::from_secs(1));
+        assert_eq!(greeting.effective_duration(), Duration::from_secs(4));
+
+        // An unset minimum never outlasts a short cap.
+        let short: GreetingScreenConfig = serde_yaml::from_str("duration-seconds: 0.5").unwrap();
+        assert_eq!(short.effective_min_duration(), Duration::from_millis(500));
+
+        let inverted: GreetingScreenConfig =
+            serde_yaml::from_str("duration-seconds: 2\nmin-duration-seconds: 3").unwrap();
+        assert!(inverted.validate().is_err());
+    }
+
+    #[test]
+    fn sleep_clock_format_is_validated() {
+        let sleep = SleepScreenConfig {
+            clock_format: Some("%H:%Q".to_string()),
+            ..SleepScreenConfig::default()
+        };
+        assert!(sleep.validate().is_err());
+    }
+
+    #[test]
+    fn empty_library_message_names_the_library_by_default() {
+        let ctx = message_context();
+        let library = [PathBuf::from("/var/lib/photoframe/photos")];
+        assert_eq!(
+            EmptyLibraryScreenConfig::default().message_for(&ctx, &library),
+            "No photos yet\nAdd photos to /var/lib/photoframe/photos"
+        );
+        let two = [PathBuf::from("/photos"), PathBuf::from("/mnt/nas")];
+        assert_eq!(
+            EmptyLibraryScreenConfig::default().message_for(&ctx, &two),
+            "No photos yet\nAdd photos to /photos or /mnt/nas"
+        );
+        let custom = EmptyLibraryScreenConfig {
+            screen: screen_with_message("Nothing to show at {time}"),
+        };
+        assert_eq!(
+            custom.message_for(&ctx, &library),
+            "Nothing to show at 09:05"
+        );
+    }
+
+    #[test]
+    fn max_width_percent_is_validated() {
+        let mut screen = screen_with_message("hello");
+        assert_eq!(screen.effective_max_width_fraction(), 1.0);
+        screen.max_width_percent = Some(60.0);
+        assert!(screen.validate("greeting-screen").is_ok());
+        assert!((screen.effective_max_width_fraction() - 0.6).abs() < f32::EPSILON);
+        for invalid in [0.0, 120.0, f32::NAN] {
+            screen.max_width_percent = Some(invalid);
+            assert!(screen.validate("greeting-screen").is_err());
+        }
+    }
+
+    #[test]
+    fn message_placeholders_expand() {
+        let screen = screen_with_message("{date} at {time}: {photo_count} photos");
+        assert_eq!(
+            screen.message_or_default(&message_context()),
+            "Friday, March 14 at 09:05: 1234 photos"
+        );
+    }
+
+    #[test]
+    fn message_startup_progress_placeholders_expand() {
+        let screen = screen_with_message("Warming up: {state}, {photo-count} photos");
+        let mut ctx = message_context();
+        ctx.state = LibraryState::Scanning;
+        ctx.photo_count = 512;
+        assert_eq!(
+            screen.message_or_default(&ctx),
+            "Warming up: scanning library, 512 photos"
+        );
+        ctx.state = LibraryState::Ready;
+        ctx.photo_count = 30_000;
+        assert_eq!(
+            screen.message_or_default(&ctx),
+            "Warming up: ready, 30000 photos"
+        );
+
+        // No placeholders: the configured text is used as-is.
+        let plain = screen_with_message("Warming up your photo memories…");
+        assert!(matches!(plain.message_or_default(&ctx), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn message_escaped_braces_stay_literal() {
+        let screen = screen_with_message("{{time}} is {time}; {{}} {unknown} {open");
+        assert_eq!(
+            screen.message_or_default(&message_context()),
+            "{time} is 09:05; {} {unknown} {open"
+        );
+    }
+
+    #[test]
+    fn message_without_placeholders_is_borrowed() {
+        let screen = screen_with_message("Hello");
+        assert!(matches!(
+            screen.message_or_default(&message_context()),
+            Cow::Borrowed("Hello")
+        ));
+        assert_eq!(
+            ScreenMessageConfig::default().message_or_default(&message_context()),
+            "Initializing…"
+        );
+    }
 
     fn schedule_from_yaml(input: &str) -> AwakeScheduleConfig {
         let mut schedule: AwakeScheduleConfig = serde_yaml::from_str(input).expect("valid yaml");
@@ -555,6 +1592,144 @@ awake-scheduled:
         );
     }
 
+    #[test]
+    fn ambient_windows_fill_gaps_between_awake_windows() {
+        let schedule = schedule_from_yaml(
+            r#"
+timezone: "UTC"
+awake-scheduled:
+  daily:
+    - ["07:00", "22:00"]
+  ambient:
+    daily:
+      - ["21:00", "01:00"]
+"#,
+        );
+        let tz = schedule.timezone();
+        let at = |d, h, m| tz.with_ymd_and_hms(2024, 1, d, h, m, 0).single().unwrap();
+
+        // The awake window wins where the two overlap.
+        assert_eq!(schedule.state_at(at(1, 21, 30)), ScheduledState::Awake);
+        assert_eq!(schedule.state_at(at(1, 23, 0)), ScheduledState::Ambient);
+        assert_eq!(schedule.state_at(at(2, 0, 30)), ScheduledState::Ambient);
+        assert_eq!(schedule.state_at(at(2, 3, 0)), ScheduledState::Asleep);
+
+        assert_eq!(
+            schedule.next_state_change_after(at(1, 12, 0)),
+            Some((at(1, 22, 0), ScheduledState::Ambient))
+        );
+        assert_eq!(
+            schedule.next_state_change_after(at(1, 22, 0)),
+            Some((at(2, 1, 0), ScheduledState::Asleep))
+        );
+        assert_eq!(
+            schedule.next_state_change_after(at(2, 3, 0)),
+            Some((at(2, 7, 0), ScheduledState::Awake))
+        );
+    }
+
+    #[test]
+    fn schedules_without_ambient_only_wake_and_sleep() {
+        let schedule = schedule_from_yaml(
+            r#"
+timezone: "UTC"
+awake-scheduled:
+  daily:
+    - ["07:00", "22:00"]
+"#,
+        );
+        let tz = schedule.timezone();
+        let at = |h| tz.with_ymd_and_hms(2024, 1, 1, h, 0, 0).single().unwrap();
+        assert_eq!(schedule.state_at(at(12)), ScheduledState::Awake);
+        assert_eq!(schedule.state_at(at(23)), ScheduledState::Asleep);
+        assert_eq!(
+            schedule.next_state_change_after(at(12)),
+            Some((at(22), ScheduledState::Asleep))
+        );
+
+        let mut nested: AwakeScheduleConfig = serde_yaml::from_str(
+            "timezone: UTC\nawake-scheduled:\n  ambient:\n    ambient:\n      daily: []\n",
+        )
+        .unwrap();
+        assert!(nested.validate().is_err());
+    }
+
+    #[test]
+    fn manual_override_minutes_is_optional_and_positive() {
+        let unset = schedule_from_yaml("timezone: \"UTC\"\n");
+        assert_eq!(unset.manual_override(), None);
+
+        let set = schedule_from_yaml("timezone: \"UTC\"\nmanual-override-minutes: 30\n");
+        assert_eq!(set.manual_override(), Some(Duration::from_secs(30 * 60)));
+
+        let mut zero: AwakeScheduleConfig =
+            serde_yaml::from_str("timezone: \"UTC\"\nmanual-override-minutes: 0\n").unwrap();
+        assert!(zero.validate().is_err());
+    }
+
+    #[test]
+    fn sleep_warning_covers_the_lead_before_each_sleep() {
+        let schedule = schedule_from_yaml(
+            r#"
+timezone: "UTC"
+sleep-warning-minutes: 5
+awake-scheduled:
+  daily:
+    - ["07:00", "22:00"]
+    - ["22:03", "23:00"]
+  ambient:
+    daily:
+      - ["05:00", "07:00"]
+"#,
+        );
+        let tz = schedule.timezone();
+        let at = |h, m| tz.with_ymd_and_hms(2024, 1, 1, h, m, 0).single().unwrap();
+
+        assert_eq!(
+            schedule.next_sleep_warning(at(12, 0)),
+            Some((at(21, 55), at(22, 0)))
+        );
+        assert_eq!(
+            schedule.next_sleep_warning(at(21, 57)),
+            Some((at(21, 55), at(22, 0)))
+        );
+        // The later stretch gets a warning of its own.
+        assert_eq!(
+            schedule.next_sleep_warning(at(22, 30)),
+            Some((at(22, 55), at(23, 0)))
+        );
+        // Asleep, or ambient, there is nothing to warn about.
+        assert_eq!(schedule.next_sleep_warning(at(22, 1)), None);
+        assert_eq!(schedule.next_sleep_warning(at(6, 0)), None);
+
+        let mut zero: AwakeScheduleConfig =
+            serde_yaml::from_str("timezone: \"UTC\"\nsleep-warning-minutes: 0\n").unwrap();
+        assert!(zero.validate().is_err());
+        assert_eq!(
+            schedule_from_yaml("timezone: \"UTC\"\n").next_sleep_warning(at(12, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn sleep_warning_skips_stretches_shorter_than_the_lead() {
+        let schedule = schedule_from_yaml(
+            r#"
+timezone: "UTC"
+sleep-warning-minutes: 5
+awake-scheduled:
+  daily:
+    - ["07:00", "22:00"]
+    - ["22:03", "22:06"]
+"#,
+        );
+        let tz = schedule.timezone();
+        let at = |h, m| tz.with_ymd_and_hms(2024, 1, 1, h, m, 0).single().unwrap();
+
+        // Three minutes awake cannot carry a five-minute warning.
+        assert_eq!(schedule.next_sleep_warning(at(22, 4)), None);
+    }
+
     #[test]
     fn equal_start_and_end_is_rejected() {
         let result: std::result::Result<AwakeScheduleConfig, _> = serde_yaml::from_str(
@@ -656,4 +1831,237 @@ awake-scheduled:
         let expected_end = tz.with_ymd_and_hms(2024, 11, 3, 2, 0, 0).latest().unwrap();
         assert_eq!(end, expected_end);
     }
+
+    #[test]
+    fn evaluation_follows_an_overnight_window_into_spring_forward() {
+        let schedule = schedule_from_yaml(
+            r#"
+timezone: "America/New_York"
+awake-scheduled:
+  daily:
+    - ["07:00", "22:00"]
+  saturday:
+    - ["21:00", "02:30"]
+"#,
+        );
+
+        let tz = schedule.timezone();
+        // 02:30 does not exist on 2024-03-10; the window runs to 03:00 EDT.
+        let at = tz.with_ymd_and_hms(2024, 3, 10, 1, 30, 0).single().unwrap();
+        let evaluation = schedule.evaluate(at);
+        assert_eq!(evaluation.rule_source, RuleSource::Daily);
+        assert_eq!(evaluation.state, ScheduledState::Awake);
+        let window = evaluation.window.expect("inside saturday's window");
+        assert_eq!(window.source, RuleSource::Day(Weekday::Sat));
+        assert_eq!(
+            window.start,
+            tz.with_ymd_and_hms(2024, 3, 9, 21, 0, 0).single().unwrap()
+        );
+        let sleep_at = tz.with_ymd_and_hms(2024, 3, 10, 3, 0, 0).single().unwrap();
+        assert_eq!(window.end, sleep_at);
+        assert_eq!(
+            evaluation.next_change,
+            Some((sleep_at, ScheduledState::Asleep))
+        );
+        assert_eq!(sleep_at - at, ChronoDuration::minutes(30));
+    }
+
+    #[test]
+    fn evaluation_holds_ambient_through_the_repeated_hour() {
+        let schedule = schedule_from_yaml(
+            r#"
+timezone: "America/New_York"
+awake-scheduled:
+  daily:
+    - ["07:00", "23:00"]
+  ambient:
+    daily:
+      - ["23:00", "01:30"]
+"#,
+        );
+
+        let tz = schedule.timezone();
+        // The first 01:15 of 2024-11-03, still on daylight time.
+        let at = tz
+            .with_ymd_and_hms(2024, 11, 3, 1, 15, 0)
+            .earliest()
+            .unwrap();
+        let evaluation = schedule.evaluate(at);
+        assert_eq!(evaluation.state, ScheduledState::Ambient);
+        let window = evaluation.window.expect("inside the ambient window");
+        assert_eq!(window.source, RuleSource::Daily);
+        let end = tz.with_ymd_and_hms(2024, 11, 3, 1, 30, 0).latest().unwrap();
+        assert_eq!(window.end, end, "the window ends at the second 01:30");
+        assert_eq!(evaluation.next_change, Some((end, ScheduledState::Asleep)));
+        assert_eq!(evaluation.sleep_warning, None, "ambient gets no warning");
+    }
+
+    #[test]
+    fn evaluation_names_the_weekend_rules_and_the_sleep_warning() {
+        let schedule = schedule_from_yaml(
+            r#"
+timezone: "Europe/Berlin"
+sleep-warning-minutes: 10
+awake-scheduled:
+  weekdays:
+    - ["07:00", "22:00"]
+  weekend:
+    - ["09:00", "02:30"]
+"#,
+        );
+
+        let tz = schedule.timezone();
+        // Clocks skip 02:00-03:00 that night, so the sleep moves to 03:00.
+        let at = tz.with_ymd_and_hms(2024, 3, 31, 1, 55, 0).single().unwrap();
+        let evaluation = schedule.evaluate(at);
+        assert_eq!(evaluation.rule_source, RuleSource::Weekend);
+        assert_eq!(evaluation.state, ScheduledState::Awake);
+        let sleep_at = tz.with_ymd_and_hms(2024, 3, 31, 3, 0, 0).single().unwrap();
+        assert_eq!(
+            evaluation.sleep_warning,
+            Some((
+                tz.with_ymd_and_hms(2024, 3, 31, 1, 50, 0).single().unwrap(),
+                sleep_at
+            ))
+        );
+        assert_eq!(
+            evaluation.next_change,
+            Some((sleep_at, ScheduledState::Asleep))
+        );
+
+        let monday = tz.with_ymd_and_hms(2024, 4, 1, 6, 0, 0).single().unwrap();
+        let evaluation = schedule.evaluate(monday);
+        assert_eq!(evaluation.rule_source, RuleSource::Weekdays);
+        assert_eq!(evaluation.window, None);
+        assert_eq!(RuleSource::Day(Weekday::Fri).label(), "friday");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn control_clients_follow_the_socket_to_the_runtime_dir() {
+        use std::os::unix::net::UnixListener;
+        let dir = tempfile::tempdir().unwrap();
+        let configured = dir.path().join("run/photo-frame/control.sock");
+        let runtime_dir = dir.path().join("user");
+        assert!(
+            control_socket::connect_with_fallback(&configured, None).is_err(),
+            "nothing is listening yet"
+        );
+
+        let fallback = fallback_control_socket_path(Some(runtime_dir.as_os_str())).unwrap();
+        let err = control_socket::connect_with_fallback(&configured, Some(runtime_dir.as_os_str()))
+            .unwrap_err();
+        let message = format!("{err:#}");
+        assert!(
+            message.contains(&configured.display().to_string()),
+            "{message}"
+        );
+        assert!(
+            message.contains(&fallback.display().to_string()),
+            "{message}"
+        );
+
+        std::fs::create_dir_all(fallback.parent().unwrap()).unwrap();
+        let _listener = UnixListener::bind(&fallback).unwrap();
+        control_socket::connect_with_fallback(&configured, Some(runtime_dir.as_os_str()))
+            .expect("the fallback socket is found");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn no_fallback_without_a_runtime_dir() {
+        assert_eq!(fallback_control_socket_path(None), None);
+        assert_eq!(fallback_control_socket_path(Some("".as_ref())), None);
+    }
+
+    #[test]
+    fn misspelled_screen_keys_are_rejected_with_a_suggestion() {
+        #[derive(Debug, Deserialize)]
+        #[serde(rename_all = "kebab-case")]
+        struct Screens {
+            #[serde(default)]
+            greeting_screen: GreetingScreenConfig,
+            #[serde(default)]
+            sleep_screen: SleepScreenConfig,
+            #[serde(default)]
+            empty_library_screen: EmptyLibraryScreenConfig,
+        }
+
+        for (yaml, path, suggestion) in [
+            (
+                "greeting-screen:\n  mesage: Hello\n",
+                "greeting-screen.mesage",
+                "message",
+            ),
+            (
+                "greeting-screen:\n  duration-secs: 3\n",
+                "greeting-screen.duration-secs",
+                "duration-seconds",
+            ),
+            (
+                "sleep-screen:\n  show-clok: true\n",
+                "sleep-screen.show-clok",
+                "show-clock",
+            ),
+            (
+                "sleep-screen:\n  colors:\n    acent: \"#ffffff\"\n",
+                "sleep-screen.colors.acent",
+                "accent",
+            ),
+            (
+                "empty-library-screen:\n  fade-sm: 200\n",
+                "empty-library-screen.fade-sm",
+                "fade-ms",
+            ),
+        ] {
+            let message = from_yaml_str::<Screens>(yaml).unwrap_err().to_string();
+            assert!(message.starts_with(path), "{message}");
+            assert!(
+                message.contains(&format!("did you mean '{suggestion}'?")),
+                "{message}"
+            );
+        }
+
+        let screens: Screens = from_yaml_str(
+            "greeting-screen:\n  message: Hi\n  duration-seconds: 3\nsleep-screen:\n  show-clock: true\nempty-library-screen:\n  fade-ms: 200\n",
+        )
+        .unwrap();
+        assert_eq!(
+            screens.greeting_screen.screen.message.as_deref(),
+            Some("Hi")
+        );
+        assert_eq!(screens.greeting_screen.duration_seconds, Some(3.0));
+        assert!(screens.sleep_screen.show_clock);
+        assert_eq!(
+            screens.sleep_screen.screen.message.as_deref(),
+            Some("Going to Sleep"),
+            "unset keys keep their defaults"
+        );
+        assert_eq!(screens.empty_library_screen.screen.fade_ms, Some(200));
+    }
+
+    #[test]
+    fn check_report_writes_the_schedule_back_as_it_is_read() {
+        let yaml = r#"
+timezone: Europe/Paris
+awake-scheduled:
+  daily:
+    - ["07:00", "22:30:15"]
+"#;
+        let schedule: AwakeScheduleConfig = from_yaml_str(yaml).unwrap();
+        let warnings = vec!["library is empty".to_string()];
+
+        let report = check_report(Path::new("frame.yaml"), &warnings, &schedule).unwrap();
+
+        assert!(
+            report
+                .starts_with("# frame.yaml: configuration is valid\n# warning: library is empty\n")
+        );
+        assert!(report.contains("- - 07:00\n    - 22:30:15\n"), "{report}");
+        let reread: AwakeScheduleConfig = from_yaml_str(&report).unwrap();
+        assert_eq!(
+            serde_yaml::to_string(&reread).unwrap(),
+            serde_yaml::to_string(&schedule).unwrap()
+        );
+    }
 }
@@ -0,0 +1,245 @@
+//! Tracing setup shared by the photo frame daemons. `photoframe` and
+//! `buttond` read the `logging` block of the shared configuration file;
+//! `wifi-manager` starts from the defaults. `RUST_LOG`, when set, still
+//! replaces the configured filter so a one-off debugging session never needs
+//! a config edit.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, ensure};
+use serde::{Deserialize, Serialize};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+/// How each log line is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogFormat {
+    /// One human-readable line per event, as journald shows it.
+    #[default]
+    Compact,
+    /// One JSON object per event, with the current span and its parents.
+    Json,
+}
+
+/// The `logging` block.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    pub format: LogFormat,
+    /// Level for every target without an entry in `modules`.
+    pub level: String,
+    /// Per-target levels keyed by module path, e.g. `photoframe::tasks::viewer: debug`.
+    pub modules: BTreeMap<String, String>,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            format: LogFormat::default(),
+            level: "info".into(),
+            modules: BTreeMap::new(),
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// Reads only the `logging` block of `path`, with its `include:` files
+    /// merged in as for the full configuration, ignoring everything else so
+    /// logging can start before the rest is validated.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Shared {
+            #[serde(default)]
+            logging: LoggingConfig,
+        }
+
+        let shared: Shared = config_model::read_yaml_file(path)
+            .with_context(|| format!("failed to parse logging in {}", path.display()))?;
+        shared.logging.validate()?;
+        Ok(shared.logging)
+    }
+
+    /// The filter as an `EnvFilter` directive string: the default level
+    /// first, then one `target=level` entry per module override.
+    pub fn directives(&self) -> String {
+        let mut directives = vec![self.level.trim().to_string()];
+        directives.extend(
+            self.modules
+                .iter()
+                .map(|(target, level)| format!("{}={}", target.trim(), level.trim())),
+        );
+        directives.join(",")
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.env_filter().map(drop)
+    }
+
+    pub fn env_filter(&self) -> Result<EnvFilter> {
+        parse_level(&self.level).context("invalid logging.level")?;
+        for (target, level) in &self.modules {
+            let target = target.trim();
+            ensure!(
+                !target.is_empty() && !target.contains([',', '=', '[', ']']),
+                "logging.modules key '{target}' must be a bare module path"
+            );
+            parse_level(level).with_context(|| format!("invalid logging.modules.{target}"))?;
+        }
+        let directives = self.directives();
+        EnvFilter::builder()
+            .parse(&directives)
+            .with_context(|| format!("invalid log filter '{directives}'"))
+    }
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter> {
+    level
+        .trim()
+        .parse::<LevelFilter>()
+        .map_err(|_| anyhow!("'{level}' is not a log level (off|error|warn|info|debug|trace)"))
+}
+
+/// Installs the global subscriber for `config`. A valid `RUST_LOG` takes the
+/// place of `level` and `modules`; the format still comes from `config`.
+pub fn init(config: &LoggingConfig) -> Result<()> {
+    let filter = match EnvFilter::try_from_default_env() {
+        Ok(filter) => filter,
+        Err(_) => config.env_filter()?,
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_target(true);
+    match config.format {
+        LogFormat::Compact => builder.compact().try_init(),
+        LogFormat::Json => builder
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .try_init(),
+    }
+    .map_err(|err| anyhow!("failed to install the log subscriber: {err}"))
+}
+
+/// Starts logging from the `logging` block of the shared configuration file,
+/// with `level_override` (a command-line flag) replacing `logging.level`.
+///
+/// A missing file or a broken `logging` block falls back to the defaults
+/// rather than failing: the daemon reports the problem through the log it
+/// just opened, and the full configuration load surfaces it again.
+pub fn init_from_file(path: &Path, level_override: Option<&str>) -> Result<()> {
+    let (mut config, problem) = match LoggingConfig::from_file(path) {
+        Ok(config) => (config, None),
+        Err(err) => (LoggingConfig::default(), Some(err)),
+    };
+    if let Some(level) = level_override {
+        config.level = level.to_string();
+    }
+    init(&config)?;
+    if let Some(err) = problem {
+        tracing::warn!(error = ?err, "using default logging settings");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn parse(yaml: &str) -> LoggingConfig {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn defaults_are_compact_at_info() {
+        let config = parse("{}");
+        assert_eq!(config, LoggingConfig::default());
+        assert_eq!(config.format, LogFormat::Compact);
+        assert_eq!(config.directives(), "info");
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn module_overrides_become_target_directives() {
+        let config = parse(
+            r#"
+format: json
+level: warn
+modules:
+  photoframe::tasks::viewer: debug
+  buttond: " trace "
+"#,
+        );
+        assert_eq!(config.format, LogFormat::Json);
+        assert_eq!(
+            config.directives(),
+            "warn,buttond=trace,photoframe::tasks::viewer=debug"
+        );
+        let filter = config.env_filter().unwrap().to_string();
+        assert!(
+            filter.contains("photoframe::tasks::viewer=debug"),
+            "{filter}"
+        );
+        assert!(filter.contains("buttond=trace"), "{filter}");
+    }
+
+    #[test]
+    fn bad_levels_and_targets_are_rejected() {
+        let err = parse("level: loud\n").validate().unwrap_err();
+        assert!(format!("{err:#}").contains("logging.level"), "{err:#}");
+
+        let err = parse("modules:\n  photoframe: chatty\n")
+            .validate()
+            .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("logging.modules.photoframe"),
+            "{err:#}"
+        );
+
+        let err = parse("modules:\n  \"a=b\": info\n").validate().unwrap_err();
+        assert!(format!("{err:#}").contains("bare module path"), "{err:#}");
+
+        assert!(serde_yaml::from_str::<LoggingConfig>("format: xml\n").is_err());
+        assert!(serde_yaml::from_str::<LoggingConfig>("targets: {}\n").is_err());
+    }
+
+    #[test]
+    fn from_file_reads_only_the_logging_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(
+            &path,
+            "photo-library-path: /photos\nlogging:\n  modules:\n    buttond: debug\n",
+        )
+        .unwrap();
+        let config = LoggingConfig::from_file(&path).unwrap();
+        assert_eq!(config.directives(), "info,buttond=debug");
+
+        fs::write(&path, "photo-library-path: /photos\n").unwrap();
+        assert_eq!(
+            LoggingConfig::from_file(&path).unwrap(),
+            LoggingConfig::default()
+        );
+    }
+
+    #[test]
+    fn from_file_follows_includes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        fs::write(
+            &path,
+            "include: conf.d/logging.yaml\nphoto-library-path: /photos\nlogging:\n  level: warn\n",
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("conf.d")).unwrap();
+        fs::write(
+            dir.path().join("conf.d/logging.yaml"),
+            "logging:\n  modules:\n    photoframe::tasks::viewer: debug\n",
+        )
+        .unwrap();
+        let config = LoggingConfig::from_file(&path).unwrap();
+        assert_eq!(config.directives(), "warn,photoframe::tasks::viewer=debug");
+    }
+}
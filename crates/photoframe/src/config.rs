@@ -1,27 +1,34 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
+use std::net::{IpAddr, SocketAddr};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, SystemTime};
 
-use anyhow::{Context, Result, ensure};
+use anyhow::{Context, Result, bail, ensure};
+use config_model::expand_path;
 use rand::Rng;
-use rand::seq::IteratorRandom;
-use serde::Deserialize;
+use rand::seq::{IndexedRandom, IteratorRandom};
 use serde::de::{self, DeserializeOwned, Deserializer, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::ser::{self, SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
 use serde_yaml::{Mapping, Value as YamlValue};
 
+use crate::processing::color::PhotoColors;
 use crate::processing::fixed_image::FixedImageBackground;
+use crate::processing::mat_texture::MatTexture;
 
 pub use config_model::{
-    AwakeScheduleConfig, GreetingScreenConfig, ScreenMessageConfig, ShowcaseConfig,
-    SleepScreenConfig,
+    AwakeScheduleConfig, EmptyLibraryScreenConfig, GreetingScreenConfig, LibraryState,
+    MessageContext, ScheduledState, ScreenMessageConfig, ShowcaseConfig, SleepScreenConfig,
+    SleepTransitionStyle,
 };
+pub use log_setup::{LogFormat, LoggingConfig};
 
 pub const DEFAULT_CONTROL_SOCKET_PATH: &str = "/run/photoframe/control.sock";
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct GlobalPhotoSettings {
     /// GPU render oversample factor relative to screen size (1.0 = native).
@@ -69,7 +76,7 @@ pub struct MattingConfig {
 /// Opt-in behavior that renders photos whose aspect ratio is already close to
 /// the display's edge-to-edge (full-bleed, cover-cropped) instead of matting
 /// them. Evaluated per photo *before* mat selection.
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct FillWhenFits {
     /// A photo is eligible when filling the screen crops less than this
@@ -163,7 +170,7 @@ impl FillWhenFits {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 enum PipelineSelection {
     Fixed,
@@ -408,18 +415,33 @@ impl Eq for MattingSelection {}
 pub enum StudioMatColor {
     Rgb([u8; 3]),
     PhotoAverage,
+    PhotoDominant,
 }
 
 impl StudioMatColor {
-    fn resolve(self, fallback: [f32; 3]) -> [f32; 3] {
+    fn resolve(self, photo: &PhotoColors) -> [f32; 3] {
         match self {
             StudioMatColor::Rgb(rgb) => [
                 (rgb[0] as f32) / 255.0,
                 (rgb[1] as f32) / 255.0,
                 (rgb[2] as f32) / 255.0,
             ],
-            StudioMatColor::PhotoAverage => fallback,
+            StudioMatColor::PhotoAverage => photo.average(),
+            StudioMatColor::PhotoDominant => photo.dominant(),
+        }
+    }
+
+    /// A bevel in this color around `photo`, blended `tint_strength` of the
+    /// way toward the photo's average so a white bevel doesn't glare on a
+    /// dark photo.
+    pub fn resolve_bevel(self, tint_strength: f32, photo: &PhotoColors) -> [f32; 3] {
+        let bevel = self.resolve(photo);
+        let tint = tint_strength.clamp(0.0, 1.0);
+        if tint <= 0.0 {
+            return bevel;
         }
+        let average = photo.average();
+        std::array::from_fn(|c| bevel[c] + (average[c] - bevel[c]) * tint)
     }
 }
 
@@ -434,7 +456,8 @@ impl<'de> Deserialize<'de> for StudioMatColor {
             type Value = StudioMatColor;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("an RGB triple or the string 'photo-average'")
+                formatter
+                    .write_str("an RGB triple or the string 'photo-average' or 'photo-dominant'")
             }
 
             fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
@@ -443,6 +466,7 @@ impl<'de> Deserialize<'de> for StudioMatColor {
             {
                 match value {
                     "photo-average" => Ok(StudioMatColor::PhotoAverage),
+                    "photo-dominant" => Ok(StudioMatColor::PhotoDominant),
                     other => Err(de::Error::invalid_value(Unexpected::Str(other), &self)),
                 }
             }
@@ -475,6 +499,19 @@ impl<'de> Deserialize<'de> for StudioMatColor {
     }
 }
 
+impl Serialize for StudioMatColor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            StudioMatColor::Rgb(rgb) => rgb.serialize(serializer),
+            StudioMatColor::PhotoAverage => serializer.serialize_str("photo-average"),
+            StudioMatColor::PhotoDominant => serializer.serialize_str("photo-dominant"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct MattingRuntime {
     fixed_color: Option<[u8; 3]>,
@@ -483,6 +520,7 @@ pub struct MattingRuntime {
     vignette_color: Option<StudioMatColor>,
     drop_shadow_color: Option<StudioMatColor>,
     fixed_image: Option<Arc<FixedImageBackground>>,
+    studio_texture: Option<Arc<MatTexture>>,
 }
 
 impl MattingRuntime {
@@ -490,26 +528,29 @@ impl MattingRuntime {
         self.fixed_color
     }
 
-    pub fn studio_color(&self, fallback: [f32; 3]) -> Option<[f32; 3]> {
-        self.studio_color.map(|color| color.resolve(fallback))
+    pub fn studio_color(&self, photo: &PhotoColors) -> Option<[f32; 3]> {
+        self.studio_color.map(|color| color.resolve(photo))
     }
 
-    pub fn passe_partout_color(&self, fallback: [f32; 3]) -> Option<[f32; 3]> {
-        self.passe_partout_color
-            .map(|color| color.resolve(fallback))
+    pub fn passe_partout_color(&self, photo: &PhotoColors) -> Option<[f32; 3]> {
+        self.passe_partout_color.map(|color| color.resolve(photo))
     }
 
-    pub fn vignette_color(&self, fallback: [f32; 3]) -> Option<[f32; 3]> {
-        self.vignette_color.map(|color| color.resolve(fallback))
+    pub fn vignette_color(&self, photo: &PhotoColors) -> Option<[f32; 3]> {
+        self.vignette_color.map(|color| color.resolve(photo))
     }
 
-    pub fn drop_shadow_color(&self, fallback: [f32; 3]) -> Option<[f32; 3]> {
-        self.drop_shadow_color.map(|color| color.resolve(fallback))
+    pub fn drop_shadow_color(&self, photo: &PhotoColors) -> Option<[f32; 3]> {
+        self.drop_shadow_color.map(|color| color.resolve(photo))
     }
 
     pub fn fixed_image(&self) -> Option<Arc<FixedImageBackground>> {
         self.fixed_image.clone()
     }
+
+    pub fn studio_texture(&self) -> Option<Arc<MatTexture>> {
+        self.studio_texture.clone()
+    }
 }
 
 impl MattingKind {
@@ -572,6 +613,15 @@ impl<'de> Deserialize<'de> for MattingKind {
     }
 }
 
+impl Serialize for MattingKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum MattingKind {
     FixedColor,
@@ -585,7 +635,7 @@ pub enum MattingKind {
     DropShadow,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "type", rename_all = "kebab-case")]
 pub enum MattingMode {
     FixedColor {
@@ -615,7 +665,10 @@ pub enum MattingMode {
             default = "MattingMode::default_studio_bevel_color",
             rename = "bevel-color"
         )]
-        bevel_color: [u8; 3],
+        bevel_color: StudioMatColor,
+        /// How far the bevel is blended toward the photo's average color, 0–1.
+        #[serde(default, rename = "bevel-tint-strength")]
+        bevel_tint_strength: f32,
         #[serde(
             default = "MattingMode::default_studio_texture_strength",
             rename = "texture-strength"
@@ -631,13 +684,12 @@ pub enum MattingMode {
             rename = "weft-period-px"
         )]
         weft_period_px: f32,
+        /// Paper or linen scan tiled over the mat instead of the procedural weave.
+        #[serde(default, rename = "texture-path")]
+        texture_path: Option<PathBuf>,
     },
     FixedImage {
-        #[serde(
-            default,
-            rename = "path",
-            deserialize_with = "deserialize_fixed_image_paths"
-        )]
+        #[serde(default, rename = "path", deserialize_with = "deserialize_path_list")]
         paths: Vec<PathBuf>,
         #[serde(default)]
         fit: FixedImageFit,
@@ -698,7 +750,7 @@ pub enum MattingMode {
             default = "MattingMode::default_studio_bevel_color",
             rename = "bevel-color"
         )]
-        bevel_color: [u8; 3],
+        bevel_color: StudioMatColor,
     },
     DropShadow {
         #[serde(default = "MattingMode::default_drop_shadow_colors")]
@@ -726,14 +778,17 @@ pub enum MattingMode {
     },
 }
 
-#[derive(Debug, Clone, Copy, Deserialize)]
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum BlurBackend {
     Cpu,
     Neon,
+    /// Compute-shader blur on the viewer's GPU; falls back to `Cpu` when the
+    /// adapter has no compute support.
+    Gpu,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum FixedImageFit {
     Cover,
@@ -753,7 +808,7 @@ impl Default for FixedImageFit {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum GradientDirection {
     Vertical,
@@ -767,7 +822,7 @@ impl Default for GradientDirection {
     }
 }
 
-fn deserialize_fixed_image_paths<'de, D>(deserializer: D) -> Result<Vec<PathBuf>, D::Error>
+fn deserialize_path_list<'de, D>(deserializer: D) -> Result<Vec<PathBuf>, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -809,6 +864,169 @@ where
     deserializer.deserialize_any(PathsVisitor)
 }
 
+/// One `photo-library-path` entry: a directory scanned for photos, written
+/// either as a bare path or as a map with `path` and the settings below.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LibraryRoot {
+    pub path: PathBuf,
+    /// Multiplies the scheduling weight of the photos found under it.
+    pub weight: f64,
+    /// Whether filesystem notifications report its changes. Without them it
+    /// is rescanned periodically, which suits network mounts that send none.
+    pub watch: bool,
+    /// Whether the frame starts without it when it is missing, checking for
+    /// it again until it appears. Set for bare paths and a sole root unless
+    /// the entry says otherwise.
+    pub optional: bool,
+}
+
+impl LibraryRoot {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            weight: 1.0,
+            watch: true,
+            optional: false,
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.path.as_os_str().is_empty(),
+            "photo-library-path entries must not be empty"
+        );
+        ensure!(
+            self.weight.is_finite() && self.weight >= 1.0,
+            "photo-library-path {} weight must be a finite number >= 1",
+            self.path.display()
+        );
+        Ok(())
+    }
+}
+
+impl From<PathBuf> for LibraryRoot {
+    fn from(path: PathBuf) -> Self {
+        Self::new(path)
+    }
+}
+
+/// The map form of a [`LibraryRoot`].
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct LibraryRootFields {
+    path: PathBuf,
+    weight: Option<f64>,
+    watch: Option<bool>,
+    optional: Option<bool>,
+}
+
+/// One entry as written. `optional` is left unset when the entry does not
+/// say, to be settled once the whole list is known.
+struct LibraryRootEntry {
+    root: LibraryRoot,
+    optional: Option<bool>,
+}
+
+impl<'de> Deserialize<'de> for LibraryRootEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(LibraryRootVisitor)
+    }
+}
+
+struct LibraryRootVisitor;
+
+impl<'de> Visitor<'de> for LibraryRootVisitor {
+    type Value = LibraryRootEntry;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a path or a map with a `path` key")
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        // A bare path is waited for when missing, as it always was.
+        Ok(LibraryRootEntry {
+            root: LibraryRoot::new(value),
+            optional: Some(true),
+        })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let fields = LibraryRootFields::deserialize(de::value::MapAccessDeserializer::new(map))?;
+        let defaults = LibraryRoot::new(fields.path);
+        Ok(LibraryRootEntry {
+            root: LibraryRoot {
+                weight: fields.weight.unwrap_or(defaults.weight),
+                watch: fields.watch.unwrap_or(defaults.watch),
+                ..defaults
+            },
+            optional: fields.optional,
+        })
+    }
+}
+
+/// `photo-library-path`: one root or a list of them. A map entry that does
+/// not set `optional` is optional only when it is the sole root, so a frame
+/// with a single library keeps waiting for it.
+fn deserialize_library_roots<'de, D>(deserializer: D) -> Result<Vec<LibraryRoot>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct RootsVisitor;
+
+    impl<'de> Visitor<'de> for RootsVisitor {
+        type Value = Vec<LibraryRootEntry>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+            formatter.write_str("a path, a map with a `path` key, or a list of them")
+        }
+
+        fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+        where
+            E: de::Error,
+        {
+            LibraryRootVisitor.visit_str(value).map(|entry| vec![entry])
+        }
+
+        fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            LibraryRootEntry::deserialize(de::value::MapAccessDeserializer::new(map))
+                .map(|entry| vec![entry])
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut entries = Vec::new();
+            while let Some(entry) = seq.next_element::<LibraryRootEntry>()? {
+                entries.push(entry);
+            }
+            Ok(entries)
+        }
+    }
+
+    let entries = deserializer.deserialize_any(RootsVisitor)?;
+    let sole = entries.len() == 1;
+    Ok(entries
+        .into_iter()
+        .map(|entry| LibraryRoot {
+            optional: entry.optional.unwrap_or(sole),
+            ..entry.root
+        })
+        .collect())
+}
+
 impl Default for MattingOptions {
     fn default() -> Self {
         Self {
@@ -825,6 +1043,13 @@ impl MattingOptions {
     }
 
     pub fn prepare_runtime(&mut self) -> Result<()> {
+        self.prepare_settings()?;
+        self.prepare_fixed_image()
+    }
+
+    /// Checks the mat's settings and resolves its colors and texture:
+    /// everything but the `fixed-image` background.
+    fn prepare_settings(&mut self) -> Result<()> {
         ensure!(
             self.minimum_mat_percentage.is_finite()
                 && (0.0..=100.0).contains(&self.minimum_mat_percentage),
@@ -839,12 +1064,28 @@ impl MattingOptions {
             );
             self.runtime.fixed_color = colors.first().copied();
         }
-        if let MattingMode::Studio { colors, .. } = &self.style {
+        if let MattingMode::Studio {
+            colors,
+            bevel_tint_strength,
+            texture_path,
+            ..
+        } = &self.style
+        {
             ensure!(
                 !colors.is_empty(),
                 "matting.studio.colors must include at least one entry",
             );
+            ensure!(
+                (0.0..=1.0).contains(bevel_tint_strength),
+                "matting.studio.bevel-tint-strength must be between 0 and 1 (got {bevel_tint_strength})",
+            );
             self.runtime.studio_color = colors.first().copied();
+            if let Some(path) = texture_path {
+                let texture = MatTexture::open(path.clone()).with_context(|| {
+                    format!("matting.studio.texture-path {} is unusable", path.display())
+                })?;
+                self.runtime.studio_texture = Some(Arc::new(texture));
+            }
         }
         if let MattingMode::PassePartout { colors, .. } = &self.style {
             ensure!(
@@ -867,34 +1108,48 @@ impl MattingOptions {
             );
             self.runtime.drop_shadow_color = colors.first().copied();
         }
-        if let MattingMode::FixedImage { paths, .. } = &self.style {
-            if paths.is_empty() {
-                return Ok(());
-            }
+        if let MattingMode::Gradient { angle_degrees, .. } = &self.style {
+            ensure!(
+                angle_degrees.is_finite() && (-360.0..=360.0).contains(angle_degrees),
+                "matting.gradient.angle-degrees must be between -360 and 360 (got {angle_degrees})",
+            );
+        }
+        Ok(())
+    }
 
-            for path in paths {
-                match FixedImageBackground::new(path.clone()) {
-                    Ok(background) => {
-                        self.runtime.fixed_image = Some(Arc::new(background));
-                        break;
-                    }
-                    Err(err) => {
-                        tracing::warn!(
-                            path = %path.display(),
-                            error = %err,
-                            "skipping fixed background image that failed to prepare"
-                        );
-                    }
-                }
-            }
+    /// Opens the first of the `fixed-image` paths that is a readable image,
+    /// skipping the others with a warning. Fails when none is.
+    fn prepare_fixed_image(&mut self) -> Result<()> {
+        let MattingMode::FixedImage { paths, .. } = &self.style else {
+            return Ok(());
+        };
+        if paths.is_empty() {
+            return Ok(());
+        }
 
-            if self.runtime.fixed_image.is_none() {
-                tracing::warn!(
-                    "all configured fixed-image backgrounds failed to load; disabling fixed-image matting"
-                );
+        for path in paths {
+            match FixedImageBackground::new(path.clone()) {
+                Ok(background) => {
+                    self.runtime.fixed_image = Some(Arc::new(background));
+                    return Ok(());
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        path = %path.display(),
+                        error = %err,
+                        "skipping fixed background image that failed to prepare"
+                    );
+                }
             }
         }
-        Ok(())
+        let listed: Vec<String> = paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        bail!(
+            "matting fixed-image {} is not a readable image",
+            listed.join(" or ")
+        )
     }
 
     pub fn fixed_color(&self) -> Option<[u8; 3]> {
@@ -930,6 +1185,7 @@ impl MattingOptions {
                 bevel_color: base
                     .bevel_color
                     .unwrap_or_else(MattingMode::default_studio_bevel_color),
+                bevel_tint_strength: base.bevel_tint_strength.unwrap_or_default(),
                 texture_strength: base
                     .texture_strength
                     .unwrap_or_else(MattingMode::default_studio_texture_strength),
@@ -939,6 +1195,7 @@ impl MattingOptions {
                 weft_period_px: base
                     .weft_period_px
                     .unwrap_or_else(MattingMode::default_studio_weft_period_px),
+                texture_path: base.studio_texture_path,
             },
             MattingKind::FixedImage => MattingMode::FixedImage {
                 paths: base
@@ -1065,11 +1322,13 @@ struct MattingOptionBuilder {
     sample_scale: Option<f32>,
     blur_backend: Option<BlurBackend>,
     bevel_width_px: Option<f32>,
-    bevel_color: Option<[u8; 3]>,
+    bevel_color: Option<StudioMatColor>,
+    bevel_tint_strength: Option<f32>,
     texture_strength: Option<f32>,
     warp_period_px: Option<f32>,
     weft_period_px: Option<f32>,
     studio_colors: Option<Vec<StudioMatColor>>,
+    studio_texture_path: Option<PathBuf>,
     fixed_image_paths: Option<Vec<PathBuf>>,
     fixed_image_fit: Option<FixedImageFit>,
     gradient_start: Option<[u8; 3]>,
@@ -1118,6 +1377,12 @@ where
     }
 }
 
+/// Prefixes an inline-field error with where it sits in the `active` list, so
+/// messages read `matting: active[1].colors: ...` rather than just `matting: ...`.
+fn at_active_field<E: de::Error>(position: usize, field: &str, err: E) -> E {
+    E::custom(format_args!("active[{position}].{field}: {err}"))
+}
+
 fn apply_inline_field<E>(
     builder: &mut MattingOptionBuilder,
     kind: MattingKind,
@@ -1206,7 +1471,13 @@ where
                     if builder.bevel_color.is_some() {
                         return Err(de::Error::duplicate_field("bevel-color"));
                     }
-                    builder.bevel_color = Some(inline_value_to::<[u8; 3], E>(value)?);
+                    builder.bevel_color = Some(inline_value_to::<StudioMatColor, E>(value)?);
+                }
+                "bevel-tint-strength" => {
+                    if builder.bevel_tint_strength.is_some() {
+                        return Err(de::Error::duplicate_field("bevel-tint-strength"));
+                    }
+                    builder.bevel_tint_strength = Some(inline_value_to::<f32, E>(value)?);
                 }
                 "texture-strength" => {
                     if builder.texture_strength.is_some() {
@@ -1226,6 +1497,12 @@ where
                     }
                     builder.weft_period_px = Some(inline_value_to::<f32, E>(value)?);
                 }
+                "texture-path" => {
+                    if builder.studio_texture_path.is_some() {
+                        return Err(de::Error::duplicate_field("texture-path"));
+                    }
+                    builder.studio_texture_path = Some(inline_value_to::<PathBuf, E>(value)?);
+                }
                 _ => {
                     return Err(de::Error::unknown_field(
                         other,
@@ -1234,9 +1511,11 @@ where
                             "color",
                             "bevel-width-px",
                             "bevel-color",
+                            "bevel-tint-strength",
                             "texture-strength",
                             "warp-period-px",
                             "weft-period-px",
+                            "texture-path",
                             "minimum-mat-percentage",
                         ],
                     ));
@@ -1419,7 +1698,7 @@ where
                     if builder.bevel_color.is_some() {
                         return Err(de::Error::duplicate_field("bevel-color"));
                     }
-                    builder.bevel_color = Some(inline_value_to::<[u8; 3], E>(value)?);
+                    builder.bevel_color = Some(inline_value_to::<StudioMatColor, E>(value)?);
                 }
                 _ => {
                     return Err(de::Error::unknown_field(
@@ -1653,11 +1932,12 @@ impl<'de> Visitor<'de> for MattingConfigVisitor {
         let active_entries = active.ok_or_else(|| de::Error::missing_field("active"))?;
         let mut options = Vec::new();
         let mut entries = Vec::new();
-        for entry in active_entries.into_iter() {
+        for (position, entry) in active_entries.into_iter().enumerate() {
             let kind = entry.kind;
             let mut builder = MattingOptionBuilder::default();
             for (field, value) in entry.fields {
-                apply_inline_field::<A::Error>(&mut builder, kind, &field, value)?;
+                apply_inline_field::<A::Error>(&mut builder, kind, &field, value)
+                    .map_err(|err| at_active_field(position, &field, err))?;
             }
             if matches!(kind, MattingKind::FixedImage) && builder.fixed_image_paths.is_none() {
                 return Err(de::Error::custom(
@@ -1695,6 +1975,54 @@ impl<'de> Visitor<'de> for MattingConfigVisitor {
     }
 }
 
+/// Written back in the shape it is read, with `selection` resolved and one
+/// `active` entry per canonical option, so a palette lists each color.
+impl Serialize for MattingConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let selection = match self.selection {
+            MattingSelection::Fixed(_) => PipelineSelection::Fixed,
+            MattingSelection::Random(_) => PipelineSelection::Random,
+            MattingSelection::Sequential { .. } => PipelineSelection::Sequential,
+        };
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("selection", &selection)?;
+        map.serialize_entry("active", &self.options)?;
+        if let Some(fill_when_fits) = &self.fill_when_fits {
+            map.serialize_entry("fill-when-fits", fill_when_fits)?;
+        }
+        map.end()
+    }
+}
+
+/// An `active` entry: `kind`, then the style's own keys as they are written
+/// inline, in place of the `type` tag the style serializes with. Unset keys
+/// are left out, since an inline key must carry a value.
+impl Serialize for MattingOptions {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let style =
+            match serde_yaml::to_value(&self.style).map_err(<S::Error as ser::Error>::custom)? {
+                YamlValue::Mapping(style) => style,
+                _ => Mapping::new(),
+            };
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", &self.kind())?;
+        map.serialize_entry("minimum-mat-percentage", &self.minimum_mat_percentage)?;
+        for (key, value) in style
+            .iter()
+            .filter(|(key, value)| key.as_str() != Some("type") && !value.is_null())
+        {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
 impl MattingConfig {
     /// Exposed for integration tests to introspect the parsed selection strategy.
     pub fn selection(&self) -> &MattingSelection {
@@ -1711,6 +2039,30 @@ impl MattingConfig {
         self.fill_when_fits.as_ref()
     }
 
+    /// Resolves relative `fixed-image` paths against `dir`, for a mat read
+    /// from a photo's sidecar file.
+    pub(crate) fn resolve_relative_paths(&mut self, dir: &Path) {
+        for option in &mut self.options {
+            if let MattingMode::FixedImage { paths, .. } = &mut option.style {
+                for path in paths.iter_mut().filter(|path| path.is_relative()) {
+                    *path = dir.join(&*path);
+                }
+            }
+        }
+    }
+
+    /// Expands `~` and `${VAR}` in `fixed-image` paths.
+    fn expand_paths(&mut self) -> Result<()> {
+        for option in &mut self.options {
+            if let MattingMode::FixedImage { paths, .. } = &mut option.style {
+                for path in paths.iter_mut() {
+                    *path = expand_path(path).context("matting fixed-image path")?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn selection_entries(&self) -> SelectionEntries<'_, MattingKind> {
         match self.selection() {
             MattingSelection::Fixed(entry) => SelectionEntries::single(*entry),
@@ -1764,14 +2116,64 @@ impl MattingConfig {
             !self.options().is_empty(),
             "matting configuration must include at least one active entry"
         );
-        for option in self.options.iter_mut() {
+        // A fixed-image mat whose background cannot be read is left out, as
+        // long as some other mat remains.
+        let mut unusable = Vec::new();
+        for (index, option) in self.options.iter_mut().enumerate() {
             option
-                .prepare_runtime()
+                .prepare_settings()
                 .context("failed to prepare matting resources")?;
+            if let Err(err) = option.prepare_fixed_image() {
+                unusable.push((index, err));
+            }
         }
+        if unusable.len() == self.options.len() {
+            let errors: Vec<String> = unusable.iter().map(|(_, err)| err.to_string()).collect();
+            bail!(
+                "no matting fixed-image background is usable: {}",
+                errors.join("; ")
+            );
+        }
+        for (_, err) in &unusable {
+            tracing::warn!("leaving out fixed-image mat: {err:#}");
+        }
+        let dropped: Vec<usize> = unusable.into_iter().map(|(index, _)| index).collect();
+        self.remove_options(&dropped);
         Ok(())
     }
 
+    /// Removes the options at `dropped` (ascending, and never all of them)
+    /// along with the selection entries naming them, renumbering the rest.
+    fn remove_options(&mut self, dropped: &[usize]) {
+        if dropped.is_empty() {
+            return;
+        }
+        let renumber = |entries: &[SelectionEntry<MattingKind>]| {
+            entries
+                .iter()
+                .filter(|entry| !dropped.contains(&entry.index))
+                .map(|entry| SelectionEntry {
+                    index: entry.index - dropped.partition_point(|&index| index < entry.index),
+                    kind: entry.kind,
+                })
+                .collect::<Vec<_>>()
+        };
+        self.selection = match &self.selection {
+            // A fixed selection has a single option, so nothing was dropped.
+            MattingSelection::Fixed(entry) => MattingSelection::Fixed(*entry),
+            MattingSelection::Random(entries) => MattingSelection::Random(renumber(entries).into()),
+            MattingSelection::Sequential { entries, .. } => MattingSelection::Sequential {
+                entries: renumber(entries).into(),
+                runtime: SequentialState::default(),
+            },
+        };
+        let mut index = 0;
+        self.options.retain(|_| {
+            index += 1;
+            !dropped.contains(&(index - 1))
+        });
+    }
+
     #[allow(dead_code)]
     pub fn choose_option<R: Rng + ?Sized>(&self, rng: &mut R) -> MattingOptions {
         self.select_active(rng).option.clone()
@@ -1833,8 +2235,8 @@ impl MattingMode {
         3.0
     }
 
-    const fn default_studio_bevel_color() -> [u8; 3] {
-        [255, 255, 255]
+    const fn default_studio_bevel_color() -> StudioMatColor {
+        StudioMatColor::Rgb([255, 255, 255])
     }
 
     const fn default_studio_texture_strength() -> f32 {
@@ -1909,15 +2311,19 @@ impl MattingMode {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PhotoEffectKind {
     PrintSimulation,
+    FilmGrain,
+    Tone,
 }
 
 impl PhotoEffectKind {
-    const ALL: &'static [Self] = &[Self::PrintSimulation];
-    const NAMES: &'static [&'static str] = &["print-simulation"];
+    const ALL: &'static [Self] = &[Self::PrintSimulation, Self::FilmGrain, Self::Tone];
+    const NAMES: &'static [&'static str] = &["print-simulation", "film-grain", "tone"];
 
     fn as_str(&self) -> &'static str {
         match self {
             Self::PrintSimulation => "print-simulation",
+            Self::FilmGrain => "film-grain",
+            Self::Tone => "tone",
         }
     }
 }
@@ -1943,7 +2349,16 @@ impl<'de> Deserialize<'de> for PhotoEffectKind {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+impl Serialize for PhotoEffectKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct PrintSimulationOptions {
     #[serde(
@@ -1973,6 +2388,10 @@ pub struct PrintSimulationOptions {
     pub paper_color: [u8; 3],
     #[serde(default)]
     pub debug: bool,
+    /// Scales relief and ink spread with the photo's age; unset applies the
+    /// strengths above to every photo.
+    #[serde(default)]
+    pub age_ramp: Option<PrintAgeRamp>,
 }
 
 impl PrintSimulationOptions {
@@ -2013,6 +2432,79 @@ impl PrintSimulationOptions {
             self.light_angle_degrees.is_finite(),
             "photo-effect.print-simulation.light-angle-degrees must be a finite value"
         );
+        if let Some(ramp) = &self.age_ramp {
+            ramp.validate()?;
+        }
+        Ok(())
+    }
+
+    /// These options for a photo taken `age` ago: relief and ink spread scaled
+    /// by `age-ramp`, when one is configured.
+    pub fn for_age(&self, age: Duration) -> Self {
+        let mut options = self.clone();
+        if let Some(ramp) = &self.age_ramp {
+            let scale = ramp.scale_for(age);
+            options.relief_strength *= scale;
+            options.ink_spread *= scale;
+        }
+        options
+    }
+}
+
+/// `print-simulation.age-ramp`: photos up to `young-years` old get
+/// `young-scale` times the configured relief and ink spread, photos
+/// `old-years` or older get `old-scale`, and ages in between are interpolated
+/// linearly.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PrintAgeRamp {
+    #[serde(default)]
+    pub young_years: f32,
+    #[serde(default = "PrintAgeRamp::default_old_years")]
+    pub old_years: f32,
+    #[serde(default = "PrintAgeRamp::default_young_scale")]
+    pub young_scale: f32,
+    #[serde(default = "PrintAgeRamp::default_old_scale")]
+    pub old_scale: f32,
+}
+
+impl PrintAgeRamp {
+    const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 60.0 * 60.0;
+
+    const fn default_old_years() -> f32 {
+        30.0
+    }
+
+    const fn default_young_scale() -> f32 {
+        0.25
+    }
+
+    const fn default_old_scale() -> f32 {
+        1.0
+    }
+
+    pub fn scale_for(&self, age: Duration) -> f32 {
+        let years = (age.as_secs_f64() / Self::SECONDS_PER_YEAR) as f32;
+        let t = ((years - self.young_years) / (self.old_years - self.young_years)).clamp(0.0, 1.0);
+        self.young_scale + (self.old_scale - self.young_scale) * t
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.young_years.is_finite() && self.young_years >= 0.0,
+            "photo-effect.print-simulation.age-ramp.young-years must be non-negative"
+        );
+        ensure!(
+            self.old_years.is_finite() && self.old_years > self.young_years,
+            "photo-effect.print-simulation.age-ramp.old-years must be greater than young-years"
+        );
+        ensure!(
+            self.young_scale.is_finite()
+                && self.young_scale >= 0.0
+                && self.old_scale.is_finite()
+                && self.old_scale >= 0.0,
+            "photo-effect.print-simulation.age-ramp scales must be non-negative"
+        );
         Ok(())
     }
 }
@@ -2026,47 +2518,204 @@ impl Default for PrintSimulationOptions {
             sheen_strength: Self::default_sheen_strength(),
             paper_color: Self::default_paper_color(),
             debug: false,
+            age_ramp: None,
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum PhotoEffectOptions {
-    PrintSimulation(PrintSimulationOptions),
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FilmGrainOptions {
+    #[serde(
+        default = "FilmGrainOptions::default_grain_strength",
+        rename = "grain-strength"
+    )]
+    pub grain_strength: f32,
+    #[serde(
+        default = "FilmGrainOptions::default_grain_size_px",
+        rename = "grain-size-px"
+    )]
+    pub grain_size_px: f32,
+    #[serde(
+        default = "FilmGrainOptions::default_vignette_strength",
+        rename = "vignette-strength"
+    )]
+    pub vignette_strength: f32,
 }
 
-impl PhotoEffectOptions {
-    pub fn kind(&self) -> PhotoEffectKind {
-        match self {
-            Self::PrintSimulation(_) => PhotoEffectKind::PrintSimulation,
-        }
+impl FilmGrainOptions {
+    const fn default_grain_strength() -> f32 {
+        0.04
+    }
+
+    const fn default_grain_size_px() -> f32 {
+        1.5
+    }
+
+    const fn default_vignette_strength() -> f32 {
+        0.15
     }
 
     pub fn validate(&self) -> Result<()> {
-        match self {
-            PhotoEffectOptions::PrintSimulation(options) => options
-                .validate()
-                .context("invalid print-simulation options"),
+        ensure!(
+            self.grain_strength.is_finite() && self.grain_strength >= 0.0,
+            "photo-effect.film-grain.grain-strength must be non-negative"
+        );
+        ensure!(
+            self.grain_size_px.is_finite() && self.grain_size_px >= 1.0,
+            "photo-effect.film-grain.grain-size-px must be at least 1.0"
+        );
+        ensure!(
+            self.vignette_strength.is_finite() && self.vignette_strength >= 0.0,
+            "photo-effect.film-grain.vignette-strength must be non-negative"
+        );
+        Ok(())
+    }
+}
+
+impl Default for FilmGrainOptions {
+    fn default() -> Self {
+        Self {
+            grain_strength: Self::default_grain_strength(),
+            grain_size_px: Self::default_grain_size_px(),
+            vignette_strength: Self::default_vignette_strength(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum PhotoEffectSelection {
-    Disabled,
-    Fixed(SelectionEntry<PhotoEffectKind>),
-    Random(Arc<[SelectionEntry<PhotoEffectKind>]>),
-    Sequential {
-        entries: Arc<[SelectionEntry<PhotoEffectKind>]>,
-        runtime: SequentialState,
-    },
+/// How the `tone` effect recolors a photo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ToneMode {
+    /// Warm brown monochrome, like an aged silver print.
+    Sepia,
+    Grayscale,
+    /// Luminance mapped from `shadow-color` to `highlight-color`.
+    Duotone,
 }
 
-impl PartialEq for PhotoEffectSelection {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (PhotoEffectSelection::Disabled, PhotoEffectSelection::Disabled) => true,
-            (PhotoEffectSelection::Fixed(a), PhotoEffectSelection::Fixed(b)) => a == b,
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ToneOptions {
+    #[serde(default = "ToneOptions::default_mode")]
+    pub mode: ToneMode,
+    /// Blend between the original colors (0.0) and the full tone (1.0).
+    #[serde(default = "ToneOptions::default_strength")]
+    pub strength: f32,
+    /// Duotone color for black; ignored by the other modes.
+    #[serde(default = "ToneOptions::default_shadow_color")]
+    pub shadow_color: [u8; 3],
+    /// Duotone color for white; ignored by the other modes.
+    #[serde(default = "ToneOptions::default_highlight_color")]
+    pub highlight_color: [u8; 3],
+}
+
+impl ToneOptions {
+    const fn default_mode() -> ToneMode {
+        ToneMode::Sepia
+    }
+
+    const fn default_strength() -> f32 {
+        1.0
+    }
+
+    const fn default_shadow_color() -> [u8; 3] {
+        [34, 30, 62]
+    }
+
+    const fn default_highlight_color() -> [u8; 3] {
+        [250, 236, 204]
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            self.strength.is_finite() && (0.0..=1.0).contains(&self.strength),
+            "photo-effect.tone.strength must be between 0.0 and 1.0"
+        );
+        Ok(())
+    }
+}
+
+impl Default for ToneOptions {
+    fn default() -> Self {
+        Self {
+            mode: Self::default_mode(),
+            strength: Self::default_strength(),
+            shadow_color: Self::default_shadow_color(),
+            highlight_color: Self::default_highlight_color(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PhotoEffectOptions {
+    PrintSimulation(PrintSimulationOptions),
+    FilmGrain(FilmGrainOptions),
+    Tone(ToneOptions),
+}
+
+/// An `active` entry: `kind` followed by the effect's options.
+impl Serialize for PhotoEffectOptions {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[derive(Serialize)]
+        struct Entry<'a, O> {
+            kind: PhotoEffectKind,
+            #[serde(flatten)]
+            options: &'a O,
+        }
+
+        let kind = self.kind();
+        match self {
+            Self::PrintSimulation(options) => Entry { kind, options }.serialize(serializer),
+            Self::FilmGrain(options) => Entry { kind, options }.serialize(serializer),
+            Self::Tone(options) => Entry { kind, options }.serialize(serializer),
+        }
+    }
+}
+
+impl PhotoEffectOptions {
+    pub fn kind(&self) -> PhotoEffectKind {
+        match self {
+            Self::PrintSimulation(_) => PhotoEffectKind::PrintSimulation,
+            Self::FilmGrain(_) => PhotoEffectKind::FilmGrain,
+            Self::Tone(_) => PhotoEffectKind::Tone,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            PhotoEffectOptions::PrintSimulation(options) => options
+                .validate()
+                .context("invalid print-simulation options"),
+            PhotoEffectOptions::FilmGrain(options) => {
+                options.validate().context("invalid film-grain options")
+            }
+            PhotoEffectOptions::Tone(options) => options.validate().context("invalid tone options"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum PhotoEffectSelection {
+    Disabled,
+    Fixed(SelectionEntry<PhotoEffectKind>),
+    Random(Arc<[SelectionEntry<PhotoEffectKind>]>),
+    Sequential {
+        entries: Arc<[SelectionEntry<PhotoEffectKind>]>,
+        runtime: SequentialState,
+    },
+    /// Every entry, applied in order to each photo.
+    Chain(Arc<[SelectionEntry<PhotoEffectKind>]>),
+}
+
+impl PartialEq for PhotoEffectSelection {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (PhotoEffectSelection::Disabled, PhotoEffectSelection::Disabled) => true,
+            (PhotoEffectSelection::Fixed(a), PhotoEffectSelection::Fixed(b)) => a == b,
             (PhotoEffectSelection::Random(a), PhotoEffectSelection::Random(b)) => {
                 a.as_ref() == b.as_ref()
             }
@@ -2074,6 +2723,9 @@ impl PartialEq for PhotoEffectSelection {
                 PhotoEffectSelection::Sequential { entries: a, .. },
                 PhotoEffectSelection::Sequential { entries: b, .. },
             ) => a.as_ref() == b.as_ref(),
+            (PhotoEffectSelection::Chain(a), PhotoEffectSelection::Chain(b)) => {
+                a.as_ref() == b.as_ref()
+            }
             _ => false,
         }
     }
@@ -2101,10 +2753,27 @@ impl PhotoEffectConfig {
         !matches!(self.selection, PhotoEffectSelection::Disabled)
     }
 
+    /// The effects to apply to the next photo, in order: the whole list
+    /// under `chain`, otherwise the single pick of [`Self::choose_option`].
+    pub fn choose_effects<R: Rng + ?Sized>(&self, rng: &mut R) -> Vec<PhotoEffectOptions> {
+        match &self.selection {
+            PhotoEffectSelection::Chain(entries) => entries
+                .iter()
+                .filter_map(|entry| self.option_for_entry(*entry).cloned())
+                .collect(),
+            _ => self.choose_option(rng).into_iter().collect(),
+        }
+    }
+
+    /// Picks one effect for the next photo. Under `chain` this is the first
+    /// link; use [`Self::choose_effects`] to get all of them.
     pub fn choose_option<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<PhotoEffectOptions> {
         match &self.selection {
             PhotoEffectSelection::Disabled => None,
             PhotoEffectSelection::Fixed(entry) => self.option_for_entry(*entry).cloned(),
+            PhotoEffectSelection::Chain(entries) => entries
+                .first()
+                .and_then(|entry| self.option_for_entry(*entry).cloned()),
             PhotoEffectSelection::Random(entries) => entries
                 .iter()
                 .copied()
@@ -2134,7 +2803,8 @@ impl PhotoEffectConfig {
                 );
             }
             PhotoEffectSelection::Random(entries)
-            | PhotoEffectSelection::Sequential { entries, .. } => {
+            | PhotoEffectSelection::Sequential { entries, .. }
+            | PhotoEffectSelection::Chain(entries) => {
                 ensure!(
                     !entries.is_empty(),
                     "photo-effect configuration must include at least one active entry",
@@ -2176,6 +2846,27 @@ impl<'de> Deserialize<'de> for PhotoEffectConfig {
     }
 }
 
+/// `photo-effect.selection`: the shared pipeline modes plus `chain`, which
+/// only makes sense for effects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum PhotoEffectSelectionMode {
+    Fixed,
+    Random,
+    Sequential,
+    Chain,
+}
+
+impl PhotoEffectSelectionMode {
+    fn pipeline(self) -> PipelineSelection {
+        match self {
+            Self::Fixed => PipelineSelection::Fixed,
+            Self::Random => PipelineSelection::Random,
+            Self::Sequential | Self::Chain => PipelineSelection::Sequential,
+        }
+    }
+}
+
 struct PhotoEffectConfigVisitor;
 
 impl<'de> Visitor<'de> for PhotoEffectConfigVisitor {
@@ -2189,7 +2880,7 @@ impl<'de> Visitor<'de> for PhotoEffectConfigVisitor {
     where
         A: MapAccess<'de>,
     {
-        let mut selection: Option<PipelineSelection> = None;
+        let mut selection: Option<PhotoEffectSelectionMode> = None;
         let mut active: Option<Vec<PipelineEntry<PhotoEffectKind>>> = None;
 
         while let Some(key) = map.next_key::<String>()? {
@@ -2222,9 +2913,11 @@ impl<'de> Visitor<'de> for PhotoEffectConfigVisitor {
 
         let mut options = Vec::new();
         let mut entries = Vec::with_capacity(active_entries.len());
-        for entry in active_entries.into_iter() {
+        for (position, entry) in active_entries.into_iter().enumerate() {
             let kind = entry.kind;
-            let option = build_photo_effect_option::<A::Error>(kind, entry.fields)?;
+            let option = build_photo_effect_option::<A::Error>(kind, entry.fields).map_err(
+                |err: A::Error| de::Error::custom(format_args!("active[{position}]: {err}")),
+            )?;
             let canonical_index = options.len();
             options.push(option);
             entries.push(SelectionEntry {
@@ -2233,10 +2926,19 @@ impl<'de> Visitor<'de> for PhotoEffectConfigVisitor {
             });
         }
 
-        let resolved_selection =
-            resolve_pipeline_selection::<A::Error>(selection, options.len(), "photo-effect")?;
-
         let entries: Arc<[SelectionEntry<PhotoEffectKind>]> = entries.into();
+        if selection == Some(PhotoEffectSelectionMode::Chain) {
+            return Ok(PhotoEffectConfig {
+                selection: PhotoEffectSelection::Chain(entries),
+                options,
+            });
+        }
+
+        let resolved_selection = resolve_pipeline_selection::<A::Error>(
+            selection.map(PhotoEffectSelectionMode::pipeline),
+            options.len(),
+            "photo-effect",
+        )?;
 
         let selection = match resolved_selection {
             PipelineSelection::Fixed => PhotoEffectSelection::Fixed(entries[0]),
@@ -2251,6 +2953,27 @@ impl<'de> Visitor<'de> for PhotoEffectConfigVisitor {
     }
 }
 
+impl Serialize for PhotoEffectConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let selection = match self.selection {
+            PhotoEffectSelection::Disabled => None,
+            PhotoEffectSelection::Fixed(_) => Some(PhotoEffectSelectionMode::Fixed),
+            PhotoEffectSelection::Random(_) => Some(PhotoEffectSelectionMode::Random),
+            PhotoEffectSelection::Sequential { .. } => Some(PhotoEffectSelectionMode::Sequential),
+            PhotoEffectSelection::Chain(_) => Some(PhotoEffectSelectionMode::Chain),
+        };
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(selection) = selection {
+            map.serialize_entry("selection", &selection)?;
+        }
+        map.serialize_entry("active", &self.options)?;
+        map.end()
+    }
+}
+
 fn build_photo_effect_option<E>(
     kind: PhotoEffectKind,
     fields: Vec<(String, YamlValue)>,
@@ -2275,6 +2998,14 @@ where
             let options = inline_value_to::<PrintSimulationOptions, E>(value)?;
             Ok(PhotoEffectOptions::PrintSimulation(options))
         }
+        PhotoEffectKind::FilmGrain => {
+            let options = inline_value_to::<FilmGrainOptions, E>(value)?;
+            Ok(PhotoEffectOptions::FilmGrain(options))
+        }
+        PhotoEffectKind::Tone => {
+            let options = inline_value_to::<ToneOptions, E>(value)?;
+            Ok(PhotoEffectOptions::Tone(options))
+        }
     }
 }
 
@@ -2318,6 +3049,7 @@ pub enum TransitionKind {
     RadialWipe,
     VenetianBlinds,
     CrossfadeZoom,
+    CrossfadeBlur,
     Iris,
 }
 
@@ -2331,6 +3063,7 @@ impl TransitionKind {
         Self::RadialWipe,
         Self::VenetianBlinds,
         Self::CrossfadeZoom,
+        Self::CrossfadeBlur,
         Self::Iris,
     ];
     const NAMES: &'static [&'static str] = &[
@@ -2342,6 +3075,7 @@ impl TransitionKind {
         "radial-wipe",
         "venetian-blinds",
         "crossfade-zoom",
+        "crossfade-blur",
         "iris",
     ];
 
@@ -2355,6 +3089,7 @@ impl TransitionKind {
             Self::RadialWipe => "radial-wipe",
             Self::VenetianBlinds => "venetian-blinds",
             Self::CrossfadeZoom => "crossfade-zoom",
+            Self::CrossfadeBlur => "crossfade-blur",
             Self::Iris => "iris",
         }
     }
@@ -2373,6 +3108,7 @@ impl TransitionKind {
             Self::VenetianBlinds => 9,
             Self::CrossfadeZoom => 10,
             Self::Iris => 11,
+            Self::CrossfadeBlur => 12,
         }
     }
 }
@@ -2398,6 +3134,15 @@ impl<'de> Deserialize<'de> for TransitionKind {
     }
 }
 
+impl Serialize for TransitionKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TransitionConfig {
     selection: TransitionSelection,
@@ -2455,9 +3200,8 @@ impl TransitionConfig {
         let entry = match self.selection() {
             TransitionSelection::Fixed(entry) => *entry,
             TransitionSelection::Random(entries) => *entries
-                .iter()
-                .choose(rng)
-                .expect("validated random transition should have options"),
+                .choose_weighted(rng, |entry| self.options[entry.index].weight)
+                .expect("validated random transition should have positive weights"),
             TransitionSelection::Sequential { entries, runtime } => {
                 let index = runtime.next(entries.len());
                 entries[index]
@@ -2542,6 +3286,8 @@ impl TransitionConfig {
 pub struct TransitionOptions {
     kind: TransitionKind,
     duration_ms: u64,
+    /// Relative draw weight under `selection: random`.
+    weight: f32,
     mode: TransitionMode,
 }
 
@@ -2567,11 +3313,16 @@ impl TransitionOptions {
                 700,
                 TransitionMode::CrossfadeZoom(CrossfadeZoomTransition::default()),
             ),
+            TransitionKind::CrossfadeBlur => (
+                900,
+                TransitionMode::CrossfadeBlur(CrossfadeBlurTransition::default()),
+            ),
             TransitionKind::Iris => (2600, TransitionMode::Iris(IrisTransition::default())),
         };
         Self {
             kind,
             duration_ms,
+            weight: 1.0,
             mode,
         }
     }
@@ -2580,6 +3331,10 @@ impl TransitionOptions {
         self.kind
     }
 
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+
     pub fn duration(&self) -> Duration {
         Duration::from_millis(self.duration_ms.max(1))
     }
@@ -2593,6 +3348,11 @@ impl TransitionOptions {
             self.duration_ms > 0,
             format!("transition option {} must set duration-ms > 0", self.kind)
         );
+        ensure!(
+            self.weight.is_finite() && self.weight > 0.0,
+            "transition option {} must set a finite weight > 0",
+            self.kind
+        );
         match &mut self.mode {
             TransitionMode::Fade(_) => {}
             TransitionMode::Wipe(wipe) => {
@@ -2645,6 +3405,9 @@ impl TransitionOptions {
                 }
                 cz.zoom = cz.zoom.clamp(0.0, 0.5);
             }
+            TransitionMode::CrossfadeBlur(cb) => {
+                cb.sanitize();
+            }
             TransitionMode::Iris(iris) => {
                 iris.sanitize();
             }
@@ -2656,6 +3419,7 @@ impl TransitionOptions {
         let duration_ms = builder
             .duration_ms
             .unwrap_or_else(|| TransitionOptions::default_for(kind).duration_ms);
+        let weight = builder.weight.unwrap_or(1.0);
         let mode = match kind {
             TransitionKind::Fade => TransitionMode::Fade(FadeTransition {
                 through_black: builder.fade_through_black.unwrap_or(false),
@@ -2703,6 +3467,7 @@ impl TransitionOptions {
                 let jitter = builder.push_angle_jitter_deg.unwrap_or(0.0);
                 TransitionMode::Push(PushTransition {
                     angles: AnglePicker::new(base, jitter),
+                    random_direction: builder.push_direction == Some(PushDirection::Random),
                 })
             }
             TransitionKind::EInk => {
@@ -2779,6 +3544,14 @@ impl TransitionOptions {
                 cz.zoom = cz.zoom.clamp(0.0, 0.5);
                 TransitionMode::CrossfadeZoom(cz)
             }
+            TransitionKind::CrossfadeBlur => {
+                let defaults = CrossfadeBlurTransition::default();
+                let mut cb = CrossfadeBlurTransition {
+                    strength: builder.crossfade_blur_strength.unwrap_or(defaults.strength),
+                };
+                cb.sanitize();
+                TransitionMode::CrossfadeBlur(cb)
+            }
             TransitionKind::Iris => {
                 let defaults = IrisTransition::default();
                 let mut iris = IrisTransition {
@@ -2790,7 +3563,20 @@ impl TransitionOptions {
                         .unwrap_or(defaults.overlap_shadow),
                     min_aperture: builder.iris_min_aperture.unwrap_or(defaults.min_aperture),
                     swirl: builder.iris_swirl.unwrap_or(defaults.swirl),
+                    stroke_width: builder.iris_stroke_width.unwrap_or(defaults.stroke_width),
+                    stroke_color: builder.iris_stroke_color.unwrap_or(defaults.stroke_color),
+                    direction: builder.iris_direction.unwrap_or(defaults.direction),
                 };
+                // Out-of-range values are clamped below; these have no
+                // sensible nearest value.
+                ensure!(
+                    iris.blades > 0,
+                    "transition option {kind} needs at least one blade"
+                );
+                ensure!(
+                    iris.stroke_width.is_finite(),
+                    "transition option {kind} has non-finite stroke-width"
+                );
                 iris.sanitize();
                 TransitionMode::Iris(iris)
             }
@@ -2798,6 +3584,7 @@ impl TransitionOptions {
         let mut option = Self {
             kind,
             duration_ms,
+            weight,
             mode,
         };
 
@@ -2826,6 +3613,7 @@ impl TransitionOptions {
             | TransitionMode::RadialWipe(_)
             | TransitionMode::VenetianBlinds(_)
             | TransitionMode::CrossfadeZoom(_)
+            | TransitionMode::CrossfadeBlur(_)
             | TransitionMode::Iris(_) => {}
         }
 
@@ -2843,6 +3631,7 @@ pub enum TransitionMode {
     RadialWipe(RadialWipeTransition),
     VenetianBlinds(VenetianBlindsTransition),
     CrossfadeZoom(CrossfadeZoomTransition),
+    CrossfadeBlur(CrossfadeBlurTransition),
     Iris(IrisTransition),
 }
 
@@ -2909,6 +3698,54 @@ impl Default for WipeTransition {
 #[derive(Debug, Clone, Default)]
 pub struct PushTransition {
     pub angles: AnglePicker,
+    /// `direction: random`: each transition travels toward a randomly drawn
+    /// screen edge instead of `angles.base_deg`.
+    pub random_direction: bool,
+}
+
+impl PushTransition {
+    /// The travel angle for one transition, in degrees (`0°` moves the photos
+    /// right, `90°` down). Jitter applies on top of a random direction too.
+    pub fn pick_angle_deg<R: Rng + ?Sized>(&self, rng: &mut R) -> f32 {
+        let base = if self.random_direction {
+            PushDirection::EDGES[rng.random_range(0..PushDirection::EDGES.len())].angle_deg()
+        } else {
+            self.angles.base_deg
+        };
+        let jitter = self.angles.jitter_deg;
+        if jitter.abs() > f32::EPSILON {
+            base + rng.random_range(-jitter..=jitter)
+        } else {
+            base
+        }
+    }
+}
+
+/// Named push directions: the way both photos travel, so `left` brings the
+/// new photo in from the right edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PushDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+    Random,
+}
+
+impl PushDirection {
+    const EDGES: [PushDirection; 4] = [Self::Left, Self::Right, Self::Up, Self::Down];
+
+    /// The matching `angles` value. `random` has none of its own and maps to
+    /// `0°`; the direction is drawn per transition instead.
+    fn angle_deg(self) -> f32 {
+        match self {
+            Self::Right | Self::Random => 0.0,
+            Self::Down => 90.0,
+            Self::Left => 180.0,
+            Self::Up => 270.0,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -2945,7 +3782,7 @@ impl Default for DissolveTransition {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum RadialShape {
     Circle,
@@ -3009,6 +3846,29 @@ impl Default for CrossfadeZoomTransition {
     }
 }
 
+/// Crossfade that blurs both photos on the way through: the blur peaks at
+/// the midpoint and is gone again at either end.
+#[derive(Debug, Clone, Copy)]
+pub struct CrossfadeBlurTransition {
+    /// Blur radius at the midpoint, in screen pixels.
+    pub strength: f32,
+}
+
+impl Default for CrossfadeBlurTransition {
+    fn default() -> Self {
+        Self { strength: 16.0 }
+    }
+}
+
+impl CrossfadeBlurTransition {
+    fn sanitize(&mut self) {
+        if !self.strength.is_finite() {
+            self.strength = Self::default().strength;
+        }
+        self.strength = self.strength.clamp(0.0, 64.0);
+    }
+}
+
 /// Mechanical camera-iris transition: N annular petals (rounded ends, pivoting
 /// at one end cap like a paper iris diaphragm) close over the current photo and
 /// reopen on the next. Petal geometry (radii, width, swing) is derived from the
@@ -3021,6 +3881,25 @@ pub struct IrisTransition {
     pub overlap_shadow: f32,
     pub min_aperture: f32,
     pub swirl: f32,
+    /// Outline drawn along the petal edges, in screen pixels; `0` draws none.
+    pub stroke_width: f32,
+    pub stroke_color: [u8; 3],
+    pub direction: IrisDirection,
+}
+
+/// Which way an iris transition runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IrisDirection {
+    /// Solid petals close over the current photo and reopen on the next.
+    #[default]
+    CloseOpen,
+    /// The aperture opens from the center onto the next photo; the petals
+    /// carry the current one out to the edges.
+    Open,
+    /// The petals close in from the edges carrying the next photo, shrinking
+    /// the aperture that frames the current one.
+    Close,
 }
 
 impl Default for IrisTransition {
@@ -3032,6 +3911,9 @@ impl Default for IrisTransition {
             overlap_shadow: 0.6,
             min_aperture: 0.05,
             swirl: -0.45,
+            stroke_width: 0.0,
+            stroke_color: [0, 0, 0],
+            direction: IrisDirection::CloseOpen,
         }
     }
 }
@@ -3058,6 +3940,10 @@ impl IrisTransition {
             self.swirl = defaults.swirl;
         }
         self.swirl = self.swirl.clamp(-1.0, 1.0);
+        if !self.stroke_width.is_finite() {
+            self.stroke_width = defaults.stroke_width;
+        }
+        self.stroke_width = self.stroke_width.clamp(0.0, 64.0);
     }
 }
 
@@ -3112,11 +3998,12 @@ impl<'de> Visitor<'de> for TransitionConfigVisitor {
 
         let mut options = Vec::new();
         let mut entries = Vec::new();
-        for entry in active_entries.into_iter() {
+        for (position, entry) in active_entries.into_iter().enumerate() {
             let kind = entry.kind;
             let mut builder = TransitionOptionBuilder::default();
             for (field, value) in entry.fields {
-                apply_transition_inline_field::<A::Error>(&mut builder, kind, &field, value)?;
+                apply_transition_inline_field::<A::Error>(&mut builder, kind, &field, value)
+                    .map_err(|err| at_active_field(position, &field, err))?;
             }
             let canonical = builder
                 .into_canonical_options(kind)
@@ -3146,15 +4033,113 @@ impl<'de> Visitor<'de> for TransitionConfigVisitor {
     }
 }
 
+/// Written back in the shape it is read, with `selection` resolved and one
+/// `active` entry per canonical option, so a list of angles lists each angle.
+impl Serialize for TransitionConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let selection = match self.selection {
+            TransitionSelection::Fixed(_) => PipelineSelection::Fixed,
+            TransitionSelection::Random(_) => PipelineSelection::Random,
+            TransitionSelection::Sequential { .. } => PipelineSelection::Sequential,
+        };
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("selection", &selection)?;
+        map.serialize_entry("active", &self.options)?;
+        map.end()
+    }
+}
+
+/// An `active` entry, under the inline keys `apply_transition_inline_field`
+/// reads.
+impl Serialize for TransitionOptions {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", &self.kind)?;
+        map.serialize_entry("duration-ms", &self.duration_ms)?;
+        map.serialize_entry("weight", &self.weight)?;
+        match &self.mode {
+            TransitionMode::Fade(fade) => {
+                map.serialize_entry("through-black", &fade.through_black)?;
+            }
+            TransitionMode::Wipe(wipe) => {
+                map.serialize_entry("angles", &[wipe.angles.base_deg])?;
+                map.serialize_entry("angle-jitter", &wipe.angles.jitter_deg)?;
+                map.serialize_entry("softness", &wipe.softness)?;
+            }
+            TransitionMode::Push(push) => {
+                if push.random_direction {
+                    map.serialize_entry("direction", &PushDirection::Random)?;
+                } else {
+                    map.serialize_entry("angles", &[push.angles.base_deg])?;
+                }
+                map.serialize_entry("angle-jitter", &push.angles.jitter_deg)?;
+            }
+            TransitionMode::EInk(eink) => {
+                map.serialize_entry("flash-count", &eink.flash_count)?;
+                map.serialize_entry("reveal-portion", &eink.reveal_portion)?;
+                map.serialize_entry("stripe-count", &eink.stripe_count)?;
+                map.serialize_entry("flash-color", &eink.flash_color)?;
+            }
+            TransitionMode::Dissolve(dissolve) => {
+                map.serialize_entry("softness", &dissolve.softness)?;
+                map.serialize_entry("scale", &dissolve.scale)?;
+            }
+            TransitionMode::RadialWipe(radial) => {
+                map.serialize_entry("softness", &radial.softness)?;
+                map.serialize_entry("shapes", &[radial.shape])?;
+                map.serialize_entry("center", &radial.center)?;
+            }
+            TransitionMode::VenetianBlinds(blinds) => {
+                let orientation = if blinds.vertical {
+                    "vertical"
+                } else {
+                    "horizontal"
+                };
+                map.serialize_entry("stripe-count", &blinds.stripe_count)?;
+                map.serialize_entry("softness", &blinds.softness)?;
+                map.serialize_entry("orientations", &[orientation])?;
+            }
+            TransitionMode::CrossfadeZoom(zoom) => {
+                map.serialize_entry("zoom", &zoom.zoom)?;
+                map.serialize_entry("current-zooms-in", &zoom.current_zooms_in)?;
+                map.serialize_entry("next-zooms-in", &zoom.next_zooms_in)?;
+            }
+            TransitionMode::CrossfadeBlur(blur) => {
+                map.serialize_entry("strength", &blur.strength)?;
+            }
+            TransitionMode::Iris(iris) => {
+                map.serialize_entry("blades", &iris.blades)?;
+                map.serialize_entry("color", &iris.color)?;
+                map.serialize_entry("petal-sheen", &iris.petal_sheen)?;
+                map.serialize_entry("overlap-shadow", &iris.overlap_shadow)?;
+                map.serialize_entry("min-aperture", &iris.min_aperture)?;
+                map.serialize_entry("swirl", &iris.swirl)?;
+                map.serialize_entry("stroke-width", &iris.stroke_width)?;
+                map.serialize_entry("stroke-color", &iris.stroke_color)?;
+                map.serialize_entry("direction", &iris.direction)?;
+            }
+        }
+        map.end()
+    }
+}
+
 #[derive(Clone, Default)]
 struct TransitionOptionBuilder {
     duration_ms: Option<u64>,
+    weight: Option<f32>,
     fade_through_black: Option<bool>,
     wipe_angle_list_deg: Option<Vec<f32>>,
     wipe_angle_jitter_deg: Option<f32>,
     wipe_softness: Option<f32>,
     push_angle_list_deg: Option<Vec<f32>>,
     push_angle_jitter_deg: Option<f32>,
+    push_direction: Option<PushDirection>,
     eink_flash_count: Option<u32>,
     eink_reveal_portion: Option<f32>,
     eink_stripe_count: Option<u32>,
@@ -3172,12 +4157,16 @@ struct TransitionOptionBuilder {
     crossfade_zoom: Option<f32>,
     crossfade_current_zooms_in: Option<bool>,
     crossfade_next_zooms_in: Option<bool>,
+    crossfade_blur_strength: Option<f32>,
     iris_blades: Option<u32>,
     iris_color: Option<[u8; 3]>,
     iris_petal_sheen: Option<f32>,
     iris_overlap_shadow: Option<f32>,
     iris_min_aperture: Option<f32>,
     iris_swirl: Option<f32>,
+    iris_stroke_width: Option<f32>,
+    iris_stroke_color: Option<[u8; 3]>,
+    iris_direction: Option<IrisDirection>,
 }
 
 impl TransitionOptionBuilder {
@@ -3191,7 +4180,15 @@ impl TransitionOptionBuilder {
                 })
             }
             TransitionKind::Push => {
-                let angles = self.push_angle_list_deg.clone();
+                let mut angles = self.push_angle_list_deg.clone();
+                if let Some(direction) = self.push_direction {
+                    ensure!(
+                        angles.is_none(),
+                        "transition option {} accepts direction or angles, not both",
+                        kind
+                    );
+                    angles = Some(vec![direction.angle_deg()]);
+                }
                 let jitter = self.push_angle_jitter_deg;
                 self.into_canonical_with_angles(kind, angles, jitter, |builder, base| {
                     builder.push_angle_list_deg = Some(vec![base])
@@ -3293,6 +4290,9 @@ fn apply_transition_inline_field<E: de::Error>(
         "duration-ms" => {
             builder.duration_ms = Some(inline_value_to::<u64, E>(value)?);
         }
+        "weight" => {
+            builder.weight = Some(inline_value_to::<f32, E>(value)?);
+        }
         "through-black" if matches!(kind, TransitionKind::Fade) => {
             builder.fade_through_black = Some(inline_value_to::<bool, E>(value)?);
         }
@@ -3304,6 +4304,15 @@ fn apply_transition_inline_field<E: de::Error>(
                 _ => {}
             }
         }
+        "direction" if matches!(kind, TransitionKind::Push | TransitionKind::Iris) => match kind {
+            TransitionKind::Push => {
+                builder.push_direction = Some(inline_value_to::<PushDirection, E>(value)?)
+            }
+            TransitionKind::Iris => {
+                builder.iris_direction = Some(inline_value_to::<IrisDirection, E>(value)?)
+            }
+            _ => {}
+        },
         "angle-jitter" if matches!(kind, TransitionKind::Wipe | TransitionKind::Push) => {
             let jitter = inline_value_to::<f32, E>(value)?;
             match kind {
@@ -3383,6 +4392,9 @@ fn apply_transition_inline_field<E: de::Error>(
         "next-zooms-in" if matches!(kind, TransitionKind::CrossfadeZoom) => {
             builder.crossfade_next_zooms_in = Some(inline_value_to::<bool, E>(value)?);
         }
+        "strength" if matches!(kind, TransitionKind::CrossfadeBlur) => {
+            builder.crossfade_blur_strength = Some(inline_value_to::<f32, E>(value)?);
+        }
         "blades" if matches!(kind, TransitionKind::Iris) => {
             builder.iris_blades = Some(inline_value_to::<u32, E>(value)?);
         }
@@ -3401,15 +4413,23 @@ fn apply_transition_inline_field<E: de::Error>(
         "swirl" if matches!(kind, TransitionKind::Iris) => {
             builder.iris_swirl = Some(inline_value_to::<f32, E>(value)?);
         }
+        "stroke-width" if matches!(kind, TransitionKind::Iris) => {
+            builder.iris_stroke_width = Some(inline_value_to::<f32, E>(value)?);
+        }
+        "stroke-color" if matches!(kind, TransitionKind::Iris) => {
+            builder.iris_stroke_color = Some(inline_value_to::<[u8; 3], E>(value)?);
+        }
         _ => {
             return Err(de::Error::unknown_field(
                 field,
                 &[
                     "duration-ms",
+                    "weight",
                     "through-black",
                     "angles",
                     "angle-selection",
                     "angle-jitter",
+                    "direction",
                     "softness",
                     "flash-count",
                     "reveal-portion",
@@ -3422,12 +4442,15 @@ fn apply_transition_inline_field<E: de::Error>(
                     "zoom",
                     "current-zooms-in",
                     "next-zooms-in",
+                    "strength",
                     "blades",
                     "color",
                     "petal-sheen",
                     "overlap-shadow",
                     "min-aperture",
                     "swirl",
+                    "stroke-width",
+                    "stroke-color",
                 ],
             ));
         }
@@ -3435,7 +4458,7 @@ fn apply_transition_inline_field<E: de::Error>(
     Ok(())
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
 pub struct Configuration {
     /// Schema version of this config file. Lets the binary detect a config written
@@ -3443,11 +4466,24 @@ pub struct Configuration {
     /// confusing per-key error. Omitted ⇒ assumed current.
     #[serde(default = "Configuration::default_config_version")]
     pub config_version: u32,
-    /// Root directory to scan recursively for images.
-    pub photo_library_path: PathBuf,
+    /// Root directories to scan recursively for images. `photo-library-path`
+    /// takes one root or a list; a photo reachable from several roots counts
+    /// once, under the first.
+    #[serde(
+        rename = "photo-library-path",
+        deserialize_with = "deserialize_library_roots"
+    )]
+    pub photo_library_paths: Vec<LibraryRoot>,
     /// Unix domain socket accepting runtime control commands.
     #[serde(default = "Configuration::default_control_socket_path")]
     pub control_socket_path: PathBuf,
+    /// User IDs allowed to send control commands. Unset (with
+    /// `control_socket_allowed_gids` also unset) accepts any local peer.
+    pub control_socket_allowed_uids: Option<Vec<u32>>,
+    /// Group IDs allowed to send control commands, alongside the allowed users.
+    pub control_socket_allowed_gids: Option<Vec<u32>>,
+    /// The control socket's JSON commands over TCP, for other hosts; off by default.
+    pub control_tcp: ControlTcpConfig,
     /// Global photo render sizing/timing controls.
     pub global_photo_settings: GlobalPhotoSettings,
     /// Transition behavior between successive photos.
@@ -3456,8 +4492,18 @@ pub struct Configuration {
     pub viewer_preload_count: usize,
     /// Maximum number of concurrent image decodes in the loader.
     pub loader_max_concurrent_decodes: usize,
+    /// Longest a single decode may run before the photo is skipped, in ms.
+    pub loader_decode_timeout_ms: u64,
     /// Optional deterministic seed for initial photo shuffle.
     pub startup_shuffle_seed: Option<u64>,
+    /// Per-library file of photo metadata that lets startup skip unchanged photos.
+    pub library_index: LibraryIndexConfig,
+    /// On-disk cache of downscaled photos; `None` decodes every photo at full size.
+    #[serde(default)]
+    pub thumbnail_cache: Option<ThumbnailCacheConfig>,
+    /// On-disk cache of decoded pixels; `None` decodes every photo each time it is shown.
+    #[serde(default)]
+    pub decode_cache: Option<DecodeCacheConfig>,
     /// Optional post-processing effects applied after loading and before display.
     pub photo_effect: PhotoEffectConfig,
     /// Matting configuration for displayed photos.
@@ -3468,9 +4514,45 @@ pub struct Configuration {
     pub greeting_screen: GreetingScreenConfig,
     /// Sleep screen shown when the frame enters sleep mode.
     pub sleep_screen: SleepScreenConfig,
+    /// Card shown instead of the slideshow while the library has no photos.
+    pub empty_library_screen: EmptyLibraryScreenConfig,
+    /// Outputs to drive; `None` keeps a single fullscreen window on the current monitor.
+    #[serde(default)]
+    pub displays: Option<DisplaysConfig>,
+    /// Rotation of the panel the frame draws on.
+    pub display: DisplayConfig,
+    /// Per-photo pan and zoom while a photo dwells; `None` keeps photos still.
+    #[serde(default)]
+    pub motion: Option<MotionConfig>,
+    /// Caption drawn over each photo from its metadata; off by default.
+    pub caption: CaptionConfig,
+    /// "Paused" label shown while the slideshow is paused; off by default.
+    pub pause_indicator: PauseIndicatorConfig,
+    /// Pipeline timings drawn over the slideshow; off by default.
+    pub debug_overlay: DebugOverlayConfig,
     /// Optional wake/sleep schedule used when a control daemon is absent.
     #[serde(default)]
     pub awake_schedule: Option<AwakeScheduleConfig>,
+    /// Remote-control web page and HTTP API; off by default.
+    pub web_control: WebControlConfig,
+    /// QR code linking to the photo on screen; off by default.
+    pub share_qr: ShareQrConfig,
+    /// Read-only `/healthz` and `/status` endpoints for dashboards; off by default.
+    pub health_server: HealthServerConfig,
+    /// State publishing and commands over MQTT for home automation; off by default.
+    pub mqtt: MqttConfig,
+    /// Recently displayed photos, kept for the `history` control command.
+    pub history: HistoryConfig,
+    /// Playback of animated GIF, APNG, and WebP files.
+    pub animations: AnimationsConfig,
+    /// Conversion of photos with embedded ICC profiles into one color space.
+    pub color: ColorConfig,
+    /// What the dimmed `ambient` state shows.
+    pub ambient_screen: AmbientScreenConfig,
+    /// Redraw rate cap and swap-chain presentation mode for the viewer.
+    pub frame_pacing: FramePacingConfig,
+    /// Log format, level, and per-module levels; read again at startup only.
+    pub logging: LoggingConfig,
     /// Placeholder for the hardware button daemon's config block so that
     /// photoframe can coexist with a shared config file without
     /// accepting other unknown keys.
@@ -3492,13 +4574,50 @@ impl Configuration {
     /// config-schema change; document the migration when you do.
     pub const SUPPORTED_CONFIG_VERSION: u32 = 1;
 
+    /// A config file spelling out every key at its default, with comments;
+    /// printed by `--print-default-config` as a reference to start from.
+    pub const DEFAULT_YAML: &str = include_str!("config/defaults.yaml");
+
     const fn default_config_version() -> u32 {
         Self::SUPPORTED_CONFIG_VERSION
     }
 
+    /// Reads the config file at `path`. Unlike [`Self::from_yaml_str`], the
+    /// files listed under `include:` are merged over it first, and `~` and
+    /// `${VAR}` in the library, control-socket, and fixed-image paths are
+    /// expanded; a variable that is not set is an error.
     pub fn from_yaml_file(path: impl AsRef<Path>) -> Result<Self> {
-        let s = std::fs::read_to_string(path)?;
-        Ok(serde_yaml::from_str(&s)?)
+        let mut config: Self = config_model::read_yaml_file(path.as_ref())?;
+        config.expand_paths()?;
+        Ok(config)
+    }
+
+    /// Parses a YAML document. A misspelled key or kind is reported with its
+    /// full key path and, when one is close enough, the name it was probably
+    /// meant to be.
+    pub fn from_yaml_str(yaml: &str) -> Result<Self> {
+        config_model::from_yaml_str(yaml)
+    }
+
+    /// The directory of each `photo-library-path` entry, in order.
+    pub fn library_paths(&self) -> Vec<PathBuf> {
+        self.photo_library_paths
+            .iter()
+            .map(|root| root.path.clone())
+            .collect()
+    }
+
+    fn expand_paths(&mut self) -> Result<()> {
+        for root in &mut self.photo_library_paths {
+            root.path = expand_path(&root.path).context("photo-library-path")?;
+        }
+        self.control_socket_path =
+            expand_path(&self.control_socket_path).context("control-socket-path")?;
+        self.matting.expand_paths()?;
+        if let Some(path) = &mut self.showcase.fixed_image_path {
+            *path = expand_path(path).context("showcase.fixed-image-path")?;
+        }
+        Ok(())
     }
 
     /// Replace transition/matting with auto-enumerated sequential lists when `showcase.enabled`.
@@ -3566,6 +4685,10 @@ impl Configuration {
             self.loader_max_concurrent_decodes > 0,
             "loader-max-concurrent-decodes must be greater than zero"
         );
+        ensure!(
+            self.loader_decode_timeout_ms > 0,
+            "loader-decode-timeout-ms must be greater than zero"
+        );
         ensure!(
             self.global_photo_settings.oversample > 0.0,
             "oversample must be positive"
@@ -3574,6 +4697,21 @@ impl Configuration {
             self.global_photo_settings.dwell_ms > 0,
             "dwell-ms must be greater than zero"
         );
+        for root in &self.photo_library_paths {
+            root.validate()?;
+        }
+        // A directory listed twice keeps its first entry and settings.
+        let mut seen = HashSet::new();
+        self.photo_library_paths.retain(|root| {
+            let fresh = seen.insert(root.path.clone());
+            if !fresh {
+                tracing::warn!(
+                    root = %root.path.display(),
+                    "photo-library-path listed more than once; using its first entry"
+                );
+            }
+            fresh
+        });
         ensure!(
             !self.control_socket_path.as_os_str().is_empty(),
             "control-socket-path must not be empty"
@@ -3582,6 +4720,35 @@ impl Configuration {
             self.control_socket_path.file_name().is_some(),
             "control-socket-path must include a socket file name"
         );
+        ensure!(
+            self.control_socket_allowed_uids
+                .as_ref()
+                .is_none_or(|uids| !uids.is_empty()),
+            "control-socket-allowed-uids must list at least one uid; omit it to allow any user"
+        );
+        ensure!(
+            self.control_socket_allowed_gids
+                .as_ref()
+                .is_none_or(|gids| !gids.is_empty()),
+            "control-socket-allowed-gids must list at least one gid; omit it to allow any group"
+        );
+        for (taken, address, by) in [
+            (
+                self.web_control.enabled,
+                self.web_control.bind_address,
+                "web-control",
+            ),
+            (
+                self.health_server.enabled,
+                self.health_server.bind_address,
+                "health-server",
+            ),
+        ] {
+            ensure!(
+                !(self.control_tcp.enabled && taken && self.control_tcp.bind_address == address),
+                "control-tcp.bind-address {address} is already taken by {by}"
+            );
+        }
         self.apply_showcase_overrides();
         self.transition
             .validate()
@@ -3597,38 +4764,195 @@ impl Configuration {
                 .context("invalid matting fill-when-fits configuration")?;
         }
         self.playlist.validate()?;
+        if let Some(cache) = &self.thumbnail_cache {
+            cache
+                .validate()
+                .context("invalid thumbnail cache configuration")?;
+        }
+        if let Some(cache) = &self.decode_cache {
+            cache
+                .validate()
+                .context("invalid decode cache configuration")?;
+        }
+        if let Some(displays) = &self.displays {
+            displays
+                .validate()
+                .context("invalid displays configuration")?;
+        }
+        if let Some(motion) = &self.motion {
+            motion.validate().context("invalid motion configuration")?;
+        }
+        self.caption
+            .validate()
+            .context("invalid caption configuration")?;
         self.greeting_screen
             .validate()
             .context("invalid greeting screen configuration")?;
         self.sleep_screen
             .validate()
             .context("invalid sleep screen configuration")?;
+        self.empty_library_screen
+            .validate()
+            .context("invalid empty library screen configuration")?;
         if let Some(schedule) = self.awake_schedule.as_mut() {
             schedule
                 .validate()
                 .context("invalid awake schedule configuration")?;
         }
-        Ok(self)
-    }
-}
-
-impl Default for Configuration {
-    fn default() -> Self {
-        Self {
-            config_version: Self::SUPPORTED_CONFIG_VERSION,
-            photo_library_path: PathBuf::new(),
-            control_socket_path: Self::default_control_socket_path(),
-            global_photo_settings: GlobalPhotoSettings::default(),
+        self.web_control
+            .validate()
+            .context("invalid web control configuration")?;
+        self.share_qr
+            .validate()
+            .context("invalid share QR configuration")?;
+        ensure!(
+            !self.share_qr.enabled || self.web_control.enabled,
+            "share-qr needs web-control.enabled to serve the photos it links to"
+        );
+        ensure!(
+            !self.share_qr.enabled
+                || (self.web_control.allow_lan
+                    && !self.web_control.bind_address.ip().is_loopback()),
+            "share-qr links phones to web-control, which listens only on {}; set web-control.allow-lan: true and a bind-address phones can reach, such as 0.0.0.0:8080",
+            self.web_control.bind_address
+        );
+        ensure!(
+            !(self.health_server.enabled
+                && self.web_control.enabled
+                && self.health_server.bind_address == self.web_control.bind_address),
+            "health-server.bind-address {} is already taken by web-control",
+            self.health_server.bind_address
+        );
+        self.mqtt.validate().context("invalid mqtt configuration")?;
+        self.history
+            .validate()
+            .context("invalid history configuration")?;
+        self.animations
+            .validate()
+            .context("invalid animations configuration")?;
+        self.ambient_screen
+            .validate()
+            .context("invalid ambient screen configuration")?;
+        self.frame_pacing
+            .validate()
+            .context("invalid frame pacing configuration")?;
+        self.logging
+            .validate()
+            .context("invalid logging configuration")?;
+        Ok(self)
+    }
+
+    /// Merges a freshly validated `next` for a live reload. Global photo
+    /// settings (dwell, oversample, upscale), transitions, matting, the
+    /// greeting, sleep, and empty-library messages, the sleep clock, and the
+    /// ambient screen are taken from `next`; everything else keeps its
+    /// running value. Returns the
+    /// merged configuration and the keys that changed but need a restart.
+    pub fn reloaded_from(&self, next: &Self) -> (Self, Vec<&'static str>) {
+        let mut merged = self.clone();
+        merged.global_photo_settings = next.global_photo_settings.clone();
+        merged.transition = next.transition.clone();
+        merged.matting = next.matting.clone();
+        merged.greeting_screen.screen.message = next.greeting_screen.screen.message.clone();
+        merged.sleep_screen.screen.message = next.sleep_screen.screen.message.clone();
+        merged.sleep_screen.show_clock = next.sleep_screen.show_clock;
+        merged.sleep_screen.clock_format = next.sleep_screen.clock_format.clone();
+        merged.empty_library_screen.screen.message =
+            next.empty_library_screen.screen.message.clone();
+        merged.ambient_screen = next.ambient_screen.clone();
+
+        // Most sections have no `PartialEq`; their `Debug` output covers every field.
+        let mut ignored = Vec::new();
+        macro_rules! restart_only {
+            ($($field:ident => $key:literal),* $(,)?) => {
+                $(
+                    if format!("{:?}", merged.$field) != format!("{:?}", next.$field) {
+                        ignored.push($key);
+                    }
+                )*
+            };
+        }
+        restart_only! {
+            photo_library_paths => "photo-library-path",
+            control_socket_path => "control-socket-path",
+            control_socket_allowed_uids => "control-socket-allowed-uids",
+            control_socket_allowed_gids => "control-socket-allowed-gids",
+            control_tcp => "control-tcp",
+            viewer_preload_count => "viewer-preload-count",
+            loader_max_concurrent_decodes => "loader-max-concurrent-decodes",
+            loader_decode_timeout_ms => "loader-decode-timeout-ms",
+            startup_shuffle_seed => "startup-shuffle-seed",
+            library_index => "library-index",
+            thumbnail_cache => "thumbnail-cache",
+            decode_cache => "decode-cache",
+            photo_effect => "photo-effect",
+            playlist => "playlist",
+            greeting_screen => "greeting-screen",
+            sleep_screen => "sleep-screen",
+            empty_library_screen => "empty-library-screen",
+            displays => "displays",
+            display => "display",
+            motion => "motion",
+            caption => "caption",
+            pause_indicator => "pause-indicator",
+            debug_overlay => "debug-overlay",
+            awake_schedule => "awake-schedule",
+            web_control => "web-control",
+            share_qr => "share-qr",
+            health_server => "health-server",
+            mqtt => "mqtt",
+            history => "history",
+            animations => "animations",
+            color => "color",
+            showcase => "showcase",
+            frame_pacing => "frame-pacing",
+            logging => "logging",
+        }
+        (merged, ignored)
+    }
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Self {
+            config_version: Self::SUPPORTED_CONFIG_VERSION,
+            photo_library_paths: Vec::new(),
+            control_socket_path: Self::default_control_socket_path(),
+            control_socket_allowed_uids: None,
+            control_socket_allowed_gids: None,
+            control_tcp: ControlTcpConfig::default(),
+            global_photo_settings: GlobalPhotoSettings::default(),
             transition: TransitionConfig::default(),
             viewer_preload_count: 3,
             loader_max_concurrent_decodes: 4,
+            loader_decode_timeout_ms: 30_000,
             startup_shuffle_seed: None,
+            library_index: LibraryIndexConfig::default(),
+            thumbnail_cache: None,
+            decode_cache: None,
             photo_effect: PhotoEffectConfig::default(),
             matting: MattingConfig::default(),
             playlist: PlaylistOptions::default(),
             greeting_screen: GreetingScreenConfig::default(),
             sleep_screen: SleepScreenConfig::default(),
+            empty_library_screen: EmptyLibraryScreenConfig::default(),
+            displays: None,
+            display: DisplayConfig::default(),
+            motion: None,
+            caption: CaptionConfig::default(),
+            pause_indicator: PauseIndicatorConfig::default(),
+            debug_overlay: DebugOverlayConfig::default(),
             awake_schedule: None,
+            web_control: WebControlConfig::default(),
+            share_qr: ShareQrConfig::default(),
+            health_server: HealthServerConfig::default(),
+            mqtt: MqttConfig::default(),
+            history: HistoryConfig::default(),
+            animations: AnimationsConfig::default(),
+            color: ColorConfig::default(),
+            ambient_screen: AmbientScreenConfig::default(),
+            frame_pacing: FramePacingConfig::default(),
+            logging: LoggingConfig::default(),
             buttond: None,
             showcase: ShowcaseConfig::default(),
         }
@@ -3641,7 +4965,7 @@ impl Configuration {
     }
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", default)]
 pub struct PlaylistOptions {
     /// Initial multiplicity for brand new photos.
@@ -3649,6 +4973,19 @@ pub struct PlaylistOptions {
     /// Half-life duration controlling the exponential decay of multiplicity.
     #[serde(with = "humantime_serde")]
     pub half_life: Duration,
+    /// Where the manager persists its schedule so restarts resume the rotation.
+    pub state_file: Option<PathBuf>,
+    /// Shows recent photos newest-first right after startup.
+    pub new_photo_boost: Option<NewPhotoBoost>,
+    /// Shows the photos listed in a sidecar file more often.
+    pub favorites: Option<PlaylistFavorites>,
+    /// Keeps photos that keep failing to decode out of the rotation for a while.
+    pub quarantine: QuarantineConfig,
+    /// Other photos shown before one comes around again, while the library
+    /// has that many; the default only rules out back-to-back repeats.
+    pub no_repeat_window: usize,
+    /// What to do about the same photo saved more than once.
+    pub dedup: DedupConfig,
 }
 
 impl PlaylistOptions {
@@ -3680,6 +5017,24 @@ impl PlaylistOptions {
             self.half_life > Duration::from_secs(0),
             "playlist.half-life must be positive"
         );
+        if let Some(path) = &self.state_file {
+            ensure!(
+                !path.as_os_str().is_empty(),
+                "playlist.state-file must not be empty when provided"
+            );
+        }
+        if let Some(boost) = &self.new_photo_boost {
+            boost.validate()?;
+        }
+        if let Some(favorites) = &self.favorites {
+            favorites.validate()?;
+        }
+        self.quarantine.validate()?;
+        ensure!(
+            self.no_repeat_window >= 1,
+            "playlist.no-repeat-window must be at least 1; back-to-back repeats are always avoided"
+        );
+        self.dedup.validate()?;
         Ok(())
     }
 }
@@ -3689,6 +5044,966 @@ impl Default for PlaylistOptions {
         Self {
             new_multiplicity: Self::default_new_multiplicity(),
             half_life: Self::default_half_life(),
+            state_file: None,
+            new_photo_boost: None,
+            favorites: None,
+            quarantine: QuarantineConfig::default(),
+            no_repeat_window: 1,
+            dedup: DedupConfig::default(),
+        }
+    }
+}
+
+/// When a photo that keeps failing to decode is set aside, and for how long.
+/// A failure counts against the file's size and modification time, so a
+/// file that changes on disk gets a fresh start.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct QuarantineConfig {
+    /// Failed decodes before the photo is skipped.
+    pub max_failures: u32,
+    /// How long a quarantined photo is skipped, even when it is re-added.
+    #[serde(with = "humantime_serde")]
+    pub cooldown: Duration,
+}
+
+impl QuarantineConfig {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.max_failures >= 1,
+            "playlist.quarantine.max-failures must be >= 1"
+        );
+        ensure!(
+            self.cooldown > Duration::ZERO,
+            "playlist.quarantine.cooldown must be positive"
+        );
+        Ok(())
+    }
+}
+
+impl Default for QuarantineConfig {
+    fn default() -> Self {
+        Self {
+            max_failures: 3,
+            cooldown: Duration::from_secs(60 * 60 * 24),
+        }
+    }
+}
+
+/// Copies of one photo, say exported at two sizes into two albums, found by
+/// a perceptual hash the files task works out in the background.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct DedupConfig {
+    /// Whether copies are left alone, collapsed to one, or spread apart.
+    pub mode: DedupMode,
+    /// With `mode: spread`, displays that must pass between two copies.
+    pub window: usize,
+}
+
+impl DedupConfig {
+    /// Most displays `window` can span; the playlist remembers this many.
+    pub const MAX_WINDOW: usize = 64;
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            (1..=Self::MAX_WINDOW).contains(&self.window),
+            "playlist.dedup.window must be between 1 and {}",
+            Self::MAX_WINDOW
+        );
+        Ok(())
+    }
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        Self {
+            mode: DedupMode::Off,
+            window: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DedupMode {
+    /// Every copy is shown like any other photo; nothing is hashed.
+    #[default]
+    Off,
+    /// Only the copy with the most pixels is shown.
+    Collapse,
+    /// Every copy is shown, but never within `window` displays of another.
+    Spread,
+}
+
+/// Puts recently created photos at the front of the rotation after startup.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct NewPhotoBoost {
+    /// How many of the first displays after startup may be boosted.
+    pub displays: u32,
+    /// Photos created within this long before now count as recent.
+    #[serde(with = "humantime_serde")]
+    pub max_age: Duration,
+}
+
+impl NewPhotoBoost {
+    /// Whether a photo created at `created_at` is recent enough to be boosted.
+    pub fn is_recent(&self, created_at: SystemTime, now: SystemTime) -> bool {
+        now.duration_since(created_at).unwrap_or_default() <= self.max_age
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.displays >= 1,
+            "playlist.new-photo-boost.displays must be >= 1"
+        );
+        ensure!(
+            self.max_age > Duration::ZERO,
+            "playlist.new-photo-boost.max-age must be positive"
+        );
+        Ok(())
+    }
+}
+
+/// Multiplies the scheduling weight of the photos listed in `file`.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PlaylistFavorites {
+    /// Text file with one photo path per line, absolute or relative to the
+    /// library. Blank lines and lines starting with `#` are ignored.
+    pub file: PathBuf,
+    /// Weight multiplier applied to listed photos.
+    #[serde(default = "PlaylistFavorites::default_factor")]
+    pub factor: f64,
+}
+
+impl PlaylistFavorites {
+    const fn default_factor() -> f64 {
+        3.0
+    }
+
+    /// Reads the listed paths. A missing or unreadable file lists nothing, so
+    /// the file can be created, emptied, or deleted while the frame runs.
+    pub fn load(&self) -> Vec<PathBuf> {
+        match std::fs::read_to_string(&self.file) {
+            Ok(text) => text
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(PathBuf::from)
+                .collect(),
+            Err(err) => {
+                if err.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!(
+                        path = %self.file.display(),
+                        error = %err,
+                        "playlist_favorites_unreadable"
+                    );
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    /// Whether `path` is one of `favorites`. A listed path matches the photo it
+    /// names or any photo whose path ends with it, so library-relative entries work.
+    pub fn contains(favorites: &[PathBuf], path: &Path) -> bool {
+        favorites.iter().any(|favorite| path.ends_with(favorite))
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.file.as_os_str().is_empty(),
+            "playlist.favorites.file must not be empty"
+        );
+        ensure!(
+            self.factor.is_finite() && self.factor >= 1.0,
+            "playlist.favorites.factor must be a finite number >= 1"
+        );
+        Ok(())
+    }
+}
+
+/// How the viewer spreads the slideshow across the listed outputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DisplayMode {
+    /// Every output shows the same frame, drawn once and presented per monitor.
+    #[default]
+    Mirror,
+    /// Each output runs its own slideshow, fed in turn from the photo stream.
+    Independent,
+}
+
+/// Which monitor outputs the slideshow runs on, and how it is shared among them.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct DisplaysConfig {
+    /// Output names as the compositor reports them (e.g. `HDMI-A-1`), in order.
+    /// The first connected one is the primary output.
+    pub outputs: Vec<String>,
+    pub mode: DisplayMode,
+}
+
+impl DisplaysConfig {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.outputs.is_empty(),
+            "displays.outputs must list at least one output"
+        );
+        for (idx, name) in self.outputs.iter().enumerate() {
+            ensure!(
+                !name.trim().is_empty(),
+                "displays.outputs[{idx}] must not be empty"
+            );
+            ensure!(
+                !self.outputs[..idx].contains(name),
+                "displays.outputs lists {name} more than once"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Clockwise turn applied to everything drawn, for a panel mounted on its side
+/// or upside down; the same sense as the compositor's output transform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(try_from = "u16")]
+pub enum DisplayRotation {
+    #[default]
+    None,
+    Clockwise90,
+    Clockwise180,
+    Clockwise270,
+}
+
+impl TryFrom<u16> for DisplayRotation {
+    type Error = String;
+
+    fn try_from(degrees: u16) -> Result<Self, Self::Error> {
+        match degrees {
+            0 => Ok(Self::None),
+            90 => Ok(Self::Clockwise90),
+            180 => Ok(Self::Clockwise180),
+            270 => Ok(Self::Clockwise270),
+            other => Err(format!(
+                "display.rotation must be 0, 90, 180, or 270 (got {other})"
+            )),
+        }
+    }
+}
+
+impl Serialize for DisplayRotation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u16(self.degrees())
+    }
+}
+
+impl DisplayRotation {
+    pub fn degrees(self) -> u16 {
+        match self {
+            Self::None => 0,
+            Self::Clockwise90 => 90,
+            Self::Clockwise180 => 180,
+            Self::Clockwise270 => 270,
+        }
+    }
+
+    /// Whether the picture's width runs along the panel's height.
+    pub fn swaps_axes(self) -> bool {
+        matches!(self, Self::Clockwise90 | Self::Clockwise270)
+    }
+
+    /// The upright size the frame lays out for, given the panel's size.
+    pub fn logical_size(self, width: u32, height: u32) -> (u32, u32) {
+        if self.swaps_axes() {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Affine map from a texture coordinate on the panel to the coordinate in
+    /// the upright picture that lands there: each row holds the `u` and `v`
+    /// weights and a constant, padded to four floats for a uniform buffer.
+    pub fn uv_transform(self) -> [[f32; 4]; 2] {
+        match self {
+            Self::None => [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0]],
+            Self::Clockwise90 => [[0.0, 1.0, 0.0, 0.0], [-1.0, 0.0, 1.0, 0.0]],
+            Self::Clockwise180 => [[-1.0, 0.0, 1.0, 0.0], [0.0, -1.0, 1.0, 0.0]],
+            Self::Clockwise270 => [[0.0, -1.0, 1.0, 0.0], [1.0, 0.0, 0.0, 0.0]],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    /// Turn the whole output by 0, 90, 180, or 270 degrees clockwise.
+    pub rotation: DisplayRotation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ThumbnailCacheConfig {
+    /// Directory holding the cached downscales; created on first use.
+    pub directory: PathBuf,
+    /// Longest edge, in pixels, of a cached photo. Larger photos are downscaled
+    /// to fit and cached; smaller ones are decoded directly every time.
+    #[serde(default = "ThumbnailCacheConfig::default_max_dimension_px")]
+    pub max_dimension_px: u32,
+}
+
+impl ThumbnailCacheConfig {
+    const MIN_DIMENSION_PX: u32 = 256;
+
+    const fn default_max_dimension_px() -> u32 {
+        3840
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.directory.as_os_str().is_empty(),
+            "thumbnail-cache.directory must not be empty"
+        );
+        ensure!(
+            self.max_dimension_px >= Self::MIN_DIMENSION_PX,
+            "thumbnail-cache.max-dimension-px must be at least {}",
+            Self::MIN_DIMENSION_PX
+        );
+        Ok(())
+    }
+}
+
+/// Corner of the screen the photo caption sits in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CaptionCorner {
+    TopLeft,
+    TopRight,
+    #[default]
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct CaptionConfig {
+    /// Draw a caption over each photo.
+    pub enabled: bool,
+    /// Caption text; `{date}`, `{description}`, `{filename}`, and
+    /// `{location}` are filled from the photo, and an empty one drops out
+    /// with its separator.
+    pub template: String,
+    pub corner: CaptionCorner,
+    /// Font family; unset or missing falls back like the greeting screen.
+    pub font: Option<String>,
+    /// Text height in pixels.
+    pub size: f32,
+}
+
+impl Default for CaptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            template: "{date} — {description}".to_string(),
+            corner: CaptionCorner::default(),
+            font: None,
+            size: 28.0,
+        }
+    }
+}
+
+impl CaptionConfig {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.size.is_finite() && (8.0..=200.0).contains(&self.size),
+            "caption.size must be between 8 and 200 pixels"
+        );
+        crate::tasks::loader::caption::check_template(&self.template)
+    }
+
+    /// The template for the loader to fill, or `None` when captions are off.
+    pub fn active_template(&self) -> Option<&str> {
+        self.enabled.then_some(self.template.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct PauseIndicatorConfig {
+    /// Show the label while paused.
+    pub enabled: bool,
+    pub corner: CaptionCorner,
+}
+
+impl Default for PauseIndicatorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            corner: CaptionCorner::TopRight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct DebugOverlayConfig {
+    /// Show the timings from startup; the `debug-overlay` command toggles them
+    /// either way.
+    pub enabled: bool,
+    pub corner: CaptionCorner,
+}
+
+impl Default for DebugOverlayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            corner: CaptionCorner::BottomRight,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct WebControlConfig {
+    /// Serve the remote-control page and API.
+    pub enabled: bool,
+    /// Address and port the server listens on.
+    pub bind_address: SocketAddr,
+    /// Permit a `bind-address` other machines can reach. The remote has no
+    /// authentication, so anyone on the network can put the frame to sleep.
+    pub allow_lan: bool,
+}
+
+impl Default for WebControlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: SocketAddr::from(([127, 0, 0, 1], 8080)),
+            allow_lan: false,
+        }
+    }
+}
+
+impl WebControlConfig {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.enabled || self.allow_lan || self.bind_address.ip().is_loopback(),
+            "web-control.bind-address {} is reachable from other machines; set web-control.allow-lan: true to serve the LAN",
+            self.bind_address
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct ControlTcpConfig {
+    /// Listen for control commands over TCP.
+    pub enabled: bool,
+    /// Address and port the listener binds.
+    pub bind_address: SocketAddr,
+    /// Remote addresses allowed to send commands. Loopback peers are always
+    /// allowed; everyone else is refused unless listed.
+    pub allowed_peers: Vec<IpAddr>,
+}
+
+impl Default for ControlTcpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: SocketAddr::from(([127, 0, 0, 1], 8082)),
+            allowed_peers: Vec::new(),
+        }
+    }
+}
+
+impl ControlTcpConfig {
+    /// Whether a connection from `peer` may send commands.
+    pub fn permits(&self, peer: IpAddr) -> bool {
+        let peer = peer.to_canonical();
+        peer.is_loopback()
+            || self
+                .allowed_peers
+                .iter()
+                .any(|ip| ip.to_canonical() == peer)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct HealthServerConfig {
+    /// Serve `/healthz` and `/status`.
+    pub enabled: bool,
+    /// Address and port the server listens on.
+    pub bind_address: SocketAddr,
+}
+
+impl Default for HealthServerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: SocketAddr::from(([127, 0, 0, 1], 8081)),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct MqttConfig {
+    /// Connect to the broker, publish state, and take commands.
+    pub enabled: bool,
+    /// Broker address as `mqtt://host[:port]`; the port defaults to 1883.
+    pub broker: String,
+    pub client_id: String,
+    pub username: Option<String>,
+    #[serde(serialize_with = "MqttConfig::serialize_password")]
+    pub password: Option<String>,
+    /// Topic read for commands, in the control socket's JSON.
+    pub command_topic: String,
+    /// Topic the frame's state is published to, retained.
+    pub state_topic: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            broker: "mqtt://localhost:1883".into(),
+            client_id: "photoframe".into(),
+            username: None,
+            password: None,
+            command_topic: "photoframe/command".into(),
+            state_topic: "photoframe/state".into(),
+        }
+    }
+}
+
+// Written out so the startup log of the loaded config never shows the password.
+impl fmt::Debug for MqttConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MqttConfig")
+            .field("enabled", &self.enabled)
+            .field("broker", &self.broker)
+            .field("client_id", &self.client_id)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "<redacted>"))
+            .field("command_topic", &self.command_topic)
+            .field("state_topic", &self.state_topic)
+            .finish()
+    }
+}
+
+impl MqttConfig {
+    /// `--check-config` shows whether a password is set, never the password.
+    fn serialize_password<S>(password: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        password
+            .as_ref()
+            .map(|_| "<redacted>")
+            .serialize(serializer)
+    }
+
+    /// Host and port from `broker`.
+    pub fn broker_address(&self) -> Result<(String, u16)> {
+        let authority = self
+            .broker
+            .strip_prefix("mqtt://")
+            .with_context(|| format!("mqtt.broker {:?} must start with mqtt://", self.broker))?
+            .trim_end_matches('/');
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port
+                    .parse()
+                    .with_context(|| format!("mqtt.broker port {port:?} is not a number"))?;
+                (host, port)
+            }
+            None => (authority, 1883),
+        };
+        ensure!(!host.is_empty(), "mqtt.broker needs a host name");
+        Ok((host.to_owned(), port))
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        self.broker_address()?;
+        ensure!(
+            !self.client_id.is_empty(),
+            "mqtt.client-id must not be empty"
+        );
+        ensure!(
+            self.username.is_some() || self.password.is_none(),
+            "mqtt.password needs mqtt.username"
+        );
+        for (key, topic) in [
+            ("command-topic", &self.command_topic),
+            ("state-topic", &self.state_topic),
+        ] {
+            ensure!(
+                !topic.is_empty() && !topic.contains(['+', '#']),
+                "mqtt.{key} must be a topic name without wildcards"
+            );
+        }
+        ensure!(
+            self.command_topic != self.state_topic,
+            "mqtt.command-topic and mqtt.state-topic must differ"
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct ShareQrConfig {
+    /// Draw a QR code linking to the photo on screen.
+    pub enabled: bool,
+    /// Address phones reach the web control at, such as
+    /// `http://frame.local:8080`; the link is `<base-url>/photo/<id>`.
+    pub base_url: String,
+    pub corner: CaptionCorner,
+    /// Width of the code on screen, in pixels.
+    pub size: u32,
+}
+
+impl Default for ShareQrConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            base_url: String::new(),
+            corner: CaptionCorner::BottomRight,
+            size: 160,
+        }
+    }
+}
+
+impl ShareQrConfig {
+    fn validate(&self) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+        ensure!(
+            self.base_url.starts_with("http://") || self.base_url.starts_with("https://"),
+            "share-qr.base-url must be an http:// or https:// address"
+        );
+        ensure!(
+            (64..=1024).contains(&self.size),
+            "share-qr.size must be between 64 and 1024 pixels"
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// JSONL log of displayed photos; `None` keeps the history in memory only.
+    pub file: Option<PathBuf>,
+    /// Entries kept in memory, and lines the log holds before it is rotated.
+    pub max_entries: usize,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            max_entries: 100,
+        }
+    }
+}
+
+impl HistoryConfig {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.max_entries > 0,
+            "history.max-entries must be greater than zero"
+        );
+        ensure!(
+            self.file
+                .as_ref()
+                .is_none_or(|file| file.file_name().is_some()),
+            "history.file must name a file"
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct AnimationsConfig {
+    /// Plays animated files; when off, only their first frame is shown.
+    pub enabled: bool,
+    /// Frames kept per animation; later frames are dropped and the
+    /// animation loops over the ones kept.
+    pub max_frames: usize,
+    /// Times an animation plays before the slideshow moves on, cutting the
+    /// dwell short. `None` loops it for the whole dwell.
+    pub loop_count: Option<u32>,
+    /// Decoded size, in MiB, one animation's frames may take up.
+    pub max_decoded_mb: u64,
+}
+
+impl Default for AnimationsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_frames: 500,
+            loop_count: None,
+            max_decoded_mb: 256,
+        }
+    }
+}
+
+impl AnimationsConfig {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.max_frames >= 2,
+            "animations.max-frames must be at least 2"
+        );
+        ensure!(
+            self.loop_count != Some(0),
+            "animations.loop-count must be at least 1; leave it unset to loop for the whole dwell"
+        );
+        ensure!(
+            (1..=512).contains(&self.max_decoded_mb),
+            "animations.max-decoded-mb must be between 1 and 512"
+        );
+        Ok(())
+    }
+
+    /// Cap on one animation's decoded frames, in bytes.
+    pub fn max_decoded_bytes(&self) -> u64 {
+        self.max_decoded_mb * 1024 * 1024
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct AmbientScreenConfig {
+    /// Brightness of the ambient picture, from just above black to 1.0.
+    pub brightness: f32,
+    /// Photo to show, full-bleed; `None` shows the sleep-screen card instead.
+    pub photo: Option<PathBuf>,
+}
+
+impl Default for AmbientScreenConfig {
+    fn default() -> Self {
+        Self {
+            brightness: 0.2,
+            photo: None,
+        }
+    }
+}
+
+impl AmbientScreenConfig {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.brightness > 0.0 && self.brightness <= 1.0,
+            "ambient-screen.brightness must be above 0.0 and at most 1.0"
+        );
+        ensure!(
+            self.photo
+                .as_ref()
+                .is_none_or(|photo| !photo.as_os_str().is_empty()),
+            "ambient-screen.photo must not be empty; omit it to show the sleep-screen card"
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct LibraryIndexConfig {
+    /// Keeps a `.photoframe-index` file in each library root with every
+    /// photo's size, modification time, capture date, and orientation. The
+    /// startup scan reads metadata only for photos not in it or changed since.
+    pub enabled: bool,
+}
+
+/// Color space the loader converts photos into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorTargetSpace {
+    #[default]
+    Srgb,
+    /// For wide-gamut panels driven without a compositor-side conversion.
+    DisplayP3,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct ColorConfig {
+    /// Converts photos whose embedded profile differs from `target-space`;
+    /// when off, pixel values are shown as stored.
+    pub enabled: bool,
+    pub target_space: ColorTargetSpace,
+}
+
+impl Default for ColorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            target_space: ColorTargetSpace::default(),
+        }
+    }
+}
+
+/// How the viewer's swap chain hands finished frames to the display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PresentMode {
+    /// Mailbox when the surface supports it, otherwise vsync.
+    #[default]
+    Auto,
+    /// Wait for vsync; every frame is shown, none are torn.
+    Fifo,
+    /// Replace a queued frame with a newer one; shown at the next vsync.
+    Mailbox,
+    /// Present at once, even mid-scanout; may tear.
+    Immediate,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", default, deny_unknown_fields)]
+pub struct FramePacingConfig {
+    /// Redraws per second the viewer may ask for; `None` follows the display.
+    pub max_fps: Option<u32>,
+    pub present_mode: PresentMode,
+}
+
+impl FramePacingConfig {
+    const MAX_FPS_LIMIT: u32 = 240;
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.max_fps
+                .is_none_or(|fps| (1..=Self::MAX_FPS_LIMIT).contains(&fps)),
+            "frame-pacing.max-fps must be between 1 and {}; omit it to follow the display",
+            Self::MAX_FPS_LIMIT
+        );
+        Ok(())
+    }
+
+    /// Shortest time between two redraws; zero when uncapped.
+    pub fn min_frame_interval(&self) -> Duration {
+        self.max_fps
+            .map_or(Duration::ZERO, |fps| Duration::from_secs(1) / fps.max(1))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DecodeCacheConfig {
+    /// Directory holding the decoded photos; created on first use.
+    pub directory: PathBuf,
+    /// Total size, in MiB, the cache may grow to before the least recently
+    /// used entries are evicted.
+    #[serde(default = "DecodeCacheConfig::default_max_size_mb")]
+    pub max_size_mb: u64,
+}
+
+impl DecodeCacheConfig {
+    const MIN_SIZE_MB: u64 = 64;
+
+    const fn default_max_size_mb() -> u64 {
+        2048
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            !self.directory.as_os_str().is_empty(),
+            "decode-cache.directory must not be empty"
+        );
+        ensure!(
+            self.max_size_mb >= Self::MIN_SIZE_MB,
+            "decode-cache.max-size-mb must be at least {}",
+            Self::MIN_SIZE_MB
+        );
+        Ok(())
+    }
+}
+
+/// Slow camera motion applied to each photo while it dwells on screen.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum MotionConfig {
+    /// Pan and zoom across each full-bleed photo ("Ken Burns").
+    KenBurns {
+        /// Zoom factors the motion travels between, `[min, max]`.
+        #[serde(default = "MotionConfig::default_zoom_range", rename = "zoom-range")]
+        zoom_range: [f32; 2],
+        /// Pan travel as a fraction (0–1) of the slack the zoom leaves around the screen.
+        #[serde(
+            default = "MotionConfig::default_pan_amplitude",
+            rename = "pan-amplitude"
+        )]
+        pan_amplitude: f32,
+        /// Whether photos zoom in, out, or pick one at random.
+        #[serde(default)]
+        direction: ZoomDirection,
+    },
+}
+
+/// Which way a Ken Burns zoom travels through `zoom-range`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ZoomDirection {
+    /// From `min` to `max`.
+    In,
+    /// From `max` to `min`.
+    Out,
+    /// In or out, picked per photo.
+    #[default]
+    Random,
+}
+
+impl MotionConfig {
+    const MAX_ZOOM: f32 = 2.0;
+
+    const fn default_zoom_range() -> [f32; 2] {
+        [1.0, 1.08]
+    }
+
+    const fn default_pan_amplitude() -> f32 {
+        1.0
+    }
+
+    fn validate(&self) -> Result<()> {
+        match self {
+            Self::KenBurns {
+                zoom_range: [min, max],
+                pan_amplitude,
+                ..
+            } => {
+                ensure!(
+                    min.is_finite() && max.is_finite(),
+                    "motion.zoom-range must be finite"
+                );
+                // Below 1.0 the photo would no longer cover the screen edges.
+                ensure!(*min >= 1.0, "motion.zoom-range must not go below 1.0");
+                ensure!(
+                    min <= max,
+                    "motion.zoom-range must be [min, max] with min <= max"
+                );
+                ensure!(
+                    *max <= Self::MAX_ZOOM,
+                    "motion.zoom-range must not exceed {}",
+                    Self::MAX_ZOOM
+                );
+                ensure!(
+                    (0.0..=1.0).contains(pan_amplitude),
+                    "motion.pan-amplitude must be between 0.0 and 1.0"
+                );
+            }
         }
+        Ok(())
     }
 }
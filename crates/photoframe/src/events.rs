@@ -1,28 +1,76 @@
+use std::fmt;
 use std::path::PathBuf;
-use std::time::SystemTime;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+use crate::config::Configuration;
+use crate::tasks::dedup::Fingerprint;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ViewerState {
     Asleep,
+    #[default]
     Awake,
+    /// Panel on but dimmed, showing a still photo or the sleep-screen card;
+    /// see `ambient-screen`.
+    Ambient,
+}
+
+/// What the viewer is showing, published after every tick for the control
+/// socket's `status` command.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ViewerStatus {
+    pub state: ViewerState,
+    /// Photo on screen in the slideshow; kept while a sleep or overlay card covers it.
+    pub current_photo: Option<PathBuf>,
+    /// Photos uploaded and waiting behind the current one.
+    pub queue_depth: usize,
+    pub paused: bool,
+}
+
+pub type SharedViewerStatus = Arc<Mutex<ViewerStatus>>;
+
+/// Who asked for a wake or sleep: a person (a button, a remote, a script) or
+/// the awake schedule. Manual changes may hold off the schedule for a while;
+/// see `awake-schedule.manual-override-minutes`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateSource {
+    #[default]
+    Manual,
+    Scheduled,
 }
 
 #[derive(Debug)]
 pub enum InventoryEvent {
     PhotoAdded(PhotoInfo),
     PhotoRemoved(PathBuf),
+    /// The photo failed to decode; it leaves the rotation like a removal and
+    /// counts toward `playlist.quarantine`.
+    PhotoFailed(PathBuf),
+    /// A photo already added was fingerprinted for `playlist.dedup`.
+    PhotoFingerprinted(PathBuf, Fingerprint),
+    /// The `playlist.favorites` file changed; carries the paths it now lists.
+    FavoritesChanged(Vec<PathBuf>),
 }
 
 #[derive(Debug, Clone)]
 pub struct PhotoInfo {
     pub path: PathBuf,
     pub created_at: SystemTime,
+    /// `weight` of the library root the photo was found under.
+    pub root_weight: f64,
 }
 
 #[derive(Debug, Clone)]
 pub struct LoadPhoto {
     pub path: PathBuf,
     pub priority: bool,
+    /// When the photo was taken, as the library reported it.
+    pub created_at: SystemTime,
 }
 
 #[derive(Debug, Clone)]
@@ -33,10 +81,33 @@ pub struct PreparedImageCpu {
     pub pixels: Vec<u8>,
 }
 
+/// One frame of an animated photo: RGBA8 pixels at the photo's full size and
+/// how long the frame stays up before the next one.
+#[derive(Debug, Clone)]
+pub struct AnimationFrame {
+    pub pixels: Vec<u8>,
+    pub delay: Duration,
+}
+
 #[derive(Debug, Clone)]
 pub struct PhotoLoaded {
     pub prepared: PreparedImageCpu,
     pub priority: bool,
+    /// Every frame of an animated photo, starting with the one in `prepared`.
+    /// `None` for stills, including single-frame GIFs.
+    pub animation: Option<Vec<AnimationFrame>>,
+    /// Caption text prepared by the loader; `None` when captions are off or
+    /// there is nothing to show.
+    pub caption: Option<String>,
+    /// Mat override from the photo's sidecar file; `None` uses the configured
+    /// matting.
+    pub matting: Option<crate::config::MattingConfig>,
+    /// When the photo was taken, carried from `LoadPhoto` for age-dependent
+    /// effects.
+    pub created_at: SystemTime,
+    /// Time spent decoding the photo and applying its effect, which the viewer
+    /// adds to its matting time to size the preload queue.
+    pub prep_time: Duration,
 }
 
 #[derive(Debug)]
@@ -44,10 +115,64 @@ pub struct InvalidPhoto(pub PathBuf);
 
 /// Emitted by the viewer after a photo is shown (for now, immediately).
 #[derive(Debug)]
-pub struct Displayed(pub PathBuf);
+pub struct Displayed {
+    pub path: PathBuf,
+    /// The `displays.outputs` entry whose window showed it, or `None` when no
+    /// outputs are configured. Mirrored outputs report once, as the primary.
+    pub output: Option<String>,
+}
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+/// A configuration re-read from disk and validated, for the viewer to apply
+/// its live-reloadable parts.
+#[derive(Clone)]
+pub struct ReloadedConfig(pub Arc<Configuration>);
+
+impl fmt::Debug for ReloadedConfig {
+    // Commands are logged at debug level; the full configuration would drown them.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ReloadedConfig { .. }")
+    }
+}
+
+#[derive(Debug, Clone)]
 pub enum ViewerCommand {
-    SetState(ViewerState),
+    SetState {
+        state: ViewerState,
+        source: StateSource,
+    },
     ToggleState,
+    /// Cut the current photo's dwell short and move on to the next one.
+    Next,
+    /// Go back to the photo shown before the current one.
+    Previous,
+    /// Keep the current photo on screen, its dwell suspended, until
+    /// `Resume`. `reason` is set for a maintenance pause and shown by the
+    /// pause indicator.
+    Pause {
+        reason: Option<String>,
+    },
+    Resume,
+    /// Startup scan progress from the files task, for the greeting's `{photo-count}`
+    /// and `{state}` placeholders. `complete` is set once every discovered photo has
+    /// been handed to the manager.
+    GreetingProgress {
+        discovered: usize,
+        complete: bool,
+    },
+    /// From the files task whenever the library goes from having photos to having
+    /// none or back; while empty the viewer shows the empty-library card in place
+    /// of the slideshow.
+    LibraryEmpty(bool),
+    /// Apply the dwell, transitions, matting, and screen messages of a
+    /// reloaded configuration.
+    ReloadConfig(ReloadedConfig),
+    /// A short banner over the slideshow, such as buttond's warning before a
+    /// scheduled sleep. It replaces any banner already up and goes away after
+    /// `duration`; the slideshow carries on underneath.
+    Notice {
+        text: String,
+        duration: Duration,
+    },
+    /// Show or hide the pipeline timings in a corner of the slideshow.
+    DebugOverlay(bool),
 }
@@ -0,0 +1,279 @@
+//! Separable Gaussian blur as two compute passes, for blur mats with
+//! `backend: gpu`. The viewer registers its device once it is up; matting
+//! workers then borrow it from their own threads. Without a registered
+//! device, or on an adapter without compute shaders, [`blur`] returns `None`
+//! and the caller falls back to the CPU blur.
+
+use std::sync::{Arc, Mutex, PoisonError, mpsc};
+
+use bytemuck::{Pod, Zeroable};
+use image::RgbaImage;
+use tracing::{debug, warn};
+
+use crate::processing::blur::gaussian_kernel;
+
+const WORKGROUP_SIZE: u32 = 8;
+
+static SHARED: Mutex<Option<Arc<GpuBlur>>> = Mutex::new(None);
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct BlurParams {
+    width: u32,
+    height: u32,
+    radius: u32,
+    horizontal: u32,
+}
+
+struct GpuBlur {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::ComputePipeline,
+}
+
+/// Whether an adapter with `caps` can run the blur's compute passes.
+pub fn compute_supported(caps: &wgpu::DownlevelCapabilities) -> bool {
+    caps.flags.contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+}
+
+/// Makes `device` available to [`blur`], replacing any earlier one. Returns
+/// `false`, leaving the CPU fallback in place, when the adapter cannot run
+/// compute shaders.
+pub fn install(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    caps: &wgpu::DownlevelCapabilities,
+) -> bool {
+    let supported = compute_supported(caps);
+    let blur = supported.then(|| Arc::new(GpuBlur::new(device.clone(), queue.clone())));
+    debug!(compute = supported, "gpu_blur_installed");
+    *SHARED.lock().unwrap_or_else(PoisonError::into_inner) = blur;
+    supported
+}
+
+/// Forgets the registered device ahead of a GPU teardown.
+pub fn uninstall() {
+    SHARED.lock().unwrap_or_else(PoisonError::into_inner).take();
+}
+
+/// Blurs `image` on the registered device. `None` when no device is
+/// registered, the image exceeds the device's storage limits, or the GPU
+/// reports an error.
+pub fn blur(image: &RgbaImage, sigma: f32) -> Option<RgbaImage> {
+    let blur = SHARED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()?;
+    blur.run(image, sigma)
+}
+
+impl GpuBlur {
+    fn new(device: wgpu::Device, queue: wgpu::Queue) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("gpu-blur"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("blur.wgsl").into()),
+        });
+        let storage = |binding, read_only| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        };
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("gpu-blur-layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                storage(1, true),
+                storage(2, true),
+                storage(3, false),
+            ],
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("gpu-blur-pipeline-layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("gpu-blur-pipeline"),
+            layout: Some(&pipeline_layout),
+            module: &shader,
+            entry_point: Some("main"),
+            compilation_options: wgpu::PipelineCompilationOptions::default(),
+            cache: None,
+        });
+        Self {
+            device,
+            queue,
+            layout,
+            pipeline,
+        }
+    }
+
+    fn run(&self, image: &RgbaImage, sigma: f32) -> Option<RgbaImage> {
+        let (width, height) = image.dimensions();
+        let size = u64::from(width) * u64::from(height) * 4;
+        let limits = self.device.limits();
+        if size == 0
+            || size > u64::from(limits.max_storage_buffer_binding_size)
+            || width.div_ceil(WORKGROUP_SIZE) > limits.max_compute_workgroups_per_dimension
+            || height.div_ceil(WORKGROUP_SIZE) > limits.max_compute_workgroups_per_dimension
+        {
+            debug!(width, height, "gpu_blur_image_exceeds_device_limits");
+            return None;
+        }
+        let (weights, radius) = gaussian_kernel(sigma);
+
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let pixels = self.dispatch(image, size, &weights, radius);
+        if let Some(err) = pollster::block_on(self.device.pop_error_scope()) {
+            warn!(error = %err, "gpu blur failed; using the CPU blur for this mat");
+            return None;
+        }
+        RgbaImage::from_raw(width, height, pixels?)
+    }
+
+    /// Blurs into `image`'s storage buffer along x, back along y, and reads
+    /// the result back.
+    fn dispatch(
+        &self,
+        image: &RgbaImage,
+        size: u64,
+        weights: &[f32],
+        radius: u32,
+    ) -> Option<Vec<u8>> {
+        let (width, height) = image.dimensions();
+        let device = &self.device;
+        let buffer = |label, usage| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size,
+                usage,
+                mapped_at_creation: false,
+            })
+        };
+        let pixels = buffer(
+            "gpu-blur-pixels",
+            wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
+        );
+        let scratch = buffer("gpu-blur-scratch", wgpu::BufferUsages::STORAGE);
+        let readback = buffer(
+            "gpu-blur-readback",
+            wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        );
+        let kernel = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("gpu-blur-kernel"),
+            size: std::mem::size_of_val(weights) as u64,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        self.queue.write_buffer(&pixels, 0, image.as_raw());
+        self.queue
+            .write_buffer(&kernel, 0, bytemuck::cast_slice(weights));
+
+        // Horizontal: pixels → scratch. Vertical: scratch → pixels.
+        let passes = [(true, &pixels, &scratch), (false, &scratch, &pixels)].map(
+            |(horizontal, src, dst)| {
+                let params = device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("gpu-blur-params"),
+                    size: std::mem::size_of::<BlurParams>() as u64,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                self.queue.write_buffer(
+                    &params,
+                    0,
+                    bytemuck::bytes_of(&BlurParams {
+                        width,
+                        height,
+                        radius,
+                        horizontal: u32::from(horizontal),
+                    }),
+                );
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("gpu-blur-bind"),
+                    layout: &self.layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: params.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: kernel.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: src.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 3,
+                            resource: dst.as_entire_binding(),
+                        },
+                    ],
+                })
+            },
+        );
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("gpu-blur-encoder"),
+        });
+        for bind in &passes {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("gpu-blur-pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, bind, &[]);
+            pass.dispatch_workgroups(
+                width.div_ceil(WORKGROUP_SIZE),
+                height.div_ceil(WORKGROUP_SIZE),
+                1,
+            );
+        }
+        encoder.copy_buffer_to_buffer(&pixels, 0, &readback, 0, size);
+        let submission = self.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let (tx, rx) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        let _ = device.poll(wgpu::PollType::Wait {
+            submission_index: Some(submission),
+            timeout: None,
+        });
+        rx.recv().ok()?.ok()?;
+        let out = slice.get_mapped_range().to_vec();
+        readback.unmap();
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adapters_without_compute_shaders_are_not_installed() {
+        let mut caps = wgpu::DownlevelCapabilities::default();
+        assert!(compute_supported(&caps));
+        caps.flags.remove(wgpu::DownlevelFlags::COMPUTE_SHADERS);
+        assert!(!compute_supported(&caps));
+    }
+}
@@ -0,0 +1,35 @@
+// One direction of a separable Gaussian blur over packed RGBA8 texels.
+
+struct Params {
+    width: u32,
+    height: u32,
+    radius: u32,
+    horizontal: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> weights: array<f32>;
+@group(0) @binding(2) var<storage, read> src: array<u32>;
+@group(0) @binding(3) var<storage, read_write> dst: array<u32>;
+
+@compute @workgroup_size(8, 8)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    if (id.x >= params.width || id.y >= params.height) {
+        return;
+    }
+    let radius = i32(params.radius);
+    var acc = vec4<f32>(0.0);
+    for (var i = -radius; i <= radius; i = i + 1) {
+        var x = i32(id.x);
+        var y = i32(id.y);
+        // Clamp to the edge, like the CPU and NEON paths.
+        if (params.horizontal == 1u) {
+            x = clamp(x + i, 0, i32(params.width) - 1);
+        } else {
+            y = clamp(y + i, 0, i32(params.height) - 1);
+        }
+        let texel = unpack4x8unorm(src[u32(y) * params.width + u32(x)]);
+        acc = acc + texel * weights[u32(i + radius)];
+    }
+    dst[id.y * params.width + id.x] = pack4x8unorm(acc);
+}
@@ -1 +1,2 @@
+pub mod blur;
 pub mod debug_overlay;
@@ -0,0 +1,87 @@
+//! Read-only HTTP endpoints for home dashboards and uptime checks, enabled by
+//! the `health-server` config block. `/healthz` answers as long as the
+//! process is serving; `/status` reports what the frame is doing from the same
+//! viewer snapshot the control socket's `status` command reads.
+
+use crate::events::SharedViewerStatus;
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::extract::State;
+use axum::response::Json;
+use axum::routing::get;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError};
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+/// What the endpoints report on.
+#[derive(Debug, Clone)]
+pub struct HealthStatus {
+    pub viewer: SharedViewerStatus,
+    /// Photos in the playlist, as counted by the manager.
+    pub library_size: Arc<AtomicUsize>,
+    pub started_at: Instant,
+}
+
+impl HealthStatus {
+    /// Like the control socket's `status` reply, with the photo count added.
+    /// The current photo's path is left out, since the server may be
+    /// reachable from the whole network.
+    fn to_json(&self) -> serde_json::Value {
+        let viewer = self
+            .viewer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        serde_json::json!({
+            "status": "ok",
+            "state": viewer.state,
+            "queue_depth": viewer.queue_depth,
+            "paused": viewer.paused,
+            "photo_count": self.library_size.load(Ordering::Relaxed),
+            "uptime_seconds": self.started_at.elapsed().as_secs(),
+        })
+    }
+}
+
+/// Binds `bind_address` and serves until `cancel` fires.
+pub async fn run(
+    cancel: CancellationToken,
+    bind_address: SocketAddr,
+    status: HealthStatus,
+) -> Result<()> {
+    let listener = TcpListener::bind(bind_address)
+        .await
+        .with_context(|| format!("failed to bind health server on {bind_address}"))?;
+    tracing::info!(address = %bind_address, "health server listening");
+    serve(listener, cancel, status).await
+}
+
+/// Serves the endpoints on an already bound listener until `cancel` fires.
+pub async fn serve(
+    listener: TcpListener,
+    cancel: CancellationToken,
+    status: HealthStatus,
+) -> Result<()> {
+    axum::serve(listener, router(status))
+        .with_graceful_shutdown(async move { cancel.cancelled().await })
+        .await
+        .context("health server exited")
+}
+
+fn router(status: HealthStatus) -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/status", get(report))
+        .with_state(status)
+}
+
+async fn healthz() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+async fn report(State(status): State<HealthStatus>) -> Json<serde_json::Value> {
+    Json(status.to_json())
+}
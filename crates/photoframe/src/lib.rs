@@ -1,13 +1,19 @@
 pub mod config;
 pub mod events;
 pub mod gpu;
+pub mod health;
+pub mod metrics;
 pub mod processing;
 pub mod renderer;
+pub mod share;
 pub mod tasks {
+    pub mod dedup;
     pub mod files;
     pub mod greeting_screen;
+    pub mod history;
     pub mod loader;
     pub mod manager;
     pub mod photo_effect;
+    pub mod quarantine;
     pub mod viewer;
 }
@@ -1,41 +1,55 @@
 mod config;
 mod events;
 mod gpu;
+mod health;
+mod metrics;
+#[cfg(unix)]
+mod mqtt;
 mod processing;
 mod renderer;
+mod share;
+#[cfg(unix)]
+mod web_control;
 mod tasks {
+    pub mod dedup;
     pub mod files;
     pub mod greeting_screen;
+    pub mod history;
     pub mod loader;
     pub mod manager;
     pub mod photo_effect;
+    pub mod quarantine;
     pub mod viewer;
 }
 
-use anyhow::{Context, Result};
-use clap::Parser;
+use anyhow::{Context, Result, ensure};
+use clap::{Parser, ValueEnum};
 use humantime::{format_rfc3339, parse_rfc3339};
 use std::io::{self, IsTerminal, Read};
-use std::path::PathBuf;
-use std::sync::Arc;
-use std::time::{Duration, SystemTime};
-use tokio::sync::mpsc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicUsize;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::{mpsc, watch};
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
-use tracing_subscriber::EnvFilter;
 
 #[cfg(unix)]
 use chrono::{DateTime, Utc};
 #[cfg(unix)]
 use serde::Deserialize;
 #[cfg(unix)]
-use tokio::io::AsyncReadExt;
+use std::sync::PoisonError;
 #[cfg(unix)]
-use tokio::net::UnixListener;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(unix)]
+use tokio::net::{TcpListener, TcpStream, UnixListener};
 
 use events::{
-    Displayed, InvalidPhoto, InventoryEvent, LoadPhoto, PhotoLoaded, ViewerCommand, ViewerState,
+    Displayed, InvalidPhoto, InventoryEvent, LoadPhoto, PhotoLoaded, ReloadedConfig,
+    SharedViewerStatus, StateSource, ViewerCommand, ViewerState, ViewerStatus,
 };
+use metrics::RuntimeMetrics;
 
 #[derive(Debug, Parser)]
 #[command(
@@ -45,48 +59,125 @@ use events::{
 )]
 struct Args {
     /// Path to YAML config
-    #[arg(value_name = "CONFIG")]
-    config: PathBuf,
+    #[arg(
+        value_name = "CONFIG",
+        required_unless_present = "print_default_config"
+    )]
+    config: Option<PathBuf>,
     /// Freeze playlist weighting at this RFC 3339 instant (useful for tests)
     #[arg(long = "playlist-now", value_name = "RFC3339")]
     playlist_now: Option<String>,
     /// Print the weighted playlist order without launching the UI
     #[arg(long = "playlist-dry-run", value_name = "ITERATIONS")]
     playlist_dry_run: Option<usize>,
+    /// Output format for `--playlist-dry-run`
+    #[arg(
+        long = "playlist-dry-run-format",
+        value_name = "FORMAT",
+        value_enum,
+        default_value_t = DryRunFormat::Text
+    )]
+    playlist_dry_run_format: DryRunFormat,
+    /// Limit `--playlist-dry-run` to photos matching this gitignore-style glob,
+    /// relative to the photo library (e.g. `2023/summer` or `*.heic`)
+    #[arg(long = "playlist-filter", value_name = "GLOB")]
+    playlist_filter: Option<String>,
     /// Deterministic RNG seed for playlist shuffling (applies to dry-run and live modes)
+    /// and for the per-photo `motion` paths
     #[arg(long = "playlist-seed", value_name = "SEED")]
     playlist_seed: Option<u64>,
+    /// Deterministic RNG seed for mat selection (`random` matting and `fill-when-fits`)
+    #[arg(long = "matting-seed", value_name = "SEED")]
+    matting_seed: Option<u64>,
+    /// Validate the config, print the effective settings, and exit
+    #[arg(long = "check-config")]
+    check_config: bool,
+    /// Validate the config and its fixed-image backgrounds, print OK or the
+    /// error, and exit
+    #[arg(long = "validate-config")]
+    validate_config: bool,
+    /// Print a config file with every key at its default, then exit
+    #[arg(long = "print-default-config")]
+    print_default_config: bool,
+    /// Pause the running frame with reason "sync", run this shell command, then
+    /// resume; exits with the command's status
+    #[cfg(unix)]
+    #[arg(long = "pause-during", value_name = "COMMAND")]
+    pause_during: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DryRunFormat {
+    /// Human-readable listing
+    Text,
+    /// A single JSON document for scripts
+    Json,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // init tracing (RUST_LOG controls level, default = info)
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .with_target(false)
-        .compact()
-        .init();
-
     let Args {
         config,
         playlist_now,
         playlist_dry_run,
+        playlist_dry_run_format,
+        playlist_filter,
         playlist_seed,
+        matting_seed,
+        check_config: check_only,
+        validate_config: validate_only,
+        print_default_config,
+        #[cfg(unix)]
+        pause_during,
     } = Args::parse();
 
+    if print_default_config {
+        print!("{}", config::Configuration::DEFAULT_YAML);
+        return Ok(());
+    }
+    let config = config.expect("clap requires CONFIG without --print-default-config");
+
+    // The config's `logging` block picks the format and levels; RUST_LOG still wins.
+    log_setup::init_from_file(&config, None)?;
+
+    if check_only {
+        match check_config(&config) {
+            Ok(report) => {
+                print!("{report}");
+                return Ok(());
+            }
+            Err(err) => {
+                eprintln!("{}: {err:#}", config.display());
+                std::process::exit(1);
+            }
+        }
+    }
+
+    if validate_only {
+        match validate_config(&config) {
+            Ok(()) => {
+                println!("OK");
+                return Ok(());
+            }
+            Err(err) => {
+                eprintln!("{}: {err:#}", config.display());
+                std::process::exit(1);
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    if let Some(command) = pause_during {
+        let code = pause_during_command(&config, &command)?;
+        std::process::exit(code);
+    }
+
     let now_override = match playlist_now {
         Some(ts) => Some(parse_rfc3339(&ts).context("failed to parse --playlist-now")?),
         None => None,
     };
 
-    let cfg = Arc::new(
-        config::Configuration::from_yaml_file(&config)
-            .with_context(|| format!("failed to load configuration from {}", config.display()))?
-            .validated()
-            .context("invalid configuration values")?,
-    );
+    let cfg = Arc::new(load_config(&config)?);
     tracing::info!(
         "Loaded configuration from {}:\n{:#?}",
         config.display(),
@@ -94,9 +185,16 @@ async fn main() -> Result<()> {
     );
 
     if let Some(iterations) = playlist_dry_run {
-        run_playlist_dry_run(&cfg, iterations, now_override, playlist_seed)?;
+        let dry_run = DryRunOptions {
+            iterations,
+            now: now_override.unwrap_or_else(SystemTime::now),
+            seed: playlist_seed.unwrap_or_else(rand::random),
+            filter: playlist_filter,
+        };
+        run_playlist_dry_run(&cfg, &dry_run, playlist_dry_run_format)?;
         return Ok(());
     }
+    tasks::files::check_required_roots(&cfg)?;
 
     // Channels (bounded).  Capacities are chosen to bound memory while keeping the pipeline fluid:
     // - inv_tx: burst during startup scan can be large; 128 gives headroom without unbounded growth.
@@ -112,8 +210,18 @@ async fn main() -> Result<()> {
     let (processed_tx, processed_rx) = mpsc::channel::<PhotoLoaded>(cfg.viewer_preload_count);
     let (displayed_tx, displayed_rx) = mpsc::channel::<Displayed>(64);
     let (viewer_control_tx, viewer_control_rx) = mpsc::channel::<ViewerCommand>(16);
+    // Flips to true once the viewer holds a prepared photo; ends the greeting early.
+    let (photo_ready_tx, photo_ready_rx) = watch::channel(false);
+    // Carries the reason of a maintenance pause; the library is held while set.
+    let (library_hold_tx, library_hold_rx) = watch::channel::<Option<String>>(None);
 
     let cancel = CancellationToken::new();
+    let library_size = Arc::new(AtomicUsize::new(0));
+    let viewer_status: SharedViewerStatus = Arc::new(Mutex::new(ViewerStatus::default()));
+    let started_at = Instant::now();
+    let history = tasks::history::HistoryRecorder::start(&cfg.history);
+    let metrics = RuntimeMetrics::shared();
+    let quarantine = tasks::quarantine::Quarantine::shared(&cfg.playlist.quarantine);
 
     // Both the stdin watcher and the Ctrl-C handler call cancel.cancel().
     // CancellationToken::cancel() is idempotent, so whichever fires first wins and
@@ -146,20 +254,97 @@ async fn main() -> Result<()> {
 
     let mut tasks = JoinSet::new();
 
+    if cfg.health_server.enabled {
+        let cancel = cancel.clone();
+        let bind_address = cfg.health_server.bind_address;
+        let status = health::HealthStatus {
+            viewer: Arc::clone(&viewer_status),
+            library_size: Arc::clone(&library_size),
+            started_at,
+        };
+        tasks.spawn(async move {
+            health::run(cancel, bind_address, status)
+                .await
+                .context("health server task failed")
+        });
+    }
+
     #[cfg(unix)]
     {
         let cancel = cancel.clone();
         let control = viewer_control_tx.clone();
         let control_socket_path = cfg.control_socket_path.clone();
-        let greeting_delay = cfg.greeting_screen.effective_duration();
+        let peer_policy = ControlPeerPolicy::from_config(&cfg);
+        let greeting = GreetingTiming::from_config(&cfg.greeting_screen);
+        let photo_ready = photo_ready_rx;
         let schedule = cfg.awake_schedule.clone();
+        let manual_override = Arc::new(Mutex::new(ManualOverride::new(
+            schedule
+                .as_ref()
+                .and_then(config::AwakeScheduleConfig::manual_override),
+        )));
+        let status = ControlStatus {
+            viewer: Arc::clone(&viewer_status),
+            started_at,
+            history: history.shared(),
+            metrics: Arc::clone(&metrics),
+            quarantine: Arc::clone(&quarantine),
+            library_hold: Arc::new(library_hold_tx),
+        };
+        if cfg.web_control.enabled {
+            let cancel = cancel.clone();
+            let bind_address = cfg.web_control.bind_address;
+            let remote = web_control::WebControl {
+                control: control.clone(),
+                status: status.clone(),
+                manual_override: Arc::clone(&manual_override),
+            };
+            tasks.spawn(async move {
+                web_control::run(cancel, bind_address, remote)
+                    .await
+                    .context("web control task failed")
+            });
+        }
+        let config_path = Arc::new(config.clone());
+        if cfg.control_tcp.enabled {
+            let cancel = cancel.clone();
+            let tcp = cfg.control_tcp.clone();
+            let control = control.clone();
+            let status = status.clone();
+            let config_path = Arc::clone(&config_path);
+            let manual_override = Arc::clone(&manual_override);
+            tasks.spawn(async move {
+                run_control_tcp(cancel, tcp, control, status, config_path, manual_override)
+                    .await
+                    .context("tcp control task failed")
+            });
+        }
+        if cfg.mqtt.enabled {
+            let cancel = cancel.clone();
+            let mqtt_config = cfg.mqtt.clone();
+            let remote = mqtt::MqttRemote {
+                control: control.clone(),
+                status: status.clone(),
+                manual_override: Arc::clone(&manual_override),
+            };
+            tasks.spawn(async move {
+                mqtt::run(cancel, mqtt_config, remote)
+                    .await
+                    .context("mqtt task failed")
+            });
+        }
         tasks.spawn(async move {
             run_control_socket(
                 cancel,
                 control,
                 control_socket_path,
-                greeting_delay,
+                peer_policy,
+                status,
+                config_path,
+                greeting,
+                photo_ready,
                 schedule,
+                manual_override,
             )
             .await
             .context("control socket task failed")
@@ -171,9 +356,11 @@ async fn main() -> Result<()> {
         let cfg = Arc::clone(&cfg);
         let inv_tx = inv_tx.clone();
         let invalid_rx = invalid_rx;
+        let viewer_tx = viewer_control_tx.clone();
         let cancel = cancel.clone();
+        let library_hold = library_hold_rx.clone();
         async move {
-            tasks::files::run(cfg, inv_tx, invalid_rx, cancel)
+            tasks::files::run(cfg, inv_tx, invalid_rx, viewer_tx, cancel, library_hold)
                 .await
                 .context("files task failed")
         }
@@ -187,6 +374,8 @@ async fn main() -> Result<()> {
         let cancel = cancel.clone();
         let playlist = cfg.playlist.clone();
         let seed_override = playlist_seed;
+        let library_size = Arc::clone(&library_size);
+        let library_hold = library_hold_rx;
         async move {
             tasks::manager::run(
                 inv_rx,
@@ -196,6 +385,10 @@ async fn main() -> Result<()> {
                 playlist,
                 now_override,
                 seed_override,
+                library_size,
+                history,
+                quarantine,
+                library_hold,
             )
             .await
             .context("manager task failed")
@@ -209,10 +402,38 @@ async fn main() -> Result<()> {
         let loaded_tx = loaded_tx.clone();
         let cancel = cancel.clone();
         let max_in_flight = cfg.loader_max_concurrent_decodes;
+        let decode_timeout = Duration::from_millis(cfg.loader_decode_timeout_ms);
+        let thumbnail_cache = cfg
+            .thumbnail_cache
+            .as_ref()
+            .map(|cache| Arc::new(tasks::loader::ThumbnailCache::new(cache)));
+        let caption_template = cfg.caption.active_template().map(str::to_owned);
+        let animations = cfg.animations.clone();
+        let color = cfg.color.clone();
+        let metrics = Arc::clone(&metrics);
+        let decode_cache = cfg.decode_cache.as_ref().map(|cache| {
+            Arc::new(tasks::loader::DecodeCache::new(
+                cache,
+                cfg.thumbnail_cache.as_ref(),
+            ))
+        });
         async move {
-            tasks::loader::run(to_load_rx, invalid_tx, loaded_tx, cancel, max_in_flight)
-                .await
-                .context("loader task failed")
+            tasks::loader::run(
+                to_load_rx,
+                invalid_tx,
+                loaded_tx,
+                cancel,
+                max_in_flight,
+                decode_timeout,
+                thumbnail_cache,
+                decode_cache,
+                caption_template,
+                animations,
+                color,
+                metrics,
+            )
+            .await
+            .context("loader task failed")
         }
     });
 
@@ -223,8 +444,9 @@ async fn main() -> Result<()> {
         let to_viewer = processed_tx.clone();
         let cancel = cancel.clone();
         let effect_cfg = photo_effect_cfg;
+        let metrics = Arc::clone(&metrics);
         async move {
-            tasks::photo_effect::run(from_loader, to_viewer, cancel, effect_cfg)
+            tasks::photo_effect::run(from_loader, to_viewer, cancel, effect_cfg, metrics)
                 .await
                 .context("photo-effect task failed")
         }
@@ -238,6 +460,12 @@ async fn main() -> Result<()> {
         cancel.clone(),
         Arc::clone(&cfg),
         viewer_control_rx,
+        library_size,
+        playlist_seed,
+        matting_seed,
+        viewer_status,
+        photo_ready_tx,
+        metrics,
     )
     .context("viewer failed");
 
@@ -259,55 +487,271 @@ async fn main() -> Result<()> {
     viewer_result
 }
 
+/// Reads and validates the YAML config at `path`; used at startup and by `reload-config`.
+fn load_config(path: &std::path::Path) -> Result<config::Configuration> {
+    config::Configuration::from_yaml_file(path)
+        .with_context(|| format!("failed to load configuration from {}", path.display()))?
+        .validated()
+        .context("invalid configuration values")
+}
+
+/// `--check-config`: validates the file exactly as startup does and renders the
+/// effective configuration as YAML with every default filled in. Opens no
+/// window or control socket and only checks that the photo library exists.
+fn check_config(path: &std::path::Path) -> Result<String> {
+    let cfg = load_config(path)?;
+    let warnings: Vec<String> = cfg
+        .photo_library_paths
+        .iter()
+        .filter(|root| !root.path.is_dir())
+        .map(|root| {
+            let effect = if root.optional {
+                "the frame waits for it"
+            } else {
+                "startup fails unless it is marked `optional: true`"
+            };
+            format!(
+                "photo-library-path {} does not exist yet; {effect}",
+                root.path.display()
+            )
+        })
+        .collect();
+    config_model::check_report(path, &warnings, &cfg)
+}
+
+/// `--validate-config`: the pass/fail half of `--check-config` for scripts and
+/// deploy hooks. Beyond what startup checks, every `fixed-image` background must
+/// exist; startup skips a missing one with a warning while another is usable.
+fn validate_config(path: &std::path::Path) -> Result<()> {
+    // Read before loading, which leaves out the mats it cannot use.
+    let listed = config::Configuration::from_yaml_file(path)
+        .with_context(|| format!("failed to load configuration from {}", path.display()))?;
+    for option in listed.matting.options() {
+        if let config::MattingMode::FixedImage { paths, .. } = &option.style {
+            for image in paths {
+                ensure!(
+                    image.is_file(),
+                    "matting fixed-image path {} does not exist",
+                    image.display()
+                );
+            }
+        }
+    }
+    load_config(path)?;
+    Ok(())
+}
+
+/// `--pause-during`: holds the running frame's library for the length of
+/// `command` (an rclone sync, say) so it never shows a half-written photo.
+/// Resumes even when the command fails, and returns its exit code.
+#[cfg(unix)]
+fn pause_during_command(path: &std::path::Path, command: &str) -> Result<i32> {
+    let cfg = load_config(path)?;
+    let socket = cfg.control_socket_path.as_path();
+    send_control_command(socket, r#"{"command":"pause","reason":"sync"}"#)?;
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .status()
+        .with_context(|| format!("failed to run {command:?}"));
+    send_control_command(socket, r#"{"command":"resume"}"#)?;
+    let status = status?;
+    if !status.success() {
+        tracing::warn!(%status, "paused command failed");
+    }
+    Ok(status.code().unwrap_or(1))
+}
+
+/// Writes one JSON command to the control socket at `socket`, or where the
+/// frame fell back to, and waits for the frame to close the connection, which
+/// it does once the command is applied.
+#[cfg(unix)]
+fn send_control_command(socket: &std::path::Path, command: &str) -> Result<()> {
+    use std::io::Write;
+    let mut stream = config_model::connect_control_socket(socket)?;
+    stream
+        .write_all(command.as_bytes())
+        .context("failed to send control command")?;
+    stream
+        .shutdown(std::net::Shutdown::Write)
+        .context("failed to finish control command")?;
+    stream
+        .read_to_end(&mut Vec::new())
+        .context("failed to wait for the control command")?;
+    Ok(())
+}
+
+/// What `--playlist-dry-run` simulates. The seed is always resolved up front
+/// so a run with a random seed can be reproduced from its output.
+struct DryRunOptions {
+    iterations: usize,
+    now: SystemTime,
+    seed: u64,
+    filter: Option<String>,
+}
+
+/// The outcome of a dry run; both output formats render from this.
+#[derive(Debug, serde::Serialize)]
+struct PlaylistDryRun {
+    now: String,
+    seed: u64,
+    iterations: usize,
+    filter: Option<String>,
+    photos: Vec<DryRunPhoto>,
+    order: Vec<PathBuf>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct DryRunPhoto {
+    path: PathBuf,
+    created_at: String,
+    /// Relative show frequency; equilibrium = 1.0.
+    weight: f64,
+}
+
 fn run_playlist_dry_run(
     cfg: &config::Configuration,
-    iterations: usize,
-    now_override: Option<SystemTime>,
-    seed: Option<u64>,
+    options: &DryRunOptions,
+    format: DryRunFormat,
 ) -> Result<()> {
-    let now = now_override.unwrap_or_else(SystemTime::now);
     let photos = tasks::files::discover_startup_photos(cfg)?;
+    let photos = match &options.filter {
+        Some(glob) => filter_photos(&cfg.library_paths(), photos, glob)?,
+        None => photos,
+    };
+    let dry_run = plan_playlist_dry_run(cfg, photos, options);
+    match format {
+        DryRunFormat::Text => print!("{}", dry_run.render_text(&cfg.library_paths())),
+        DryRunFormat::Json => println!("{}", serde_json::to_string_pretty(&dry_run)?),
+    }
+    Ok(())
+}
+
+/// Keeps the photos matching `glob`, read as a gitignore line relative to the
+/// innermost library root holding each photo. A pattern naming a folder keeps
+/// everything below it.
+fn filter_photos(
+    roots: &[PathBuf],
+    photos: Vec<events::PhotoInfo>,
+    glob: &str,
+) -> Result<Vec<events::PhotoInfo>> {
+    let matchers = roots
+        .iter()
+        .map(|root| {
+            let mut builder = ignore::gitignore::GitignoreBuilder::new(root);
+            builder
+                .add_line(None, glob)
+                .with_context(|| format!("invalid --playlist-filter {glob:?}"))?;
+            let matcher = builder
+                .build()
+                .with_context(|| format!("invalid --playlist-filter {glob:?}"))?;
+            Ok((root, matcher))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let keep = |path: &Path| {
+        matchers
+            .iter()
+            .filter(|(root, _)| path.starts_with(root))
+            .max_by_key(|(root, _)| root.components().count())
+            .is_some_and(|(_, matcher)| {
+                matcher.matched_path_or_any_parents(path, false).is_ignore()
+            })
+    };
+    Ok(photos.into_iter().filter(|info| keep(&info.path)).collect())
+}
+
+fn plan_playlist_dry_run(
+    cfg: &config::Configuration,
+    photos: Vec<events::PhotoInfo>,
+    options: &DryRunOptions,
+) -> PlaylistDryRun {
+    let favorites = cfg.playlist.favorites.as_ref();
+    let favorite_paths = favorites
+        .map(config::PlaylistFavorites::load)
+        .unwrap_or_default();
+    let entries = photos
+        .iter()
+        .map(|info| {
+            let mut weight =
+                cfg.playlist.weight_for(info.created_at, options.now) * info.root_weight;
+            if let Some(favorites) = favorites
+                && config::PlaylistFavorites::contains(&favorite_paths, &info.path)
+            {
+                weight *= favorites.factor;
+            }
+            DryRunPhoto {
+                path: info.path.clone(),
+                created_at: format_rfc3339(info.created_at).to_string(),
+                weight,
+            }
+        })
+        .collect();
 
-    println!(
-        "# playlist dry run\n# photos: {}\n# now: {}\n# iterations: {}\n# seed: {}\n",
-        photos.len(),
-        format_rfc3339(now),
-        iterations,
-        seed.map_or_else(|| "(random)".to_string(), |s| s.to_string())
+    // Plan from a fresh schedule; a dry run must never touch the live state file.
+    let playlist = config::PlaylistOptions {
+        state_file: None,
+        ..cfg.playlist.clone()
+    };
+    let order = tasks::manager::simulate_playlist(
+        photos,
+        playlist,
+        options.now,
+        options.iterations,
+        Some(options.seed),
     );
 
-    if photos.is_empty() {
-        println!(
-            "(no photos discovered under {})",
-            cfg.photo_library_path.display()
-        );
-        return Ok(());
+    PlaylistDryRun {
+        now: format_rfc3339(options.now).to_string(),
+        seed: options.seed,
+        iterations: options.iterations,
+        filter: options.filter.clone(),
+        photos: entries,
+        order,
     }
+}
 
-    println!("# weights (relative show frequency; equilibrium = 1.0):");
-    for info in &photos {
-        let weight = cfg.playlist.weight_for(info.created_at, now);
-        println!("  {:>5.2} × {}", weight, info.path.display());
-    }
+impl PlaylistDryRun {
+    fn render_text(&self, roots: &[PathBuf]) -> String {
+        use std::fmt::Write as _;
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "# playlist dry run\n# photos: {}\n# now: {}\n# iterations: {}\n# seed: {}",
+            self.photos.len(),
+            self.now,
+            self.iterations,
+            self.seed
+        );
+        if let Some(filter) = &self.filter {
+            let _ = writeln!(out, "# filter: {filter}");
+        }
+        out.push('\n');
+
+        if self.photos.is_empty() {
+            let roots: Vec<String> = roots
+                .iter()
+                .map(|root| root.display().to_string())
+                .collect();
+            let _ = writeln!(out, "(no photos discovered under {})", roots.join(", "));
+            return out;
+        }
 
-    let plan = tasks::manager::simulate_playlist(
-        photos.clone(),
-        cfg.playlist.clone(),
-        now,
-        iterations,
-        seed,
-    );
+        out.push_str("# weights (relative show frequency; equilibrium = 1.0):\n");
+        for photo in &self.photos {
+            let _ = writeln!(out, "  {:>5.2} × {}", photo.weight, photo.path.display());
+        }
 
-    println!("\n# planned order:");
-    if plan.is_empty() {
-        println!("(playlist empty)");
-    } else {
-        for (idx, path) in plan.iter().enumerate() {
-            println!("  {:>4}: {}", idx + 1, path.display());
+        out.push_str("\n# planned order:\n");
+        if self.order.is_empty() {
+            out.push_str("(playlist empty)\n");
+        } else {
+            for (idx, path) in self.order.iter().enumerate() {
+                let _ = writeln!(out, "  {:>4}: {}", idx + 1, path.display());
+            }
         }
+        out
     }
-
-    Ok(())
 }
 
 #[cfg(unix)]
@@ -317,7 +761,39 @@ enum ControlCommand {
     #[serde(rename = "toggle-state")]
     ToggleState,
     #[serde(rename = "set-state")]
-    SetState { state: ControlState },
+    SetState {
+        state: ControlState,
+        #[serde(default)]
+        source: StateSource,
+    },
+    #[serde(rename = "next")]
+    Next,
+    #[serde(rename = "previous")]
+    Previous,
+    #[serde(rename = "pause")]
+    Pause {
+        #[serde(default)]
+        reason: Option<String>,
+    },
+    #[serde(rename = "resume")]
+    Resume,
+    #[serde(rename = "status")]
+    Status,
+    #[serde(rename = "reload-config")]
+    ReloadConfig,
+    #[serde(rename = "history")]
+    History {
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    #[serde(rename = "metrics")]
+    Metrics,
+    #[serde(rename = "quarantine-list")]
+    QuarantineList,
+    #[serde(rename = "notice")]
+    Notice { text: String, seconds: u64 },
+    #[serde(rename = "debug-overlay")]
+    DebugOverlay { enabled: bool },
 }
 
 #[cfg(unix)]
@@ -326,6 +802,7 @@ enum ControlCommand {
 enum ControlState {
     Awake,
     Asleep,
+    Ambient,
 }
 
 #[cfg(unix)]
@@ -334,6 +811,7 @@ impl From<ControlState> for ViewerState {
         match value {
             ControlState::Awake => ViewerState::Awake,
             ControlState::Asleep => ViewerState::Asleep,
+            ControlState::Ambient => ViewerState::Ambient,
         }
     }
 }
@@ -354,14 +832,216 @@ impl Drop for SocketCleanup {
     }
 }
 
+/// Which local peers may send control commands, from
+/// `control-socket-allowed-uids` and `control-socket-allowed-gids`.
 #[cfg(unix)]
-async fn run_control_socket(
-    cancel: CancellationToken,
-    control: mpsc::Sender<ViewerCommand>,
-    socket_path: PathBuf,
-    greeting_delay: Duration,
-    schedule: Option<config::AwakeScheduleConfig>,
-) -> Result<()> {
+#[derive(Debug, Clone, Default)]
+struct ControlPeerPolicy {
+    allowed_uids: Option<Vec<u32>>,
+    allowed_gids: Option<Vec<u32>>,
+}
+
+#[cfg(unix)]
+impl ControlPeerPolicy {
+    fn from_config(cfg: &config::Configuration) -> Self {
+        Self {
+            allowed_uids: cfg.control_socket_allowed_uids.clone(),
+            allowed_gids: cfg.control_socket_allowed_gids.clone(),
+        }
+    }
+
+    /// With neither list set every peer that can open the socket is trusted,
+    /// as before the lists existed.
+    fn is_restricted(&self) -> bool {
+        self.allowed_uids.is_some() || self.allowed_gids.is_some()
+    }
+
+    /// A peer passes when its uid or its primary gid is listed. Supplementary
+    /// groups are not visible through `SO_PEERCRED`.
+    fn permits(&self, uid: u32, gid: u32) -> bool {
+        if !self.is_restricted() {
+            return true;
+        }
+        let listed = |ids: &Option<Vec<u32>>, id| ids.as_ref().is_some_and(|ids| ids.contains(&id));
+        listed(&self.allowed_uids, uid) || listed(&self.allowed_gids, gid)
+    }
+}
+
+/// A manual wake or sleep holding off the schedule for
+/// `awake-schedule.manual-override-minutes`. Scheduled `set-state` commands
+/// that disagree with it are refused until it runs out, or until the schedule
+/// has moved on and come round to the same state. Without the setting manual
+/// changes never hold, as before it existed.
+#[cfg(unix)]
+#[derive(Debug, Default)]
+struct ManualOverride {
+    ttl: Option<Duration>,
+    active: Option<ActiveOverride>,
+}
+
+#[cfg(unix)]
+#[derive(Debug, Clone, Copy)]
+struct ActiveOverride {
+    state: ViewerState,
+    until: Instant,
+    /// The schedule has asked for the other state since the override began, so
+    /// it asking for this one again means it has passed a boundary.
+    contested: bool,
+}
+
+#[cfg(unix)]
+impl ManualOverride {
+    fn new(ttl: Option<Duration>) -> Self {
+        Self { ttl, active: None }
+    }
+
+    /// Starts (or restarts) the override for a manual change to `state`.
+    fn record_manual(&mut self, state: ViewerState, now: Instant) {
+        self.active = self.ttl.map(|ttl| ActiveOverride {
+            state,
+            until: now + ttl,
+            contested: false,
+        });
+    }
+
+    /// Whether a scheduled change to `state` may go ahead. When it may not, the
+    /// error holds how much longer the override lasts.
+    fn admit_scheduled(&mut self, state: ViewerState, now: Instant) -> Result<(), Duration> {
+        let Some(active) = self.active.as_mut() else {
+            return Ok(());
+        };
+        if now >= active.until {
+            self.active = None;
+        } else if state != active.state {
+            active.contested = true;
+            return Err(active.until - now);
+        } else if active.contested {
+            self.active = None;
+        }
+        Ok(())
+    }
+}
+
+/// Photos a `history` request lists when it names no `limit`.
+#[cfg(unix)]
+const DEFAULT_HISTORY_LIMIT: usize = 10;
+
+/// Source of the `status`, `history`, `metrics`, and `quarantine-list`
+/// replies: the viewer's published snapshot, the process start for uptime,
+/// the recently shown photos, the pipeline timings, and the photos set aside
+/// after repeated decode failures. Also holds the library for a pause that
+/// gives a reason.
+#[cfg(unix)]
+#[derive(Debug, Clone)]
+struct ControlStatus {
+    viewer: SharedViewerStatus,
+    started_at: Instant,
+    history: tasks::history::SharedHistory,
+    metrics: metrics::SharedMetrics,
+    quarantine: tasks::quarantine::SharedQuarantine,
+    library_hold: Arc<watch::Sender<Option<String>>>,
+}
+
+#[cfg(unix)]
+impl ControlStatus {
+    fn to_json(&self) -> serde_json::Value {
+        let viewer = self
+            .viewer
+            .lock()
+            .map(|status| status.clone())
+            .unwrap_or_default();
+        serde_json::json!({
+            "status": "ok",
+            "state": viewer.state,
+            "current_photo": viewer.current_photo,
+            "queue_depth": viewer.queue_depth,
+            "paused": viewer.paused,
+            "pause_reason": self.library_hold.borrow().clone(),
+            "uptime_seconds": self.started_at.elapsed().as_secs(),
+        })
+    }
+
+    /// The newest `limit` displays, newest first.
+    fn history_json(&self, limit: Option<usize>) -> serde_json::Value {
+        let recent = self
+            .history
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .recent(limit.unwrap_or(DEFAULT_HISTORY_LIMIT));
+        serde_json::json!({ "status": "ok", "history": recent })
+    }
+
+    fn metrics_json(&self) -> serde_json::Value {
+        let metrics = self
+            .metrics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let mut reply = serde_json::to_value(metrics).unwrap_or_default();
+        reply["status"] = "ok".into();
+        reply
+    }
+
+    /// The photo on screen or in the history whose `share::photo_id` is `id`.
+    fn shared_photo(&self, id: &str) -> Option<PathBuf> {
+        let current = self
+            .viewer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .current_photo
+            .clone();
+        let recent = self
+            .history
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .recent(usize::MAX);
+        current
+            .into_iter()
+            .chain(recent.into_iter().map(|entry| entry.path))
+            .find(|path| share::photo_id(path) == id)
+    }
+
+    /// Quarantined photos, soonest release first.
+    fn quarantine_json(&self) -> serde_json::Value {
+        let listed = self
+            .quarantine
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .list(SystemTime::now());
+        serde_json::json!({ "status": "ok", "quarantine": listed })
+    }
+}
+
+/// Binds the control socket at `socket_path`, or at `fallback` when the
+/// service user may not create it there. Returns the listener and the path
+/// it is bound at.
+#[cfg(unix)]
+fn bind_control_socket(
+    socket_path: &Path,
+    fallback: Option<PathBuf>,
+) -> Result<(UnixListener, PathBuf)> {
+    let err = match bind_control_socket_at(socket_path) {
+        Ok(listener) => return Ok((listener, socket_path.to_path_buf())),
+        Err(err) => err,
+    };
+    let denied = err
+        .downcast_ref::<io::Error>()
+        .is_some_and(|err| err.kind() == io::ErrorKind::PermissionDenied);
+    let Some(fallback) = fallback.filter(|_| denied) else {
+        return Err(err);
+    };
+    tracing::warn!(
+        path = %socket_path.display(),
+        fallback = %fallback.display(),
+        error = %format!("{err:#}"),
+        "control socket path not permitted; using the user runtime directory, where --pause-during and buttond look next"
+    );
+    let listener = bind_control_socket_at(&fallback)?;
+    Ok((listener, fallback))
+}
+
+#[cfg(unix)]
+fn bind_control_socket_at(socket_path: &Path) -> Result<UnixListener> {
     if let Some(parent) = socket_path.parent()
         && let Err(err) = std::fs::create_dir_all(parent)
     {
@@ -377,7 +1057,7 @@ async fn run_control_socket(
     }
 
     if socket_path.exists() {
-        match std::fs::remove_file(&socket_path) {
+        match std::fs::remove_file(socket_path) {
             Ok(_) => tracing::warn!(path = %socket_path.display(), "removed stale control socket"),
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
             Err(err) => {
@@ -391,16 +1071,44 @@ async fn run_control_socket(
         }
     }
 
-    let listener = UnixListener::bind(&socket_path)
-        .with_context(|| format!("failed to bind control socket at {}", socket_path.display()))?;
+    UnixListener::bind(socket_path)
+        .with_context(|| format!("failed to bind control socket at {}", socket_path.display()))
+}
+
+#[cfg(unix)]
+#[allow(clippy::too_many_arguments)]
+async fn run_control_socket(
+    cancel: CancellationToken,
+    control: mpsc::Sender<ViewerCommand>,
+    socket_path: PathBuf,
+    peer_policy: ControlPeerPolicy,
+    status: ControlStatus,
+    config_path: Arc<PathBuf>,
+    greeting: GreetingTiming,
+    photo_ready: watch::Receiver<bool>,
+    schedule: Option<config::AwakeScheduleConfig>,
+    manual_override: Arc<Mutex<ManualOverride>>,
+) -> Result<()> {
+    let fallback =
+        config_model::fallback_control_socket_path(std::env::var_os("XDG_RUNTIME_DIR").as_deref());
+    let (listener, socket_path) = bind_control_socket(&socket_path, fallback)?;
     let _cleanup = SocketCleanup {
         path: socket_path.clone(),
     };
 
     tracing::info!(path = %socket_path.display(), "listening for control commands");
+    if peer_policy.is_restricted() {
+        tracing::info!(
+            uids = ?peer_policy.allowed_uids,
+            gids = ?peer_policy.allowed_gids,
+            "control socket restricted to listed peers"
+        );
+    }
+    let peer_policy = Arc::new(peer_policy);
 
     if let Err(err) =
-        run_initial_schedule_preamble(&cancel, &control, greeting_delay, schedule.as_ref()).await
+        run_initial_schedule_preamble(&cancel, &control, greeting, photo_ready, schedule.as_ref())
+            .await
     {
         tracing::warn!("control preamble failed: {err}");
     }
@@ -415,8 +1123,21 @@ async fn run_control_socket(
                 match accept_result {
                     Ok((stream, _addr)) => {
                         let control = control.clone();
+                        let policy = Arc::clone(&peer_policy);
+                        let status = status.clone();
+                        let config_path = Arc::clone(&config_path);
+                        let manual_override = Arc::clone(&manual_override);
                         tokio::spawn(async move {
-                            if let Err(err) = handle_control_connection(stream, control).await {
+                            let handled = handle_control_connection(
+                                stream,
+                                control,
+                                policy,
+                                status,
+                                &config_path,
+                                &manual_override,
+                            )
+                            .await;
+                            if let Err(err) = handled {
                                 tracing::warn!("control connection failed: {err}");
                             }
                         });
@@ -435,27 +1156,194 @@ async fn run_control_socket(
     Ok(())
 }
 
+/// How long a TCP peer may stay silent before its connection is closed.
 #[cfg(unix)]
-async fn run_initial_schedule_preamble(
-    cancel: &CancellationToken,
-    control: &mpsc::Sender<ViewerCommand>,
-    greeting_delay: Duration,
-    schedule: Option<&config::AwakeScheduleConfig>,
+const TCP_CONTROL_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// `control-tcp`: the control socket's protocol over TCP, for other hosts.
+/// Peers other than loopback and `allowed-peers` are refused.
+#[cfg(unix)]
+async fn run_control_tcp(
+    cancel: CancellationToken,
+    tcp: config::ControlTcpConfig,
+    control: mpsc::Sender<ViewerCommand>,
+    status: ControlStatus,
+    config_path: Arc<PathBuf>,
+    manual_override: Arc<Mutex<ManualOverride>>,
 ) -> Result<()> {
-    if !greeting_delay.is_zero() {
-        tracing::debug!(
-            delay = %humantime::format_duration(greeting_delay),
-            "waiting for greeting preamble before applying schedule",
-        );
-        let delay = tokio::time::sleep(greeting_delay);
-        tokio::pin!(delay);
+    let listener = TcpListener::bind(tcp.bind_address)
+        .await
+        .with_context(|| format!("failed to bind control listener on {}", tcp.bind_address))?;
+    tracing::info!(
+        address = %tcp.bind_address,
+        allowed = ?tcp.allowed_peers,
+        "listening for control commands over tcp"
+    );
+    serve_control_tcp(
+        listener,
+        cancel,
+        Arc::new(tcp),
+        control,
+        status,
+        config_path,
+        manual_override,
+    )
+    .await
+}
+
+#[cfg(unix)]
+async fn serve_control_tcp(
+    listener: TcpListener,
+    cancel: CancellationToken,
+    tcp: Arc<config::ControlTcpConfig>,
+    control: mpsc::Sender<ViewerCommand>,
+    status: ControlStatus,
+    config_path: Arc<PathBuf>,
+    manual_override: Arc<Mutex<ManualOverride>>,
+) -> Result<()> {
+    loop {
         tokio::select! {
-            _ = cancel.cancelled() => return Ok(()),
-            _ = delay => {}
+            _ = cancel.cancelled() => {
+                tracing::debug!("shutdown requested; closing tcp control listener");
+                break;
+            }
+            accept_result = listener.accept() => {
+                let (stream, peer) = match accept_result {
+                    Ok(accepted) => accepted,
+                    // A peer that vanished mid-handshake is no reason to stop listening.
+                    Err(err) => {
+                        tracing::warn!("failed to accept tcp control connection: {err}");
+                        continue;
+                    }
+                };
+                let tcp = Arc::clone(&tcp);
+                let control = control.clone();
+                let status = status.clone();
+                let config_path = Arc::clone(&config_path);
+                let manual_override = Arc::clone(&manual_override);
+                tokio::spawn(async move {
+                    let handled = handle_tcp_control_connection(
+                        stream,
+                        peer,
+                        &tcp,
+                        control,
+                        status,
+                        &config_path,
+                        &manual_override,
+                    )
+                    .await;
+                    if let Err(err) = handled {
+                        tracing::warn!(%peer, "tcp control connection failed: {err:#}");
+                    }
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+async fn handle_tcp_control_connection(
+    mut stream: TcpStream,
+    peer: std::net::SocketAddr,
+    tcp: &config::ControlTcpConfig,
+    control: mpsc::Sender<ViewerCommand>,
+    status: ControlStatus,
+    config_path: &std::path::Path,
+    manual_override: &Mutex<ManualOverride>,
+) -> Result<()> {
+    if !tcp.permits(peer.ip()) {
+        tracing::warn!(%peer, "rejected tcp control command from unlisted peer");
+        refuse_control_peer(&mut stream, "peer is not allowed to control the frame").await;
+        return Ok(());
+    }
+    handle_control_lines(
+        &mut stream,
+        control,
+        status,
+        config_path,
+        manual_override,
+        Some(TCP_CONTROL_IDLE_TIMEOUT),
+    )
+    .await
+}
+
+/// How long the greeting stays up: at least `min`, then until the first photo
+/// is ready, but never past `max` — the fallback when no photo shows up.
+#[cfg(unix)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct GreetingTiming {
+    min: Duration,
+    max: Duration,
+}
+
+#[cfg(unix)]
+impl GreetingTiming {
+    fn from_config(greeting: &config::GreetingScreenConfig) -> Self {
+        Self {
+            min: greeting.effective_min_duration(),
+            max: greeting.effective_duration(),
+        }
+    }
+}
+
+/// Waits out the greeting. Returns `false` when cancelled first.
+#[cfg(unix)]
+async fn wait_for_greeting(
+    cancel: &CancellationToken,
+    timing: GreetingTiming,
+    mut photo_ready: watch::Receiver<bool>,
+) -> bool {
+    if timing.max.is_zero() {
+        return !cancel.is_cancelled();
+    }
+    tracing::debug!(
+        min = %humantime::format_duration(timing.min),
+        max = %humantime::format_duration(timing.max),
+        "waiting for greeting preamble before applying schedule",
+    );
+    let started = tokio::time::Instant::now();
+    tokio::select! {
+        _ = cancel.cancelled() => return false,
+        _ = tokio::time::sleep(timing.min) => {}
+    }
+    let deadline = tokio::time::sleep_until(started + timing.max);
+    tokio::pin!(deadline);
+    tokio::select! {
+        _ = cancel.cancelled() => return false,
+        ready = photo_ready.wait_for(|ready| *ready) => {
+            if ready.is_ok() {
+                tracing::debug!(
+                    elapsed_ms = started.elapsed().as_millis() as u64,
+                    "greeting_ended_on_first_photo"
+                );
+            } else {
+                // The viewer is gone; fall back to the fixed duration.
+                tokio::select! {
+                    _ = cancel.cancelled() => return false,
+                    _ = &mut deadline => {}
+                }
+            }
+        }
+        _ = &mut deadline => {
+            tracing::info!(
+                max = %humantime::format_duration(timing.max),
+                "no photo ready before greeting-screen.duration-seconds; waking anyway"
+            );
         }
     }
+    !cancel.is_cancelled()
+}
 
-    if cancel.is_cancelled() {
+#[cfg(unix)]
+async fn run_initial_schedule_preamble(
+    cancel: &CancellationToken,
+    control: &mpsc::Sender<ViewerCommand>,
+    greeting: GreetingTiming,
+    photo_ready: watch::Receiver<bool>,
+    schedule: Option<&config::AwakeScheduleConfig>,
+) -> Result<()> {
+    if !wait_for_greeting(cancel, greeting, photo_ready).await {
         return Ok(());
     }
 
@@ -468,7 +1356,10 @@ async fn run_initial_schedule_preamble(
         "control preamble sending initial viewer state"
     );
     control
-        .send(ViewerCommand::SetState(target_state))
+        .send(ViewerCommand::SetState {
+            state: target_state,
+            source: StateSource::Scheduled,
+        })
         .await
         .context("failed to send initial viewer state")?;
 
@@ -481,10 +1372,10 @@ fn scheduled_viewer_state(
     now: DateTime<Utc>,
 ) -> ViewerState {
     let local_now = now.with_timezone(&schedule.timezone());
-    if schedule.is_awake_at(local_now) {
-        ViewerState::Awake
-    } else {
-        ViewerState::Asleep
+    match schedule.evaluate(local_now).state {
+        config::ScheduledState::Awake => ViewerState::Awake,
+        config::ScheduledState::Ambient => ViewerState::Ambient,
+        config::ScheduledState::Asleep => ViewerState::Asleep,
     }
 }
 
@@ -533,27 +1424,254 @@ awake-scheduled:
             ViewerState::Awake
         );
     }
-}
-
-#[cfg(unix)]
-async fn handle_control_connection(
-    mut stream: tokio::net::UnixStream,
-    control: mpsc::Sender<ViewerCommand>,
-) -> Result<()> {
-    let mut buf = Vec::with_capacity(128);
-    stream
-        .read_to_end(&mut buf)
-        .await
-        .context("failed to read control command")?;
 
-    if buf.is_empty() {
-        tracing::debug!("ignoring empty control payload");
-        return Ok(());
-    }
+    #[test]
+    fn scheduled_viewer_state_reports_ambient_windows() {
+        let yaml = r#"
+timezone: "UTC"
+awake-scheduled:
+  daily:
+    - ["07:00", "22:00"]
+  ambient:
+    daily:
+      - ["22:00", "23:30"]
+"#;
+        let schedule: config::AwakeScheduleConfig =
+            serde_yaml::from_str(yaml).expect("valid schedule");
+        let at = |h, m| {
+            Utc.with_ymd_and_hms(2024, 5, 1, h, m, 0)
+                .earliest()
+                .unwrap()
+        };
+
+        assert_eq!(
+            scheduled_viewer_state(&schedule, at(12, 0)),
+            ViewerState::Awake
+        );
+        assert_eq!(
+            scheduled_viewer_state(&schedule, at(22, 30)),
+            ViewerState::Ambient
+        );
+        assert_eq!(
+            scheduled_viewer_state(&schedule, at(23, 45)),
+            ViewerState::Asleep
+        );
+    }
+
+    const GREETING: GreetingTiming = GreetingTiming {
+        min: Duration::from_secs(1),
+        max: Duration::from_secs(4),
+    };
+
+    /// How long the greeting lasts when the first photo is ready after
+    /// `photo_after` (never, for `None`), on tokio's paused clock.
+    async fn greeting_length(photo_after: Option<Duration>) -> Duration {
+        let (ready_tx, ready_rx) = watch::channel(false);
+        if let Some(delay) = photo_after {
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                ready_tx.send_replace(true);
+                // Keep the sender alive so the wait sees a live viewer.
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            });
+        } else {
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                drop(ready_tx);
+            });
+        }
+        let started = tokio::time::Instant::now();
+        assert!(wait_for_greeting(&CancellationToken::new(), GREETING, ready_rx).await);
+        started.elapsed()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn greeting_holds_for_its_minimum_when_a_photo_is_ready_early() {
+        let length = greeting_length(Some(Duration::from_millis(200))).await;
+        assert_eq!(length, GREETING.min);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn greeting_ends_when_the_first_photo_is_ready() {
+        let length = greeting_length(Some(Duration::from_millis(2500))).await;
+        assert_eq!(length, Duration::from_millis(2500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn greeting_gives_up_at_its_cap_without_a_photo() {
+        assert_eq!(
+            greeting_length(Some(Duration::from_secs(6))).await,
+            GREETING.max
+        );
+        assert_eq!(greeting_length(None).await, GREETING.max);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn greeting_wait_stops_on_cancel() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let (_ready_tx, ready_rx) = watch::channel(false);
+        assert!(!wait_for_greeting(&cancel, GREETING, ready_rx).await);
+    }
+}
+
+#[cfg(unix)]
+async fn handle_control_connection(
+    mut stream: tokio::net::UnixStream,
+    control: mpsc::Sender<ViewerCommand>,
+    policy: Arc<ControlPeerPolicy>,
+    status: ControlStatus,
+    config_path: &std::path::Path,
+    manual_override: &Mutex<ManualOverride>,
+) -> Result<()> {
+    if policy.is_restricted() {
+        match stream.peer_cred() {
+            Ok(cred) if policy.permits(cred.uid(), cred.gid()) => {}
+            Ok(cred) => {
+                tracing::warn!(
+                    uid = cred.uid(),
+                    gid = cred.gid(),
+                    pid = ?cred.pid(),
+                    "rejected control command from unlisted peer"
+                );
+                refuse_control_peer(&mut stream, "peer is not allowed to control the frame").await;
+                return Ok(());
+            }
+            Err(err) => {
+                tracing::warn!("rejected control command; peer credentials unavailable: {err}");
+                refuse_control_peer(&mut stream, "peer credentials unavailable").await;
+                return Ok(());
+            }
+        }
+    }
+
+    handle_control_lines(
+        &mut stream,
+        control,
+        status,
+        config_path,
+        manual_override,
+        None,
+    )
+    .await
+}
+
+/// Largest command accepted on one line, over the socket or TCP.
+#[cfg(unix)]
+const MAX_CONTROL_LINE: usize = 64 * 1024;
+
+/// How long a refused peer gets to finish sending before it is answered.
+#[cfg(unix)]
+const REFUSAL_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Answers a peer that may not control the frame. What it sent is read and
+/// dropped first: closing with unread input resets the connection, and the
+/// peer would lose the error.
+#[cfg(unix)]
+async fn refuse_control_peer<S>(stream: &mut S, message: &str)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let mut discarded = Vec::new();
+    let mut limited = (&mut *stream).take(MAX_CONTROL_LINE as u64);
+    let _ = tokio::time::timeout(REFUSAL_DRAIN_TIMEOUT, limited.read_to_end(&mut discarded)).await;
+    reply_control_error(stream, message).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Reads newline-delimited JSON commands from `stream` and carries out each
+/// as its line arrives, so a long-lived client can send many on one
+/// connection. A last command without a trailing newline is carried out when
+/// the client closes its side, which keeps `echo ... | socat` working. The
+/// write side is closed once the client's is, after the last reply.
+#[cfg(unix)]
+async fn handle_control_lines<S>(
+    stream: &mut S,
+    control: mpsc::Sender<ViewerCommand>,
+    status: ControlStatus,
+    config_path: &std::path::Path,
+    manual_override: &Mutex<ManualOverride>,
+    idle_timeout: Option<Duration>,
+) -> Result<()>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let mut pending = Vec::with_capacity(128);
+    let mut chunk = [0u8; 4096];
+    loop {
+        while let Some(newline) = pending.iter().position(|byte| *byte == b'\n') {
+            let line: Vec<u8> = pending.drain(..=newline).collect();
+            handle_control_request(
+                stream,
+                line.trim_ascii(),
+                control.clone(),
+                status.clone(),
+                config_path,
+                manual_override,
+            )
+            .await?;
+        }
+        if pending.len() > MAX_CONTROL_LINE {
+            tracing::warn!(
+                limit = MAX_CONTROL_LINE,
+                "control command too long; closing connection"
+            );
+            reply_control_error(
+                stream,
+                &format!("control command exceeds {MAX_CONTROL_LINE} bytes"),
+            )
+            .await;
+            let _ = stream.shutdown().await;
+            return Ok(());
+        }
+        let read = match idle_timeout {
+            Some(idle) => tokio::time::timeout(idle, stream.read(&mut chunk))
+                .await
+                .context("control connection went idle")?,
+            None => stream.read(&mut chunk).await,
+        }
+        .context("failed to read control command")?;
+        if read == 0 {
+            break;
+        }
+        pending.extend_from_slice(&chunk[..read]);
+    }
 
-    let payload_text = String::from_utf8_lossy(&buf);
+    handle_control_request(
+        stream,
+        pending.trim_ascii(),
+        control,
+        status,
+        config_path,
+        manual_override,
+    )
+    .await?;
+    let _ = stream.shutdown().await;
+    Ok(())
+}
 
-    let request: ControlCommand = match serde_json::from_slice(&buf) {
+/// Carries out one JSON command read from the Unix socket or the TCP
+/// listener, writing any reply to `stream`.
+#[cfg(unix)]
+async fn handle_control_request<S>(
+    stream: &mut S,
+    buf: &[u8],
+    control: mpsc::Sender<ViewerCommand>,
+    status: ControlStatus,
+    config_path: &std::path::Path,
+    manual_override: &Mutex<ManualOverride>,
+) -> Result<()>
+where
+    S: tokio::io::AsyncWrite + Unpin,
+{
+    if buf.is_empty() {
+        tracing::debug!("ignoring empty control payload");
+        return Ok(());
+    }
+
+    let payload_text = String::from_utf8_lossy(buf);
+
+    let request: ControlCommand = match serde_json::from_slice(buf) {
         Ok(command) => command,
         Err(err) => {
             tracing::warn!(payload = %payload_text, "invalid control payload: {err}");
@@ -564,30 +1682,393 @@ async fn handle_control_connection(
     match request {
         ControlCommand::ToggleState => {
             tracing::info!(command = "toggle-state", "received control command");
+            // Ambient counts as off: a toggle from it brings the frame fully awake.
+            let toggled = match status.viewer.lock().map(|viewer| viewer.state) {
+                Ok(ViewerState::Asleep | ViewerState::Ambient) => ViewerState::Awake,
+                _ => ViewerState::Asleep,
+            };
+            manual_override
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .record_manual(toggled, Instant::now());
             control
                 .send(ViewerCommand::ToggleState)
                 .await
                 .context("failed to forward toggle-state command")?;
         }
-        ControlCommand::SetState { state } => {
+        ControlCommand::SetState { state, source } => {
             let viewer_state: ViewerState = state.into();
             tracing::info!(
                 command = "set-state",
                 ?viewer_state,
+                ?source,
                 "received control command"
             );
+            let admitted = {
+                let mut manual_override = manual_override
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner);
+                let now = Instant::now();
+                match source {
+                    StateSource::Manual => {
+                        manual_override.record_manual(viewer_state, now);
+                        Ok(())
+                    }
+                    StateSource::Scheduled => manual_override.admit_scheduled(viewer_state, now),
+                }
+            };
+            if let Err(remaining) = admitted {
+                // Rounded up, so a caller retrying after this long finds it over.
+                let remaining = Duration::from_secs(remaining.as_millis().div_ceil(1000) as u64);
+                tracing::info!(
+                    ?viewer_state,
+                    remaining = %humantime::format_duration(remaining),
+                    "scheduled set-state held off by a manual override"
+                );
+                let mut reply = serde_json::json!({
+                    "status": "error",
+                    "error": "manual override active",
+                    "override_remaining_seconds": remaining.as_secs(),
+                })
+                .to_string();
+                reply.push('\n');
+                let _ = stream.write_all(reply.as_bytes()).await;
+                return Ok(());
+            }
             control
-                .send(ViewerCommand::SetState(viewer_state))
+                .send(ViewerCommand::SetState {
+                    state: viewer_state,
+                    source,
+                })
                 .await
                 .context("failed to forward set-state command")?;
         }
+        ControlCommand::Next => {
+            tracing::info!(command = "next", "received control command");
+            control
+                .send(ViewerCommand::Next)
+                .await
+                .context("failed to forward next command")?;
+        }
+        ControlCommand::Previous => {
+            tracing::info!(command = "previous", "received control command");
+            control
+                .send(ViewerCommand::Previous)
+                .await
+                .context("failed to forward previous command")?;
+        }
+        ControlCommand::Pause { reason } => {
+            tracing::info!(command = "pause", ?reason, "received control command");
+            control
+                .send(ViewerCommand::Pause {
+                    reason: reason.clone(),
+                })
+                .await
+                .context("failed to forward pause command")?;
+            // A reason marks a maintenance pause: the library is held until resume.
+            if reason.is_some() {
+                status.library_hold.send_replace(reason);
+            }
+        }
+        ControlCommand::Resume => {
+            tracing::info!(command = "resume", "received control command");
+            control
+                .send(ViewerCommand::Resume)
+                .await
+                .context("failed to forward resume command")?;
+            status.library_hold.send_replace(None);
+        }
+        ControlCommand::Status => {
+            tracing::debug!(command = "status", "received control command");
+            let mut reply = status.to_json().to_string();
+            reply.push('\n');
+            stream
+                .write_all(reply.as_bytes())
+                .await
+                .context("failed to write status reply")?;
+        }
+        ControlCommand::History { limit } => {
+            tracing::debug!(command = "history", ?limit, "received control command");
+            let mut reply = status.history_json(limit).to_string();
+            reply.push('\n');
+            stream
+                .write_all(reply.as_bytes())
+                .await
+                .context("failed to write history reply")?;
+        }
+        ControlCommand::Metrics => {
+            tracing::debug!(command = "metrics", "received control command");
+            let mut reply = status.metrics_json().to_string();
+            reply.push('\n');
+            stream
+                .write_all(reply.as_bytes())
+                .await
+                .context("failed to write metrics reply")?;
+        }
+        ControlCommand::QuarantineList => {
+            tracing::debug!(command = "quarantine-list", "received control command");
+            let mut reply = status.quarantine_json().to_string();
+            reply.push('\n');
+            stream
+                .write_all(reply.as_bytes())
+                .await
+                .context("failed to write quarantine reply")?;
+        }
+        ControlCommand::Notice { text, seconds } => {
+            tracing::info!(command = "notice", %text, seconds, "received control command");
+            control
+                .send(ViewerCommand::Notice {
+                    text,
+                    duration: Duration::from_secs(seconds),
+                })
+                .await
+                .context("failed to forward notice command")?;
+        }
+        ControlCommand::DebugOverlay { enabled } => {
+            tracing::info!(
+                command = "debug-overlay",
+                enabled,
+                "received control command"
+            );
+            control
+                .send(ViewerCommand::DebugOverlay(enabled))
+                .await
+                .context("failed to forward debug-overlay command")?;
+        }
+        ControlCommand::ReloadConfig => {
+            tracing::info!(
+                command = "reload-config",
+                path = %config_path.display(),
+                "received control command"
+            );
+            // Parse and validate here so a bad edit is reported to the caller
+            // and the viewer keeps running on the configuration it has.
+            let reloaded = match load_config(config_path) {
+                Ok(cfg) => cfg,
+                Err(err) => {
+                    tracing::warn!("config reload rejected: {err:#}");
+                    reply_control_error(stream, &format!("{err:#}")).await;
+                    return Ok(());
+                }
+            };
+            let command = ViewerCommand::ReloadConfig(ReloadedConfig(Arc::new(reloaded)));
+            control
+                .send(command)
+                .await
+                .context("failed to forward reload-config command")?;
+            let _ = stream.write_all(b"{\"status\":\"ok\"}\n").await;
+        }
     }
 
     Ok(())
 }
 
+/// Sends `{"status":"error",...}` back to a client. Clients that do not read
+/// a reply have usually hung up already, so write errors are ignored.
 #[cfg(unix)]
+async fn reply_control_error<S>(stream: &mut S, message: &str)
+where
+    S: tokio::io::AsyncWrite + Unpin,
+{
+    let mut reply = serde_json::json!({ "status": "error", "error": message }).to_string();
+    reply.push('\n');
+    let _ = stream.write_all(reply.as_bytes()).await;
+}
+
+#[cfg(all(test, unix))]
 mod tests {
+    use super::{
+        ControlPeerPolicy, ControlStatus, ManualOverride, StateSource, ViewerCommand, ViewerState,
+        ViewerStatus, bind_control_socket, handle_control_connection,
+    };
+    use crate::config::QuarantineConfig;
+    use crate::metrics::RuntimeMetrics;
+    use crate::tasks::history::{HistoryEntry, PhotoHistory};
+    use crate::tasks::quarantine::Quarantine;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant, SystemTime};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixStream;
+    use tokio::sync::{mpsc, watch};
+
+    /// Sends `payload` over a socketpair to a handler running `policy` and
+    /// returns what reached the viewer plus the reply bytes.
+    async fn exchange(
+        policy: ControlPeerPolicy,
+        payload: &[u8],
+    ) -> (Option<ViewerCommand>, Vec<u8>) {
+        let status = status_of(ViewerStatus::default());
+        exchange_with(policy, payload, status, Path::new(NO_CONFIG)).await
+    }
+
+    const NO_CONFIG: &str = "/nonexistent/photoframe/config.yaml";
+
+    async fn exchange_with(
+        policy: ControlPeerPolicy,
+        payload: &[u8],
+        status: ControlStatus,
+        config_path: &Path,
+    ) -> (Option<ViewerCommand>, Vec<u8>) {
+        let manual_override = Mutex::new(ManualOverride::default());
+        exchange_under(policy, payload, status, config_path, &manual_override).await
+    }
+
+    async fn exchange_under(
+        policy: ControlPeerPolicy,
+        payload: &[u8],
+        status: ControlStatus,
+        config_path: &Path,
+        manual_override: &Mutex<ManualOverride>,
+    ) -> (Option<ViewerCommand>, Vec<u8>) {
+        let (mut client, server) = UnixStream::pair().expect("socketpair");
+        let (tx, mut rx) = mpsc::channel(1);
+        client.write_all(payload).await.unwrap();
+        client.shutdown().await.unwrap();
+        let policy = Arc::new(policy);
+        handle_control_connection(server, tx, policy, status, config_path, manual_override)
+            .await
+            .expect("handler");
+        let mut reply = Vec::new();
+        client.read_to_end(&mut reply).await.unwrap();
+        (rx.try_recv().ok(), reply)
+    }
+
+    fn status_of(viewer: ViewerStatus) -> ControlStatus {
+        ControlStatus {
+            viewer: Arc::new(Mutex::new(viewer)),
+            started_at: Instant::now(),
+            history: Arc::new(Mutex::new(PhotoHistory::new(10))),
+            metrics: RuntimeMetrics::shared(),
+            quarantine: Quarantine::shared(&QuarantineConfig::default()),
+            library_hold: Arc::new(watch::channel(None).0),
+        }
+    }
+
+    fn own_ids() -> (u32, u32) {
+        let (_a, b) = UnixStream::pair().expect("socketpair");
+        let cred = b.peer_cred().expect("peer credentials");
+        (cred.uid(), cred.gid())
+    }
+
+    #[test]
+    fn peer_policy_matches_uid_or_gid() {
+        assert!(ControlPeerPolicy::default().permits(1234, 1234));
+        let policy = ControlPeerPolicy {
+            allowed_uids: Some(vec![1000]),
+            allowed_gids: Some(vec![44]),
+        };
+        assert!(policy.permits(1000, 1));
+        assert!(policy.permits(1, 44));
+        assert!(!policy.permits(1, 1));
+    }
+
+    #[tokio::test]
+    async fn listed_uid_is_accepted() {
+        let (uid, _) = own_ids();
+        let policy = ControlPeerPolicy {
+            allowed_uids: Some(vec![uid]),
+            allowed_gids: None,
+        };
+        let (command, reply) = exchange(policy, br#"{"command":"toggle-state"}"#).await;
+        assert!(matches!(command, Some(ViewerCommand::ToggleState)));
+        assert!(reply.is_empty());
+    }
+
+    #[tokio::test]
+    async fn listed_gid_is_accepted() {
+        let (uid, gid) = own_ids();
+        let policy = ControlPeerPolicy {
+            allowed_uids: Some(vec![uid.wrapping_add(1)]),
+            allowed_gids: Some(vec![gid]),
+        };
+        let (command, _) = exchange(policy, br#"{"command":"toggle-state"}"#).await;
+        assert!(matches!(command, Some(ViewerCommand::ToggleState)));
+    }
+
+    #[tokio::test]
+    async fn newline_delimited_commands_are_forwarded_in_turn() {
+        let (mut client, server) = UnixStream::pair().expect("socketpair");
+        let (tx, mut rx) = mpsc::channel(4);
+        let manual_override = Mutex::new(ManualOverride::default());
+        let handler = tokio::spawn(async move {
+            let status = status_of(ViewerStatus::default());
+            let policy = Arc::new(ControlPeerPolicy::default());
+            let config_path = Path::new(NO_CONFIG);
+            handle_control_connection(server, tx, policy, status, config_path, &manual_override)
+                .await
+        });
+
+        client
+            .write_all(b"{\"command\":\"next\"}\n{\"command\":\"pause\"}\n")
+            .await
+            .unwrap();
+        // Each line is carried out as it arrives, before the client is done.
+        assert!(matches!(rx.recv().await, Some(ViewerCommand::Next)));
+        assert!(matches!(
+            rx.recv().await,
+            Some(ViewerCommand::Pause { reason: None })
+        ));
+        client
+            .write_all(b"{\"command\":\"resume\"}\n")
+            .await
+            .unwrap();
+        client.shutdown().await.unwrap();
+        handler.await.unwrap().expect("handler");
+
+        assert!(matches!(rx.recv().await, Some(ViewerCommand::Resume)));
+        assert!(rx.recv().await.is_none(), "exactly three commands");
+    }
+
+    #[tokio::test]
+    async fn an_overlong_line_is_refused() {
+        let mut payload = vec![b' '; super::MAX_CONTROL_LINE + 1];
+        payload.extend_from_slice(br#"{"command":"next"}"#);
+        let (command, reply) = exchange(ControlPeerPolicy::default(), &payload).await;
+        assert!(command.is_none());
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["status"], "error");
+    }
+
+    #[tokio::test]
+    async fn an_unwritable_socket_directory_falls_back_to_the_runtime_dir() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let locked = dir.path().join("run");
+        std::fs::create_dir(&locked).unwrap();
+        std::fs::set_permissions(&locked, std::fs::Permissions::from_mode(0o555)).unwrap();
+        if std::fs::create_dir(locked.join("probe")).is_ok() {
+            eprintln!("skipping socket fallback test: permissions are not enforced for this user");
+            return;
+        }
+        let primary = locked.join("photo-frame/control.sock");
+        let runtime_dir = dir.path().join("user");
+        let fallback =
+            config_model::fallback_control_socket_path(Some(runtime_dir.as_os_str())).unwrap();
+        assert_eq!(fallback, runtime_dir.join("photo-frame/control.sock"));
+
+        let (_listener, path) = bind_control_socket(&primary, Some(fallback.clone())).unwrap();
+        assert_eq!(path, fallback);
+        assert!(fallback.exists(), "the socket is bound at the fallback");
+        assert!(
+            bind_control_socket(&primary, None).is_err(),
+            "without a runtime dir the error stands"
+        );
+    }
+
+    #[tokio::test]
+    async fn unlisted_peer_is_rejected_with_error_json() {
+        let (uid, gid) = own_ids();
+        let policy = ControlPeerPolicy {
+            allowed_uids: Some(vec![uid.wrapping_add(1)]),
+            allowed_gids: Some(vec![gid.wrapping_add(1)]),
+        };
+        let (command, reply) = exchange(policy, br#"{"command":"toggle-state"}"#).await;
+        assert!(command.is_none(), "rejected peer must not reach the viewer");
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["status"], "error");
+    }
+
     #[test]
     fn deserialize_toggle_state_command() {
         let cmd: super::ControlCommand = serde_json::from_str(r#"{"command":"toggle-state"}"#)
@@ -604,7 +2085,8 @@ mod tests {
         assert_eq!(
             cmd,
             super::ControlCommand::SetState {
-                state: super::ControlState::Awake
+                state: super::ControlState::Awake,
+                source: StateSource::Manual,
             }
         );
     }
@@ -618,9 +2100,564 @@ mod tests {
         assert_eq!(
             cmd,
             super::ControlCommand::SetState {
-                state: super::ControlState::Asleep
+                state: super::ControlState::Asleep,
+                source: StateSource::Manual,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_set_state_ambient() {
+        let cmd: super::ControlCommand = serde_json::from_str(
+            r#"{"command":"set-state","state":"ambient","source":"scheduled"}"#,
+        )
+        .expect("set-state should parse");
+        assert_eq!(
+            cmd,
+            super::ControlCommand::SetState {
+                state: super::ControlState::Ambient,
+                source: StateSource::Scheduled,
+            }
+        );
+        assert_eq!(
+            ViewerState::from(super::ControlState::Ambient),
+            ViewerState::Ambient
+        );
+    }
+
+    #[test]
+    fn deserialize_set_state_with_source() {
+        let cmd: super::ControlCommand = serde_json::from_str(
+            r#"{"command":"set-state","state":"asleep","source":"scheduled"}"#,
+        )
+        .expect("set-state should parse");
+        assert_eq!(
+            cmd,
+            super::ControlCommand::SetState {
+                state: super::ControlState::Asleep,
+                source: StateSource::Scheduled,
+            }
+        );
+    }
+
+    #[test]
+    fn manual_wake_before_a_scheduled_sleep_holds_for_the_override_window() {
+        let window = Duration::from_secs(30 * 60);
+        let mut manual_override = ManualOverride::new(Some(window));
+        let woken = Instant::now();
+        manual_override.record_manual(ViewerState::Awake, woken);
+
+        // The schedule's sleep boundary arrives two minutes later and keeps
+        // being retried; every attempt inside the window is refused.
+        let boundary = woken + Duration::from_secs(2 * 60);
+        assert_eq!(
+            manual_override.admit_scheduled(ViewerState::Asleep, boundary),
+            Err(window - Duration::from_secs(2 * 60))
+        );
+        let last_minute = woken + window - Duration::from_secs(60);
+        assert!(
+            manual_override
+                .admit_scheduled(ViewerState::Asleep, last_minute)
+                .is_err()
+        );
+
+        // Once the window has run out the schedule takes over again.
+        assert_eq!(
+            manual_override.admit_scheduled(ViewerState::Asleep, woken + window),
+            Ok(())
+        );
+        assert_eq!(
+            manual_override.admit_scheduled(ViewerState::Awake, woken + window),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn override_ends_when_the_schedule_comes_round_to_it() {
+        let mut manual_override = ManualOverride::new(Some(Duration::from_secs(3600)));
+        let slept = Instant::now();
+        manual_override.record_manual(ViewerState::Asleep, slept);
+        let later = slept + Duration::from_secs(60);
+        assert!(
+            manual_override
+                .admit_scheduled(ViewerState::Awake, later)
+                .is_err()
+        );
+        // The schedule reaches its own sleep boundary: the override is spent.
+        assert_eq!(
+            manual_override.admit_scheduled(ViewerState::Asleep, later),
+            Ok(())
+        );
+        assert_eq!(
+            manual_override.admit_scheduled(ViewerState::Awake, later),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn manual_changes_never_hold_without_a_window() {
+        let mut manual_override = ManualOverride::new(None);
+        let now = Instant::now();
+        manual_override.record_manual(ViewerState::Awake, now);
+        assert_eq!(
+            manual_override.admit_scheduled(ViewerState::Asleep, now),
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn scheduled_set_state_is_refused_during_a_manual_override() {
+        let manual_override = Mutex::new(ManualOverride::new(Some(Duration::from_secs(1800))));
+        let send = |payload: &'static [u8]| {
+            exchange_under(
+                ControlPeerPolicy::default(),
+                payload,
+                status_of(ViewerStatus::default()),
+                Path::new(NO_CONFIG),
+                &manual_override,
+            )
+        };
+
+        let (command, _) = send(br#"{"command":"set-state","state":"awake"}"#).await;
+        assert!(matches!(
+            command,
+            Some(ViewerCommand::SetState {
+                state: ViewerState::Awake,
+                source: StateSource::Manual,
+            })
+        ));
+
+        let scheduled = br#"{"command":"set-state","state":"asleep","source":"scheduled"}"#;
+        let (command, reply) = send(scheduled).await;
+        assert!(
+            command.is_none(),
+            "a held-off sleep must not reach the viewer"
+        );
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["status"], "error");
+        let remaining = reply["override_remaining_seconds"].as_u64().unwrap();
+        assert!((1790..=1800).contains(&remaining), "{remaining}");
+    }
+
+    #[test]
+    fn deserialize_next_and_previous() {
+        let next: super::ControlCommand =
+            serde_json::from_str(r#"{"command":"next"}"#).expect("next should parse");
+        assert_eq!(next, super::ControlCommand::Next);
+        let previous: super::ControlCommand =
+            serde_json::from_str(r#"{"command":"previous"}"#).expect("previous should parse");
+        assert_eq!(previous, super::ControlCommand::Previous);
+        serde_json::from_str::<super::ControlCommand>(r#"{"command":"prev"}"#)
+            .expect_err("only the full name is accepted");
+    }
+
+    #[test]
+    fn deserialize_pause_and_resume() {
+        let pause: super::ControlCommand =
+            serde_json::from_str(r#"{"command":"pause"}"#).expect("pause should parse");
+        assert_eq!(pause, super::ControlCommand::Pause { reason: None });
+        let sync: super::ControlCommand =
+            serde_json::from_str(r#"{"command":"pause","reason":"sync"}"#)
+                .expect("pause with a reason should parse");
+        assert_eq!(
+            sync,
+            super::ControlCommand::Pause {
+                reason: Some("sync".into())
+            }
+        );
+        let resume: super::ControlCommand =
+            serde_json::from_str(r#"{"command":"resume"}"#).expect("resume should parse");
+        assert_eq!(resume, super::ControlCommand::Resume);
+    }
+
+    #[test]
+    fn deserialize_notice() {
+        let notice: super::ControlCommand = serde_json::from_str(
+            r#"{"command":"notice","text":"Going to sleep in 5 minutes","seconds":300}"#,
+        )
+        .expect("notice should parse");
+        assert_eq!(
+            notice,
+            super::ControlCommand::Notice {
+                text: "Going to sleep in 5 minutes".into(),
+                seconds: 300,
             }
         );
+        serde_json::from_str::<super::ControlCommand>(r#"{"command":"notice","text":"hi"}"#)
+            .expect_err("a notice needs a duration");
+    }
+
+    #[test]
+    fn deserialize_debug_overlay() {
+        let on: super::ControlCommand =
+            serde_json::from_str(r#"{"command":"debug-overlay","enabled":true}"#)
+                .expect("debug-overlay should parse");
+        assert_eq!(on, super::ControlCommand::DebugOverlay { enabled: true });
+        serde_json::from_str::<super::ControlCommand>(r#"{"command":"debug-overlay"}"#)
+            .expect_err("debug-overlay needs `enabled`");
+    }
+
+    #[tokio::test]
+    async fn status_replies_with_the_published_snapshot() {
+        let mut status = status_of(ViewerStatus {
+            state: ViewerState::Asleep,
+            current_photo: Some(PathBuf::from("/photos/beach.jpg")),
+            queue_depth: 2,
+            paused: true,
+        });
+        status.started_at -= Duration::from_secs(90);
+
+        let payload = br#"{"command":"status"}"#;
+        let (command, reply) = exchange_with(
+            ControlPeerPolicy::default(),
+            payload,
+            status,
+            Path::new(NO_CONFIG),
+        )
+        .await;
+
+        assert!(command.is_none(), "status is answered without the viewer");
+        assert!(reply.ends_with(b"\n"), "reply is one JSON line");
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["status"], "ok");
+        assert_eq!(reply["state"], "asleep");
+        assert_eq!(reply["current_photo"], "/photos/beach.jpg");
+        assert_eq!(reply["queue_depth"], 2);
+        assert_eq!(reply["paused"], true);
+        assert!(reply["uptime_seconds"].as_u64().unwrap() >= 90);
+    }
+
+    #[tokio::test]
+    async fn history_lists_recent_photos_newest_first() {
+        let status = status_of(ViewerStatus::default());
+        {
+            let mut history = status.history.lock().unwrap();
+            for (n, name) in ["a.jpg", "b.jpg", "c.jpg"].into_iter().enumerate() {
+                let shown_at = SystemTime::UNIX_EPOCH + Duration::from_secs(60 * n as u64);
+                history.push(HistoryEntry::new(Path::new("/photos").join(name), shown_at));
+            }
+        }
+
+        let payload = br#"{"command":"history","limit":2}"#;
+        let (command, reply) = exchange_with(
+            ControlPeerPolicy::default(),
+            payload,
+            status.clone(),
+            Path::new(NO_CONFIG),
+        )
+        .await;
+        assert!(command.is_none(), "history is answered without the viewer");
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["status"], "ok");
+        assert_eq!(
+            reply["history"],
+            serde_json::json!([
+                { "shown_at": "1970-01-01T00:02:00Z", "path": "/photos/c.jpg" },
+                { "shown_at": "1970-01-01T00:01:00Z", "path": "/photos/b.jpg" },
+            ])
+        );
+
+        let payload = br#"{"command":"history"}"#;
+        let (_, reply) = exchange_with(
+            ControlPeerPolicy::default(),
+            payload,
+            status,
+            Path::new(NO_CONFIG),
+        )
+        .await;
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["history"].as_array().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn metrics_report_recorded_timings() {
+        let status = status_of(ViewerStatus::default());
+        RuntimeMetrics::update(&status.metrics, |m| {
+            m.decode.record(Duration::from_millis(120));
+            m.decode.record(Duration::from_millis(80));
+            m.effect.record(Duration::from_millis(15));
+            m.record_frame(None, Duration::from_millis(16));
+            m.record_frame(Some(Duration::from_millis(50)), Duration::from_millis(16));
+        });
+
+        let (command, reply) = exchange_with(
+            ControlPeerPolicy::default(),
+            br#"{"command":"metrics"}"#,
+            status,
+            Path::new(NO_CONFIG),
+        )
+        .await;
+        assert!(command.is_none(), "metrics are answered without the viewer");
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["status"], "ok");
+        assert_eq!(
+            reply["decode"],
+            serde_json::json!({ "count": 2, "avg_ms": 100.0, "max_ms": 120.0 })
+        );
+        assert_eq!(reply["effect"]["avg_ms"], 15.0);
+        assert_eq!(reply["transition"]["count"], 0);
+        assert_eq!(reply["frames_rendered"], 2);
+        assert_eq!(reply["frames_dropped"], 2);
+    }
+
+    #[tokio::test]
+    async fn quarantine_list_reports_quarantined_photos() {
+        let status = status_of(ViewerStatus::default());
+        {
+            let mut quarantine = status.quarantine.lock().unwrap();
+            let broken = Path::new("/photos/broken.jpg");
+            for _ in 0..3 {
+                quarantine.record_failure(broken, SystemTime::now());
+            }
+            quarantine.record_failure(Path::new("/photos/flaky.jpg"), SystemTime::now());
+        }
+
+        let (command, reply) = exchange_with(
+            ControlPeerPolicy::default(),
+            br#"{"command":"quarantine-list"}"#,
+            status,
+            Path::new(NO_CONFIG),
+        )
+        .await;
+        assert!(
+            command.is_none(),
+            "the quarantine is answered without the viewer"
+        );
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["status"], "ok");
+        let listed = reply["quarantine"].as_array().unwrap();
+        assert_eq!(listed.len(), 1, "{reply}");
+        assert_eq!(listed[0]["path"], "/photos/broken.jpg");
+        assert_eq!(listed[0]["failures"], 3);
+        assert!(listed[0]["until"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[tokio::test]
+    async fn status_before_the_first_photo_has_no_current_photo() {
+        let (_, reply) = exchange(ControlPeerPolicy::default(), br#"{"command":"status"}"#).await;
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["state"], "awake");
+        assert!(reply["current_photo"].is_null());
+        assert_eq!(reply["queue_depth"], 0);
+    }
+
+    #[tokio::test]
+    async fn reload_config_forwards_a_valid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(
+            &path,
+            "photo-library-path: /photos\nglobal-photo-settings:\n  dwell-ms: 12000\n",
+        )
+        .unwrap();
+
+        let payload = br#"{"command":"reload-config"}"#;
+        let status = status_of(ViewerStatus::default());
+        let (command, reply) =
+            exchange_with(ControlPeerPolicy::default(), payload, status, &path).await;
+
+        let Some(ViewerCommand::ReloadConfig(reloaded)) = command else {
+            panic!("expected ReloadConfig, got {command:?}");
+        };
+        assert_eq!(reloaded.0.global_photo_settings.dwell_ms, 12_000);
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["status"], "ok");
+    }
+
+    #[tokio::test]
+    async fn reload_config_rejects_an_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(&path, "global-photo-settings:\n  dwell-ms: 0\n").unwrap();
+
+        let payload = br#"{"command":"reload-config"}"#;
+        let status = status_of(ViewerStatus::default());
+        let (command, reply) =
+            exchange_with(ControlPeerPolicy::default(), payload, status, &path).await;
+
+        assert!(
+            command.is_none(),
+            "an invalid config must not reach the viewer"
+        );
+        let reply: serde_json::Value = serde_json::from_slice(&reply).expect("json reply");
+        assert_eq!(reply["status"], "error");
+        assert!(reply["error"].as_str().unwrap().contains("dwell-ms"));
+    }
+
+    /// Sends `payload` to a TCP control listener on loopback and returns what
+    /// reached the viewer plus the reply bytes.
+    async fn exchange_over_tcp(payload: &[u8]) -> (Option<ViewerCommand>, Vec<u8>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let (tx, mut rx) = mpsc::channel(1);
+        let cancel = tokio_util::sync::CancellationToken::new();
+        let tcp = crate::config::ControlTcpConfig {
+            enabled: true,
+            bind_address: address,
+            allowed_peers: Vec::new(),
+        };
+        let server = tokio::spawn(super::serve_control_tcp(
+            listener,
+            cancel.clone(),
+            Arc::new(tcp),
+            tx,
+            status_of(ViewerStatus::default()),
+            Arc::new(PathBuf::from(NO_CONFIG)),
+            Arc::new(Mutex::new(ManualOverride::default())),
+        ));
+
+        let mut client = tokio::net::TcpStream::connect(address).await.unwrap();
+        client.write_all(payload).await.unwrap();
+        client.shutdown().await.unwrap();
+        let mut reply = Vec::new();
+        client.read_to_end(&mut reply).await.unwrap();
+        let command = tokio::time::timeout(Duration::from_millis(200), rx.recv())
+            .await
+            .ok()
+            .flatten();
+        cancel.cancel();
+        server.await.unwrap().unwrap();
+        (command, reply)
+    }
+
+    #[tokio::test]
+    async fn tcp_toggle_state_reaches_the_viewer() {
+        let (command, reply) = exchange_over_tcp(br#"{"command":"toggle-state"}"#).await;
+        assert!(matches!(command, Some(ViewerCommand::ToggleState)));
+        assert!(
+            reply.is_empty(),
+            "toggle-state answers as it does on the socket"
+        );
+    }
+
+    #[test]
+    fn startup_waits_for_a_missing_library_unless_it_is_required() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        let missing = dir.path().join("not-mounted-yet");
+        let start = |yaml: String| {
+            std::fs::write(&path, yaml).unwrap();
+            let cfg = super::load_config(&path).expect("valid config");
+            crate::tasks::files::check_required_roots(&cfg)
+        };
+
+        // The scalar form and a sole root keep waiting, as they always have.
+        start(format!("photo-library-path: {}\n", missing.display()))
+            .expect("a missing scalar library is waited for");
+        start(format!(
+            "photo-library-path:\n  - path: {}\n    weight: 2\n",
+            missing.display()
+        ))
+        .expect("a missing sole root is waited for");
+
+        let err = start(format!(
+            "photo-library-path:\n  - {}\n  - path: {}\n",
+            dir.path().display(),
+            missing.display()
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("optional: true"), "{err}");
+    }
+
+    #[test]
+    fn check_config_prints_the_effective_configuration() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        let yaml = format!("photo-library-path: {}\n", dir.path().display());
+        std::fs::write(&path, yaml).unwrap();
+
+        let report = super::check_config(&path).expect("valid config");
+
+        assert!(report.starts_with(&format!("# {}: configuration is valid", path.display())));
+        assert!(!report.contains("warning"), "{report}");
+        // Defaults are filled in, under the keys the file uses.
+        for key in [
+            "photo-library-path:",
+            "dwell-ms:",
+            "matting:",
+            "control-socket-path:",
+        ] {
+            assert!(report.contains(key), "missing {key} in {report}");
+        }
+        // The report reads back as the same configuration.
+        let reread = super::config::Configuration::from_yaml_str(&report).expect("report parses");
+        assert_eq!(reread.library_paths(), vec![dir.path().to_path_buf()]);
+    }
+
+    #[test]
+    fn check_config_points_at_the_bad_matting_field() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        let yaml = r#"
+photo-library-path: /photos
+matting:
+  active:
+    - kind: fixed-color
+      colour: [1, 2, 3]
+"#;
+        std::fs::write(&path, yaml).unwrap();
+
+        let err = format!("{:#}", super::check_config(&path).unwrap_err());
+
+        assert!(
+            err.contains("matting.active[0].colour: unknown field `colour`; did you mean 'color'?"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn print_default_config_needs_no_config_path() {
+        use clap::Parser;
+
+        let args = super::Args::try_parse_from(["photoframe", "--print-default-config"])
+            .expect("no CONFIG needed");
+        assert!(args.print_default_config);
+        assert!(args.config.is_none());
+        super::Args::try_parse_from(["photoframe", "--check-config"])
+            .expect_err("CONFIG is required otherwise");
+    }
+
+    #[test]
+    fn validate_config_accepts_a_good_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let background = dir.path().join("linen.png");
+        image::RgbImage::new(4, 4).save(&background).unwrap();
+        let path = dir.path().join("config.yaml");
+        let yaml = format!(
+            "photo-library-path: {}\nmatting:\n  active:\n    - kind: fixed-image\n      path: {}\n",
+            dir.path().display(),
+            background.display()
+        );
+        std::fs::write(&path, yaml).unwrap();
+
+        super::validate_config(&path).expect("valid config");
+    }
+
+    #[test]
+    fn validate_config_rejects_a_missing_fixed_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let background = dir.path().join("linen.png");
+        image::RgbImage::new(4, 4).save(&background).unwrap();
+        let missing = dir.path().join("missing.png");
+        let path = dir.path().join("config.yaml");
+        let yaml = format!(
+            "photo-library-path: {}\nmatting:\n  active:\n    - kind: fixed-image\n      path: [{}, {}]\n",
+            dir.path().display(),
+            background.display(),
+            missing.display()
+        );
+        std::fs::write(&path, yaml).unwrap();
+
+        // Startup only warns about it while the other background is usable.
+        super::load_config(&path).expect("loads with a warning");
+        let err = format!("{:#}", super::validate_config(&path).unwrap_err());
+        assert!(
+            err.contains(&format!(
+                "fixed-image path {} does not exist",
+                missing.display()
+            )),
+            "{err}"
+        );
     }
 
     #[test]
@@ -647,4 +2684,98 @@ mod tests {
         assert!(message.contains("awake"));
         assert!(message.contains("asleep"));
     }
+
+    fn dry_run_fixture() -> (tempfile::TempDir, super::config::Configuration) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        let yaml = format!("photo-library-path: {}\n", dir.path().display());
+        std::fs::write(&path, yaml).unwrap();
+        let cfg = super::load_config(&path).unwrap();
+        (dir, cfg)
+    }
+
+    fn library_photos(root: &Path, now: std::time::SystemTime) -> Vec<crate::events::PhotoInfo> {
+        [
+            "2023/summer/beach.jpg",
+            "2023/winter/snow.jpg",
+            "2024/new.heic",
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(idx, name)| crate::events::PhotoInfo {
+            path: root.join(name),
+            created_at: now - Duration::from_secs(86_400 * idx as u64),
+            root_weight: 1.0,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn playlist_dry_run_json_is_deterministic_under_a_seed() {
+        let (dir, cfg) = dry_run_fixture();
+        let now = humantime::parse_rfc3339("2025-01-01T00:00:00Z").unwrap();
+        let options = super::DryRunOptions {
+            iterations: 12,
+            now,
+            seed: 1234,
+            filter: None,
+        };
+        let render = || {
+            let photos = library_photos(dir.path(), now);
+            let dry_run = super::plan_playlist_dry_run(&cfg, photos, &options);
+            serde_json::to_string(&dry_run).unwrap()
+        };
+        let first = render();
+        assert_eq!(first, render(), "same seed must plan the same order");
+
+        let json: serde_json::Value = serde_json::from_str(&first).unwrap();
+        assert_eq!(json["seed"], 1234);
+        assert_eq!(json["now"], "2025-01-01T00:00:00Z");
+        assert_eq!(json["iterations"], 12);
+        let photos = json["photos"].as_array().unwrap();
+        assert_eq!(photos.len(), 3);
+        assert_eq!(photos[0]["created_at"], "2025-01-01T00:00:00Z");
+        let weights: Vec<f64> = photos
+            .iter()
+            .map(|photo| photo["weight"].as_f64().unwrap())
+            .collect();
+        assert!(
+            weights[0] > weights[1] && weights[1] > weights[2],
+            "{weights:?}"
+        );
+        let known: Vec<&str> = photos
+            .iter()
+            .map(|photo| photo["path"].as_str().unwrap())
+            .collect();
+        let order = json["order"].as_array().unwrap();
+        assert_eq!(order.len(), 12);
+        assert!(
+            order
+                .iter()
+                .all(|path| known.contains(&path.as_str().unwrap()))
+        );
+    }
+
+    #[test]
+    fn playlist_filter_scopes_the_dry_run_to_a_folder() {
+        let (dir, cfg) = dry_run_fixture();
+        let now = std::time::SystemTime::now();
+        let roots = &cfg.library_paths();
+        let names = |glob: &str| -> Vec<PathBuf> {
+            let photos = library_photos(dir.path(), now);
+            super::filter_photos(roots, photos, glob)
+                .unwrap()
+                .into_iter()
+                .map(|info| info.path.strip_prefix(dir.path()).unwrap().to_path_buf())
+                .collect()
+        };
+        assert_eq!(
+            names("2023/summer"),
+            [PathBuf::from("2023/summer/beach.jpg")]
+        );
+        assert_eq!(names("2023"), names("2023/*"));
+        assert_eq!(names("2023").len(), 2);
+        assert_eq!(names("*.heic"), [PathBuf::from("2024/new.heic")]);
+        assert!(names("2022").is_empty());
+    }
 }
@@ -0,0 +1,153 @@
+//! Running timings for tuning the frame on slow hardware.
+//!
+//! The loader records how long each photo took to decode, the photo-effect
+//! task how long its effects ran, and the viewer every presented frame and
+//! finished transition. The control socket's `metrics` command reads the
+//! totals, and the debug overlay the latest of each. Nothing here is
+//! persisted; a restart starts from zero.
+
+use serde::Serialize;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+pub type SharedMetrics = Arc<Mutex<RuntimeMetrics>>;
+
+/// Count, mean, worst case, and latest of one kind of work.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timing {
+    count: u64,
+    total: Duration,
+    max: Duration,
+    last: Duration,
+}
+
+impl Timing {
+    pub fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+        self.last = elapsed;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Mean in milliseconds; `0.0` before anything was recorded.
+    pub fn avg_ms(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        self.total.as_secs_f64() * 1000.0 / self.count as f64
+    }
+
+    pub fn max_ms(&self) -> f64 {
+        self.max.as_secs_f64() * 1000.0
+    }
+
+    /// The most recent sample in milliseconds; `None` before the first.
+    pub fn last_ms(&self) -> Option<f64> {
+        (self.count > 0).then_some(self.last.as_secs_f64() * 1000.0)
+    }
+}
+
+impl Serialize for Timing {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Summary {
+            count: u64,
+            avg_ms: f64,
+            max_ms: f64,
+        }
+
+        // Rounded to a hundredth of a millisecond; finer digits are noise.
+        let round = |ms: f64| (ms * 100.0).round() / 100.0;
+        Summary {
+            count: self.count,
+            avg_ms: round(self.avg_ms()),
+            max_ms: round(self.max_ms()),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RuntimeMetrics {
+    /// Loading a photo: decode, plus caches, caption, and sidecar lookups.
+    pub decode: Timing,
+    /// The photo-effect chain; photos without effects are not counted.
+    pub effect: Timing,
+    /// Fitting each photo onto its mat, on the viewer's worker threads.
+    pub matting: Timing,
+    /// Wall time of each transition from its first frame to its last.
+    pub transition: Timing,
+    pub frames_rendered: u64,
+    /// Refresh intervals missed while a transition was playing.
+    pub frames_dropped: u64,
+}
+
+impl RuntimeMetrics {
+    pub fn shared() -> SharedMetrics {
+        Arc::new(Mutex::new(Self::default()))
+    }
+
+    /// Runs `update` on the shared totals. A panic elsewhere while holding the
+    /// lock leaves counters that are still worth reading, so poisoning is
+    /// ignored.
+    pub fn update(metrics: &SharedMetrics, update: impl FnOnce(&mut Self)) {
+        update(&mut metrics.lock().unwrap_or_else(PoisonError::into_inner));
+    }
+
+    /// Counts a presented frame. `interval` is the time since the previous
+    /// frame of a continuous animation; every whole `refresh` period past the
+    /// first that it spans (rounding half up) was a frame the display wanted
+    /// but did not get.
+    pub fn record_frame(&mut self, interval: Option<Duration>, refresh: Duration) {
+        self.frames_rendered += 1;
+        let Some(interval) = interval else {
+            return;
+        };
+        let periods = interval.as_secs_f64() / refresh.as_secs_f64().max(f64::EPSILON);
+        self.frames_dropped += (periods - 0.5).floor().max(0.0) as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timings_average_and_keep_the_worst_case() {
+        let mut timing = Timing::default();
+        assert_eq!(timing.avg_ms(), 0.0);
+        assert_eq!(timing.last_ms(), None);
+        for ms in [10, 60, 20] {
+            timing.record(Duration::from_millis(ms));
+        }
+        assert_eq!(timing.count(), 3);
+        assert!((timing.avg_ms() - 30.0).abs() < 1e-9);
+        assert!((timing.max_ms() - 60.0).abs() < 1e-9);
+        assert_eq!(timing.last_ms(), Some(20.0));
+
+        let json = serde_json::to_value(timing).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "count": 3, "avg_ms": 30.0, "max_ms": 60.0 })
+        );
+    }
+
+    #[test]
+    fn late_frames_count_the_refreshes_they_missed() {
+        let refresh = Duration::from_micros(16_667);
+        let mut metrics = RuntimeMetrics::default();
+        metrics.record_frame(None, refresh);
+        metrics.record_frame(Some(Duration::from_millis(17)), refresh);
+        metrics.record_frame(Some(Duration::from_millis(22)), refresh);
+        assert_eq!(metrics.frames_dropped, 0);
+
+        metrics.record_frame(Some(Duration::from_millis(34)), refresh);
+        metrics.record_frame(Some(Duration::from_millis(67)), refresh);
+        assert_eq!(metrics.frames_rendered, 5);
+        assert_eq!(metrics.frames_dropped, 1 + 3);
+    }
+}
@@ -0,0 +1,268 @@
+//! Home automation over MQTT, enabled by the `mqtt` config block: the frame's
+//! state is published, retained, to `state-topic` whenever it changes, and
+//! messages on `command-topic` take the control socket's JSON and feed the
+//! same viewer command channel.
+
+use super::{ControlCommand, ControlStatus, ManualOverride};
+use crate::config::MqttConfig;
+use crate::events::{StateSource, ViewerCommand, ViewerState};
+use anyhow::{Context, Result, bail};
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::time::{MissedTickBehavior, interval};
+use tokio_util::sync::CancellationToken;
+
+/// How often the viewer's snapshot is checked for a change worth publishing.
+const STATE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Wait before reconnecting after the broker drops or refuses the connection.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// What the MQTT client shares with the control socket.
+#[derive(Clone)]
+pub(crate) struct MqttRemote {
+    pub(crate) control: mpsc::Sender<ViewerCommand>,
+    pub(crate) status: ControlStatus,
+    pub(crate) manual_override: Arc<Mutex<ManualOverride>>,
+}
+
+pub(crate) async fn run(
+    cancel: CancellationToken,
+    config: MqttConfig,
+    remote: MqttRemote,
+) -> Result<()> {
+    let (host, port) = config.broker_address()?;
+    let mut options = MqttOptions::new(&config.client_id, &host, port);
+    options.set_keep_alive(Duration::from_secs(30));
+    if let Some(username) = &config.username {
+        options.set_credentials(username, config.password.as_deref().unwrap_or_default());
+    }
+    // The broker marks the frame offline if it goes away without saying so.
+    options.set_last_will(LastWill::new(
+        &config.state_topic,
+        offline_payload(),
+        QoS::AtLeastOnce,
+        true,
+    ));
+    let (client, mut events) = AsyncClient::new(options, 16);
+    tracing::info!(broker = %config.broker, "mqtt connecting");
+
+    let mut poll = interval(STATE_POLL_INTERVAL);
+    poll.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut published: Option<String> = None;
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            event = events.poll() => match event {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    tracing::info!(broker = %config.broker, "mqtt connected");
+                    if let Err(err) = client.try_subscribe(&config.command_topic, QoS::AtLeastOnce) {
+                        tracing::warn!(error = %err, "failed to subscribe to mqtt commands");
+                    }
+                    // A new session starts without the retained state; send it again.
+                    published = None;
+                }
+                Ok(Event::Incoming(Packet::Publish(message))) if message.topic == config.command_topic => {
+                    handle_message(&remote, &message.payload).await;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    tracing::warn!(error = %err, broker = %config.broker, "mqtt connection lost; retrying");
+                    tokio::select! {
+                        _ = cancel.cancelled() => break,
+                        _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+                    }
+                }
+            },
+            _ = poll.tick() => {
+                let payload = state_payload(&remote.status);
+                if published.as_ref() != Some(&payload) {
+                    match client.try_publish(&config.state_topic, QoS::AtLeastOnce, true, payload.clone()) {
+                        Ok(()) => published = Some(payload),
+                        Err(err) => tracing::debug!(error = %err, "mqtt state not published yet"),
+                    }
+                }
+            }
+        }
+    }
+
+    let _ = client.try_publish(
+        &config.state_topic,
+        QoS::AtLeastOnce,
+        true,
+        offline_payload(),
+    );
+    let _ = client.try_disconnect();
+    // Let the event loop flush the offline state and the disconnect.
+    let _ = tokio::time::timeout(Duration::from_secs(1), async {
+        while events.poll().await.is_ok() {}
+    })
+    .await;
+    Ok(())
+}
+
+async fn handle_message(remote: &MqttRemote, payload: &[u8]) {
+    let command = match viewer_command(remote, payload) {
+        Ok(command) => command,
+        Err(err) => {
+            tracing::warn!(error = %format!("{err:#}"), "ignored mqtt command");
+            return;
+        }
+    };
+    tracing::info!(?command, "received mqtt command");
+    if remote.control.send(command).await.is_err() {
+        tracing::warn!("viewer is not running; mqtt command dropped");
+    }
+}
+
+/// The viewer command for an MQTT message, after the same bookkeeping the
+/// control socket does: a manual state change starts the manual override, a
+/// scheduled one must get past it, and a pause with a reason holds the library.
+/// Commands that answer with a reply, like `status`, have no use here.
+fn viewer_command(remote: &MqttRemote, payload: &[u8]) -> Result<ViewerCommand> {
+    let command: ControlCommand =
+        serde_json::from_slice(payload).context("not a control command")?;
+    let now = Instant::now();
+    let manual_override = || {
+        remote
+            .manual_override
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    };
+    Ok(match command {
+        ControlCommand::ToggleState => {
+            let toggled = match remote.status.viewer.lock().map(|viewer| viewer.state) {
+                Ok(ViewerState::Asleep | ViewerState::Ambient) => ViewerState::Awake,
+                _ => ViewerState::Asleep,
+            };
+            manual_override().record_manual(toggled, now);
+            ViewerCommand::ToggleState
+        }
+        ControlCommand::SetState { state, source } => {
+            let state = state.into();
+            match source {
+                StateSource::Manual => manual_override().record_manual(state, now),
+                StateSource::Scheduled => {
+                    if manual_override().admit_scheduled(state, now).is_err() {
+                        bail!("scheduled set-state held off by a manual override");
+                    }
+                }
+            }
+            ViewerCommand::SetState { state, source }
+        }
+        ControlCommand::Next => ViewerCommand::Next,
+        ControlCommand::Previous => ViewerCommand::Previous,
+        ControlCommand::Pause { reason } => {
+            if reason.is_some() {
+                remote.status.library_hold.send_replace(reason.clone());
+            }
+            ViewerCommand::Pause { reason }
+        }
+        ControlCommand::Resume => {
+            remote.status.library_hold.send_replace(None);
+            ViewerCommand::Resume
+        }
+        ControlCommand::Notice { text, seconds } => ViewerCommand::Notice {
+            text,
+            duration: Duration::from_secs(seconds),
+        },
+        other => bail!("{other:?} is not available over mqtt"),
+    })
+}
+
+/// What is published to `state-topic`: the `status` reply without the
+/// fields that change on their own, so only real changes are sent.
+fn state_payload(status: &ControlStatus) -> String {
+    let mut state = status.to_json();
+    if let Some(fields) = state.as_object_mut() {
+        fields.remove("status");
+        fields.remove("queue_depth");
+        fields.remove("uptime_seconds");
+    }
+    state.to_string()
+}
+
+fn offline_payload() -> String {
+    serde_json::json!({ "state": "offline" }).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::QuarantineConfig;
+    use crate::events::ViewerStatus;
+    use crate::metrics::RuntimeMetrics;
+    use crate::tasks::history::PhotoHistory;
+    use crate::tasks::quarantine::Quarantine;
+    use std::path::PathBuf;
+    use tokio::sync::watch;
+
+    fn remote(ttl: Option<Duration>) -> (MqttRemote, mpsc::Receiver<ViewerCommand>) {
+        let (control, rx) = mpsc::channel(4);
+        let remote = MqttRemote {
+            control,
+            status: ControlStatus {
+                viewer: Arc::new(Mutex::new(ViewerStatus::default())),
+                started_at: Instant::now(),
+                history: Arc::new(Mutex::new(PhotoHistory::new(10))),
+                metrics: RuntimeMetrics::shared(),
+                quarantine: Quarantine::shared(&QuarantineConfig::default()),
+                library_hold: Arc::new(watch::channel(None).0),
+            },
+            manual_override: Arc::new(Mutex::new(ManualOverride::new(ttl))),
+        };
+        (remote, rx)
+    }
+
+    #[tokio::test]
+    async fn set_state_messages_reach_the_viewer() {
+        let (remote, mut rx) = remote(None);
+        handle_message(&remote, br#"{"command":"set-state","state":"asleep"}"#).await;
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(ViewerCommand::SetState {
+                state: ViewerState::Asleep,
+                source: StateSource::Manual,
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn a_scheduled_change_waits_out_a_manual_one() {
+        let (remote, mut rx) = remote(Some(Duration::from_secs(60)));
+        handle_message(&remote, br#"{"command":"set-state","state":"awake"}"#).await;
+        rx.try_recv().expect("the manual change goes through");
+        handle_message(
+            &remote,
+            br#"{"command":"set-state","state":"asleep","source":"scheduled"}"#,
+        )
+        .await;
+        assert!(
+            rx.try_recv().is_err(),
+            "the override holds the schedule off"
+        );
+    }
+
+    #[tokio::test]
+    async fn replies_and_garbage_are_ignored() {
+        let (remote, mut rx) = remote(None);
+        handle_message(&remote, br#"{"command":"status"}"#).await;
+        handle_message(&remote, b"ON").await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn published_state_changes_only_with_the_frame() {
+        let (remote, _rx) = remote(None);
+        let before = state_payload(&remote.status);
+        assert_eq!(state_payload(&remote.status), before);
+
+        remote.status.viewer.lock().unwrap().current_photo = Some(PathBuf::from("/photos/a.jpg"));
+        let after: serde_json::Value =
+            serde_json::from_str(&state_payload(&remote.status)).unwrap();
+        assert_eq!(after["state"], "awake");
+        assert_eq!(after["current_photo"], "/photos/a.jpg");
+        assert!(after.get("uptime_seconds").is_none());
+    }
+}
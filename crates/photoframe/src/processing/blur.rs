@@ -1,6 +1,10 @@
+use std::sync::Once;
+
 use image::{RgbaImage, imageops};
+use tracing::warn;
 
 use crate::config::BlurBackend;
+use crate::gpu;
 
 pub fn apply_blur(image: &RgbaImage, sigma: f32, backend: BlurBackend) -> RgbaImage {
     if sigma <= 0.0 {
@@ -10,6 +14,11 @@ pub fn apply_blur(image: &RgbaImage, sigma: f32, backend: BlurBackend) -> RgbaIm
     match backend {
         BlurBackend::Cpu => blur_cpu(image, sigma),
         BlurBackend::Neon => neon_blur(image, sigma).unwrap_or_else(|| blur_cpu(image, sigma)),
+        BlurBackend::Gpu => gpu::blur::blur(image, sigma).unwrap_or_else(|| {
+            static FALLBACK: Once = Once::new();
+            FALLBACK.call_once(|| warn!("gpu blur unavailable; falling back to the CPU blur"));
+            blur_cpu(image, sigma)
+        }),
     }
 }
 
@@ -17,8 +26,8 @@ fn blur_cpu(image: &RgbaImage, sigma: f32) -> RgbaImage {
     imageops::blur(image, sigma)
 }
 
-#[cfg(target_arch = "aarch64")]
-fn gaussian_kernel(sigma: f32) -> (Vec<f32>, u32) {
+/// Normalized Gaussian weights for `sigma`, and the radius they span.
+pub(crate) fn gaussian_kernel(sigma: f32) -> (Vec<f32>, u32) {
     let sigma = sigma.max(0.01);
     let radius = (sigma * 3.0).ceil() as i32;
     if radius <= 0 {
@@ -192,7 +201,7 @@ mod tests {
     #[test]
     fn zero_sigma_returns_clone() {
         let img = single_pixel_image(128, 64, 32, 255);
-        for backend in [BlurBackend::Cpu, BlurBackend::Neon] {
+        for backend in [BlurBackend::Cpu, BlurBackend::Neon, BlurBackend::Gpu] {
             let out = apply_blur(&img, 0.0, backend);
             assert_eq!(out.dimensions(), img.dimensions());
             assert_eq!(out.get_pixel(0, 0), img.get_pixel(0, 0));
@@ -202,9 +211,18 @@ mod tests {
     #[test]
     fn one_by_one_image_does_not_crash() {
         let img = single_pixel_image(200, 100, 50, 255);
-        for backend in [BlurBackend::Cpu, BlurBackend::Neon] {
+        for backend in [BlurBackend::Cpu, BlurBackend::Neon, BlurBackend::Gpu] {
             let out = apply_blur(&img, 1.0, backend);
             assert_eq!(out.dimensions(), (1, 1));
         }
     }
+
+    #[test]
+    fn gpu_backend_falls_back_to_cpu_without_a_device() {
+        let img = RgbaImage::from_fn(12, 8, |x, y| {
+            Rgba([(x * 20) as u8, (y * 30) as u8, 90, 255])
+        });
+        let gpu = apply_blur(&img, 1.5, BlurBackend::Gpu);
+        assert_eq!(gpu, apply_blur(&img, 1.5, BlurBackend::Cpu));
+    }
 }
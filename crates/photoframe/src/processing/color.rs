@@ -1,5 +1,12 @@
+use std::cell::OnceCell;
+
 use image::{Rgba, RgbaImage};
 
+/// Longest side, in samples, of the grid `dominant_color` reads.
+const DOMINANT_SAMPLE_EDGE: u32 = 96;
+/// Bits kept per channel when binning colors for `dominant_color`.
+const DOMINANT_BIN_BITS: u32 = 4;
+
 pub fn average_color(img: &RgbaImage) -> [f32; 3] {
     let mut accum = [0f64; 3];
     let mut total = 0f64;
@@ -32,3 +39,70 @@ pub fn average_color_rgba(img: &RgbaImage) -> Rgba<u8> {
         255,
     ])
 }
+
+/// The most common color: the peak of a coarse color histogram over a
+/// downscaled sampling of `img`, refined to the mean of the pixels in that bin.
+/// Unlike [`average_color`], the result is a color that actually appears in
+/// the photo. Transparent pixels are ignored.
+pub fn dominant_color(img: &RgbaImage) -> [f32; 3] {
+    let (width, height) = img.dimensions();
+    let step = (width.max(height) / DOMINANT_SAMPLE_EDGE).max(1);
+    let levels = 1usize << DOMINANT_BIN_BITS;
+    let shift = 8 - DOMINANT_BIN_BITS;
+    // Per bin: summed alpha weight and weighted channel sums.
+    let mut bins = vec![[0f64; 4]; levels * levels * levels];
+    for y in (0..height).step_by(step as usize) {
+        for x in (0..width).step_by(step as usize) {
+            let pixel = img.get_pixel(x, y);
+            let alpha = (pixel[3] as f64) / 255.0;
+            if alpha <= 0.0 {
+                continue;
+            }
+            let index = ((pixel[0] >> shift) as usize * levels + (pixel[1] >> shift) as usize)
+                * levels
+                + (pixel[2] >> shift) as usize;
+            let bin = &mut bins[index];
+            bin[0] += alpha;
+            for c in 0..3 {
+                bin[c + 1] += (pixel[c] as f64) * alpha;
+            }
+        }
+    }
+    let Some(peak) = bins
+        .iter()
+        .filter(|bin| bin[0] > f64::EPSILON)
+        .max_by(|a, b| a[0].total_cmp(&b[0]))
+    else {
+        return [0.1, 0.1, 0.1];
+    };
+    [
+        (peak[1] / (255.0 * peak[0])) as f32,
+        (peak[2] / (255.0 * peak[0])) as f32,
+        (peak[3] / (255.0 * peak[0])) as f32,
+    ]
+}
+
+/// The colors a mat can borrow from its photo, each computed on first use.
+pub struct PhotoColors<'a> {
+    image: &'a RgbaImage,
+    average: OnceCell<[f32; 3]>,
+    dominant: OnceCell<[f32; 3]>,
+}
+
+impl<'a> PhotoColors<'a> {
+    pub fn new(image: &'a RgbaImage) -> Self {
+        Self {
+            image,
+            average: OnceCell::new(),
+            dominant: OnceCell::new(),
+        }
+    }
+
+    pub fn average(&self) -> [f32; 3] {
+        *self.average.get_or_init(|| average_color(self.image))
+    }
+
+    pub fn dominant(&self) -> [f32; 3] {
+        *self.dominant.get_or_init(|| dominant_color(self.image))
+    }
+}
@@ -0,0 +1,178 @@
+use crate::config::FilmGrainOptions;
+use image::RgbaImage;
+
+/// Overlays film grain and an optional corner vignette.
+///
+/// Grain is smooth value noise on a lattice of `grain-size-px` cells, so larger
+/// sizes give coarser clumps. It is weighted towards the midtones the way
+/// silver grain reads on a print, leaving deep shadows and clipped highlights
+/// mostly clean. The noise is hashed from pixel coordinates, so the same photo
+/// always gets the same grain. Alpha is left untouched.
+pub fn apply_film_grain(image: &mut RgbaImage, options: &FilmGrainOptions) {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let grain_strength = options.grain_strength.max(0.0);
+    let cell = options.grain_size_px.max(1.0);
+    let vignette_strength = options.vignette_strength.clamp(0.0, 1.0);
+    let half_w = width as f32 * 0.5;
+    let half_h = height as f32 * 0.5;
+
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let px = x as f32 + 0.5;
+        let py = y as f32 + 0.5;
+
+        // 0 at the centre, 1 in the corners.
+        let dx = (px - half_w) / half_w;
+        let dy = (py - half_h) / half_h;
+        let radius = ((dx * dx + dy * dy) * 0.5).sqrt();
+        let vignette = 1.0 - vignette_strength * smoothstep(0.25, 1.0, radius);
+
+        let luma = (0.2126 * f32::from(pixel[0])
+            + 0.7152 * f32::from(pixel[1])
+            + 0.0722 * f32::from(pixel[2]))
+            / 255.0;
+        let midtone = 1.0 - (2.0 * luma - 1.0).powi(2);
+        let grain = value_noise(px / cell, py / cell) * grain_strength * (0.25 + 0.75 * midtone);
+
+        for channel in pixel.0.iter_mut().take(3) {
+            let value = f32::from(*channel) / 255.0 * vignette + grain;
+            *channel = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+}
+
+/// Smoothly interpolated lattice noise in `[-1, 1]`.
+fn value_noise(x: f32, y: f32) -> f32 {
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let tx = smoothstep(0.0, 1.0, x - x0);
+    let ty = smoothstep(0.0, 1.0, y - y0);
+    let (cx, cy) = (x0 as i32, y0 as i32);
+    let top = lerp(lattice(cx, cy), lattice(cx + 1, cy), tx);
+    let bottom = lerp(lattice(cx, cy + 1), lattice(cx + 1, cy + 1), tx);
+    lerp(top, bottom, ty)
+}
+
+/// Pseudo-random value in `[-1, 1]` for one lattice point.
+fn lattice(x: i32, y: i32) -> f32 {
+    let mut h = (x as u32).wrapping_mul(0x8da6_b343) ^ (y as u32).wrapping_mul(0xd816_3841);
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x7feb_352d);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x846c_a68b);
+    h ^= h >> 16;
+    (h as f32 / u32::MAX as f32) * 2.0 - 1.0
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn gradient(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, y| {
+            Rgba([
+                (40 + x * 4) as u8,
+                (60 + y * 4) as u8,
+                128,
+                (100 + x + y) as u8,
+            ])
+        })
+    }
+
+    #[test]
+    fn preserves_image_when_strength_zero() {
+        let mut image = gradient(16, 12);
+        let before = image.clone();
+        let options = FilmGrainOptions {
+            grain_strength: 0.0,
+            vignette_strength: 0.0,
+            ..FilmGrainOptions::default()
+        };
+
+        apply_film_grain(&mut image, &options);
+        assert_eq!(image, before);
+    }
+
+    #[test]
+    fn defaults_change_pixels_but_keep_dimensions_and_alpha() {
+        let mut image = gradient(32, 24);
+        let before = image.clone();
+
+        apply_film_grain(&mut image, &FilmGrainOptions::default());
+
+        assert_eq!(image.dimensions(), before.dimensions());
+        assert_ne!(image.as_raw(), before.as_raw());
+        for (after, original) in image.pixels().zip(before.pixels()) {
+            assert_eq!(after[3], original[3]);
+        }
+        // The defaults are meant to be subtle: no channel moves far.
+        let max_delta = image
+            .as_raw()
+            .iter()
+            .zip(before.as_raw())
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap();
+        assert!(max_delta <= 48, "max channel delta {max_delta}");
+    }
+
+    #[test]
+    fn vignette_darkens_corners_and_spares_the_centre() {
+        let mut image = RgbaImage::from_pixel(41, 41, Rgba([180, 180, 180, 255]));
+        let options = FilmGrainOptions {
+            grain_strength: 0.0,
+            vignette_strength: 0.5,
+            ..FilmGrainOptions::default()
+        };
+
+        apply_film_grain(&mut image, &options);
+
+        assert_eq!(image.get_pixel(20, 20).0, [180, 180, 180, 255]);
+        let corner = image.get_pixel(0, 0).0;
+        assert!(corner[0] < 110, "{corner:?}");
+        assert_eq!(corner[3], 255);
+    }
+
+    #[test]
+    fn grain_is_deterministic_and_coarsens_with_size() {
+        let fine = FilmGrainOptions {
+            grain_strength: 0.2,
+            grain_size_px: 1.0,
+            vignette_strength: 0.0,
+        };
+        let coarse = FilmGrainOptions {
+            grain_size_px: 8.0,
+            ..fine.clone()
+        };
+        let flat = RgbaImage::from_pixel(64, 1, Rgba([128, 128, 128, 255]));
+
+        let mut a = flat.clone();
+        let mut b = flat.clone();
+        apply_film_grain(&mut a, &fine);
+        apply_film_grain(&mut b, &fine);
+        assert_eq!(a, b);
+
+        let mut c = flat.clone();
+        apply_film_grain(&mut c, &coarse);
+        let roughness = |img: &RgbaImage| -> u32 {
+            img.pixels()
+                .zip(img.pixels().skip(1))
+                .map(|(l, r)| u32::from(l[0].abs_diff(r[0])))
+                .sum()
+        };
+        assert!(roughness(&a) > roughness(&c));
+    }
+}
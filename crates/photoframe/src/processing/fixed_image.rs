@@ -6,14 +6,13 @@ use std::sync::{Arc, Mutex, OnceLock};
 use anyhow::anyhow;
 
 use anyhow::{Context, Result};
-use fast_image_resize as fir;
 use image::{ImageFormat, ImageReader, RgbaImage, imageops};
 use jpeg_decoder::{Decoder as JpegDecoder, PixelFormat};
 use tracing::debug;
 
 use crate::config::FixedImageFit;
 use crate::processing::color::average_color_rgba;
-use crate::processing::layout::{center_offset, resize_to_contain, resize_to_cover};
+use crate::processing::layout::{center_offset, resize_rgba, resize_to_contain, resize_to_cover};
 
 #[derive(Debug)]
 pub struct FixedImageBackground {
@@ -267,30 +266,3 @@ impl FixedImageBackground {
             .ok_or_else(|| anyhow!("failed to construct RGBA image for {}", self.path.display()))
     }
 }
-
-fn resize_rgba(source: &RgbaImage, target_w: u32, target_h: u32) -> Result<RgbaImage> {
-    if target_w == 0 || target_h == 0 {
-        anyhow::bail!("resize dimensions must be positive");
-    }
-    if source.width() == target_w && source.height() == target_h {
-        return Ok(source.clone());
-    }
-
-    let src_view = fir::images::ImageRef::new(
-        source.width(),
-        source.height(),
-        source.as_raw(),
-        fir::PixelType::U8x4,
-    )
-    .context("failed to create source view for fixed-image resize")?;
-    let mut dst_image = fir::images::Image::new(target_w, target_h, fir::PixelType::U8x4);
-    let options = fir::ResizeOptions::new()
-        .resize_alg(fir::ResizeAlg::Convolution(fir::FilterType::CatmullRom));
-    let mut resizer = fir::Resizer::new();
-    resizer
-        .resize(&src_view, &mut dst_image, Some(&options))
-        .context("fixed-image resize failed")?;
-    let buffer = dst_image.into_vec();
-    RgbaImage::from_raw(target_w, target_h, buffer)
-        .ok_or_else(|| anyhow::anyhow!("failed to construct resized RGBA image"))
-}
@@ -1,3 +1,7 @@
+use anyhow::{Context, Result};
+use fast_image_resize as fir;
+use image::RgbaImage;
+
 pub fn resize_to_cover(
     canvas_w: u32,
     canvas_h: u32,
@@ -43,6 +47,34 @@ pub fn center_offset(inner_w: u32, inner_h: u32, outer_w: u32, outer_h: u32) ->
     (ox, oy)
 }
 
+/// Resamples `source` to exactly `target_w`×`target_h` with a Catmull-Rom filter.
+pub fn resize_rgba(source: &RgbaImage, target_w: u32, target_h: u32) -> Result<RgbaImage> {
+    if target_w == 0 || target_h == 0 {
+        anyhow::bail!("resize dimensions must be positive");
+    }
+    if source.width() == target_w && source.height() == target_h {
+        return Ok(source.clone());
+    }
+
+    let src_view = fir::images::ImageRef::new(
+        source.width(),
+        source.height(),
+        source.as_raw(),
+        fir::PixelType::U8x4,
+    )
+    .context("failed to create source view for resize")?;
+    let mut dst_image = fir::images::Image::new(target_w, target_h, fir::PixelType::U8x4);
+    let options = fir::ResizeOptions::new()
+        .resize_alg(fir::ResizeAlg::Convolution(fir::FilterType::CatmullRom));
+    let mut resizer = fir::Resizer::new();
+    resizer
+        .resize(&src_view, &mut dst_image, Some(&options))
+        .context("image resize failed")?;
+    let buffer = dst_image.into_vec();
+    RgbaImage::from_raw(target_w, target_h, buffer)
+        .ok_or_else(|| anyhow::anyhow!("failed to construct resized RGBA image"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::resize_to_cover;
@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, ensure};
+use image::RgbaImage;
+
+/// A paper or linen scan tiled across a studio mat in place of the procedural
+/// weave. Only its brightness is kept, normalized so the tile averages 1.0:
+/// the mat keeps its configured color and the scan adds the surface.
+#[derive(Debug)]
+pub struct MatTexture {
+    path: PathBuf,
+    width: u32,
+    height: u32,
+    shade: Vec<f32>,
+}
+
+impl MatTexture {
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let image = image::open(&path)
+            .with_context(|| format!("failed to load mat texture at {}", path.display()))?
+            .to_rgba8();
+        Self::from_image(path, &image)
+    }
+
+    fn from_image(path: PathBuf, image: &RgbaImage) -> Result<Self> {
+        let (width, height) = image.dimensions();
+        ensure!(
+            width > 0 && height > 0,
+            "mat texture at {} is empty",
+            path.display()
+        );
+        let luma: Vec<f32> = image
+            .pixels()
+            .map(|p| (0.2126 * p[0] as f32 + 0.7152 * p[1] as f32 + 0.0722 * p[2] as f32) / 255.0)
+            .collect();
+        let mean = luma.iter().sum::<f32>() / luma.len() as f32;
+        ensure!(
+            mean > 1e-3,
+            "mat texture at {} is black; it needs some brightness to modulate the mat",
+            path.display()
+        );
+        Ok(Self {
+            path,
+            width,
+            height,
+            shade: luma.into_iter().map(|value| value / mean).collect(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Brightness multiplier at canvas pixel `(x, y)`, tiling the texture.
+    pub fn shade_at(&self, x: u32, y: u32) -> f32 {
+        let index = (y % self.height) as usize * self.width as usize + (x % self.width) as usize;
+        self.shade[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shade_is_normalized_and_tiles() {
+        let image = RgbaImage::from_fn(2, 1, |x, _| {
+            let v = if x == 0 { 100 } else { 200 };
+            image::Rgba([v, v, v, 255])
+        });
+        let texture = MatTexture::from_image(PathBuf::from("linen.png"), &image).unwrap();
+        assert!((texture.shade_at(0, 0) - 2.0 / 3.0).abs() < 1e-4);
+        assert!((texture.shade_at(1, 0) - 4.0 / 3.0).abs() < 1e-4);
+        assert_eq!(texture.shade_at(2, 5), texture.shade_at(0, 0));
+    }
+}
@@ -1,5 +1,8 @@
 pub mod blur;
 pub mod color;
+pub mod film_grain;
 pub mod fixed_image;
 pub mod layout;
+pub mod mat_texture;
 pub mod print_simulation;
+pub mod tone;
@@ -0,0 +1,104 @@
+use crate::config::{ToneMode, ToneOptions};
+use image::RgbaImage;
+
+/// Recolors a photo as sepia, grayscale, or a two-color duotone.
+///
+/// Every mode starts from Rec. 709 luminance except sepia, which uses the
+/// classic sepia matrix so reds and greens keep a little of their weight.
+/// `strength` blends the toned pixel with the original. Alpha is left
+/// untouched.
+pub fn apply_tone(image: &mut RgbaImage, options: &ToneOptions) {
+    let strength = options.strength.clamp(0.0, 1.0);
+    if strength == 0.0 {
+        return;
+    }
+    let shadow = options.shadow_color.map(f32::from);
+    let highlight = options.highlight_color.map(f32::from);
+
+    for pixel in image.pixels_mut() {
+        let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(f32::from);
+        let toned = match options.mode {
+            ToneMode::Grayscale => [luma(r, g, b); 3],
+            ToneMode::Sepia => [
+                0.393 * r + 0.769 * g + 0.189 * b,
+                0.349 * r + 0.686 * g + 0.168 * b,
+                0.272 * r + 0.534 * g + 0.131 * b,
+            ],
+            ToneMode::Duotone => {
+                let t = luma(r, g, b) / 255.0;
+                [0, 1, 2].map(|c| shadow[c] + (highlight[c] - shadow[c]) * t)
+            }
+        };
+        for (channel, (original, toned)) in [r, g, b].into_iter().zip(toned).enumerate() {
+            let value = original + (toned - original) * strength;
+            pixel[channel] = value.round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+fn luma(r: f32, g: f32, b: f32) -> f32 {
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn colorful() -> RgbaImage {
+        RgbaImage::from_fn(16, 8, |x, y| {
+            Rgba([(x * 16) as u8, (y * 32) as u8, 255 - (x * 8) as u8, 200])
+        })
+    }
+
+    fn tone(mode: ToneMode) -> ToneOptions {
+        ToneOptions {
+            mode,
+            ..ToneOptions::default()
+        }
+    }
+
+    #[test]
+    fn grayscale_zeroes_chroma_and_keeps_alpha() {
+        let mut image = colorful();
+        apply_tone(&mut image, &tone(ToneMode::Grayscale));
+        for pixel in image.pixels() {
+            assert_eq!(pixel[0], pixel[1], "{pixel:?}");
+            assert_eq!(pixel[1], pixel[2], "{pixel:?}");
+            assert_eq!(pixel[3], 200);
+        }
+    }
+
+    #[test]
+    fn sepia_is_warm_and_zero_strength_is_a_no_op() {
+        let mut image = colorful();
+        apply_tone(&mut image, &tone(ToneMode::Sepia));
+        for pixel in image.pixels() {
+            assert!(pixel[0] >= pixel[1] && pixel[1] >= pixel[2], "{pixel:?}");
+        }
+
+        let mut image = colorful();
+        let options = ToneOptions {
+            strength: 0.0,
+            ..tone(ToneMode::Sepia)
+        };
+        apply_tone(&mut image, &options);
+        assert_eq!(image, colorful());
+    }
+
+    #[test]
+    fn duotone_maps_black_and_white_to_its_colors() {
+        let mut image = RgbaImage::from_fn(2, 1, |x, _| {
+            let v = if x == 0 { 0 } else { 255 };
+            Rgba([v, v, v, 255])
+        });
+        let options = ToneOptions {
+            shadow_color: [20, 40, 80],
+            highlight_color: [240, 220, 160],
+            ..tone(ToneMode::Duotone)
+        };
+        apply_tone(&mut image, &options);
+        assert_eq!(image.get_pixel(0, 0).0, [20, 40, 80, 255]);
+        assert_eq!(image.get_pixel(1, 0).0, [240, 220, 160, 255]);
+    }
+}
@@ -0,0 +1,70 @@
+//! Links to the photo on screen, for the `share-qr` overlay.
+//!
+//! A photo is named in the link by a hash of its path, so the library layout
+//! stays off the wire. Web control serves `/photo/<id>` only for photos that
+//! are on screen or in the recent history, so a guessed id leads nowhere.
+
+use crate::tasks::loader::fnv1a;
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Stable identifier for the photo at `path`: the 64-bit FNV-1a hash of its
+/// bytes as 16 hex digits.
+pub fn photo_id(path: &Path) -> String {
+    format!("{:016x}", fnv1a(path.as_os_str().as_encoded_bytes()))
+}
+
+/// The link a QR code carries for the photo at `path`.
+pub fn photo_url(base_url: &str, path: &Path) -> String {
+    format!(
+        "{}/photo/{}",
+        base_url.trim_end_matches('/'),
+        photo_id(path)
+    )
+}
+
+/// A QR code drawn black on white, as opaque RGBA rows.
+#[derive(Debug, Clone)]
+pub struct QrImage {
+    /// Width and height in pixels.
+    pub size: u32,
+    pub rgba: Vec<u8>,
+}
+
+/// Draws `text` as a QR code at least `size` pixels across, quiet zone
+/// included; modules stay whole, so the result may be slightly larger.
+pub fn render_qr(text: &str, size: u32) -> Result<QrImage> {
+    let (size, rgba) =
+        qr_render::render_rgba(text, size).context("failed to generate share QR code")?;
+    Ok(QrImage { size, rgba })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_link_names_the_photo_by_its_id() {
+        let path = Path::new("/var/lib/photoframe/photos/cloud/2019/beach.jpg");
+        let id = photo_id(path);
+        assert_eq!(id.len(), 16);
+        assert_eq!(id, photo_id(path), "the id is stable");
+        assert_ne!(
+            id,
+            photo_id(Path::new("/var/lib/photoframe/photos/beach.jpg"))
+        );
+
+        let url = photo_url("http://frame.local:8080/", path);
+        assert_eq!(url, format!("http://frame.local:8080/photo/{id}"));
+        assert!(!url.contains("beach"), "the path stays off the wire");
+    }
+
+    #[test]
+    fn a_link_too_long_for_a_code_names_the_share_code() {
+        let err = render_qr(&"x".repeat(8000), 160).unwrap_err();
+        assert!(
+            format!("{err:#}").starts_with("failed to generate share QR code: "),
+            "{err:#}"
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! Copies of one photo: the same picture saved more than once, say exported
+//! at two sizes into two album folders.
+//!
+//! With `playlist.dedup.mode` set, the files task gives each photo a
+//! [`Fingerprint`]: a difference hash of the picture shrunk to 9×8 grey
+//! pixels, along with its size. Two photos of the same shape whose hashes
+//! differ in at most [`MAX_DISTANCE`] bits are copies. Fingerprints are worked
+//! out in the background, a few photos at a time, and kept in the library
+//! index when it is on. The manager then either shows only the copy with the
+//! most pixels (`collapse`) or keeps copies `playlist.dedup.window` displays
+//! apart (`spread`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError, mpsc};
+use std::thread;
+
+/// Hash bits two copies may differ in, for resampling and recompression.
+pub const MAX_DISTANCE: u32 = 2;
+/// Photos fingerprinted at once, by the startup pass and likewise by photos
+/// added later, so hashing never crowds out the loader.
+pub const MAX_CONCURRENT_HASHES: usize = 2;
+/// Hashes are indexed by each of their 16-bit quarters: two hashes within
+/// [`MAX_DISTANCE`] bits agree on at least one quarter.
+const QUARTERS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// One bit per pair of neighbouring pixels in each row of the shrunk
+    /// picture, set where the left one is brighter.
+    pub hash: u64,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Fingerprint {
+    /// `None` when `path` cannot be decoded; such a photo has no copies.
+    pub fn read(path: &Path) -> Option<Self> {
+        let image = image::ImageReader::open(path)
+            .ok()?
+            .with_guessed_format()
+            .ok()?
+            .decode()
+            .ok()?;
+        Some(Self::of(&image))
+    }
+
+    pub fn of(image: &image::DynamicImage) -> Self {
+        let small = image.thumbnail_exact(9, 8).to_luma8();
+        let mut hash = 0u64;
+        for y in 0..8 {
+            for x in 0..8 {
+                let left = small.get_pixel(x, y)[0];
+                let right = small.get_pixel(x + 1, y)[0];
+                hash = (hash << 1) | u64::from(left > right);
+            }
+        }
+        Self {
+            hash,
+            width: image.width(),
+            height: image.height(),
+        }
+    }
+
+    pub fn is_copy_of(&self, other: &Self) -> bool {
+        (self.hash ^ other.hash).count_ones() <= MAX_DISTANCE && self.same_shape(other)
+    }
+
+    fn pixels(&self) -> u64 {
+        u64::from(self.width) * u64::from(self.height)
+    }
+
+    /// Aspect ratios within 1%, which resizing keeps and cropping does not.
+    fn same_shape(&self, other: &Self) -> bool {
+        let a = u64::from(self.width) * u64::from(other.height);
+        let b = u64::from(other.width) * u64::from(self.height);
+        a.abs_diff(b) * 100 <= a.max(b)
+    }
+
+    fn quarter(&self, index: usize) -> u16 {
+        (self.hash >> (16 * index)) as u16
+    }
+}
+
+/// Fingerprints `paths` on [`MAX_CONCURRENT_HASHES`] threads, handing each
+/// result to `on_done` as it comes. Photos that cannot be decoded are left
+/// out. Stops early when `on_done` returns `false`.
+pub fn fingerprint_photos(
+    paths: Vec<PathBuf>,
+    mut on_done: impl FnMut(PathBuf, Fingerprint) -> bool,
+) {
+    let queue = Mutex::new(paths.into_iter());
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..MAX_CONCURRENT_HASHES {
+            let tx = tx.clone();
+            let queue = &queue;
+            scope.spawn(move || {
+                loop {
+                    let next = queue.lock().unwrap_or_else(PoisonError::into_inner).next();
+                    let Some(path) = next else {
+                        break;
+                    };
+                    if let Some(fingerprint) = Fingerprint::read(&path)
+                        && tx.send((path, fingerprint)).is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+        for (path, fingerprint) in rx {
+            if !on_done(path, fingerprint) {
+                // Dropping the receiver stops the workers at their next photo.
+                break;
+            }
+        }
+    });
+}
+
+/// Fingerprints of the photos in the playlist, for finding each one's copies.
+#[derive(Default)]
+pub struct Copies {
+    fingerprints: HashMap<PathBuf, Fingerprint>,
+    by_quarter: [HashMap<u16, Vec<PathBuf>>; QUARTERS],
+}
+
+impl Copies {
+    pub fn insert(&mut self, path: PathBuf, fingerprint: Fingerprint) {
+        self.remove(&path);
+        for (index, quarters) in self.by_quarter.iter_mut().enumerate() {
+            quarters
+                .entry(fingerprint.quarter(index))
+                .or_default()
+                .push(path.clone());
+        }
+        self.fingerprints.insert(path, fingerprint);
+    }
+
+    pub fn remove(&mut self, path: &Path) -> Option<Fingerprint> {
+        let fingerprint = self.fingerprints.remove(path)?;
+        for (index, quarters) in self.by_quarter.iter_mut().enumerate() {
+            let quarter = fingerprint.quarter(index);
+            if let Some(paths) = quarters.get_mut(&quarter) {
+                paths.retain(|other| other != path);
+                if paths.is_empty() {
+                    quarters.remove(&quarter);
+                }
+            }
+        }
+        Some(fingerprint)
+    }
+
+    /// Whether `a` and `b` are both fingerprinted and copies of each other.
+    pub fn are_copies(&self, a: &Path, b: &Path) -> bool {
+        match (self.fingerprints.get(a), self.fingerprints.get(b)) {
+            (Some(a), Some(b)) => a.is_copy_of(b),
+            _ => false,
+        }
+    }
+
+    /// The other copies of `path`, sorted so seeded runs are repeatable.
+    pub fn copies_of(&self, path: &Path) -> Vec<PathBuf> {
+        let Some(fingerprint) = self.fingerprints.get(path) else {
+            return Vec::new();
+        };
+        let mut copies: Vec<PathBuf> = self
+            .by_quarter
+            .iter()
+            .enumerate()
+            .filter_map(|(index, quarters)| quarters.get(&fingerprint.quarter(index)))
+            .flatten()
+            .filter(|other| {
+                other.as_path() != path && fingerprint.is_copy_of(&self.fingerprints[*other])
+            })
+            .cloned()
+            .collect();
+        copies.sort();
+        copies.dedup();
+        copies
+    }
+
+    /// The best copy of `path` when it is not `path` itself: the one with
+    /// the most pixels, and of those the smallest path.
+    pub fn better_copy(&self, path: &Path) -> Option<PathBuf> {
+        let rank = |path: &Path| {
+            (
+                self.fingerprints[path].pixels(),
+                std::cmp::Reverse(path.to_path_buf()),
+            )
+        };
+        let own = rank(path);
+        self.copies_of(path)
+            .into_iter()
+            .filter(|copy| rank(copy) > own)
+            .max_by_key(|copy| rank(copy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, GrayImage, Luma, imageops::FilterType};
+
+    /// A picture with enough structure to hash: diagonal bands whose width
+    /// depends on `seed`.
+    fn picture(seed: u32, width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(width, height, |x, y| {
+            let band = (x * 7 + y * 3 * seed) / (width / 8).max(1);
+            Luma([((band * 37 + seed * 11) % 256) as u8])
+        }))
+    }
+
+    fn fingerprint(hash: u64, width: u32, height: u32) -> Fingerprint {
+        Fingerprint {
+            hash,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn a_resized_copy_matches_and_another_picture_does_not() {
+        let original = picture(1, 1200, 900);
+        let smaller = original.resize_exact(400, 300, FilterType::Triangle);
+        let other = picture(5, 1200, 900);
+
+        let a = Fingerprint::of(&original);
+        let b = Fingerprint::of(&smaller);
+        assert_eq!((b.width, b.height), (400, 300));
+        assert!(a.is_copy_of(&b), "{:064b}\n{:064b}", a.hash, b.hash);
+        assert!(!a.is_copy_of(&Fingerprint::of(&other)));
+    }
+
+    #[test]
+    fn a_crop_is_not_a_copy() {
+        let a = fingerprint(0xF0F0, 1200, 900);
+        assert!(a.is_copy_of(&fingerprint(0xF0F1, 600, 450)));
+        assert!(!a.is_copy_of(&fingerprint(0xF0F0, 900, 900)));
+        assert!(!a.is_copy_of(&fingerprint(0xF0F7, 1200, 900)));
+    }
+
+    #[test]
+    fn the_copy_with_the_most_pixels_is_best() {
+        let mut copies = Copies::default();
+        let (big, small, other) = (
+            PathBuf::from("/b/big.jpg"),
+            PathBuf::from("/a/small.jpg"),
+            PathBuf::from("/a/other.jpg"),
+        );
+        copies.insert(small.clone(), fingerprint(u64::MAX, 400, 300));
+        copies.insert(big.clone(), fingerprint(u64::MAX - 1, 1200, 900));
+        copies.insert(other.clone(), fingerprint(0, 1200, 900));
+
+        assert_eq!(copies.copies_of(&small), vec![big.clone()]);
+        assert_eq!(copies.better_copy(&small), Some(big.clone()));
+        assert_eq!(copies.better_copy(&big), None);
+        assert_eq!(copies.better_copy(&other), None);
+        assert!(copies.copies_of(&other).is_empty());
+
+        // Two copies of one size: the smaller path is kept.
+        let twin = PathBuf::from("/c/big.jpg");
+        copies.insert(twin.clone(), fingerprint(u64::MAX, 1200, 900));
+        assert_eq!(copies.better_copy(&twin), Some(big.clone()));
+
+        copies.remove(&big);
+        assert_eq!(copies.better_copy(&small), Some(twin));
+        assert!(!copies.are_copies(&small, &big));
+    }
+
+    #[test]
+    fn fingerprints_every_readable_photo() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut paths = Vec::new();
+        for seed in 1..=5 {
+            let path = dir.path().join(format!("{seed}.png"));
+            picture(seed, 90, 80).save(&path).unwrap();
+            paths.push(path);
+        }
+        let broken = dir.path().join("broken.jpg");
+        std::fs::write(&broken, b"not a photo").unwrap();
+        paths.push(broken);
+
+        let mut done = Vec::new();
+        fingerprint_photos(paths.clone(), |path, _| {
+            done.push(path);
+            true
+        });
+        done.sort();
+        paths.pop();
+        paths.sort();
+        assert_eq!(done, paths);
+
+        let mut first = Vec::new();
+        fingerprint_photos(paths, |path, _| {
+            first.push(path);
+            false
+        });
+        assert_eq!(first.len(), 1);
+    }
+}
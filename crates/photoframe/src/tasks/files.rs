@@ -1,60 +1,199 @@
-use crate::config::Configuration;
-use crate::events::{InvalidPhoto, InventoryEvent, PhotoInfo};
-use anyhow::Result;
+use crate::config::{Configuration, DedupMode, LibraryRoot, PlaylistFavorites};
+use crate::events::{InvalidPhoto, InventoryEvent, PhotoInfo, ViewerCommand};
+use crate::tasks::dedup::{self, Fingerprint, MAX_CONCURRENT_HASHES};
+use crate::tasks::loader::HEIF_EXTENSIONS;
+use anyhow::{Result, ensure};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use notify::event::{CreateKind, ModifyKind, RemoveKind};
-use notify::{Event, EventKind, RecursiveMode, Watcher, recommended_watcher};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher, recommended_watcher};
 use rand::{SeedableRng, seq::SliceRandom};
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::{Semaphore, watch};
 use tokio_util::sync::CancellationToken;
 use tracing::instrument;
 use tracing::{debug, error, info, warn};
 
 /// Image file extensions recognised by the scanner (lowercase, without leading dot).
-const SUPPORTED_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp"];
+const SUPPORTED_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "apng", "webp", "gif"];
+/// Minimum spacing between scan progress reports sent to the viewer.
+const SCAN_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// How often a missing library directory is checked for again.
+const MISSING_LIBRARY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// How often the library is rescanned when no filesystem watcher is available,
+/// or a root is set not to be watched.
+const FALLBACK_RESCAN_INTERVAL: Duration = Duration::from_secs(30);
+/// Gitignore-style file naming photos and folders to leave out, read from the
+/// library root and from any folder below it.
+pub const IGNORE_FILE_NAME: &str = ".photoframeignore";
+/// Earlier name of [`IGNORE_FILE_NAME`], still honored. Where a folder has both,
+/// `.photoframeignore` patterns take precedence.
+pub const LEGACY_IGNORE_FILE_NAME: &str = ".frameignore";
 use walkdir::WalkDir;
 
+mod index;
+
+pub use self::index::INDEX_FILE_NAME;
+use self::index::LibraryIndex;
+
+/// A photo's `playlist.dedup` fingerprint, on its way to the manager.
+type Fingerprinted = (PathBuf, Fingerprint);
+
+/// Scans the library, then reports photos as they come and go. While
+/// `library_hold` carries a reason (a maintenance pause for a sync), changes
+/// are held back and handed to the manager in one batch once it clears.
 #[instrument(
-    skip(to_manager, invalid_rx, cancel),
-    fields(root = %cfg.photo_library_path.display())
+    skip(to_manager, invalid_rx, viewer_tx, cancel, library_hold),
+    fields(roots = ?cfg.library_paths())
 )]
 pub async fn run(
     cfg: Arc<Configuration>,
     to_manager: Sender<InventoryEvent>,
     mut invalid_rx: Receiver<InvalidPhoto>,
+    viewer_tx: Sender<ViewerCommand>,
     cancel: CancellationToken,
+    library_hold: watch::Receiver<Option<String>>,
 ) -> Result<()> {
-    // 1) Startup scan (recursive) -> collect, shuffle, emit
-    let initial = discover_startup_photos(&cfg)?;
+    let (gated_tx, gated_rx) = mpsc::channel::<InventoryEvent>(128);
+    tokio::spawn(gate_inventory(gated_rx, to_manager, library_hold));
+    let to_manager = gated_tx;
+    let roots = &cfg.photo_library_paths;
+    let root_paths = cfg.library_paths();
+    // A library directory that is not there yet (an unmounted share, a sync job
+    // that has not run) is checked for again until it appears; while no photos
+    // are found the empty-library card stays up.
+    let (present, mut missing): (Vec<LibraryRoot>, Vec<LibraryRoot>) =
+        roots.iter().cloned().partition(|root| root.path.is_dir());
+    for root in &missing {
+        warn!(
+            root = %root.path.display(),
+            "photo library path does not exist; waiting for it to be created"
+        );
+    }
+
+    // 1) Startup scan (recursive) -> collect, shuffle, emit. Progress is best-effort:
+    // try_send drops reports rather than stalling the scan on a busy viewer.
+    let mut last_report: Option<Instant> = None;
+    let mut ignore = LibraryIgnore::new(&root_paths);
+    let started = Instant::now();
+    let (initial, indexes) = scan_roots(&cfg, &present, &mut ignore, |discovered| {
+        if last_report.is_none_or(|at| at.elapsed() >= SCAN_PROGRESS_INTERVAL) {
+            last_report = Some(Instant::now());
+            let _ = viewer_tx.try_send(ViewerCommand::GreetingProgress {
+                discovered,
+                complete: false,
+            });
+        }
+    });
     for info in &initial {
         debug!(action = "startup_add", path = %info.path.display());
         let _ = to_manager
             .send(InventoryEvent::PhotoAdded(info.clone()))
             .await;
     }
+    let _ = viewer_tx
+        .send(ViewerCommand::GreetingProgress {
+            discovered: initial.len(),
+            complete: true,
+        })
+        .await;
     info!(
         discovered = initial.len(),
+        elapsed_ms = started.elapsed().as_millis(),
+        library_index = cfg.library_index.enabled,
+        indexed = indexes.iter().map(LibraryIndex::hits).sum::<usize>(),
+        to_read = indexes.iter().map(LibraryIndex::misses).sum::<usize>(),
         "startup recursive scan complete (shuffled)"
     );
+    // Capture dates of new and changed photos, read in the background for the
+    // library index; each one re-weights a photo already in the playlist.
+    let (dated_tx, mut dated_rx) = mpsc::channel::<PhotoInfo>(64);
+    // Fingerprints for `playlist.dedup`: the photos found so far are hashed
+    // along with the index refresh, later arrivals as they come.
+    let (hashed_tx, mut hashed_rx) = mpsc::channel::<Fingerprinted>(64);
+    let dedup = cfg.playlist.dedup.mode != DedupMode::Off;
+    let startup_photos = || initial.iter().map(|info| info.path.clone());
+    refresh_library(
+        indexes,
+        dated_tx.clone(),
+        dedup.then(|| (startup_photos().collect(), hashed_tx.clone())),
+    );
+    let exif_dates = cfg.library_index.enabled;
+    let hasher = dedup.then(|| AddedHasher::new(hashed_tx));
+    let mut presence = LibraryPresence::new(startup_photos(), viewer_tx, hasher);
+    if initial.is_empty() {
+        warn!(roots = ?root_paths, "photo library is empty; waiting for photos to be added");
+        presence.report(true).await;
+    }
+    let mut missing_poll = tokio::time::interval(MISSING_LIBRARY_POLL_INTERVAL);
+    missing_poll.reset();
 
-    // 2) Bridge notify callback -> async channel
+    // 2) Bridge notify callback -> async channel. Without a watcher (inotify
+    // limits exhausted, an unsupported filesystem) the library is rescanned on
+    // an interval instead, so photos still come and go, only more slowly.
     let (watch_tx, mut watch_rx) = mpsc::channel::<notify::Result<Event>>(128);
-    let mut _watcher = recommended_watcher(move |res| {
-        let _ = watch_tx.blocking_send(res);
-    })?;
-
-    // Log what we’re watching
-    match cfg.photo_library_path.canonicalize() {
-        Ok(abs) => info!(watching = %abs.display(), "notify watcher initialized (recursive)"),
-        Err(_) => {
-            info!(watching = %cfg.photo_library_path.display(), "notify watcher initialized (recursive)")
+    let mut library_watcher = match watch_library(&present, watch_tx) {
+        Ok(watcher) => {
+            // Log what we’re watching
+            for root in &present {
+                let path = &root.path;
+                if root.watch {
+                    let watching = path.canonicalize().unwrap_or_else(|_| path.clone());
+                    info!(watching = %watching.display(), "notify watcher initialized (recursive)");
+                }
+            }
+            Some(watcher)
+        }
+        Err(err) => {
+            warn!(
+                error = %err,
+                interval = ?FALLBACK_RESCAN_INTERVAL,
+                "filesystem watcher unavailable; rescanning the library periodically"
+            );
+            None
         }
+    };
+    // Roots with `watch: false` are rescanned along with the rest of the
+    // library; the rescan sees every root at once.
+    for root in roots.iter().filter(|root| !root.watch) {
+        info!(
+            root = %root.path.display(),
+            interval = ?FALLBACK_RESCAN_INTERVAL,
+            "photo library not watched; rescanning it periodically"
+        );
     }
-    _watcher.watch(&cfg.photo_library_path, RecursiveMode::Recursive)?;
+    let polling = library_watcher.is_none() || roots.iter().any(|root| !root.watch);
+    let mut rescan = tokio::time::interval(FALLBACK_RESCAN_INTERVAL);
+    rescan.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    rescan.reset();
+    // Photos that failed to decode stay out of the periodic rescan; the watcher
+    // gets another try at them when the file changes, the rescan cannot tell.
+    let mut rejected = HashSet::<PathBuf>::new();
+
+    // The favorites file is watched through its directory so that editors which
+    // replace the file, and deleting and re-creating it, are all noticed.
+    let favorites = cfg.playlist.favorites.as_ref();
+    let mut favorite_paths = favorites.map(PlaylistFavorites::load).unwrap_or_default();
+    let (fav_tx, mut fav_rx) = mpsc::channel::<notify::Result<Event>>(16);
+    let _favorites_watcher = match favorites {
+        Some(favorites) => match watch_favorites(&favorites.file, fav_tx) {
+            Ok(watcher) => Some(watcher),
+            Err(err) => {
+                warn!(
+                    path = %favorites.file.display(),
+                    error = %err,
+                    "favorites file cannot be watched; changes need a restart"
+                );
+                None
+            }
+        },
+        None => None,
+    };
 
     // 3) Event loop
     loop {
@@ -68,27 +207,43 @@ pub async fn run(
             // A decode failure can be transient (the file is still being copied
             // in by the sync job, or a momentary read error), and destroying a
             // user's photo is never acceptable. Drop it from the current rotation
-            // only; it is retried on the next startup scan or re-add event.
+            // only; it is retried on the next startup scan or re-add event,
+            // unless the manager has quarantined it after repeated failures.
             Some(InvalidPhoto(path)) = invalid_rx.recv() => {
                 warn!(path = %path.display(), "photo failed to decode; skipping (left on disk)");
-                let _ = to_manager.send(InventoryEvent::PhotoRemoved(path)).await;
+                if polling {
+                    rejected.insert(path.clone());
+                }
+                presence.remove(&path).await;
+                let _ = to_manager.send(InventoryEvent::PhotoFailed(path)).await;
             }
 
             // Filesystem notifications -> InventoryEvent
             Some(res) = watch_rx.recv() => match res {
-                Ok(event) => {
+                Ok(mut event) => {
                     debug!(kind = ?event.kind, paths = ?event.paths, "notify event");
+                    // An edited ignore file can both hide and reveal photos.
+                    if event.paths.iter().any(|p| is_ignore_file(p)) {
+                        ignore = resync(roots, &rejected, exif_dates, &mut presence, &to_manager).await;
+                    }
+                    event.paths.retain(|p| !ignore.excludes(p, false));
                     match &event.kind {
                         EventKind::Create(CreateKind::File) => {
                             for p in event.paths.into_iter().filter(|p| is_image(p.as_path())) {
+                                if !presence.is_new(&p) {
+                                    continue;
+                                }
                                 debug!(path = %p.display(), "fs: add (create)");
-                                let created_at = photo_created_at(&p);
-                                let info = PhotoInfo { path: p.clone(), created_at };
+                                let info = added_photo(roots, &p, exif_dates);
                                 let _ = to_manager.send(InventoryEvent::PhotoAdded(info)).await;
+                                presence.add(p).await;
                             }
                         }
                         EventKind::Remove(RemoveKind::File) => {
                             for p in event.paths.into_iter().filter(|p| is_image(p.as_path())) {
+                                if !presence.remove(&p).await {
+                                    continue;
+                                }
                                 debug!(path = %p.display(), "fs: remove (remove)");
                                 let _ = to_manager.send(InventoryEvent::PhotoRemoved(p)).await;
                             }
@@ -97,11 +252,14 @@ pub async fn run(
                             // macOS often reports moves as Name(Any). Decide per-path by existence.
                             for p in event.paths.into_iter().filter(|p| is_image(p.as_path())) {
                                 if p.exists() {
+                                    if !presence.is_new(&p) {
+                                        continue;
+                                    }
                                     debug!(path = %p.display(), "fs: add (rename/name)");
-                                    let created_at = photo_created_at(&p);
-                                    let info = PhotoInfo { path: p.clone(), created_at };
+                                    let info = added_photo(roots, &p, exif_dates);
                                     let _ = to_manager.send(InventoryEvent::PhotoAdded(info)).await;
-                                } else {
+                                    presence.add(p).await;
+                                } else if presence.remove(&p).await {
                                     debug!(path = %p.display(), "fs: remove (rename/name)");
                                     let _ = to_manager.send(InventoryEvent::PhotoRemoved(p)).await;
                                 }
@@ -113,18 +271,465 @@ pub async fn run(
                     }
                 }
                 Err(err) => error!("watch error: {err}"),
+            },
+
+            // No watcher: diff a fresh scan against what the manager knows.
+            _ = rescan.tick(), if polling => {
+                rejected.retain(|p| p.exists());
+                ignore = resync(roots, &rejected, exif_dates, &mut presence, &to_manager).await;
+            }
+
+            Some(info) = dated_rx.recv() => {
+                if presence.photos.contains_key(&info.path) {
+                    debug!(path = %info.path.display(), "library index: capture date read");
+                    let _ = to_manager.send(InventoryEvent::PhotoAdded(info)).await;
+                }
+            }
+
+            Some((path, fingerprint)) = hashed_rx.recv() => {
+                if presence.photos.contains_key(&path) {
+                    let _ = to_manager.send(InventoryEvent::PhotoFingerprinted(path, fingerprint)).await;
+                }
+            }
+
+            // A missing library root appeared: scan it and start watching it.
+            _ = missing_poll.tick(), if !missing.is_empty() => {
+                let appeared: Vec<LibraryRoot>;
+                (appeared, missing) = missing.drain(..).partition(|root| root.path.is_dir());
+                for root in appeared {
+                    if root.watch
+                        && let Some(watcher) = library_watcher.as_mut()
+                        && let Err(err) = watcher.watch(&root.path, RecursiveMode::Recursive)
+                    {
+                        warn!(root = %root.path.display(), error = %err, "cannot watch photo library");
+                    }
+                    let (found, indexes) = scan_roots(&cfg, std::slice::from_ref(&root), &mut ignore, |_| {});
+                    refresh_library(indexes, dated_tx.clone(), None);
+                    info!(root = %root.path.display(), discovered = found.len(), "photo library appeared");
+                    for info in found {
+                        if !presence.is_new(&info.path) {
+                            continue;
+                        }
+                        debug!(action = "startup_add", path = %info.path.display());
+                        let path = info.path.clone();
+                        let _ = to_manager.send(InventoryEvent::PhotoAdded(info)).await;
+                        presence.add(path).await;
+                    }
+                }
+            }
+
+            // Favorites file edits -> re-weight the playlist
+            Some(res) = fav_rx.recv() => match (res, favorites) {
+                (Ok(event), Some(favorites)) => {
+                    let name = favorites.file.file_name();
+                    if event.paths.iter().any(|p| p.file_name() == name) {
+                        let next = favorites.load();
+                        if next != favorite_paths {
+                            info!(favorites = next.len(), "favorites file changed");
+                            favorite_paths = next.clone();
+                            let _ = to_manager.send(InventoryEvent::FavoritesChanged(next)).await;
+                        }
+                    }
+                }
+                (Ok(_), None) => {}
+                (Err(err), _) => error!("favorites watch error: {err}"),
             }
         }
     }
     Ok(())
 }
 
+/// Passes inventory changes on to the manager, holding them while
+/// `library_hold` carries a reason and releasing them together after.
+async fn gate_inventory(
+    mut changes: Receiver<InventoryEvent>,
+    to_manager: Sender<InventoryEvent>,
+    mut library_hold: watch::Receiver<Option<String>>,
+) {
+    let mut held = HeldChanges::default();
+    let mut holding = library_hold.borrow_and_update().is_some();
+    let mut hold_open = true;
+    loop {
+        tokio::select! {
+            change = changes.recv() => {
+                let Some(change) = change else {
+                    break;
+                };
+                if holding {
+                    held.push(change);
+                } else if to_manager.send(change).await.is_err() {
+                    break;
+                }
+            }
+            changed = library_hold.changed(), if hold_open => {
+                // A dropped sender can no longer release the hold, so it ends.
+                hold_open = changed.is_ok();
+                let reason = library_hold.borrow_and_update().clone().filter(|_| hold_open);
+                if let Some(reason) = &reason && !holding {
+                    info!(reason, "library held; inventory changes wait for resume");
+                }
+                holding = reason.is_some();
+                if !holding && !held.is_empty() {
+                    info!(changes = held.len(), "library released; applying held changes");
+                    for change in held.drain() {
+                        if to_manager.send(change).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Inventory changes waiting out a library hold, in arrival order. A later
+/// add or remove of a path replaces an earlier one, and the latest favorites
+/// list replaces earlier ones, so a file a sync rewrites many times is
+/// reported once.
+#[derive(Default)]
+struct HeldChanges {
+    changes: Vec<Option<InventoryEvent>>,
+    by_path: HashMap<PathBuf, usize>,
+    favorites: Option<usize>,
+}
+
+impl HeldChanges {
+    fn push(&mut self, change: InventoryEvent) {
+        let index = self.changes.len();
+        let replaced = match &change {
+            InventoryEvent::PhotoAdded(PhotoInfo { path, .. })
+            | InventoryEvent::PhotoRemoved(path) => self.by_path.insert(path.clone(), index),
+            InventoryEvent::FavoritesChanged(_) => self.favorites.replace(index),
+            InventoryEvent::PhotoFailed(_) | InventoryEvent::PhotoFingerprinted(..) => None,
+        };
+        if let Some(replaced) = replaced {
+            self.changes[replaced] = None;
+        }
+        self.changes.push(Some(change));
+    }
+
+    fn len(&self) -> usize {
+        self.changes.iter().flatten().count()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    fn drain(&mut self) -> impl Iterator<Item = InventoryEvent> + use<> {
+        self.by_path.clear();
+        self.favorites = None;
+        std::mem::take(&mut self.changes).into_iter().flatten()
+    }
+}
+
+/// Brings the manager in line with a fresh walk of the library: photos that are
+/// gone or now ignored are removed, new or no longer ignored ones are added.
+/// Paths in `rejected` failed to decode and are left out. Returns the ignore
+/// rules found on the way.
+async fn resync(
+    roots: &[LibraryRoot],
+    rejected: &HashSet<PathBuf>,
+    exif_dates: bool,
+    presence: &mut LibraryPresence,
+    to_manager: &Sender<InventoryEvent>,
+) -> LibraryIgnore {
+    let paths: Vec<PathBuf> = roots.iter().map(|root| root.path.clone()).collect();
+    let mut ignore = LibraryIgnore::new(&paths);
+    // Keyed by canonical path, so a photo under two roots is found once, under
+    // the first.
+    let mut found = HashMap::<PathBuf, (PathBuf, f64)>::new();
+    for root in roots {
+        for entry in walk_library(&root.path, &mut ignore).filter(|e| is_image(e.path())) {
+            let path = entry.into_path();
+            found
+                .entry(canonical_path(&path))
+                .or_insert((path, root.weight));
+        }
+    }
+    let removed: Vec<PathBuf> = presence
+        .photos
+        .iter()
+        .filter(|(_, canonical)| !found.contains_key(*canonical))
+        .map(|(path, _)| path.clone())
+        .collect();
+    for p in removed {
+        debug!(path = %p.display(), "fs: remove (rescan)");
+        presence.remove(&p).await;
+        let _ = to_manager.send(InventoryEvent::PhotoRemoved(p)).await;
+    }
+    for (canonical, (p, root_weight)) in found {
+        if presence.canonical.contains(&canonical) || rejected.contains(&p) {
+            continue;
+        }
+        debug!(path = %p.display(), "fs: add (rescan)");
+        let info = PhotoInfo {
+            path: p.clone(),
+            created_at: added_photo_created_at(&p, exif_dates),
+            root_weight,
+        };
+        let _ = to_manager.send(InventoryEvent::PhotoAdded(info)).await;
+        presence.add(p).await;
+    }
+    ignore
+}
+
+/// Watches each library root recursively, leaving out those set not to be.
+fn watch_library(
+    roots: &[LibraryRoot],
+    tx: Sender<notify::Result<Event>>,
+) -> notify::Result<RecommendedWatcher> {
+    let mut watcher = recommended_watcher(move |res| {
+        let _ = tx.blocking_send(res);
+    })?;
+    for root in roots.iter().filter(|root| root.watch) {
+        watcher.watch(&root.path, RecursiveMode::Recursive)?;
+    }
+    Ok(watcher)
+}
+
+/// Watches the directory holding the favorites file, non-recursively.
+fn watch_favorites(
+    file: &Path,
+    tx: Sender<notify::Result<Event>>,
+) -> notify::Result<RecommendedWatcher> {
+    let dir = match file.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut watcher = recommended_watcher(move |res| {
+        let _ = tx.blocking_send(res);
+    })?;
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+/// Tracks which photos the library currently holds so the viewer hears when it
+/// becomes empty (and when the first photo arrives again).
+struct LibraryPresence {
+    /// Every photo reported to the manager, with its canonical path.
+    photos: HashMap<PathBuf, PathBuf>,
+    /// Canonical paths of `photos`, so a photo reachable from two roots (or
+    /// through a symlink) is reported once.
+    canonical: HashSet<PathBuf>,
+    viewer_tx: Sender<ViewerCommand>,
+    /// Fingerprints each photo added, with `playlist.dedup` on.
+    hasher: Option<AddedHasher>,
+}
+
+impl LibraryPresence {
+    fn new(
+        photos: impl IntoIterator<Item = PathBuf>,
+        viewer_tx: Sender<ViewerCommand>,
+        hasher: Option<AddedHasher>,
+    ) -> Self {
+        let photos: HashMap<PathBuf, PathBuf> = photos
+            .into_iter()
+            .map(|path| {
+                let canonical = canonical_path(&path);
+                (path, canonical)
+            })
+            .collect();
+        Self {
+            canonical: photos.values().cloned().collect(),
+            photos,
+            viewer_tx,
+            hasher,
+        }
+    }
+
+    /// Whether `path` is a photo the manager has not been told about, under
+    /// this path or any other.
+    fn is_new(&self, path: &Path) -> bool {
+        !self.photos.contains_key(path) && !self.canonical.contains(&canonical_path(path))
+    }
+
+    async fn add(&mut self, path: PathBuf) {
+        let was_empty = self.photos.is_empty();
+        let canonical = canonical_path(&path);
+        if self
+            .photos
+            .insert(path.clone(), canonical.clone())
+            .is_none()
+        {
+            self.canonical.insert(canonical);
+            if let Some(hasher) = &self.hasher {
+                hasher.hash(path);
+            }
+            if was_empty {
+                info!("photo library is no longer empty");
+                self.report(false).await;
+            }
+        }
+    }
+
+    /// Forgets `path`; returns whether it was present.
+    async fn remove(&mut self, path: &Path) -> bool {
+        let Some(canonical) = self.photos.remove(path) else {
+            return false;
+        };
+        self.canonical.remove(&canonical);
+        if self.photos.is_empty() {
+            warn!("photo library is now empty");
+            self.report(true).await;
+        }
+        true
+    }
+
+    async fn report(&self, empty: bool) {
+        let _ = self
+            .viewer_tx
+            .send(ViewerCommand::LibraryEmpty(empty))
+            .await;
+    }
+}
+
+/// `path` with symlinks and `..` resolved, or as given once the file is gone.
+fn canonical_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
 #[inline]
 fn is_image(p: &Path) -> bool {
     p.extension()
         .and_then(OsStr::to_str)
         .map(|s| s.to_ascii_lowercase())
-        .is_some_and(|ext| SUPPORTED_EXTENSIONS.contains(&ext.as_str()))
+        .is_some_and(|ext| {
+            // HEIC/HEIF is listed even without the `heif` feature, so the
+            // loader reports each such photo as invalid, naming the feature.
+            SUPPORTED_EXTENSIONS.contains(&ext.as_str()) || HEIF_EXTENSIONS.contains(&ext.as_str())
+        })
+}
+
+/// A photo found after the startup scan, weighted by the first root it is
+/// under, as the startup scan would list it.
+fn added_photo(roots: &[LibraryRoot], path: &Path, exif_dates: bool) -> PhotoInfo {
+    let root_weight = roots
+        .iter()
+        .find(|root| path.starts_with(&root.path))
+        .map_or(1.0, |root| root.weight);
+    PhotoInfo {
+        path: path.to_path_buf(),
+        created_at: added_photo_created_at(path, exif_dates),
+        root_weight,
+    }
+}
+
+/// When a photo found after the startup scan was taken: its EXIF capture
+/// date when the library index is on, as the index would record it.
+fn added_photo_created_at(path: &Path, exif_dates: bool) -> SystemTime {
+    exif_dates
+        .then(|| index::capture_date(path))
+        .flatten()
+        .unwrap_or_else(|| photo_created_at(path))
+}
+
+/// Reads new and changed photos into `indexes` on a blocking thread, sending
+/// each one whose capture date differs from its file time. With `fingerprint`
+/// set, the photos it lists are then fingerprinted for `playlist.dedup`.
+/// The indexes are written back last.
+fn refresh_library(
+    mut indexes: Vec<LibraryIndex>,
+    dated_tx: Sender<PhotoInfo>,
+    fingerprint: Option<(Vec<PathBuf>, Sender<Fingerprinted>)>,
+) {
+    if indexes.is_empty() && fingerprint.is_none() {
+        return;
+    }
+    tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        for index in &mut indexes {
+            // A closed channel means the files task is gone; stop reading.
+            index.read_stale(|info| dated_tx.blocking_send(info).is_ok());
+        }
+        if let Some((photos, hashed_tx)) = fingerprint {
+            fingerprint_library(&mut indexes, photos, &hashed_tx);
+        }
+        for index in indexes {
+            match index.save() {
+                Ok(()) => info!(
+                    root = %index.root().display(),
+                    read = index.misses(),
+                    elapsed_ms = started.elapsed().as_millis(),
+                    "library index up to date"
+                ),
+                Err(err) => warn!(
+                    root = %index.root().display(),
+                    error = %format!("{err:#}"),
+                    "cannot write the library index; the next start reads these photos again"
+                ),
+            }
+        }
+    });
+}
+
+/// Sends a fingerprint for each of `photos`: the one its index kept, or a
+/// fresh one, which the index keeps from now on.
+fn fingerprint_library(
+    indexes: &mut [LibraryIndex],
+    photos: Vec<PathBuf>,
+    hashed_tx: &Sender<Fingerprinted>,
+) {
+    let started = Instant::now();
+    let mut to_hash = Vec::new();
+    let mut cached = 0;
+    for path in photos {
+        match indexes.iter().find_map(|index| index.fingerprint(&path)) {
+            Some(fingerprint) => {
+                if hashed_tx.blocking_send((path, fingerprint)).is_err() {
+                    return;
+                }
+                cached += 1;
+            }
+            None => to_hash.push(path),
+        }
+    }
+    let mut hashed = 0;
+    dedup::fingerprint_photos(to_hash, |path, fingerprint| {
+        hashed += 1;
+        indexes
+            .iter_mut()
+            .any(|index| index.set_fingerprint(&path, fingerprint));
+        hashed_tx.blocking_send((path, fingerprint)).is_ok()
+    });
+    info!(
+        cached,
+        hashed,
+        elapsed_ms = started.elapsed().as_millis(),
+        "library fingerprinted for dedup"
+    );
+}
+
+/// Fingerprints photos found after the startup scan, at most
+/// [`MAX_CONCURRENT_HASHES`] at a time.
+struct AddedHasher {
+    permits: Arc<Semaphore>,
+    hashed_tx: Sender<Fingerprinted>,
+}
+
+impl AddedHasher {
+    fn new(hashed_tx: Sender<Fingerprinted>) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT_HASHES)),
+            hashed_tx,
+        }
+    }
+
+    fn hash(&self, path: PathBuf) {
+        let permits = Arc::clone(&self.permits);
+        let hashed_tx = self.hashed_tx.clone();
+        tokio::spawn(async move {
+            let Ok(_permit) = permits.acquire_owned().await else {
+                return;
+            };
+            let read = path.clone();
+            if let Ok(Some(fingerprint)) =
+                tokio::task::spawn_blocking(move || Fingerprint::read(&read)).await
+            {
+                let _ = hashed_tx.send((path, fingerprint)).await;
+            }
+        });
+    }
 }
 
 fn photo_created_at(path: &Path) -> SystemTime {
@@ -137,19 +742,210 @@ fn photo_created_at(path: &Path) -> SystemTime {
     }
 }
 
-pub fn discover_startup_photos(cfg: &Configuration) -> Result<Vec<PhotoInfo>> {
-    let mut initial = Vec::<PathBuf>::new();
+/// Ignore rules from every ignore file in the library. A file applies to its
+/// folder and everything below it, like a nested `.gitignore`: the deepest
+/// file with a matching pattern decides, and nothing inside an ignored folder
+/// can be re-included.
+struct LibraryIgnore {
+    roots: Vec<PathBuf>,
+    /// One matcher per folder with an ignore file, deepest folders first.
+    matchers: Vec<Gitignore>,
+}
+
+impl LibraryIgnore {
+    fn new(roots: &[PathBuf]) -> Self {
+        Self {
+            roots: roots.to_vec(),
+            matchers: Vec::new(),
+        }
+    }
+
+    /// Reads the ignore files in `dir`, if it has any. A pattern that does not
+    /// parse is logged with its line number and skipped.
+    fn load_dir(&mut self, dir: &Path) {
+        let mut builder = GitignoreBuilder::new(dir);
+        let mut found = false;
+        for name in [LEGACY_IGNORE_FILE_NAME, IGNORE_FILE_NAME] {
+            let file = dir.join(name);
+            let contents = match fs::read_to_string(&file) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(err) => {
+                    warn!(path = %file.display(), error = %err, "ignore file unreadable; skipping");
+                    continue;
+                }
+            };
+            found = true;
+            for (index, line) in contents.lines().enumerate() {
+                if let Err(err) = builder.add_line(Some(file.clone()), line) {
+                    warn!(
+                        path = %file.display(),
+                        line = index + 1,
+                        error = %err,
+                        "invalid ignore pattern; skipping"
+                    );
+                }
+            }
+        }
+        if !found {
+            return;
+        }
+        match builder.build() {
+            Ok(matcher) => {
+                debug!(
+                    dir = %dir.display(),
+                    ignores = matcher.num_ignores(),
+                    whitelists = matcher.num_whitelists(),
+                    "ignore file loaded"
+                );
+                self.matchers.push(matcher);
+                self.matchers
+                    .sort_by_key(|m| std::cmp::Reverse(m.path().components().count()));
+            }
+            Err(err) => warn!(dir = %dir.display(), error = %err, "ignore file unusable; skipping"),
+        }
+    }
+
+    /// Whether `path`, or a folder above it, is ignored.
+    fn excludes(&self, path: &Path, is_dir: bool) -> bool {
+        if self.matchers.is_empty() {
+            return false;
+        }
+        // Roots may nest; the rules start at the innermost one holding `path`.
+        let Some(root) = self
+            .roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+        else {
+            return false;
+        };
+        let mut folders: Vec<&Path> = path
+            .ancestors()
+            .skip(1)
+            .take_while(|folder| folder != root && folder.starts_with(root))
+            .collect();
+        folders.reverse();
+        folders.into_iter().any(|folder| self.matches(folder, true)) || self.matches(path, is_dir)
+    }
+
+    /// Whether the deepest ignore file above `path` with a pattern matching it
+    /// ignores it. The folders above `path` are not checked.
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        self.matchers
+            .iter()
+            .filter(|m| path != m.path() && path.starts_with(m.path()))
+            .map(|m| m.matched(path, is_dir))
+            .find(|m| !m.is_none())
+            .is_some_and(|m| m.is_ignore())
+    }
+}
+
+fn is_ignore_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == IGNORE_FILE_NAME || name == LEGACY_IGNORE_FILE_NAME)
+}
+
+/// Walks `root` for files, following symlinks, and leaves out whatever the
+/// library's ignore files exclude. Ignore files are read into `ignore` as their
+/// folders are entered, so afterwards it holds every one that applied.
+fn walk_library<'a>(
+    root: &Path,
+    ignore: &'a mut LibraryIgnore,
+) -> impl Iterator<Item = walkdir::DirEntry> + 'a {
     // follow_links(true) is intentional so symlinked sub-directories work. WalkDir's internal
     // inode tracker prevents infinite loops from circular symlinks.
-    for entry in WalkDir::new(&cfg.photo_library_path)
+    WalkDir::new(root)
         .follow_links(true)
         .into_iter()
+        .filter_entry(move |e| {
+            let is_dir = e.file_type().is_dir();
+            // Folders above were already checked on the way down.
+            if e.depth() > 0 && ignore.matches(e.path(), is_dir) {
+                return false;
+            }
+            if is_dir {
+                ignore.load_dir(e.path());
+            }
+            true
+        })
         .filter_map(Result::ok)
         .filter(|e| e.file_type().is_file())
-    {
-        let path = entry.path().to_path_buf();
-        if is_image(&path) {
-            initial.push(path);
+}
+
+/// Fails when a library root not marked `optional` is missing, so a frame
+/// whose library did not mount stops with an error rather than coming up
+/// without those photos.
+pub fn check_required_roots(cfg: &Configuration) -> Result<()> {
+    let missing: Vec<String> = cfg
+        .photo_library_paths
+        .iter()
+        .filter(|root| !root.optional && !root.path.is_dir())
+        .map(|root| root.path.display().to_string())
+        .collect();
+    ensure!(
+        missing.is_empty(),
+        "photo-library-path {} does not exist; mark it `optional: true` to start without it",
+        missing.join(", ")
+    );
+    Ok(())
+}
+
+pub fn discover_startup_photos(cfg: &Configuration) -> Result<Vec<PhotoInfo>> {
+    discover_startup_photos_with_progress(cfg, |_| {})
+}
+
+/// Like [`discover_startup_photos`], calling `on_progress` with the running count each
+/// time another photo is found.
+pub fn discover_startup_photos_with_progress(
+    cfg: &Configuration,
+    on_progress: impl FnMut(usize),
+) -> Result<Vec<PhotoInfo>> {
+    let mut ignore = LibraryIgnore::new(&cfg.library_paths());
+    let (mut photos, mut indexes) =
+        scan_roots(cfg, &cfg.photo_library_paths, &mut ignore, on_progress);
+    // Read what the index lacks right away, without writing it back.
+    let mut dated = HashMap::new();
+    for index in &mut indexes {
+        index.read_stale(|info| {
+            dated.insert(info.path, info.created_at);
+            true
+        });
+    }
+    for info in &mut photos {
+        if let Some(created_at) = dated.remove(&info.path) {
+            info.created_at = created_at;
+        }
+    }
+    Ok(photos)
+}
+
+/// The startup scan of `roots`, reading their ignore files into `ignore`. A
+/// photo reachable from more than one root is listed once, under the first,
+/// and carries that root's weight. With `library-index` on, capture dates
+/// come from each root's index, which is returned with the photos it does not
+/// cover yet still to be read.
+fn scan_roots(
+    cfg: &Configuration,
+    roots: &[LibraryRoot],
+    ignore: &mut LibraryIgnore,
+    mut on_progress: impl FnMut(usize),
+) -> (Vec<PhotoInfo>, Vec<LibraryIndex>) {
+    let mut indexes: Vec<LibraryIndex> = if cfg.library_index.enabled {
+        roots.iter().map(LibraryIndex::load).collect()
+    } else {
+        Vec::new()
+    };
+    // Each photo with the index of the root it was found under.
+    let mut initial = Vec::<(usize, PathBuf)>::new();
+    let mut seen = HashSet::<PathBuf>::new();
+    for (root_index, root) in roots.iter().enumerate() {
+        for entry in walk_library(&root.path, ignore) {
+            let path = entry.into_path();
+            if is_image(&path) && seen.insert(canonical_path(&path)) {
+                initial.push((root_index, path));
+                on_progress(initial.len());
+            }
         }
     }
 
@@ -159,11 +955,19 @@ pub fn discover_startup_photos(cfg: &Configuration) -> Result<Vec<PhotoInfo>> {
     };
     initial.shuffle(&mut rng);
 
-    Ok(initial
+    let photos = initial
         .into_iter()
-        .map(|path| {
-            let created_at = photo_created_at(&path);
-            PhotoInfo { path, created_at }
+        .map(|(root_index, path)| {
+            let created_at = match indexes.get_mut(root_index) {
+                Some(index) => index.created_at(&path),
+                None => photo_created_at(&path),
+            };
+            PhotoInfo {
+                path,
+                created_at,
+                root_weight: roots[root_index].weight,
+            }
         })
-        .collect())
+        .collect();
+    (photos, indexes)
 }
@@ -0,0 +1,419 @@
+//! The library index: what the startup scan learned about each photo, kept
+//! between runs.
+//!
+//! With `library-index.enabled` each library root holds a `.photoframe-index`
+//! file listing every photo's size, modification time, capture date, and EXIF
+//! orientation. The startup scan only stats each photo: one whose size and
+//! modification time still match its entry takes its capture date from the
+//! index, so playlist weighting is right from the first cycle. New and changed
+//! photos start out with their file time and have their EXIF block read in the
+//! background once the slideshow is running; the index is then written back,
+//! leaving out photos that are gone. An index that cannot be read is set aside
+//! with a warning and rebuilt as if the library were new. With
+//! `playlist.dedup` on, each photo's fingerprint is kept too, so only new and
+//! changed photos are hashed again.
+
+use super::photo_created_at;
+use crate::config::LibraryRoot;
+use crate::events::PhotoInfo;
+use crate::tasks::dedup::Fingerprint;
+use crate::tasks::quarantine::{FileStamp, epoch_ms};
+use anyhow::{Context, Result};
+use chrono::TimeZone;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{info, warn};
+
+/// Name of the index file kept in each library root.
+pub const INDEX_FILE_NAME: &str = ".photoframe-index";
+/// Bumped when [`IndexEntry`] changes shape; an index of another version is rebuilt.
+const INDEX_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    #[serde(flatten)]
+    pub stamp: FileStamp,
+    /// When the photo was taken, milliseconds since the Unix epoch: the EXIF
+    /// capture date, or the file's own time when it has none.
+    pub created_at_ms: u64,
+    /// EXIF orientation; 1 (upright) when the photo has none.
+    pub orientation: u16,
+    /// Set once the photo is fingerprinted for `playlist.dedup`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<Fingerprint>,
+}
+
+impl IndexEntry {
+    /// Reads the EXIF block of `path`, which has `stamp` on disk. `file_time`
+    /// stands in for a missing capture date.
+    fn read(path: &Path, stamp: FileStamp, file_time: SystemTime) -> Self {
+        let exif = read_exif(path);
+        let orientation = exif
+            .as_ref()
+            .and_then(|exif| exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY))
+            .and_then(|field| field.value.get_uint(0))
+            .and_then(|value| u16::try_from(value).ok())
+            .unwrap_or(1);
+        let created_at = exif.as_ref().and_then(capture_time).unwrap_or(file_time);
+        Self {
+            stamp,
+            created_at_ms: epoch_ms(created_at),
+            orientation,
+            fingerprint: None,
+        }
+    }
+
+    pub fn created_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.created_at_ms)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct IndexFile {
+    version: u32,
+    /// Keyed by path relative to the library root, so the index survives the
+    /// library being mounted somewhere else.
+    photos: BTreeMap<PathBuf, IndexEntry>,
+}
+
+/// One library root's index, filled in as the startup scan finds photos.
+pub struct LibraryIndex {
+    root: PathBuf,
+    /// The root's `weight`, carried by the photos it reports.
+    root_weight: f64,
+    /// The index file as it was when the scan started.
+    saved: HashMap<PathBuf, IndexEntry>,
+    /// Photos the scan found, unchanged since they were indexed or read since.
+    current: IndexFile,
+    /// New and changed photos still to be read, with their stamp and the file
+    /// time they start out with.
+    stale: Vec<(PathBuf, FileStamp, SystemTime)>,
+    read: usize,
+    fingerprinted: usize,
+}
+
+impl LibraryIndex {
+    pub fn load(library_root: &LibraryRoot) -> Self {
+        let root = &library_root.path;
+        let file = root.join(INDEX_FILE_NAME);
+        let saved = match fs::read(&file) {
+            Ok(bytes) => match serde_json::from_slice::<IndexFile>(&bytes) {
+                Ok(index) if index.version == INDEX_VERSION => index.photos.into_iter().collect(),
+                Ok(index) => {
+                    info!(
+                        path = %file.display(),
+                        version = index.version,
+                        "library index has another layout; rebuilding it"
+                    );
+                    HashMap::new()
+                }
+                Err(err) => {
+                    warn!(
+                        path = %file.display(),
+                        error = %err,
+                        "library index is unreadable; reading every photo again"
+                    );
+                    HashMap::new()
+                }
+            },
+            Err(err) if err.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(err) => {
+                warn!(
+                    path = %file.display(),
+                    error = %err,
+                    "cannot open the library index; reading every photo again"
+                );
+                HashMap::new()
+            }
+        };
+        Self {
+            root: root.to_path_buf(),
+            root_weight: library_root.weight,
+            saved,
+            current: IndexFile {
+                version: INDEX_VERSION,
+                photos: BTreeMap::new(),
+            },
+            stale: Vec::new(),
+            read: 0,
+            fingerprinted: 0,
+        }
+    }
+
+    /// When the photo at `path`, found under this root, was taken: from the
+    /// index while the file is unchanged, otherwise its file time until
+    /// [`Self::read_stale`] gets to it.
+    pub fn created_at(&mut self, path: &Path) -> SystemTime {
+        let (Ok(key), Some(stamp)) = (path.strip_prefix(&self.root), FileStamp::of(path)) else {
+            return photo_created_at(path);
+        };
+        match self.saved.get(key) {
+            Some(entry) if entry.stamp == stamp => {
+                self.current.photos.insert(key.to_path_buf(), *entry);
+                entry.created_at()
+            }
+            _ => {
+                let file_time = photo_created_at(path);
+                self.stale.push((path.to_path_buf(), stamp, file_time));
+                file_time
+            }
+        }
+    }
+
+    /// Photos found so far whose entry was still good.
+    pub fn hits(&self) -> usize {
+        self.current.photos.len() - self.read
+    }
+
+    /// Photos found so far that are new or changed since the index was written.
+    pub fn misses(&self) -> usize {
+        self.stale.len() + self.read
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Reads every new and changed photo into the index, handing `on_dated`
+    /// each one whose capture date differs from the file time it started out
+    /// with. Stops early when `on_dated` returns `false`; photos not read by
+    /// then are left out of the index and read on the next start.
+    pub fn read_stale(&mut self, mut on_dated: impl FnMut(PhotoInfo) -> bool) {
+        for (path, stamp, file_time) in std::mem::take(&mut self.stale) {
+            let entry = IndexEntry::read(&path, stamp, file_time);
+            let Ok(key) = path.strip_prefix(&self.root) else {
+                continue;
+            };
+            self.current.photos.insert(key.to_path_buf(), entry);
+            self.read += 1;
+            if entry.created_at_ms != epoch_ms(file_time)
+                && !on_dated(PhotoInfo {
+                    path,
+                    created_at: entry.created_at(),
+                    root_weight: self.root_weight,
+                })
+            {
+                break;
+            }
+        }
+    }
+
+    /// The fingerprint kept for the photo at `path`, while the file is
+    /// unchanged.
+    pub fn fingerprint(&self, path: &Path) -> Option<Fingerprint> {
+        let key = path.strip_prefix(&self.root).ok()?;
+        self.current.photos.get(key)?.fingerprint
+    }
+
+    /// Keeps `fingerprint` for the photo at `path`; returns whether the photo
+    /// is in this index.
+    pub fn set_fingerprint(&mut self, path: &Path, fingerprint: Fingerprint) -> bool {
+        let Some(entry) = path
+            .strip_prefix(&self.root)
+            .ok()
+            .and_then(|key| self.current.photos.get_mut(key))
+        else {
+            return false;
+        };
+        entry.fingerprint = Some(fingerprint);
+        self.fingerprinted += 1;
+        true
+    }
+
+    /// Writes the index back, without the photos the scan did not find. An
+    /// index the scan left as it was is not rewritten.
+    pub fn save(&self) -> Result<()> {
+        if self.read == 0
+            && self.fingerprinted == 0
+            && self.current.photos.len() == self.saved.len()
+        {
+            return Ok(());
+        }
+        let file = self.root.join(INDEX_FILE_NAME);
+        let partial = file.with_extension("tmp");
+        let json = serde_json::to_vec(&self.current).context("serialize library index")?;
+        fs::write(&partial, json)
+            .with_context(|| format!("write library index {}", partial.display()))?;
+        fs::rename(&partial, &file)
+            .with_context(|| format!("replace library index {}", file.display()))?;
+        Ok(())
+    }
+}
+
+/// The EXIF capture date of `path`, for photos found after startup.
+pub fn capture_date(path: &Path) -> Option<SystemTime> {
+    read_exif(path).as_ref().and_then(capture_time)
+}
+
+fn read_exif(path: &Path) -> Option<exif::Exif> {
+    let file = File::open(path).ok()?;
+    exif::Reader::new()
+        .read_from_container(&mut BufReader::new(file))
+        .ok()
+}
+
+/// `DateTimeOriginal`, in the time zone of `OffsetTimeOriginal` when the
+/// camera recorded one and in local time otherwise.
+fn capture_time(exif: &exif::Exif) -> Option<SystemTime> {
+    let ascii = |tag| match &exif.get_field(tag, exif::In::PRIMARY)?.value {
+        exif::Value::Ascii(values) => values.first(),
+        _ => None,
+    };
+    let mut taken = exif::DateTime::from_ascii(ascii(exif::Tag::DateTimeOriginal)?).ok()?;
+    if let Some(offset) = ascii(exif::Tag::OffsetTimeOriginal) {
+        let _ = taken.parse_offset(offset);
+    }
+    let local = chrono::NaiveDate::from_ymd_opt(
+        i32::from(taken.year),
+        u32::from(taken.month),
+        u32::from(taken.day),
+    )?
+    .and_hms_opt(
+        u32::from(taken.hour),
+        u32::from(taken.minute),
+        u32::from(taken.second),
+    )?;
+    let taken = match taken.offset {
+        Some(minutes) => chrono::FixedOffset::east_opt(i32::from(minutes) * 60)?
+            .from_local_datetime(&local)
+            .single()?
+            .into(),
+        None => chrono::Local.from_local_datetime(&local).earliest()?.into(),
+    };
+    Some(taken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dated_fixture() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/exif/dated.png")
+    }
+
+    /// Scans `root` the way the startup scan does, reading what is stale.
+    fn scan(root: &Path, names: &[&str]) -> (LibraryIndex, Vec<PhotoInfo>) {
+        let mut index = LibraryIndex::load(&LibraryRoot::new(root));
+        for name in names {
+            index.created_at(&root.join(name));
+        }
+        let mut dated = Vec::new();
+        index.read_stale(|info| {
+            dated.push(info);
+            true
+        });
+        (index, dated)
+    }
+
+    #[test]
+    fn reads_the_capture_date_and_orientation() {
+        let path = dated_fixture();
+        let stamp = FileStamp::of(&path).unwrap();
+        let entry = IndexEntry::read(&path, stamp, UNIX_EPOCH);
+        // 2019:05:04 12:00:00 at +02:00.
+        assert_eq!(entry.created_at_ms, 1_556_964_000_000);
+        assert_eq!(entry.orientation, 6);
+
+        let undated = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/exif/no-exif.png");
+        let entry = IndexEntry::read(&undated, stamp, UNIX_EPOCH + Duration::from_secs(7));
+        assert_eq!(entry.created_at_ms, 7_000);
+        assert_eq!(entry.orientation, 1);
+    }
+
+    #[test]
+    fn reconciles_added_modified_and_deleted_photos() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::copy(dated_fixture(), root.join("kept.png")).unwrap();
+        fs::copy(dated_fixture(), root.join("edited.png")).unwrap();
+        fs::copy(dated_fixture(), root.join("deleted.png")).unwrap();
+
+        let (index, dated) = scan(root, &["kept.png", "edited.png", "deleted.png"]);
+        assert_eq!((index.hits(), index.misses()), (0, 3));
+        assert_eq!(
+            dated.len(),
+            3,
+            "every capture date differs from the file time"
+        );
+        index.save().unwrap();
+
+        fs::remove_file(root.join("deleted.png")).unwrap();
+        fs::write(root.join("edited.png"), b"no longer a photo").unwrap();
+        fs::copy(dated_fixture(), root.join("added.png")).unwrap();
+        let mut index = LibraryIndex::load(&LibraryRoot::new(root));
+        let taken = UNIX_EPOCH + Duration::from_secs(1_556_964_000);
+        assert_eq!(index.created_at(&root.join("kept.png")), taken);
+        assert_ne!(index.created_at(&root.join("edited.png")), taken);
+        index.created_at(&root.join("added.png"));
+        assert_eq!((index.hits(), index.misses()), (1, 2));
+
+        let mut dated = Vec::new();
+        index.read_stale(|info| {
+            dated.push(info.path);
+            true
+        });
+        assert_eq!(dated, vec![root.join("added.png")]);
+        index.save().unwrap();
+
+        let saved: IndexFile =
+            serde_json::from_slice(&fs::read(root.join(INDEX_FILE_NAME)).unwrap()).unwrap();
+        let names: Vec<_> = saved.photos.keys().cloned().collect();
+        assert_eq!(
+            names,
+            ["added.png", "edited.png", "kept.png"].map(PathBuf::from)
+        );
+        assert_eq!(saved.photos[Path::new("edited.png")].orientation, 1);
+    }
+
+    #[test]
+    fn keeps_fingerprints_until_the_photo_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let (kept, edited) = (root.join("kept.png"), root.join("edited.png"));
+        fs::copy(dated_fixture(), &kept).unwrap();
+        fs::copy(dated_fixture(), &edited).unwrap();
+        let fingerprint = Fingerprint::read(&kept).unwrap();
+
+        let (index, _) = scan(root, &["kept.png", "edited.png"]);
+        assert_eq!(index.fingerprint(&kept), None);
+        index.save().unwrap();
+
+        // Both photos are unchanged, so only the fingerprints are new.
+        let (mut index, _) = scan(root, &["kept.png", "edited.png"]);
+        assert_eq!((index.hits(), index.misses()), (2, 0));
+        assert!(index.set_fingerprint(&kept, fingerprint));
+        assert!(index.set_fingerprint(&edited, fingerprint));
+        assert!(!index.set_fingerprint(&root.join("elsewhere.png"), fingerprint));
+        index.save().unwrap();
+
+        let (index, _) = scan(root, &["kept.png", "edited.png"]);
+        assert_eq!(index.fingerprint(&kept), Some(fingerprint));
+        assert_eq!(index.fingerprint(&edited), Some(fingerprint));
+
+        fs::write(&edited, b"no longer a photo").unwrap();
+        let (index, _) = scan(root, &["kept.png", "edited.png"]);
+        assert_eq!(index.fingerprint(&kept), Some(fingerprint));
+        assert_eq!(index.fingerprint(&edited), None);
+    }
+
+    #[test]
+    fn a_corrupt_index_reads_every_photo_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::copy(dated_fixture(), root.join("photo.png")).unwrap();
+        fs::write(root.join(INDEX_FILE_NAME), b"{\"version\": 1, \"phot").unwrap();
+
+        let (index, dated) = scan(root, &["photo.png"]);
+        assert_eq!((index.hits(), index.misses()), (0, 1));
+        assert_eq!(dated.len(), 1);
+        index.save().unwrap();
+
+        let (index, dated) = scan(root, &["photo.png"]);
+        assert_eq!((index.hits(), index.misses()), (1, 0));
+        assert!(dated.is_empty());
+    }
+}
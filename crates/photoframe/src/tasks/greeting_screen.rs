@@ -36,6 +36,10 @@ pub struct GreetingScreen {
     corner_radius_dip: f32,
     scale_factor: f64,
     padding_px: f32,
+    /// Share of the inner width the message wraps within (`max-width-percent`).
+    max_width_fraction: f32,
+    /// Scales every colour toward black; 1.0 is fully visible.
+    opacity: f32,
     frame_renderer: FrameRenderer,
 }
 
@@ -88,6 +92,8 @@ impl GreetingScreen {
             corner_radius_dip,
             scale_factor: 1.0,
             padding_px: 0.0,
+            max_width_fraction: screen.effective_max_width_fraction(),
+            opacity: 1.0,
             frame_renderer,
         };
         instance.recompute_padding();
@@ -95,7 +101,7 @@ impl GreetingScreen {
     }
 
     pub fn set_message(&mut self, message: impl Into<String>) -> bool {
-        let message = message.into();
+        let message = normalize_line_breaks(message.into());
         if self.message == message {
             return false;
         }
@@ -103,6 +109,18 @@ impl GreetingScreen {
         true
     }
 
+    /// Fades the whole screen (background, frame, and text) toward black.
+    /// Returns `true` when the opacity changed and a redraw is needed.
+    pub fn set_opacity(&mut self, opacity: f32) -> bool {
+        let opacity = opacity.clamp(0.0, 1.0);
+        if (self.opacity - opacity).abs() < f32::EPSILON {
+            return false;
+        }
+        self.opacity = opacity;
+        self.recompute_padding();
+        true
+    }
+
     pub fn resize(&mut self, new_size: PhysicalSize<u32>, scale_factor: f64) {
         self.size = new_size;
         self.scale_factor = scale_factor;
@@ -125,7 +143,7 @@ impl GreetingScreen {
             },
         );
 
-        let text_color = to_text_color(self.font_colour);
+        let text_color = to_text_color(fade_toward_black(self.font_colour, self.opacity));
         if let Err(err) = self.text_renderer.prepare(
             &self.device,
             &self.queue,
@@ -155,7 +173,7 @@ impl GreetingScreen {
             encoder,
             target_view,
             "greeting-background",
-            to_wgpu_color(self.background),
+            to_wgpu_color(fade_toward_black(self.background, self.opacity)),
             None::<fn(&mut wgpu::RenderPass<'_>)>,
         );
 
@@ -208,30 +226,40 @@ impl GreetingScreen {
             return false;
         }
 
-        let font_size = compute_font_size(&self.message, self.size);
-        let metrics = Metrics::new(font_size, font_size * 1.2);
         let available_width = (self.size.width as f32 - 2.0 * self.padding_px).max(1.0);
         let available_height = (self.size.height as f32 - 2.0 * self.padding_px).max(1.0);
-        self.text_buffer.set_metrics_and_size(
-            &mut self.font_system,
-            metrics,
-            Some(available_width),
-            Some(available_height),
-        );
+        let wrap_width = (available_width * self.max_width_fraction).max(1.0);
+        let mut font_size =
+            compute_font_size(&self.message, self.size, wrap_width, available_height);
 
         let attrs = Attrs::new().family(self.font_family.as_family());
-        self.text_buffer.set_text(
-            &mut self.font_system,
-            &self.message,
-            &attrs,
-            Shaping::Advanced,
-            None,
-        );
-        apply_center_alignment(&mut self.text_buffer);
-        self.text_buffer
-            .shape_until_scroll(&mut self.font_system, false);
+        // The size estimate ignores real glyph widths, so shrink until the shaped
+        // text actually fits rather than letting the bottom lines clip.
+        loop {
+            self.text_buffer.set_metrics_and_size(
+                &mut self.font_system,
+                Metrics::new(font_size, font_size * LINE_HEIGHT_EM),
+                Some(wrap_width),
+                None,
+            );
+            self.text_buffer.set_text(
+                &mut self.font_system,
+                &self.message,
+                &attrs,
+                Shaping::Advanced,
+                None,
+            );
+            apply_center_alignment(&mut self.text_buffer);
+            self.text_buffer
+                .shape_until_scroll(&mut self.font_system, false);
+            if font_size <= MIN_FONT_SIZE || text_height(&self.text_buffer) <= available_height {
+                break;
+            }
+            font_size = (font_size * 0.9).max(MIN_FONT_SIZE);
+        }
 
-        self.text_origin = compute_text_origin(&self.text_buffer, self.size, self.padding_px);
+        let left = self.padding_px + (available_width - wrap_width) * 0.5;
+        self.text_origin = compute_text_origin(&self.text_buffer, self.size, self.padding_px, left);
         true
     }
 
@@ -246,8 +274,8 @@ impl GreetingScreen {
             self.size,
             stroke_px,
             corner_px,
-            self.accent_colour,
-            self.background,
+            fade_toward_black(self.accent_colour, self.opacity),
+            fade_toward_black(self.background, self.opacity),
         );
     }
 }
@@ -442,18 +470,97 @@ impl FrameRenderer {
     }
 }
 
-fn compute_font_size(message: &str, size: PhysicalSize<u32>) -> f32 {
+const MIN_FONT_SIZE: f32 = 24.0;
+const MAX_FONT_SIZE: f32 = 360.0;
+const LINE_HEIGHT_EM: f32 = 1.2;
+/// Typical glyph advance as a fraction of the font size; only used to estimate
+/// wrapping when picking a font size; the shaper does the real wrapping.
+const AVERAGE_ADVANCE_EM: f32 = 0.55;
+
+/// Picks the largest font size (up to 12% of the smaller screen side) whose
+/// estimated wrapped block fits `box_width` × `box_height`.
+fn compute_font_size(
+    message: &str,
+    size: PhysicalSize<u32>,
+    box_width: f32,
+    box_height: f32,
+) -> f32 {
     if message.trim().is_empty() {
         return 16.0;
     }
     let min_dim = size.width.min(size.height) as f32;
-    let mut scale = min_dim * 0.12; // start with 12% of the smaller side
-    scale = scale.clamp(24.0, 360.0);
+    let mut font_size = (min_dim * 0.12).clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
+    while font_size > MIN_FONT_SIZE {
+        let lines = wrap_message_lines(message, box_width, font_size).len();
+        if lines as f32 * font_size * LINE_HEIGHT_EM <= box_height {
+            break;
+        }
+        font_size = (font_size * 0.9).max(MIN_FONT_SIZE);
+    }
+    font_size
+}
+
+/// Estimates how `message` wraps at `max_width` pixels: explicit line breaks are
+/// kept, words move to the next line when they would overflow, and a word wider
+/// than a whole line is split.
+fn wrap_message_lines(message: &str, max_width: f32, font_size: f32) -> Vec<String> {
+    let max_chars = ((max_width / (font_size * AVERAGE_ADVANCE_EM)).floor() as usize).max(1);
+    let mut lines = Vec::new();
+    for paragraph in message.split('\n') {
+        let mut current = String::new();
+        let mut current_chars = 0;
+        for word in paragraph.split_whitespace() {
+            let mut rest = word;
+            while !rest.is_empty() {
+                let len = rest.chars().count();
+                let needed = if current_chars == 0 {
+                    len
+                } else {
+                    current_chars + 1 + len
+                };
+                if needed <= max_chars {
+                    if current_chars > 0 {
+                        current.push(' ');
+                    }
+                    current.push_str(rest);
+                    current_chars = needed;
+                    break;
+                }
+                if current_chars > 0 {
+                    lines.push(std::mem::take(&mut current));
+                    current_chars = 0;
+                    continue;
+                }
+                let split = rest
+                    .char_indices()
+                    .nth(max_chars)
+                    .map_or(rest.len(), |(idx, _)| idx);
+                lines.push(rest[..split].to_string());
+                rest = &rest[split..];
+            }
+        }
+        lines.push(current);
+    }
+    lines
+}
+
+/// Turns a literal `\n` (as typed in a plain YAML scalar) into a line break.
+fn normalize_line_breaks(message: String) -> String {
+    if message.contains("\\n") {
+        message.replace("\\n", "\n")
+    } else {
+        message
+    }
+}
 
-    // If the message is very long, reduce the scale heuristically.
-    let chars = message.chars().count().max(1);
-    let adjustment = ((chars as f32) / 24.0).sqrt();
-    (scale / adjustment).clamp(24.0, 360.0)
+fn text_height(buffer: &Buffer) -> f32 {
+    let mut top = f32::MAX;
+    let mut bottom = f32::MIN;
+    for run in buffer.layout_runs() {
+        top = top.min(run.line_top);
+        bottom = bottom.max(run.line_top + run.line_height);
+    }
+    (bottom - top).max(0.0)
 }
 
 fn resolve_background_colour(source: Option<&str>) -> LinSrgba<f32> {
@@ -474,7 +581,7 @@ fn resolve_accent_colour(source: Option<&str>) -> LinSrgba<f32> {
         .unwrap_or_else(default_accent_colour)
 }
 
-fn initialize_font_database(db: &mut Database) {
+pub(crate) fn initialize_font_database(db: &mut Database) {
     db.load_system_fonts();
     let bundled_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/fonts");
     if bundled_path.exists() {
@@ -482,7 +589,10 @@ fn initialize_font_database(db: &mut Database) {
     }
 }
 
-fn resolve_font_family(font_system: &FontSystem, requested: Option<&str>) -> FamilyOwned {
+pub(crate) fn resolve_font_family(
+    font_system: &FontSystem,
+    requested: Option<&str>,
+) -> FamilyOwned {
     let db = font_system.db();
     if let Some(name) = requested.and_then(|value| {
         let trimmed = value.trim();
@@ -515,7 +625,12 @@ fn apply_center_alignment(buffer: &mut Buffer) {
     }
 }
 
-fn compute_text_origin(buffer: &Buffer, size: PhysicalSize<u32>, padding: f32) -> (f32, f32) {
+fn compute_text_origin(
+    buffer: &Buffer,
+    size: PhysicalSize<u32>,
+    padding: f32,
+    left: f32,
+) -> (f32, f32) {
     let mut min_top = f32::MAX;
     let mut max_bottom = f32::MIN;
     let mut has_runs = false;
@@ -528,7 +643,7 @@ fn compute_text_origin(buffer: &Buffer, size: PhysicalSize<u32>, padding: f32) -
 
     if !has_runs {
         return (
-            padding.max(0.0),
+            left.max(0.0),
             (size.height as f32 * 0.5).max(padding.max(0.0)),
         );
     }
@@ -538,7 +653,7 @@ fn compute_text_origin(buffer: &Buffer, size: PhysicalSize<u32>, padding: f32) -
     let centered_offset = ((container_height - text_height) * 0.5).max(0.0);
     let top_offset = padding + centered_offset - min_top;
 
-    (padding.max(0.0), top_offset.max(padding.max(0.0)))
+    (left.max(0.0), top_offset.max(padding.max(0.0)))
 }
 
 fn to_text_color(color: LinSrgba<f32>) -> Color {
@@ -547,6 +662,15 @@ fn to_text_color(color: LinSrgba<f32>) -> Color {
     Color::rgba(srgb_u8.red, srgb_u8.green, srgb_u8.blue, srgb_u8.alpha)
 }
 
+fn fade_toward_black(color: LinSrgba<f32>, opacity: f32) -> LinSrgba<f32> {
+    LinSrgba::new(
+        color.red * opacity,
+        color.green * opacity,
+        color.blue * opacity,
+        color.alpha,
+    )
+}
+
 fn linear_color_to_array(color: LinSrgba<f32>) -> [f32; 4] {
     [color.red, color.green, color.blue, color.alpha]
 }
@@ -588,3 +712,51 @@ fn default_font_colour() -> LinSrgba<f32> {
 fn default_accent_colour() -> LinSrgba<f32> {
     parse_hex_color("#38BDF8").unwrap()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{compute_font_size, normalize_line_breaks, wrap_message_lines};
+    use winit::dpi::PhysicalSize;
+
+    #[test]
+    fn long_message_wraps_to_width() {
+        // 40px glyphs at 0.55em ≈ 22px each, so 440px holds 20 characters.
+        let message = "Tucking in for a nap while the house sleeps, back at seven";
+        let lines = wrap_message_lines(message, 440.0, 40.0);
+        assert_eq!(
+            lines,
+            [
+                "Tucking in for a nap",
+                "while the house",
+                "sleeps, back at",
+                "seven"
+            ]
+        );
+        assert!(lines.iter().all(|line| line.chars().count() <= 20));
+        assert_eq!(wrap_message_lines(message, 4000.0, 40.0).len(), 1);
+    }
+
+    #[test]
+    fn explicit_breaks_and_long_words_split_lines() {
+        let lines = wrap_message_lines("Good night\n\nSee you soon", 2000.0, 40.0);
+        assert_eq!(lines, ["Good night", "", "See you soon"]);
+
+        let lines = wrap_message_lines("Supercalifragilistic", 220.0, 40.0);
+        assert_eq!(lines, ["Supercalif", "ragilistic"]);
+
+        assert_eq!(normalize_line_breaks("a\\nb".to_string()), "a\nb");
+    }
+
+    #[test]
+    fn font_shrinks_until_wrapped_text_fits() {
+        let size = PhysicalSize::new(1920, 1080);
+        let short = compute_font_size("Hello", size, 1600.0, 800.0);
+        assert_eq!(short, 1080.0 * 0.12);
+
+        let long = "word ".repeat(200);
+        let font = compute_font_size(&long, size, 1600.0, 800.0);
+        assert!(font < short);
+        let lines = wrap_message_lines(&long, 1600.0, font).len();
+        assert!(lines as f32 * font * 1.2 <= 800.0 || font == 24.0);
+    }
+}
@@ -0,0 +1,300 @@
+//! Recently displayed photos, for "what was that photo a minute ago?".
+//!
+//! The manager records every `Displayed` event into a bounded in-memory ring
+//! that the control socket (`history` command) and the web remote
+//! (`/api/history`) read. With `history.file` set, each entry is also
+//! appended to a JSONL log by a blocking task, so disk writes and rotation
+//! never hold up the display pipeline. Once the log holds `max-entries`
+//! lines it is renamed to `<file>.1`, replacing the previous one, and a fresh
+//! log is started; at startup the ring is refilled from both files.
+
+use crate::config::HistoryConfig;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::SystemTime;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+/// Entries waiting for the disk writer. A full queue drops entries from the
+/// log (never from the ring) rather than stall the manager.
+const LOG_QUEUE: usize = 64;
+
+pub type SharedHistory = Arc<Mutex<PhotoHistory>>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// RFC 3339, UTC, to the second.
+    pub shown_at: String,
+    pub path: PathBuf,
+}
+
+impl HistoryEntry {
+    pub fn new(path: PathBuf, shown_at: SystemTime) -> Self {
+        Self {
+            shown_at: humantime::format_rfc3339_seconds(shown_at).to_string(),
+            path,
+        }
+    }
+}
+
+/// The most recent `capacity` displays, oldest first.
+#[derive(Debug)]
+pub struct PhotoHistory {
+    entries: VecDeque<HistoryEntry>,
+    capacity: usize,
+}
+
+impl PhotoHistory {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, entry: HistoryEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Up to `limit` entries, newest first.
+    pub fn recent(&self, limit: usize) -> Vec<HistoryEntry> {
+        self.entries.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+/// What the manager holds: the shared ring plus, with `history.file` set, the
+/// queue into the disk writer.
+pub struct HistoryRecorder {
+    ring: SharedHistory,
+    log: Option<mpsc::Sender<HistoryEntry>>,
+}
+
+impl HistoryRecorder {
+    /// Refills the ring from an existing log and starts the disk writer.
+    /// Must be called inside the tokio runtime when a log file is configured.
+    pub fn start(config: &HistoryConfig) -> Self {
+        let mut ring = PhotoHistory::new(config.max_entries);
+        let log = config.file.as_ref().map(|path| {
+            let (log, restored) = HistoryLog::open(path.clone(), config.max_entries);
+            for entry in restored {
+                ring.push(entry);
+            }
+            let (tx, rx) = mpsc::channel(LOG_QUEUE);
+            tokio::task::spawn_blocking(move || log.write_from(rx));
+            tx
+        });
+        Self {
+            ring: Arc::new(Mutex::new(ring)),
+            log,
+        }
+    }
+
+    pub fn shared(&self) -> SharedHistory {
+        Arc::clone(&self.ring)
+    }
+
+    pub fn record(&self, path: PathBuf, shown_at: SystemTime) {
+        let entry = HistoryEntry::new(path, shown_at);
+        if let Some(log) = &self.log
+            && let Err(mpsc::error::TrySendError::Full(entry)) = log.try_send(entry.clone())
+        {
+            warn!(path = %entry.path.display(), "history_log_backlogged");
+        }
+        self.ring
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(entry);
+    }
+}
+
+/// The JSONL file behind `history.file`. Only the disk writer touches it.
+pub struct HistoryLog {
+    path: PathBuf,
+    max_entries: usize,
+    /// Lines in the current file, so rotation needs no re-read.
+    lines: usize,
+}
+
+impl HistoryLog {
+    /// Opens the log at `path`, returning it with the newest `max_entries`
+    /// entries found in it and its rotated predecessor, oldest first.
+    /// Unreadable files and malformed lines are skipped.
+    pub fn open(path: PathBuf, max_entries: usize) -> (Self, Vec<HistoryEntry>) {
+        let max_entries = max_entries.max(1);
+        let mut entries = read_entries(&rotated_path(&path));
+        let current = read_entries(&path);
+        let lines = current.len();
+        entries.extend(current);
+        let skip = entries.len().saturating_sub(max_entries);
+        entries.drain(..skip);
+        let log = Self {
+            path,
+            max_entries,
+            lines,
+        };
+        (log, entries)
+    }
+
+    pub fn append(&mut self, entry: &HistoryEntry) -> Result<()> {
+        if self.lines >= self.max_entries {
+            self.rotate()?;
+        }
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("failed to create {}", parent.display()))?;
+        }
+        let mut line = serde_json::to_vec(entry).context("failed to encode history entry")?;
+        line.push(b'\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(&line))
+            .with_context(|| format!("failed to append to {}", self.path.display()))?;
+        self.lines += 1;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> Result<()> {
+        let rotated = rotated_path(&self.path);
+        fs::rename(&self.path, &rotated).with_context(|| {
+            format!(
+                "failed to rotate {} to {}",
+                self.path.display(),
+                rotated.display()
+            )
+        })?;
+        debug!(path = %rotated.display(), entries = self.lines, "history_log_rotated");
+        self.lines = 0;
+        Ok(())
+    }
+
+    fn write_from(mut self, mut rx: mpsc::Receiver<HistoryEntry>) {
+        while let Some(entry) = rx.blocking_recv() {
+            if let Err(err) = self.append(&entry) {
+                warn!(error = %format!("{err:#}"), "history_log_write_failed");
+            }
+        }
+    }
+}
+
+/// `history.jsonl` → `history.jsonl.1`.
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(".1");
+    path.with_file_name(name)
+}
+
+fn read_entries(path: &Path) -> Vec<HistoryEntry> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
+        Err(err) => {
+            warn!(path = %path.display(), error = %err, "history_log_unreadable");
+            return Vec::new();
+        }
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn entry(n: u64) -> HistoryEntry {
+        HistoryEntry::new(
+            PathBuf::from(format!("/photos/{n}.jpg")),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000 + n),
+        )
+    }
+
+    fn names(entries: &[HistoryEntry]) -> Vec<String> {
+        entries
+            .iter()
+            .map(|entry| entry.path.display().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn ring_keeps_the_newest_entries_newest_first() {
+        let mut history = PhotoHistory::new(3);
+        assert!(history.recent(10).is_empty());
+        for n in 1..=5 {
+            history.push(entry(n));
+        }
+        assert_eq!(
+            names(&history.recent(10)),
+            ["/photos/5.jpg", "/photos/4.jpg", "/photos/3.jpg"]
+        );
+        assert_eq!(names(&history.recent(1)), ["/photos/5.jpg"]);
+        assert_eq!(entry(0).shown_at, "2023-11-14T22:13:20Z");
+    }
+
+    #[test]
+    fn log_rotates_at_max_entries_and_restores_the_ring() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state/history.jsonl");
+        let (mut log, restored) = HistoryLog::open(path.clone(), 3);
+        assert!(restored.is_empty());
+        for n in 1..=3 {
+            log.append(&entry(n)).unwrap();
+        }
+        assert!(!rotated_path(&path).exists());
+
+        // The fourth entry starts a new file; the full one becomes `.1`.
+        log.append(&entry(4)).unwrap();
+        let rotated = dir.path().join("state/history.jsonl.1");
+        assert_eq!(names(&read_entries(&rotated)).len(), 3);
+        assert_eq!(names(&read_entries(&path)), ["/photos/4.jpg"]);
+
+        // Later rotations replace the old `.1`, so disk use stays bounded.
+        for n in 5..=7 {
+            log.append(&entry(n)).unwrap();
+        }
+        assert_eq!(
+            names(&read_entries(&rotated)),
+            ["/photos/4.jpg", "/photos/5.jpg", "/photos/6.jpg"]
+        );
+        assert_eq!(names(&read_entries(&path)), ["/photos/7.jpg"]);
+
+        // A restart refills the ring with the newest entries across both files
+        // and keeps counting lines in the current one.
+        let (mut log, restored) = HistoryLog::open(path.clone(), 3);
+        assert_eq!(
+            names(&restored),
+            ["/photos/5.jpg", "/photos/6.jpg", "/photos/7.jpg"]
+        );
+        log.append(&entry(8)).unwrap();
+        log.append(&entry(9)).unwrap();
+        log.append(&entry(10)).unwrap();
+        assert_eq!(
+            names(&read_entries(&rotated)),
+            ["/photos/7.jpg", "/photos/8.jpg", "/photos/9.jpg"]
+        );
+    }
+
+    #[test]
+    fn malformed_log_lines_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        let good = serde_json::to_string(&entry(1)).unwrap();
+        fs::write(&path, format!("{good}\nnot json\n")).unwrap();
+        let (_, restored) = HistoryLog::open(path, 10);
+        assert_eq!(names(&restored), ["/photos/1.jpg"]);
+    }
+}
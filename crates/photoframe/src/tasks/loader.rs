@@ -1,14 +1,27 @@
-use crate::events::{InvalidPhoto, LoadPhoto, PhotoLoaded, PreparedImageCpu};
-use anyhow::Result;
+pub(crate) mod caption;
+mod color;
+mod decode_cache;
+pub(crate) mod sidecar;
+mod thumbnail_cache;
+
+use self::color::ColorConverter;
+pub use self::decode_cache::DecodeCache;
+pub use self::thumbnail_cache::ThumbnailCache;
+use crate::config::{AnimationsConfig, ColorConfig, MattingConfig};
+use crate::events::{AnimationFrame, InvalidPhoto, LoadPhoto, PhotoLoaded, PreparedImageCpu};
+use crate::metrics::{RuntimeMetrics, SharedMetrics};
+use anyhow::{Context, Result};
 use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::{BufReader, Seek};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::select;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Upper bound on peak allocation while decoding a single image. On a
 /// memory-constrained Pi a pathological image (e.g. a multi-gigapixel scan or
@@ -17,11 +30,261 @@ use tracing::debug;
 /// surfaces as a normal decode error and is skipped (never deleted).
 const MAX_DECODE_ALLOC_BYTES: u64 = 512 * 1024 * 1024;
 
+/// HEIC/HEIF extensions (lowercase, without leading dot). These are decoded
+/// through libheif and only accepted when built with the `heif` feature.
+pub(crate) const HEIF_EXTENSIONS: &[&str] = &["heic", "heif"];
+
+pub(crate) fn is_heif_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .is_some_and(|ext| HEIF_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// FNV-1a, for cache keys and share links: stable across builds, unlike
+/// `DefaultHasher`, so a rebuilt binary keeps using the existing caches.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Containers that may hold an animation, picked by extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnimatedFormat {
+    Gif,
+    Png,
+    WebP,
+}
+
+fn animated_format(path: &Path) -> Option<AnimatedFormat> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "gif" => Some(AnimatedFormat::Gif),
+        "png" | "apng" => Some(AnimatedFormat::Png),
+        "webp" => Some(AnimatedFormat::WebP),
+        _ => None,
+    }
+}
+
+/// Frames asking for less than this are shown for [`FALLBACK_FRAME_DELAY`]
+/// instead, as browsers do: many files store a zero delay and expect it.
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+const FALLBACK_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// A decoded photo: the still shown first plus, for animations, every frame.
+struct DecodedPhoto {
+    image: image::RgbaImage,
+    animation: Option<Vec<AnimationFrame>>,
+    /// Filled from `caption.template` when captions are enabled.
+    caption: Option<String>,
+    /// The mat asked for by the photo's sidecar file, if any.
+    matting: Option<MattingConfig>,
+}
+
+impl From<image::RgbaImage> for DecodedPhoto {
+    fn from(image: image::RgbaImage) -> Self {
+        Self {
+            image,
+            animation: None,
+            caption: None,
+            matting: None,
+        }
+    }
+}
+
+/// The blocking thread behind one decode, yielding the photo and how long
+/// decoding took.
+type DecodeThread = tokio::task::JoinHandle<Result<(DecodedPhoto, Duration)>>;
+
+/// A finished decode task: its request sequence number, the photo, the
+/// decoded result, and for a timed-out decode the thread still winding down.
+type DecodeOutcome = (
+    u64,
+    PathBuf,
+    SystemTime,
+    Result<(DecodedPhoto, Duration)>,
+    Option<DecodeThread>,
+);
+
+/// A HEIC/HEIF file reached a build without the `heif` feature.
+#[cfg(not(feature = "heif"))]
+#[derive(Debug)]
+struct HeifSupportDisabled;
+
+#[cfg(not(feature = "heif"))]
+impl std::fmt::Display for HeifSupportDisabled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HEIC/HEIF support is not compiled in; rebuild with the `heif` cargo feature")
+    }
+}
+
+#[cfg(not(feature = "heif"))]
+impl std::error::Error for HeifSupportDisabled {}
+
+/// A decode ran past the configured timeout and was abandoned.
+#[derive(Debug)]
+struct DecodeTimedOut(Duration);
+
+impl std::fmt::Display for DecodeTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "decode did not finish within {} ms; skipping",
+            self.0.as_millis()
+        )
+    }
+}
+
+impl std::error::Error for DecodeTimedOut {}
+
+/// Checked between decode stages: once a decode has run past its timeout,
+/// `abandon` is cancelled and the decode stops at the next stage instead of
+/// holding its blocking thread until it finishes.
+fn ensure_live(abandon: &CancellationToken) -> Result<()> {
+    anyhow::ensure!(!abandon.is_cancelled(), "decode abandoned");
+    Ok(())
+}
+
+/// Decodes any supported photo to RGBA8, picking the decoder by extension.
+fn decode_photo(path: &Path, abandon: &CancellationToken) -> anyhow::Result<image::RgbaImage> {
+    if is_heif_path(path) {
+        #[cfg(feature = "heif")]
+        return heif::decode_rgba8(path);
+        #[cfg(not(feature = "heif"))]
+        anyhow::bail!(HeifSupportDisabled);
+    }
+    decode_rgba8_apply_exif(path, abandon)
+}
+
+/// Decodes a photo through whichever caches are configured: the decode cache
+/// first, then the thumbnail cache. With `animations` set, GIFs and animated
+/// PNG and WebP files bypass both so their frames survive; without it they
+/// decode to their first frame like any still.
+fn load_photo(
+    path: &Path,
+    thumbnails: Option<&ThumbnailCache>,
+    decoded: Option<&DecodeCache>,
+    animations: Option<&AnimationsConfig>,
+    abandon: &CancellationToken,
+) -> anyhow::Result<DecodedPhoto> {
+    ensure_live(abandon)?;
+    if let Some(limits) = animations
+        && let Some(format) = animated_format(path)
+        && let Some(photo) = decode_animation(path, format, limits, abandon)?
+    {
+        return Ok(photo);
+    }
+    let decode = |path: &Path| match thumbnails {
+        Some(cache) => cache.load(path, abandon, |path| decode_photo(path, abandon)),
+        None => decode_photo(path, abandon),
+    };
+    match decoded {
+        Some(cache) => cache.load(path, abandon, decode),
+        None => decode(path),
+    }
+    .map(DecodedPhoto::from)
+}
+
+/// Decodes a GIF, or a PNG or WebP that turns out to be animated, keeping
+/// every frame. `None` means a PNG or WebP holds a single image and should be
+/// decoded as a still. The decoders composite each frame onto the full
+/// canvas, so every frame is a complete picture. Frames past
+/// `animations.max-frames` or `animations.max-decoded-mb` are dropped and the
+/// animation loops over the ones that fit.
+fn decode_animation(
+    path: &Path,
+    format: AnimatedFormat,
+    config: &AnimationsConfig,
+    abandon: &CancellationToken,
+) -> anyhow::Result<Option<DecodedPhoto>> {
+    use image::codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder};
+    use image::{AnimationDecoder, ImageDecoder};
+
+    let mut limits = image::Limits::default();
+    limits.max_alloc = Some(MAX_DECODE_ALLOC_BYTES);
+    let reader = BufReader::new(File::open(path)?);
+    let (dimensions, frames) = match format {
+        AnimatedFormat::Gif => {
+            let mut decoder = GifDecoder::new(reader)?;
+            decoder.set_limits(limits)?;
+            (decoder.dimensions(), decoder.into_frames())
+        }
+        AnimatedFormat::Png => {
+            let mut decoder = PngDecoder::new(reader)?;
+            if !decoder.is_apng()? {
+                return Ok(None);
+            }
+            decoder.set_limits(limits)?;
+            (decoder.dimensions(), decoder.apng()?.into_frames())
+        }
+        AnimatedFormat::WebP => {
+            let mut decoder = WebPDecoder::new(reader)?;
+            if !decoder.has_animation() {
+                return Ok(None);
+            }
+            decoder.set_limits(limits)?;
+            (decoder.dimensions(), decoder.into_frames())
+        }
+    };
+    let (width, height) = dimensions;
+    let frame_bytes = (u64::from(width) * u64::from(height) * 4).max(1);
+    let max_frames =
+        ((config.max_decoded_bytes() / frame_bytes).max(1) as usize).min(config.max_frames);
+
+    let mut kept = Vec::new();
+    for frame in frames {
+        if kept.len() == max_frames {
+            debug!(path = %path.display(), frames = max_frames, "animation_truncated");
+            break;
+        }
+        ensure_live(abandon)?;
+        let frame = frame?;
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let delay = Duration::from_millis(u64::from(numer / denom.max(1)));
+        let delay = if delay < MIN_FRAME_DELAY {
+            FALLBACK_FRAME_DELAY
+        } else {
+            delay
+        };
+        kept.push(AnimationFrame {
+            pixels: frame.into_buffer().into_raw(),
+            delay,
+        });
+    }
+
+    let first = kept.first().context("animation contains no frames")?;
+    let image = image::RgbaImage::from_raw(width, height, first.pixels.clone())
+        .context("animation frame does not match the image dimensions")?;
+    let animation = (kept.len() > 1).then_some(kept);
+    Ok(Some(DecodedPhoto {
+        image,
+        animation,
+        caption: None,
+        matting: None,
+    }))
+}
+
+fn log_decode_failure(path: &Path, err: &anyhow::Error) {
+    if err.is::<DecodeTimedOut>() {
+        warn!(path = %path.display(), "{err}");
+        return;
+    }
+    #[cfg(not(feature = "heif"))]
+    if err.is::<HeifSupportDisabled>() {
+        warn!(path = %path.display(), "{err}");
+        return;
+    }
+    debug!("invalid photo {}: {err:#}", path.display());
+}
+
 // Decodes an image to RGBA8 and applies EXIF orientation if available.
 // Note: Orientation handling is a best-effort; if metadata is missing, the original
 // orientation is preserved. The file is opened only once: EXIF is read first, then
 // the reader is seeked back to the start for image decoding.
-fn decode_rgba8_apply_exif(path: &Path) -> anyhow::Result<image::RgbaImage> {
+fn decode_rgba8_apply_exif(
+    path: &Path,
+    abandon: &CancellationToken,
+) -> anyhow::Result<image::RgbaImage> {
     let file = File::open(path)?;
     let mut buf = BufReader::new(file);
 
@@ -42,63 +305,190 @@ fn decode_rgba8_apply_exif(path: &Path) -> anyhow::Result<image::RgbaImage> {
     let mut limits = image::Limits::default();
     limits.max_alloc = Some(MAX_DECODE_ALLOC_BYTES);
     reader.limits(limits);
-    let mut img = reader.decode()?.to_rgba8();
+    ensure_live(abandon)?;
+    let img = reader.decode()?.to_rgba8();
+
+    ensure_live(abandon)?;
+    Ok(apply_exif_orientation(img, orientation))
+}
 
-    // Map common EXIF orientations. Unsupported cases fall through as-is.
+/// Turns a decoded image upright according to its EXIF `Orientation` tag
+/// (1–8). Any other value leaves the pixels untouched.
+fn apply_exif_orientation(img: image::RgbaImage, orientation: u16) -> image::RgbaImage {
+    use image::imageops::{flip_horizontal, flip_vertical, rotate90, rotate180, rotate270};
     match orientation {
-        1 => {}
-        2 => {
-            // horizontal flip
-            img = image::imageops::flip_horizontal(&img);
-        }
-        3 => {
-            img = image::imageops::rotate180(&img);
-        }
-        4 => {
-            // vertical flip
-            img = image::imageops::flip_vertical(&img);
+        1 => img,
+        2 => flip_horizontal(&img),
+        3 => rotate180(&img),
+        4 => flip_vertical(&img),
+        // Transpose: mirror across the top-left/bottom-right diagonal.
+        5 => flip_horizontal(&rotate90(&img)),
+        6 => rotate90(&img),
+        // Transverse: mirror across the top-right/bottom-left diagonal.
+        7 => flip_horizontal(&rotate270(&img)),
+        8 => rotate270(&img),
+        other => {
+            debug!(orientation = other, "ignoring unknown exif orientation");
+            img
         }
-        5 => {
-            // transpose (flip diag): rotate90 + flip_horizontal
-            img = image::imageops::rotate90(&img);
-            img = image::imageops::flip_horizontal(&img);
-        }
-        6 => {
-            // rotate 90 CW
-            img = image::imageops::rotate90(&img);
-        }
-        7 => {
-            // transverse: rotate270 + flip_horizontal
-            img = image::imageops::rotate270(&img);
-            img = image::imageops::flip_horizontal(&img);
-        }
-        8 => {
-            // rotate 270 CW
-            img = image::imageops::rotate270(&img);
+    }
+}
+
+#[cfg(feature = "heif")]
+mod heif {
+    use super::MAX_DECODE_ALLOC_BYTES;
+    use anyhow::{Context, Result, ensure};
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+    use std::path::Path;
+
+    /// Decodes the primary image of a HEIC/HEIF file to RGBA8.
+    ///
+    /// libheif applies the container's rotation and mirror boxes itself, so
+    /// unlike JPEG no EXIF orientation is applied on top. Images deeper than
+    /// 8 bits per channel (10-bit iPhone HDR captures) are decoded at full
+    /// depth and scaled down to 8 bits for the GPU upload.
+    pub(super) fn decode_rgba8(path: &Path) -> Result<image::RgbaImage> {
+        let path_str = path.to_str().context("HEIF path is not valid UTF-8")?;
+        let ctx = HeifContext::read_from_file(path_str)?;
+        let handle = ctx.primary_image_handle()?;
+        let bit_depth = handle.luma_bits_per_pixel();
+        let high_depth = bit_depth > 8;
+        let bytes_per_pixel: usize = if high_depth { 8 } else { 4 };
+        let decoded_bytes =
+            u64::from(handle.width()) * u64::from(handle.height()) * bytes_per_pixel as u64;
+        ensure!(
+            decoded_bytes <= MAX_DECODE_ALLOC_BYTES,
+            "HEIF image {}x{} exceeds the decode memory limit",
+            handle.width(),
+            handle.height()
+        );
+
+        let chroma = if high_depth {
+            RgbChroma::HdrRgbaLe
+        } else {
+            RgbChroma::Rgba
+        };
+        let image = LibHeif::new().decode(&handle, ColorSpace::Rgb(chroma), None)?;
+        let planes = image.planes();
+        let plane = planes
+            .interleaved
+            .context("libheif returned no interleaved RGBA plane")?;
+
+        let row_bytes = plane.width as usize * bytes_per_pixel;
+        let mut pixels = Vec::with_capacity(plane.width as usize * plane.height as usize * 4);
+        for row in plane.data.chunks(plane.stride).take(plane.height as usize) {
+            let row = row
+                .get(..row_bytes)
+                .context("HEIF row shorter than its width")?;
+            if high_depth {
+                pixels.extend(row.chunks_exact(2).map(|sample| {
+                    tonemap_sample(u16::from_le_bytes([sample[0], sample[1]]), bit_depth)
+                }));
+            } else {
+                pixels.extend_from_slice(row);
+            }
         }
-        _ => {}
+        image::RgbaImage::from_raw(plane.width, plane.height, pixels)
+            .context("HEIF plane does not match its reported dimensions")
+    }
+
+    /// The ICC profile stored with the primary image, if any. Profiles given
+    /// as CICP (nclx) values are not read.
+    pub(super) fn icc_profile(path: &Path) -> Option<Vec<u8>> {
+        let ctx = HeifContext::read_from_file(path.to_str()?).ok()?;
+        let handle = ctx.primary_image_handle().ok()?;
+        handle.color_profile_raw().map(|profile| profile.data)
     }
 
-    Ok(img)
+    /// Scales a `bit_depth`-bit sample to 8 bits, rounding to nearest.
+    pub(super) fn tonemap_sample(sample: u16, bit_depth: u8) -> u8 {
+        let max = (1u32 << bit_depth) - 1;
+        let value = u32::from(sample).min(max);
+        ((value * 255 + max / 2) / max) as u8
+    }
 }
 
 /// Very simple loader:
 /// - Reads the bytes (to prove existence) and forwards a `PreparedPhoto`.
 /// - On I/O error, emits `InvalidPhoto`.
+/// - A decode still running after `decode_timeout` is abandoned and also emits
+///   `InvalidPhoto`, freeing its slot for the next photo.
+/// - With a `caption_template`, each photo's metadata fills it in alongside
+///   the decode.
+/// - A sidecar file next to the photo can override its mat.
+/// - Animated files keep their frames while `animations.enabled` is set.
+/// - Photos with an embedded ICC profile are converted into
+///   `color.target-space` unless `color.enabled` is off.
+/// - Each finished load is timed into `metrics.decode`.
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
+    load_rx: Receiver<LoadPhoto>,
+    invalid_tx: Sender<InvalidPhoto>,
+    to_viewer: Sender<PhotoLoaded>,
+    cancel: CancellationToken,
+    max_in_flight: usize,
+    decode_timeout: Duration,
+    thumbnail_cache: Option<Arc<ThumbnailCache>>,
+    decode_cache: Option<Arc<DecodeCache>>,
+    caption_template: Option<String>,
+    animations: AnimationsConfig,
+    color: ColorConfig,
+    metrics: SharedMetrics,
+) -> Result<()> {
+    let color = ColorConverter::new(&color);
+    run_with_decoder(
+        load_rx,
+        invalid_tx,
+        to_viewer,
+        cancel,
+        max_in_flight,
+        decode_timeout,
+        move |path: &Path, abandon: &CancellationToken| {
+            let started = Instant::now();
+            let mut photo = load_photo(
+                path,
+                thumbnail_cache.as_deref(),
+                decode_cache.as_deref(),
+                animations.enabled.then_some(&animations),
+                abandon,
+            )?;
+            ensure_live(abandon)?;
+            if let Some(color) = &color {
+                color.convert(path, &mut photo.image, photo.animation.as_deref_mut());
+            }
+            if let Some(template) = &caption_template {
+                let metadata = caption::PhotoMetadata::read(path);
+                let text = caption::render(template, &metadata);
+                photo.caption = (!text.is_empty()).then_some(text);
+            }
+            photo.matting = sidecar::PhotoSidecar::matting_for(path);
+            RuntimeMetrics::update(&metrics, |m| m.decode.record(started.elapsed()));
+            Ok(photo)
+        },
+    )
+    .await
+}
+
+async fn run_with_decoder(
     mut load_rx: Receiver<LoadPhoto>,
     invalid_tx: Sender<InvalidPhoto>,
     to_viewer: Sender<PhotoLoaded>,
     cancel: CancellationToken,
     max_in_flight: usize,
+    decode_timeout: Duration,
+    decode: impl Fn(&Path, &CancellationToken) -> anyhow::Result<DecodedPhoto> + Send + Sync + 'static,
 ) -> Result<()> {
+    let decode = Arc::new(decode);
     let mut in_flight: std::collections::HashSet<std::path::PathBuf> =
         std::collections::HashSet::new();
     let mut priority_inflight: std::collections::HashSet<std::path::PathBuf> =
         std::collections::HashSet::new();
     // Each decode carries the sequence number it was requested in, so results can
     // be emitted in request order even though they finish out of order.
-    let mut tasks: JoinSet<(u64, std::path::PathBuf, Option<image::RgbaImage>)> = JoinSet::new();
+    let mut tasks: JoinSet<DecodeOutcome> = JoinSet::new();
+    // Timed-out decodes whose threads have not exited yet. They still hold a
+    // slot, so a decoder that ignores `abandon` cannot pile up threads.
+    let mut detached: JoinSet<()> = JoinSet::new();
     let mut next_seq: u64 = 0;
     let mut reorder = ReorderBuffer::new();
     let mut pending_ready: Option<ReadyPhoto> = None;
@@ -107,7 +497,8 @@ pub async fn run(
     loop {
         // Bound outstanding work (in-flight + buffered) so a slow decode applies
         // backpressure instead of letting the reorder buffer grow without limit.
-        let can_accept = next_seq.saturating_sub(reorder.next_emit()) < max_in_flight as u64;
+        let outstanding = next_seq.saturating_sub(reorder.next_emit()) + detached.len() as u64;
+        let can_accept = outstanding < max_in_flight as u64;
 
         select! {
             _ = cancel.cancelled() => {
@@ -116,7 +507,7 @@ pub async fn run(
             },
 
             // Accept new load requests while the outstanding window has room.
-            Some(LoadPhoto { path, priority }) = load_rx.recv(), if can_accept => {
+            Some(LoadPhoto { path, priority, created_at }) = load_rx.recv(), if can_accept => {
                 if priority {
                     priority_inflight.insert(path.clone());
                 }
@@ -125,9 +516,31 @@ pub async fn run(
                     next_seq += 1;
                     tasks.spawn({
                         let p = path.clone();
+                        let decode = Arc::clone(&decode);
+                        let abandon = cancel.child_token();
                         async move {
-                            let res = tokio::task::spawn_blocking(move || decode_rgba8_apply_exif(&p)).await;
-                            (seq, path, res.ok().and_then(|r| r.ok()))
+                            let mut blocking = tokio::task::spawn_blocking({
+                                let abandon = abandon.clone();
+                                move || {
+                                    let started = Instant::now();
+                                    decode(&p, &abandon).map(|photo| (photo, started.elapsed()))
+                                }
+                            });
+                            // On timeout the decode is abandoned: it stops at its
+                            // next stage, the photo is reported invalid right away,
+                            // and the thread is handed back to keep its slot taken
+                            // until it exits.
+                            match tokio::time::timeout(decode_timeout, &mut blocking).await {
+                                Ok(joined) => {
+                                    let res = joined.map_err(anyhow::Error::from).and_then(|r| r);
+                                    (seq, path, created_at, res, None)
+                                }
+                                Err(_) => {
+                                    abandon.cancel();
+                                    let err = anyhow::Error::new(DecodeTimedOut(decode_timeout));
+                                    (seq, path, created_at, Err(err), Some(blocking))
+                                }
+                            }
                         }
                     });
                 }
@@ -137,19 +550,28 @@ pub async fn run(
 
             // Handle completed decodes as they finish, then release in request order.
             Some(join_res) = tasks.join_next() => {
-                if let Ok((seq, path, maybe_img)) = join_res {
+                if let Ok((seq, path, created_at, decoded, thread)) = join_res {
+                    if let Some(thread) = thread {
+                        detached.spawn(async move {
+                            let _ = thread.await;
+                        });
+                    }
                     in_flight.remove(&path);
                     let priority = priority_inflight.remove(&path);
-                    match maybe_img {
-                        Some(rgba8) => {
-                            debug!("loaded (rgba8): {}", path.display());
+                    match decoded {
+                        Ok((DecodedPhoto { image: rgba8, animation, caption, matting }, prep_time)) => {
+                            debug!(
+                                decode_ms = prep_time.as_millis() as u64,
+                                "loaded (rgba8): {}",
+                                path.display()
+                            );
                             let (width, height) = rgba8.dimensions();
                             let prepared = PreparedImageCpu { path: path.clone(), width, height, pixels: rgba8.into_raw() };
-                            let event = PhotoLoaded { prepared, priority };
+                            let event = PhotoLoaded { prepared, priority, animation, caption, matting, created_at, prep_time };
                             reorder.insert(seq, Some(ReadyPhoto { path, event }));
                         }
-                        None => {
-                            debug!("invalid photo {}", path.display());
+                        Err(err) => {
+                            log_decode_failure(&path, &err);
                             let _ = invalid_tx.send(InvalidPhoto(path)).await;
                             // Mark the slot done so emission can advance past it.
                             reorder.insert(seq, None);
@@ -161,6 +583,9 @@ pub async fn run(
                 }
             }
 
+            // An abandoned decode's thread exited, freeing its slot.
+            Some(_) = detached.join_next() => {}
+
             else => {
                 // Channels closed and nothing in flight: drain, flush, exit.
                 if in_flight.is_empty() {
@@ -273,6 +698,11 @@ mod tests {
             event: PhotoLoaded {
                 prepared,
                 priority: false,
+                animation: None,
+                caption: None,
+                matting: None,
+                created_at: SystemTime::UNIX_EPOCH,
+                prep_time: Duration::ZERO,
             },
         }
     }
@@ -291,10 +721,151 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let path = dir.path().join("orient6.jpg");
         std::fs::write(&path, &bytes).unwrap();
-        let img = decode_rgba8_apply_exif(&path).unwrap();
+        let img = decode_rgba8_apply_exif(&path, &CancellationToken::new()).unwrap();
         assert_eq!(img.dimensions(), (1, 2));
     }
 
+    fn exif_fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/exif")
+            .join(name)
+    }
+
+    /// How every `tests/fixtures/exif` image should look once upright: red,
+    /// green, blue over white, black, yellow. Each `orientation-N.png` stores
+    /// these pixels pre-transformed so that tag N turns them back.
+    const UPRIGHT: [[[u8; 4]; 3]; 2] = [
+        [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255]],
+        [[255, 255, 255, 255], [0, 0, 0, 255], [255, 255, 0, 255]],
+    ];
+
+    fn assert_upright(img: &image::RgbaImage, label: &str) {
+        assert_eq!(img.dimensions(), (3, 2), "{label}");
+        for (y, row) in UPRIGHT.iter().enumerate() {
+            for (x, expected) in row.iter().enumerate() {
+                assert_eq!(
+                    &img.get_pixel(x as u32, y as u32).0,
+                    expected,
+                    "{label} at ({x}, {y})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn applies_every_exif_orientation() {
+        for orientation in 1..=8 {
+            let name = format!("orientation-{orientation}.png");
+            let img =
+                decode_rgba8_apply_exif(&exif_fixture(&name), &CancellationToken::new()).unwrap();
+            assert_upright(&img, &name);
+        }
+    }
+
+    #[test]
+    fn missing_or_unknown_orientation_leaves_pixels_alone() {
+        let img = decode_rgba8_apply_exif(&exif_fixture("no-exif.png"), &CancellationToken::new())
+            .unwrap();
+        assert_upright(&img, "no-exif.png");
+        assert_upright(&apply_exif_orientation(img, 9), "orientation 9");
+    }
+
+    fn heif_fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/heif")
+            .join(name)
+    }
+
+    #[cfg(feature = "heif")]
+    fn assert_close(actual: &image::Rgba<u8>, expected: [u8; 4]) {
+        // HEVC is lossy; allow a little drift per channel.
+        for (a, e) in actual.0.iter().zip(expected) {
+            assert!(a.abs_diff(e) <= 8, "{actual:?} vs {expected:?}");
+        }
+    }
+
+    // landscape.heic is 16x8: red left half, blue right half.
+    #[cfg(feature = "heif")]
+    #[test]
+    fn decodes_heic_fixture() {
+        assert!(is_heif_path(Path::new("IMG_0001.HEIC")));
+        let img = decode_photo(&heif_fixture("landscape.heic"), &CancellationToken::new()).unwrap();
+        assert_eq!(img.dimensions(), (16, 8));
+        assert_close(img.get_pixel(0, 4), [220, 30, 30, 255]);
+        assert_close(img.get_pixel(15, 4), [30, 30, 220, 255]);
+    }
+
+    // rotate90.heic is the same picture stored with an `irot` box for a
+    // 90° clockwise turn, so the red half ends up on top — exactly once.
+    #[cfg(feature = "heif")]
+    #[test]
+    fn applies_heic_container_rotation_once() {
+        let img = decode_photo(&heif_fixture("rotate90.heic"), &CancellationToken::new()).unwrap();
+        assert_eq!(img.dimensions(), (8, 16));
+        assert_close(img.get_pixel(4, 0), [220, 30, 30, 255]);
+        assert_close(img.get_pixel(4, 15), [30, 30, 220, 255]);
+    }
+
+    // ten_bit.heic uses 10-bit samples (880 and 120 of 1023).
+    #[cfg(feature = "heif")]
+    #[test]
+    fn tonemaps_ten_bit_heic_to_rgba8() {
+        assert_eq!(heif::tonemap_sample(0, 10), 0);
+        assert_eq!(heif::tonemap_sample(512, 10), 128);
+        assert_eq!(heif::tonemap_sample(1023, 10), 255);
+        assert_eq!(heif::tonemap_sample(u16::MAX, 10), 255);
+
+        let img = decode_photo(&heif_fixture("ten_bit.heic"), &CancellationToken::new()).unwrap();
+        assert_eq!(img.dimensions(), (16, 8));
+        assert_close(img.get_pixel(0, 4), [219, 30, 30, 255]);
+        assert_close(img.get_pixel(15, 4), [30, 30, 219, 255]);
+    }
+
+    #[cfg(not(feature = "heif"))]
+    #[test]
+    fn heic_without_feature_names_the_missing_feature() {
+        let err =
+            decode_photo(&heif_fixture("landscape.heic"), &CancellationToken::new()).unwrap_err();
+        assert!(err.is::<HeifSupportDisabled>());
+        assert!(err.to_string().contains("`heif` cargo feature"));
+    }
+
+    #[cfg(not(feature = "heif"))]
+    #[tokio::test]
+    async fn heic_without_feature_is_reported_invalid() {
+        let (load_tx, load_rx) = mpsc::channel(4);
+        let (invalid_tx, mut invalid_rx) = mpsc::channel(4);
+        let (viewer_tx, _viewer_rx) = mpsc::channel(4);
+        let cancel = CancellationToken::new();
+        let loader = tokio::spawn(run_with_decoder(
+            load_rx,
+            invalid_tx,
+            viewer_tx,
+            cancel.clone(),
+            1,
+            Duration::from_secs(5),
+            |path: &Path, abandon: &CancellationToken| decode_photo(path, abandon).map(Into::into),
+        ));
+        let path = heif_fixture("landscape.heic");
+        load_tx
+            .send(LoadPhoto {
+                path: path.clone(),
+                priority: false,
+                created_at: SystemTime::UNIX_EPOCH,
+            })
+            .await
+            .unwrap();
+
+        let invalid = tokio::time::timeout(Duration::from_secs(5), invalid_rx.recv())
+            .await
+            .expect("HEIC reported invalid")
+            .unwrap();
+        assert_eq!(invalid.0, path);
+
+        cancel.cancel();
+        loader.await.unwrap().unwrap();
+    }
+
     #[tokio::test]
     async fn reorders_single_repeat_when_possible() {
         let (tx, mut rx) = mpsc::channel(4);
@@ -343,6 +914,251 @@ mod tests {
         assert_eq!(last_sent, Some(PathBuf::from("a")));
     }
 
+    #[tokio::test]
+    async fn decode_past_the_timeout_is_invalid_and_stops_at_its_next_stage() {
+        let (load_tx, load_rx) = mpsc::channel(4);
+        let (invalid_tx, mut invalid_rx) = mpsc::channel(4);
+        let (viewer_tx, mut viewer_rx) = mpsc::channel(4);
+        let cancel = CancellationToken::new();
+        let abandoned = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        // One slot: the second photo only decodes once the stuck one has stopped.
+        let loader = tokio::spawn(run_with_decoder(
+            load_rx,
+            invalid_tx,
+            viewer_tx,
+            cancel.clone(),
+            1,
+            Duration::from_millis(50),
+            {
+                let abandoned = Arc::clone(&abandoned);
+                move |path: &Path, abandon: &CancellationToken| {
+                    if path.ends_with("stuck.jpg") {
+                        // A stage that never finishes on its own.
+                        while !abandon.is_cancelled() {
+                            std::thread::sleep(Duration::from_millis(5));
+                        }
+                        abandoned.store(true, std::sync::atomic::Ordering::SeqCst);
+                        ensure_live(abandon)?;
+                    }
+                    Ok(image::RgbaImage::new(2, 2).into())
+                }
+            },
+        ));
+        for name in ["stuck.jpg", "fine.jpg"] {
+            let path = PathBuf::from(name);
+            load_tx
+                .send(LoadPhoto {
+                    path,
+                    priority: false,
+                    created_at: SystemTime::UNIX_EPOCH,
+                })
+                .await
+                .unwrap();
+        }
+
+        let wait = Duration::from_secs(2);
+        let invalid = tokio::time::timeout(wait, invalid_rx.recv())
+            .await
+            .expect("timed-out decode reported")
+            .unwrap();
+        assert_eq!(invalid.0, PathBuf::from("stuck.jpg"));
+        let loaded = tokio::time::timeout(wait, viewer_rx.recv())
+            .await
+            .expect("next photo decoded once the stuck one stopped")
+            .unwrap();
+        assert_eq!(loaded.prepared.path, PathBuf::from("fine.jpg"));
+        assert!(
+            abandoned.load(std::sync::atomic::Ordering::SeqCst),
+            "the decoder saw it was abandoned"
+        );
+
+        cancel.cancel();
+        loader.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn abandoned_decode_holds_its_slot_until_its_thread_exits() {
+        let (load_tx, load_rx) = mpsc::channel(4);
+        let (invalid_tx, mut invalid_rx) = mpsc::channel(4);
+        let (viewer_tx, mut viewer_rx) = mpsc::channel(4);
+        let cancel = CancellationToken::new();
+        // The stuck decode ignores `abandon`, so its slot stays taken for the
+        // whole sleep even though the photo is reported invalid at once.
+        let loader = tokio::spawn(run_with_decoder(
+            load_rx,
+            invalid_tx,
+            viewer_tx,
+            cancel.clone(),
+            1,
+            Duration::from_millis(50),
+            |path: &Path, _: &CancellationToken| {
+                if path.ends_with("stuck.jpg") {
+                    std::thread::sleep(Duration::from_millis(600));
+                }
+                Ok(image::RgbaImage::new(2, 2).into())
+            },
+        ));
+        for name in ["stuck.jpg", "fine.jpg"] {
+            let path = PathBuf::from(name);
+            load_tx
+                .send(LoadPhoto {
+                    path,
+                    priority: false,
+                    created_at: SystemTime::UNIX_EPOCH,
+                })
+                .await
+                .unwrap();
+        }
+
+        let invalid = tokio::time::timeout(Duration::from_millis(400), invalid_rx.recv())
+            .await
+            .expect("timed-out decode reported before its thread exits")
+            .unwrap();
+        assert_eq!(invalid.0, PathBuf::from("stuck.jpg"));
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), viewer_rx.recv())
+                .await
+                .is_err(),
+            "no second decode while the abandoned thread still runs"
+        );
+        let loaded = tokio::time::timeout(Duration::from_secs(2), viewer_rx.recv())
+            .await
+            .expect("next photo decoded once the thread exited")
+            .unwrap();
+        assert_eq!(loaded.prepared.path, PathBuf::from("fine.jpg"));
+
+        cancel.cancel();
+        loader.await.unwrap().unwrap();
+    }
+
+    fn write_gif(path: &Path, delays_ms: &[u32]) {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, Frame, Rgba, RgbaImage};
+
+        let file = File::create(path).unwrap();
+        let mut encoder = GifEncoder::new(file);
+        let frames = delays_ms.iter().enumerate().map(|(idx, ms)| {
+            let shade = 60 * (idx as u8 + 1);
+            let pixels = RgbaImage::from_pixel(4, 3, Rgba([shade, 0, 255 - shade, 255]));
+            Frame::from_parts(pixels, 0, 0, Delay::from_numer_denom_ms(*ms, 1))
+        });
+        encoder.encode_frames(frames).unwrap();
+    }
+
+    #[test]
+    fn animated_gif_yields_every_frame_with_its_delay() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spinner.gif");
+        write_gif(&path, &[100, 250, 500]);
+
+        let decoded = load_photo(
+            &path,
+            None,
+            None,
+            Some(&AnimationsConfig::default()),
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert_eq!(decoded.image.dimensions(), (4, 3));
+        let frames = decoded.animation.expect("three frames make an animation");
+        let delays: Vec<_> = frames.iter().map(|f| f.delay).collect();
+        assert_eq!(delays, [100, 250, 500].map(Duration::from_millis).to_vec());
+        assert!(frames.iter().all(|f| f.pixels.len() == 4 * 3 * 4));
+        assert_eq!(frames[0].pixels, decoded.image.as_raw().as_slice());
+        assert_ne!(frames[0].pixels, frames[1].pixels);
+        assert_ne!(frames[1].pixels, frames[2].pixels);
+    }
+
+    #[test]
+    fn single_frame_gif_is_a_still_and_zero_delays_fall_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = AnimationsConfig::default();
+        let still = dir.path().join("still.gif");
+        write_gif(&still, &[0]);
+        let decoded =
+            load_photo(&still, None, None, Some(&config), &CancellationToken::new()).unwrap();
+        assert!(decoded.animation.is_none());
+
+        let fast = dir.path().join("fast.gif");
+        write_gif(&fast, &[0, 10]);
+        let decoded =
+            load_photo(&fast, None, None, Some(&config), &CancellationToken::new()).unwrap();
+        let frames = decoded.animation.unwrap();
+        assert!(frames.iter().all(|f| f.delay == FALLBACK_FRAME_DELAY));
+    }
+
+    fn write_apng(path: &Path, frames: u32) {
+        let file = File::create(path).unwrap();
+        let mut encoder = png::Encoder::new(file, 2, 2);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_animated(frames, 0).unwrap();
+        let mut writer = encoder.write_header().unwrap();
+        for idx in 0..frames {
+            writer.set_frame_delay(150, 1000).unwrap();
+            writer.write_image_data(&[idx as u8 * 40; 16]).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn animated_png_plays_and_plain_png_stays_a_still() {
+        let dir = tempfile::tempdir().unwrap();
+        let animated = dir.path().join("loop.png");
+        write_apng(&animated, 3);
+        let config = AnimationsConfig::default();
+        let frames = load_photo(
+            &animated,
+            None,
+            None,
+            Some(&config),
+            &CancellationToken::new(),
+        )
+        .unwrap()
+        .animation
+        .expect("an APNG with three frames animates");
+        assert_eq!(frames.len(), 3);
+        assert!(frames.iter().all(|f| f.delay == Duration::from_millis(150)));
+
+        let still = dir.path().join("still.png");
+        image::RgbaImage::from_pixel(2, 2, image::Rgba([1, 2, 3, 255]))
+            .save(&still)
+            .unwrap();
+        let decoded =
+            load_photo(&still, None, None, Some(&config), &CancellationToken::new()).unwrap();
+        assert!(decoded.animation.is_none());
+        assert_eq!(decoded.image.dimensions(), (2, 2));
+    }
+
+    #[test]
+    fn animation_limits_cap_frames_and_disabling_keeps_the_first_frame() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("long.gif");
+        write_gif(&path, &[100, 100, 100, 100]);
+
+        let capped = AnimationsConfig {
+            max_frames: 2,
+            ..AnimationsConfig::default()
+        };
+        let frames = load_photo(&path, None, None, Some(&capped), &CancellationToken::new())
+            .unwrap()
+            .animation
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+
+        let animated = load_photo(
+            &path,
+            None,
+            None,
+            Some(&AnimationsConfig::default()),
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        let still = load_photo(&path, None, None, None, &CancellationToken::new()).unwrap();
+        assert!(still.animation.is_none());
+        assert_eq!(still.image, animated.image);
+    }
+
     fn ready_paths(items: &[ReadyPhoto]) -> Vec<String> {
         items
             .iter()
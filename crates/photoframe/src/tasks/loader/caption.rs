@@ -0,0 +1,387 @@
+//! Per-photo captions built from the photo's own metadata.
+//!
+//! The loader reads the capture date (EXIF `DateTimeOriginal`), a
+//! description (XMP `dc:description`, else EXIF `ImageDescription`), and the
+//! GPS position while it decodes the photo, and fills the `caption.template`
+//! placeholders with them. The position is shown as coordinates; there is no
+//! place-name lookup.
+//! A placeholder with nothing to show drops out together with the text that
+//! separates it from its neighbours, so `{date} — {description}` renders as
+//! just the date when a photo has no description. When every placeholder is
+//! empty the caption falls back to the file name.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use anyhow::{Result, bail};
+
+/// How far into a file to look for an XMP packet. Writers put it in the
+/// header segments, well before the image data.
+const XMP_SCAN_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Date,
+    Description,
+    Filename,
+    Location,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Segment<'a> {
+    Text(&'a str),
+    Field(Field),
+}
+
+/// What a caption can say about one photo.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct PhotoMetadata {
+    pub(crate) date: Option<String>,
+    pub(crate) description: Option<String>,
+    pub(crate) filename: Option<String>,
+    pub(crate) location: Option<String>,
+}
+
+impl PhotoMetadata {
+    /// Best-effort: unreadable or missing metadata leaves the field empty.
+    pub(crate) fn read(path: &Path) -> Self {
+        let exif = File::open(path).ok().and_then(|file| {
+            exif::Reader::new()
+                .read_from_container(&mut BufReader::new(file))
+                .ok()
+        });
+        let exif_ascii = |tag| {
+            let field = exif.as_ref()?.get_field(tag, exif::In::PRIMARY)?;
+            match &field.value {
+                exif::Value::Ascii(values) => values.first().cloned(),
+                _ => None,
+            }
+        };
+        let date = exif_ascii(exif::Tag::DateTimeOriginal).and_then(|raw| format_exif_date(&raw));
+        let description = read_xmp_description(path).or_else(|| {
+            exif_ascii(exif::Tag::ImageDescription)
+                .map(|raw| String::from_utf8_lossy(&raw).into_owned())
+        });
+        Self {
+            date,
+            description,
+            filename: path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned()),
+            location: exif.as_ref().and_then(gps_location),
+        }
+    }
+
+    fn value(&self, field: Field) -> Option<&str> {
+        let value = match field {
+            Field::Date => self.date.as_deref(),
+            Field::Description => self.description.as_deref(),
+            Field::Filename => self.filename.as_deref(),
+            Field::Location => self.location.as_deref(),
+        };
+        value.map(str::trim).filter(|value| !value.is_empty())
+    }
+}
+
+/// Rejects a template with an unknown placeholder or an unclosed `{`.
+pub(crate) fn check_template(template: &str) -> Result<()> {
+    parse(template).map(drop)
+}
+
+/// Fills `template` for one photo. Returns an empty string when there is
+/// nothing to show, including for a template that fails [`check_template`].
+pub(crate) fn render(template: &str, metadata: &PhotoMetadata) -> String {
+    let Ok(segments) = parse(template) else {
+        return String::new();
+    };
+    let has_fields = segments
+        .iter()
+        .any(|segment| matches!(segment, Segment::Field(_)));
+
+    let mut out = String::new();
+    // Text since the previous placeholder; written only if the placeholders on
+    // both sides of it (or the template edge) have something to show.
+    let mut between = String::new();
+    let mut first_field = true;
+    let mut last_shown = true;
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => between.push_str(text),
+            Segment::Field(field) => {
+                last_shown = match metadata.value(field) {
+                    Some(value) => {
+                        if !out.is_empty() || first_field {
+                            out.push_str(&between);
+                        }
+                        out.push_str(value);
+                        true
+                    }
+                    None => false,
+                };
+                between.clear();
+                first_field = false;
+            }
+        }
+    }
+    if last_shown {
+        out.push_str(&between);
+    }
+
+    let caption = out.trim();
+    if caption.is_empty() && has_fields {
+        return metadata
+            .value(Field::Filename)
+            .unwrap_or_default()
+            .to_owned();
+    }
+    caption.to_owned()
+}
+
+fn parse(template: &str) -> Result<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        if open > 0 {
+            segments.push(Segment::Text(&rest[..open]));
+        }
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            bail!("caption template {template:?} has a '{{' without a closing '}}'");
+        };
+        let field = match &after[..close] {
+            "date" => Field::Date,
+            "description" => Field::Description,
+            "filename" => Field::Filename,
+            "location" => Field::Location,
+            other => bail!(
+                "caption template placeholder {{{other}}} is unknown; use {{date}}, {{description}}, {{filename}}, or {{location}}"
+            ),
+        };
+        segments.push(Segment::Field(field));
+        rest = &after[close + 1..];
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    Ok(segments)
+}
+
+/// `2023:07:14 10:22:01` → `14 July 2023`.
+fn format_exif_date(raw: &[u8]) -> Option<String> {
+    let parsed = exif::DateTime::from_ascii(raw).ok()?;
+    let date = chrono::NaiveDate::from_ymd_opt(
+        i32::from(parsed.year),
+        u32::from(parsed.month),
+        u32::from(parsed.day),
+    )?;
+    Some(date.format("%-d %B %Y").to_string())
+}
+
+/// The EXIF GPS position as `51.9634° N, 0.6891° E`.
+fn gps_location(exif: &exif::Exif) -> Option<String> {
+    let coordinate = |tag, ref_tag| {
+        let exif::Value::Rational(parts) = &exif.get_field(tag, exif::In::PRIMARY)?.value else {
+            return None;
+        };
+        let hemisphere = match &exif.get_field(ref_tag, exif::In::PRIMARY)?.value {
+            exif::Value::Ascii(values) => *values.first()?.first()?,
+            _ => return None,
+        };
+        Some((gps_degrees(parts)?, hemisphere))
+    };
+    let (lat, lat_ref) = coordinate(exif::Tag::GPSLatitude, exif::Tag::GPSLatitudeRef)?;
+    let (lon, lon_ref) = coordinate(exif::Tag::GPSLongitude, exif::Tag::GPSLongitudeRef)?;
+    format_location(lat, lat_ref, lon, lon_ref)
+}
+
+/// Degrees, minutes, and seconds as EXIF stores them, in degrees.
+fn gps_degrees(parts: &[exif::Rational]) -> Option<f64> {
+    if parts.is_empty() || parts.iter().any(|part| part.denom == 0) {
+        return None;
+    }
+    Some(
+        parts
+            .iter()
+            .zip([1.0, 60.0, 3600.0])
+            .map(|(part, per_degree)| part.to_f64() / per_degree)
+            .sum(),
+    )
+}
+
+fn format_location(lat: f64, lat_ref: u8, lon: f64, lon_ref: u8) -> Option<String> {
+    let lat_ref = matches!(lat_ref, b'N' | b'S').then_some(lat_ref as char)?;
+    let lon_ref = matches!(lon_ref, b'E' | b'W').then_some(lon_ref as char)?;
+    // Cameras without a fix write zeros rather than leaving the tags out.
+    if lat > 90.0 || lon > 180.0 || (lat == 0.0 && lon == 0.0) {
+        return None;
+    }
+    Some(format!("{lat:.4}° {lat_ref}, {lon:.4}° {lon_ref}"))
+}
+
+fn read_xmp_description(path: &Path) -> Option<String> {
+    let mut bytes = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(XMP_SCAN_BYTES)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    xmp_description(&bytes)
+}
+
+/// The default-language entry of `dc:description` in an embedded XMP packet.
+fn xmp_description(bytes: &[u8]) -> Option<String> {
+    let find = |haystack: &[u8], needle: &[u8]| {
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+    };
+    let start = find(bytes, b"<dc:description")?;
+    let rest = &bytes[start..];
+    let end = find(rest, b"</dc:description>")?;
+    let block = std::str::from_utf8(&rest[..end]).ok()?;
+    let item = block.find("<rdf:li")?;
+    let text_start = item + block[item..].find('>')? + 1;
+    let text_end = text_start + block[text_start..].find("</rdf:li>")?;
+    let text = unescape_xml(block[text_start..text_end].trim());
+    (!text.is_empty()).then_some(text)
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = "{date} — {description}";
+
+    fn metadata(date: Option<&str>, description: Option<&str>) -> PhotoMetadata {
+        PhotoMetadata {
+            date: date.map(str::to_owned),
+            description: description.map(str::to_owned),
+            filename: Some("IMG_0042".to_owned()),
+            location: None,
+        }
+    }
+
+    #[test]
+    fn fills_every_placeholder() {
+        let meta = metadata(Some("14 July 2023"), Some("Low tide at Brancaster"));
+        assert_eq!(
+            render(TEMPLATE, &meta),
+            "14 July 2023 — Low tide at Brancaster"
+        );
+        assert_eq!(
+            render("{filename}: {description}", &meta),
+            "IMG_0042: Low tide at Brancaster"
+        );
+    }
+
+    #[test]
+    fn missing_fields_take_their_separators_with_them() {
+        assert_eq!(
+            render(TEMPLATE, &metadata(None, Some("Low tide"))),
+            "Low tide"
+        );
+        assert_eq!(
+            render(TEMPLATE, &metadata(Some("14 July 2023"), Some("  "))),
+            "14 July 2023"
+        );
+        let template = "Taken {date} ({description})";
+        assert_eq!(
+            render(template, &metadata(Some("14 July 2023"), None)),
+            "Taken 14 July 2023"
+        );
+        assert_eq!(
+            render(template, &metadata(Some("14 July 2023"), Some("Beach"))),
+            "Taken 14 July 2023 (Beach)"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_file_name() {
+        assert_eq!(render(TEMPLATE, &metadata(None, None)), "IMG_0042");
+        let nameless = PhotoMetadata::default();
+        assert_eq!(render(TEMPLATE, &nameless), "");
+        // A template without placeholders is shown as written.
+        assert_eq!(render("Our holidays", &nameless), "Our holidays");
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        check_template(TEMPLATE).unwrap();
+        let err = check_template("{date} {camera}").unwrap_err();
+        assert!(err.to_string().contains("{camera}"), "{err}");
+        assert!(check_template("{date").is_err());
+    }
+
+    #[test]
+    fn formats_the_exif_capture_date() {
+        assert_eq!(
+            format_exif_date(b"2023:07:14 10:22:01").as_deref(),
+            Some("14 July 2023")
+        );
+        assert_eq!(format_exif_date(b"0000:00:00 00:00:00"), None);
+    }
+
+    #[test]
+    fn puts_the_exif_date_into_the_caption() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/exif/dated.png");
+        let meta = PhotoMetadata::read(&path);
+        assert_eq!(meta.date.as_deref(), Some("4 May 2019"));
+        assert_eq!(render("Taken {date}", &meta), "Taken 4 May 2019");
+        assert_eq!(render("{date} · {location}", &meta), "4 May 2019");
+    }
+
+    #[test]
+    fn formats_the_gps_position() {
+        let rational = |num, denom| exif::Rational { num, denom };
+        let lat = gps_degrees(&[rational(51, 1), rational(57, 1), rational(4824, 100)]).unwrap();
+        let lon = gps_degrees(&[rational(0, 1), rational(41, 1), rational(2076, 100)]).unwrap();
+        assert_eq!(
+            format_location(lat, b'N', lon, b'E').as_deref(),
+            Some("51.9634° N, 0.6891° E")
+        );
+        assert_eq!(gps_degrees(&[rational(1, 0)]), None);
+        assert_eq!(format_location(0.0, b'N', 0.0, b'E'), None, "no fix");
+        assert_eq!(format_location(lat, b'X', lon, b'E'), None);
+
+        let meta = PhotoMetadata {
+            location: format_location(lat, b'S', lon, b'W'),
+            ..metadata(Some("14 July 2023"), None)
+        };
+        assert_eq!(
+            render("{date} · {location}", &meta),
+            "14 July 2023 · 51.9634° S, 0.6891° W"
+        );
+    }
+
+    #[test]
+    fn reads_the_xmp_description() {
+        let packet = br#"<x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF>
+  <rdf:Description xmlns:dc="http://purl.org/dc/elements/1.1/">
+   <dc:description>
+    <rdf:Alt><rdf:li xml:lang="x-default">Fish &amp; chips</rdf:li></rdf:Alt>
+   </dc:description>
+  </rdf:Description></rdf:RDF></x:xmpmeta>"#;
+        let mut file = b"\xff\xd8\xff\xe1junk".to_vec();
+        file.extend_from_slice(packet);
+        assert_eq!(xmp_description(&file).as_deref(), Some("Fish & chips"));
+        assert_eq!(xmp_description(b"no metadata here"), None);
+    }
+
+    #[test]
+    fn photos_without_metadata_are_known_by_name() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/exif/no-exif.png");
+        let meta = PhotoMetadata::read(&path);
+        assert_eq!(meta.date, None);
+        assert_eq!(meta.description, None);
+        assert_eq!(render(TEMPLATE, &meta), "no-exif");
+    }
+}
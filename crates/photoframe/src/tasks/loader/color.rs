@@ -0,0 +1,240 @@
+//! Color management for decoded photos.
+//!
+//! Everything after the loader (mats, effects, the GPU upload) treats pixel
+//! values as one color space. A photo exported with an Adobe RGB or Display
+//! P3 profile would otherwise be shown as if its numbers were sRGB, and look
+//! washed out or oversaturated. The loader reads the profile embedded in the
+//! original file, converts the pixels it is about to hand on into
+//! `color.target-space`, and drops the profile. Photos without a profile are
+//! taken to be sRGB.
+//!
+//! Conversion runs after the thumbnail and decode caches, on the downscaled
+//! pixels, so the caches keep the photo's own values and a change of target
+//! space needs no cache flush.
+
+use std::path::Path;
+
+use image::ImageDecoder;
+use qcms::{CIE_xyY, CIE_xyYTRIPLE, DataType, Intent, Profile, Transform};
+use tracing::debug;
+
+use crate::config::{ColorConfig, ColorTargetSpace};
+use crate::events::AnimationFrame;
+
+/// Pixels run through a new transform to tell whether it changes anything:
+/// the primaries, a few grays, and two mid-saturation tones. An embedded
+/// sRGB profile maps every one of them to itself (within rounding), and the
+/// photo is left alone.
+const PROBE: [u8; 32] = [
+    255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 128, 128, 128, 255, //
+    200, 120, 60, 255, 60, 120, 200, 255, 30, 30, 30, 255, 230, 230, 230, 255,
+];
+
+pub struct ColorConverter {
+    target_space: ColorTargetSpace,
+    target: Box<Profile>,
+    srgb: Box<Profile>,
+}
+
+impl ColorConverter {
+    /// `None` when `color.enabled` is off.
+    pub fn new(cfg: &ColorConfig) -> Option<Self> {
+        if !cfg.enabled {
+            return None;
+        }
+        let mut target = match cfg.target_space {
+            ColorTargetSpace::Srgb => Profile::new_sRGB(),
+            ColorTargetSpace::DisplayP3 => display_p3(),
+        };
+        target.precache_output_transform();
+        Some(Self {
+            target_space: cfg.target_space,
+            target,
+            srgb: Profile::new_sRGB(),
+        })
+    }
+
+    /// Converts `image`, and any animation frames decoded with it, from the
+    /// profile embedded in `path` into the target space. Returns whether the
+    /// pixels were changed.
+    pub fn convert(
+        &self,
+        path: &Path,
+        image: &mut image::RgbaImage,
+        frames: Option<&mut [AnimationFrame]>,
+    ) -> bool {
+        let Some(transform) = self.transform_for(path) else {
+            return false;
+        };
+        transform.apply(image);
+        for frame in frames.into_iter().flatten() {
+            transform.apply(&mut frame.pixels);
+        }
+        true
+    }
+
+    /// The transform from `path`'s profile to the target, or `None` when the
+    /// photo is already in the target space.
+    fn transform_for(&self, path: &Path) -> Option<Transform> {
+        let embedded = embedded_profile(path).and_then(|icc| {
+            let parsed = Profile::new_from_slice(&icc, false);
+            if parsed.is_none() {
+                debug!(path = %path.display(), "unreadable ICC profile; treating the photo as sRGB");
+            }
+            parsed
+        });
+        let source = match &embedded {
+            Some(profile) => profile,
+            // Untagged photos are sRGB, which is already the target.
+            None if self.target_space == ColorTargetSpace::Srgb => return None,
+            None => &self.srgb,
+        };
+        // `None` for profiles that do not describe RGB, such as a CMYK or
+        // grayscale profile left on a file the decoder expanded to RGB.
+        let transform = Transform::new(source, &self.target, DataType::RGBA8, Intent::Perceptual)?;
+        let mut probe = PROBE;
+        transform.apply(&mut probe);
+        if probe
+            .iter()
+            .zip(PROBE)
+            .all(|(out, inp)| out.abs_diff(inp) <= 1)
+        {
+            return None;
+        }
+        debug!(path = %path.display(), target = ?self.target_space, "color_profile_converted");
+        Some(transform)
+    }
+}
+
+/// The ICC profile embedded in `path`, read from the headers only.
+fn embedded_profile(path: &Path) -> Option<Vec<u8>> {
+    if super::is_heif_path(path) {
+        #[cfg(feature = "heif")]
+        return super::heif::icc_profile(path);
+        #[cfg(not(feature = "heif"))]
+        return None;
+    }
+    let mut decoder = image::ImageReader::open(path)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .into_decoder()
+        .ok()?;
+    decoder.icc_profile().ok().flatten()
+}
+
+/// Display P3: the DCI-P3 primaries with a D65 white and the sRGB curve.
+fn display_p3() -> Box<Profile> {
+    let xy = |x, y| CIE_xyY { x, y, Y: 1.0 };
+    let primaries = CIE_xyYTRIPLE {
+        red: xy(0.680, 0.320),
+        green: xy(0.265, 0.690),
+        blue: xy(0.150, 0.060),
+    };
+    let curve: Vec<u16> = (0..1024)
+        .map(|i| {
+            let v = f64::from(i) / 1023.0;
+            let linear = if v <= 0.04045 {
+                v / 12.92
+            } else {
+                ((v + 0.055) / 1.055).powf(2.4)
+            };
+            (linear * 65535.0).round() as u16
+        })
+        .collect();
+    Profile::new_rgb_with_table(xy(0.3127, 0.3290), primaries, &curve)
+        .expect("Display P3 primaries form a valid RGB profile")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/color")
+            .join(name)
+    }
+
+    fn converter(target_space: ColorTargetSpace) -> ColorConverter {
+        ColorConverter::new(&ColorConfig {
+            enabled: true,
+            target_space,
+        })
+        .expect("enabled")
+    }
+
+    /// Pixels of the Adobe RGB fixtures: muted green, mid gray, muted red, white.
+    const MUTED_GREEN: u32 = 0;
+    const GRAY: u32 = 1;
+    const MUTED_RED: u32 = 2;
+
+    fn decode(name: &str) -> image::RgbaImage {
+        image::open(fixture(name)).unwrap().to_rgba8()
+    }
+
+    fn pixel(img: &image::RgbaImage, index: u32) -> [i32; 3] {
+        let [r, g, b, _] = img.get_pixel(index % 2, index / 2).0;
+        [r.into(), g.into(), b.into()]
+    }
+
+    #[test]
+    fn adobe_rgb_photos_gain_saturation_in_srgb() {
+        for name in ["adobe-rgb.png", "adobe-rgb.jpg"] {
+            let original = decode(name);
+            let mut converted = original.clone();
+            assert!(converter(ColorTargetSpace::Srgb).convert(
+                &fixture(name),
+                &mut converted,
+                None
+            ));
+
+            // The same numbers mean more saturated colors in the wider
+            // Adobe RGB gamut, so in sRGB they move apart.
+            let [r0, g0, b0] = pixel(&original, MUTED_GREEN);
+            let [r1, g1, b1] = pixel(&converted, MUTED_GREEN);
+            assert!(g1 - r1 > g0 - r0 && g1 - b1 > g0 - b0, "{name}: green");
+            let [r0, g0, _] = pixel(&original, MUTED_RED);
+            let [r1, g1, _] = pixel(&converted, MUTED_RED);
+            assert!(r1 - g1 > r0 - g0, "{name}: red");
+            // Both spaces share the D65 white, so grays stay gray.
+            let tint = |[r, g, b]: [i32; 3]| r.abs_diff(g).max(g.abs_diff(b)).max(r.abs_diff(b));
+            assert!(
+                tint(pixel(&converted, GRAY)) <= tint(pixel(&original, GRAY)) + 2,
+                "{name}: gray"
+            );
+            assert_eq!(converted.get_pixel(1, 1).0[3], 255, "alpha is kept");
+        }
+    }
+
+    #[test]
+    fn untagged_srgb_photos_are_left_alone() {
+        let path =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/exif/orientation-1.png");
+        let original = image::open(&path).unwrap().to_rgba8();
+        let mut image = original.clone();
+        assert!(!converter(ColorTargetSpace::Srgb).convert(&path, &mut image, None));
+        assert_eq!(image, original);
+
+        // A P3 panel still needs sRGB photos mapped into its wider gamut,
+        // which pulls saturated colors in toward the middle.
+        assert!(converter(ColorTargetSpace::DisplayP3).convert(&path, &mut image, None));
+        let [r, g, b, _] = image.get_pixel(0, 0).0;
+        assert!(
+            r < 255 && g > 0 && b > 0,
+            "pure red desaturates: {r},{g},{b}"
+        );
+    }
+
+    #[test]
+    fn disabled_color_management_builds_no_converter() {
+        assert!(
+            ColorConverter::new(&ColorConfig {
+                enabled: false,
+                target_space: ColorTargetSpace::DisplayP3,
+            })
+            .is_none()
+        );
+    }
+}
@@ -0,0 +1,401 @@
+//! On-disk cache of decoded photos.
+//!
+//! Even with the thumbnail cache, turning a JPEG back into pixels costs a Pi
+//! hundreds of milliseconds per photo, enough for a short dwell to run out
+//! before the next photo is ready. This cache keeps the loader's finished
+//! pixels (decoded, turned upright, and downscaled) as uncompressed RGBA, so a
+//! hit is one sequential read.
+//!
+//! Entries are named `<content hash>-<params hash>.rgba`. The content hash
+//! covers the photo's bytes, so an edited photo misses while a renamed one
+//! still hits. The params hash covers the settings that shape the cached
+//! pixels, so changing one of them misses too. Mats and photo effects are
+//! chosen afresh for every showing, after the loader, and are never cached.
+//! Once the directory outgrows its size limit, the entries used least recently
+//! (by mtime, refreshed on every hit) are removed.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, ensure};
+use image::RgbaImage;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use super::{ensure_live, fnv1a};
+use crate::config::{DecodeCacheConfig, ThumbnailCacheConfig};
+
+/// Leads every entry, followed by the width and height as little-endian `u32`s.
+const MAGIC: &[u8; 4] = b"PFD1";
+const HEADER_LEN: usize = 12;
+/// Bumped whenever the loader changes how it produces pixels, so entries
+/// written by an older build are never served.
+const PIXEL_PIPELINE_VERSION: u32 = 1;
+/// Lookups between hit-rate reports in the log.
+const STATS_LOG_INTERVAL: u64 = 50;
+
+pub struct DecodeCache {
+    directory: PathBuf,
+    max_bytes: u64,
+    params_hash: u64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl DecodeCache {
+    /// `thumbnails` is the thumbnail cache configuration, if any: its size
+    /// limit decides the dimensions of what gets cached here.
+    pub fn new(cfg: &DecodeCacheConfig, thumbnails: Option<&ThumbnailCacheConfig>) -> Self {
+        let params = format!(
+            "pipeline={PIXEL_PIPELINE_VERSION};max-dimension-px={:?}",
+            thumbnails.map(|cfg| cfg.max_dimension_px)
+        );
+        Self {
+            directory: cfg.directory.clone(),
+            max_bytes: cfg.max_size_mb.saturating_mul(1024 * 1024),
+            params_hash: fnv1a(params.as_bytes()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the pixels `decode` would produce for `path`, from the cache
+    /// when an entry for the photo's current contents exists. A missing,
+    /// unreadable, or corrupt entry is a miss; failing to store the result
+    /// only logs. An `abandon`ed load stops before writing its entry.
+    pub fn load(
+        &self,
+        path: &Path,
+        abandon: &CancellationToken,
+        decode: impl FnOnce(&Path) -> Result<RgbaImage>,
+    ) -> Result<RgbaImage> {
+        let contents =
+            fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        let entry = self.directory.join(format!(
+            "{:016x}-{:016x}.rgba",
+            fnv1a(&contents),
+            self.params_hash
+        ));
+        drop(contents);
+
+        match read_entry(&entry) {
+            Ok(Some(image)) => {
+                self.record(true);
+                debug!(path = %path.display(), "decode_cache_hit");
+                // Refresh the mtime so eviction sees the entry as recently used.
+                let _ = File::options()
+                    .write(true)
+                    .open(&entry)
+                    .and_then(|file| file.set_modified(SystemTime::now()));
+                return Ok(image);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                warn!(path = %path.display(), "decode cache entry unreadable, replacing it: {err:#}");
+                let _ = fs::remove_file(&entry);
+            }
+        }
+        self.record(false);
+
+        let image = decode(path)?;
+        ensure_live(abandon)?;
+        match self.store(&entry, &image) {
+            Ok(()) => debug!(path = %path.display(), "decode_cache_stored"),
+            Err(err) => warn!(path = %path.display(), "decode cache write failed: {err:#}"),
+        }
+        Ok(image)
+    }
+
+    /// Lookups so far as `(hits, misses)`.
+    pub fn stats(&self) -> (u64, u64) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
+    fn record(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        let (hits, misses) = self.stats();
+        if (hits + misses) % STATS_LOG_INTERVAL == 0 {
+            let hit_rate_pct = hits * 100 / (hits + misses);
+            info!(hits, misses, hit_rate_pct, "decode_cache_stats");
+        }
+    }
+
+    /// Writes `image` under a temporary name and renames it into place, so a
+    /// reader never sees a partial entry, then trims the cache to its limit.
+    fn store(&self, entry: &Path, image: &RgbaImage) -> Result<()> {
+        fs::create_dir_all(&self.directory)
+            .with_context(|| format!("failed to create {}", self.directory.display()))?;
+        let tmp = entry.with_extension("rgba.tmp");
+        {
+            let mut out = BufWriter::new(File::create(&tmp)?);
+            out.write_all(MAGIC)?;
+            out.write_all(&image.width().to_le_bytes())?;
+            out.write_all(&image.height().to_le_bytes())?;
+            out.write_all(image.as_raw())?;
+            out.flush()?;
+        }
+        fs::rename(&tmp, entry)?;
+        self.evict(entry)
+    }
+
+    /// Removes the least recently used entries until the cache fits its
+    /// limit. `keep` (the entry just written) is never removed.
+    fn evict(&self, keep: &Path) -> Result<()> {
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = fs::read_dir(&self.directory)?
+            .filter_map(Result::ok)
+            .filter(|dirent| dirent.path().extension().is_some_and(|ext| ext == "rgba"))
+            .filter_map(|dirent| {
+                let meta = dirent.metadata().ok()?;
+                Some((meta.modified().ok()?, meta.len(), dirent.path()))
+            })
+            .collect();
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= self.max_bytes {
+            return Ok(());
+        }
+        entries.sort();
+        for (_, len, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if path == keep {
+                continue;
+            }
+            match fs::remove_file(&path) {
+                Ok(()) => {
+                    total = total.saturating_sub(len);
+                    debug!(entry = %path.display(), "decode_cache_evicted");
+                }
+                Err(err) => debug!(entry = %path.display(), "decode cache eviction failed: {err}"),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads an entry; `Ok(None)` when there is none.
+fn read_entry(entry: &Path) -> Result<Option<RgbaImage>> {
+    let mut file = match File::open(entry) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let mut header = [0u8; HEADER_LEN];
+    file.read_exact(&mut header)
+        .context("entry is shorter than its header")?;
+    ensure!(&header[..4] == MAGIC, "entry has an unknown format");
+    let width = u32::from_le_bytes(header[4..8].try_into()?);
+    let height = u32::from_le_bytes(header[8..12].try_into()?);
+    // Check the size before allocating: a damaged header can claim anything.
+    let expected = u64::from(width) * u64::from(height) * 4;
+    let stored = file.metadata()?.len().saturating_sub(HEADER_LEN as u64);
+    ensure!(
+        stored == expected,
+        "entry holds {stored} bytes of pixels, expected {expected} for {width}x{height}"
+    );
+    let mut pixels = Vec::with_capacity(expected as usize);
+    file.read_to_end(&mut pixels)?;
+    ensure!(
+        pixels.len() as u64 == expected,
+        "entry changed while it was read"
+    );
+    Ok(RgbaImage::from_raw(width, height, pixels))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecodeCache;
+    use crate::config::{DecodeCacheConfig, ThumbnailCacheConfig};
+    use std::cell::Cell;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, SystemTime};
+    use tokio_util::sync::CancellationToken;
+
+    fn cache_in(dir: &Path, thumbnails: Option<&ThumbnailCacheConfig>) -> DecodeCache {
+        let cfg = DecodeCacheConfig {
+            directory: dir.join("cache"),
+            max_size_mb: 64,
+        };
+        DecodeCache::new(&cfg, thumbnails)
+    }
+
+    /// A photo with varied colour and alpha, so a lossy round trip would show.
+    fn write_photo(path: &Path, seed: u8) {
+        image::RgbaImage::from_fn(40, 30, |x, y| {
+            image::Rgba([x as u8 * 6, y as u8 * 8, seed, (x + y) as u8 + 100])
+        })
+        .save(path)
+        .unwrap();
+    }
+
+    fn entries(dir: &Path) -> Vec<PathBuf> {
+        let mut found: Vec<PathBuf> = fs::read_dir(dir.join("cache"))
+            .map(|rd| rd.filter_map(Result::ok).map(|e| e.path()).collect())
+            .unwrap_or_default();
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn round_trip_is_lossless_and_skips_the_decode() {
+        let dir = tempfile::tempdir().unwrap();
+        let photo = dir.path().join("photo.png");
+        write_photo(&photo, 7);
+        let cache = cache_in(dir.path(), None);
+        let decodes = Cell::new(0);
+        let decode = |p: &Path| {
+            decodes.set(decodes.get() + 1);
+            Ok(image::open(p)?.to_rgba8())
+        };
+
+        let first = cache
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        let second = cache
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+
+        assert_eq!(decodes.get(), 1, "the second load must come from the cache");
+        assert_eq!(first, second);
+        assert_eq!(second, image::open(&photo).unwrap().to_rgba8());
+        assert_eq!(cache.stats(), (1, 1));
+        assert_eq!(entries(dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn changed_options_or_contents_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let photo = dir.path().join("photo.png");
+        write_photo(&photo, 1);
+        let decodes = Cell::new(0);
+        let decode = |p: &Path| {
+            decodes.set(decodes.get() + 1);
+            Ok(image::open(p)?.to_rgba8())
+        };
+        let plain = cache_in(dir.path(), None);
+        plain
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+
+        // A thumbnail size shapes the decoded pixels, so it is part of the key.
+        let thumbnails = ThumbnailCacheConfig {
+            directory: dir.path().join("thumbs"),
+            max_dimension_px: 1920,
+        };
+        let resized = cache_in(dir.path(), Some(&thumbnails));
+        resized
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        assert_eq!(decodes.get(), 2);
+        assert_eq!(entries(dir.path()).len(), 2);
+
+        // New contents at the same path are a different photo.
+        write_photo(&photo, 2);
+        let reloaded = plain
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        assert_eq!(decodes.get(), 3);
+        assert_eq!(reloaded.get_pixel(0, 0).0[2], 2);
+
+        // And unchanged options still hit.
+        resized
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        plain
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        assert_eq!(decodes.get(), 4);
+    }
+
+    #[test]
+    fn corrupt_entries_and_a_blocked_directory_fall_back_to_decoding() {
+        let dir = tempfile::tempdir().unwrap();
+        let photo = dir.path().join("photo.png");
+        write_photo(&photo, 3);
+        let cache = cache_in(dir.path(), None);
+        let decode = |p: &Path| Ok(image::open(p)?.to_rgba8());
+        cache
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        let entry = entries(dir.path()).pop().unwrap();
+
+        fs::write(&entry, b"PFD1 truncated").unwrap();
+        let image = cache
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        assert_eq!(image, image::open(&photo).unwrap().to_rgba8());
+        assert!(
+            fs::metadata(&entry).unwrap().len() > 1000,
+            "entry rewritten"
+        );
+
+        // A file where the directory should be: every load still works.
+        let blocked_dir = tempfile::tempdir().unwrap();
+        fs::write(blocked_dir.path().join("cache"), b"not a directory").unwrap();
+        let blocked = cache_in(blocked_dir.path(), None);
+        blocked
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        blocked
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        assert_eq!(blocked.stats(), (0, 2));
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = cache_in(dir.path(), None);
+        // Room for two 40x30 entries but not three.
+        cache.max_bytes = 2 * (12 + 40 * 30 * 4);
+        let decode = |p: &Path| Ok(image::open(p)?.to_rgba8());
+        let photos: Vec<PathBuf> = (0..3)
+            .map(|i| {
+                let path = dir.path().join(format!("p{i}.png"));
+                write_photo(&path, i);
+                path
+            })
+            .collect();
+
+        cache
+            .load(&photos[0], &CancellationToken::new(), decode)
+            .unwrap();
+        cache
+            .load(&photos[1], &CancellationToken::new(), decode)
+            .unwrap();
+        // Age the entries, then use p0 again so p1 becomes the oldest.
+        let past = SystemTime::now() - Duration::from_secs(60);
+        for entry in entries(dir.path()) {
+            fs::File::options()
+                .write(true)
+                .open(&entry)
+                .unwrap()
+                .set_modified(past)
+                .unwrap();
+        }
+        cache
+            .load(&photos[0], &CancellationToken::new(), decode)
+            .unwrap();
+        cache
+            .load(&photos[2], &CancellationToken::new(), decode)
+            .unwrap();
+
+        assert_eq!(entries(dir.path()).len(), 2);
+        let (hits, misses) = cache.stats();
+        cache
+            .load(&photos[0], &CancellationToken::new(), decode)
+            .unwrap();
+        cache
+            .load(&photos[1], &CancellationToken::new(), decode)
+            .unwrap();
+        assert_eq!(cache.stats(), (hits + 1, misses + 1), "p0 kept, p1 evicted");
+    }
+}
@@ -0,0 +1,156 @@
+//! Per-photo settings read from a sidecar file next to the photo.
+//!
+//! `beach.jpg.frame.yaml` beside `beach.jpg` can give that one photo its own
+//! mat, written exactly like the top-level `matting` block:
+//!
+//! ```yaml
+//! matting:
+//!   active:
+//!     - kind: fixed-color
+//!       color: [12, 12, 12]
+//! ```
+//!
+//! The loader reads the sidecar each time it prepares the photo, so edits
+//! apply the next time the photo comes up. Photos without one use the
+//! configured matting, as does a photo whose sidecar does not parse.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, ensure};
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::config::MattingConfig;
+
+/// Appended to the photo's full file name, extension included.
+pub(crate) const SIDECAR_SUFFIX: &str = ".frame.yaml";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub(crate) struct PhotoSidecar {
+    #[serde(default)]
+    pub(crate) matting: Option<MattingConfig>,
+}
+
+pub(crate) fn sidecar_path(photo: &Path) -> PathBuf {
+    let mut name = photo.as_os_str().to_owned();
+    name.push(SIDECAR_SUFFIX);
+    PathBuf::from(name)
+}
+
+impl PhotoSidecar {
+    /// `Ok(None)` when the photo has no sidecar. Relative `fixed-image` paths
+    /// are taken from the sidecar's folder.
+    pub(crate) fn read(photo: &Path) -> Result<Option<Self>> {
+        let path = sidecar_path(photo);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()));
+            }
+        };
+        let mut sidecar: Self = serde_yaml::from_str(&text)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        if let Some(matting) = &mut sidecar.matting {
+            ensure!(
+                matting.fill_when_fits().is_none(),
+                "{}: matting.fill-when-fits only applies to the main config",
+                path.display()
+            );
+            if let Some(dir) = path.parent() {
+                matting.resolve_relative_paths(dir);
+            }
+            matting
+                .prepare_runtime()
+                .with_context(|| format!("invalid matting in {}", path.display()))?;
+        }
+        Ok(Some(sidecar))
+    }
+
+    /// The mat `photo` asks for, or `None` to use the configured matting. A
+    /// broken sidecar is logged and ignored.
+    pub(crate) fn matting_for(photo: &Path) -> Option<MattingConfig> {
+        match Self::read(photo) {
+            Ok(sidecar) => sidecar?.matting,
+            Err(err) => {
+                warn!(path = %photo.display(), "ignoring photo sidecar: {err:#}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MattingKind;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn kind_of(matting: &MattingConfig) -> MattingKind {
+        matting
+            .select_active(&mut StdRng::seed_from_u64(0))
+            .entry
+            .kind
+    }
+
+    #[test]
+    fn a_sidecar_overrides_the_mat_for_its_photo_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let framed = dir.path().join("beach.jpg");
+        let plain = dir.path().join("forest.jpg");
+        assert_eq!(
+            sidecar_path(&framed),
+            dir.path().join("beach.jpg.frame.yaml")
+        );
+        fs::write(
+            sidecar_path(&framed),
+            "matting:\n  active:\n    - kind: fixed-color\n      color: [12, 34, 56]\n",
+        )
+        .unwrap();
+
+        let matting = PhotoSidecar::matting_for(&framed).expect("sidecar mat");
+        assert_eq!(kind_of(&matting), MattingKind::FixedColor);
+        assert_eq!(matting.options()[0].fixed_color(), Some([12, 34, 56]));
+        assert!(PhotoSidecar::matting_for(&plain).is_none());
+    }
+
+    #[test]
+    fn a_broken_sidecar_falls_back_to_the_configured_mat() {
+        let dir = tempfile::tempdir().unwrap();
+        let photo = dir.path().join("beach.jpg");
+        fs::write(
+            sidecar_path(&photo),
+            "matting:\n  active:\n    - kind: velvet\n",
+        )
+        .unwrap();
+        assert!(PhotoSidecar::read(&photo).is_err());
+        assert!(PhotoSidecar::matting_for(&photo).is_none());
+
+        fs::write(sidecar_path(&photo), "mat: {}\n").unwrap();
+        let err = PhotoSidecar::read(&photo).unwrap_err();
+        assert!(format!("{err:#}").contains("unknown field"), "{err:#}");
+    }
+
+    #[test]
+    fn fixed_image_paths_are_relative_to_the_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+        let photo = dir.path().join("beach.jpg");
+        fs::write(
+            sidecar_path(&photo),
+            "matting:\n  active:\n    - kind: fixed-image\n      path: linen.png\n",
+        )
+        .unwrap();
+        image::RgbImage::new(4, 4)
+            .save(dir.path().join("linen.png"))
+            .unwrap();
+        let matting = PhotoSidecar::matting_for(&photo).expect("sidecar mat");
+        let crate::config::MattingMode::FixedImage { paths, .. } = &matting.options()[0].style
+        else {
+            panic!("expected a fixed-image mat");
+        };
+        assert_eq!(paths, &[dir.path().join("linen.png")]);
+    }
+}
@@ -0,0 +1,248 @@
+//! On-disk cache of downscaled photos.
+//!
+//! Decoding a 24 MP JPEG on a Pi takes far longer than the display needs: the
+//! viewer throws most of those pixels away when it fits the photo to the
+//! screen. The cache decodes each large photo once, downscales it to the
+//! configured longest edge, and stores the result as a JPEG that later loads
+//! read instead of the original.
+//!
+//! Entries live at `<directory>/<path hash>/<mtime ns>-<max edge>.jpg`. The
+//! per-photo directory holds at most one entry: storing a fresh one removes any
+//! left behind by an older mtime or a different size, so an edited photo
+//! invalidates its own cache.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use image::RgbaImage;
+use image::buffer::ConvertBuffer;
+use image::codecs::jpeg::JpegEncoder;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+use super::{ensure_live, fnv1a};
+use crate::config::ThumbnailCacheConfig;
+use crate::processing::layout::{resize_rgba, resize_to_contain};
+
+/// JPEG quality for cached downscales; visually lossless at display size.
+const JPEG_QUALITY: u8 = 90;
+
+pub struct ThumbnailCache {
+    directory: PathBuf,
+    max_dimension: u32,
+}
+
+impl ThumbnailCache {
+    pub fn new(cfg: &ThumbnailCacheConfig) -> Self {
+        Self {
+            directory: cfg.directory.clone(),
+            max_dimension: cfg.max_dimension_px,
+        }
+    }
+
+    /// Returns `path` as RGBA8, no larger than the configured longest edge.
+    ///
+    /// A fresh cache entry is read when present; otherwise `decode` produces
+    /// the full image, which is downscaled and stored when it is larger than
+    /// the limit. Photos with transparency are never cached because the cache
+    /// format has no alpha channel. Failing to write the cache only logs.
+    /// An `abandon`ed load stops before downscaling or writing.
+    pub fn load(
+        &self,
+        path: &Path,
+        abandon: &CancellationToken,
+        decode: impl FnOnce(&Path) -> Result<RgbaImage>,
+    ) -> Result<RgbaImage> {
+        let entry = self.entry_path(path)?;
+        match image::open(&entry) {
+            Ok(cached) => {
+                debug!(path = %path.display(), "thumbnail_cache_hit");
+                return Ok(cached.to_rgba8());
+            }
+            Err(err) if entry.exists() => {
+                debug!(path = %path.display(), "thumbnail_cache_unreadable: {err}");
+            }
+            Err(_) => {}
+        }
+
+        let full = decode(path)?;
+        let (w, h) = full.dimensions();
+        if w.max(h) <= self.max_dimension {
+            return Ok(full);
+        }
+        ensure_live(abandon)?;
+        let (tw, th) = resize_to_contain(self.max_dimension, self.max_dimension, w, h, u32::MAX);
+        let thumb = resize_rgba(&full, tw, th)?;
+        drop(full);
+        ensure_live(abandon)?;
+        if thumb.pixels().any(|px| px.0[3] != u8::MAX) {
+            return Ok(thumb);
+        }
+        match store(&entry, &thumb) {
+            Ok(()) => {
+                debug!(path = %path.display(), width = tw, height = th, "thumbnail_cache_stored")
+            }
+            Err(err) => warn!(path = %path.display(), "thumbnail cache write failed: {err:#}"),
+        }
+        Ok(thumb)
+    }
+
+    /// Cache file for the photo's current mtime and the configured size.
+    fn entry_path(&self, path: &Path) -> Result<PathBuf> {
+        let modified = fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .with_context(|| format!("failed to read mtime of {}", path.display()))?;
+        let mtime_ns = modified
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos());
+        Ok(self
+            .directory
+            .join(format!("{:016x}", path_hash(path)))
+            .join(format!("{mtime_ns}-{}.jpg", self.max_dimension)))
+    }
+}
+
+fn path_hash(path: &Path) -> u64 {
+    fnv1a(path.as_os_str().as_encoded_bytes())
+}
+
+/// Writes `thumb` as the only entry in its photo's cache directory. The file
+/// is written under a temporary name and renamed into place, so a reader never
+/// sees a partial JPEG.
+fn store(entry: &Path, thumb: &RgbaImage) -> Result<()> {
+    let dir = entry
+        .parent()
+        .context("cache entry has no parent directory")?;
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    for stale in fs::read_dir(dir)?.filter_map(Result::ok) {
+        let _ = fs::remove_file(stale.path());
+    }
+    let tmp = entry.with_extension("jpg.tmp");
+    {
+        let mut out = BufWriter::new(File::create(&tmp)?);
+        let rgb: image::RgbImage = thumb.convert();
+        JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY).encode_image(&rgb)?;
+        out.flush()?;
+    }
+    fs::rename(&tmp, entry)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThumbnailCache;
+    use crate::config::ThumbnailCacheConfig;
+    use std::cell::Cell;
+    use std::fs::{self, File};
+    use std::path::Path;
+    use std::time::{Duration, SystemTime};
+    use tokio_util::sync::CancellationToken;
+
+    fn cache_in(dir: &Path) -> ThumbnailCache {
+        ThumbnailCache::new(&ThumbnailCacheConfig {
+            directory: dir.join("cache"),
+            max_dimension_px: 64,
+        })
+    }
+
+    fn write_photo(path: &Path, w: u32, h: u32) {
+        image::RgbaImage::from_pixel(w, h, image::Rgba([200, 40, 40, 255]))
+            .save(path)
+            .unwrap();
+    }
+
+    fn entries(dir: &Path) -> Vec<std::path::PathBuf> {
+        walkdir::WalkDir::new(dir.join("cache"))
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect()
+    }
+
+    #[test]
+    fn miss_decodes_and_stores_then_hit_skips_decode() {
+        let dir = tempfile::tempdir().unwrap();
+        let photo = dir.path().join("big.png");
+        write_photo(&photo, 256, 128);
+        let cache = cache_in(dir.path());
+        let decodes = Cell::new(0);
+        let decode = |p: &Path| {
+            decodes.set(decodes.get() + 1);
+            Ok(image::open(p)?.to_rgba8())
+        };
+
+        let first = cache
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        assert_eq!(first.dimensions(), (64, 32));
+        assert_eq!(decodes.get(), 1);
+        assert_eq!(entries(dir.path()).len(), 1);
+
+        let second = cache
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        assert_eq!(second.dimensions(), (64, 32));
+        assert_eq!(decodes.get(), 1, "a fresh entry must not decode again");
+        let px = second.get_pixel(10, 10).0;
+        assert!(px[0] > 180 && px[1] < 70 && px[3] == 255, "{px:?}");
+    }
+
+    #[test]
+    fn changed_mtime_invalidates_the_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let photo = dir.path().join("edited.png");
+        write_photo(&photo, 256, 128);
+        let cache = cache_in(dir.path());
+        let decodes = Cell::new(0);
+        let decode = |p: &Path| {
+            decodes.set(decodes.get() + 1);
+            Ok(image::open(p)?.to_rgba8())
+        };
+
+        cache
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        let before = entries(dir.path());
+
+        let later = SystemTime::now() + Duration::from_secs(60);
+        File::options()
+            .write(true)
+            .open(&photo)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        cache
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        assert_eq!(decodes.get(), 2, "a new mtime must decode again");
+        let after = entries(dir.path());
+        assert_eq!(after.len(), 1, "the stale entry is replaced");
+        assert_ne!(before, after);
+
+        cache
+            .load(&photo, &CancellationToken::new(), decode)
+            .unwrap();
+        assert_eq!(decodes.get(), 2);
+    }
+
+    #[test]
+    fn photos_within_the_limit_are_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let photo = dir.path().join("small.png");
+        write_photo(&photo, 48, 32);
+        let cache = cache_in(dir.path());
+
+        let img = cache
+            .load(&photo, &CancellationToken::new(), |p| {
+                Ok(image::open(p)?.to_rgba8())
+            })
+            .unwrap();
+        assert_eq!(img.dimensions(), (48, 32));
+        assert!(entries(dir.path()).is_empty());
+        assert!(!fs::exists(dir.path().join("cache")).unwrap());
+    }
+}
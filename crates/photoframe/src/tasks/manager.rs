@@ -1,17 +1,30 @@
-use crate::config::PlaylistOptions;
+use crate::config::{DedupMode, PlaylistFavorites, PlaylistOptions};
 use crate::events::{Displayed, InventoryEvent, LoadPhoto, PhotoInfo};
-use anyhow::Result;
+use crate::tasks::dedup::{Copies, Fingerprint};
+use crate::tasks::history::HistoryRecorder;
+use crate::tasks::quarantine::{Quarantine, QuarantineEntry, SharedQuarantine};
+use anyhow::{Context, Result};
 use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::{Arc, MutexGuard, PoisonError};
 use std::time::SystemTime;
 use tokio::select;
 use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::time::{Duration, sleep};
+use tokio::sync::watch;
+use tokio::time::{Duration, MissedTickBehavior, interval, sleep};
 use tokio_util::sync::CancellationToken;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
+
+/// How often a changed playlist is written to `playlist.state-file`.
+const STATE_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+/// Number of recently shown photos kept in the persisted display history.
+const RECENT_HISTORY_LEN: usize = 64;
+const SNAPSHOT_VERSION: u32 = 1;
 
 /// Orchestrates the playlist via a virtual-time min-heap scheduler.
 ///
@@ -21,6 +34,28 @@ use tracing::{debug, warn};
 /// - The photo with the smallest key is always shown next.
 /// - On show, the photo is rescheduled at vclock + new gap (no rebuild needed).
 /// - `PhotoAdded` / `PhotoRemoved` are O(log n) heap ops; removed entries are lazily skipped.
+///
+/// `library_size` is kept in sync with the number of live photos so other tasks (the
+/// viewer's greeting message) can read it without a round trip.
+///
+/// With `playlist.state-file` set, the schedule is restored from that file at startup and
+/// written back every minute (when something was shown) and on shutdown, so a restart
+/// resumes the rotation instead of reshuffling it.
+///
+/// With `playlist.new-photo-boost` set, the first displays after startup go to recent
+/// photos, newest first, ahead of the schedule. Each boosted photo is then rescheduled as
+/// if it had been shown normally.
+///
+/// Every `Displayed` notification is added to `history`, except one for a
+/// photo another output is still showing, which is the same showing seen twice.
+///
+/// Photos that fail to decode `playlist.quarantine.max-failures` times are kept
+/// out of the rotation for its cooldown; `quarantine` is the list the control
+/// socket reads, saved in the state file alongside the schedule.
+///
+/// While `library_hold` carries a reason (a `pause` with a `reason`, sent
+/// while a sync rewrites the library), no photos are sent to the loader.
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     mut inv_rx: Receiver<InventoryEvent>,
     mut displayed_rx: Receiver<Displayed>,
@@ -29,16 +64,30 @@ pub async fn run(
     options: PlaylistOptions,
     now_override: Option<SystemTime>,
     seed_override: Option<u64>,
+    library_size: Arc<AtomicUsize>,
+    history: HistoryRecorder,
+    quarantine: SharedQuarantine,
+    library_hold: watch::Receiver<Option<String>>,
 ) -> Result<()> {
-    let rng = match seed_override {
-        Some(seed) => StdRng::seed_from_u64(seed),
-        None => StdRng::from_os_rng(),
-    };
-    let mut playlist = PlaylistState::with_rng(options, rng, now_override);
+    let seed = seed_override.unwrap_or_else(rand::random);
+    let state_file = options.state_file.clone();
+    let mut playlist = PlaylistState::with_seed(options, seed, now_override);
+    playlist.quarantine = quarantine;
+    if let Some(snapshot) = state_file.as_deref().and_then(load_snapshot) {
+        info!(photos = snapshot.entries.len(), "playlist_state_restored");
+        playlist.restore(snapshot);
+    }
+    let mut save_ticker = interval(STATE_SAVE_INTERVAL);
+    save_ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut on_screen = OnScreen::default();
 
     loop {
-        let next = playlist.peek_next();
+        let next = playlist.peek_next().map(|(path, priority)| {
+            let created_at = playlist.created_at(&path);
+            (path, priority, created_at)
+        });
         let next_is_some = next.is_some();
+        let held = library_hold.borrow().is_some();
 
         select! {
             _ = cancel.cancelled() => break,
@@ -49,14 +98,14 @@ pub async fn run(
                 let to_loader = to_loader.clone();
                 async move {
                     match next {
-                        Some((path, priority)) => to_loader
-                            .send(LoadPhoto { path: (*path).clone(), priority })
+                        Some((path, priority, created_at)) => to_loader
+                            .send(LoadPhoto { path: (*path).clone(), priority, created_at })
                             .await
                             .map_err(|_| ()),
                         None => Err(()),
                     }
                 }
-            }, if next_is_some => {
+            }, if next_is_some && !held => {
                 match res {
                     Ok(()) => playlist.commit_shown(),
                     Err(()) => {
@@ -67,27 +116,151 @@ pub async fn run(
             }
 
             // Inventory updates (from files task)
-            maybe_ev = inv_rx.recv() => match maybe_ev {
-                Some(InventoryEvent::PhotoAdded(info)) => playlist.record_add(info),
-                Some(InventoryEvent::PhotoRemoved(p)) => playlist.record_remove(&p),
-                None => {}
-            },
+            maybe_ev = inv_rx.recv() => {
+                match maybe_ev {
+                    Some(InventoryEvent::PhotoAdded(info)) => playlist.record_add(info),
+                    Some(InventoryEvent::PhotoRemoved(p)) => playlist.record_remove(&p),
+                    Some(InventoryEvent::PhotoFailed(p)) => playlist.record_failure(&p),
+                    Some(InventoryEvent::PhotoFingerprinted(p, fingerprint)) => {
+                        playlist.record_fingerprint(p, fingerprint)
+                    }
+                    Some(InventoryEvent::FavoritesChanged(list)) => playlist.set_favorites(list),
+                    None => {}
+                }
+                library_size.store(playlist.known.len(), AtomicOrdering::Relaxed);
+            }
 
-            // Displayed notifications (informational only)
+            // Displayed notifications feed the history only.
             maybe_disp = displayed_rx.recv() => {
-                if let Some(Displayed(p)) = maybe_disp {
-                    debug!("displayed: {}", p.display());
+                if let Some(displayed) = maybe_disp {
+                    debug!(
+                        output = displayed.output.as_deref().unwrap_or("-"),
+                        "displayed: {}",
+                        displayed.path.display()
+                    );
+                    if let Some(p) = on_screen.show(displayed) {
+                        playlist.record_displayed(&p);
+                        history.record(p, SystemTime::now());
+                    }
                 }
             }
 
+            _ = save_ticker.tick(), if state_file.is_some() => {
+                persist_state(&mut playlist, state_file.as_deref());
+            }
+
             // Idle tick: prevents spinning when the heap is empty at startup.
             _ = sleep(Duration::from_millis(50)) => {}
         }
     }
 
+    persist_state(&mut playlist, state_file.as_deref());
+    Ok(())
+}
+
+/// On-disk form of the scheduler, written to `playlist.state-file`.
+#[derive(Debug, Serialize, Deserialize)]
+struct PlaylistSnapshot {
+    version: u32,
+    /// The scheduler's RNG seed and how many values it has drawn since, so a
+    /// restored playlist continues the exact sequence the running one would.
+    rng_seed: u64,
+    rng_draws: u64,
+    vclock: f64,
+    entries: Vec<SnapshotEntry>,
+    /// Recently shown photos, oldest first.
+    recent: Vec<PathBuf>,
+    /// Photos that have failed to decode, including quarantined ones.
+    #[serde(default)]
+    quarantine: Vec<QuarantineEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SnapshotEntry {
+    path: PathBuf,
+    key: f64,
+    shown: bool,
+}
+
+/// Reads a snapshot, treating a missing, unreadable, or corrupt file as "start fresh".
+fn load_snapshot(path: &Path) -> Option<PlaylistSnapshot> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            debug!(path = %path.display(), "no playlist state file yet");
+            return None;
+        }
+        Err(err) => {
+            warn!(path = %path.display(), error = %err, "playlist_state_unreadable");
+            return None;
+        }
+    };
+    match serde_json::from_slice::<PlaylistSnapshot>(&bytes) {
+        Ok(snapshot) if snapshot.version == SNAPSHOT_VERSION => Some(snapshot),
+        Ok(snapshot) => {
+            warn!(
+                path = %path.display(),
+                version = snapshot.version,
+                "playlist_state_unsupported_version"
+            );
+            None
+        }
+        Err(err) => {
+            warn!(path = %path.display(), error = %err, "playlist_state_corrupt");
+            None
+        }
+    }
+}
+
+/// Writes via a temporary file and rename so a power cut never leaves a torn file.
+fn save_snapshot(path: &Path, snapshot: &PlaylistSnapshot) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let tmp = path.with_extension("tmp");
+    let bytes = serde_json::to_vec(snapshot).context("failed to encode playlist state")?;
+    fs::write(&tmp, bytes).with_context(|| format!("failed to write {}", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("failed to replace {}", path.display()))?;
     Ok(())
 }
 
+fn persist_state(playlist: &mut PlaylistState, path: Option<&Path>) {
+    let Some(path) = path else {
+        return;
+    };
+    if !playlist.dirty {
+        return;
+    }
+    let snapshot = playlist.snapshot();
+    match save_snapshot(path, &snapshot) {
+        Ok(()) => {
+            debug!(path = %path.display(), photos = snapshot.entries.len(), "playlist_state_saved")
+        }
+        Err(err) => {
+            warn!(path = %path.display(), error = %format!("{err:#}"), "playlist_state_save_failed")
+        }
+    }
+}
+
+/// The photo each output is showing, from its latest `Displayed` event.
+#[derive(Default)]
+struct OnScreen(HashMap<Option<String>, PathBuf>);
+
+impl OnScreen {
+    /// Notes what `displayed.output` now shows and returns the path when it is a
+    /// new showing, or `None` when another output is already showing that photo.
+    fn show(&mut self, displayed: Displayed) -> Option<PathBuf> {
+        let Displayed { path, output } = displayed;
+        let elsewhere = self
+            .0
+            .iter()
+            .any(|(shown_on, shown)| *shown_on != output && *shown == path);
+        self.0.insert(output, path.clone());
+        (!elsewhere).then_some(path)
+    }
+}
+
 struct PlaylistState {
     heap: BinaryHeap<Entry>,
     known: HashMap<PathBuf, Meta>,
@@ -96,8 +269,32 @@ struct PlaylistState {
     vclock: f64,
     seq: u64,
     rng: StdRng,
+    rng_seed: u64,
+    rng_draws: u64,
     options: PlaylistOptions,
     now_override: Option<SystemTime>,
+    /// Schedule positions from a restored snapshot, claimed as each photo is re-added.
+    restored: HashMap<PathBuf, SnapshotEntry>,
+    recent: VecDeque<PathBuf>,
+    /// Something was shown, or a photo failed to decode, since the last snapshot.
+    dirty: bool,
+    /// Displays left before `new-photo-boost` stops applying.
+    boost_remaining: u32,
+    /// Photos the boost has already shown this session.
+    boosted: HashSet<PathBuf>,
+    /// The boosted photo `peek_next` returned, for `commit_shown` to commit.
+    boost_pick: Option<Arc<PathBuf>>,
+    /// Paths listed in `playlist.favorites`, weighted up by its factor.
+    favorites: Vec<PathBuf>,
+    /// Photos from library roots with a `weight` other than 1, with it.
+    root_weights: HashMap<PathBuf, f64>,
+    /// Photos that keep failing to decode; shared with the control socket.
+    quarantine: SharedQuarantine,
+    /// Fingerprints for `playlist.dedup`, of photos in `known` or `collapsed`.
+    copies: Copies,
+    /// Photos left out for a better copy, with their capture time, until that
+    /// copy goes.
+    collapsed: HashMap<PathBuf, SystemTime>,
 }
 
 struct Meta {
@@ -136,25 +333,124 @@ impl PartialEq for Entry {
 impl Eq for Entry {}
 
 impl PlaylistState {
-    fn with_rng(options: PlaylistOptions, rng: StdRng, now_override: Option<SystemTime>) -> Self {
+    fn with_seed(options: PlaylistOptions, seed: u64, now_override: Option<SystemTime>) -> Self {
+        let boost_remaining = options
+            .new_photo_boost
+            .as_ref()
+            .map_or(0, |boost| boost.displays);
+        let favorites = options
+            .favorites
+            .as_ref()
+            .map(PlaylistFavorites::load)
+            .unwrap_or_default();
+        let quarantine = Quarantine::shared(&options.quarantine);
         Self {
             heap: BinaryHeap::new(),
             known: HashMap::new(),
             generations: HashMap::new(),
             vclock: 0.0,
             seq: 0,
-            rng,
+            rng: StdRng::seed_from_u64(seed),
+            rng_seed: seed,
+            rng_draws: 0,
             options,
             now_override,
+            restored: HashMap::new(),
+            recent: VecDeque::new(),
+            dirty: false,
+            boost_remaining,
+            boosted: HashSet::new(),
+            boost_pick: None,
+            favorites,
+            root_weights: HashMap::new(),
+            quarantine,
+            copies: Copies::default(),
+            collapsed: HashMap::new(),
+        }
+    }
+
+    /// Resumes from `snapshot`. Photos are matched up as they are re-added; entries for
+    /// photos that never come back are simply never claimed.
+    fn restore(&mut self, snapshot: PlaylistSnapshot) {
+        self.rng = StdRng::seed_from_u64(snapshot.rng_seed);
+        for _ in 0..snapshot.rng_draws {
+            self.rng.random::<f64>();
         }
+        self.rng_seed = snapshot.rng_seed;
+        self.rng_draws = snapshot.rng_draws;
+        self.vclock = snapshot.vclock;
+        self.recent = snapshot.recent.into_iter().collect();
+        self.quarantine().restore(snapshot.quarantine);
+        self.restored = snapshot
+            .entries
+            .into_iter()
+            .filter(|entry| entry.key.is_finite())
+            .map(|entry| (entry.path.clone(), entry))
+            .collect();
+    }
+
+    /// Captures the live schedule and clears the dirty flag.
+    fn snapshot(&mut self) -> PlaylistSnapshot {
+        self.dirty = false;
+        let mut entries: Vec<SnapshotEntry> = self
+            .heap
+            .iter()
+            .filter_map(|entry| {
+                let meta = self.known.get(entry.path.as_ref())?;
+                (meta.generation == entry.generation).then(|| SnapshotEntry {
+                    path: (*entry.path).clone(),
+                    key: entry.key,
+                    shown: meta.shown,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a.key.total_cmp(&b.key));
+        PlaylistSnapshot {
+            version: SNAPSHOT_VERSION,
+            rng_seed: self.rng_seed,
+            rng_draws: self.rng_draws,
+            vclock: self.vclock,
+            entries,
+            recent: self.recent.iter().cloned().collect(),
+            quarantine: self.quarantine().entries(self.now()),
+        }
+    }
+
+    fn quarantine(&self) -> MutexGuard<'_, Quarantine> {
+        self.quarantine
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn remember_shown(&mut self, path: &Path) {
+        if self.recent.len() == RECENT_HISTORY_LEN {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(path.to_path_buf());
+        self.dirty = true;
     }
 
     fn now(&self) -> SystemTime {
         self.now_override.unwrap_or_else(SystemTime::now)
     }
 
+    /// Scheduling weight for `path`: the age-based weight, multiplied by its
+    /// library root's weight and by the favorites factor when the photo is
+    /// listed.
+    fn weight_for(&self, path: &Path, created_at: SystemTime) -> f64 {
+        let weight = self.options.weight_for(created_at, self.now())
+            * self.root_weights.get(path).copied().unwrap_or(1.0);
+        match &self.options.favorites {
+            Some(favorites) if PlaylistFavorites::contains(&self.favorites, path) => {
+                weight * favorites.factor
+            }
+            _ => weight,
+        }
+    }
+
     /// Exponential gap with mean 1/weight (Poisson scheduling). u in (0,1] avoids ln(0).
     fn sample_gap(&mut self, weight: f64) -> f64 {
+        self.rng_draws += 1;
         let u = 1.0 - self.rng.random::<f64>(); // random::<f64>() ∈ [0,1), so u ∈ (0,1]
         -u.ln() / weight.max(1.0)
     }
@@ -166,7 +462,7 @@ impl PlaylistState {
     }
 
     fn schedule(&mut self, path: Arc<PathBuf>, created_at: SystemTime, generation: u32) {
-        let weight = self.options.weight_for(created_at, self.now());
+        let weight = self.weight_for(&path, created_at);
         let key = self.vclock + self.sample_gap(weight);
         let seq = self.next_seq();
         self.heap.push(Entry {
@@ -186,17 +482,22 @@ impl PlaylistState {
     /// is preserved; only genuine back-to-back repeats are bumped. With a single
     /// photo the heap is empty here, so it is allowed to repeat — there is
     /// nothing else to show.
+    ///
+    /// A `playlist.no-repeat-window` of N widens "the next photo" to the N-th
+    /// one due, so N other photos come up before this one does again. Each of
+    /// them is popped before the bumped key whether or not it is shown again
+    /// in between. A library with N photos or fewer cycles through all of them.
     fn reschedule_after_show(
         &mut self,
         path: Arc<PathBuf>,
         created_at: SystemTime,
         generation: u32,
     ) {
-        let weight = self.options.weight_for(created_at, self.now());
+        let weight = self.weight_for(&path, created_at);
         let mut key = self.vclock + self.sample_gap(weight);
-        // Copy the next key out so the immutable heap borrow ends before we draw
+        // Copy the key out so the immutable heap borrow ends before we draw
         // another gap.
-        if let Some(next_key) = self.heap.peek().map(|entry| entry.key)
+        if let Some(next_key) = self.upcoming_key(self.options.no_repeat_window)
             && key <= next_key
         {
             key = next_key + self.sample_gap(weight);
@@ -210,29 +511,78 @@ impl PlaylistState {
         });
     }
 
+    /// Key of the `n`-th photo due, or of the last one when fewer are queued.
+    fn upcoming_key(&self, n: usize) -> Option<f64> {
+        if n <= 1 {
+            return self.heap.peek().map(|entry| entry.key);
+        }
+        // Stale entries would stand in for photos that are never shown.
+        let mut keys: Vec<f64> = self
+            .heap
+            .iter()
+            .filter(|entry| {
+                self.known
+                    .get(entry.path.as_ref())
+                    .is_some_and(|meta| meta.generation == entry.generation)
+            })
+            .map(|entry| entry.key)
+            .collect();
+        let index = n.min(keys.len()).checked_sub(1)?;
+        let (_, nth, _) = keys.select_nth_unstable_by(index, f64::total_cmp);
+        Some(*nth)
+    }
+
     fn record_add(&mut self, info: PhotoInfo) {
+        if info.root_weight == 1.0 {
+            self.root_weights.remove(&info.path);
+        } else {
+            self.root_weights
+                .insert(info.path.clone(), info.root_weight);
+        }
         // Already live (e.g. a metadata refresh): update created_at but keep the existing
         // schedule and generation — do not push another heap entry.
         if let Some(meta) = self.known.get_mut(&info.path) {
             meta.created_at = info.created_at;
             return;
         }
+        if let Some(created_at) = self.collapsed.get_mut(&info.path) {
+            *created_at = info.created_at;
+            return;
+        }
+        let now = self.now();
+        if self.quarantine().blocks(&info.path, now) {
+            debug!(path = %info.path.display(), "quarantined photo not added");
+            return;
+        }
         // New, or re-added after removal. Reading the bumped generation here ensures the
         // fresh heap entry has a strictly higher generation than any orphaned stale entries.
         let created_at = info.created_at;
+        let restored = self.restored.remove(&info.path);
         let path_arc = Arc::new(info.path);
         let generation = *self.generations.entry((*path_arc).clone()).or_insert(0);
-        let weight = self.options.weight_for(created_at, self.now());
+        let weight = self.weight_for(&path_arc, created_at);
         self.known.insert(
             (*path_arc).clone(),
             Meta {
                 created_at,
                 generation,
-                shown: false,
+                shown: restored.as_ref().is_some_and(|entry| entry.shown),
             },
         );
         debug!(path = %path_arc.display(), weight, "photo added to playlist");
-        self.schedule(path_arc, created_at, generation);
+        match restored {
+            // Back where it was in the saved rotation.
+            Some(entry) => {
+                let seq = self.next_seq();
+                self.heap.push(Entry {
+                    key: entry.key,
+                    seq,
+                    generation,
+                    path: path_arc,
+                });
+            }
+            None => self.schedule(path_arc, created_at, generation),
+        }
     }
 
     fn record_remove(&mut self, path: &Path) {
@@ -243,13 +593,210 @@ impl PlaylistState {
                 *g += 1;
             }
             debug!(path = %path.display(), "photo removed from playlist");
+        } else if self.collapsed.remove(path).is_some() {
+            debug!(path = %path.display(), "collapsed copy removed");
+        }
+        self.root_weights.remove(path);
+        self.restore_copies_of(path);
+    }
+
+    /// Notes the fingerprint of a photo in the playlist. With `dedup.mode:
+    /// collapse` the photo leaves the rotation when it has a better copy, as do
+    /// the copies it is better than.
+    fn record_fingerprint(&mut self, path: PathBuf, fingerprint: Fingerprint) {
+        let mode = self.options.dedup.mode;
+        if mode == DedupMode::Off
+            || !(self.known.contains_key(&path) || self.collapsed.contains_key(&path))
+        {
+            return;
+        }
+        self.copies.insert(path.clone(), fingerprint);
+        if mode != DedupMode::Collapse {
+            return;
+        }
+        let mut group = self.copies.copies_of(&path);
+        group.push(path);
+        for copy in group {
+            if let Some(kept) = self.copies.better_copy(&copy) {
+                self.collapse(copy, &kept);
+            }
+        }
+    }
+
+    /// Takes `path` out of the rotation in favor of its copy `kept`.
+    fn collapse(&mut self, path: PathBuf, kept: &Path) {
+        let Some(meta) = self.known.remove(&path) else {
+            return;
+        };
+        if let Some(g) = self.generations.get_mut(&path) {
+            *g += 1;
+        }
+        info!(
+            path = %path.display(),
+            kept = %kept.display(),
+            "duplicate_collapsed"
+        );
+        self.collapsed.insert(path, meta.created_at);
+    }
+
+    /// Forgets the fingerprint of `path`, which left the playlist, and puts
+    /// back each collapsed copy of it that no longer has a better one.
+    fn restore_copies_of(&mut self, path: &Path) {
+        let copies = self.copies.copies_of(path);
+        if self.copies.remove(path).is_none() {
+            return;
+        }
+        for copy in copies {
+            if self.copies.better_copy(&copy).is_some() {
+                continue;
+            }
+            if let Some(created_at) = self.collapsed.remove(&copy) {
+                info!(path = %copy.display(), "duplicate_restored");
+                let root_weight = self.root_weights.get(&copy).copied().unwrap_or(1.0);
+                self.record_add(PhotoInfo {
+                    path: copy,
+                    created_at,
+                    root_weight,
+                });
+            }
+        }
+    }
+
+    /// Whether `path` is a copy of one of the last `dedup.window` photos
+    /// shown, with `dedup.mode: spread`. A library too small to keep copies
+    /// apart shows them as they come.
+    fn too_soon(&self, path: &Path) -> bool {
+        let dedup = &self.options.dedup;
+        dedup.mode == DedupMode::Spread
+            && self.known.len() > dedup.window
+            && self
+                .recent
+                .iter()
+                .rev()
+                .take(dedup.window)
+                .any(|shown| shown != path && self.copies.are_copies(path, shown))
+    }
+
+    /// Moves the front entry, which came up too soon after a copy of it, back
+    /// behind the next `dedup.window` photos due.
+    fn defer_front(&mut self) {
+        let Some(entry) = self.heap.pop() else {
+            return;
+        };
+        let created_at = self.created_at(&entry.path);
+        let weight = self.weight_for(&entry.path, created_at);
+        let after = self
+            .upcoming_key(self.options.dedup.window)
+            .unwrap_or(entry.key);
+        let key = after + self.sample_gap(weight);
+        let seq = self.next_seq();
+        debug!(path = %entry.path.display(), "duplicate_deferred");
+        self.heap.push(Entry { key, seq, ..entry });
+    }
+
+    /// Drops a photo that failed to decode and counts the failure toward its
+    /// quarantine.
+    fn record_failure(&mut self, path: &Path) {
+        self.record_remove(path);
+        let now = self.now();
+        if let Some(entry) = self.quarantine().record_failure(path, now) {
+            info!(
+                path = %path.display(),
+                failures = entry.failures,
+                "photo_quarantined"
+            );
+        }
+        self.dirty = true;
+    }
+
+    /// A photo that made it to the screen is clear of earlier failures.
+    fn record_displayed(&mut self, path: &Path) {
+        if self.quarantine().forget(path) {
+            self.dirty = true;
         }
     }
 
+    /// Replaces the favorites list. Photos whose favorite status changed get a
+    /// fresh schedule entry at their new weight; the old entry goes stale.
+    fn set_favorites(&mut self, favorites: Vec<PathBuf>) {
+        let before = std::mem::replace(&mut self.favorites, favorites);
+        let changed: Vec<(PathBuf, SystemTime)> = self
+            .known
+            .iter()
+            .filter(|(path, _)| {
+                PlaylistFavorites::contains(&before, path)
+                    != PlaylistFavorites::contains(&self.favorites, path)
+            })
+            .map(|(path, meta)| (path.clone(), meta.created_at))
+            .collect();
+        info!(
+            favorites = self.favorites.len(),
+            rescheduled = changed.len(),
+            "playlist_favorites_updated"
+        );
+        for (path, created_at) in changed {
+            let generation = self.generations.entry(path.clone()).or_insert(0);
+            *generation += 1;
+            let generation = *generation;
+            if let Some(meta) = self.known.get_mut(&path) {
+                meta.generation = generation;
+            }
+            self.schedule(Arc::new(path), created_at, generation);
+        }
+    }
+
+    /// The photo the new-photo boost puts next: the newest recent photo it has not shown
+    /// yet. `None` once the boost is spent or no photo qualifies.
+    fn boosted_next(&self) -> Option<&PathBuf> {
+        let boost = self
+            .options
+            .new_photo_boost
+            .as_ref()
+            .filter(|_| self.boost_remaining > 0)?;
+        let now = self.now();
+        self.known
+            .iter()
+            .filter(|(path, meta)| {
+                boost.is_recent(meta.created_at, now) && !self.boosted.contains(*path)
+            })
+            // Newest first; ties go to the smaller path so seeded runs are repeatable.
+            .max_by(|(a_path, a), (b_path, b)| {
+                a.created_at
+                    .cmp(&b.created_at)
+                    .then_with(|| b_path.cmp(a_path))
+            })
+            .map(|(path, _)| path)
+    }
+
+    /// Show `path` out of turn. Its queued heap entry is retired the way a removal retires
+    /// it, and a fresh one is scheduled as after any showing.
+    fn commit_boosted(&mut self, path: Arc<PathBuf>) {
+        let Some(meta) = self.known.get_mut(path.as_ref()) else {
+            return;
+        };
+        let generation = self.generations.entry((*path).clone()).or_insert(0);
+        *generation += 1;
+        meta.generation = *generation;
+        meta.shown = true;
+        let (created_at, generation) = (meta.created_at, meta.generation);
+        debug!(path = %path.display(), "photo boosted as recent");
+        self.boosted.insert((*path).clone());
+        self.remember_shown(&path);
+        self.reschedule_after_show(path, created_at, generation);
+    }
+
     /// Drain leading tombstoned/stale entries off the heap, then return the front entry's
     /// path and priority (`!shown`) without popping or marking it shown. Returns `None` when
-    /// the heap is empty or all entries are invalid.
+    /// the heap is empty or all entries are invalid. While the new-photo boost applies, its
+    /// pick is returned instead of the front entry.
     fn peek_next(&mut self) -> Option<(Arc<PathBuf>, bool)> {
+        self.boost_pick = self.boosted_next().map(|path| Arc::new(path.clone()));
+        if let Some(path) = &self.boost_pick {
+            let priority = !self.known[path.as_ref()].shown;
+            return Some((Arc::clone(path), priority));
+        }
+        // Bounded, for when every photo due is a copy of one just shown.
+        let mut deferrals = 0;
         loop {
             let (path, generation) = match self.heap.peek() {
                 None => return None,
@@ -259,6 +806,11 @@ impl PlaylistState {
                 .known
                 .get(path.as_ref())
                 .is_some_and(|m| m.generation == generation);
+            if valid && deferrals < self.known.len() && self.too_soon(&path) {
+                self.defer_front();
+                deferrals += 1;
+                continue;
+            }
             if valid {
                 let priority = !self.known[path.as_ref()].shown;
                 return Some((path, priority));
@@ -267,9 +819,21 @@ impl PlaylistState {
         }
     }
 
+    /// When a live photo was taken; the epoch for one the playlist no longer knows.
+    fn created_at(&self, path: &Path) -> SystemTime {
+        self.known
+            .get(path)
+            .map_or(SystemTime::UNIX_EPOCH, |meta| meta.created_at)
+    }
+
     /// Pop the front entry (the one `peek_next` just returned), advance vclock, mark it
     /// shown, and reschedule it. Defensively re-validates before committing.
     fn commit_shown(&mut self) {
+        self.boost_remaining = self.boost_remaining.saturating_sub(1);
+        if let Some(path) = self.boost_pick.take() {
+            self.commit_boosted(path);
+            return;
+        }
         let entry = match self.heap.pop() {
             None => return,
             Some(e) => e,
@@ -285,17 +849,32 @@ impl PlaylistState {
             (meta.created_at, meta.generation)
         };
         self.vclock = entry.key;
+        self.remember_shown(&entry.path);
         self.reschedule_after_show(entry.path, created_at, generation);
     }
 
     /// Pop the earliest still-valid entry, advance vclock, mark shown, and reschedule.
     /// Used by `simulate_playlist` where peek+commit can be a single call.
     fn pop_next(&mut self) -> Option<(Arc<PathBuf>, bool)> {
-        while let Some(entry) = self.heap.pop() {
+        if let Some(path) = self.boosted_next() {
+            let path = Arc::new(path.clone());
+            let priority = !self.known[path.as_ref()].shown;
+            self.boost_remaining -= 1;
+            self.commit_boosted(Arc::clone(&path));
+            return Some((path, priority));
+        }
+        let mut deferrals = 0;
+        while let Some(entry) = self.heap.peek() {
             let valid = self
                 .known
                 .get(entry.path.as_ref())
                 .is_some_and(|m| m.generation == entry.generation);
+            if valid && deferrals < self.known.len() && self.too_soon(&entry.path) {
+                self.defer_front();
+                deferrals += 1;
+                continue;
+            }
+            let entry = self.heap.pop().expect("peeked above");
             if !valid {
                 continue;
             }
@@ -310,7 +889,9 @@ impl PlaylistState {
                 meta.shown = true;
                 (meta.created_at, p)
             };
+            self.remember_shown(&path);
             self.reschedule_after_show(Arc::clone(&path), created_at, entry.generation);
+            self.boost_remaining = self.boost_remaining.saturating_sub(1);
             return Some((path, priority));
         }
         None
@@ -327,11 +908,13 @@ pub fn simulate_playlist<I>(
 where
     I: IntoIterator<Item = PhotoInfo>,
 {
-    let rng = match seed {
-        Some(s) => StdRng::seed_from_u64(s),
-        None => StdRng::from_os_rng(),
-    };
-    let mut pl = PlaylistState::with_rng(options, rng, Some(now));
+    let seed = seed.unwrap_or_else(rand::random);
+    let mut pl = PlaylistState::with_seed(options, seed, Some(now));
+    // Mirror the live manager: resume from the state file and save back to it.
+    let state_file = pl.options.state_file.clone();
+    if let Some(snapshot) = state_file.as_deref().and_then(load_snapshot) {
+        pl.restore(snapshot);
+    }
     for info in photos {
         pl.record_add(info);
     }
@@ -342,5 +925,6 @@ where
             None => break,
         }
     }
+    persist_state(&mut pl, state_file.as_deref());
     plan
 }
@@ -1,8 +1,10 @@
 use crate::config::{PhotoEffectConfig, PhotoEffectOptions};
-use crate::events::PhotoLoaded;
+use crate::events::{AnimationFrame, PhotoLoaded};
+use crate::metrics::{RuntimeMetrics, SharedMetrics};
 use anyhow::Result;
 use image::RgbaImage;
 use rand::{SeedableRng, rngs::StdRng};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::select;
 use tokio::sync::mpsc::{Receiver, Sender};
 use tokio_util::sync::CancellationToken;
@@ -14,11 +16,12 @@ pub async fn run(
     to_viewer: Sender<PhotoLoaded>,
     cancel: CancellationToken,
     config: PhotoEffectConfig,
+    metrics: SharedMetrics,
 ) -> Result<()> {
     if !config.is_enabled() {
         forward_only(from_loader, to_viewer, cancel).await
     } else {
-        run_with_effects(from_loader, to_viewer, cancel, config).await
+        run_with_effects(from_loader, to_viewer, cancel, config, metrics).await
     }
 }
 
@@ -50,6 +53,7 @@ async fn run_with_effects(
     to_viewer: Sender<PhotoLoaded>,
     cancel: CancellationToken,
     config: PhotoEffectConfig,
+    metrics: SharedMetrics,
 ) -> Result<()> {
     let mut rng = StdRng::from_os_rng();
 
@@ -57,14 +61,26 @@ async fn run_with_effects(
         select! {
             _ = cancel.cancelled() => break,
             maybe_loaded = from_loader.recv() => {
-                let Some(PhotoLoaded { mut prepared, priority }) = maybe_loaded else {
+                let Some(loaded) = maybe_loaded else {
                     break;
                 };
+                let PhotoLoaded { mut prepared, priority, mut animation, caption, matting, created_at, mut prep_time } = loaded;
 
-                if let Some(option) = config.choose_option(&mut rng) {
+                let effects = config.choose_effects(&mut rng);
+                if !effects.is_empty() {
+                    let age = SystemTime::now().duration_since(created_at).unwrap_or_default();
+                    let effects: Vec<_> = effects
+                        .into_iter()
+                        .map(|option| for_photo_age(option, age))
+                        .collect();
+                    let started = Instant::now();
                     if let Some(mut image) = reconstruct_image(&mut prepared) {
-                        apply_effect(&mut image, &option);
+                        apply_effects(&mut image, &effects);
                         prepared.pixels = image.into_raw();
+                        // Every frame of an animation gets the same effects as its still.
+                        for frame in animation.iter_mut().flatten() {
+                            apply_effects_to_frame(frame, prepared.width, prepared.height, &effects);
+                        }
                     } else {
                         warn!(
                             path = %prepared.path.display(),
@@ -73,10 +89,13 @@ async fn run_with_effects(
                             "failed to reconstruct RGBA image for photo effect"
                         );
                     }
+                    let elapsed = started.elapsed();
+                    RuntimeMetrics::update(&metrics, |m| m.effect.record(elapsed));
+                    prep_time += elapsed;
                 }
 
                 if to_viewer
-                    .send(PhotoLoaded { prepared, priority })
+                    .send(PhotoLoaded { prepared, priority, animation, caption, matting, created_at, prep_time })
                     .await
                     .is_err()
                 {
@@ -89,6 +108,16 @@ async fn run_with_effects(
     Ok(())
 }
 
+/// Applies age-dependent settings, such as the print simulation's `age-ramp`.
+fn for_photo_age(option: PhotoEffectOptions, age: Duration) -> PhotoEffectOptions {
+    match option {
+        PhotoEffectOptions::PrintSimulation(settings) => {
+            PhotoEffectOptions::PrintSimulation(settings.for_age(age))
+        }
+        other => other,
+    }
+}
+
 fn reconstruct_image(prepared: &mut crate::events::PreparedImageCpu) -> Option<RgbaImage> {
     let width = prepared.width;
     let height = prepared.height;
@@ -103,11 +132,40 @@ fn reconstruct_image(prepared: &mut crate::events::PreparedImageCpu) -> Option<R
     RgbaImage::from_raw(width, height, pixels)
 }
 
+fn apply_effects_to_frame(
+    frame: &mut AnimationFrame,
+    width: u32,
+    height: u32,
+    effects: &[PhotoEffectOptions],
+) {
+    if frame.pixels.len() != width as usize * height as usize * 4 {
+        warn!("animation frame is not {width}x{height}; effect skipped");
+        return;
+    }
+    let pixels = std::mem::take(&mut frame.pixels);
+    if let Some(mut image) = RgbaImage::from_raw(width, height, pixels) {
+        apply_effects(&mut image, effects);
+        frame.pixels = image.into_raw();
+    }
+}
+
+fn apply_effects(image: &mut RgbaImage, effects: &[PhotoEffectOptions]) {
+    for option in effects {
+        apply_effect(image, option);
+    }
+}
+
 fn apply_effect(image: &mut RgbaImage, option: &PhotoEffectOptions) {
     match option {
         PhotoEffectOptions::PrintSimulation(settings) => {
             crate::processing::print_simulation::apply_print_simulation(image, settings);
         }
+        PhotoEffectOptions::FilmGrain(settings) => {
+            crate::processing::film_grain::apply_film_grain(image, settings);
+        }
+        PhotoEffectOptions::Tone(settings) => {
+            crate::processing::tone::apply_tone(image, settings);
+        }
     }
     debug!("applied photo effect {:?}", option.kind());
 }
@@ -115,9 +173,11 @@ fn apply_effect(image: &mut RgbaImage, option: &PhotoEffectOptions) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::PhotoEffectKind;
     use crate::events::PreparedImageCpu;
     use image::RgbaImage;
     use rand::{SeedableRng, rngs::StdRng};
+    use std::sync::Arc;
     use tokio::sync::mpsc;
     use tokio_util::sync::CancellationToken;
 
@@ -136,17 +196,32 @@ mod tests {
                     pixels: vec![10, 20, 30, 255],
                 },
                 priority: false,
+                animation: None,
+                caption: None,
+                matting: None,
+                created_at: SystemTime::UNIX_EPOCH,
+                prep_time: Duration::ZERO,
             })
             .await
             .unwrap();
         drop(tx_in);
 
-        run(rx_in, tx_out, cancel.clone(), PhotoEffectConfig::default())
-            .await
-            .unwrap();
+        let metrics = RuntimeMetrics::shared();
+        run(
+            rx_in,
+            tx_out,
+            cancel.clone(),
+            PhotoEffectConfig::default(),
+            Arc::clone(&metrics),
+        )
+        .await
+        .unwrap();
+        assert_eq!(metrics.lock().unwrap().effect.count(), 0);
 
         let received = rx_out.try_recv().unwrap();
-        let PhotoLoaded { prepared, priority } = received;
+        let PhotoLoaded {
+            prepared, priority, ..
+        } = received;
         assert_eq!(prepared.pixels, vec![10, 20, 30, 255]);
         assert!(!priority);
     }
@@ -182,15 +257,128 @@ active:
                     pixels: vec![10, 20, 30, 255, 200, 150, 100, 255],
                 },
                 priority: false,
+                animation: None,
+                caption: None,
+                matting: None,
+                created_at: SystemTime::UNIX_EPOCH,
+                prep_time: Duration::ZERO,
             })
             .await
             .unwrap();
         drop(tx_in);
 
-        run(rx_in, tx_out, cancel, config).await.unwrap();
+        let metrics = RuntimeMetrics::shared();
+        run(rx_in, tx_out, cancel, config, Arc::clone(&metrics))
+            .await
+            .unwrap();
+        assert_eq!(metrics.lock().unwrap().effect.count(), 1);
 
-        let PhotoLoaded { prepared, priority } = rx_out.try_recv().unwrap();
+        let PhotoLoaded {
+            prepared, priority, ..
+        } = rx_out.try_recv().unwrap();
         assert_eq!(prepared.pixels, expected_pixels);
         assert!(!priority);
     }
+
+    #[test]
+    fn print_simulation_strength_follows_photo_age() {
+        let yaml = r#"
+selection: fixed
+active:
+  - kind: print-simulation
+    relief-strength: 0.4
+    ink-spread: 0.2
+    age-ramp:
+      young-years: 5
+      old-years: 25
+      young-scale: 0.5
+      old-scale: 1.5
+"#;
+        let config: PhotoEffectConfig = serde_yaml::from_str(yaml).unwrap();
+        let option = config.choose_option(&mut StdRng::seed_from_u64(1)).unwrap();
+        option.validate().unwrap();
+        let year = Duration::from_secs_f64(365.25 * 24.0 * 3600.0);
+        let strengths = |years: f64| match for_photo_age(option.clone(), year.mul_f64(years)) {
+            PhotoEffectOptions::PrintSimulation(settings) => {
+                (settings.relief_strength, settings.ink_spread)
+            }
+            other => panic!("expected print-simulation, got {other:?}"),
+        };
+
+        let close = |(relief, ink): (f32, f32), scale: f32| {
+            (relief - 0.4 * scale).abs() < 1e-4 && (ink - 0.2 * scale).abs() < 1e-4
+        };
+        // Younger than the ramp, at its start, halfway, at its end, and beyond.
+        assert!(close(strengths(1.0), 0.5), "{:?}", strengths(1.0));
+        assert!(close(strengths(5.0), 0.5), "{:?}", strengths(5.0));
+        assert!(close(strengths(15.0), 1.0), "{:?}", strengths(15.0));
+        assert!(close(strengths(25.0), 1.5), "{:?}", strengths(25.0));
+        assert!(close(strengths(60.0), 1.5), "{:?}", strengths(60.0));
+    }
+
+    #[tokio::test]
+    async fn chain_applies_every_effect_in_order() {
+        let yaml = r#"
+selection: chain
+active:
+  - kind: tone
+    mode: sepia
+  - kind: print-simulation
+    relief-strength: 1.0
+    sheen-strength: 0.5
+"#;
+        let config: PhotoEffectConfig = serde_yaml::from_str(yaml).unwrap();
+        let effects = config.choose_effects(&mut StdRng::seed_from_u64(7));
+        let kinds: Vec<_> = effects.iter().map(PhotoEffectOptions::kind).collect();
+        assert_eq!(
+            kinds,
+            [PhotoEffectKind::Tone, PhotoEffectKind::PrintSimulation]
+        );
+
+        let source = RgbaImage::from_fn(4, 4, |x, y| {
+            image::Rgba([(x * 60) as u8, (y * 60) as u8, 200 - (x * 40) as u8, 255])
+        });
+        let after = |order: &[&PhotoEffectOptions]| {
+            let mut image = source.clone();
+            for option in order {
+                apply_effect(&mut image, option);
+            }
+            image.into_raw()
+        };
+        let tone_first = after(&[&effects[0], &effects[1]]);
+        assert_ne!(tone_first, after(&[&effects[1], &effects[0]]));
+
+        let (tx_in, rx_in) = mpsc::channel(1);
+        let (tx_out, mut rx_out) = mpsc::channel(1);
+        tx_in
+            .send(PhotoLoaded {
+                prepared: PreparedImageCpu {
+                    path: std::path::PathBuf::from("dummy"),
+                    width: 4,
+                    height: 4,
+                    pixels: source.into_raw(),
+                },
+                priority: false,
+                animation: None,
+                caption: None,
+                matting: None,
+                created_at: SystemTime::now(),
+                prep_time: Duration::ZERO,
+            })
+            .await
+            .unwrap();
+        drop(tx_in);
+
+        run(
+            rx_in,
+            tx_out,
+            CancellationToken::new(),
+            config,
+            RuntimeMetrics::shared(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(rx_out.try_recv().unwrap().prepared.pixels, tone_first);
+    }
 }
@@ -0,0 +1,261 @@
+//! Photos set aside after failing to decode again and again.
+//!
+//! A photo that fails to decode is dropped from the rotation, but a sync job
+//! that keeps restoring a truncated file would have it decoded and dropped
+//! over and over. The manager counts each failure against the file's size and
+//! modification time; after `playlist.quarantine.max-failures` the photo is
+//! ignored, even when it is added again, until the cooldown runs out or the
+//! file changes on disk. A photo that goes on screen is forgiven its earlier
+//! failures. With `playlist.state-file` set the list is saved along with the
+//! schedule. The control socket's `quarantine-list` command reads it.
+
+use crate::config::QuarantineConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub type SharedQuarantine = Arc<Mutex<Quarantine>>;
+
+/// Which version of a file failed: a resync that rewrites it changes one of these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    pub size: u64,
+    /// Modification time, milliseconds since the Unix epoch.
+    pub modified_ms: u64,
+}
+
+impl FileStamp {
+    /// `None` when the file cannot be read; such failures still count, under
+    /// a stamp no readable file will match.
+    pub fn of(path: &Path) -> Option<Self> {
+        let metadata = fs::metadata(path).ok()?;
+        let modified = metadata.modified().ok()?;
+        Some(Self {
+            size: metadata.len(),
+            modified_ms: epoch_ms(modified),
+        })
+    }
+}
+
+/// One photo's failures, as saved in the playlist state file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuarantineEntry {
+    pub path: PathBuf,
+    pub stamp: Option<FileStamp>,
+    pub failures: u32,
+    /// Milliseconds since the Unix epoch; set once `failures` reached the limit.
+    pub until_ms: Option<u64>,
+}
+
+/// A quarantined photo as the `quarantine-list` command reports it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QuarantinedPhoto {
+    pub path: PathBuf,
+    pub failures: u32,
+    /// RFC 3339, UTC, to the second.
+    pub until: String,
+}
+
+#[derive(Debug)]
+pub struct Quarantine {
+    max_failures: u32,
+    cooldown: Duration,
+    entries: HashMap<PathBuf, QuarantineEntry>,
+}
+
+impl Quarantine {
+    pub fn new(config: &QuarantineConfig) -> Self {
+        Self {
+            max_failures: config.max_failures.max(1),
+            cooldown: config.cooldown,
+            entries: HashMap::new(),
+        }
+    }
+
+    pub fn shared(config: &QuarantineConfig) -> SharedQuarantine {
+        Arc::new(Mutex::new(Self::new(config)))
+    }
+
+    /// Counts a failed decode of `path`. Returns the entry when this failure
+    /// put the photo into quarantine.
+    pub fn record_failure(&mut self, path: &Path, now: SystemTime) -> Option<&QuarantineEntry> {
+        let stamp = FileStamp::of(path);
+        let entry = self
+            .entries
+            .entry(path.to_path_buf())
+            .or_insert_with(|| QuarantineEntry {
+                path: path.to_path_buf(),
+                stamp,
+                failures: 0,
+                until_ms: None,
+            });
+        if entry.stamp != stamp {
+            // A different file now: earlier failures no longer count.
+            entry.stamp = stamp;
+            entry.failures = 0;
+            entry.until_ms = None;
+        }
+        entry.failures = entry.failures.saturating_add(1);
+        if entry.failures < self.max_failures || entry.until_ms.is_some() {
+            return None;
+        }
+        entry.until_ms = Some(epoch_ms(now + self.cooldown));
+        Some(entry)
+    }
+
+    /// Whether `path` should be left out of the rotation at `now`. An entry
+    /// whose cooldown has run out, or whose file has changed, is dropped, so
+    /// the photo gets a full set of tries again.
+    pub fn blocks(&mut self, path: &Path, now: SystemTime) -> bool {
+        let Some(entry) = self.entries.get(path) else {
+            return false;
+        };
+        let Some(until_ms) = entry.until_ms else {
+            return false;
+        };
+        if epoch_ms(now) < until_ms && FileStamp::of(path) == entry.stamp {
+            return true;
+        }
+        self.entries.remove(path);
+        false
+    }
+
+    /// Clears the failures of a photo that decoded after all.
+    pub fn forget(&mut self, path: &Path) -> bool {
+        self.entries.remove(path).is_some()
+    }
+
+    /// Photos quarantined at `now`, soonest release first.
+    pub fn list(&self, now: SystemTime) -> Vec<QuarantinedPhoto> {
+        let now_ms = epoch_ms(now);
+        let mut active: Vec<&QuarantineEntry> = self
+            .entries
+            .values()
+            .filter(|entry| entry.until_ms.is_some_and(|until| until > now_ms))
+            .collect();
+        active.sort_by_key(|entry| (entry.until_ms, entry.path.clone()));
+        active
+            .into_iter()
+            .map(|entry| QuarantinedPhoto {
+                path: entry.path.clone(),
+                failures: entry.failures,
+                until: humantime::format_rfc3339_seconds(
+                    UNIX_EPOCH + Duration::from_millis(entry.until_ms.unwrap_or_default()),
+                )
+                .to_string(),
+            })
+            .collect()
+    }
+
+    /// Entries still in force at `now`, including photos that have failed
+    /// fewer than `max-failures` times, for the playlist state file.
+    pub fn entries(&self, now: SystemTime) -> Vec<QuarantineEntry> {
+        let now_ms = epoch_ms(now);
+        let mut entries: Vec<QuarantineEntry> = self
+            .entries
+            .values()
+            .filter(|entry| entry.until_ms.is_none_or(|until| until > now_ms))
+            .cloned()
+            .collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        entries
+    }
+
+    pub fn restore(&mut self, entries: Vec<QuarantineEntry>) {
+        self.entries = entries
+            .into_iter()
+            .map(|entry| (entry.path.clone(), entry))
+            .collect();
+    }
+}
+
+pub(crate) fn epoch_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis()
+        .try_into()
+        .unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quarantine() -> Quarantine {
+        Quarantine::new(&QuarantineConfig {
+            max_failures: 3,
+            cooldown: Duration::from_secs(3600),
+        })
+    }
+
+    #[test]
+    fn third_failure_quarantines_until_the_cooldown_ends() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("broken.jpg");
+        fs::write(&path, b"not a jpeg").unwrap();
+        let now = SystemTime::now();
+        let mut quarantine = quarantine();
+
+        assert!(quarantine.record_failure(&path, now).is_none());
+        assert!(quarantine.record_failure(&path, now).is_none());
+        assert!(!quarantine.blocks(&path, now));
+        let entry = quarantine.record_failure(&path, now).expect("quarantined");
+        assert_eq!(entry.failures, 3);
+
+        assert!(quarantine.blocks(&path, now + Duration::from_secs(60)));
+        let listed = quarantine.list(now);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].path, path);
+        assert_eq!(listed[0].failures, 3);
+
+        assert!(!quarantine.blocks(&path, now + Duration::from_secs(3601)));
+        assert!(
+            quarantine.list(now).is_empty(),
+            "expired entries are dropped"
+        );
+    }
+
+    #[test]
+    fn a_changed_file_or_a_successful_decode_starts_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("resynced.jpg");
+        fs::write(&path, b"truncated").unwrap();
+        let now = SystemTime::now();
+        let mut quarantine = quarantine();
+        for _ in 0..3 {
+            quarantine.record_failure(&path, now);
+        }
+        assert!(quarantine.blocks(&path, now));
+
+        fs::write(&path, b"the whole file, longer this time").unwrap();
+        assert!(!quarantine.blocks(&path, now), "a new size is a new file");
+        assert!(quarantine.record_failure(&path, now).is_none());
+
+        assert!(quarantine.forget(&path));
+        assert!(quarantine.entries(now).is_empty());
+    }
+
+    #[test]
+    fn entries_round_trip_through_restore() {
+        let now = SystemTime::now();
+        let mut quarantine = quarantine();
+        let missing = Path::new("/nonexistent/photo.jpg");
+        for _ in 0..3 {
+            quarantine.record_failure(missing, now);
+        }
+        let saved = serde_json::to_string(&quarantine.entries(now)).unwrap();
+        let later = now + Duration::from_secs(7200);
+        assert!(
+            quarantine.entries(later).is_empty(),
+            "expired entries are not saved"
+        );
+
+        let mut restored = self::quarantine();
+        restored.restore(serde_json::from_str(&saved).unwrap());
+        assert!(restored.blocks(missing, now));
+        assert_eq!(restored.list(now), quarantine.list(now));
+    }
+}
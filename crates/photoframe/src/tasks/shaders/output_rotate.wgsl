@@ -0,0 +1,46 @@
+// Copies the upright frame onto a panel mounted sideways or upside down.
+// Each row maps the panel's texture coordinate (u, v, 1) to the coordinate
+// in the frame that lands there; see DisplayRotation::uv_transform.
+struct RotateUniforms {
+  row_u: vec4<f32>,
+  row_v: vec4<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> R: RotateUniforms;
+
+@group(1) @binding(0)
+var frame_tex: texture_2d<f32>;
+@group(1) @binding(1)
+var frame_samp: sampler;
+
+struct VSOut {
+  @builtin(position) pos: vec4<f32>,
+  @location(0) uv: vec2<f32>,
+};
+
+@vertex
+fn vs_main(@builtin(vertex_index) vid: u32) -> VSOut {
+  // Fullscreen triangle (covers entire target)
+  var positions = array<vec2<f32>, 3>(
+    vec2<f32>(-1.0, -3.0),
+    vec2<f32>(-1.0, 1.0),
+    vec2<f32>(3.0, 1.0),
+  );
+  var uvs = array<vec2<f32>, 3>(
+    vec2<f32>(0.0, 2.0),
+    vec2<f32>(0.0, 0.0),
+    vec2<f32>(2.0, 0.0),
+  );
+  var out: VSOut;
+  out.pos = vec4<f32>(positions[vid], 0.0, 1.0);
+  out.uv = uvs[vid];
+  return out;
+}
+
+@fragment
+fn fs_main(in: VSOut) -> @location(0) vec4<f32> {
+  let p = vec3<f32>(in.uv, 1.0);
+  let uv = vec2<f32>(dot(R.row_u.xyz, p), dot(R.row_v.xyz, p));
+  return vec4<f32>(textureSample(frame_tex, frame_samp, uv).rgb, 1.0);
+}
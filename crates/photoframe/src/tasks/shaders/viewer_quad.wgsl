@@ -4,12 +4,21 @@ struct TransitionUniforms {
   kind: u32,
   current_dest: vec4<f32>,
   next_dest: vec4<f32>,
+  // Ken Burns on a matted photo: the photo's area in its texture and the
+  // part of it sampled there, both in UV. A zero window samples the whole
+  // texture untouched.
+  current_photo: vec4<f32>,
+  current_view: vec4<f32>,
+  next_photo: vec4<f32>,
+  next_view: vec4<f32>,
   params0: vec4<f32>,
   params1: vec4<f32>,
   params3: vec4<f32>,
   // Viewer background (linear RGB, alpha unused); letterbox regions are
   // composited over this in-shader so the pipeline can render opaquely.
   background: vec4<f32>,
+  // Iris petal outline: linear RGB and width in screen pixels.
+  iris_stroke: vec4<f32>,
   // Per-petal constants for the iris transition, solved on the CPU each
   // frame (see the Iris arm in viewer.rs):
   // petals_a[i] = (annulus_center.xy, tip_dir.xy)
@@ -61,23 +70,71 @@ fn vs_main(@builtin(vertex_index) vid: u32) -> VSOut {
   return out;
 }
 
+// Where a plane is drawn on screen and, for a matted photo, the window its
+// photo area samples.
+struct Plane {
+  dest: vec4<f32>,
+  photo: vec4<f32>,
+  view: vec4<f32>,
+};
+
+fn current_plane() -> Plane {
+  return Plane(U.current_dest, U.current_photo, U.current_view);
+}
+
+fn next_plane() -> Plane {
+  return Plane(U.next_dest, U.next_photo, U.next_view);
+}
+
 fn sample_plane(
   tex: texture_2d<f32>,
   samp: sampler,
-  dest: vec4<f32>,
+  plane: Plane,
   sample_pos: vec2<f32>,
 ) -> vec4<f32> {
+  let dest = plane.dest;
   if (dest.z <= 0.0 || dest.w <= 0.0) {
     return vec4<f32>(0.0, 0.0, 0.0, 0.0);
   }
-  let uv = (sample_pos - dest.xy) / dest.zw;
+  var uv = (sample_pos - dest.xy) / dest.zw;
   if (uv.x < 0.0 || uv.x > 1.0 || uv.y < 0.0 || uv.y > 1.0) {
     return vec4<f32>(0.0, 0.0, 0.0, 0.0);
   }
+  // Inside a matted photo's area, sample its Ken Burns window instead so the
+  // photo moves while the mat around it stays put.
+  let photo = plane.photo;
+  let inside = uv >= photo.xy && uv <= photo.xy + photo.zw;
+  if (plane.view.z > 0.0 && all(inside)) {
+    uv = plane.view.xy + (uv - photo.xy) / photo.zw * plane.view.zw;
+  }
   let c = textureSample(tex, samp, uv);
   return vec4<f32>(c.rgb, 1.0);
 }
 
+// Disc blur of a plane around `pos`: the centre tap plus two rings of eight
+// taps at half and full `radius`. Cheap enough for every transition frame,
+// and at the radii used the ring pattern is lost in the blur.
+fn blurred_plane(
+  tex: texture_2d<f32>,
+  samp: sampler,
+  plane: Plane,
+  pos: vec2<f32>,
+  radius: f32,
+) -> vec4<f32> {
+  var sum = sample_plane(tex, samp, plane, pos) * 2.0;
+  var weight = 2.0;
+  if (radius > 0.5) {
+    for (var i = 0; i < 8; i = i + 1) {
+      let angle = f32(i) * 0.78539816;
+      let dir = vec2<f32>(cos(angle), sin(angle));
+      sum = sum + sample_plane(tex, samp, plane, pos + dir * radius * 0.5) * 1.5;
+      sum = sum + sample_plane(tex, samp, plane, pos + dir * radius);
+      weight = weight + 2.5;
+    }
+  }
+  return sum / weight;
+}
+
 // Signed distance to iris petal `i` using the CPU-precomputed constants in
 // U.petals_a / U.petals_b. Returns (distance, radius about the petal's
 // annulus center). On-screen the only petal boundaries are the inner arc and
@@ -111,7 +168,9 @@ fn iris_petal(i: i32, p: vec2<f32>, r_mid: f32, w: f32) -> vec2<f32> {
 // stays at least one layer texel wide.
 // params0 = (blades, petal_sheen, overlap_shadow, photo_swap_mix)
 // params1 = (open_radius_px, color.r, color.g, color.b)
-// params3 = (layer_scale, unused, unused, unused)
+// params3 = (layer_scale, direction, unused, unused)
+// direction 0 draws shaded petals; 1 (open) and 2 (close) use the petals as
+// a mask, writing (outline coverage, 0, 0, petal coverage) instead.
 @fragment
 fn fs_iris_layer(in: VSOut) -> @location(0) vec4<f32> {
   let screen_pos = in.screen_uv * U.screen_size;
@@ -179,8 +238,20 @@ fn fs_iris_layer(in: VSOut) -> @location(0) vec4<f32> {
     + 0.22 * (1.0 - smoothstep(0.0, shadow_w * 0.6, dn2));
   // Thin edge highlight, also under petal_sheen so 0 is perfectly flat.
   let rim = smoothstep(3.5 * aa, 0.5 * aa, abs(d_min)) * contrast * 0.2;
+  // Outline along the edge of the petal run and the seam where the next
+  // petal overlaps the top one.
+  let stroke_w = U.iris_stroke.w;
+  var stroke = 0.0;
+  if (stroke_w > 0.0) {
+    stroke = 1.0 - smoothstep(stroke_w - aa, stroke_w + aa, min(-d_min, dn1));
+  }
+  if (U.params3.y > 0.5) {
+    return vec4<f32>(stroke, 0.0, 0.0, cov);
+  }
   let blade_rgb = clamp(U.params1.yzw, vec3<f32>(0.0), vec3<f32>(1.0));
-  let blade_col = blade_rgb * tone * (1.0 - shadow_amt * occ) + vec3<f32>(rim);
+  let stroke_rgb = clamp(U.iris_stroke.rgb, vec3<f32>(0.0), vec3<f32>(1.0));
+  let shaded = blade_rgb * tone * (1.0 - shadow_amt * occ) + vec3<f32>(rim);
+  let blade_col = mix(shaded, stroke_rgb, stroke);
   return vec4<f32>(blade_col * cov, cov);
 }
 
@@ -196,8 +267,8 @@ fn fs_blit(in: VSOut) -> @location(0) vec4<f32> {
 @fragment
 fn fs_main(in: VSOut) -> @location(0) vec4<f32> {
   let screen_pos = in.screen_uv * U.screen_size;
-  var current = sample_plane(cur_tex, cur_samp, U.current_dest, screen_pos);
-  var next = sample_plane(next_tex, next_samp, U.next_dest, screen_pos);
+  var current = sample_plane(cur_tex, cur_samp, current_plane(), screen_pos);
+  var next = sample_plane(next_tex, next_samp, next_plane(), screen_pos);
   var color = current;
   let progress = clamp(U.progress, 0.0, 1.0);
 switch (U.kind) {
@@ -238,8 +309,8 @@ switch (U.kind) {
       let translation = U.params0.xy;
       let cur_pos = screen_pos - translation * progress;
       let next_pos = screen_pos + translation * (1.0 - progress);
-      current = sample_plane(cur_tex, cur_samp, U.current_dest, cur_pos);
-      next = sample_plane(next_tex, next_samp, U.next_dest, next_pos);
+      current = sample_plane(cur_tex, cur_samp, current_plane(), cur_pos);
+      next = sample_plane(next_tex, next_samp, next_plane(), next_pos);
       let mask = step(0.5, next.a);
       color = current * (1.0 - mask) + next * mask;
     }
@@ -337,8 +408,16 @@ switch (U.kind) {
       let next_scale = select(1.0, 1.0 + bump, next_in);
       let cur_pos = center + (screen_pos - center) / max(cur_scale, 1e-3);
       let next_pos = center + (screen_pos - center) / max(next_scale, 1e-3);
-      let c = sample_plane(cur_tex, cur_samp, U.current_dest, cur_pos);
-      let nxt = sample_plane(next_tex, next_samp, U.next_dest, next_pos);
+      let c = sample_plane(cur_tex, cur_samp, current_plane(), cur_pos);
+      let nxt = sample_plane(next_tex, next_samp, next_plane(), next_pos);
+      color = mix(c, nxt, progress);
+    }
+    case 12u: {
+      // crossfade-blur: fade while both photos blur, peaking at the midpoint.
+      // params0.x = blur radius at the midpoint (screen px)
+      let radius = U.params0.x * sin(3.14159265 * progress);
+      let c = blurred_plane(cur_tex, cur_samp, current_plane(), screen_pos, radius);
+      let nxt = blurred_plane(next_tex, next_samp, next_plane(), screen_pos, radius);
       color = mix(c, nxt, progress);
     }
     case 11u: {
@@ -346,10 +425,24 @@ switch (U.kind) {
       // were rendered into the reduced-resolution petal layer by
       // fs_iris_layer this frame; here we just composite it (premultiplied
       // alpha) over the crossfading photos.
-      // params0.w = photo_swap_mix
-      let photo = mix(current, next, clamp(U.params0.w, 0.0, 1.0));
+      // params0.w = photo_swap_mix, params3.y = direction
       let petal = textureSample(petal_tex, petal_samp, in.screen_uv);
-      color = vec4<f32>(photo.rgb * (1.0 - petal.a) + petal.rgb, max(photo.a, petal.a));
+      if (U.params3.y > 0.5) {
+        // One-way iris: the aperture frames one photo and the petals carry
+        // the other; petal.r is the outline coverage.
+        var inner = next;
+        var outer = current;
+        if (U.params3.y > 1.5) {
+          inner = current;
+          outer = next;
+        }
+        let photo = mix(inner, outer, petal.a);
+        let stroke_rgb = clamp(U.iris_stroke.rgb, vec3<f32>(0.0), vec3<f32>(1.0));
+        color = vec4<f32>(mix(photo.rgb, stroke_rgb, petal.r), photo.a);
+      } else {
+        let photo = mix(current, next, clamp(U.params0.w, 0.0, 1.0));
+        color = vec4<f32>(photo.rgb * (1.0 - petal.a) + petal.rgb, max(photo.a, petal.a));
+      }
     }
     case 6u: {
       // Debug: stroke a single quadratic Bezier over the current image
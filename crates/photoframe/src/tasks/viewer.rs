@@ -1,29 +1,49 @@
+mod animation;
+mod debug_overlay;
 mod image_ops;
+mod motion;
+mod preload;
 pub mod scenes;
 
+use self::debug_overlay::DebugOverlay;
 use self::image_ops::*;
-use self::scenes::{GreetingScene, Scene, SceneContext, SleepScene};
+use self::preload::{Orientation, PrepCosts};
+use self::scenes::{
+    EmptyScene, GreetingScene, Scene, SceneContext, SleepFade, SleepFadeDirection, SleepFadeFrame,
+    SleepScene,
+};
 
 use crate::config::{
-    MattingConfig, MattingMode, MattingOptions, SelectedTransition, TransitionConfig,
-    TransitionKind, TransitionMode,
+    AwakeScheduleConfig, CaptionCorner, DisplayMode, DisplayRotation, IrisDirection,
+    IrisTransition, LibraryState, MattingConfig, MattingMode, MattingOptions, MessageContext,
+    PresentMode, SelectedTransition, SleepTransitionStyle, TransitionConfig, TransitionKind,
+    TransitionMode,
 };
 use crate::events::{
-    Displayed, PhotoLoaded, PreparedImageCpu, ViewerCommand, ViewerState as ControlViewerState,
+    AnimationFrame, Displayed, PhotoLoaded, PreparedImageCpu, ReloadedConfig, SharedViewerStatus,
+    ViewerCommand, ViewerState as ControlViewerState, ViewerStatus,
 };
+use crate::metrics::{RuntimeMetrics, SharedMetrics};
 use crate::processing::blur::apply_blur;
-use crate::processing::color::average_color;
+use crate::processing::color::PhotoColors;
 use crate::processing::layout::center_offset;
 use crate::tasks::greeting_screen::GreetingScreen;
-use crossbeam_channel::{Receiver as CbReceiver, Sender as CbSender, TrySendError, bounded};
+use crossbeam_channel::{
+    Receiver as CbReceiver, Sender as CbSender, TryRecvError, TrySendError, bounded,
+};
 use futures::executor::block_on;
 use image::{Rgba, RgbaImage, imageops};
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::borrow::Cow;
+use std::cell::Cell;
 use std::collections::VecDeque;
-use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::sync::watch;
 use tokio::time::{MissedTickBehavior, interval};
 use tokio_util::sync::CancellationToken;
 use tracing::{Level, debug, info, warn};
@@ -117,14 +137,51 @@ pub(super) enum ActiveTransition {
         current_zooms_in: bool,
         next_zooms_in: bool,
     },
-    Iris {
-        blades: u32,
-        color: [f32; 3],
-        petal_sheen: f32,
-        overlap_shadow: f32,
-        min_aperture: f32,
-        swirl: f32,
+    CrossfadeBlur {
+        strength: f32,
     },
+    Iris(IrisLook),
+}
+
+/// Iris settings in the form the renderer uses; shared by the `iris`
+/// transition and the iris `sleep-screen.transition-style`.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct IrisLook {
+    blades: u32,
+    /// Linear RGB.
+    color: [f32; 3],
+    petal_sheen: f32,
+    overlap_shadow: f32,
+    min_aperture: f32,
+    swirl: f32,
+    stroke_width: f32,
+    /// Linear RGB.
+    stroke_color: [f32; 3],
+    direction: IrisDirection,
+}
+
+impl From<&IrisTransition> for IrisLook {
+    fn from(cfg: &IrisTransition) -> Self {
+        Self {
+            blades: cfg.blades,
+            // Config colors are sRGB; the shader works in linear light
+            // (its output is re-encoded by the sRGB render target).
+            // Without this conversion the petals display ~2.5x lighter
+            // than the configured color.
+            color: cfg
+                .color
+                .map(|channel| srgb_to_linear((channel as f32 / 255.0).clamp(0.0, 1.0))),
+            petal_sheen: cfg.petal_sheen,
+            overlap_shadow: cfg.overlap_shadow,
+            min_aperture: cfg.min_aperture,
+            swirl: cfg.swirl,
+            stroke_width: cfg.stroke_width,
+            stroke_color: cfg
+                .stroke_color
+                .map(|channel| srgb_to_linear((channel as f32 / 255.0).clamp(0.0, 1.0))),
+            direction: cfg.direction,
+        }
+    }
 }
 
 pub(super) struct TexturePlane {
@@ -138,6 +195,58 @@ pub(super) struct ImgTex {
     pub(super) path: std::path::PathBuf,
     /// Mat kind that was baked for this image; `None` means full-bleed (no mat).
     pub(super) mat_kind: Option<crate::config::MattingKind>,
+    /// Where the photo sits in the texture; all of it for a full-bleed photo.
+    photo_rect: animation::PhotoRect,
+    /// Frame playback for animated GIFs; `None` for stills.
+    pub(super) animation: Option<animation::CanvasAnimation>,
+    /// Caption the loader prepared from the photo's metadata.
+    pub(super) caption: Option<String>,
+    /// The canvas the texture was uploaded from, kept so the photo can be
+    /// uploaded again if the GPU device is lost. Dropped once the photo moves
+    /// into the step-back history, which a lost device clears anyway.
+    canvas: Option<ImagePlane>,
+    /// The photo as it was before matting, kept so a rotated or resized
+    /// screen can lay it out again. `None` for animated photos, and dropped
+    /// with `canvas` once the photo moves into the history.
+    source: Option<Arc<MatSource>>,
+}
+
+impl ImgTex {
+    /// Destination rect, photo area, and sampling window (the last two in
+    /// texture UV) for drawing this photo on a `layout_w`x`layout_h` screen
+    /// under `framing`. A full-bleed photo moves as a whole and samples
+    /// untouched, signalled by a zero window; a matted one stays in place and
+    /// only the photo moves, inside its own area.
+    fn framed_uniforms(
+        &self,
+        layout_w: u32,
+        layout_h: u32,
+        framing: motion::Framing,
+    ) -> ([f32; 4], [f32; 4], [f32; 4]) {
+        let (w, h) = (self.plane.w, self.plane.h);
+        let cover = compute_cover_rect(w, h, layout_w, layout_h);
+        if self.photo_rect == (0, 0, w, h) {
+            let rect = motion::framed_rect(cover, layout_w as f32, layout_h as f32, framing);
+            return (rect_to_uniform(rect), [0.0; 4], [0.0; 4]);
+        }
+        let (x, y, pw, ph) = self.photo_rect;
+        let (w, h) = (w.max(1) as f32, h.max(1) as f32);
+        let photo = (x as f32 / w, y as f32 / h, pw as f32 / w, ph as f32 / h);
+        let view = motion::framed_view(photo, framing);
+        (
+            rect_to_uniform(cover),
+            rect_to_uniform(photo),
+            rect_to_uniform(view),
+        )
+    }
+}
+
+/// What matting started from, enough to mat the photo again for a new
+/// screen shape.
+struct MatSource {
+    image: PreparedImageCpu,
+    caption: Option<String>,
+    sidecar_matting: Option<MattingConfig>,
 }
 
 pub(super) struct TransitionState {
@@ -170,8 +279,7 @@ impl TransitionState {
                 }
             }
             TransitionMode::Push(cfg) => {
-                let angle = jittered_angle(&cfg.angles, rng);
-                let direction = angle_to_unit_vec(angle);
+                let direction = angle_to_unit_vec(cfg.pick_angle_deg(rng));
                 ActiveTransition::Push { direction }
             }
             TransitionMode::EInk(cfg) => ActiveTransition::EInk {
@@ -202,20 +310,10 @@ impl TransitionState {
                 current_zooms_in: cfg.current_zooms_in,
                 next_zooms_in: cfg.next_zooms_in,
             },
-            TransitionMode::Iris(cfg) => ActiveTransition::Iris {
-                blades: cfg.blades,
-                // Config colors are sRGB; the shader works in linear light
-                // (its output is re-encoded by the sRGB render target).
-                // Without this conversion the petals display ~2.5x lighter
-                // than the configured color.
-                color: cfg
-                    .color
-                    .map(|channel| srgb_to_linear((channel as f32 / 255.0).clamp(0.0, 1.0))),
-                petal_sheen: cfg.petal_sheen,
-                overlap_shadow: cfg.overlap_shadow,
-                min_aperture: cfg.min_aperture,
-                swirl: cfg.swirl,
+            TransitionMode::CrossfadeBlur(cfg) => ActiveTransition::CrossfadeBlur {
+                strength: cfg.strength,
             },
+            TransitionMode::Iris(cfg) => ActiveTransition::Iris(IrisLook::from(&cfg)),
         };
 
         Self {
@@ -231,7 +329,12 @@ impl TransitionState {
     }
 
     pub(super) fn progress(&self) -> f32 {
-        let elapsed = self.started_at.elapsed().as_secs_f32();
+        self.progress_at(std::time::Instant::now())
+    }
+
+    /// How far through its duration the transition is at `now`, from 0 to 1.
+    fn progress_at(&self, now: std::time::Instant) -> f32 {
+        let elapsed = now.saturating_duration_since(self.started_at).as_secs_f32();
         let duration = self.duration.as_secs_f32().max(f32::EPSILON);
         (elapsed / duration).clamp(0.0, 1.0)
     }
@@ -245,6 +348,52 @@ impl TransitionState {
     }
 }
 
+/// Holds redraw requests to `frame-pacing.max-fps`. A request that comes too
+/// soon after the last present is remembered, and the first control tick past
+/// the interval lets it through, so no redraw is lost, only delayed.
+#[derive(Debug, Default)]
+struct FramePacer {
+    min_interval: Duration,
+    last_present: Cell<Option<Instant>>,
+    deferred: Cell<bool>,
+}
+
+impl FramePacer {
+    fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            ..Self::default()
+        }
+    }
+
+    /// Whether a redraw asked for at `now` may go ahead; if not, it is
+    /// deferred to `take_due`.
+    fn admit(&self, now: Instant) -> bool {
+        let due = self.is_due(now);
+        self.deferred.set(!due);
+        due
+    }
+
+    /// Whether a deferred redraw has become due at `now`; clears it if so.
+    fn take_due(&self, now: Instant) -> bool {
+        if self.deferred.get() && self.is_due(now) {
+            self.deferred.set(false);
+            return true;
+        }
+        false
+    }
+
+    fn presented(&self, now: Instant) {
+        self.last_present.set(Some(now));
+    }
+
+    fn is_due(&self, now: Instant) -> bool {
+        self.last_present
+            .get()
+            .is_none_or(|last| now.saturating_duration_since(last) >= self.min_interval)
+    }
+}
+
 /// Frame cadence accumulator for one transition playback, logged when the
 /// transition ends so on-device performance is visible in the journal.
 pub(super) struct TransitionFrameStats {
@@ -268,10 +417,23 @@ impl TransitionFrameStats {
         }
     }
 
-    fn log(&self) {
+    /// Frames per second so far; `None` until a second frame.
+    fn fps(&self) -> Option<f32> {
+        let span = self.last_frame.duration_since(self.started).as_secs_f32();
+        (self.frames >= 2 && span > 0.0).then_some((self.frames - 1) as f32 / span)
+    }
+
+    /// Logs the summary and adds the playback time to `metrics.transition`.
+    fn finish(self, metrics: &SharedMetrics) {
         if self.frames < 2 {
             return;
         }
+        let elapsed = self.last_frame.duration_since(self.started);
+        RuntimeMetrics::update(metrics, |m| m.transition.record(elapsed));
+        self.log();
+    }
+
+    fn log(&self) {
         let span = self
             .last_frame
             .duration_since(self.started)
@@ -326,9 +488,18 @@ struct MatParams {
 
 struct MatTask {
     image: PreparedImageCpu,
+    animation: Option<Vec<AnimationFrame>>,
     params: MatParams,
     priority: bool,
     mat_kind: Option<crate::config::MattingKind>,
+    caption: Option<String>,
+    /// The photo's sidecar mat, kept so a task the pipeline turns away can be
+    /// queued again without losing it.
+    sidecar_matting: Option<MattingConfig>,
+    /// Decode and effect time so far; matting time is added by the worker.
+    prep_time: Duration,
+    /// A new layout for the photo on screen rather than one to stage.
+    replaces_current: bool,
 }
 
 struct ImagePlane {
@@ -340,42 +511,97 @@ struct ImagePlane {
 struct MatResult {
     path: std::path::PathBuf,
     canvas: ImagePlane,
+    /// Where the photo landed in `canvas`.
+    photo_rect: animation::PhotoRect,
     priority: bool,
     mat_kind: Option<crate::config::MattingKind>,
+    animation: Option<animation::AnimationPatches>,
+    caption: Option<String>,
+    source: Option<Arc<MatSource>>,
+    replaces_current: bool,
 }
 
 struct QueuedImage {
     image: PreparedImageCpu,
+    animation: Option<Vec<AnimationFrame>>,
     priority: bool,
+    caption: Option<String>,
+    /// Mat override from the photo's sidecar file.
+    sidecar_matting: Option<MattingConfig>,
+    prep_time: Duration,
+    replaces_current: bool,
+}
+
+impl QueuedImage {
+    /// Queues a photo to be matted again from its retained source.
+    fn relayout(source: &MatSource, replaces_current: bool) -> Self {
+        Self {
+            image: source.image.clone(),
+            animation: None,
+            priority: false,
+            caption: source.caption.clone(),
+            sidecar_matting: source.sidecar_matting.clone(),
+            prep_time: Duration::ZERO,
+            replaces_current,
+        }
+    }
 }
 
 struct MattingPipeline {
     task_tx: CbSender<MatTask>,
     result_rx: CbReceiver<MatResult>,
+    /// Preparation times reported by the workers.
+    costs: Arc<Mutex<PrepCosts>>,
 }
 
 impl MattingPipeline {
-    fn new(worker_count: usize, capacity: usize) -> Self {
+    fn new(worker_count: usize, capacity: usize, metrics: SharedMetrics) -> Self {
         let worker_count = worker_count.max(1);
         let capacity = capacity.max(worker_count).max(2);
         let (task_tx, task_rx) = bounded::<MatTask>(capacity);
         let (result_tx, result_rx) = bounded::<MatResult>(capacity);
         let task_rx = Arc::new(task_rx);
         let result_tx = Arc::new(result_tx);
+        let costs = Arc::new(Mutex::new(PrepCosts::default()));
         for _ in 0..worker_count {
             let task_rx = Arc::clone(&task_rx);
             let result_tx = Arc::clone(&result_tx);
+            let costs = Arc::clone(&costs);
+            let metrics = Arc::clone(&metrics);
             std::thread::spawn(move || {
                 while let Ok(task) = task_rx.recv() {
-                    if let Some(result) = process_mat_task(task)
-                        && result_tx.send(result).is_err()
-                    {
+                    let orientation = Orientation::of(task.image.width, task.image.height);
+                    let started = Instant::now();
+                    let upstream = task.prep_time;
+                    let Some(result) = process_mat_task(task) else {
+                        continue;
+                    };
+                    let elapsed = started.elapsed();
+                    RuntimeMetrics::update(&metrics, |m| m.matting.record(elapsed));
+                    costs
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .record(orientation, upstream + elapsed);
+                    if result_tx.send(result).is_err() {
                         break;
                     }
                 }
             });
         }
-        Self { task_tx, result_rx }
+        Self {
+            task_tx,
+            result_rx,
+            costs,
+        }
+    }
+
+    /// Photos to keep prepared or in preparation, from `base` deepened for
+    /// photos that take longer than a dwell to prepare.
+    fn preload_depth(&self, base: usize, dwell: Duration) -> usize {
+        self.costs
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .update_depth(base, dwell)
     }
 
     // MatTask is large by design (contains image buffers); returning it on error
@@ -401,10 +627,16 @@ struct SurfaceState {
 }
 
 impl SurfaceState {
-    fn from_config(config: &wgpu::SurfaceConfiguration, limits: &wgpu::Limits) -> Self {
+    /// The surface as the frame lays it out, after `display.rotation`.
+    fn from_config(
+        config: &wgpu::SurfaceConfiguration,
+        limits: &wgpu::Limits,
+        rotation: DisplayRotation,
+    ) -> Self {
+        let (width, height) = rotation.logical_size(config.width, config.height);
         Self {
-            width: config.width,
-            height: config.height,
+            width,
+            height,
             max_texture_dimension: limits.max_texture_dimension_2d,
         }
     }
@@ -419,6 +651,15 @@ impl SurfaceState {
     }
 }
 
+/// A window size as the frame lays it out, after `display.rotation`.
+fn rotated_size(
+    rotation: DisplayRotation,
+    size: winit::dpi::PhysicalSize<u32>,
+) -> winit::dpi::PhysicalSize<u32> {
+    let (width, height) = rotation.logical_size(size.width, size.height);
+    winit::dpi::PhysicalSize::new(width, height)
+}
+
 #[derive(Clone, Copy, Debug)]
 enum SurfaceReport {
     InitialConfig,
@@ -515,6 +756,9 @@ struct MattingBridge<'a> {
     oversample: f32,
     max_upscale_factor: f32,
     mat_pipeline: &'a MattingPipeline,
+    /// Drives `random` mat selection and `fill-when-fits`; seeded by
+    /// `--matting-seed` so runs can be reproduced.
+    rng: &'a mut StdRng,
 }
 
 fn surface_state_for_queue(
@@ -537,14 +781,30 @@ impl<'a> MattingBridge<'a> {
         if wake.transition_state().is_some() {
             return;
         }
-        while wake.pending().len() + *self.mat_inflight < self.preload_count {
+        let depth = self
+            .mat_pipeline
+            .preload_depth(self.preload_count, wake.dwell());
+        while wake.pending().len() + *self.mat_inflight < depth {
             let next_img = if let Some(img) = self.deferred_images.pop_front() {
                 Some(img)
             } else {
                 match self.from_loader.try_recv() {
-                    Ok(PhotoLoaded { prepared, priority }) => Some(QueuedImage {
+                    Ok(PhotoLoaded {
+                        prepared,
+                        priority,
+                        animation,
+                        caption,
+                        matting,
+                        prep_time,
+                        ..
+                    }) => Some(QueuedImage {
                         image: prepared,
+                        animation,
                         priority,
+                        caption,
+                        sidecar_matting: matting,
+                        prep_time,
+                        replaces_current: false,
                     }),
                     Err(_) => None,
                 }
@@ -576,31 +836,35 @@ impl<'a> MattingBridge<'a> {
                 self.deferred_images.push_front(queued);
                 break;
             }
-            let mut rng = rand::rng();
-            let fill_screen = self
-                .matting
-                .fill_when_fits()
-                .map(|fill| {
-                    fill.should_fill(
-                        queued.image.width,
-                        queued.image.height,
-                        surface.width.max(1),
-                        surface.height.max(1),
-                        self.max_upscale_factor,
-                        &mut rng,
-                    )
-                })
-                .unwrap_or(false);
+            let rng = &mut *self.rng;
+            // A photo with its own mat is never filled edge-to-edge.
+            let fill_screen = queued.sidecar_matting.is_none()
+                && self
+                    .matting
+                    .fill_when_fits()
+                    .map(|fill| {
+                        fill.should_fill(
+                            queued.image.width,
+                            queued.image.height,
+                            surface.width.max(1),
+                            surface.height.max(1),
+                            self.max_upscale_factor,
+                            rng,
+                        )
+                    })
+                    .unwrap_or(false);
             // When filling the screen, the selected mat is ignored. Use a
             // non-mutating placeholder so the sequential selector is not
             // advanced for a photo that renders without a mat.
-            let matting = if fill_screen {
+            let matting = if let Some(own) = &queued.sidecar_matting {
+                own.select_active(rng).option.clone()
+            } else if fill_screen {
                 self.matting
                     .primary_option()
                     .cloned()
                     .expect("validated matting configuration has at least one option")
             } else {
-                self.matting.select_active(&mut rng).option.clone()
+                self.matting.select_active(rng).option.clone()
             };
             let params = MatParams {
                 screen_w: surface.width.max(1),
@@ -618,23 +882,47 @@ impl<'a> MattingBridge<'a> {
             };
             let QueuedImage {
                 image: img,
+                animation,
                 priority,
+                caption,
+                sidecar_matting,
+                prep_time,
+                replaces_current,
             } = queued;
             let task = MatTask {
                 image: img,
+                animation,
                 params,
                 priority,
                 mat_kind,
+                caption,
+                sidecar_matting,
+                prep_time,
+                replaces_current,
             };
             match self.mat_pipeline.try_submit(task) {
                 Ok(()) => {
                     *self.mat_inflight += 1;
                 }
                 Err(MatTask {
-                    image, priority, ..
+                    image,
+                    animation,
+                    priority,
+                    caption,
+                    sidecar_matting,
+                    prep_time,
+                    replaces_current,
+                    ..
                 }) => {
-                    self.deferred_images
-                        .push_front(QueuedImage { image, priority });
+                    self.deferred_images.push_front(QueuedImage {
+                        image,
+                        animation,
+                        priority,
+                        caption,
+                        sidecar_matting,
+                        prep_time,
+                        replaces_current,
+                    });
                     break;
                 }
             }
@@ -651,6 +939,11 @@ impl<'a> MattingBridge<'a> {
 //    redraws while transitions or new frames are pending.
 //  - `ViewerModeKind::Sleep` renders a single "Going to Sleep" banner and then
 //    idles until the power state changes again.
+//  - `ViewerModeKind::Empty` stands in for `Wake` while the files task reports
+//    an empty library, showing the empty-library card until a photo arrives.
+//  - Going to sleep from a live slideshow runs a `SleepFade`: the photo fades to
+//    black, the mode flips to `Sleep` at the midpoint, and the banner fades in.
+//    Waking (or toggling) mid-fade reverses it from wherever it is.
 //
 // Redraw policy
 //  - Greeting/Sleep request exactly one redraw on entry and after resizes.
@@ -659,12 +952,44 @@ impl<'a> MattingBridge<'a> {
 //    scale/format in sync.
 
 const CONTROL_TICK_INTERVAL: Duration = Duration::from_millis(4);
-fn process_mat_task(task: MatTask) -> Option<MatResult> {
+fn process_mat_task(mut task: MatTask) -> Option<MatResult> {
+    let frames = task.animation.take();
+    let source = (task.image.width, task.image.height);
+    let (fill_screen, max_dim) = (task.params.fill_screen, task.params.max_dim);
+    // Animated photos keep their frames only as patches, so they are not
+    // laid out again.
+    let retained = frames.is_none().then(|| {
+        Arc::new(MatSource {
+            image: task.image.clone(),
+            caption: task.caption.clone(),
+            sidecar_matting: task.sidecar_matting.clone(),
+        })
+    });
+    let mut result = render_mat_canvas(task)?;
+    result.source = retained;
+    if let Some(frames) = frames {
+        result.animation = animation::AnimationPatches::build(
+            &result.canvas,
+            result.photo_rect,
+            fill_screen,
+            max_dim,
+            source,
+            frames,
+        );
+    }
+    Some(result)
+}
+
+/// Bakes the photo and its mat into one canvas.
+fn render_mat_canvas(task: MatTask) -> Option<MatResult> {
     let MatTask {
         image,
         params,
         priority,
         mat_kind,
+        caption,
+        replaces_current,
+        ..
     } = task;
     let PreparedImageCpu {
         path,
@@ -702,25 +1027,34 @@ fn process_mat_task(task: MatTask) -> Option<MatResult> {
         return Some(MatResult {
             path,
             canvas,
+            photo_rect: (0, 0, canvas_w, canvas_h),
             priority,
             mat_kind,
+            animation: None,
+            caption,
+            source: None,
+            replaces_current,
         });
     }
 
     let margin = (matting.minimum_mat_percentage / 100.0).clamp(0.0, 0.45);
     let max_upscale = max_upscale_factor.max(1.0);
-    let avg_color = average_color(&src);
+    let photo_colors = PhotoColors::new(&src);
 
     if let MattingMode::Studio {
         bevel_width_px,
         bevel_color,
+        bevel_tint_strength,
         texture_strength,
         warp_period_px,
         weft_period_px,
         ..
     } = &matting.style
     {
-        let mat_color = matting.runtime.studio_color(avg_color).unwrap_or(avg_color);
+        let mat_color = matting
+            .runtime
+            .studio_color(&photo_colors)
+            .unwrap_or_else(|| photo_colors.average());
         let mut bevel_px = bevel_width_px.max(0.0);
         let margin_x = (canvas_w as f32 * margin).round();
         let margin_y = (canvas_h as f32 * margin).round();
@@ -774,10 +1108,11 @@ fn process_mat_task(task: MatTask) -> Option<MatResult> {
             main_img.as_ref(),
             mat_color,
             bevel_px,
-            *bevel_color,
+            bevel_color.resolve_bevel(*bevel_tint_strength, &photo_colors),
             *texture_strength,
             *warp_period_px,
             *weft_period_px,
+            matting.runtime.studio_texture().as_deref(),
         );
 
         let canvas = ImagePlane {
@@ -789,8 +1124,13 @@ fn process_mat_task(task: MatTask) -> Option<MatResult> {
         return Some(MatResult {
             path,
             canvas,
+            photo_rect: (offset_x, offset_y, photo_w, photo_h),
             priority,
             mat_kind,
+            animation: None,
+            caption,
+            source: None,
+            replaces_current,
         });
     }
 
@@ -802,8 +1142,8 @@ fn process_mat_task(task: MatTask) -> Option<MatResult> {
     {
         let mat_color = matting
             .runtime
-            .passe_partout_color(avg_color)
-            .unwrap_or(avg_color);
+            .passe_partout_color(&photo_colors)
+            .unwrap_or_else(|| photo_colors.average());
         let mut bevel_px = bevel_width_px.max(0.0);
         let margin_x = (canvas_w as f32 * margin).round();
         let margin_y = (canvas_h as f32 * margin).round();
@@ -857,10 +1197,11 @@ fn process_mat_task(task: MatTask) -> Option<MatResult> {
             main_img.as_ref(),
             mat_color,
             bevel_px,
-            *bevel_color,
+            bevel_color.resolve_bevel(0.0, &photo_colors),
             0.0,
             5.6,
             5.2,
+            None,
         );
 
         let canvas = ImagePlane {
@@ -872,8 +1213,13 @@ fn process_mat_task(task: MatTask) -> Option<MatResult> {
         return Some(MatResult {
             path,
             canvas,
+            photo_rect: (offset_x, offset_y, photo_w, photo_h),
             priority,
             mat_kind,
+            animation: None,
+            caption,
+            source: None,
+            replaces_current,
         });
     }
 
@@ -888,8 +1234,8 @@ fn process_mat_task(task: MatTask) -> Option<MatResult> {
         let color = f32_rgb_to_u8(
             matting
                 .runtime
-                .drop_shadow_color(avg_color)
-                .unwrap_or(avg_color),
+                .drop_shadow_color(&photo_colors)
+                .unwrap_or_else(|| photo_colors.average()),
         );
         let (final_w, final_h) =
             resize_to_fit_with_margin(canvas_w, canvas_h, width, height, margin, max_upscale);
@@ -939,8 +1285,13 @@ fn process_mat_task(task: MatTask) -> Option<MatResult> {
         return Some(MatResult {
             path,
             canvas,
+            photo_rect: (offset_x, offset_y, final_w, final_h),
             priority,
             mat_kind,
+            animation: None,
+            caption,
+            source: None,
+            replaces_current,
         });
     }
 
@@ -1038,8 +1389,8 @@ fn process_mat_task(task: MatTask) -> Option<MatResult> {
         } => {
             let resolved = matting
                 .runtime
-                .vignette_color(avg_color)
-                .unwrap_or(avg_color);
+                .vignette_color(&photo_colors)
+                .unwrap_or_else(|| photo_colors.average());
             let color = f32_rgb_to_u8(resolved);
             render_vignette_canvas(canvas_w, canvas_h, color, *strength, *radius, *softness)
         }
@@ -1127,12 +1478,17 @@ fn process_mat_task(task: MatTask) -> Option<MatResult> {
     Some(MatResult {
         path,
         canvas,
+        photo_rect: (offset_x, offset_y, final_w, final_h),
         priority,
         mat_kind,
+        animation: None,
+        caption,
+        source: None,
+        replaces_current,
     })
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum ViewerEvent {
     Tick,
     Command(ViewerCommand),
@@ -1182,16 +1538,65 @@ async fn drive_viewer_events(
     }
 }
 
+/// Indices into `available` of the `configured` output names that are
+/// connected, in configured order, matching names exactly as the compositor
+/// reports them.
+fn pick_outputs(configured: &[String], available: &[Option<String>]) -> Vec<usize> {
+    configured
+        .iter()
+        .filter_map(|wanted| {
+            available
+                .iter()
+                .position(|name| name.as_deref() == Some(wanted.as_str()))
+        })
+        .collect()
+}
+
+/// Hands processed photos to the `displays.mode: independent` windows in
+/// turn, skipping windows that have gone away, until the loader stops or
+/// every window is gone.
+async fn distribute_round_robin(
+    mut from_loader: Receiver<PhotoLoaded>,
+    outputs: Vec<Sender<PhotoLoaded>>,
+) {
+    let mut turn = 0;
+    while let Some(mut photo) = from_loader.recv().await {
+        let mut delivered = false;
+        for _ in 0..outputs.len() {
+            let output = &outputs[turn % outputs.len()];
+            turn += 1;
+            match output.send(photo).await {
+                Ok(()) => {
+                    delivered = true;
+                    break;
+                }
+                Err(err) => photo = err.0,
+            }
+        }
+        if !delivered {
+            debug!("viewer_distributor_no_outputs_left");
+            return;
+        }
+    }
+}
+
 pub fn run_windowed(
     from_loader: Receiver<PhotoLoaded>,
     to_manager_displayed: Sender<Displayed>,
     cancel: CancellationToken,
     cfg: Arc<crate::config::Configuration>,
     mut control: Receiver<ViewerCommand>,
+    library_size: Arc<AtomicUsize>,
+    playlist_seed: Option<u64>,
+    matting_seed: Option<u64>,
+    status: SharedViewerStatus,
+    photo_ready: watch::Sender<bool>,
+    metrics: SharedMetrics,
 ) -> anyhow::Result<()> {
     use winit::application::ApplicationHandler;
     use winit::event::WindowEvent;
     use winit::event_loop::{ActiveEventLoop, EventLoop, EventLoopProxy};
+    use winit::monitor::MonitorHandle;
     use winit::window::{Fullscreen, Window, WindowAttributes, WindowId};
 
     fn with_photo_app_id(attrs: WindowAttributes) -> WindowAttributes {
@@ -1219,6 +1624,32 @@ pub fn run_windowed(
         }
     }
 
+    /// A fullscreen window on `monitor` for `displays.mode: mirror`; `None`
+    /// (with a warning) leaves that output out.
+    fn open_mirror_window(
+        event_loop: &ActiveEventLoop,
+        monitor: MonitorHandle,
+    ) -> Option<Arc<Window>> {
+        let attrs = with_photo_app_id(Window::default_attributes().with_title("Photo Frame"));
+        match event_loop.create_window(attrs) {
+            Ok(window) => {
+                info!(output = ?monitor.name(), "viewer: mirroring onto display output");
+                window.set_decorations(false);
+                window.set_fullscreen(Some(Fullscreen::Borderless(Some(monitor))));
+                window.set_cursor_visible(false);
+                Some(Arc::new(window))
+            }
+            Err(err) => {
+                warn!(
+                    output = ?monitor.name(),
+                    error = %err,
+                    "failed to create mirror window; leaving the output out"
+                );
+                None
+            }
+        }
+    }
+
     #[repr(C)]
     #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
     struct TransitionUniforms {
@@ -1227,6 +1658,13 @@ pub fn run_windowed(
         kind: u32,
         current_dest: [f32; 4],
         next_dest: [f32; 4],
+        // Ken Burns on a matted photo: the photo's area in its texture and
+        // the part of it sampled there, both in UV. A zero window samples the
+        // whole texture untouched.
+        current_photo: [f32; 4],
+        current_view: [f32; 4],
+        next_photo: [f32; 4],
+        next_view: [f32; 4],
         params0: [f32; 4],
         params1: [f32; 4],
         params3: [f32; 4],
@@ -1234,6 +1672,8 @@ pub fn run_windowed(
         // letterbox regions over this itself so the pipeline can render
         // opaquely without a per-pixel destination read for blending.
         background: [f32; 4],
+        // Iris petal outline: linear RGB and width in screen pixels.
+        iris_stroke: [f32; 4],
         // Per-petal constants for the iris transition, solved on the CPU each
         // frame so the fragment loop needs no transcendentals:
         // petals_a[i] = (annulus_center.xy, tip_dir.xy)
@@ -1258,6 +1698,104 @@ pub fn run_windowed(
         iris_layer: Option<OffscreenTarget>,
         blit_pipeline: wgpu::RenderPipeline,
         half_target: Option<OffscreenTarget>,
+        /// `display.rotation`; when it turns the picture, every mode draws
+        /// upright into `rotated_target` and `present_rotated` turns it onto
+        /// the surface.
+        rotation: DisplayRotation,
+        rotate_pipeline: wgpu::RenderPipeline,
+        rotate_bind: wgpu::BindGroup,
+        rotated_target: Option<OffscreenTarget>,
+        /// The other outputs' surfaces in `displays.mode: mirror`. Frames then
+        /// draw into `rotated_target` too, and `finish_frame` scales it onto
+        /// each of these as well as the window's own surface.
+        mirrors: Vec<MirrorSurface>,
+    }
+
+    /// A window on another output that shows the same frame in
+    /// `displays.mode: mirror`, sharing the primary window's device.
+    struct MirrorSurface {
+        window: Arc<Window>,
+        surface: wgpu::Surface<'static>,
+        config: wgpu::SurfaceConfiguration,
+    }
+
+    impl MirrorSurface {
+        fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+            if width == 0 || height == 0 {
+                return;
+            }
+            self.config.width = width;
+            self.config.height = height;
+            self.surface.configure(device, &self.config);
+        }
+
+        /// The texture to present this frame, reconfiguring once when the
+        /// surface went stale; `None` leaves the output on its last frame.
+        fn acquire(&mut self, device: &wgpu::Device) -> Option<wgpu::SurfaceTexture> {
+            match self.surface.get_current_texture() {
+                Ok(frame) => Some(frame),
+                Err(wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated) => {
+                    let size = self.window.inner_size();
+                    self.resize(device, size.width, size.height);
+                    self.surface.get_current_texture().ok()
+                }
+                Err(err) => {
+                    debug!(error = ?err, "viewer_mirror_frame_skipped");
+                    None
+                }
+            }
+        }
+    }
+
+    /// Sets up a mirror window's surface like the primary one, or `None` (with
+    /// a warning) when the adapter cannot present to it in the same format.
+    fn mirror_surface(
+        instance: &wgpu::Instance,
+        adapter: &wgpu::Adapter,
+        device: &wgpu::Device,
+        primary: &wgpu::SurfaceConfiguration,
+        window: Arc<Window>,
+    ) -> Option<MirrorSurface> {
+        let surface = match instance.create_surface(Arc::clone(&window)) {
+            Ok(surface) => surface,
+            Err(err) => {
+                warn!(error = %err, "failed to create mirror surface; leaving the output out");
+                return None;
+            }
+        };
+        let caps = surface.get_capabilities(adapter);
+        if !caps.formats.contains(&primary.format) {
+            warn!(
+                format = ?primary.format,
+                supported = ?caps.formats,
+                "mirror output cannot present the primary format; leaving it out"
+            );
+            return None;
+        }
+        let alpha_mode = if caps.alpha_modes.contains(&primary.alpha_mode) {
+            primary.alpha_mode
+        } else {
+            caps.alpha_modes.first().copied()?
+        };
+        let present_mode = if caps.present_modes.contains(&primary.present_mode) {
+            primary.present_mode
+        } else {
+            wgpu::PresentMode::AutoVsync
+        };
+        let size = window.inner_size();
+        let config = wgpu::SurfaceConfiguration {
+            width: size.width.max(1),
+            height: size.height.max(1),
+            present_mode,
+            alpha_mode,
+            ..primary.clone()
+        };
+        surface.configure(device, &config);
+        Some(MirrorSurface {
+            window,
+            surface,
+            config,
+        })
     }
 
     /// Reduced-resolution offscreen render target that can also be sampled.
@@ -1313,12 +1851,98 @@ pub fn run_windowed(
             OffscreenTarget { view, bind, w, h }
         }
 
+        /// The size the frame lays out for: the surface's, turned by
+        /// `display.rotation`.
+        fn logical_size(&self) -> (u32, u32) {
+            self.rotation
+                .logical_size(self.config.width, self.config.height)
+        }
+
+        /// Create (or re-create after a resize) the upright frame that
+        /// `present_rotated` turns onto the surface; dropped when there is no
+        /// rotation and nothing to mirror, so modes draw straight to the surface.
+        fn ensure_rotated_target(&mut self) {
+            if self.rotation == DisplayRotation::None && self.mirrors.is_empty() {
+                self.rotated_target = None;
+                return;
+            }
+            let (w, h) = self.logical_size();
+            if let Some(target) = self.rotated_target.as_ref()
+                && target.w == w
+                && target.h == h
+            {
+                return;
+            }
+            let format = self.config.format;
+            self.rotated_target = Some(self.make_offscreen("rotated-frame", w, h, format));
+        }
+
+        /// Turns the upright frame onto the surface; a no-op without
+        /// `rotated_target`.
+        fn present_rotated(
+            &self,
+            encoder: &mut wgpu::CommandEncoder,
+            surface_view: &wgpu::TextureView,
+        ) {
+            let Some(target) = self.rotated_target.as_ref() else {
+                return;
+            };
+            let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("output-rotate"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: surface_view,
+                    resolve_target: None,
+                    depth_slice: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            rpass.set_pipeline(&self.rotate_pipeline);
+            rpass.set_bind_group(0, &self.rotate_bind, &[]);
+            rpass.set_bind_group(1, &target.bind, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+
+        /// Submits a drawn frame and presents it on the window and every
+        /// mirror, each getting `rotated_target` scaled to its own size.
+        fn finish_frame(
+            &mut self,
+            mut encoder: wgpu::CommandEncoder,
+            surface_view: &wgpu::TextureView,
+            frame: wgpu::SurfaceTexture,
+        ) {
+            self.present_rotated(&mut encoder, surface_view);
+            let mut mirror_frames = Vec::with_capacity(self.mirrors.len());
+            for mirror in &mut self.mirrors {
+                if let Some(mirror_frame) = mirror.acquire(&self.device) {
+                    mirror_frames.push(mirror_frame);
+                }
+            }
+            for mirror_frame in &mirror_frames {
+                let view = mirror_frame
+                    .texture
+                    .create_view(&wgpu::TextureViewDescriptor::default());
+                self.present_rotated(&mut encoder, &view);
+            }
+            self.queue.submit(Some(encoder.finish()));
+            frame.present();
+            for mirror_frame in mirror_frames {
+                mirror_frame.present();
+            }
+        }
+
         /// Create (or re-create after a resize) the iris petal layer so it
         /// tracks the current surface size.
         fn ensure_iris_layer(&mut self) {
             let scale = iris_layer_scale();
-            let w = (self.config.width / scale).max(1);
-            let h = (self.config.height / scale).max(1);
+            let (w, h) = self.logical_size();
+            let w = (w / scale).max(1);
+            let h = (h / scale).max(1);
             if let Some(layer) = self.iris_layer.as_ref()
                 && layer.w == w
                 && layer.h == h
@@ -1342,8 +1966,9 @@ pub fn run_windowed(
         /// intermediate that transition frames render into.
         fn ensure_half_target(&mut self) {
             let scale = transition_scale();
-            let w = (self.config.width / scale).max(1);
-            let h = (self.config.height / scale).max(1);
+            let (w, h) = self.logical_size();
+            let w = (w / scale).max(1);
+            let h = (h / scale).max(1);
             if let Some(target) = self.half_target.as_ref()
                 && target.w == w
                 && target.h == h
@@ -1355,12 +1980,12 @@ pub fn run_windowed(
         }
     }
 
-    fn upload_plane(gpu: &GpuCtx, plane: ImagePlane) -> Option<TexturePlane> {
+    fn upload_plane(gpu: &GpuCtx, plane: &ImagePlane) -> Option<(TexturePlane, wgpu::Texture)> {
         let ImagePlane {
             width,
             height,
-            pixels,
-        } = plane;
+            ref pixels,
+        } = *plane;
         if width == 0 || height == 0 {
             return None;
         }
@@ -1391,7 +2016,7 @@ pub fn run_windowed(
             }
             Cow::Owned(staging)
         } else {
-            Cow::Borrowed(&pixels)
+            Cow::Borrowed(pixels.as_slice())
         };
         gpu.queue.write_texture(
             wgpu::TexelCopyTextureInfo {
@@ -1427,33 +2052,187 @@ pub fn run_windowed(
                 },
             ],
         });
-        Some(TexturePlane {
+        let plane = TexturePlane {
             bind,
             w: width,
             h: height,
-        })
+        };
+        Some((plane, tex))
     }
 
     fn upload_mat_result(gpu: &GpuCtx, result: MatResult) -> Option<ImgTex> {
         let MatResult {
             path,
             canvas,
+            photo_rect,
             mat_kind,
+            animation: patches,
+            caption,
+            source,
             ..
         } = result;
-        let plane = upload_plane(gpu, canvas)?;
+        let (plane, texture) = upload_plane(gpu, &canvas)?;
         Some(ImgTex {
             plane,
             path,
             mat_kind,
+            photo_rect,
+            animation: patches.map(|patches| animation::CanvasAnimation::new(texture, patches)),
+            caption,
+            canvas: Some(canvas),
+            source,
+        })
+    }
+
+    /// Uploads a photo again from its retained canvas, onto a new device after
+    /// the old one was lost. `None` when the canvas was already dropped.
+    fn reupload_image(gpu: &GpuCtx, img: ImgTex) -> Option<ImgTex> {
+        let canvas = img.canvas?;
+        let (plane, texture) = upload_plane(gpu, &canvas)?;
+        Some(ImgTex {
+            plane,
+            path: img.path,
+            mat_kind: img.mat_kind,
+            photo_rect: img.photo_rect,
+            animation: img
+                .animation
+                .map(|playback| animation::CanvasAnimation::new(texture, playback.into_patches())),
+            caption: img.caption,
+            canvas: Some(canvas),
+            source: img.source,
         })
     }
 
+    /// The dimmed `ambient` state, held from `set-state ambient` until the
+    /// frame wakes or sleeps.
+    struct AmbientView {
+        brightness: f32,
+        path: Option<std::path::PathBuf>,
+        /// Decode of `ambient-screen.photo` still in flight.
+        decoding: Option<CbReceiver<anyhow::Result<ImagePlane>>>,
+        /// Decoded pixels waiting for a GPU to upload them to.
+        decoded: Option<ImagePlane>,
+        /// The uploaded photo; until it exists the sleep card stands in.
+        photo: Option<ImgTex>,
+    }
+
+    /// Decodes the ambient photo off the event loop, cropped to fill the
+    /// surface, or scaled to fit within 2048 px when no surface is up yet.
+    fn spawn_ambient_decode(
+        path: std::path::PathBuf,
+        surface: Option<(u32, u32)>,
+    ) -> CbReceiver<anyhow::Result<ImagePlane>> {
+        let (tx, rx) = bounded(1);
+        std::thread::spawn(move || {
+            let decoded = image::open(&path)
+                .map(|image| {
+                    let image = match surface {
+                        Some((width, height)) => {
+                            image.resize_to_fill(width, height, imageops::FilterType::Lanczos3)
+                        }
+                        None => image.thumbnail(2048, 2048),
+                    }
+                    .to_rgba8();
+                    ImagePlane {
+                        width: image.width(),
+                        height: image.height(),
+                        pixels: image.into_raw(),
+                    }
+                })
+                .map_err(|err| {
+                    anyhow::Error::new(err).context(format!("failed to open {}", path.display()))
+                });
+            let _ = tx.send(decoded);
+        });
+        rx
+    }
+
     #[derive(Debug, Copy, Clone, Eq, PartialEq)]
     enum ViewerModeKind {
         Greeting,
         Wake,
         Sleep,
+        Empty,
+    }
+
+    /// Solves the petal kinematics for `uniforms.progress` on the CPU so the
+    /// per-pixel shader loop is transcendental-free.
+    fn write_iris_uniforms(
+        uniforms: &mut TransitionUniforms,
+        iris: &IrisLook,
+        screen_w: f32,
+        screen_h: f32,
+    ) {
+        // Geometry derivation: r_in circumscribes the screen, petals are
+        // annular arcs (band r_in..2*r_in) with semicircular end caps,
+        // pivoting by `psi` about the center of the trailing cap.
+        let n = iris.blades.clamp(1, 16) as usize;
+        let t = uniforms.progress;
+        // `x` is how far the petals have closed. The one-way directions
+        // close fully, so the photo the petals carry fills the screen at the
+        // closed end and the cut to or from it is invisible.
+        let (x, min_aperture, mode) = match iris.direction {
+            IrisDirection::CloseOpen => (
+                if t < 0.5 { t * 2.0 } else { 2.0 - t * 2.0 },
+                iris.min_aperture,
+                0.0,
+            ),
+            IrisDirection::Open => (1.0 - t, 0.0, 1.0),
+            IrisDirection::Close => (t, 0.0, 2.0),
+        };
+        let f = x * x * (3.0 - 2.0 * x);
+        let r_in = 1.02 * 0.5 * (screen_w * screen_w + screen_h * screen_h).sqrt();
+        let r_mid = 1.5 * r_in;
+        let e = f * r_in * (1.0 - min_aperture);
+        let psi = 2.0 * (e / (2.0 * r_mid)).clamp(0.0, 1.0).asin();
+        let spin = iris.swirl * psi;
+        let sigma = std::f32::consts::TAU / n as f32 + IRIS_EXTRA_WIDTH_RAD;
+        // Photos crossfade behind the petals around full close.
+        let swap = ((t - 0.42) / 0.16).clamp(0.0, 1.0);
+        let swap = swap * swap * (3.0 - 2.0 * swap);
+        uniforms.params0 = [n as f32, iris.petal_sheen, iris.overlap_shadow, swap];
+        // Inscribed aperture radius: pixels closer to center than this are
+        // provably petal-free.
+        uniforms.params1 = [r_in - e, iris.color[0], iris.color[1], iris.color[2]];
+        // Petal-layer upscale factor: keeps the edge feather at least one
+        // layer texel wide.
+        uniforms.params3[0] = iris_layer_scale() as f32;
+        uniforms.params3[1] = mode;
+        uniforms.iris_stroke = [
+            iris.stroke_color[0],
+            iris.stroke_color[1],
+            iris.stroke_color[2],
+            iris.stroke_width,
+        ];
+        let (s_psi, c_psi) = psi.sin_cos();
+        for i in 0..n {
+            let ai = std::f32::consts::TAU * i as f32 / n as f32 + spin;
+            let (s_ai, c_ai) = ai.sin_cos();
+            let piv = [r_mid * c_ai, r_mid * s_ai];
+            // Annulus center after swinging about the pivot.
+            let center = [
+                piv[0] - (c_psi * piv[0] - s_psi * piv[1]),
+                piv[1] - (s_psi * piv[0] + c_psi * piv[1]),
+            ];
+            let trail = ai + psi;
+            let tip = trail + sigma;
+            // Directional sheen: cos of the petal's facing angle against a
+            // fixed light, constant across a petal. Baked raw here; the
+            // shader scales it by petal_sheen and fades it near full closure.
+            let facing = (ai + 0.5 * sigma + psi - 2.3).cos();
+            uniforms.petals_a[i] = [center[0], center[1], tip.cos(), tip.sin()];
+            uniforms.petals_b[i] = [trail.cos(), trail.sin(), facing, 0.0];
+        }
+    }
+
+    /// The mode an awake viewer shows: the slideshow, or the empty-library card
+    /// while there is nothing to show.
+    fn awake_mode(library_empty: bool) -> ViewerModeKind {
+        if library_empty {
+            ViewerModeKind::Empty
+        } else {
+            ViewerModeKind::Wake
+        }
     }
 
     struct ViewerMode {
@@ -1461,6 +2240,7 @@ pub fn run_windowed(
         greeting: Option<GreetingScene>,
         wake: scenes::WakeScene,
         sleep: Option<SleepScene>,
+        empty: Option<EmptyScene>,
     }
 
     #[derive(Copy, Clone, Debug)]
@@ -1476,6 +2256,7 @@ pub fn run_windowed(
                 greeting: None,
                 wake,
                 sleep: None,
+                empty: None,
             }
         }
 
@@ -1507,6 +2288,14 @@ pub fn run_windowed(
             self.sleep.as_mut()
         }
 
+        fn empty(&self) -> Option<&EmptyScene> {
+            self.empty.as_ref()
+        }
+
+        fn empty_mut(&mut self) -> Option<&mut EmptyScene> {
+            self.empty.as_mut()
+        }
+
         #[allow(clippy::too_many_arguments)]
         fn with_scene_mut<'a, R>(
             &'a mut self,
@@ -1516,6 +2305,7 @@ pub fn run_windowed(
             rng: &'a mut rand::rngs::ThreadRng,
             notify_displayed: &'a mut dyn FnMut(std::path::PathBuf),
             enqueue_matting: &'a mut dyn FnMut(&mut scenes::WakeScene),
+            message_context: MessageContext,
             f: impl FnOnce(&mut dyn Scene, SceneContext<'a>) -> R,
         ) -> Option<R> {
             let ctx = SceneContext::new(
@@ -1525,17 +2315,25 @@ pub fn run_windowed(
                 rng,
                 notify_displayed,
                 enqueue_matting,
+                message_context,
             );
             match self.kind {
                 ViewerModeKind::Greeting => Some(f(self.greeting.as_mut()?, ctx)),
                 ViewerModeKind::Wake => Some(f(&mut self.wake, ctx)),
                 ViewerModeKind::Sleep => Some(f(self.sleep.as_mut()?, ctx)),
+                ViewerModeKind::Empty => Some(f(self.empty.as_mut()?, ctx)),
             }
         }
 
-        fn set_overlays(&mut self, greeting: Option<GreetingScene>, sleep: Option<SleepScene>) {
+        fn set_overlays(
+            &mut self,
+            greeting: Option<GreetingScene>,
+            sleep: Option<SleepScene>,
+            empty: Option<EmptyScene>,
+        ) {
             self.greeting = greeting;
             self.sleep = sleep;
+            self.empty = empty;
         }
 
         fn into_kind(self, kind: ViewerModeKind) -> Self {
@@ -1548,6 +2346,18 @@ pub fn run_windowed(
         to_manager_displayed: Sender<Displayed>,
         cancel: CancellationToken,
         window: Option<Arc<Window>>,
+        /// `displays.outputs` entries this window may open on, in order: all
+        /// of them in mirror mode, or its own in independent mode.
+        outputs: Vec<String>,
+        /// Open windows on the other connected `outputs` and mirror onto them.
+        mirror: bool,
+        /// Those windows, kept across GPU rebuilds like `window`.
+        mirror_windows: Vec<Arc<Window>>,
+        /// The output `window` opened on, reported with each `Displayed`.
+        output: Option<String>,
+        /// Only the primary window publishes `status` and frame metrics and
+        /// lends its device to GPU blur mats.
+        primary: bool,
         gpu: Option<GpuCtx>,
         surface_configured: bool,
         surface_gate: SurfaceReadyGate,
@@ -1558,19 +2368,59 @@ pub fn run_windowed(
         max_upscale_factor: f32,
         matting: MattingConfig,
         mat_pipeline: MattingPipeline,
+        matting_rng: StdRng,
         mat_inflight: usize,
         ready_results: VecDeque<MatResult>,
         deferred_images: VecDeque<QueuedImage>,
         clear_color: wgpu::Color,
         rng: rand::rngs::ThreadRng,
         full_config: Arc<crate::config::Configuration>,
+        /// Live photo count published by the manager, for `{photo_count}` in messages.
+        library_size: Arc<AtomicUsize>,
+        /// Latest startup scan report from the files task, for `{state}` and the
+        /// photo count while the manager has yet to hear about every photo.
+        scan_progress: (usize, bool),
         surface_timeout_streak: u32,
+        /// Set from wgpu's device-lost callback; the next tick rebuilds the GPU.
+        device_lost: Arc<AtomicBool>,
+        /// How many times a lost device has been rebuilt, for the logs.
+        device_recoveries: u32,
         // Last surface size that we considered "configured" and stabilized
         configured_surface_size: Option<(u32, u32)>,
         /// Caption overlay for showcase mode; `None` when showcase is disabled.
         caption_overlay: Option<scenes::CaptionOverlay>,
+        /// Text of the banner from the last `Notice` command, and when it goes.
+        notice: Option<(String, Instant)>,
+        /// Built on the first notice and kept for later ones.
+        notice_overlay: Option<scenes::CaptionOverlay>,
+        /// Reason given with the current pause, for the pause indicator.
+        pause_reason: Option<String>,
+        /// Built on the first pause when `pause-indicator` is on.
+        pause_overlay: Option<scenes::CaptionOverlay>,
+        /// QR code linking to the photo on screen; `None` unless `share-qr` is on.
+        share_qr_overlay: Option<scenes::ShareQrOverlay>,
+        /// Pipeline timings over the slideshow; `None` while switched off.
+        debug_overlay: Option<DebugOverlay>,
         /// Frame cadence of the transition currently being presented.
         transition_frame_stats: Option<TransitionFrameStats>,
+        /// In-flight wake ↔ sleep fade; the mode flips to `Sleep` at its midpoint.
+        sleep_fade: Option<SleepFade>,
+        /// Mode to switch to once the greeting/sleep overlay finishes fading out.
+        overlay_exit: Option<ViewerModeKind>,
+        /// Set while the viewer is in the `ambient` state.
+        ambient: Option<AmbientView>,
+        /// Set while the files task reports no photos in the library.
+        library_empty: bool,
+        /// Snapshot read by the control socket's `status` command.
+        status: SharedViewerStatus,
+        /// Raised once a photo is ready to show, so the greeting can end.
+        photo_ready: watch::Sender<bool>,
+        /// Pipeline timings read by the control socket's `metrics` command.
+        metrics: SharedMetrics,
+        /// One refresh of the fullscreen output, for counting dropped frames.
+        refresh_interval: Duration,
+        /// Shared with the scenes' redraw callback to apply `frame-pacing.max-fps`.
+        frame_pacer: Rc<FramePacer>,
     }
 
     impl App {
@@ -1588,7 +2438,7 @@ pub fn run_windowed(
             }
             self.gpu
                 .as_ref()
-                .map(|gpu| SurfaceState::from_config(&gpu.config, &gpu.limits))
+                .map(|gpu| SurfaceState::from_config(&gpu.config, &gpu.limits, gpu.rotation))
         }
 
         fn update_surface_ready(&mut self, width: u32, height: u32, report: SurfaceReport) -> bool {
@@ -1608,13 +2458,18 @@ pub fn run_windowed(
             let ready = self.surface_gate.is_ready();
             // Detect stabilized size changes and purge stale queues/results
             if ready {
+                let (width, height) = self
+                    .full_config
+                    .display
+                    .rotation
+                    .logical_size(width, height);
                 let size_changed = match self.configured_surface_size {
                     Some((w, h)) => w != width || h != height,
                     None => true,
                 };
                 if size_changed {
                     self.configured_surface_size = Some((width, height));
-                    self.on_surface_size_settled();
+                    self.on_surface_size_settled(width, height);
                 }
             }
             self.surface_configured = ready;
@@ -1624,16 +2479,85 @@ pub fn run_windowed(
             ready
         }
 
-        fn on_surface_size_settled(&mut self) {
-            // Drop any precomputed canvases for the previous size and flush pending queue.
-            self.ready_results.clear();
-            if let Some(mode) = self.mode.as_mut() {
-                let wake = mode.wake_mut();
-                wake.set_next(None);
-                wake.pending_mut().clear();
-                wake.set_transition_state(None);
+        /// Lays the slideshow out again after the screen changed shape, say
+        /// when the monitor was rotated. Photos matted for the old shape are
+        /// matted again from their retained sources: the one on screen first,
+        /// swapped in place, then the staged ones in their original order.
+        /// Photos without a source (animated ones) are dropped from the
+        /// queue, and the step-back history keeps only photos that still fit.
+        fn on_surface_size_settled(&mut self, width: u32, height: u32) {
+            let Some(max_dim) = self
+                .gpu
+                .as_ref()
+                .map(|gpu| gpu.limits.max_texture_dimension_2d)
+            else {
+                return;
+            };
+            let expected = compute_canvas_size(width, height, self.oversample, max_dim);
+            let Some(mode) = self.mode.as_mut() else {
+                return;
+            };
+            let wake = mode.wake_mut();
+            wake.set_transition_state(None);
+            let stale = |img: &ImgTex| (img.plane.w, img.plane.h) != expected;
+
+            let mut requeued = Vec::new();
+            let mut kept = 0usize;
+            let mut dropped = 0usize;
+            let current_relayout = wake
+                .current()
+                .filter(|&img| stale(img))
+                .and_then(|img| img.source.as_deref())
+                .map(|source| QueuedImage::relayout(source, true));
+            let current_refreshed = current_relayout.is_some();
+            requeued.extend(current_relayout);
+
+            let staged = wake
+                .take_next()
+                .into_iter()
+                .chain(wake.pending_mut().drain(..));
+            for img in staged.collect::<Vec<_>>() {
+                if !stale(&img) {
+                    wake.pending_mut().push_back(img);
+                    kept += 1;
+                } else if let Some(source) = img.source.as_deref() {
+                    requeued.push(QueuedImage::relayout(source, false));
+                } else {
+                    dropped += 1;
+                }
+            }
+            for result in std::mem::take(&mut self.ready_results) {
+                if (result.canvas.width, result.canvas.height) == expected {
+                    self.ready_results.push_back(result);
+                } else if let Some(source) = result.source.as_deref() {
+                    requeued.push(QueuedImage::relayout(source, result.replaces_current));
+                } else {
+                    dropped += 1;
+                }
+            }
+            let history_before = wake.history_len();
+            wake.retain_history(|img| !stale(img));
+            let history_dropped = history_before - wake.history_len();
+
+            let relayouts = requeued.len();
+            for queued in requeued.into_iter().rev() {
+                self.deferred_images.push_front(queued);
             }
-            // We cannot cancel inflight matting; mismatched results will be dropped on upload.
+            wake.mark_redraw_needed();
+            // Matting already in flight for the old shape is caught on upload.
+            debug!(
+                width,
+                height,
+                canvas_w = expected.0,
+                canvas_h = expected.1,
+                current_refreshed,
+                relayouts,
+                kept,
+                dropped,
+                history_dropped,
+                inflight = self.mat_inflight,
+                "wake_relayout"
+            );
         }
 
         fn with_active_scene<R>(
@@ -1648,8 +2572,11 @@ pub fn run_windowed(
             let window_ref = window_handle.as_deref();
             let mut request_redraw = {
                 let window_handle = window_handle.clone();
+                let pacer = Rc::clone(&self.frame_pacer);
                 move || {
-                    if let Some(window) = window_handle.as_ref() {
+                    if let Some(window) = window_handle.as_ref()
+                        && pacer.admit(Instant::now())
+                    {
                         window.request_redraw();
                     }
                 }
@@ -1657,11 +2584,16 @@ pub fn run_windowed(
             let config = Arc::clone(&self.full_config);
             let mut notify_displayed = {
                 let sender = self.to_manager_displayed.clone();
+                let output = self.output.clone();
                 move |path: std::path::PathBuf| {
-                    let _ = sender.try_send(Displayed(path));
+                    let _ = sender.try_send(Displayed {
+                        path,
+                        output: output.clone(),
+                    });
                 }
             };
 
+            let message_context = self.message_context();
             let mut mode = self.mode.take()?;
             let surface = self.active_surface();
             let mut bridge = MattingBridge {
@@ -1675,6 +2607,7 @@ pub fn run_windowed(
                 oversample: self.oversample,
                 max_upscale_factor: self.max_upscale_factor,
                 mat_pipeline: &self.mat_pipeline,
+                rng: &mut self.matting_rng,
             };
             let mut enqueue_matting = move |wake: &mut scenes::WakeScene| {
                 bridge.queue_for_wake(wake);
@@ -1688,6 +2621,7 @@ pub fn run_windowed(
                 rng,
                 &mut notify_displayed,
                 &mut enqueue_matting,
+                message_context,
                 f,
             );
             self.mode = Some(mode);
@@ -1698,6 +2632,25 @@ pub fn run_windowed(
             self.mode().kind()
         }
 
+        /// Placeholder values for greeting/sleep messages, in the schedule's timezone.
+        fn message_context(&self) -> MessageContext {
+            let known = self.library_size.load(Ordering::Relaxed);
+            let (discovered, complete) = self.scan_progress;
+            let (photo_count, state) = if complete {
+                (known, LibraryState::Ready)
+            } else {
+                (known.max(discovered), LibraryState::Scanning)
+            };
+            MessageContext::current(
+                self.full_config
+                    .awake_schedule
+                    .as_ref()
+                    .map(AwakeScheduleConfig::timezone),
+                photo_count,
+                state,
+            )
+        }
+
         fn set_mode(&mut self, target: ViewerModeKind) -> ViewerModeKind {
             let current = self.mode_kind();
             if current == target {
@@ -1728,8 +2681,11 @@ pub fn run_windowed(
             let window_ref = window_handle.as_deref();
             let mut request_redraw = {
                 let window_handle = window_handle.clone();
+                let pacer = Rc::clone(&self.frame_pacer);
                 move || {
-                    if let Some(window) = window_handle.as_ref() {
+                    if let Some(window) = window_handle.as_ref()
+                        && pacer.admit(Instant::now())
+                    {
                         window.request_redraw();
                     }
                 }
@@ -1737,10 +2693,15 @@ pub fn run_windowed(
             let config = Arc::clone(&self.full_config);
             let mut notify_displayed = {
                 let sender = self.to_manager_displayed.clone();
+                let output = self.output.clone();
                 move |path: std::path::PathBuf| {
-                    let _ = sender.try_send(Displayed(path));
+                    let _ = sender.try_send(Displayed {
+                        path,
+                        output: output.clone(),
+                    });
                 }
             };
+            let message_context = self.message_context();
             let surface = self.active_surface();
             let mut bridge = MattingBridge {
                 preload_count: self.preload_count,
@@ -1753,6 +2714,7 @@ pub fn run_windowed(
                 oversample: self.oversample,
                 max_upscale_factor: self.max_upscale_factor,
                 mat_pipeline: &self.mat_pipeline,
+                rng: &mut self.matting_rng,
             };
             let mut enqueue_matting = move |wake: &mut scenes::WakeScene| {
                 bridge.queue_for_wake(wake);
@@ -1765,6 +2727,7 @@ pub fn run_windowed(
                 rng,
                 &mut notify_displayed,
                 &mut enqueue_matting,
+                message_context,
                 |scene, ctx| {
                     Self::run_scene_hook(scene, hook, ctx);
                 },
@@ -1867,6 +2830,29 @@ pub fn run_windowed(
                         return false;
                     }
                 };
+            // The driver can drop the device out from under us, e.g. when the
+            // HDMI link renegotiates. Flag it and rebuild on the next tick
+            // rather than panicking on the dead device's errors.
+            let device_lost = Arc::clone(&self.device_lost);
+            device.set_device_lost_callback(move |lost_reason, message| {
+                if matches!(lost_reason, wgpu::DeviceLostReason::Destroyed) {
+                    // Dropped by the viewer itself during a teardown.
+                    return;
+                }
+                warn!(reason = ?lost_reason, %message, "viewer_gpu_device_lost");
+                device_lost.store(true, Ordering::Release);
+            });
+            // Blur mats with `backend: gpu` borrow this device from the
+            // matting workers; with independent outputs, the primary's.
+            if self.primary
+                && !crate::gpu::blur::install(
+                    &device,
+                    &queue,
+                    &adapter.get_downlevel_capabilities(),
+                )
+            {
+                info!("viewer: GPU lacks compute shaders; gpu blur mats use the CPU");
+            }
             let caps = surface.get_capabilities(&adapter);
             let Some(format) = caps
                 .formats
@@ -1909,10 +2895,17 @@ pub fn run_windowed(
                 present_modes = ?caps.present_modes,
                 "viewer_gpu_surface_caps"
             );
-            // PHOTOFRAME_PRESENT_MODE=fifo|mailbox|immediate overrides the
-            // presentation mode for frame-pacing experiments: fifo serializes
-            // on the compositor's vsync cadence, mailbox lets the app render
-            // ahead and the compositor latch the newest complete frame.
+            // `frame-pacing.present-mode` picks the presentation mode: fifo
+            // serializes on the compositor's vsync cadence, mailbox lets the
+            // app render ahead and the compositor latch the newest complete
+            // frame. PHOTOFRAME_PRESENT_MODE=fifo|mailbox|immediate overrides
+            // it for frame-pacing experiments without a config edit.
+            let configured_present = match self.full_config.frame_pacing.present_mode {
+                PresentMode::Auto => None,
+                PresentMode::Fifo => Some(wgpu::PresentMode::Fifo),
+                PresentMode::Mailbox => Some(wgpu::PresentMode::Mailbox),
+                PresentMode::Immediate => Some(wgpu::PresentMode::Immediate),
+            };
             let requested_present = std::env::var("PHOTOFRAME_PRESENT_MODE").ok();
             let present_mode = match requested_present.as_deref() {
                 Some("fifo") => Some(wgpu::PresentMode::Fifo),
@@ -1921,11 +2914,11 @@ pub fn run_windowed(
                 Some(other) => {
                     warn!(
                         requested = other,
-                        "unknown PHOTOFRAME_PRESENT_MODE; using auto-vsync"
+                        "unknown PHOTOFRAME_PRESENT_MODE; using frame-pacing.present-mode"
                     );
-                    None
+                    configured_present
                 }
-                None => None,
+                None => configured_present,
             };
             let present_mode = match present_mode {
                 Some(mode) if caps.present_modes.contains(&mode) => {
@@ -2107,6 +3100,55 @@ pub fn run_windowed(
                 multiview: None,
                 cache: None,
             });
+            let rotation = self.full_config.display.rotation;
+            let rotate_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("output-rotate"),
+                source: wgpu::ShaderSource::Wgsl(std::borrow::Cow::Borrowed(include_str!(
+                    "shaders/output_rotate.wgsl"
+                ))),
+            });
+            let rotate_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("output-rotate-pipeline"),
+                layout: Some(&blit_pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &rotate_shader,
+                    entry_point: Some("vs_main"),
+                    buffers: &[],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                },
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                fragment: Some(wgpu::FragmentState {
+                    module: &rotate_shader,
+                    entry_point: Some("fs_main"),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                    compilation_options: wgpu::PipelineCompilationOptions::default(),
+                }),
+                multiview: None,
+                cache: None,
+            });
+            // The rotation is fixed for the life of the process.
+            let rotate_uniforms = rotation.uv_transform();
+            let rotate_buf = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("output-rotate-uniforms"),
+                size: std::mem::size_of_val(&rotate_uniforms) as u64,
+                usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            queue.write_buffer(&rotate_buf, 0, bytemuck::cast_slice(&rotate_uniforms));
+            let rotate_bind = device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("output-rotate-bind"),
+                layout: &uniform_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: rotate_buf.as_entire_binding(),
+                }],
+            });
             let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                 label: Some("quad-pipeline"),
                 layout: Some(&pipeline_layout),
@@ -2206,9 +3248,30 @@ pub fn run_windowed(
                 self.full_config.sleep_screen.screen(),
             ));
 
-            if self.full_config.showcase.enabled && self.full_config.showcase.caption_enabled() {
-                self.caption_overlay = Some(scenes::CaptionOverlay::new(&device, &queue, format));
+            let empty = EmptyScene::new(GreetingScreen::new(
+                &device,
+                &queue,
+                format,
+                self.full_config.empty_library_screen.screen(),
+            ));
+
+            if self.full_config.showcase.enabled && self.full_config.showcase.caption_enabled() {
+                self.caption_overlay = Some(scenes::CaptionOverlay::new(&device, &queue, format));
             }
+            let photo_caption = self.full_config.caption.enabled.then(|| {
+                let style = scenes::CaptionStyle::from(&self.full_config.caption);
+                scenes::CaptionOverlay::with_style(&device, &queue, format, style)
+            });
+            self.share_qr_overlay = self.full_config.share_qr.enabled.then(|| {
+                scenes::ShareQrOverlay::new(&device, &queue, format, &self.full_config.share_qr)
+            });
+            let mirrors = self
+                .mirror_windows
+                .iter()
+                .filter_map(|mirror| {
+                    mirror_surface(&instance, &adapter, &device, &config, Arc::clone(mirror))
+                })
+                .collect();
             self.window = Some(window);
             let gpu = GpuCtx {
                 device,
@@ -2226,9 +3289,15 @@ pub fn run_windowed(
                 iris_layer: None,
                 blit_pipeline,
                 half_target: None,
+                rotation,
+                rotate_pipeline,
+                rotate_bind,
+                rotated_target: None,
+                mirrors,
             };
             if let Some(mode) = self.mode.as_mut() {
-                mode.set_overlays(Some(greeting), Some(sleep));
+                mode.set_overlays(Some(greeting), Some(sleep), Some(empty));
+                mode.wake_mut().set_photo_caption(photo_caption);
             }
             self.gpu = Some(gpu);
             self.surface_gate.reset();
@@ -2285,6 +3354,7 @@ pub fn run_windowed(
                 .map(GreetingScene::needs_redraw)
                 .unwrap_or(false);
             let sleep_pending = mode.sleep().map(SleepScene::needs_redraw).unwrap_or(false);
+            let empty_pending = mode.empty().map(EmptyScene::needs_redraw).unwrap_or(false);
 
             debug!(
                 context = context,
@@ -2300,6 +3370,7 @@ pub fn run_windowed(
                 pending_redraw = wake.needs_redraw(),
                 greeting_overlay_pending = greeting_pending,
                 sleep_overlay_pending = sleep_pending,
+                empty_overlay_pending = empty_pending,
                 displayed_elapsed_ms,
                 current_path = current_path.as_deref(),
                 next_path = next_path.as_deref(),
@@ -2327,9 +3398,24 @@ pub fn run_windowed(
                 }
             };
             window.set_decorations(false);
-            let fullscreen_monitor = window
-                .current_monitor()
+            let mut configured = self.configured_monitors(event_loop).into_iter();
+            let own_monitor = configured.next();
+            self.output = own_monitor.as_ref().and_then(MonitorHandle::name);
+            if self.mirror {
+                self.mirror_windows = configured
+                    .filter_map(|monitor| open_mirror_window(event_loop, monitor))
+                    .collect();
+            }
+            let fullscreen_monitor = own_monitor
+                .or_else(|| window.current_monitor())
                 .or_else(|| event_loop.primary_monitor());
+            if let Some(millihertz) = fullscreen_monitor
+                .as_ref()
+                .and_then(MonitorHandle::refresh_rate_millihertz)
+                .filter(|&mhz| mhz > 0)
+            {
+                self.refresh_interval = Duration::from_secs_f64(1000.0 / f64::from(millihertz));
+            }
             window.set_fullscreen(Some(match fullscreen_monitor {
                 Some(m) => Fullscreen::Borderless(Some(m)),
                 None => Fullscreen::Borderless(None),
@@ -2340,6 +3426,40 @@ pub fn run_windowed(
             Some(window)
         }
 
+        /// The connected monitors named by `outputs`, in configured order; the
+        /// window goes on the first and, in mirror mode, mirrors onto the rest.
+        fn configured_monitors(&self, event_loop: &ActiveEventLoop) -> Vec<MonitorHandle> {
+            if self.outputs.is_empty() {
+                return Vec::new();
+            }
+            let monitors: Vec<MonitorHandle> = event_loop.available_monitors().collect();
+            let names: Vec<Option<String>> = monitors.iter().map(MonitorHandle::name).collect();
+            let picked = pick_outputs(&self.outputs, &names);
+            match picked.first() {
+                Some(&idx) => {
+                    info!(output = ?names[idx], "viewer: using configured display output");
+                    if self.mirror && picked.len() < self.outputs.len() {
+                        warn!(
+                            configured = ?self.outputs,
+                            available = ?names,
+                            "some displays.outputs are not connected; mirroring onto the rest"
+                        );
+                    }
+                }
+                None => {
+                    warn!(
+                        configured = ?self.outputs,
+                        available = ?names,
+                        "displays.outputs is not connected; using the current monitor"
+                    );
+                }
+            }
+            picked
+                .into_iter()
+                .map(|idx| monitors[idx].clone())
+                .collect()
+        }
+
         fn teardown_gpu(&mut self) {
             let current_kind = self.mode_kind();
             {
@@ -2348,7 +3468,23 @@ pub fn run_windowed(
                 if current_kind == ViewerModeKind::Wake {
                     mode.wake_mut().mark_redraw_needed();
                 }
-                mode.set_overlays(None, None);
+                mode.set_overlays(None, None, None);
+                mode.wake_mut().set_photo_caption(None);
+            }
+            self.notice_overlay = None;
+            self.pause_overlay = None;
+            self.share_qr_overlay = None;
+            if let Some(debug) = self.debug_overlay.as_mut() {
+                debug.release_gpu();
+            }
+            // Keep the ambient photo's pixels; the next tick uploads them again.
+            if let Some(ambient) = self.ambient.as_mut()
+                && let Some(photo) = ambient.photo.take()
+            {
+                ambient.decoded = photo.canvas;
+            }
+            if self.primary {
+                crate::gpu::blur::uninstall();
             }
             self.gpu = None;
             self.surface_gate.reset();
@@ -2360,12 +3496,178 @@ pub fn run_windowed(
         fn handle_control_command(&mut self, cmd: ViewerCommand) {
             debug!(command = ?cmd, "viewer_control_command");
             match cmd {
-                ViewerCommand::SetState(ControlViewerState::Awake) => self.enter_wake(),
-                ViewerCommand::SetState(ControlViewerState::Asleep) => self.enter_sleep(),
-                ViewerCommand::ToggleState => match self.mode_kind() {
-                    ViewerModeKind::Sleep => self.enter_wake(),
-                    ViewerModeKind::Greeting | ViewerModeKind::Wake => self.enter_sleep(),
-                },
+                ViewerCommand::SetState {
+                    state: ControlViewerState::Awake,
+                    ..
+                } => self.enter_wake(),
+                ViewerCommand::SetState {
+                    state: ControlViewerState::Asleep,
+                    ..
+                } => self.enter_sleep(),
+                ViewerCommand::SetState {
+                    state: ControlViewerState::Ambient,
+                    ..
+                } => self.enter_ambient(),
+                // Ambient counts as asleep: a toggle wakes the frame fully.
+                ViewerCommand::ToggleState if self.ambient.is_some() => self.enter_wake(),
+                // Toggle relative to where the viewer is heading, so a second
+                // press mid-fade turns the fade around.
+                ViewerCommand::ToggleState => {
+                    let fading = self.sleep_fade.as_ref().map(SleepFade::direction);
+                    let heading_to_sleep = match (fading, self.overlay_exit) {
+                        (Some(direction), _) => direction == SleepFadeDirection::ToSleep,
+                        (None, Some(target)) => target == ViewerModeKind::Sleep,
+                        (None, None) => self.mode_kind() == ViewerModeKind::Sleep,
+                    };
+                    if heading_to_sleep {
+                        self.enter_wake();
+                    } else {
+                        self.enter_sleep();
+                    }
+                }
+                // Picked up by the greeting on its next tick.
+                ViewerCommand::GreetingProgress {
+                    discovered,
+                    complete,
+                } => self.scan_progress = (discovered, complete),
+                ViewerCommand::LibraryEmpty(empty) => self.set_library_empty(empty),
+                ViewerCommand::Next => self.step_slideshow(false),
+                ViewerCommand::Previous => self.step_slideshow(true),
+                ViewerCommand::Pause { reason } => self.set_slideshow_paused(true, reason),
+                ViewerCommand::Resume => self.set_slideshow_paused(false, None),
+                ViewerCommand::ReloadConfig(ReloadedConfig(next)) => self.reload_config(&next),
+                ViewerCommand::Notice { text, duration } => self.show_notice(text, duration),
+                ViewerCommand::DebugOverlay(enabled) => self.set_debug_overlay(enabled),
+            }
+        }
+
+        fn set_debug_overlay(&mut self, enabled: bool) {
+            if enabled == self.debug_overlay.is_some() {
+                return;
+            }
+            info!(enabled, "viewer: debug overlay changed");
+            self.debug_overlay =
+                enabled.then(|| DebugOverlay::new(self.full_config.debug_overlay.corner));
+            self.mode_mut().wake_mut().mark_redraw_needed();
+        }
+
+        /// Puts fresh numbers in the debug overlay a few times a second and
+        /// redraws when they changed, so they keep moving during a still dwell.
+        fn refresh_debug_overlay(&mut self, now: Instant) {
+            let Some(debug) = self.debug_overlay.as_mut() else {
+                return;
+            };
+            if !debug.is_due(now) {
+                return;
+            }
+            let mut readout = debug_overlay::Readout::from_metrics(
+                &self.metrics.lock().unwrap_or_else(PoisonError::into_inner),
+            );
+            readout.queue_depth = self
+                .mode
+                .as_ref()
+                .map_or(0, |mode| mode.wake().pending().len());
+            let fps = self
+                .transition_frame_stats
+                .as_ref()
+                .and_then(TransitionFrameStats::fps);
+            if debug.refresh(now, readout, fps) {
+                self.mode_mut().wake_mut().mark_redraw_needed();
+            }
+        }
+
+        /// Puts up a banner over the slideshow until `duration` has passed.
+        /// Only the slideshow draws it; a notice that arrives while another
+        /// screen is up still shows if the slideshow returns in time.
+        fn show_notice(&mut self, text: String, duration: Duration) {
+            info!(%text, duration_ms = duration.as_millis() as u64, "viewer: showing notice");
+            self.notice = Some((text, Instant::now() + duration));
+            self.mode_mut().wake_mut().mark_redraw_needed();
+        }
+
+        /// Drops a notice whose time is up and redraws without it.
+        fn expire_notice(&mut self, now: Instant) {
+            if self.notice.as_ref().is_some_and(|(_, until)| now >= *until) {
+                self.notice = None;
+                debug!("viewer_notice_expired");
+                self.mode_mut().wake_mut().mark_redraw_needed();
+            }
+        }
+
+        /// Applies the live-reloadable parts of `next`. Photos already matted
+        /// keep their mat; the new matting applies from the next one prepared.
+        fn reload_config(&mut self, next: &crate::config::Configuration) {
+            let (merged, ignored) = self.full_config.reloaded_from(next);
+            if !ignored.is_empty() {
+                warn!(keys = ?ignored, "viewer: config reload ignored changes that need a restart");
+            }
+            self.matting = merged.matting.clone();
+            self.oversample = merged.global_photo_settings.oversample;
+            self.max_upscale_factor = merged.global_photo_settings.max_upscale_factor;
+            self.mode_mut().wake_mut().reconfigure(
+                merged.global_photo_settings.dwell_ms,
+                merged.transition.clone(),
+            );
+            info!(
+                dwell_ms = merged.global_photo_settings.dwell_ms,
+                "viewer: configuration reloaded"
+            );
+            self.full_config = Arc::new(merged);
+        }
+
+        /// Holds the slideshow on its current photo. Accepted in any mode, so
+        /// a pause sent while asleep still holds once the frame wakes.
+        fn set_slideshow_paused(&mut self, paused: bool, reason: Option<String>) {
+            info!(paused, ?reason, "viewer: slideshow pause changed");
+            self.pause_reason = reason;
+            let wake = self.mode_mut().wake_mut();
+            wake.set_paused(paused, Instant::now());
+            wake.mark_redraw_needed();
+        }
+
+        /// Skips ahead, or back through the recently shown photos. Only the
+        /// slideshow itself responds; other screens ignore the command.
+        fn step_slideshow(&mut self, back: bool) {
+            let direction = if back { "previous" } else { "next" };
+            if self.mode_kind() != ViewerModeKind::Wake
+                || self.sleep_fade.is_some()
+                || self.overlay_exit.is_some()
+                || self.ambient.is_some()
+            {
+                info!(direction, "viewer: slideshow not showing; step ignored");
+                return;
+            }
+            let wake = self.mode_mut().wake_mut();
+            let stepped = if back {
+                wake.request_previous()
+            } else {
+                wake.request_next()
+            };
+            if stepped {
+                info!(direction, "viewer: stepping slideshow");
+            } else {
+                info!(direction, "viewer: nothing to step to yet; step ignored");
+            }
+        }
+
+        /// Swaps between the slideshow and the empty-library card while awake.
+        /// Greeting, sleep, and ambient are left alone; waking picks the right
+        /// mode.
+        fn set_library_empty(&mut self, empty: bool) {
+            if self.library_empty == empty {
+                return;
+            }
+            self.library_empty = empty;
+            info!(empty, "viewer: library emptiness changed");
+            if self.ambient.is_some() {
+                return;
+            }
+            let awake = matches!(
+                self.mode_kind(),
+                ViewerModeKind::Wake | ViewerModeKind::Empty
+            );
+            if awake && self.sleep_fade.is_none() && self.overlay_exit.is_none() {
+                self.enter_wake();
             }
         }
 
@@ -2375,6 +3677,9 @@ pub fn run_windowed(
                 event_loop.exit();
                 return;
             }
+            if self.device_lost.swap(false, Ordering::AcqRel) {
+                self.recover_lost_device(event_loop);
+            }
 
             // Keep surface configuration and readiness in sync even if winit
             // doesn't deliver an explicit resize event (Wayland/Sway can do this).
@@ -2386,7 +3691,13 @@ pub fn run_windowed(
                 });
             }
 
+            if let Some(mode) = self.mode.as_mut() {
+                mode.wake_mut().note_tick(Instant::now(), SystemTime::now());
+            }
             self.drain_mat_results();
+            self.poll_ambient_photo();
+            self.expire_notice(Instant::now());
+            self.refresh_debug_overlay(Instant::now());
 
             let mode_kind = self.mode_kind();
             if !matches!(mode_kind, ViewerModeKind::Sleep)
@@ -2400,16 +3711,119 @@ pub fn run_windowed(
                 self.mode = Some(mode);
             }
 
-            let _ = self.with_active_scene(|scene, ctx| {
-                scene.process_tick(ctx);
-            });
+            self.finish_overlay_exit();
+            let ambient_photo = self
+                .ambient
+                .as_ref()
+                .is_some_and(|ambient| ambient.photo.is_some());
+            if self.sleep_fade.is_some() {
+                // The slideshow is frozen while it fades; the fade drives redraws.
+                self.advance_sleep_fade();
+            } else if !ambient_photo {
+                // Skipped under the ambient photo, which holds the slideshow
+                // still: no dwell and no transitions.
+                let _ = self.with_active_scene(|scene, ctx| {
+                    scene.process_tick(ctx);
+                });
+            }
+            if self.frame_pacer.take_due(Instant::now())
+                && let Some(window) = self.window.as_ref()
+            {
+                window.request_redraw();
+            }
+            self.publish_status();
             self.log_event_loop_state("process_tick_end");
         }
 
+        fn publish_status(&self) {
+            let Some(mode) = self.mode.as_ref() else {
+                return;
+            };
+            let wake = mode.wake();
+            let snapshot = ViewerStatus {
+                state: if self.ambient.is_some() {
+                    ControlViewerState::Ambient
+                } else if mode.kind() == ViewerModeKind::Sleep {
+                    ControlViewerState::Asleep
+                } else {
+                    ControlViewerState::Awake
+                },
+                current_photo: wake.current().map(|img| img.path.clone()),
+                queue_depth: wake.pending().len(),
+                paused: wake.is_paused(),
+            };
+            if snapshot.queue_depth > 0 || snapshot.current_photo.is_some() {
+                self.photo_ready
+                    .send_if_modified(|ready| !std::mem::replace(ready, true));
+            }
+            if self.primary
+                && let Ok(mut status) = self.status.lock()
+            {
+                *status = snapshot;
+            }
+        }
+
+        /// Steps the wake ↔ sleep fade: swaps scenes at its midpoint, applies the
+        /// sleep screen's opacity, and settles into the final mode once complete.
+        fn advance_sleep_fade(&mut self) {
+            let Some(fade) = self.sleep_fade.as_ref() else {
+                return;
+            };
+            let now = Instant::now();
+            let frame = fade.frame(now);
+            let complete = fade.is_complete(now);
+            let direction = fade.direction();
+            match frame {
+                SleepFadeFrame::Slideshow { .. } => {
+                    if self.mode_kind() == ViewerModeKind::Sleep {
+                        self.set_mode(ViewerModeKind::Wake);
+                    }
+                }
+                SleepFadeFrame::SleepScreen { opacity } => {
+                    let entering = self.mode_kind() != ViewerModeKind::Sleep;
+                    if entering {
+                        self.mode_mut().wake_mut().take_redraw_needed();
+                        self.set_mode(ViewerModeKind::Sleep);
+                    }
+                    if let Some(sleep) = self.mode_mut().sleep_mut() {
+                        if entering {
+                            sleep.show_immediately();
+                        }
+                        sleep.set_opacity(opacity);
+                    }
+                }
+            }
+            if complete {
+                self.sleep_fade = None;
+                debug!(?direction, "viewer_sleep_fade_complete");
+                if direction == SleepFadeDirection::ToWake {
+                    if self.library_empty {
+                        // The last photo went away mid-fade.
+                        self.enter_wake();
+                    } else {
+                        // Restart the dwell so the photo that faded back in gets
+                        // its full time on screen.
+                        self.mode_mut().wake_mut().enter_wake();
+                    }
+                }
+            }
+            if let Some(window) = self.window.as_ref()
+                && self.frame_pacer.admit(Instant::now())
+            {
+                window.request_redraw();
+            }
+        }
+
         fn refresh_surface_config_from_window(&mut self) {
             let (Some(window), Some(gpu)) = (self.window.as_ref(), self.gpu.as_mut()) else {
                 return;
             };
+            for mirror in &mut gpu.mirrors {
+                let size = mirror.window.inner_size();
+                if mirror.config.width != size.width || mirror.config.height != size.height {
+                    mirror.resize(&gpu.device, size.width, size.height);
+                }
+            }
             let size = window.inner_size();
             if size.width == 0 || size.height == 0 {
                 gpu.config.width = 0;
@@ -2471,17 +3885,21 @@ pub fn run_windowed(
 
         fn upload_results_now(&mut self, wake: &mut scenes::WakeScene) {
             let gpu = self.gpu.as_ref().unwrap();
+            let (width, height) = gpu.logical_size();
             let expected = compute_canvas_size(
-                gpu.config.width,
-                gpu.config.height,
+                width,
+                height,
                 self.oversample,
                 gpu.limits.max_texture_dimension_2d,
             );
 
+            let mut requeued = Vec::new();
             while let Some(result) = self.ready_results.pop_front() {
                 let path = result.path.clone();
                 let priority = result.priority;
-                // Discard canvases that were prepared for a different surface size.
+                let replaces_current = result.replaces_current;
+                // Canvases prepared for a different surface size are matted
+                // again when their source was kept, and dropped otherwise.
                 if result.canvas.width != expected.0 || result.canvas.height != expected.1 {
                     debug!(
                         path = %path.display(),
@@ -2489,8 +3907,21 @@ pub fn run_windowed(
                         canvas_h = result.canvas.height,
                         expected_w = expected.0,
                         expected_h = expected.1,
-                        "dropping_mismatched_canvas_after_resize"
+                        relayout = result.source.is_some(),
+                        "mismatched_canvas_after_resize"
                     );
+                    if let Some(source) = result.source.as_deref() {
+                        requeued.push(QueuedImage::relayout(source, replaces_current));
+                    }
+                    continue;
+                }
+                if replaces_current {
+                    if wake.current().is_some_and(|current| current.path == path)
+                        && let Some(new_tex) = upload_mat_result(gpu, result)
+                    {
+                        wake.replace_current(new_tex);
+                        debug!(path = %path.display(), "relaid_out_current_image");
+                    }
                     continue;
                 }
                 if let Some(new_tex) = upload_mat_result(gpu, result) {
@@ -2512,6 +3943,9 @@ pub fn run_windowed(
                     );
                 }
             }
+            for queued in requeued.into_iter().rev() {
+                self.deferred_images.push_front(queued);
+            }
         }
 
         fn queue_mat_tasks_for_wake(&mut self, wake: &mut scenes::WakeScene) {
@@ -2530,12 +3964,42 @@ pub fn run_windowed(
                 oversample: self.oversample,
                 max_upscale_factor: self.max_upscale_factor,
                 mat_pipeline: &self.mat_pipeline,
+                rng: &mut self.matting_rng,
             };
             bridge.queue_for_wake(wake);
         }
 
         fn enter_sleep(&mut self) {
+            let from_ambient_photo = self.leave_ambient();
+            if let Some(fade) = self.sleep_fade.as_mut() {
+                if fade.direction() == SleepFadeDirection::ToWake {
+                    info!("viewer: reversing wake fade back toward sleep");
+                    fade.reverse(Instant::now());
+                }
+                return;
+            }
             if self.mode_kind() == ViewerModeKind::Sleep {
+                self.cancel_overlay_exit();
+                return;
+            }
+            if self.overlay_exit == Some(ViewerModeKind::Sleep) {
+                return;
+            }
+            let transition = self.full_config.sleep_screen.effective_transition();
+            if self.mode_kind() == ViewerModeKind::Wake
+                && !transition.is_zero()
+                && !from_ambient_photo
+                && self.mode().wake().current().is_some()
+            {
+                info!(
+                    duration_ms = transition.as_millis() as u64,
+                    "viewer: fading to sleep"
+                );
+                self.sleep_fade = Some(SleepFade::to_sleep(Instant::now(), transition));
+                self.log_event_loop_state("enter_sleep_fade");
+                return;
+            }
+            if self.defer_for_overlay_fade_out(ViewerModeKind::Sleep) {
                 return;
             }
             info!("viewer: entering sleep");
@@ -2545,15 +4009,169 @@ pub fn run_windowed(
         }
 
         fn enter_wake(&mut self) {
-            if self.mode_kind() == ViewerModeKind::Wake {
+            self.leave_ambient();
+            if let Some(fade) = self.sleep_fade.as_mut() {
+                if fade.direction() == SleepFadeDirection::ToSleep {
+                    info!("viewer: woken mid-fade; reversing toward wake");
+                    fade.reverse(Instant::now());
+                }
                 return;
             }
-            info!("viewer: entering wake");
-            self.set_mode(ViewerModeKind::Wake);
+            let target = awake_mode(self.library_empty);
+            if self.mode_kind() == target {
+                // Also brings back an empty-library card that had started (or
+                // finished) fading out toward the slideshow.
+                self.overlay_exit = None;
+                if target == ViewerModeKind::Empty
+                    && let Some(scene) = self.mode_mut().empty_mut()
+                {
+                    scene.resume_fade_in();
+                }
+                return;
+            }
+            if self.overlay_exit == Some(target) {
+                return;
+            }
+            if self.mode_kind() == ViewerModeKind::Sleep
+                && let Some(transition) = self.iris_wake_transition(target)
+            {
+                info!(
+                    duration_ms = transition.as_millis() as u64,
+                    "viewer: opening iris onto the slideshow"
+                );
+                self.sleep_fade = Some(SleepFade::to_wake(Instant::now(), transition));
+                self.log_event_loop_state("enter_wake_fade");
+                return;
+            }
+            if self.defer_for_overlay_fade_out(target) {
+                return;
+            }
+            let from_greeting = self.mode_kind() == ViewerModeKind::Greeting;
+            if target == ViewerModeKind::Empty {
+                info!("viewer: entering wake (library empty)");
+            } else {
+                info!("viewer: entering wake");
+            }
+            self.set_mode(target);
+            if from_greeting && let Some(transition) = self.iris_wake_transition(target) {
+                // The greeting has already faded out; open from black.
+                self.sleep_fade = Some(SleepFade::reveal(Instant::now(), transition));
+            }
             self.log_event_loop_state("enter_wake");
         }
 
+        /// Dims the frame for the `ambient` state: the sleep card at
+        /// `ambient-screen.brightness`, replaced by the configured photo once
+        /// it has decoded. The slideshow holds its place underneath until the
+        /// frame wakes or sleeps.
+        fn enter_ambient(&mut self) {
+            if self.ambient.is_some() {
+                return;
+            }
+            let settings = self.full_config.ambient_screen.clone();
+            info!(
+                brightness = settings.brightness,
+                photo = ?settings.photo,
+                "viewer: entering ambient"
+            );
+            self.sleep_fade = None;
+            self.cancel_overlay_exit();
+            let decoding = settings
+                .photo
+                .clone()
+                .map(|path| spawn_ambient_decode(path, self.configured_surface_size));
+            self.ambient = Some(AmbientView {
+                brightness: settings.brightness,
+                path: settings.photo,
+                decoding,
+                decoded: None,
+                photo: None,
+            });
+            self.mode_mut().wake_mut().take_redraw_needed();
+            self.set_mode(ViewerModeKind::Sleep);
+            if let Some(sleep) = self.mode_mut().sleep_mut() {
+                sleep.show_immediately();
+                sleep.set_opacity(settings.brightness);
+            }
+            self.log_event_loop_state("enter_ambient");
+        }
+
+        /// Ends the ambient state ahead of waking or sleeping. Returns whether
+        /// the ambient photo was on screen, which leaves nothing worth fading.
+        fn leave_ambient(&mut self) -> bool {
+            let Some(ambient) = self.ambient.take() else {
+                return false;
+            };
+            info!("viewer: leaving ambient");
+            if let Some(sleep) = self.mode_mut().sleep_mut() {
+                sleep.set_opacity(1.0);
+            }
+            // Restart the dwell so the slideshow photo gets its full time.
+            self.mode_mut().wake_mut().enter_wake();
+            ambient.photo.is_some()
+        }
+
+        /// Uploads the ambient photo once its decode lands (or again after the
+        /// GPU was rebuilt) and swaps the dimmed sleep card for it.
+        fn poll_ambient_photo(&mut self) {
+            let (Some(ambient), Some(gpu)) = (self.ambient.as_mut(), self.gpu.as_ref()) else {
+                return;
+            };
+            if let Some(decoding) = ambient.decoding.as_ref() {
+                match decoding.try_recv() {
+                    Ok(Ok(canvas)) => ambient.decoded = Some(canvas),
+                    Ok(Err(err)) => {
+                        warn!(
+                            error = ?err,
+                            "viewer: ambient photo unavailable; showing the sleep card"
+                        );
+                    }
+                    Err(TryRecvError::Empty) => return,
+                    Err(TryRecvError::Disconnected) => {}
+                }
+                ambient.decoding = None;
+            }
+            let Some(canvas) = ambient.decoded.take() else {
+                return;
+            };
+            let Some((plane, _texture)) = upload_plane(gpu, &canvas) else {
+                return;
+            };
+            ambient.photo = Some(ImgTex {
+                photo_rect: (0, 0, plane.w, plane.h),
+                plane,
+                path: ambient.path.clone().unwrap_or_default(),
+                mat_kind: None,
+                animation: None,
+                caption: None,
+                canvas: Some(canvas),
+                source: None,
+            });
+            debug!("viewer_ambient_photo_ready");
+            self.set_mode(ViewerModeKind::Wake);
+            self.mode_mut().wake_mut().mark_redraw_needed();
+            if let Some(window) = self.window.as_ref() {
+                window.request_redraw();
+            }
+        }
+
+        /// The `sleep-screen.transition-seconds` an iris wake into `target`
+        /// should take, or `None` when waking should cut straight to it: the
+        /// style is `fade`, the transition is instant, or there is no photo to
+        /// open onto.
+        fn iris_wake_transition(&self, target: ViewerModeKind) -> Option<Duration> {
+            let sleep_screen = &self.full_config.sleep_screen;
+            let transition = sleep_screen.effective_transition();
+            (sleep_screen.transition_style == SleepTransitionStyle::Iris
+                && target == ViewerModeKind::Wake
+                && !transition.is_zero()
+                && self.mode().wake().current().is_some())
+            .then_some(transition)
+        }
+
         fn enter_greeting(&mut self) {
+            self.sleep_fade = None;
+            self.overlay_exit = None;
             if self.mode_kind() != ViewerModeKind::Greeting {
                 info!("viewer: entering greeting");
             }
@@ -2562,6 +4180,89 @@ pub fn run_windowed(
             self.log_event_loop_state("enter_greeting");
         }
 
+        /// Starts the active overlay's `fade-ms` fade-out and parks `target` until
+        /// it finishes. Returns `false` when the mode should switch right away.
+        fn defer_for_overlay_fade_out(&mut self, target: ViewerModeKind) -> bool {
+            if !self.surface_configured {
+                return false;
+            }
+            let mode = self.mode.as_mut().expect("viewer mode not initialized");
+            let still_visible = match mode.kind() {
+                ViewerModeKind::Greeting => mode.greeting_mut().is_some_and(|scene| {
+                    scene.begin_fade_out();
+                    !scene.fade_out_complete()
+                }),
+                ViewerModeKind::Sleep => mode.sleep_mut().is_some_and(|scene| {
+                    scene.begin_fade_out();
+                    !scene.fade_out_complete()
+                }),
+                ViewerModeKind::Empty => mode.empty_mut().is_some_and(|scene| {
+                    scene.begin_fade_out();
+                    !scene.fade_out_complete()
+                }),
+                ViewerModeKind::Wake => false,
+            };
+            if !still_visible {
+                return false;
+            }
+            if self.overlay_exit.replace(target).is_none() {
+                debug!(?target, "viewer_overlay_fade_out_started");
+            }
+            true
+        }
+
+        /// Turns a pending overlay fade-out back around.
+        fn cancel_overlay_exit(&mut self) {
+            if self.overlay_exit.take().is_none() {
+                return;
+            }
+            debug!("viewer_overlay_fade_out_cancelled");
+            let mode = self.mode_mut();
+            match mode.kind() {
+                ViewerModeKind::Greeting => {
+                    if let Some(scene) = mode.greeting_mut() {
+                        scene.resume_fade_in();
+                    }
+                }
+                ViewerModeKind::Sleep => {
+                    if let Some(scene) = mode.sleep_mut() {
+                        scene.resume_fade_in();
+                    }
+                }
+                ViewerModeKind::Empty => {
+                    if let Some(scene) = mode.empty_mut() {
+                        scene.resume_fade_in();
+                    }
+                }
+                ViewerModeKind::Wake => {}
+            }
+        }
+
+        /// Completes a deferred mode switch once the overlay has faded out.
+        fn finish_overlay_exit(&mut self) {
+            let Some(target) = self.overlay_exit else {
+                return;
+            };
+            let mode = self.mode();
+            let faded_out = match mode.kind() {
+                ViewerModeKind::Greeting => {
+                    mode.greeting().is_none_or(GreetingScene::fade_out_complete)
+                }
+                ViewerModeKind::Sleep => mode.sleep().is_none_or(SleepScene::fade_out_complete),
+                ViewerModeKind::Empty => mode.empty().is_none_or(EmptyScene::fade_out_complete),
+                ViewerModeKind::Wake => true,
+            };
+            if !faded_out {
+                return;
+            }
+            self.overlay_exit = None;
+            match target {
+                ViewerModeKind::Wake | ViewerModeKind::Empty => self.enter_wake(),
+                ViewerModeKind::Sleep => self.enter_sleep(),
+                ViewerModeKind::Greeting => self.enter_greeting(),
+            }
+        }
+
         fn handle_surface_timeout(&mut self, event_loop: &ActiveEventLoop) {
             const MAX_CONSECUTIVE_TIMEOUTS: u32 = 6;
 
@@ -2583,32 +4284,91 @@ pub fn run_windowed(
             }
         }
 
-        fn record_frame_presented(&mut self) {
+        /// Rebuilds the GPU after the device was lost. Unlike a surface
+        /// timeout this keeps the current mode, and the photos on screen and
+        /// queued are uploaded again from their retained canvases so the
+        /// slideshow carries on where it was.
+        fn recover_lost_device(&mut self, event_loop: &ActiveEventLoop) {
+            self.device_recoveries = self.device_recoveries.saturating_add(1);
+            warn!(
+                recoveries = self.device_recoveries,
+                "viewer_gpu_device_recovering"
+            );
+            let (current, queued) = {
+                let wake = self.mode_mut().wake_mut();
+                let current = wake.take_current();
+                let mut queued: Vec<ImgTex> = wake.take_next().into_iter().collect();
+                queued.extend(wake.pending_mut().drain(..));
+                (current, queued)
+            };
+            self.transition_frame_stats = None;
+            self.teardown_gpu();
+            if !self.ensure_gpu_ready(event_loop, "device_lost_recover") {
+                return;
+            }
+            let Some(gpu) = self.gpu.as_ref() else {
+                return;
+            };
+            let current = current.and_then(|img| reupload_image(gpu, img));
+            let queued: Vec<ImgTex> = queued
+                .into_iter()
+                .filter_map(|img| reupload_image(gpu, img))
+                .collect();
+            let restored = usize::from(current.is_some()) + queued.len();
+            let wake = self.mode_mut().wake_mut();
+            if current.is_some() {
+                wake.set_displayed_at(Some(Instant::now()));
+            }
+            wake.set_current(current);
+            wake.pending_mut().extend(queued);
+            wake.mark_redraw_needed();
+            info!(
+                recoveries = self.device_recoveries,
+                restored, "viewer_gpu_device_recovered"
+            );
+        }
+
+        /// `interval` is the gap since the previous frame of a playing
+        /// transition; see `note_transition_frame`.
+        fn record_frame_presented(&mut self, interval: Option<Duration>) {
             self.surface_timeout_streak = 0;
+            self.frame_pacer.presented(Instant::now());
+            if self.primary {
+                let refresh = self.refresh_interval;
+                RuntimeMetrics::update(&self.metrics, |m| m.record_frame(interval, refresh));
+            }
         }
 
         /// Track per-frame cadence while a transition plays; log a summary
         /// once it ends (or hands over to a different transition kind).
-        fn note_transition_frame(&mut self, active: Option<TransitionKind>) {
+        /// Returns the gap since the transition's previous frame.
+        fn note_transition_frame(&mut self, active: Option<TransitionKind>) -> Option<Duration> {
             let now = Instant::now();
             match (self.transition_frame_stats.take(), active) {
                 (None, Some(kind)) => {
                     self.transition_frame_stats = Some(TransitionFrameStats::begin(kind, now));
+                    None
                 }
                 (Some(mut stats), Some(kind)) if stats.kind == kind => {
-                    let dt_ms = now.duration_since(stats.last_frame).as_secs_f32() * 1000.0;
+                    let dt = now.duration_since(stats.last_frame);
+                    let dt_ms = dt.as_secs_f32() * 1000.0;
                     stats.worst_frame_ms = stats.worst_frame_ms.max(dt_ms);
                     stats.best_frame_ms = stats.best_frame_ms.min(dt_ms);
                     stats.frames += 1;
                     stats.last_frame = now;
                     self.transition_frame_stats = Some(stats);
+                    Some(dt)
                 }
                 (Some(stats), Some(kind)) => {
-                    stats.log();
+                    stats.finish(&self.metrics);
                     self.transition_frame_stats = Some(TransitionFrameStats::begin(kind, now));
+                    None
+                }
+                (Some(stats), None) => {
+                    stats.finish(&self.metrics);
+                    None
                 }
-                (Some(stats), None) => stats.log(),
-                (None, None) => {}
+                (None, None) => None,
             }
         }
     }
@@ -2679,12 +4439,14 @@ pub fn run_windowed(
                             });
                         }
                         if ready {
+                            let layout_size =
+                                rotated_size(self.full_config.display.rotation, new_size);
                             if let Some(cap) = self.caption_overlay.as_mut() {
-                                cap.resize(new_size);
+                                cap.resize(layout_size);
                             }
                             let scale_factor = window.scale_factor();
                             let _ = self.with_active_scene(|scene, ctx| {
-                                scene.handle_resize(ctx, new_size, scale_factor);
+                                scene.handle_resize(ctx, layout_size, scale_factor);
                             });
                         } else {
                             debug!(
@@ -2740,8 +4502,9 @@ pub fn run_windowed(
                             });
                         }
                         if ready {
+                            let layout_size = rotated_size(self.full_config.display.rotation, size);
                             let _ = self.with_active_scene(|scene, ctx| {
-                                scene.handle_scale_factor_changed(ctx, size, scale_factor);
+                                scene.handle_scale_factor_changed(ctx, layout_size, scale_factor);
                             });
                         } else {
                             debug!(
@@ -2781,6 +4544,11 @@ pub fn run_windowed(
                             .sleep()
                             .map(SleepScene::needs_redraw)
                             .unwrap_or(false),
+                        ViewerModeKind::Empty => self
+                            .mode()
+                            .empty()
+                            .map(EmptyScene::needs_redraw)
+                            .unwrap_or(false),
                         ViewerModeKind::Wake => false,
                     };
                     let (pending_redraw, queue_depth, has_transition) = {
@@ -2801,14 +4569,21 @@ pub fn run_windowed(
                         has_transition,
                         "viewer_window_redraw_requested"
                     );
-                    if matches!(mode_kind, ViewerModeKind::Greeting | ViewerModeKind::Sleep)
-                        && !overlay_pending
+                    if matches!(
+                        mode_kind,
+                        ViewerModeKind::Greeting | ViewerModeKind::Sleep | ViewerModeKind::Empty
+                    ) && !overlay_pending
                     {
                         return;
                     }
-                    if matches!(mode_kind, ViewerModeKind::Greeting | ViewerModeKind::Sleep) {
-                        let size = window.inner_size();
+                    if matches!(
+                        mode_kind,
+                        ViewerModeKind::Greeting | ViewerModeKind::Sleep | ViewerModeKind::Empty
+                    ) {
+                        let size =
+                            rotated_size(self.full_config.display.rotation, window.inner_size());
                         let scale_factor = window.scale_factor();
+                        let message_context = self.message_context();
                         let layout_ready = {
                             let Some(mode) = self.mode.as_mut() else {
                                 return;
@@ -2820,7 +4595,7 @@ pub fn run_windowed(
                                             .full_config
                                             .greeting_screen
                                             .screen()
-                                            .message_or_default()
+                                            .message_or_default(&message_context)
                                             .into_owned();
                                         screen.resize(size, scale_factor);
                                         screen.set_message(message);
@@ -2834,9 +4609,21 @@ pub fn run_windowed(
                                         let message = self
                                             .full_config
                                             .sleep_screen
-                                            .screen()
-                                            .message_or_default()
-                                            .into_owned();
+                                            .message_with_clock(&message_context);
+                                        screen.resize(size, scale_factor);
+                                        screen.set_message(message);
+                                        screen.ensure_layout_ready()
+                                    } else {
+                                        false
+                                    }
+                                }
+                                ViewerModeKind::Empty => {
+                                    if let Some(screen) = mode.empty_mut() {
+                                        let message =
+                                            self.full_config.empty_library_screen.message_for(
+                                                &message_context,
+                                                &self.full_config.library_paths(),
+                                            );
                                         screen.resize(size, scale_factor);
                                         screen.set_message(message);
                                         screen.ensure_layout_ready()
@@ -2956,12 +4743,37 @@ pub fn run_windowed(
                         }
                         return;
                     };
+                    let frame_started = self.debug_overlay.is_some().then(Instant::now);
+                    let ambient_photo = self
+                        .ambient
+                        .as_ref()
+                        .and_then(|ambient| Some((ambient.photo.as_ref()?, ambient.brightness)));
+                    // The ambient photo is held partway down the sleep fade.
+                    let sleep_fade_black = match self.sleep_fade.as_ref() {
+                        Some(fade) => match fade.frame(Instant::now()) {
+                            SleepFadeFrame::Slideshow { black } => Some(black),
+                            SleepFadeFrame::SleepScreen { .. } => Some(1.0),
+                        },
+                        None => ambient_photo.map(|(_, brightness)| 1.0 - brightness),
+                    };
+                    let ambient_photo = ambient_photo.map(|(photo, _)| photo);
+                    let sleep_iris = self.sleep_fade.is_some()
+                        && self.full_config.sleep_screen.transition_style
+                            == SleepTransitionStyle::Iris;
                     let (Some(gpu), Some(mode)) = (self.gpu.as_mut(), self.mode.as_mut()) else {
                         return;
                     };
-                    let view = frame
+                    gpu.ensure_rotated_target();
+                    let surface_view = frame
                         .texture
                         .create_view(&wgpu::TextureViewDescriptor::default());
+                    // Under `display.rotation` or with mirrors every mode draws
+                    // upright offscreen and `finish_frame` turns the result onto
+                    // each surface.
+                    let view = gpu
+                        .rotated_target
+                        .as_ref()
+                        .map_or_else(|| surface_view.clone(), |target| target.view.clone());
                     let mut encoder =
                         gpu.device
                             .create_command_encoder(&wgpu::CommandEncoderDescriptor {
@@ -2982,10 +4794,9 @@ pub fn run_windowed(
                                 return;
                             }
 
-                            gpu.queue.submit(Some(encoder.finish()));
-                            frame.present();
+                            gpu.finish_frame(encoder, &surface_view, frame);
                             screen.after_submit();
-                            self.record_frame_presented();
+                            self.record_frame_presented(None);
                         }
                         ViewerModeKind::Greeting => {
                             let Some(screen) = mode.greeting_mut() else {
@@ -3000,13 +4811,32 @@ pub fn run_windowed(
                                 return;
                             }
 
-                            gpu.queue.submit(Some(encoder.finish()));
-                            frame.present();
+                            gpu.finish_frame(encoder, &surface_view, frame);
+                            screen.after_submit();
+                            self.record_frame_presented(None);
+                        }
+                        ViewerModeKind::Empty => {
+                            let Some(screen) = mode.empty_mut() else {
+                                return;
+                            };
+                            encoder.push_debug_group("empty-library-banner");
+                            let rendered = screen.render(&mut encoder, &view);
+                            encoder.pop_debug_group();
+
+                            if !rendered {
+                                debug!("empty_library_banner_render_deferred");
+                                return;
+                            }
+
+                            gpu.finish_frame(encoder, &surface_view, frame);
                             screen.after_submit();
-                            self.record_frame_presented();
+                            self.record_frame_presented(None);
                         }
                         ViewerModeKind::Wake => {
                             let wake = mode.wake_mut();
+                            if ambient_photo.is_none() {
+                                wake.advance_animations(&gpu.queue, Instant::now());
+                            }
                             encoder.push_debug_group("wake-draw");
                             // Created up front: later code holds immutable
                             // borrows of gpu for the bind groups.
@@ -3018,14 +4848,22 @@ pub fn run_windowed(
                                     gpu.ensure_iris_layer();
                                 }
                             }
-                            let screen_w = gpu.config.width as f32;
-                            let screen_h = gpu.config.height as f32;
+                            if sleep_iris {
+                                gpu.ensure_iris_layer();
+                            }
+                            let (layout_w, layout_h) = gpu.logical_size();
+                            let screen_w = layout_w as f32;
+                            let screen_h = layout_h as f32;
                             let mut uniforms = TransitionUniforms {
                                 screen_size: [screen_w, screen_h],
                                 progress: 0.0,
                                 kind: 0,
                                 current_dest: [0.0; 4],
                                 next_dest: [0.0; 4],
+                                current_photo: [0.0; 4],
+                                current_view: [0.0; 4],
+                                next_photo: [0.0; 4],
+                                next_view: [0.0; 4],
                                 params0: [0.0; 4],
                                 params1: [0.0; 4],
                                 params3: [0.0; 4],
@@ -3035,6 +4873,7 @@ pub fn run_windowed(
                                     self.clear_color.b as f32,
                                     1.0,
                                 ],
+                                iris_stroke: [0.0; 4],
                                 petals_a: [[0.0; 4]; 16],
                                 petals_b: [[0.0; 4]; 16],
                             };
@@ -3043,31 +4882,32 @@ pub fn run_windowed(
                             let mut have_current = false;
                             let mut have_next = false;
 
-                            if let Some(cur) = wake.current() {
-                                let rect = compute_cover_rect(
-                                    cur.plane.w,
-                                    cur.plane.h,
-                                    gpu.config.width,
-                                    gpu.config.height,
-                                );
-                                uniforms.current_dest = rect_to_uniform(rect);
+                            let resting = match ambient_photo {
+                                Some(photo) => Some((photo, motion::Framing::IDENTITY)),
+                                None => wake.current().map(|cur| (cur, wake.current_framing())),
+                            };
+                            if let Some((cur, framing)) = resting {
+                                (
+                                    uniforms.current_dest,
+                                    uniforms.current_photo,
+                                    uniforms.current_view,
+                                ) = cur.framed_uniforms(layout_w, layout_h, framing);
                                 current_bind = &cur.plane.bind;
                                 have_current = true;
                             }
-                            if let Some(next) = wake.next() {
-                                let rect = compute_cover_rect(
-                                    next.plane.w,
-                                    next.plane.h,
-                                    gpu.config.width,
-                                    gpu.config.height,
-                                );
-                                uniforms.next_dest = rect_to_uniform(rect);
+                            if let Some(next) = wake.next().filter(|_| ambient_photo.is_none()) {
+                                (uniforms.next_dest, uniforms.next_photo, uniforms.next_view) =
+                                    next.framed_uniforms(layout_w, layout_h, wake.next_framing());
                                 next_bind = &next.plane.bind;
                                 have_next = true;
                             }
 
-                            let active_transition =
-                                wake.transition_state().map(|state| state.kind());
+                            // A sleep fade freezes the slideshow on the current
+                            // photo, so any interrupted transition is not drawn.
+                            let active_transition = wake
+                                .transition_state()
+                                .filter(|_| sleep_fade_black.is_none())
+                                .map(|state| state.kind());
                             let mut should_draw_quad = false;
                             let debug_bezier = std::env::var("PHOTOFRAME_DEBUG_BEZIER")
                                 .map(|s| matches!(s.as_str(), "1" | "true" | "yes" | "on"))
@@ -3084,6 +4924,31 @@ pub fn run_windowed(
                                 // Red stroke by default
                                 uniforms.params3 = [1.0, 0.1, 0.1, 1.0];
                                 should_draw_quad = have_current;
+                            } else if let Some(black) = sleep_fade_black {
+                                should_draw_quad = have_current;
+                                uniforms.progress = 0.5 * black;
+                                uniforms.next_dest = [0.0; 4];
+                                next_bind = &gpu.blank_plane.bind;
+                                if sleep_iris {
+                                    // The closing half of an iris transition
+                                    // into a blank frame; waking runs it
+                                    // backwards, opening onto the photo. It
+                                    // closes fully so the sleep screen takes
+                                    // over from solid petals.
+                                    uniforms.kind = TransitionKind::Iris.as_index();
+                                    let iris = IrisLook {
+                                        min_aperture: 0.0,
+                                        ..IrisLook::from(&IrisTransition::default())
+                                    };
+                                    write_iris_uniforms(&mut uniforms, &iris, screen_w, screen_h);
+                                } else {
+                                    // Fade the resting photo toward black ahead
+                                    // of the sleep screen: the fade-through-black
+                                    // arm with no incoming photo, stopped at its
+                                    // black midpoint.
+                                    uniforms.kind = TransitionKind::Fade.as_index();
+                                    uniforms.params0[0] = 1.0;
+                                }
                             } else if let Some(state) = wake.transition_state() {
                                 should_draw_quad = have_current || have_next;
                                 let base_progress = state.progress();
@@ -3105,11 +4970,10 @@ pub fn run_windowed(
                                         uniforms.params1[0] = *softness;
                                     }
                                     ActiveTransition::Push { direction } => {
-                                        let direction = *direction;
-                                        let diag =
-                                            (screen_w * screen_w + screen_h * screen_h).sqrt();
-                                        uniforms.params0[0] = direction[0] * diag;
-                                        uniforms.params0[1] = direction[1] * diag;
+                                        let offset =
+                                            compute_push_offset(*direction, screen_w, screen_h);
+                                        uniforms.params0[0] = offset[0];
+                                        uniforms.params0[1] = offset[1];
                                     }
                                     ActiveTransition::EInk {
                                         flash_count,
@@ -3164,67 +5028,16 @@ pub fn run_windowed(
                                         uniforms.params0[2] =
                                             if *next_zooms_in { 1.0 } else { 0.0 };
                                     }
-                                    ActiveTransition::Iris {
-                                        blades,
-                                        color,
-                                        petal_sheen,
-                                        overlap_shadow,
-                                        min_aperture,
-                                        swirl,
-                                    } => {
-                                        // Solve the petal kinematics here so the
-                                        // per-pixel shader loop is transcendental-free.
-                                        // Geometry derivation: r_in circumscribes the
-                                        // screen, petals are annular arcs (band r_in..
-                                        // 2*r_in) with semicircular end caps, pivoting
-                                        // by `psi` about the center of the trailing cap.
-                                        let n = (*blades).clamp(1, 16) as usize;
-                                        let t = uniforms.progress;
-                                        let x = if t < 0.5 { t * 2.0 } else { 2.0 - t * 2.0 };
-                                        let f = x * x * (3.0 - 2.0 * x);
-                                        let r_in = 1.02
-                                            * 0.5
-                                            * (screen_w * screen_w + screen_h * screen_h).sqrt();
-                                        let r_mid = 1.5 * r_in;
-                                        let e = f * r_in * (1.0 - min_aperture);
-                                        let psi = 2.0 * (e / (2.0 * r_mid)).clamp(0.0, 1.0).asin();
-                                        let spin = swirl * psi;
-                                        let sigma =
-                                            std::f32::consts::TAU / n as f32 + IRIS_EXTRA_WIDTH_RAD;
-                                        // Photos crossfade behind the petals around full close.
-                                        let swap = ((t - 0.42) / 0.16).clamp(0.0, 1.0);
-                                        let swap = swap * swap * (3.0 - 2.0 * swap);
-                                        uniforms.params0 =
-                                            [n as f32, *petal_sheen, *overlap_shadow, swap];
-                                        // Inscribed aperture radius: pixels closer to
-                                        // center than this are provably petal-free.
-                                        uniforms.params1 = [r_in - e, color[0], color[1], color[2]];
-                                        // Petal-layer upscale factor: keeps the edge
-                                        // feather at least one layer texel wide.
-                                        uniforms.params3[0] = iris_layer_scale() as f32;
-                                        let (s_psi, c_psi) = psi.sin_cos();
-                                        for i in 0..n {
-                                            let ai =
-                                                std::f32::consts::TAU * i as f32 / n as f32 + spin;
-                                            let (s_ai, c_ai) = ai.sin_cos();
-                                            let piv = [r_mid * c_ai, r_mid * s_ai];
-                                            // Annulus center after swinging about the pivot.
-                                            let center = [
-                                                piv[0] - (c_psi * piv[0] - s_psi * piv[1]),
-                                                piv[1] - (s_psi * piv[0] + c_psi * piv[1]),
-                                            ];
-                                            let trail = ai + psi;
-                                            let tip = trail + sigma;
-                                            // Directional sheen: cos of the petal's facing
-                                            // angle against a fixed light, constant across a
-                                            // petal. Baked raw here; the shader scales it by
-                                            // petal_sheen and fades it near full closure.
-                                            let facing = (ai + 0.5 * sigma + psi - 2.3).cos();
-                                            uniforms.petals_a[i] =
-                                                [center[0], center[1], tip.cos(), tip.sin()];
-                                            uniforms.petals_b[i] =
-                                                [trail.cos(), trail.sin(), facing, 0.0];
-                                        }
+                                    ActiveTransition::CrossfadeBlur { strength } => {
+                                        uniforms.params0[0] = *strength;
+                                    }
+                                    ActiveTransition::Iris(iris) => {
+                                        write_iris_uniforms(
+                                            &mut uniforms,
+                                            iris,
+                                            screen_w,
+                                            screen_h,
+                                        );
                                     }
                                 }
                             } else if have_current {
@@ -3343,22 +5156,104 @@ pub fn run_windowed(
                                 let mat_kind = wake.current().and_then(|img| img.mat_kind);
                                 let text = scenes::showcase_caption(transition_kind, mat_kind);
                                 cap.set_text(text);
-                                let surface_size = winit::dpi::PhysicalSize::new(
-                                    gpu.config.width,
-                                    gpu.config.height,
-                                );
+                                let (width, height) = gpu.logical_size();
+                                let surface_size = winit::dpi::PhysicalSize::new(width, height);
                                 cap.resize(surface_size);
                                 cap.render(&mut encoder, &view);
                             }
+                            // Hidden while the photo fades out for sleep.
+                            if sleep_fade_black.is_none() {
+                                let (width, height) = gpu.logical_size();
+                                let surface_size = winit::dpi::PhysicalSize::new(width, height);
+                                wake.render_photo_caption(&mut encoder, &view, surface_size);
+                                if let Some(overlay) = self.share_qr_overlay.as_mut()
+                                    && let Some(current) = wake.current()
+                                {
+                                    overlay.render(
+                                        &mut encoder,
+                                        &view,
+                                        &current.path,
+                                        surface_size,
+                                    );
+                                }
+                                if let Some((text, _)) = self.notice.as_ref() {
+                                    let overlay = self.notice_overlay.get_or_insert_with(|| {
+                                        // Along the top, clear of the photo caption.
+                                        let corner = match self.full_config.caption.corner {
+                                            CaptionCorner::TopLeft => CaptionCorner::TopRight,
+                                            _ => CaptionCorner::TopLeft,
+                                        };
+                                        let style = scenes::CaptionStyle {
+                                            corner,
+                                            ..scenes::CaptionStyle::default()
+                                        };
+                                        scenes::CaptionOverlay::with_style(
+                                            &gpu.device,
+                                            &gpu.queue,
+                                            gpu.config.format,
+                                            style,
+                                        )
+                                    });
+                                    overlay.set_text(text.as_str());
+                                    overlay.resize(surface_size);
+                                    overlay.render(&mut encoder, &view);
+                                }
+                                if self.full_config.pause_indicator.enabled && wake.is_paused() {
+                                    let overlay = self.pause_overlay.get_or_insert_with(|| {
+                                        let style = scenes::CaptionStyle {
+                                            corner: self.full_config.pause_indicator.corner,
+                                            ..scenes::CaptionStyle::default()
+                                        };
+                                        scenes::CaptionOverlay::with_style(
+                                            &gpu.device,
+                                            &gpu.queue,
+                                            gpu.config.format,
+                                            style,
+                                        )
+                                    });
+                                    let text = match self.pause_reason.as_deref() {
+                                        Some(reason) => format!("Paused — {reason}"),
+                                        None => "Paused".to_string(),
+                                    };
+                                    overlay.set_text(text);
+                                    overlay.resize(surface_size);
+                                    overlay.render(&mut encoder, &view);
+                                }
+                            }
+                            if let Some(debug) = self.debug_overlay.as_mut() {
+                                let (width, height) = gpu.logical_size();
+                                debug.render(
+                                    &gpu.device,
+                                    &gpu.queue,
+                                    gpu.config.format,
+                                    &mut encoder,
+                                    &view,
+                                    winit::dpi::PhysicalSize::new(width, height),
+                                );
+                            }
 
-                            gpu.queue.submit(Some(encoder.finish()));
-                            frame.present();
+                            gpu.finish_frame(encoder, &surface_view, frame);
                             if let Some(cap) = self.caption_overlay.as_mut() {
                                 cap.after_submit();
                             }
+                            if let Some(overlay) = self.notice_overlay.as_mut() {
+                                overlay.after_submit();
+                            }
+                            if let Some(overlay) = self.pause_overlay.as_mut() {
+                                overlay.after_submit();
+                            }
+                            if let Some(overlay) = self.share_qr_overlay.as_mut() {
+                                overlay.after_submit();
+                            }
+                            if let Some(debug) = self.debug_overlay.as_mut() {
+                                debug.after_submit();
+                                if let Some(started) = frame_started {
+                                    debug.record_frame(started.elapsed());
+                                }
+                            }
                             wake.after_present();
-                            self.record_frame_presented();
-                            self.note_transition_frame(active_transition);
+                            let interval = self.note_transition_frame(active_transition);
+                            self.record_frame_presented(interval);
                         }
                     }
                 }
@@ -3397,6 +5292,91 @@ pub fn run_windowed(
         }
     }
 
+    /// The viewer windows sharing the event loop: one `App`, or one per
+    /// connected output with `displays.mode: independent`. Window events go to
+    /// the window's own `App`; everything else goes to each of them.
+    struct Outputs {
+        apps: Vec<App>,
+        /// Set once the first `resumed` has dropped unconnected outputs.
+        checked: bool,
+    }
+
+    impl Outputs {
+        /// Drops the independent windows whose output is not connected, keeping
+        /// the first when none is, and makes the first left the primary.
+        fn retain_connected(&mut self, event_loop: &ActiveEventLoop) {
+            if self.apps.len() > 1 {
+                let names: Vec<Option<String>> = event_loop
+                    .available_monitors()
+                    .map(|monitor| monitor.name())
+                    .collect();
+                let (connected, missing): (Vec<App>, Vec<App>) = std::mem::take(&mut self.apps)
+                    .into_iter()
+                    .partition(|app| !pick_outputs(&app.outputs, &names).is_empty());
+                for app in &missing {
+                    warn!(
+                        configured = ?app.outputs,
+                        available = ?names,
+                        "displays.outputs entry is not connected; no slideshow runs for it"
+                    );
+                }
+                self.apps = if connected.is_empty() {
+                    missing.into_iter().take(1).collect()
+                } else {
+                    connected
+                };
+            }
+            for (idx, app) in self.apps.iter_mut().enumerate() {
+                app.primary = idx == 0;
+            }
+        }
+    }
+
+    impl ApplicationHandler<ViewerEvent> for Outputs {
+        fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+            if !self.checked {
+                self.checked = true;
+                self.retain_connected(event_loop);
+            }
+            for app in &mut self.apps {
+                app.resumed(event_loop);
+            }
+        }
+
+        fn suspended(&mut self, event_loop: &ActiveEventLoop) {
+            for app in &mut self.apps {
+                app.suspended(event_loop);
+            }
+        }
+
+        fn window_event(
+            &mut self,
+            event_loop: &ActiveEventLoop,
+            window_id: WindowId,
+            event: WindowEvent,
+        ) {
+            if let Some(app) = self.apps.iter_mut().find(|app| {
+                app.window
+                    .as_ref()
+                    .is_some_and(|window| window.id() == window_id)
+            }) {
+                app.window_event(event_loop, window_id, event);
+            }
+        }
+
+        fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+            for app in &mut self.apps {
+                app.about_to_wait(event_loop);
+            }
+        }
+
+        fn user_event(&mut self, event_loop: &ActiveEventLoop, event: ViewerEvent) {
+            for app in &mut self.apps {
+                app.user_event(event_loop, event.clone());
+            }
+        }
+    }
+
     // winit permits only one EventLoop per process, even after a failed attempt,
     // so retrying in-process can never recover — it would just surface a confusing
     // "EventLoop can't be recreated" error. Build once and, on failure, return an
@@ -3422,8 +5402,7 @@ pub fn run_windowed(
         .map(|n| n.get())
         .unwrap_or(2)
         .max(1);
-    let pipeline_capacity = cfg.viewer_preload_count.max(2);
-    let mat_pipeline = MattingPipeline::new(worker_count, pipeline_capacity);
+    let pipeline_capacity = (cfg.viewer_preload_count + preload::MAX_EXTRA_DEPTH).max(2);
     let clear_color = cfg
         .matting
         .primary_option()
@@ -3451,36 +5430,109 @@ pub fn run_windowed(
         })
     };
     let control_driver = tokio::spawn(drive_viewer_events(command_rx, control_cancel, proxy));
-    let initial_wake =
-        scenes::WakeScene::new(cfg.global_photo_settings.dwell_ms, cfg.transition.clone());
-    let mut app = App {
-        from_loader,
-        to_manager_displayed,
-        cancel,
-        window: None,
-        gpu: None,
-        surface_configured: false,
-        surface_gate: SurfaceReadyGate::default(),
-        pending_scene_enter: true,
-        mode: Some(ViewerMode::new(ViewerModeKind::Greeting, initial_wake)),
-        preload_count: cfg.viewer_preload_count,
-        oversample: cfg.global_photo_settings.oversample,
-        max_upscale_factor: cfg.global_photo_settings.max_upscale_factor,
-        matting: cfg.matting.clone(),
-        mat_pipeline,
-        mat_inflight: 0,
-        ready_results: VecDeque::new(),
-        deferred_images: VecDeque::new(),
-        clear_color,
-        rng: rand::rng(),
-        full_config: cfg,
-        surface_timeout_streak: 0,
-        configured_surface_size: None,
-        caption_overlay: None,
-        transition_frame_stats: None,
+    if let Some(max_fps) = cfg.frame_pacing.max_fps {
+        info!(max_fps, "viewer_frame_rate_capped");
+    }
+    // Independent outputs each get a window with its own slideshow, fed in
+    // turn from the loader; otherwise one window mirrors onto the others.
+    let displays = cfg.displays.clone().unwrap_or_default();
+    let window_outputs: Vec<Vec<String>> = match displays.mode {
+        DisplayMode::Independent => displays
+            .outputs
+            .iter()
+            .map(|output| vec![output.clone()])
+            .collect(),
+        DisplayMode::Mirror => vec![displays.outputs.clone()],
+    };
+    let (from_loaders, distributor) = if window_outputs.len() > 1 {
+        let (senders, receivers): (Vec<_>, Vec<_>) = window_outputs
+            .iter()
+            .map(|_| tokio::sync::mpsc::channel(cfg.viewer_preload_count.max(1)))
+            .unzip();
+        let distributor = tokio::spawn(distribute_round_robin(from_loader, senders));
+        (receivers, Some(distributor))
+    } else {
+        (vec![from_loader], None)
     };
-    app.enter_greeting();
-    event_loop.run_app(&mut app)?;
+    let worker_count = (worker_count / window_outputs.len()).max(1);
+    let mut apps = Vec::with_capacity(window_outputs.len());
+    for (idx, (outputs, from_loader)) in window_outputs.into_iter().zip(from_loaders).enumerate() {
+        // Offset the seeds so independent outputs don't move and mat in step.
+        let offset = idx as u64;
+        let initial_wake =
+            scenes::WakeScene::new(cfg.global_photo_settings.dwell_ms, cfg.transition.clone())
+                .with_motion(
+                    cfg.motion.clone(),
+                    playlist_seed.map(|seed| seed.wrapping_add(offset)),
+                )
+                .with_animation_loops(cfg.animations.loop_count);
+        let mut app = App {
+            from_loader,
+            to_manager_displayed: to_manager_displayed.clone(),
+            cancel: cancel.clone(),
+            window: None,
+            outputs,
+            mirror: displays.mode == DisplayMode::Mirror,
+            mirror_windows: Vec::new(),
+            output: None,
+            primary: idx == 0,
+            gpu: None,
+            surface_configured: false,
+            surface_gate: SurfaceReadyGate::default(),
+            pending_scene_enter: true,
+            mode: Some(ViewerMode::new(ViewerModeKind::Greeting, initial_wake)),
+            preload_count: cfg.viewer_preload_count,
+            oversample: cfg.global_photo_settings.oversample,
+            max_upscale_factor: cfg.global_photo_settings.max_upscale_factor,
+            matting: cfg.matting.clone(),
+            mat_pipeline: MattingPipeline::new(worker_count, pipeline_capacity, metrics.clone()),
+            matting_rng: matting_seed.map_or_else(StdRng::from_os_rng, |seed| {
+                StdRng::seed_from_u64(seed.wrapping_add(offset))
+            }),
+            mat_inflight: 0,
+            ready_results: VecDeque::new(),
+            deferred_images: VecDeque::new(),
+            clear_color,
+            rng: rand::rng(),
+            full_config: Arc::clone(&cfg),
+            library_size: Arc::clone(&library_size),
+            scan_progress: (0, false),
+            library_empty: false,
+            surface_timeout_streak: 0,
+            device_lost: Arc::new(AtomicBool::new(false)),
+            device_recoveries: 0,
+            configured_surface_size: None,
+            caption_overlay: None,
+            notice: None,
+            notice_overlay: None,
+            pause_reason: None,
+            pause_overlay: None,
+            share_qr_overlay: None,
+            debug_overlay: cfg
+                .debug_overlay
+                .enabled
+                .then(|| DebugOverlay::new(cfg.debug_overlay.corner)),
+            transition_frame_stats: None,
+            sleep_fade: None,
+            overlay_exit: None,
+            ambient: None,
+            status: Arc::clone(&status),
+            photo_ready: photo_ready.clone(),
+            metrics: Arc::clone(&metrics),
+            refresh_interval: Duration::from_micros(16_667),
+            frame_pacer: Rc::new(FramePacer::new(cfg.frame_pacing.min_frame_interval())),
+        };
+        app.enter_greeting();
+        apps.push(app);
+    }
+    let mut outputs = Outputs {
+        apps,
+        checked: false,
+    };
+    event_loop.run_app(&mut outputs)?;
+    if let Some(distributor) = distributor {
+        distributor.abort();
+    }
 
     drop(command_tx);
     control_driver.abort();
@@ -3530,6 +5582,7 @@ pub mod testkit {
         from_loader_tx: mpsc::Sender<PhotoLoaded>,
         from_loader_rx: mpsc::Receiver<PhotoLoaded>,
         mat_pipeline: MattingPipeline,
+        matting_rng: StdRng,
         wake: scenes::WakeScene,
         oversample: f32,
         max_upscale_factor: f32,
@@ -3561,7 +5614,12 @@ pub mod testkit {
                 ready_results: VecDeque::new(),
                 from_loader_tx,
                 from_loader_rx,
-                mat_pipeline: MattingPipeline::new(1, preload_count.max(2)),
+                mat_pipeline: MattingPipeline::new(
+                    1,
+                    preload_count.max(2),
+                    RuntimeMetrics::shared(),
+                ),
+                matting_rng: StdRng::from_os_rng(),
                 wake: scenes::WakeScene::new(dwell_ms, transition_cfg),
                 oversample,
                 max_upscale_factor: 1.0,
@@ -3588,8 +5646,15 @@ pub mod testkit {
         }
 
         pub fn push_deferred(&mut self, image: PreparedImageCpu, priority: bool) {
-            self.deferred_images
-                .push_back(QueuedImage { image, priority });
+            self.deferred_images.push_back(QueuedImage {
+                image,
+                animation: None,
+                priority,
+                caption: None,
+                sidecar_matting: None,
+                prep_time: Duration::ZERO,
+                replaces_current: false,
+            });
         }
 
         pub fn queue_once(&mut self) {
@@ -3604,6 +5669,7 @@ pub mod testkit {
                 oversample: self.oversample,
                 max_upscale_factor: self.max_upscale_factor,
                 mat_pipeline: &self.mat_pipeline,
+                rng: &mut self.matting_rng,
             };
             bridge.queue_for_wake(&mut self.wake);
         }
@@ -3681,6 +5747,72 @@ mod tests {
         })
     }
 
+    #[test]
+    fn pick_outputs_keeps_configured_order() {
+        let configured = vec![
+            "HDMI-A-2".to_string(),
+            "DP-1".to_string(),
+            "HDMI-A-1".to_string(),
+        ];
+        let available = vec![
+            None,
+            Some("HDMI-A-1".to_string()),
+            Some("HDMI-A-2".to_string()),
+        ];
+        assert_eq!(pick_outputs(&configured, &available), [2, 1]);
+        assert_eq!(pick_outputs(&configured[2..], &available), [1]);
+        assert!(pick_outputs(&configured[1..2], &available).is_empty());
+    }
+
+    #[tokio::test]
+    async fn distribute_round_robin_takes_turns_and_skips_closed_outputs() {
+        fn photo(name: &str) -> PhotoLoaded {
+            PhotoLoaded {
+                prepared: PreparedImageCpu {
+                    path: PathBuf::from(name),
+                    width: 1,
+                    height: 1,
+                    pixels: vec![0; 4],
+                },
+                priority: false,
+                animation: None,
+                caption: None,
+                matting: None,
+                created_at: SystemTime::UNIX_EPOCH,
+                prep_time: Duration::ZERO,
+            }
+        }
+        async fn run(names: &[&str], outputs: Vec<mpsc::Sender<PhotoLoaded>>) {
+            let (loader_tx, loader_rx) = mpsc::channel(8);
+            for name in names {
+                loader_tx.send(photo(name)).await.unwrap();
+            }
+            drop(loader_tx);
+            distribute_round_robin(loader_rx, outputs).await;
+        }
+        fn drain(rx: &mut mpsc::Receiver<PhotoLoaded>) -> Vec<PathBuf> {
+            std::iter::from_fn(|| rx.try_recv().ok())
+                .map(|photo| photo.prepared.path)
+                .collect()
+        }
+
+        let (left_tx, mut left_rx) = mpsc::channel(8);
+        let (right_tx, mut right_rx) = mpsc::channel(8);
+        run(&["a", "b", "c"], vec![left_tx.clone(), right_tx.clone()]).await;
+        assert_eq!(
+            drain(&mut left_rx),
+            [PathBuf::from("a"), PathBuf::from("c")]
+        );
+        assert_eq!(drain(&mut right_rx), [PathBuf::from("b")]);
+
+        drop(right_rx);
+        run(&["d", "e"], vec![left_tx, right_tx]).await;
+        assert_eq!(
+            drain(&mut left_rx),
+            [PathBuf::from("d"), PathBuf::from("e")]
+        );
+    }
+
     #[test]
     fn scale_cover_matches_canvas_dimensions() {
         let src = make_gradient(400, 300);
@@ -3740,13 +5872,18 @@ mod tests {
         };
         deferred_images.push_back(QueuedImage {
             image: prepared,
+            animation: None,
             priority: false,
+            caption: None,
+            sidecar_matting: None,
+            prep_time: Duration::ZERO,
+            replaces_current: false,
         });
         let mut ready_results = VecDeque::new();
         let (tx, mut rx) = mpsc::channel(1);
         drop(tx);
         let matting = MattingConfig::default();
-        let mat_pipeline = MattingPipeline::new(1, 2);
+        let mat_pipeline = MattingPipeline::new(1, 2, RuntimeMetrics::shared());
         let mut bridge = MattingBridge {
             preload_count: 1,
             mat_inflight: &mut mat_inflight,
@@ -3758,6 +5895,7 @@ mod tests {
             oversample: 1.0,
             max_upscale_factor: 1.0,
             mat_pipeline: &mat_pipeline,
+            rng: &mut StdRng::seed_from_u64(0),
         };
         let mut wake = scenes::WakeScene::new(5_000, TransitionConfig::default());
 
@@ -3803,4 +5941,141 @@ mod tests {
         );
         assert_eq!(harness.deferred_queue_len(), 0);
     }
+
+    #[test]
+    fn kept_sources_mat_again_for_a_rotated_screen() {
+        let matting = MattingConfig::default();
+        let params = |screen_w, screen_h| MatParams {
+            screen_w,
+            screen_h,
+            oversample: 1.0,
+            max_dim: 4096,
+            max_upscale_factor: 1.0,
+            matting: matting.primary_option().cloned().unwrap(),
+            fill_screen: false,
+        };
+        let landscape = process_mat_task(MatTask {
+            image: PreparedImageCpu {
+                path: PathBuf::from("/tmp/photo.jpg"),
+                width: 800,
+                height: 600,
+                pixels: make_gradient(800, 600).into_raw(),
+            },
+            animation: None,
+            params: params(1920, 1080),
+            priority: false,
+            mat_kind: None,
+            caption: Some("Beach".into()),
+            sidecar_matting: None,
+            prep_time: Duration::ZERO,
+            replaces_current: false,
+        })
+        .expect("landscape canvas");
+        assert_eq!(
+            (landscape.canvas.width, landscape.canvas.height),
+            (1920, 1080)
+        );
+        let source = landscape.source.expect("stills keep their source");
+
+        let queued = QueuedImage::relayout(&source, true);
+        let portrait = process_mat_task(MatTask {
+            image: queued.image,
+            animation: None,
+            params: params(1080, 1920),
+            priority: false,
+            mat_kind: None,
+            caption: queued.caption,
+            sidecar_matting: queued.sidecar_matting,
+            prep_time: queued.prep_time,
+            replaces_current: queued.replaces_current,
+        })
+        .expect("portrait canvas");
+        assert_eq!(
+            (portrait.canvas.width, portrait.canvas.height),
+            (1080, 1920)
+        );
+        assert_eq!(portrait.path, PathBuf::from("/tmp/photo.jpg"));
+        assert_eq!(portrait.caption.as_deref(), Some("Beach"));
+        assert!(portrait.replaces_current);
+    }
+
+    #[test]
+    fn push_offset_moves_the_photos_exactly_one_screen() {
+        let (w, h) = (1920.0, 1080.0);
+        let right = compute_push_offset(angle_to_unit_vec(0.0), w, h);
+        assert!((right[0] - w).abs() < 1e-2 && right[1].abs() < 1e-2);
+        let up = compute_push_offset(angle_to_unit_vec(270.0), w, h);
+        assert!(up[0].abs() < 1e-2 && (up[1] + h).abs() < 1e-2);
+        // A diagonal stops as soon as the screen clears its shorter side.
+        let diagonal = compute_push_offset(angle_to_unit_vec(45.0), w, h);
+        assert!((diagonal[0] - h).abs() < 1e-2 && (diagonal[1] - h).abs() < 1e-2);
+    }
+
+    #[test]
+    fn push_travel_runs_from_rest_to_one_screen_over_its_duration() {
+        let cfg: TransitionConfig = serde_yaml::from_str(
+            "selection: fixed\nactive:\n  - kind: push\n    duration-ms: 800\n    direction: left\n",
+        )
+        .expect("push transition config");
+        let selected = cfg.primary_selected().expect("push transition");
+        let started = std::time::Instant::now();
+        let state = TransitionState::new(selected, started, &mut rand::rng());
+        let ActiveTransition::Push { direction } = state.variant() else {
+            panic!("expected a push transition");
+        };
+        let (w, h) = (1920.0, 1080.0);
+        let offset = compute_push_offset(*direction, w, h);
+        // The shader moves the outgoing photo by `offset * progress`.
+        let travel = |ms: u64| {
+            let progress = state.progress_at(started + std::time::Duration::from_millis(ms));
+            (offset[0] * progress, offset[1] * progress)
+        };
+        assert_eq!(travel(0), (0.0, 0.0));
+        let (mid_x, _) = travel(400);
+        assert!((mid_x + w / 2.0).abs() < 1e-2, "halfway: {mid_x}");
+        let (end_x, end_y) = travel(800);
+        assert!((end_x + w).abs() < 1e-2 && end_y.abs() < 1e-2);
+        assert_eq!(travel(1_200), travel(800));
+    }
+
+    #[test]
+    fn crossfade_blur_starts_with_its_configured_strength() {
+        let cfg: TransitionConfig = serde_yaml::from_str(
+            "selection: fixed\nactive:\n  - kind: crossfade-blur\n    strength: 24\n",
+        )
+        .expect("crossfade-blur transition config");
+        let selected = cfg.primary_selected().expect("crossfade-blur transition");
+        let started = std::time::Instant::now();
+        let state = TransitionState::new(selected, started, &mut rand::rng());
+        assert_eq!(state.kind(), TransitionKind::CrossfadeBlur);
+        assert_eq!(state.kind().as_index(), 12);
+        assert!(matches!(
+            state.variant(),
+            ActiveTransition::CrossfadeBlur { strength } if *strength == 24.0
+        ));
+        assert_eq!(state.progress_at(started), 0.0);
+        assert_eq!(
+            state.progress_at(started + std::time::Duration::from_millis(900)),
+            1.0
+        );
+    }
+
+    #[test]
+    fn frame_pacer_defers_redraws_until_the_interval_passes() {
+        let start = Instant::now();
+        let pacer = FramePacer::new(Duration::from_millis(100));
+        assert!(pacer.admit(start), "the first frame is never held");
+        pacer.presented(start);
+
+        let soon = start + Duration::from_millis(40);
+        assert!(!pacer.admit(soon));
+        assert!(!pacer.take_due(soon), "still inside the interval");
+        let later = start + Duration::from_millis(100);
+        assert!(pacer.take_due(later), "the deferred redraw goes out");
+        assert!(!pacer.take_due(later), "and only once");
+
+        let uncapped = FramePacer::new(Duration::ZERO);
+        uncapped.presented(start);
+        assert!(uncapped.admit(start));
+    }
 }
@@ -0,0 +1,338 @@
+//! Animated GIF, APNG, and WebP playback inside a matted canvas.
+//!
+//! The matting worker bakes the first frame into the canvas like any still
+//! and also prepares one [`AnimationPatches`] entry per frame: the photo's
+//! rectangle in the canvas, already scaled and composited. While the photo is
+//! on screen, [`CanvasAnimation`] writes the patch that is due into that
+//! rectangle of the canvas texture, so the mat is rendered once and only the
+//! photo area changes.
+
+use std::time::{Duration, Instant};
+
+use image::{RgbaImage, imageops};
+
+use super::ImagePlane;
+use super::image_ops::{compute_padded_stride, scale_image_to_cover_canvas};
+use crate::events::AnimationFrame;
+
+/// Cap on the CPU memory held by one photo's patches. Frames past it are
+/// dropped and the animation loops over the ones that fit.
+const MAX_ANIMATION_PATCH_BYTES: usize = 128 * 1024 * 1024;
+
+/// Where the photo sits in its canvas, as `(x, y, width, height)` in pixels.
+pub(super) type PhotoRect = (u32, u32, u32, u32);
+
+/// Per-frame pixels for the photo rectangle of a matted canvas.
+pub(super) struct AnimationPatches {
+    origin: (u32, u32),
+    width: u32,
+    height: u32,
+    /// Row stride of every patch, padded for texture uploads.
+    padded_stride: u32,
+    frames: Vec<(Vec<u8>, Duration)>,
+}
+
+impl AnimationPatches {
+    /// Scales each frame into `rect` the way the matting pass scaled the
+    /// first one. `fill_screen` photos cover the whole canvas. Frames are
+    /// drawn over the first frame's patch so transparent areas keep what the
+    /// mat showed there. Returns `None` when fewer than two frames fit.
+    pub(super) fn build(
+        canvas: &ImagePlane,
+        rect: PhotoRect,
+        fill_screen: bool,
+        max_dim: u32,
+        source: (u32, u32),
+        frames: Vec<AnimationFrame>,
+    ) -> Option<Self> {
+        let (x, y, width, height) = rect;
+        if width == 0
+            || height == 0
+            || x + width > canvas.width
+            || y + height > canvas.height
+            || frames.len() < 2
+        {
+            return None;
+        }
+        let stride = 4 * width;
+        let mut base = Vec::with_capacity(stride as usize * height as usize);
+        for row in y..y + height {
+            let start = (row as usize * canvas.width as usize + x as usize) * 4;
+            base.extend_from_slice(canvas.pixels.get(start..start + stride as usize)?);
+        }
+        let base = RgbaImage::from_raw(width, height, base)?;
+
+        let padded_stride = compute_padded_stride(stride);
+        let patch_bytes = padded_stride as usize * height as usize;
+        let max_frames = (MAX_ANIMATION_PATCH_BYTES / patch_bytes.max(1)).max(1);
+        if frames.len() > max_frames {
+            tracing::debug!(
+                frames = frames.len(),
+                kept = max_frames,
+                "animation_truncated_to_memory_budget"
+            );
+        }
+
+        let mut patches = Vec::with_capacity(frames.len().min(max_frames));
+        for (idx, frame) in frames.into_iter().take(max_frames).enumerate() {
+            let patch = if idx == 0 {
+                base.clone()
+            } else {
+                let src = RgbaImage::from_raw(source.0, source.1, frame.pixels)?;
+                let scaled = if fill_screen {
+                    scale_image_to_cover_canvas(&src, width, height, max_dim)
+                } else if src.dimensions() == (width, height) {
+                    src
+                } else {
+                    imageops::resize(&src, width, height, imageops::FilterType::Triangle)
+                };
+                let mut patch = base.clone();
+                imageops::overlay(&mut patch, &scaled, 0, 0);
+                patch
+            };
+            patches.push((pad_rows(patch.as_raw(), stride, padded_stride), frame.delay));
+        }
+        if patches.len() < 2 {
+            return None;
+        }
+        Some(Self {
+            origin: (x, y),
+            width,
+            height,
+            padded_stride,
+            frames: patches,
+        })
+    }
+
+    fn delays(&self) -> impl Iterator<Item = Duration> + Clone + '_ {
+        self.frames.iter().map(|(_, delay)| *delay)
+    }
+}
+
+fn pad_rows(pixels: &[u8], stride: u32, padded_stride: u32) -> Vec<u8> {
+    if stride == padded_stride {
+        return pixels.to_vec();
+    }
+    let mut padded = Vec::with_capacity(pixels.len() / stride as usize * padded_stride as usize);
+    for row in pixels.chunks_exact(stride as usize) {
+        padded.extend_from_slice(row);
+        padded.resize(padded.len() + (padded_stride - stride) as usize, 0);
+    }
+    padded
+}
+
+/// How long `loops` plays of the animation take; `None` when it loops
+/// without end.
+fn play_time(delays: impl Iterator<Item = Duration>, loops: Option<u32>) -> Option<Duration> {
+    loops.map(|loops| delays.sum::<Duration>() * loops)
+}
+
+/// Index of the frame showing `elapsed` into the animation. It loops without
+/// end, or holds its last frame once `loops` plays are over.
+fn frame_index_at(
+    delays: impl Iterator<Item = Duration> + Clone,
+    elapsed: Duration,
+    loops: Option<u32>,
+) -> usize {
+    let total: Duration = delays.clone().sum();
+    if total.is_zero() {
+        return 0;
+    }
+    if play_time(delays.clone(), loops).is_some_and(|play| elapsed >= play) {
+        return delays.count() - 1;
+    }
+    let mut remaining = Duration::from_nanos((elapsed.as_nanos() % total.as_nanos()) as u64);
+    for (idx, delay) in delays.enumerate() {
+        if remaining < delay {
+            return idx;
+        }
+        remaining -= delay;
+    }
+    0
+}
+
+/// A canvas texture plus the patches that animate it.
+pub(super) struct CanvasAnimation {
+    texture: wgpu::Texture,
+    patches: AnimationPatches,
+    /// Set on the first frame drawn; the loop is timed from here.
+    started_at: Option<Instant>,
+    /// Patch currently in the texture. The upload starts with frame 0.
+    shown: usize,
+}
+
+impl CanvasAnimation {
+    pub(super) fn new(texture: wgpu::Texture, patches: AnimationPatches) -> Self {
+        Self {
+            texture,
+            patches,
+            started_at: None,
+            shown: 0,
+        }
+    }
+
+    /// The frames, freed from the texture they were playing into.
+    pub(super) fn into_patches(self) -> AnimationPatches {
+        self.patches
+    }
+
+    /// Whether a different frame than the one in the texture is due at `now`.
+    pub(super) fn frame_due(&self, now: Instant, loops: Option<u32>) -> bool {
+        self.started_at.is_some_and(|start| {
+            frame_index_at(
+                self.patches.delays(),
+                now.saturating_duration_since(start),
+                loops,
+            ) != self.shown
+        })
+    }
+
+    /// Whether `loops` plays have finished by `now`. Never true for an
+    /// animation that loops without end or has not started.
+    pub(super) fn finished(&self, now: Instant, loops: Option<u32>) -> bool {
+        match (self.started_at, play_time(self.patches.delays(), loops)) {
+            (Some(start), Some(play)) => now.saturating_duration_since(start) >= play,
+            _ => false,
+        }
+    }
+
+    /// Writes the frame due at `now` into the texture. The first call starts
+    /// the loop's clock.
+    pub(super) fn advance(&mut self, queue: &wgpu::Queue, now: Instant, loops: Option<u32>) {
+        let start = *self.started_at.get_or_insert(now);
+        let index = frame_index_at(
+            self.patches.delays(),
+            now.saturating_duration_since(start),
+            loops,
+        );
+        if index == self.shown {
+            return;
+        }
+        let patches = &self.patches;
+        let (pixels, _) = &patches.frames[index];
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: patches.origin.0,
+                    y: patches.origin.1,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            pixels,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(patches.padded_stride),
+                rows_per_image: Some(patches.height),
+            },
+            wgpu::Extent3d {
+                width: patches.width,
+                height: patches.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.shown = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn frame_index_follows_delays_and_loops() {
+        let delays = ms(&[100, 250, 500]);
+        let at = |t| frame_index_at(delays.iter().copied(), Duration::from_millis(t), None);
+        assert_eq!(at(0), 0);
+        assert_eq!(at(99), 0);
+        assert_eq!(at(100), 1);
+        assert_eq!(at(349), 1);
+        assert_eq!(at(350), 2);
+        assert_eq!(at(849), 2);
+        assert_eq!(at(850), 0);
+        assert_eq!(at(850 * 4 + 120), 1);
+    }
+
+    #[test]
+    fn a_loop_count_holds_the_last_frame_once_played() {
+        let delays = ms(&[100, 250, 500]);
+        let at = |t| frame_index_at(delays.iter().copied(), Duration::from_millis(t), Some(2));
+        assert_eq!(
+            play_time(delays.iter().copied(), Some(2)),
+            Some(Duration::from_millis(1700))
+        );
+        assert_eq!(play_time(delays.iter().copied(), None), None);
+        assert_eq!(at(850 + 120), 1);
+        assert_eq!(at(1699), 2);
+        assert_eq!(at(1700), 2);
+        assert_eq!(at(60_000), 2);
+    }
+
+    fn frame(w: u32, h: u32, px: [u8; 4], delay_ms: u64) -> AnimationFrame {
+        AnimationFrame {
+            pixels: RgbaImage::from_pixel(w, h, Rgba(px)).into_raw(),
+            delay: Duration::from_millis(delay_ms),
+        }
+    }
+
+    #[test]
+    fn patches_cover_the_photo_rect_of_the_canvas() {
+        // 80x60 canvas with a 20x10 photo at (30, 25); the first frame is baked in.
+        let mut canvas = RgbaImage::from_pixel(80, 60, Rgba([9, 9, 9, 255]));
+        for y in 25..35 {
+            for x in 30..50 {
+                canvas.put_pixel(x, y, Rgba([200, 0, 0, 255]));
+            }
+        }
+        let plane = ImagePlane {
+            width: 80,
+            height: 60,
+            pixels: canvas.into_raw(),
+        };
+        let frames = vec![
+            frame(10, 5, [200, 0, 0, 255], 100),
+            frame(10, 5, [0, 200, 0, 255], 200),
+            frame(10, 5, [0, 0, 0, 0], 300),
+        ];
+
+        let patches =
+            AnimationPatches::build(&plane, (30, 25, 20, 10), false, 4096, (10, 5), frames)
+                .expect("three frames animate");
+
+        assert_eq!(patches.origin, (30, 25));
+        assert_eq!((patches.width, patches.height), (20, 10));
+        assert_eq!(patches.padded_stride, 256);
+        assert_eq!(patches.delays().collect::<Vec<_>>(), ms(&[100, 200, 300]));
+        let px = |frame: usize, x: usize, y: usize| {
+            let offset = y * 256 + x * 4;
+            patches.frames[frame].0[offset..offset + 4].to_vec()
+        };
+        assert_eq!(px(0, 5, 5), [200, 0, 0, 255]);
+        assert_eq!(px(1, 19, 9), [0, 200, 0, 255]);
+        // A fully transparent frame keeps what the first frame showed.
+        assert_eq!(px(2, 0, 0), [200, 0, 0, 255]);
+    }
+
+    #[test]
+    fn a_single_frame_or_bad_rect_is_not_animated() {
+        let plane = ImagePlane {
+            width: 8,
+            height: 8,
+            pixels: vec![0; 8 * 8 * 4],
+        };
+        let one = vec![frame(4, 4, [1, 2, 3, 255], 100)];
+        assert!(AnimationPatches::build(&plane, (0, 0, 4, 4), false, 64, (4, 4), one).is_none());
+        let two = vec![
+            frame(4, 4, [1, 2, 3, 255], 100),
+            frame(4, 4, [3, 2, 1, 255], 100),
+        ];
+        assert!(AnimationPatches::build(&plane, (6, 6, 4, 4), false, 64, (4, 4), two).is_none());
+    }
+}
@@ -0,0 +1,213 @@
+//! Debug overlay: one line of pipeline timings in a corner of the slideshow,
+//! for tuning dwell, preload depth, and blur on slow hardware.
+//!
+//! Decode, effect, and matting times come from the shared [`RuntimeMetrics`];
+//! the frame time, transition rate, and queue depth from the viewer itself.
+//! The text is rebuilt at most every [`REFRESH_INTERVAL`], and while the
+//! overlay is off the viewer holds no [`DebugOverlay`] at all.
+
+use std::time::{Duration, Instant};
+
+use winit::dpi::PhysicalSize;
+
+use super::scenes::{CaptionOverlay, CaptionStyle};
+use crate::config::CaptionCorner;
+use crate::metrics::RuntimeMetrics;
+
+/// How often the numbers are redrawn; faster is unreadable anyway.
+pub(super) const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Smaller than the caption, so it covers less of the photo.
+const TEXT_SIZE: f32 = 18.0;
+
+/// The numbers behind one refresh of the overlay.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(super) struct Readout {
+    pub(super) decode_ms: Option<f64>,
+    pub(super) matting_ms: Option<f64>,
+    pub(super) effect_ms: Option<f64>,
+    /// CPU time to record, submit, and present the last slideshow frame.
+    pub(super) frame_ms: Option<f64>,
+    /// Rate of the transition playing, or of the last one.
+    pub(super) transition_fps: Option<f32>,
+    /// Photos prepared and waiting behind the one on screen.
+    pub(super) queue_depth: usize,
+    /// Display refreshes missed during transitions since startup.
+    pub(super) frames_dropped: u64,
+}
+
+impl Readout {
+    pub(super) fn from_metrics(metrics: &RuntimeMetrics) -> Self {
+        Self {
+            decode_ms: metrics.decode.last_ms(),
+            matting_ms: metrics.matting.last_ms(),
+            effect_ms: metrics.effect.last_ms(),
+            frames_dropped: metrics.frames_dropped,
+            ..Self::default()
+        }
+    }
+
+    pub(super) fn text(&self) -> String {
+        let ms =
+            |value: Option<f64>| value.map_or_else(|| "–".to_string(), |ms| format!("{ms:.0} ms"));
+        let frame = self
+            .frame_ms
+            .map_or_else(|| "–".to_string(), |ms| format!("{ms:.1} ms"));
+        let fps = self
+            .transition_fps
+            .map_or_else(|| "–".to_string(), |fps| format!("{fps:.0}"));
+        format!(
+            "decode {} · mat {} · effect {} · frame {frame} · {fps} fps · queue {} · dropped {}",
+            ms(self.decode_ms),
+            ms(self.matting_ms),
+            ms(self.effect_ms),
+            self.queue_depth,
+            self.frames_dropped,
+        )
+    }
+}
+
+pub(super) struct DebugOverlay {
+    corner: CaptionCorner,
+    /// Built on the first frame drawn with the overlay on.
+    overlay: Option<CaptionOverlay>,
+    text: String,
+    refreshed: Option<Instant>,
+    frame_time: Option<Duration>,
+    transition_fps: Option<f32>,
+}
+
+impl DebugOverlay {
+    pub(super) fn new(corner: CaptionCorner) -> Self {
+        Self {
+            corner,
+            overlay: None,
+            text: String::new(),
+            refreshed: None,
+            frame_time: None,
+            transition_fps: None,
+        }
+    }
+
+    pub(super) fn is_due(&self, now: Instant) -> bool {
+        self.refreshed
+            .is_none_or(|last| now.saturating_duration_since(last) >= REFRESH_INTERVAL)
+    }
+
+    /// Rebuilds the text from `readout`, filling in what the overlay tracks
+    /// itself. `transition_fps` is the rate of a transition still playing;
+    /// without one the last rate stays up. Returns whether the text changed.
+    pub(super) fn refresh(
+        &mut self,
+        now: Instant,
+        mut readout: Readout,
+        transition_fps: Option<f32>,
+    ) -> bool {
+        self.refreshed = Some(now);
+        if transition_fps.is_some() {
+            self.transition_fps = transition_fps;
+        }
+        readout.frame_ms = self.frame_time.map(|time| time.as_secs_f64() * 1000.0);
+        readout.transition_fps = self.transition_fps;
+        let text = readout.text();
+        if text == self.text {
+            return false;
+        }
+        self.text = text;
+        true
+    }
+
+    pub(super) fn record_frame(&mut self, elapsed: Duration) {
+        self.frame_time = Some(elapsed);
+    }
+
+    pub(super) fn render(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        encoder: &mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        surface_size: PhysicalSize<u32>,
+    ) {
+        let corner = self.corner;
+        let overlay = self.overlay.get_or_insert_with(|| {
+            let style = CaptionStyle {
+                corner,
+                size: TEXT_SIZE,
+                ..CaptionStyle::default()
+            };
+            CaptionOverlay::with_style(device, queue, format, style)
+        });
+        overlay.set_text(self.text.as_str());
+        overlay.resize(surface_size);
+        overlay.render(encoder, view);
+    }
+
+    /// Drops the text's GPU resources along with the device; they are built
+    /// again on the next frame.
+    pub(super) fn release_gpu(&mut self) {
+        self.overlay = None;
+    }
+
+    pub(super) fn after_submit(&mut self) {
+        if let Some(overlay) = self.overlay.as_mut() {
+            overlay.after_submit();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readout_lists_every_number() {
+        let readout = Readout {
+            decode_ms: Some(182.4),
+            matting_ms: Some(95.0),
+            effect_ms: None,
+            frame_ms: Some(6.24),
+            transition_fps: Some(58.6),
+            queue_depth: 2,
+            frames_dropped: 4,
+        };
+        assert_eq!(
+            readout.text(),
+            "decode 182 ms · mat 95 ms · effect – · frame 6.2 ms · 59 fps · queue 2 · dropped 4"
+        );
+    }
+
+    #[test]
+    fn refreshes_are_throttled_and_keep_the_last_transition_rate() {
+        let start = Instant::now();
+        let mut overlay = DebugOverlay::new(CaptionCorner::BottomRight);
+        assert!(overlay.is_due(start));
+
+        overlay.record_frame(Duration::from_micros(7_500));
+        assert!(overlay.refresh(start, Readout::default(), Some(60.0)));
+        assert!(
+            overlay.text.contains("frame 7.5 ms · 60 fps"),
+            "{}",
+            overlay.text
+        );
+        assert!(!overlay.is_due(start + REFRESH_INTERVAL / 2));
+        assert!(overlay.is_due(start + REFRESH_INTERVAL));
+
+        // The transition has ended; its rate stays up and nothing else moved.
+        assert!(!overlay.refresh(start + REFRESH_INTERVAL, Readout::default(), None));
+        assert!(overlay.text.contains("60 fps"), "{}", overlay.text);
+    }
+
+    #[test]
+    fn readout_takes_the_latest_timings() {
+        let mut metrics = RuntimeMetrics::default();
+        metrics.decode.record(Duration::from_millis(300));
+        metrics.decode.record(Duration::from_millis(120));
+        metrics.frames_dropped = 3;
+        let readout = Readout::from_metrics(&metrics);
+        assert_eq!(readout.decode_ms, Some(120.0));
+        assert_eq!(readout.matting_ms, None);
+        assert_eq!(readout.frames_dropped, 3);
+    }
+}
@@ -4,6 +4,7 @@
 //! types, so they are easy to unit-test without a GPU or display.
 
 use crate::config::GradientDirection;
+use crate::processing::mat_texture::MatTexture;
 use image::{Rgba, RgbaImage, imageops};
 
 // ── Stride / sizing ──────────────────────────────────────────────────────────
@@ -163,10 +164,11 @@ pub(super) fn render_studio_mat(
     photo: &RgbaImage,
     mat_color: [f32; 3],
     bevel_width_px: f32,
-    bevel_color: [u8; 3],
+    bevel_color: [f32; 3],
     texture_strength: f32,
     warp_period_px: f32,
     weft_period_px: f32,
+    texture: Option<&MatTexture>,
 ) -> RgbaImage {
     let mut bevel_px = bevel_width_px.max(0.0);
     let max_border = photo_x
@@ -184,11 +186,6 @@ pub(super) fn render_studio_mat(
     let window_max_x = window_x + photo_w.max(1) as f32;
     let window_max_y = window_y + photo_h.max(1) as f32;
 
-    let bevel_rgb_f32 = [
-        bevel_color[0] as f32 / 255.0,
-        bevel_color[1] as f32 / 255.0,
-        bevel_color[2] as f32 / 255.0,
-    ];
     let light_dir = normalize3([-0.55, -0.65, 0.52]);
     let ambient = 0.88;
     let diffuse = 0.18;
@@ -275,7 +272,7 @@ pub(super) fn render_studio_mat(
 
                 let mut color = [0u8; 3];
                 for c in 0..3 {
-                    let base = lerp(bevel_rgb_f32[c], mat_color[c], mat_mix);
+                    let base = lerp(bevel_color[c], mat_color[c], mat_mix);
                     let shaded = (base * shade).clamp(0.0, 1.0);
                     color[c] = srgb_u8(shaded);
                 }
@@ -288,6 +285,16 @@ pub(super) fn render_studio_mat(
             }
         }
 
+        if let Some(texture) = texture {
+            // The scan replaces the weave; strength scales its contrast.
+            let shade = lerp(1.0, texture.shade_at(x, y), texture_strength);
+            for c in 0..3 {
+                pixel[c] = srgb_u8(mat_color[c] * shade);
+            }
+            pixel[3] = 255;
+            continue;
+        }
+
         let warp_noise = (weave_grain(x, y) - 0.5) * 0.65;
         let weft_noise = (weave_grain(x.wrapping_add(17), y.wrapping_add(113)) - 0.5) * 0.65;
         let warp_phase = ((px + warp_noise) / warp_period).fract();
@@ -439,6 +446,26 @@ pub(super) fn compute_wipe_span(normal: [f32; 2], screen_w: f32, screen_h: f32)
 
 /// Convert an angle in degrees to a unit-length 2-D direction vector `[x, y]`.
 /// Falls back to `[1.0, 0.0]` on degenerate input.
+/// How far the outgoing photo travels during a push: the shortest move along
+/// `direction` that takes the screen rectangle clear of itself. The incoming
+/// photo trails by the same offset, so the two meet edge to edge with no gap
+/// (a screen diagonal overshoots horizontal and vertical pushes).
+pub(super) fn compute_push_offset(direction: [f32; 2], screen_w: f32, screen_h: f32) -> [f32; 2] {
+    let along = |component: f32, extent: f32| {
+        if component.abs() > 1e-4 {
+            extent / component.abs()
+        } else {
+            f32::INFINITY
+        }
+    };
+    let distance = along(direction[0], screen_w).min(along(direction[1], screen_h));
+    if distance.is_finite() {
+        [direction[0] * distance, direction[1] * distance]
+    } else {
+        [screen_w, 0.0]
+    }
+}
+
 pub(super) fn angle_to_unit_vec(angle_deg: f32) -> [f32; 2] {
     let (sin, cos) = angle_deg.to_radians().sin_cos();
     let mut v = [cos, sin];
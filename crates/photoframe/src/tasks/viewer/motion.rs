@@ -0,0 +1,341 @@
+//! Ken Burns motion: a slow pan and zoom across a photo while it dwells.
+//!
+//! A [`KenBurnsPath`] is picked once per photo and describes where the virtual
+//! camera starts and ends. The renderer asks for the [`Framing`] at the current
+//! dwell progress and turns it into a destination rectangle with
+//! [`framed_rect`], which always keeps the screen covered. A matted photo
+//! keeps its mat still and moves inside its own area instead, through the
+//! sampling window [`framed_view`] picks.
+
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::config::{MotionConfig, ZoomDirection};
+
+/// Camera position at one instant: a zoom factor (>= 1.0) and a pan offset.
+///
+/// `pan` is in `[-1, 1]` per axis, measured against the slack the zoom leaves
+/// between the photo and the screen edge, so any value keeps the screen covered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Framing {
+    pub(super) zoom: f32,
+    pub(super) pan: [f32; 2],
+}
+
+impl Framing {
+    /// The untouched framing: no zoom, centred.
+    pub(super) const IDENTITY: Self = Self {
+        zoom: 1.0,
+        pan: [0.0, 0.0],
+    };
+}
+
+/// Start and end framing for one photo's dwell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct KenBurnsPath {
+    start: Framing,
+    end: Framing,
+}
+
+impl KenBurnsPath {
+    /// Picks a path within the configured zoom range and pan amplitude. Each
+    /// photo zooms across the full range in the configured direction, with
+    /// independent random pan offsets at each end.
+    pub(super) fn random(motion: &MotionConfig, rng: &mut impl Rng) -> Self {
+        let MotionConfig::KenBurns {
+            zoom_range: [min, max],
+            pan_amplitude,
+            direction,
+        } = *motion;
+        let mut pan = || {
+            [
+                rng.random_range(-1.0..=1.0) * pan_amplitude,
+                rng.random_range(-1.0..=1.0) * pan_amplitude,
+            ]
+        };
+        let (near, far) = (pan(), pan());
+        let zoom_in = match direction {
+            ZoomDirection::In => true,
+            ZoomDirection::Out => false,
+            ZoomDirection::Random => rng.random_bool(0.5),
+        };
+        let (start_zoom, end_zoom) = if zoom_in { (min, max) } else { (max, min) };
+        Self {
+            start: Framing {
+                zoom: start_zoom,
+                pan: near,
+            },
+            end: Framing {
+                zoom: end_zoom,
+                pan: far,
+            },
+        }
+    }
+
+    /// Framing at `t` (dwell progress, clamped to `0..=1`), interpolated linearly
+    /// so the camera drifts at a constant speed.
+    pub(super) fn framing_at(&self, t: f32) -> Framing {
+        let t = if t.is_finite() {
+            t.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        Framing {
+            zoom: lerp(self.start.zoom, self.end.zoom),
+            pan: [
+                lerp(self.start.pan[0], self.end.pan[0]),
+                lerp(self.start.pan[1], self.end.pan[1]),
+            ],
+        }
+    }
+}
+
+/// How far through its dwell a photo shown for `elapsed` is, from `0.0` to
+/// `1.0`. A zero dwell counts as finished.
+pub(super) fn dwell_progress(elapsed: Duration, dwell: Duration) -> f32 {
+    if dwell.is_zero() {
+        return 1.0;
+    }
+    (elapsed.as_secs_f32() / dwell.as_secs_f32()).min(1.0)
+}
+
+/// Applies `framing` to the cover rectangle `base` (`x, y, w, h` in pixels).
+///
+/// The rectangle grows about its centre by `framing.zoom`, then shifts by the
+/// pan offset. The shift is limited to the overhang past the screen on each
+/// axis, so the photo never pulls away from an edge.
+pub(super) fn framed_rect(
+    base: (f32, f32, f32, f32),
+    screen_w: f32,
+    screen_h: f32,
+    framing: Framing,
+) -> (f32, f32, f32, f32) {
+    let (x, y, w, h) = base;
+    let zoom = framing.zoom.max(1.0);
+    let zw = w * zoom;
+    let zh = h * zoom;
+    let cx = x + w * 0.5;
+    let cy = y + h * 0.5;
+    let pan = |center: f32, size: f32, screen: f32, amount: f32| {
+        // Range of centres for which [center - size/2, center + size/2] still spans 0..screen.
+        let lo = screen - size * 0.5;
+        let hi = size * 0.5;
+        if lo > hi {
+            return center;
+        }
+        let mid = center.clamp(lo, hi);
+        let reach = if amount >= 0.0 { hi - mid } else { mid - lo };
+        mid + amount.clamp(-1.0, 1.0) * reach
+    };
+    let ncx = pan(cx, zw, screen_w, framing.pan[0]);
+    let ncy = pan(cy, zh, screen_h, framing.pan[1]);
+    (ncx - zw * 0.5, ncy - zh * 0.5, zw, zh)
+}
+
+/// Applies `framing` to a matted photo, which moves inside its own area so
+/// the mat around it stays put.
+///
+/// `photo` is where the photo sits in its canvas texture (`x, y, w, h` in
+/// UV). The result is the part of it to sample across that area: the zoomed
+/// photo framed against its own area the way [`framed_rect`] frames it
+/// against the screen, so it always lies within `photo`.
+pub(super) fn framed_view(photo: (f32, f32, f32, f32), framing: Framing) -> (f32, f32, f32, f32) {
+    let (x, y, w, h) = photo;
+    let (fx, fy, fw, fh) = framed_rect((0.0, 0.0, 1.0, 1.0), 1.0, 1.0, framing);
+    (x - fx / fw * w, y - fy / fh * h, w / fw, h / fh)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Framing, KenBurnsPath, dwell_progress, framed_rect, framed_view};
+    use crate::config::{MotionConfig, ZoomDirection};
+    use rand::{SeedableRng, rngs::StdRng};
+    use std::time::Duration;
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    fn covers(rect: (f32, f32, f32, f32), sw: f32, sh: f32) -> bool {
+        let (x, y, w, h) = rect;
+        x <= 1e-3 && y <= 1e-3 && x + w >= sw - 1e-3 && y + h >= sh - 1e-3
+    }
+
+    #[test]
+    fn framing_interpolates_linearly_and_clamps() {
+        let path = KenBurnsPath {
+            start: Framing {
+                zoom: 1.0,
+                pan: [-1.0, 0.5],
+            },
+            end: Framing {
+                zoom: 1.1,
+                pan: [1.0, -0.5],
+            },
+        };
+        let mid = path.framing_at(0.5);
+        assert!(close(mid.zoom, 1.05));
+        assert!(close(mid.pan[0], 0.0) && close(mid.pan[1], 0.0));
+        assert_eq!(path.framing_at(-2.0), path.start);
+        assert_eq!(path.framing_at(3.0), path.end);
+        assert_eq!(path.framing_at(f32::NAN), path.start);
+    }
+
+    #[test]
+    fn identity_framing_leaves_the_rect_alone() {
+        let base = (0.0, -60.0, 1920.0, 1200.0);
+        assert_eq!(framed_rect(base, 1920.0, 1080.0, Framing::IDENTITY), base);
+    }
+
+    #[test]
+    fn zoom_grows_about_the_centre() {
+        let rect = framed_rect(
+            (0.0, 0.0, 1000.0, 500.0),
+            1000.0,
+            500.0,
+            Framing {
+                zoom: 1.2,
+                pan: [0.0, 0.0],
+            },
+        );
+        assert!(close(rect.0, -100.0) && close(rect.1, -50.0));
+        assert!(close(rect.2, 1200.0) && close(rect.3, 600.0));
+    }
+
+    #[test]
+    fn full_pan_stops_at_the_screen_edge() {
+        let zoomed = |pan| {
+            framed_rect(
+                (0.0, 0.0, 1000.0, 500.0),
+                1000.0,
+                500.0,
+                Framing { zoom: 1.2, pan },
+            )
+        };
+        let right = zoomed([1.0, 1.0]);
+        assert!(close(right.0, 0.0) && close(right.1, 0.0));
+        let left = zoomed([-1.0, -1.0]);
+        assert!(close(left.0 + left.2, 1000.0) && close(left.1 + left.3, 500.0));
+    }
+
+    #[test]
+    fn matted_photo_moves_inside_its_own_area() {
+        // A portrait photo centred in its mat.
+        let photo = (0.3, 0.1, 0.4, 0.8);
+        assert_eq!(framed_view(photo, Framing::IDENTITY), photo);
+
+        let centred = framed_view(
+            photo,
+            Framing {
+                zoom: 2.0,
+                pan: [0.0, 0.0],
+            },
+        );
+        assert!(close(centred.0, 0.4) && close(centred.1, 0.3));
+        assert!(close(centred.2, 0.2) && close(centred.3, 0.4));
+
+        let zoomed = |pan| framed_view(photo, Framing { zoom: 1.25, pan });
+        let right = zoomed([1.0, 1.0]);
+        assert!(close(right.0, 0.3) && close(right.1, 0.1));
+        assert!(close(right.2, 0.32) && close(right.3, 0.64));
+        let left = zoomed([-1.0, -1.0]);
+        assert!(close(left.0 + left.2, 0.7) && close(left.1 + left.3, 0.9));
+
+        let motion = MotionConfig::KenBurns {
+            zoom_range: [1.0, 1.08],
+            pan_amplitude: 1.0,
+            direction: ZoomDirection::Random,
+        };
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..50 {
+            let path = KenBurnsPath::random(&motion, &mut rng);
+            for step in 0..=10 {
+                let (x, y, w, h) = framed_view(photo, path.framing_at(step as f32 / 10.0));
+                assert!(x >= 0.3 - 1e-6 && y >= 0.1 - 1e-6, "({x}, {y})");
+                assert!(x + w <= 0.7 + 1e-6 && y + h <= 0.9 + 1e-6, "({w}, {h})");
+            }
+        }
+    }
+
+    #[test]
+    fn random_paths_stay_in_range_and_keep_the_screen_covered() {
+        let motion = MotionConfig::KenBurns {
+            zoom_range: [1.0, 1.08],
+            pan_amplitude: 0.5,
+            direction: ZoomDirection::Random,
+        };
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..100 {
+            let path = KenBurnsPath::random(&motion, &mut rng);
+            for step in 0..=10 {
+                let framing = path.framing_at(step as f32 / 10.0);
+                assert!((1.0..=1.08 + 1e-6).contains(&framing.zoom));
+                assert!(framing.pan.iter().all(|p| p.abs() <= 0.5));
+                let rect = framed_rect((0.0, 0.0, 1920.0, 1080.0), 1920.0, 1080.0, framing);
+                assert!(covers(rect, 1920.0, 1080.0), "{framing:?} -> {rect:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_picks_the_same_paths() {
+        let motion = MotionConfig::KenBurns {
+            zoom_range: [1.0, 1.08],
+            pan_amplitude: 1.0,
+            direction: ZoomDirection::Random,
+        };
+        let mut a = StdRng::seed_from_u64(42);
+        let mut b = StdRng::seed_from_u64(42);
+        for _ in 0..5 {
+            assert_eq!(
+                KenBurnsPath::random(&motion, &mut a),
+                KenBurnsPath::random(&motion, &mut b)
+            );
+        }
+    }
+
+    #[test]
+    fn transform_runs_from_min_zoom_at_start_to_max_at_dwell_end() {
+        let dwell = Duration::from_secs(30);
+        let base = (0.0, 0.0, 1920.0, 1080.0);
+        let transform_at = |direction, elapsed| {
+            let motion = MotionConfig::KenBurns {
+                zoom_range: [1.0, 1.2],
+                pan_amplitude: 0.0,
+                direction,
+            };
+            let path = KenBurnsPath::random(&motion, &mut StdRng::seed_from_u64(1));
+            let framing = path.framing_at(dwell_progress(elapsed, dwell));
+            framed_rect(base, 1920.0, 1080.0, framing)
+        };
+        let untouched = base;
+        let zoomed = (-192.0, -108.0, 2304.0, 1296.0);
+        let matches = |a: (f32, f32, f32, f32), b: (f32, f32, f32, f32)| {
+            close(a.0, b.0) && close(a.1, b.1) && close(a.2, b.2) && close(a.3, b.3)
+        };
+
+        assert!(matches(
+            transform_at(ZoomDirection::In, Duration::ZERO),
+            untouched
+        ));
+        assert!(matches(transform_at(ZoomDirection::In, dwell), zoomed));
+        assert!(matches(
+            transform_at(ZoomDirection::Out, Duration::ZERO),
+            zoomed
+        ));
+        assert!(matches(transform_at(ZoomDirection::Out, dwell), untouched));
+        // Past the dwell the camera holds its final framing.
+        assert!(matches(transform_at(ZoomDirection::In, dwell * 2), zoomed));
+    }
+
+    #[test]
+    fn zero_dwell_counts_as_finished() {
+        assert_eq!(dwell_progress(Duration::ZERO, Duration::ZERO), 1.0);
+        assert_eq!(
+            dwell_progress(Duration::from_secs(5), Duration::from_secs(20)),
+            0.25
+        );
+    }
+}
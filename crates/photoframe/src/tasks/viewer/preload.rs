@@ -0,0 +1,209 @@
+//! Adaptive preload depth: how far ahead of the slideshow photos are prepared.
+//!
+//! `viewer-preload-count` photos are kept decoded and matted ahead of the one
+//! on screen. That is plenty while every photo takes the same time to
+//! prepare, but a portrait photo on a landscape frame needs its blur or studio
+//! mat computed over far more of the screen than a landscape one, and can
+//! take longer than a whole dwell. [`PrepCosts`] keeps a moving average of
+//! each photo's preparation time (decode, effect, and matting) per
+//! orientation and deepens the queue until the slowest kind is started early
+//! enough to be ready when its turn comes.
+
+use std::time::Duration;
+
+use tracing::{debug, info};
+
+/// Extra photos the queue may grow by on top of `viewer-preload-count`. Each
+/// one holds a screen-sized canvas, so the depth stays bounded.
+pub(super) const MAX_EXTRA_DEPTH: usize = 3;
+
+/// Weight of the newest sample in the moving average.
+const SMOOTHING: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Orientation {
+    Landscape,
+    Portrait,
+}
+
+impl Orientation {
+    /// Square photos count as landscape.
+    pub(super) fn of(width: u32, height: u32) -> Self {
+        if height > width {
+            Self::Portrait
+        } else {
+            Self::Landscape
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Landscape => "landscape",
+            Self::Portrait => "portrait",
+        }
+    }
+}
+
+/// Moving averages of photo preparation time, one per orientation.
+#[derive(Debug, Default)]
+pub(super) struct PrepCosts {
+    landscape: Option<Duration>,
+    portrait: Option<Duration>,
+    /// The depth last reported, so changes are logged once.
+    depth: Option<usize>,
+}
+
+impl PrepCosts {
+    pub(super) fn record(&mut self, orientation: Orientation, cost: Duration) {
+        let average = match orientation {
+            Orientation::Landscape => &mut self.landscape,
+            Orientation::Portrait => &mut self.portrait,
+        };
+        *average = Some(match *average {
+            Some(previous) => previous.mul_f64(1.0 - SMOOTHING) + cost.mul_f64(SMOOTHING),
+            None => cost,
+        });
+        debug!(
+            orientation = orientation.as_str(),
+            prep_ms = cost.as_millis() as u64,
+            average_ms = average.unwrap_or_default().as_millis() as u64,
+            "photo_prep_cost"
+        );
+    }
+
+    pub(super) fn estimate(&self, orientation: Orientation) -> Option<Duration> {
+        match orientation {
+            Orientation::Landscape => self.landscape,
+            Orientation::Portrait => self.portrait,
+        }
+    }
+
+    /// Photos to keep prepared or in preparation: at least `base`, and enough
+    /// that a photo of the slowest orientation, started as soon as a slot
+    /// frees up, finishes before the dwells of the photos queued ahead of it
+    /// run out. Grows by at most [`MAX_EXTRA_DEPTH`].
+    pub(super) fn target_depth(&self, base: usize, dwell: Duration) -> usize {
+        let Some(slowest) = self.landscape.max(self.portrait) else {
+            return base;
+        };
+        let dwell = dwell.max(Duration::from_millis(1));
+        let needed = (slowest.as_secs_f64() / dwell.as_secs_f64()).ceil() as usize + 1;
+        needed.clamp(base, base + MAX_EXTRA_DEPTH)
+    }
+
+    /// [`Self::target_depth`], logging when it changes.
+    pub(super) fn update_depth(&mut self, base: usize, dwell: Duration) -> usize {
+        let depth = self.target_depth(base, dwell);
+        if self.depth != Some(depth) {
+            if self.depth.is_some() || depth != base {
+                info!(
+                    depth,
+                    base,
+                    landscape_ms = self.landscape.map(|cost| cost.as_millis() as u64),
+                    portrait_ms = self.portrait.map(|cost| cost.as_millis() as u64),
+                    dwell_ms = dwell.as_millis() as u64,
+                    "preload_depth_changed"
+                );
+            }
+            self.depth = Some(depth);
+        }
+        depth
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn averages_each_orientation_separately() {
+        let mut costs = PrepCosts::default();
+        assert_eq!(costs.estimate(Orientation::Portrait), None);
+        costs.record(Orientation::Portrait, SECOND * 10);
+        costs.record(Orientation::Portrait, SECOND * 20);
+        costs.record(Orientation::Landscape, SECOND);
+        assert_eq!(costs.estimate(Orientation::Portrait), Some(SECOND * 13));
+        assert_eq!(costs.estimate(Orientation::Landscape), Some(SECOND));
+        assert_eq!(Orientation::of(3000, 4000), Orientation::Portrait);
+        assert_eq!(Orientation::of(4000, 4000), Orientation::Landscape);
+    }
+
+    #[test]
+    fn depth_follows_the_slowest_orientation_within_bounds() {
+        let dwell = SECOND * 8;
+        let mut costs = PrepCosts::default();
+        assert_eq!(costs.target_depth(2, dwell), 2);
+        costs.record(Orientation::Landscape, SECOND * 2);
+        assert_eq!(costs.target_depth(2, dwell), 2);
+        costs.record(Orientation::Portrait, SECOND * 12);
+        assert_eq!(costs.target_depth(2, dwell), 3);
+        // A very slow photo cannot grow the queue without limit.
+        costs.record(Orientation::Portrait, SECOND * 600);
+        assert_eq!(costs.target_depth(2, dwell), 2 + MAX_EXTRA_DEPTH);
+    }
+
+    /// Replays a slideshow on a fake clock with one preparation worker: photo
+    /// `i` can start once fewer than `depth` photos are prepared or in
+    /// preparation and the worker is free, and goes on screen when the previous
+    /// one has dwelt, or late if it is not ready. Returns how many were late.
+    fn late_photos(sequence: &[(Orientation, Duration)], base: usize, adaptive: bool) -> usize {
+        let dwell = SECOND * 8;
+        let mut costs = PrepCosts::default();
+        let mut shown_at: Vec<Duration> = Vec::new();
+        let mut worker_free = Duration::ZERO;
+        let mut late = 0;
+        let mut previous_shown = Duration::ZERO;
+        for (index, &(orientation, cost)) in sequence.iter().enumerate() {
+            let depth = if adaptive {
+                costs.target_depth(base, dwell)
+            } else {
+                base
+            };
+            // The slot frees when the photo `depth` places ahead goes on screen.
+            let slot_free = index
+                .checked_sub(depth)
+                .map_or(Duration::ZERO, |ahead| shown_at[ahead]);
+            let ready = worker_free.max(slot_free) + cost;
+            worker_free = ready;
+            costs.record(orientation, cost);
+
+            let due = if index == 0 {
+                ready
+            } else {
+                previous_shown + dwell
+            };
+            if ready > due {
+                late += 1;
+            }
+            previous_shown = ready.max(due);
+            shown_at.push(previous_shown);
+        }
+        late
+    }
+
+    #[test]
+    fn mixed_orientations_no_longer_starve_the_slideshow() {
+        // A portrait takes longer than the two dwells a depth of 2 allows.
+        let landscape = (Orientation::Landscape, SECOND * 2);
+        let portrait = (Orientation::Portrait, SECOND * 20);
+        let sequence: Vec<_> = [landscape, portrait, landscape, landscape, landscape]
+            .into_iter()
+            .cycle()
+            .take(40)
+            .collect();
+
+        let fixed = late_photos(&sequence, 2, false);
+        assert!(
+            fixed > 5,
+            "a fixed depth misses portrait deadlines: {fixed}"
+        );
+        // Only the first portrait, before anything was learned, may be late.
+        let adaptive = late_photos(&sequence, 2, true);
+        assert!(
+            adaptive <= 1,
+            "adaptive depth still starved {adaptive} photos"
+        );
+    }
+}
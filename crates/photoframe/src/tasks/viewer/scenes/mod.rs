@@ -3,22 +3,29 @@
 //! This module will house the logic for state-specific viewer behaviour.
 
 use std::collections::VecDeque;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 use glyphon::{
     Attrs, Buffer, Color as GlyphonColor, FamilyOwned, FontSystem, Metrics, Shaping, SwashCache,
     Wrap,
 };
-use rand::Rng;
+use rand::{Rng, SeedableRng, rngs::StdRng};
 use wgpu::{CommandEncoder, TextureView};
 use winit::dpi::PhysicalSize;
 use winit::window::Window;
 
-use crate::config::{Configuration, MattingKind, TransitionConfig, TransitionKind};
-use crate::tasks::greeting_screen::GreetingScreen;
+use crate::config::{
+    CaptionConfig, CaptionCorner, Configuration, MattingKind, MessageContext, MotionConfig,
+    ScreenMessageConfig, ShareQrConfig, SleepScreenConfig, TransitionConfig, TransitionKind,
+};
+use crate::share;
+use crate::tasks::greeting_screen::{
+    GreetingScreen, initialize_font_database, resolve_font_family,
+};
 
+use super::motion::{Framing, KenBurnsPath, dwell_progress};
 use super::{ImgTex, TransitionState};
 
 // ── Caption overlay ───────────────────────────────────────────────────────────
@@ -32,65 +39,36 @@ struct CompositeUniforms {
     rect: [f32; 4],
 }
 
-/// Lightweight text overlay rendered on top of the live photo via `LoadOp::Load`.
-/// Draws a single short line in the bottom-left corner, on a solid backing panel
-/// so it stays legible over any mat (light, dark, or busy).
-pub(super) struct CaptionOverlay {
-    device: wgpu::Device,
-    queue: wgpu::Queue,
-    // Text is shaped (cosmic-text) and rasterized to a CPU buffer (swash), then
-    // uploaded as one plain texture. We deliberately do NOT use glyphon's GPU
-    // glyph atlas: its eviction/growth corrupts glyphs on the Pi's V3D driver.
-    text_buffer: Buffer,
-    font_system: FontSystem,
-    swash_cache: SwashCache,
-    // The finished caption (backing + text), rebuilt only on text/size change and
-    // composited as a single scissored triangle every frame.
-    cache_texture: Option<wgpu::Texture>,
-    cache_view: Option<wgpu::TextureView>,
-    cache_dims: (u32, u32),
-    composite_pipeline: wgpu::RenderPipeline,
-    composite_layout: wgpu::BindGroupLayout,
-    composite_sampler: wgpu::Sampler,
-    composite_uniform_buffer: wgpu::Buffer,
-    composite_bind_group: Option<wgpu::BindGroup>,
-    text: String,
-    size: PhysicalSize<u32>,
-    dirty: bool,
+/// The pipeline that composites a cached, premultiplied panel over the frame
+/// as one triangle scissored to the panel rect in pixel space
+/// (caption_composite.wgsl explains why it must not be a quad).
+struct CompositePipeline {
+    pipeline: wgpu::RenderPipeline,
+    layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
 }
 
-impl CaptionOverlay {
-    pub(super) fn new(
-        device: &wgpu::Device,
-        queue: &wgpu::Queue,
-        format: wgpu::TextureFormat,
-    ) -> Self {
-        let mut font_system = FontSystem::new();
-        let mut text_buffer = Buffer::new(&mut font_system, Metrics::new(28.0, 34.0));
-        text_buffer.set_wrap(&mut font_system, Wrap::None);
-        let swash_cache = SwashCache::new();
-
-        // Composite pipeline: samples the cached caption texture and draws it as
-        // one premultiplied-alpha triangle, scissored to the panel rect in pixel
-        // space (caption_composite.wgsl explains why it must not be a quad).
-        let composite_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+impl CompositePipeline {
+    fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("caption-composite-shader"),
             source: wgpu::ShaderSource::Wgsl(include_str!("caption_composite.wgsl").into()),
         });
-        let composite_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("caption-composite-uniforms"),
             size: std::mem::size_of::<CompositeUniforms>() as u64,
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
-        let composite_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
             label: Some("caption-composite-sampler"),
             // 1:1 blit (cache rendered at exact panel pixel size) keeps text crisp.
             mag_filter: wgpu::FilterMode::Nearest,
             min_filter: wgpu::FilterMode::Nearest,
             ..Default::default()
         });
-        let composite_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("caption-composite-bind-layout"),
             entries: &[
                 wgpu::BindGroupLayoutEntry {
@@ -123,23 +101,22 @@ impl CaptionOverlay {
                 },
             ],
         });
-        let composite_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("caption-composite-pipeline-layout"),
-                bind_group_layouts: &[&composite_layout],
-                push_constant_ranges: &[],
-            });
-        let composite_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("caption-composite-pipeline-layout"),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("caption-composite-pipeline"),
-            layout: Some(&composite_pipeline_layout),
+            layout: Some(&pipeline_layout),
             vertex: wgpu::VertexState {
-                module: &composite_shader,
+                module: &shader,
                 entry_point: Some("vs_main"),
                 buffers: &[],
                 compilation_options: wgpu::PipelineCompilationOptions::default(),
             },
             fragment: Some(wgpu::FragmentState {
-                module: &composite_shader,
+                module: &shader,
                 entry_point: Some("fs_main"),
                 targets: &[Some(wgpu::ColorTargetState {
                     format,
@@ -155,6 +132,201 @@ impl CaptionOverlay {
             multiview: None,
             cache: None,
         });
+        Self {
+            pipeline,
+            layout,
+            sampler,
+            uniform_buffer,
+        }
+    }
+
+    fn bind_group(&self, device: &wgpu::Device, view: &wgpu::TextureView) -> wgpu::BindGroup {
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("caption-composite-bind-group"),
+            layout: &self.layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: self.uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        })
+    }
+
+    /// Draws the panel bound in `bind_group` at `rect` (x, y, width, height in
+    /// pixels, top-left origin) over `target_view`.
+    fn draw(
+        &self,
+        queue: &wgpu::Queue,
+        encoder: &mut wgpu::CommandEncoder,
+        target_view: &wgpu::TextureView,
+        bind_group: &wgpu::BindGroup,
+        surface: PhysicalSize<u32>,
+        rect: [f32; 4],
+    ) -> bool {
+        // The scissor, not the geometry, bounds the panel (the shader emits one
+        // oversized triangle — see caption_composite.wgsl for the V3D tile-binner
+        // bug this works around). Clamp to the surface: wgpu rejects a scissor
+        // that hangs past the attachment, and a panel wider than a small
+        // surface would otherwise panic instead of rendering partially.
+        let [rect_x, rect_y, width, height] = rect;
+        let (cw, ch) = (width as u32, height as u32);
+        let scissor_x = rect_x as u32;
+        let scissor_y = rect_y as u32;
+        let scissor_w = cw.min(surface.width.saturating_sub(scissor_x));
+        let scissor_h = ch.min(surface.height.saturating_sub(scissor_y));
+        if scissor_w == 0 || scissor_h == 0 {
+            return false;
+        }
+
+        let uniforms = CompositeUniforms {
+            resolution: [surface.width as f32, surface.height as f32],
+            _pad0: [0.0, 0.0],
+            rect,
+        };
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+
+        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("caption-composite"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: target_view,
+                depth_slice: None,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(&self.pipeline);
+        pass.set_bind_group(0, bind_group, &[]);
+        pass.set_scissor_rect(scissor_x, scissor_y, scissor_w, scissor_h);
+        pass.draw(0..3, 0..1);
+        true
+    }
+}
+
+/// Top-left pixel of a `width` x `height` panel set `inset_x`/`inset_y` in
+/// from the edges of a `surface`-sized target, in `corner`.
+fn corner_origin(
+    corner: CaptionCorner,
+    surface: PhysicalSize<u32>,
+    (width, height): (u32, u32),
+    inset_x: f32,
+    inset_y: f32,
+) -> (f32, f32) {
+    let right = surface.width as f32 - inset_x - width as f32;
+    let bottom = surface.height as f32 - inset_y - height as f32;
+    let (x, y) = match corner {
+        CaptionCorner::TopLeft => (inset_x, inset_y),
+        CaptionCorner::TopRight => (right, inset_y),
+        CaptionCorner::BottomLeft => (inset_x, bottom),
+        CaptionCorner::BottomRight => (right, bottom),
+    };
+    (x.max(0.0).floor(), y.max(0.0).floor())
+}
+
+/// Where a [`CaptionOverlay`] sits and how large its text is.
+#[derive(Debug, Clone)]
+pub(super) struct CaptionStyle {
+    pub(super) corner: CaptionCorner,
+    /// Font family name; `None` uses the default sans-serif face.
+    pub(super) font: Option<String>,
+    /// Text height in pixels.
+    pub(super) size: f32,
+}
+
+impl Default for CaptionStyle {
+    fn default() -> Self {
+        Self {
+            corner: CaptionCorner::BottomLeft,
+            font: None,
+            size: 28.0,
+        }
+    }
+}
+
+impl From<&CaptionConfig> for CaptionStyle {
+    fn from(cfg: &CaptionConfig) -> Self {
+        Self {
+            corner: cfg.corner,
+            font: cfg.font.clone(),
+            size: cfg.size,
+        }
+    }
+}
+
+/// Lightweight text overlay rendered on top of the live photo via `LoadOp::Load`.
+/// Draws a single short line in one corner (bottom-left unless styled
+/// otherwise), on a solid backing panel so it stays legible over any mat
+/// (light, dark, or busy).
+pub(super) struct CaptionOverlay {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    // Text is shaped (cosmic-text) and rasterized to a CPU buffer (swash), then
+    // uploaded as one plain texture. We deliberately do NOT use glyphon's GPU
+    // glyph atlas: its eviction/growth corrupts glyphs on the Pi's V3D driver.
+    text_buffer: Buffer,
+    font_system: FontSystem,
+    swash_cache: SwashCache,
+    font_family: FamilyOwned,
+    corner: CaptionCorner,
+    font_size: f32,
+    // The finished caption (backing + text), rebuilt only on text/size change and
+    // composited as a single scissored triangle every frame.
+    cache_texture: Option<wgpu::Texture>,
+    cache_view: Option<wgpu::TextureView>,
+    cache_dims: (u32, u32),
+    composite: CompositePipeline,
+    composite_bind_group: Option<wgpu::BindGroup>,
+    text: String,
+    size: PhysicalSize<u32>,
+    dirty: bool,
+}
+
+impl CaptionOverlay {
+    pub(super) fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        Self::with_style(device, queue, format, CaptionStyle::default())
+    }
+
+    pub(super) fn with_style(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        style: CaptionStyle,
+    ) -> Self {
+        let mut font_system = FontSystem::new();
+        let font_family = match style.font.as_deref() {
+            Some(name) => {
+                initialize_font_database(font_system.db_mut());
+                resolve_font_family(&font_system, Some(name))
+            }
+            None => FamilyOwned::SansSerif,
+        };
+        let font_size = style.size;
+        let mut text_buffer = Buffer::new(
+            &mut font_system,
+            Metrics::new(font_size, Self::line_height(font_size)),
+        );
+        text_buffer.set_wrap(&mut font_system, Wrap::None);
+        let swash_cache = SwashCache::new();
+
+        let composite = CompositePipeline::new(device, format);
 
         Self {
             device: device.clone(),
@@ -162,13 +334,13 @@ impl CaptionOverlay {
             text_buffer,
             font_system,
             swash_cache,
+            font_family,
+            corner: style.corner,
+            font_size,
             cache_texture: None,
             cache_view: None,
             cache_dims: (0, 0),
-            composite_pipeline,
-            composite_layout,
-            composite_sampler,
-            composite_uniform_buffer,
+            composite,
             composite_bind_group: None,
             text: String::new(),
             size: PhysicalSize::new(0, 0),
@@ -176,6 +348,21 @@ impl CaptionOverlay {
         }
     }
 
+    fn line_height(font_size: f32) -> f32 {
+        (font_size * 1.2).round()
+    }
+
+    /// Line height and horizontal/vertical panel padding, in pixels; 34, 14,
+    /// and 8 at the default 28 px text.
+    fn panel_metrics(&self) -> (f32, f32, f32) {
+        let size = self.font_size;
+        (
+            Self::line_height(size),
+            (size * 0.5).round(),
+            (size * 2.0 / 7.0).round(),
+        )
+    }
+
     pub(super) fn set_text(&mut self, text: impl Into<String>) {
         let t = text.into();
         if self.text != t {
@@ -212,60 +399,23 @@ impl CaptionOverlay {
             return false;
         };
 
-        // Place the cached panel at the bottom-left of the current surface.
+        // Place the cached panel in its corner of the current surface, with the
+        // text `margin` in from the edges.
         let margin = 20.0_f32;
-        let pad_x = 14.0_f32;
-        let pad_y = 8.0_f32;
-        let line_h = 34.0_f32;
-        let top = (self.size.height as f32 - line_h - margin).max(0.0);
-        let rect_x = (margin - pad_x).max(0.0).floor();
-        let rect_y = (top - pad_y).max(0.0).floor();
+        let (_, pad_x, pad_y) = self.panel_metrics();
         let (cw, ch) = self.cache_dims;
+        let inset_x = (margin - pad_x).max(0.0);
+        let inset_y = (margin - pad_y).max(0.0);
+        let (rect_x, rect_y) = corner_origin(self.corner, self.size, (cw, ch), inset_x, inset_y);
 
-        // The scissor, not the geometry, bounds the panel (the shader emits one
-        // oversized triangle — see caption_composite.wgsl for the V3D tile-binner
-        // bug this works around). Clamp to the surface: wgpu rejects a scissor
-        // that hangs past the attachment, and a caption wider than a small
-        // surface would otherwise panic instead of rendering partially.
-        let scissor_x = rect_x as u32;
-        let scissor_y = rect_y as u32;
-        let scissor_w = cw.min(self.size.width.saturating_sub(scissor_x));
-        let scissor_h = ch.min(self.size.height.saturating_sub(scissor_y));
-        if scissor_w == 0 || scissor_h == 0 {
-            return false;
-        }
-
-        let uniforms = CompositeUniforms {
-            resolution: [self.size.width as f32, self.size.height as f32],
-            _pad0: [0.0, 0.0],
-            rect: [rect_x, rect_y, cw as f32, ch as f32],
-        };
-        self.queue.write_buffer(
-            &self.composite_uniform_buffer,
-            0,
-            bytemuck::bytes_of(&uniforms),
-        );
-
-        let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            label: Some("caption-composite"),
-            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: target_view,
-                depth_slice: None,
-                resolve_target: None,
-                ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Load,
-                    store: wgpu::StoreOp::Store,
-                },
-            })],
-            depth_stencil_attachment: None,
-            occlusion_query_set: None,
-            timestamp_writes: None,
-        });
-        pass.set_pipeline(&self.composite_pipeline);
-        pass.set_bind_group(0, bind_group, &[]);
-        pass.set_scissor_rect(scissor_x, scissor_y, scissor_w, scissor_h);
-        pass.draw(0..3, 0..1);
-        true
+        self.composite.draw(
+            &self.queue,
+            encoder,
+            target_view,
+            bind_group,
+            self.size,
+            [rect_x, rect_y, cw as f32, ch as f32],
+        )
     }
 
     /// Shape the caption and rasterize it (dark backing + text) to a CPU buffer,
@@ -280,18 +430,16 @@ impl CaptionOverlay {
             }
         }
 
-        let pad_x = 14.0_f32;
-        let pad_y = 8.0_f32;
-        let line_h = 34.0_f32;
+        let (line_h, pad_x, pad_y) = self.panel_metrics();
 
         // Shape the full line before measuring.
         self.text_buffer.set_metrics_and_size(
             &mut self.font_system,
-            Metrics::new(28.0, 34.0),
+            Metrics::new(self.font_size, line_h),
             Some(self.size.width as f32),
             Some(self.size.height as f32),
         );
-        let attrs = Attrs::new().family(FamilyOwned::SansSerif.as_family());
+        let attrs = Attrs::new().family(self.font_family.as_family());
         self.text_buffer.set_text(
             &mut self.font_system,
             &self.text,
@@ -334,24 +482,7 @@ impl CaptionOverlay {
                 view_formats: &[],
             });
             let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
-            let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                label: Some("caption-composite-bind-group"),
-                layout: &self.composite_layout,
-                entries: &[
-                    wgpu::BindGroupEntry {
-                        binding: 0,
-                        resource: self.composite_uniform_buffer.as_entire_binding(),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 1,
-                        resource: wgpu::BindingResource::TextureView(&view),
-                    },
-                    wgpu::BindGroupEntry {
-                        binding: 2,
-                        resource: wgpu::BindingResource::Sampler(&self.composite_sampler),
-                    },
-                ],
-            });
+            let bind_group = self.composite.bind_group(&self.device, &view);
             self.cache_texture = Some(texture);
             self.cache_view = Some(view);
             self.composite_bind_group = Some(bind_group);
@@ -454,6 +585,119 @@ impl CaptionOverlay {
     }
 }
 
+// ── Share QR overlay ──────────────────────────────────────────────────────────
+
+/// The `share-qr` code in a corner of the photo, linking to the photo on
+/// screen. Redrawn only when the photo changes.
+pub(super) struct ShareQrOverlay {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    base_url: String,
+    corner: CaptionCorner,
+    size: u32,
+    /// Photo the uploaded code links to; set even when drawing it failed, so a
+    /// bad link is not retried every frame.
+    photo: Option<PathBuf>,
+    /// The uploaded code and its width in pixels; `None` when drawing failed.
+    code: Option<(wgpu::Texture, wgpu::BindGroup, u32)>,
+    composite: CompositePipeline,
+}
+
+impl ShareQrOverlay {
+    pub(super) fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        format: wgpu::TextureFormat,
+        config: &ShareQrConfig,
+    ) -> Self {
+        Self {
+            device: device.clone(),
+            queue: queue.clone(),
+            base_url: config.base_url.clone(),
+            corner: config.corner,
+            size: config.size,
+            photo: None,
+            code: None,
+            composite: CompositePipeline::new(device, format),
+        }
+    }
+
+    /// Draws the code for `photo` over `target_view`.
+    pub(super) fn render(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        target_view: &wgpu::TextureView,
+        photo: &Path,
+        surface: PhysicalSize<u32>,
+    ) -> bool {
+        if self.photo.as_deref() != Some(photo) {
+            self.photo = Some(photo.to_path_buf());
+            let url = share::photo_url(&self.base_url, photo);
+            self.code = match share::render_qr(&url, self.size) {
+                Ok(qr) => Some(self.upload(&qr)),
+                Err(err) => {
+                    tracing::warn!(error = ?err, "failed to draw the share QR code");
+                    None
+                }
+            };
+        }
+        let Some((_, bind_group, width)) = self.code.as_ref() else {
+            return false;
+        };
+        let margin = 20.0_f32;
+        let (x, y) = corner_origin(self.corner, surface, (*width, *width), margin, margin);
+        self.composite.draw(
+            &self.queue,
+            encoder,
+            target_view,
+            bind_group,
+            surface,
+            [x, y, *width as f32, *width as f32],
+        )
+    }
+
+    fn upload(&self, qr: &share::QrImage) -> (wgpu::Texture, wgpu::BindGroup, u32) {
+        let extent = wgpu::Extent3d {
+            width: qr.size,
+            height: qr.size,
+            depth_or_array_layers: 1,
+        };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("share-qr-texture"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            // Opaque black and white, so the bytes need no premultiplying.
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        self.queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &qr.rgba,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(qr.size * 4),
+                rows_per_image: Some(qr.size),
+            },
+            extent,
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.composite.bind_group(&self.device, &view);
+        (texture, bind_group, qr.size)
+    }
+
+    pub(super) fn after_submit(&mut self) {
+        let _ = self.device.poll(wgpu::PollType::Poll);
+    }
+}
+
 /// Build the showcase caption string from the current transition and mat.
 pub(super) fn showcase_caption(
     transition_kind: Option<TransitionKind>,
@@ -468,12 +712,62 @@ pub(super) fn showcase_caption(
     format!("transition: {t}    mat: {m}")
 }
 
+/// Minimum spacing between message changes that force an overlay re-layout.
+const MESSAGE_REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Rate-limits overlay text changes so fast-moving placeholders (the startup
+/// scan's `{photo-count}`) do not rebuild the text layout every frame. Callers
+/// re-offer the current text each tick, so the latest value always lands once
+/// the interval has passed.
+#[derive(Debug)]
+pub(super) struct MessageThrottle {
+    min_interval: Duration,
+    applied: Option<(String, Instant)>,
+}
+
+impl MessageThrottle {
+    pub(super) fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            applied: None,
+        }
+    }
+
+    /// Whether `message` should be laid out now: it differs from the last
+    /// applied text and the last change is at least `min_interval` old.
+    pub(super) fn offer(&mut self, message: &str, now: Instant) -> bool {
+        if let Some((current, at)) = &self.applied
+            && (current == message || now.saturating_duration_since(*at) < self.min_interval)
+        {
+            return false;
+        }
+        self.record(message, now);
+        true
+    }
+
+    /// Notes a message applied outside the throttle (e.g. on scene entry).
+    pub(super) fn record(&mut self, message: &str, now: Instant) {
+        self.applied = Some((message.to_owned(), now));
+    }
+}
+
+/// Eased opacity for an overlay fade at linear `level` (0 hidden, 1 shown).
+pub(super) fn overlay_fade_alpha(level: f32) -> f32 {
+    let t = level.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
 struct OverlayScene {
     screen: GreetingScreen,
     layout_dirty: bool,
     redraw_pending: bool,
     size: PhysicalSize<u32>,
     scale_factor: f64,
+    /// Enter/exit fade driven by `fade-ms`.
+    fade: FadeClock,
+    /// Opacity imposed from outside the scene (the sleep transition).
+    external_opacity: f32,
+    message_throttle: MessageThrottle,
 }
 
 impl OverlayScene {
@@ -484,6 +778,54 @@ impl OverlayScene {
             redraw_pending: true,
             size: PhysicalSize::new(0, 0),
             scale_factor: 1.0,
+            fade: FadeClock::settled(1.0, Instant::now()),
+            external_opacity: 1.0,
+            message_throttle: MessageThrottle::new(MESSAGE_REFRESH_INTERVAL),
+        }
+    }
+
+    /// Ramps the overlay in from black over `duration` (instantly when zero).
+    fn begin_fade_in(&mut self, duration: Duration) {
+        let now = Instant::now();
+        self.fade = FadeClock::start(now, duration, true, 0.0);
+        self.apply_opacity(now);
+    }
+
+    /// Reverses toward hidden from the current opacity, keeping the same rate.
+    fn begin_fade_out(&mut self) {
+        let now = Instant::now();
+        self.fade.head(now, false);
+        self.apply_opacity(now);
+    }
+
+    /// Cancels a pending fade-out, ramping back in from the current opacity.
+    fn resume_fade_in(&mut self) {
+        let now = Instant::now();
+        self.fade.head(now, true);
+        self.apply_opacity(now);
+    }
+
+    fn show_immediately(&mut self) {
+        let now = Instant::now();
+        self.fade = FadeClock::settled(1.0, now);
+        self.apply_opacity(now);
+    }
+
+    fn fade_out_complete(&self) -> bool {
+        !self.fade.is_rising() && self.fade.is_complete(Instant::now())
+    }
+
+    fn set_external_opacity(&mut self, opacity: f32) {
+        self.external_opacity = opacity.clamp(0.0, 1.0);
+        self.apply_opacity(Instant::now());
+    }
+
+    /// Pushes the current fade opacity to the screen, marking a redraw when it
+    /// changed.
+    fn apply_opacity(&mut self, now: Instant) {
+        let alpha = overlay_fade_alpha(self.fade.level(now)) * self.external_opacity;
+        if self.screen.set_opacity(alpha) {
+            self.mark_redraw_needed();
         }
     }
 
@@ -498,11 +840,20 @@ impl OverlayScene {
     }
 
     fn set_message(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        self.message_throttle.record(&message, Instant::now());
         if self.screen.set_message(message) {
             self.mark_layout_dirty();
         }
     }
 
+    /// Per-tick message update; re-layouts at most every [`MESSAGE_REFRESH_INTERVAL`].
+    fn refresh_message(&mut self, message: String, now: Instant) {
+        if self.message_throttle.offer(&message, now) && self.screen.set_message(message) {
+            self.mark_layout_dirty();
+        }
+    }
+
     fn ensure_layout_ready(&mut self) -> bool {
         if !self.layout_dirty {
             return true;
@@ -576,6 +927,137 @@ impl GreetingScene {
         self.overlay.render(encoder, target_view)
     }
 
+    /// Starts fading the overlay out ahead of leaving this scene.
+    pub(super) fn begin_fade_out(&mut self) {
+        self.overlay.begin_fade_out();
+    }
+
+    /// Cancels a fade-out begun by [`Self::begin_fade_out`].
+    pub(super) fn resume_fade_in(&mut self) {
+        self.overlay.resume_fade_in();
+    }
+
+    /// Whether a fade-out has finished and the scene can be left.
+    pub(super) fn fade_out_complete(&self) -> bool {
+        self.overlay.fade_out_complete()
+    }
+
+    pub(super) fn mark_redraw_needed(&mut self) {
+        self.overlay.mark_redraw_needed();
+    }
+
+    pub(super) fn needs_redraw(&self) -> bool {
+        self.overlay.needs_redraw()
+    }
+
+    pub(super) fn after_submit(&mut self) {
+        self.overlay.after_submit();
+    }
+}
+
+impl Scene for GreetingScene {
+    fn enter(&mut self, mut ctx: SceneContext<'_>) {
+        if let (Some(window), Some(size)) = (ctx.window(), ctx.surface_size()) {
+            self.resize(size, window.scale_factor());
+        }
+        let screen = ctx.config().greeting_screen.screen();
+        let message = ctx.screen_message(screen);
+        let fade = screen.effective_fade();
+        self.set_message(message);
+        self.overlay.begin_fade_in(fade);
+        self.mark_redraw_needed();
+        ctx.request_redraw();
+    }
+
+    fn process_tick(&mut self, mut ctx: SceneContext<'_>) {
+        // Keeps `{time}`/`{date}`/scan-progress placeholders current; unchanged
+        // text is a no-op and changes are throttled.
+        let message = ctx.screen_message(ctx.config().greeting_screen.screen());
+        let now = Instant::now();
+        self.overlay.refresh_message(message, now);
+        self.overlay.apply_opacity(now);
+        if self.needs_redraw() {
+            ctx.request_redraw();
+        }
+    }
+
+    fn handle_resize(
+        &mut self,
+        mut ctx: SceneContext<'_>,
+        new_size: PhysicalSize<u32>,
+        scale_factor: f64,
+    ) {
+        self.resize(new_size, scale_factor);
+        self.mark_redraw_needed();
+        ctx.request_redraw();
+    }
+
+    fn handle_visibility(&mut self, mut ctx: SceneContext<'_>, is_visible: bool) {
+        if is_visible {
+            self.mark_redraw_needed();
+            ctx.request_redraw();
+        }
+    }
+}
+
+/// State container for the sleep overlay scene.
+pub(super) struct SleepScene {
+    overlay: OverlayScene,
+}
+
+impl SleepScene {
+    pub(super) fn new(screen: GreetingScreen) -> Self {
+        Self {
+            overlay: OverlayScene::new(screen),
+        }
+    }
+
+    pub(super) fn resize(&mut self, new_size: PhysicalSize<u32>, scale_factor: f64) {
+        self.overlay.resize(new_size, scale_factor);
+    }
+
+    pub(super) fn set_message(&mut self, message: impl Into<String>) {
+        self.overlay.set_message(message);
+    }
+
+    /// Fades the sleep screen in from black during the sleep transition, or
+    /// holds it dimmed while the frame is ambient.
+    pub(super) fn set_opacity(&mut self, opacity: f32) {
+        self.overlay.set_external_opacity(opacity);
+    }
+
+    /// Skips the `fade-ms` ramp; the sleep transition supplies its own fade.
+    pub(super) fn show_immediately(&mut self) {
+        self.overlay.show_immediately();
+    }
+
+    pub(super) fn ensure_layout_ready(&mut self) -> bool {
+        self.overlay.ensure_layout_ready()
+    }
+
+    pub(super) fn render(
+        &mut self,
+        encoder: &mut CommandEncoder,
+        target_view: &TextureView,
+    ) -> bool {
+        self.overlay.render(encoder, target_view)
+    }
+
+    /// Starts fading the overlay out ahead of leaving this scene.
+    pub(super) fn begin_fade_out(&mut self) {
+        self.overlay.begin_fade_out();
+    }
+
+    /// Cancels a fade-out begun by [`Self::begin_fade_out`].
+    pub(super) fn resume_fade_in(&mut self) {
+        self.overlay.resume_fade_in();
+    }
+
+    /// Whether a fade-out has finished and the scene can be left.
+    pub(super) fn fade_out_complete(&self) -> bool {
+        self.overlay.fade_out_complete()
+    }
+
     pub(super) fn mark_redraw_needed(&mut self) {
         self.overlay.mark_redraw_needed();
     }
@@ -589,23 +1071,32 @@ impl GreetingScene {
     }
 }
 
-impl Scene for GreetingScene {
+impl Scene for SleepScene {
     fn enter(&mut self, mut ctx: SceneContext<'_>) {
-        if let Some(window) = ctx.window() {
-            self.resize(window.inner_size(), window.scale_factor());
-        }
-        let message = ctx
-            .config()
-            .greeting_screen
-            .screen()
-            .message_or_default()
-            .into_owned();
+        if let (Some(window), Some(size)) = (ctx.window(), ctx.surface_size()) {
+            self.resize(size, window.scale_factor());
+        }
+        let message = ctx.sleep_message(&ctx.config().sleep_screen);
+        let fade = ctx.config().sleep_screen.screen().effective_fade();
         self.set_message(message);
+        self.overlay.begin_fade_in(fade);
         self.mark_redraw_needed();
         ctx.request_redraw();
     }
 
+    fn exit(&mut self, _ctx: SceneContext<'_>) {
+        // Leave the screen fully visible for the next entry; a sleep fade
+        // re-applies its own opacity as soon as it re-enters.
+        self.overlay.set_external_opacity(1.0);
+    }
+
     fn process_tick(&mut self, mut ctx: SceneContext<'_>) {
+        // Keeps the clock and `{time}`/`{date}` placeholders current; the text
+        // (and so the layout) only changes when the minute does.
+        let message = ctx.sleep_message(&ctx.config().sleep_screen);
+        let now = Instant::now();
+        self.overlay.refresh_message(message, now);
+        self.overlay.apply_opacity(now);
         if self.needs_redraw() {
             ctx.request_redraw();
         }
@@ -630,12 +1121,13 @@ impl Scene for GreetingScene {
     }
 }
 
-/// State container for the sleep overlay scene.
-pub(super) struct SleepScene {
+/// State container for the empty-library overlay, shown in place of the
+/// slideshow while the library has no photos.
+pub(super) struct EmptyScene {
     overlay: OverlayScene,
 }
 
-impl SleepScene {
+impl EmptyScene {
     pub(super) fn new(screen: GreetingScreen) -> Self {
         Self {
             overlay: OverlayScene::new(screen),
@@ -662,6 +1154,21 @@ impl SleepScene {
         self.overlay.render(encoder, target_view)
     }
 
+    /// Starts fading the overlay out ahead of leaving this scene.
+    pub(super) fn begin_fade_out(&mut self) {
+        self.overlay.begin_fade_out();
+    }
+
+    /// Cancels a fade-out begun by [`Self::begin_fade_out`].
+    pub(super) fn resume_fade_in(&mut self) {
+        self.overlay.resume_fade_in();
+    }
+
+    /// Whether a fade-out has finished and the scene can be left.
+    pub(super) fn fade_out_complete(&self) -> bool {
+        self.overlay.fade_out_complete()
+    }
+
     pub(super) fn mark_redraw_needed(&mut self) {
         self.overlay.mark_redraw_needed();
     }
@@ -675,23 +1182,24 @@ impl SleepScene {
     }
 }
 
-impl Scene for SleepScene {
+impl Scene for EmptyScene {
     fn enter(&mut self, mut ctx: SceneContext<'_>) {
-        if let Some(window) = ctx.window() {
-            self.resize(window.inner_size(), window.scale_factor());
-        }
-        let message = ctx
-            .config()
-            .sleep_screen
-            .screen()
-            .message_or_default()
-            .into_owned();
+        if let (Some(window), Some(size)) = (ctx.window(), ctx.surface_size()) {
+            self.resize(size, window.scale_factor());
+        }
+        let message = ctx.empty_library_message();
+        let fade = ctx.config().empty_library_screen.screen().effective_fade();
         self.set_message(message);
+        self.overlay.begin_fade_in(fade);
         self.mark_redraw_needed();
         ctx.request_redraw();
     }
 
     fn process_tick(&mut self, mut ctx: SceneContext<'_>) {
+        let message = ctx.empty_library_message();
+        let now = Instant::now();
+        self.overlay.refresh_message(message, now);
+        self.overlay.apply_opacity(now);
         if self.needs_redraw() {
             ctx.request_redraw();
         }
@@ -716,6 +1224,169 @@ impl Scene for SleepScene {
     }
 }
 
+/// Linear 0..=1 animation level that can be turned around mid-flight.
+///
+/// The level moves at a constant rate of one full sweep per `duration`, so a
+/// reversal retraces from wherever it is and takes only as long as the
+/// distance already covered.
+#[derive(Debug, Clone)]
+pub(super) struct FadeClock {
+    duration: Duration,
+    rising: bool,
+    from_level: f32,
+    started_at: Instant,
+}
+
+impl FadeClock {
+    /// A clock already resting at `level`.
+    pub(super) fn settled(level: f32, now: Instant) -> Self {
+        Self {
+            duration: Duration::ZERO,
+            rising: level >= 1.0,
+            from_level: level.clamp(0.0, 1.0),
+            started_at: now,
+        }
+    }
+
+    /// Starts a sweep from `from_level` toward 1 (`rising`) or 0.
+    pub(super) fn start(now: Instant, duration: Duration, rising: bool, from_level: f32) -> Self {
+        Self {
+            duration,
+            rising,
+            from_level: from_level.clamp(0.0, 1.0),
+            started_at: now,
+        }
+    }
+
+    pub(super) fn is_rising(&self) -> bool {
+        self.rising
+    }
+
+    /// Heads toward 1 (`rising`) or 0 from the current level; a no-op when
+    /// already heading that way.
+    pub(super) fn head(&mut self, now: Instant, rising: bool) {
+        if self.rising == rising {
+            return;
+        }
+        self.from_level = self.level(now);
+        self.started_at = now;
+        self.rising = rising;
+    }
+
+    pub(super) fn level(&self, now: Instant) -> f32 {
+        let delta = if self.duration.is_zero() {
+            1.0
+        } else {
+            now.saturating_duration_since(self.started_at).as_secs_f32()
+                / self.duration.as_secs_f32()
+        };
+        if self.rising {
+            (self.from_level + delta).min(1.0)
+        } else {
+            (self.from_level - delta).max(0.0)
+        }
+    }
+
+    pub(super) fn is_complete(&self, now: Instant) -> bool {
+        let level = self.level(now);
+        if self.rising {
+            level >= 1.0
+        } else {
+            level <= 0.0
+        }
+    }
+}
+
+/// Which way a [`SleepFade`] is heading.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum SleepFadeDirection {
+    ToSleep,
+    ToWake,
+}
+
+/// What the viewer should show at a point in a [`SleepFade`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum SleepFadeFrame {
+    /// The slideshow, darkened toward black by `black` (0 = untouched, 1 = black).
+    Slideshow { black: f32 },
+    /// The sleep screen, faded in from black by `opacity`.
+    SleepScreen { opacity: f32 },
+}
+
+/// Wake → sleep transition: the slideshow fades to black over the first half
+/// of its duration, then the sleep screen fades in over the second half.
+///
+/// Level 0 is the slideshow and 1 the sleep screen, so reversing mid-fade
+/// (waking up while falling asleep) retraces the same path.
+#[derive(Debug, Clone)]
+pub(super) struct SleepFade {
+    clock: FadeClock,
+    /// Started from black by [`SleepFade::reveal`]; the sleep screen is not
+    /// part of the path until the fade is turned around.
+    from_black: bool,
+}
+
+impl SleepFade {
+    /// Starts fading from the slideshow toward the sleep screen.
+    pub(super) fn to_sleep(now: Instant, duration: Duration) -> Self {
+        Self {
+            clock: FadeClock::start(now, duration, true, 0.0),
+            from_black: false,
+        }
+    }
+
+    /// Starts on the sleep screen and heads back to the slideshow, so waking
+    /// from sleep retraces the path the frame took falling asleep.
+    pub(super) fn to_wake(now: Instant, duration: Duration) -> Self {
+        Self {
+            clock: FadeClock::start(now, duration, false, 1.0),
+            from_black: false,
+        }
+    }
+
+    /// Only the slideshow half of a wake: from black back to the photo over
+    /// half of `duration`. Used when the screen before it was not the sleep
+    /// screen (the greeting).
+    pub(super) fn reveal(now: Instant, duration: Duration) -> Self {
+        Self {
+            clock: FadeClock::start(now, duration, false, 0.5),
+            from_black: true,
+        }
+    }
+
+    pub(super) fn direction(&self) -> SleepFadeDirection {
+        if self.clock.is_rising() {
+            SleepFadeDirection::ToSleep
+        } else {
+            SleepFadeDirection::ToWake
+        }
+    }
+
+    /// Turns the fade around, continuing from its current level.
+    pub(super) fn reverse(&mut self, now: Instant) {
+        let rising = self.clock.is_rising();
+        self.clock.head(now, !rising);
+        self.from_black = false;
+    }
+
+    pub(super) fn is_complete(&self, now: Instant) -> bool {
+        self.clock.is_complete(now)
+    }
+
+    pub(super) fn frame(&self, now: Instant) -> SleepFadeFrame {
+        let level = self.clock.level(now);
+        if level < 0.5 || self.from_black {
+            SleepFadeFrame::Slideshow {
+                black: (level * 2.0).min(1.0),
+            }
+        } else {
+            SleepFadeFrame::SleepScreen {
+                opacity: level * 2.0 - 1.0,
+            }
+        }
+    }
+}
+
 /// Minimum spacing between transition frame submissions. Mailbox
 /// presentation never blocks, so an unpaced render loop can submit frames
 /// faster than the compositor latches them; mailbox then discards the older
@@ -724,6 +1395,14 @@ impl Scene for SleepScene {
 /// just under one 60 Hz refresh so a healthy loop still reaches 60 fps.
 const MIN_TRANSITION_FRAME_INTERVAL: Duration = Duration::from_millis(15);
 
+/// Photos kept on the GPU after they leave the screen, for the `previous`
+/// control command. Each holds a full-screen texture, so the buffer is small.
+const HISTORY_LEN: usize = 3;
+
+/// Ticks normally come every few milliseconds; a gap this long means the
+/// system was suspended (or the process stopped) in between.
+const TICK_GAP: Duration = Duration::from_secs(10);
+
 /// State container for the wake (slideshow) scene.
 pub(super) struct WakeScene {
     current: Option<ImgTex>,
@@ -739,6 +1418,59 @@ pub(super) struct WakeScene {
     last_present: Option<Instant>,
     dwell_ms: u64,
     transition_cfg: TransitionConfig,
+    /// Pan-and-zoom applied while a photo dwells; `None` keeps photos still.
+    motion: Option<MotionConfig>,
+    /// Plays of an animated photo before it gives way to the next photo,
+    /// even with dwell time left; `None` loops it for the whole dwell.
+    animation_loops: Option<u32>,
+    /// Picks each photo's motion path; seeded from `--playlist-seed` when given.
+    motion_rng: StdRng,
+    current_motion: Option<KenBurnsPath>,
+    next_motion: Option<KenBurnsPath>,
+    /// Dwell progress the current photo's motion was frozen at when a
+    /// transition started; the motion holds still until the transition ends.
+    motion_paused_at: Option<f32>,
+    /// Recently replaced photos, oldest first.
+    history: VecDeque<ImgTex>,
+    /// A `next` or `previous` command cut the dwell short.
+    advance_requested: bool,
+    /// Held by the `pause` command: the current photo stays until `resume`.
+    /// Survives [`WakeScene::reset`], which only drops GPU-side state.
+    paused: bool,
+    /// When the dwell clock stopped for the pause, or the moment a photo
+    /// shown during the pause went on screen; `None` while not paused.
+    paused_since: Option<Instant>,
+    /// The staged photo came from `history`; the photo it replaces goes back
+    /// to the front of the queue instead of into the history.
+    stepping_back: bool,
+    /// Draws the current photo's caption (`caption.enabled`); owned here so it
+    /// follows the photo on screen, installed once the GPU is up.
+    photo_caption: Option<CaptionOverlay>,
+    /// Monotonic and wall-clock time of the last viewer tick. `Instant`
+    /// stands still through a suspend, so only the wall clock shows one.
+    last_tick: Option<(Instant, SystemTime)>,
+}
+
+/// Whether the photo on screen should give way to the next one. A `next` or
+/// `previous` request wins over both the dwell and a pause.
+fn ready_to_advance(
+    shown_for: Duration,
+    dwell: Duration,
+    paused: bool,
+    advance_requested: bool,
+) -> bool {
+    advance_requested || (!paused && shown_for >= dwell)
+}
+
+/// Plans the motion path for a newly shown photo. A matted photo moves inside
+/// its mat, which stays still; see [`super::motion::framed_view`].
+fn plan_motion(
+    motion: Option<&MotionConfig>,
+    rng: &mut StdRng,
+    img: Option<&ImgTex>,
+) -> Option<KenBurnsPath> {
+    img.and(motion)
+        .map(|motion| KenBurnsPath::random(motion, rng))
 }
 
 impl WakeScene {
@@ -764,12 +1496,65 @@ impl WakeScene {
             last_present: None,
             dwell_ms,
             transition_cfg,
+            motion: None,
+            animation_loops: None,
+            motion_rng: StdRng::from_os_rng(),
+            current_motion: None,
+            next_motion: None,
+            motion_paused_at: None,
+            history: VecDeque::new(),
+            advance_requested: false,
+            paused: false,
+            paused_since: None,
+            stepping_back: false,
+            photo_caption: None,
+            last_tick: None,
+        }
+    }
+
+    /// Enables per-photo motion. A `seed` makes the chosen paths repeatable.
+    pub(super) fn with_motion(mut self, motion: Option<MotionConfig>, seed: Option<u64>) -> Self {
+        self.motion = motion;
+        if let Some(seed) = seed {
+            self.motion_rng = StdRng::seed_from_u64(seed);
         }
+        self
+    }
+
+    /// Lets animated photos end their dwell after `loops` plays.
+    pub(super) fn with_animation_loops(mut self, loops: Option<u32>) -> Self {
+        self.animation_loops = loops;
+        self
+    }
+
+    /// Applies a reloaded dwell and transition list. The photo on screen keeps
+    /// the time it has already been shown; a transition in flight finishes
+    /// with the settings it started with.
+    pub(super) fn reconfigure(&mut self, dwell_ms: u64, transition_cfg: TransitionConfig) {
+        self.dwell_ms = dwell_ms;
+        self.transition_cfg = transition_cfg;
+    }
+
+    /// How long each photo stays on screen before the next transition.
+    pub(super) fn dwell(&self) -> Duration {
+        Duration::from_millis(self.dwell_ms)
+    }
+
+    /// The dwell of the photo on screen: cut short once an animated photo
+    /// has played `animation_loops` times.
+    fn current_dwell(&self, now: Instant) -> Duration {
+        let played = self
+            .current
+            .as_ref()
+            .and_then(|img| img.animation.as_ref())
+            .is_some_and(|animation| animation.finished(now, self.animation_loops));
+        if played { Duration::ZERO } else { self.dwell() }
     }
 
     /// Clears all slideshow state, returning the scene to its initial idle state.
     pub(super) fn reset(&mut self) {
         self.current = None;
+        self.sync_photo_caption();
         self.next = None;
         self.transition_state = None;
         self.last_transition_kind = None;
@@ -777,6 +1562,12 @@ impl WakeScene {
         self.pending.clear();
         self.pending_redraw = false;
         self.last_present = None;
+        self.current_motion = None;
+        self.next_motion = None;
+        self.motion_paused_at = None;
+        self.history.clear();
+        self.advance_requested = false;
+        self.stepping_back = false;
     }
 
     /// Returns the currently displayed image, if present.
@@ -787,6 +1578,71 @@ impl WakeScene {
     /// Sets the currently displayed image.
     pub(super) fn set_current(&mut self, current: Option<ImgTex>) {
         self.current = current;
+        self.current_motion = plan_motion(
+            self.motion.as_ref(),
+            &mut self.motion_rng,
+            self.current.as_ref(),
+        );
+        self.motion_paused_at = None;
+        self.sync_photo_caption();
+    }
+
+    /// Installs (or with `None`, drops) the overlay that shows each photo's
+    /// caption. Dropped with the GPU, since it holds the device.
+    pub(super) fn set_photo_caption(&mut self, overlay: Option<CaptionOverlay>) {
+        self.photo_caption = overlay;
+        self.sync_photo_caption();
+    }
+
+    /// Points the caption at the photo now on screen. The overlay re-lays out
+    /// only when the text actually changes.
+    fn sync_photo_caption(&mut self) {
+        if let Some(overlay) = self.photo_caption.as_mut() {
+            let text = self
+                .current
+                .as_ref()
+                .and_then(|img| img.caption.as_deref())
+                .unwrap_or_default();
+            overlay.set_text(text);
+        }
+    }
+
+    /// Draws the current photo's caption over the frame in `target_view`.
+    pub(super) fn render_photo_caption(
+        &mut self,
+        encoder: &mut CommandEncoder,
+        target_view: &TextureView,
+        surface_size: PhysicalSize<u32>,
+    ) -> bool {
+        let Some(overlay) = self.photo_caption.as_mut() else {
+            return false;
+        };
+        overlay.resize(surface_size);
+        overlay.render(encoder, target_view)
+    }
+
+    /// Swaps in a new layout of the photo on screen, keeping its dwell.
+    pub(super) fn replace_current(&mut self, img: ImgTex) {
+        self.set_current(Some(img));
+        self.pending_redraw = true;
+    }
+
+    /// Number of photos a step back can return to.
+    pub(super) fn history_len(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Keeps only the history entries for which `keep` returns true.
+    pub(super) fn retain_history(&mut self, keep: impl FnMut(&ImgTex) -> bool) {
+        self.history.retain(keep);
+    }
+
+    /// Takes the currently displayed image, if present.
+    pub(super) fn take_current(&mut self) -> Option<ImgTex> {
+        self.current_motion = None;
+        let current = self.current.take();
+        self.sync_photo_caption();
+        current
     }
 
     /// Returns the next staged image.
@@ -797,10 +1653,16 @@ impl WakeScene {
     /// Sets the next staged image.
     pub(super) fn set_next(&mut self, next: Option<ImgTex>) {
         self.next = next;
+        self.next_motion = plan_motion(
+            self.motion.as_ref(),
+            &mut self.motion_rng,
+            self.next.as_ref(),
+        );
     }
 
     /// Takes the next staged image, if present.
     pub(super) fn take_next(&mut self) -> Option<ImgTex> {
+        self.next_motion = None;
         self.next.take()
     }
 
@@ -833,6 +1695,9 @@ impl WakeScene {
     pub(super) fn after_present(&mut self) {
         let _ = self.take_redraw_needed();
         self.last_present = Some(Instant::now());
+        if let Some(overlay) = self.photo_caption.as_mut() {
+            overlay.after_submit();
+        }
     }
 
     /// Returns the timestamp when the current image started displaying.
@@ -883,9 +1748,23 @@ impl WakeScene {
                     path.display(),
                     self.pending.len()
                 );
-                self.current = Some(next);
+                if let Some(mut replaced) = self.current.replace(next) {
+                    if std::mem::take(&mut self.stepping_back) {
+                        self.pending.push_front(replaced);
+                    } else {
+                        if self.history.len() == HISTORY_LEN {
+                            self.history.pop_front();
+                        }
+                        replaced.canvas = None;
+                        replaced.source = None;
+                        self.history.push_back(replaced);
+                    }
+                }
+                self.current_motion = self.next_motion.take();
+                self.motion_paused_at = None;
+                self.sync_photo_caption();
                 self.pending_redraw = true;
-                self.displayed_at = Some(Instant::now());
+                self.start_dwell(Instant::now());
                 ctx.notify_displayed(path);
             }
         }
@@ -903,21 +1782,65 @@ impl WakeScene {
                 self.pending.len()
             );
             self.current = Some(first);
+            self.current_motion = plan_motion(
+                self.motion.as_ref(),
+                &mut self.motion_rng,
+                self.current.as_ref(),
+            );
+            self.sync_photo_caption();
             self.pending_redraw = true;
-            self.displayed_at = Some(Instant::now());
+            self.start_dwell(Instant::now());
             ctx.notify_displayed(path);
         }
     }
 
+    /// Starts the dwell clock for a photo that just went on screen; while
+    /// paused it stays at zero until resume.
+    fn start_dwell(&mut self, now: Instant) {
+        self.displayed_at = Some(now);
+        if self.paused_since.is_some() {
+            self.paused_since = Some(now);
+        }
+    }
+
+    /// Notes a viewer tick. After a long gap since the last one the photo on
+    /// screen gets a fresh dwell, rather than finishing the one it had left
+    /// before the frame went to sleep.
+    pub(super) fn note_tick(&mut self, now: Instant, wall: SystemTime) {
+        let Some((last, last_wall)) = self.last_tick.replace((now, wall)) else {
+            return;
+        };
+        let gap = now
+            .saturating_duration_since(last)
+            .max(wall.duration_since(last_wall).unwrap_or_default());
+        if gap >= TICK_GAP && self.displayed_at.is_some() {
+            tracing::info!("dwell_restart gap_ms={}", gap.as_millis());
+            self.start_dwell(now);
+        }
+    }
+
+    /// How much of its dwell the current photo has used by `now`, not
+    /// counting time spent paused.
+    fn shown_for(&self, now: Instant) -> Option<Duration> {
+        let shown_at = self.displayed_at?;
+        Some(
+            self.paused_since
+                .unwrap_or(now)
+                .saturating_duration_since(shown_at),
+        )
+    }
+
     /// Starts a transition when the dwell time elapses and staged images are available.
     pub(super) fn maybe_start_transition(&mut self, rng: &mut impl Rng) {
         if self.transition_state.is_some() {
             return;
         }
-        let Some(shown_at) = self.displayed_at else {
+        let now = Instant::now();
+        let Some(shown_for) = self.shown_for(now) else {
             return;
         };
-        if shown_at.elapsed() < std::time::Duration::from_millis(self.dwell_ms) {
+        let dwell = self.current_dwell(now);
+        if !ready_to_advance(shown_for, dwell, self.paused, self.advance_requested) {
             return;
         }
         if self.next.is_none()
@@ -929,8 +1852,14 @@ impl WakeScene {
                 self.pending.len()
             );
             self.next = Some(stage);
+            self.next_motion = plan_motion(
+                self.motion.as_ref(),
+                &mut self.motion_rng,
+                self.next.as_ref(),
+            );
         }
         if self.next.is_some() && self.current.is_some() {
+            self.motion_paused_at = Some(self.motion_progress());
             let selected = self.transition_cfg.select_active(rng);
             let kind = selected.entry.kind;
             let selection_index = selected.entry.index;
@@ -946,19 +1875,131 @@ impl WakeScene {
             }
             self.last_transition_kind = Some(kind);
             self.transition_state = Some(state);
+            self.advance_requested = false;
+        }
+    }
+
+    /// Holds the current photo on screen with its dwell clock stopped, or
+    /// releases it to finish the dwell it had left when paused.
+    pub(super) fn set_paused(&mut self, paused: bool, now: Instant) {
+        if paused == self.paused {
+            return;
+        }
+        self.paused = paused;
+        if paused {
+            self.paused_since = Some(now);
+        } else if let Some(since) = self.paused_since.take()
+            && let Some(shown_at) = self.displayed_at
+        {
+            self.displayed_at = Some(shown_at + now.saturating_duration_since(since));
+        }
+    }
+
+    pub(super) fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Ends the current dwell early: the next photo transitions in as soon as
+    /// one is ready. Returns `false` while a transition is already running.
+    pub(super) fn request_next(&mut self) -> bool {
+        if self.transition_state.is_some() || self.current.is_none() {
+            return false;
+        }
+        self.advance_requested = true;
+        true
+    }
+
+    /// Transitions back to the photo shown before the current one. The
+    /// current photo is queued to come next again. Returns `false` when there
+    /// is no history or a transition is already running.
+    pub(super) fn request_previous(&mut self) -> bool {
+        if self.transition_state.is_some() || self.current.is_none() {
+            return false;
+        }
+        let Some(previous) = self.history.pop_back() else {
+            return false;
+        };
+        if let Some(staged) = self.take_next() {
+            self.pending.push_front(staged);
         }
+        tracing::debug!(
+            "transition_back path={} history={}",
+            previous.path.display(),
+            self.history.len()
+        );
+        self.set_next(Some(previous));
+        self.stepping_back = true;
+        self.advance_requested = true;
+        true
     }
 
     pub(super) fn enter_wake(&mut self) {
         self.pending_redraw = true;
         if self.displayed_at.is_some() {
-            self.displayed_at = Some(Instant::now());
+            self.start_dwell(Instant::now());
+        }
+    }
+
+    /// Fraction of the dwell the current photo has been on screen, held while
+    /// a transition runs.
+    fn motion_progress(&self) -> f32 {
+        if let Some(paused) = self.motion_paused_at {
+            return paused;
+        }
+        let Some(shown_for) = self.shown_for(Instant::now()) else {
+            return 0.0;
+        };
+        dwell_progress(shown_for, self.dwell())
+    }
+
+    /// Framing for the current photo right now; identity when motion is off.
+    pub(super) fn current_framing(&self) -> Framing {
+        self.current_motion.map_or(Framing::IDENTITY, |path| {
+            path.framing_at(self.motion_progress())
+        })
+    }
+
+    /// Framing the staged photo will start its dwell with.
+    pub(super) fn next_framing(&self) -> Framing {
+        self.next_motion
+            .map_or(Framing::IDENTITY, |path| path.framing_at(0.0))
+    }
+
+    /// Writes the due frame of any animated photo on screen into its
+    /// texture. Called just before the wake scene is drawn; the staged photo
+    /// only animates while a transition shows it.
+    pub(super) fn advance_animations(&mut self, queue: &wgpu::Queue, now: Instant) {
+        let next = self
+            .next
+            .as_mut()
+            .filter(|_| self.transition_state.is_some());
+        for img in [self.current.as_mut(), next].into_iter().flatten() {
+            if let Some(animation) = img.animation.as_mut() {
+                animation.advance(queue, now, self.animation_loops);
+            }
         }
     }
 
+    /// Whether the current photo is animated and its next frame is due.
+    fn animation_frame_due(&self) -> bool {
+        self.current
+            .as_ref()
+            .and_then(|img| img.animation.as_ref())
+            .is_some_and(|animation| animation.frame_due(Instant::now(), self.animation_loops))
+    }
+
+    /// Whether the current photo is still drifting and needs fresh frames.
+    fn motion_in_progress(&self) -> bool {
+        self.current_motion.is_some()
+            && self.transition_state.is_none()
+            && self.motion_progress() < 1.0
+    }
+
     fn ensure_redraw_requested(&mut self, ctx: &mut SceneContext<'_>) {
         let pending_redraw = self.needs_redraw();
         let has_transition = self.transition_state().is_some();
+        let has_motion = self.motion_in_progress();
+        let has_animation_frame = self.animation_frame_due();
         if pending_redraw {
             self.take_redraw_needed();
         }
@@ -969,8 +2010,14 @@ impl WakeScene {
         let pace_open = self
             .last_present
             .is_none_or(|t| t.elapsed() >= MIN_TRANSITION_FRAME_INTERVAL);
-        if pending_redraw || (has_transition && pace_open) {
-            tracing::debug!(pending_redraw, has_transition, "viewer_request_redraw_wake");
+        if pending_redraw || has_animation_frame || ((has_transition || has_motion) && pace_open) {
+            tracing::debug!(
+                pending_redraw,
+                has_transition,
+                has_motion,
+                has_animation_frame,
+                "viewer_request_redraw_wake"
+            );
             ctx.request_redraw();
         }
     }
@@ -988,6 +2035,7 @@ pub(super) struct SceneContext<'a> {
     rng: &'a mut rand::rngs::ThreadRng,
     notify_displayed: &'a mut dyn FnMut(PathBuf),
     enqueue_matting: &'a mut dyn FnMut(&mut WakeScene),
+    message_context: MessageContext,
 }
 
 impl<'a> SceneContext<'a> {
@@ -999,6 +2047,7 @@ impl<'a> SceneContext<'a> {
         rng: &'a mut rand::rngs::ThreadRng,
         notify_displayed: &'a mut dyn FnMut(PathBuf),
         enqueue_matting: &'a mut dyn FnMut(&mut WakeScene),
+        message_context: MessageContext,
     ) -> Self {
         Self {
             window,
@@ -1007,6 +2056,7 @@ impl<'a> SceneContext<'a> {
             rng,
             notify_displayed,
             enqueue_matting,
+            message_context,
         }
     }
 
@@ -1015,6 +2065,18 @@ impl<'a> SceneContext<'a> {
         self.window
     }
 
+    /// The window's size as scenes lay out for it: turned by
+    /// `display.rotation`, so a panel mounted sideways gets a portrait layout.
+    pub(super) fn surface_size(&self) -> Option<PhysicalSize<u32>> {
+        let size = self.window?.inner_size();
+        let (width, height) = self
+            .config
+            .display
+            .rotation
+            .logical_size(size.width, size.height);
+        Some(PhysicalSize::new(width, height))
+    }
+
     /// Requests a redraw from the viewer event loop.
     pub(super) fn request_redraw(&mut self) {
         (self.redraw)();
@@ -1025,6 +2087,25 @@ impl<'a> SceneContext<'a> {
         &self.config
     }
 
+    /// Renders an overlay message with its placeholders expanded for the current moment.
+    pub(super) fn screen_message(&self, screen: &ScreenMessageConfig) -> String {
+        screen
+            .message_or_default(&self.message_context)
+            .into_owned()
+    }
+
+    /// Renders the sleep card's text, including the clock line when enabled.
+    pub(super) fn sleep_message(&self, sleep: &SleepScreenConfig) -> String {
+        sleep.message_with_clock(&self.message_context)
+    }
+
+    /// Renders the empty-library card's text.
+    pub(super) fn empty_library_message(&self) -> String {
+        self.config
+            .empty_library_screen
+            .message_for(&self.message_context, &self.config.library_paths())
+    }
+
     /// Provides mutable access to the viewer RNG for scenes that need randomness.
     pub(super) fn rng(&mut self) -> &mut rand::rngs::ThreadRng {
         self.rng
@@ -1121,9 +2202,262 @@ impl Scene for WakeScene {
 
 #[cfg(test)]
 mod tests {
-    use super::CaptionOverlay;
+    use super::{
+        CaptionOverlay, FadeClock, MessageThrottle, SleepFade, SleepFadeDirection, SleepFadeFrame,
+        TransitionConfig, WakeScene, overlay_fade_alpha, ready_to_advance,
+    };
+    use std::time::{Duration, Instant, SystemTime};
     use winit::dpi::PhysicalSize;
 
+    #[test]
+    fn sleep_fade_darkens_slideshow_then_reveals_sleep_screen() {
+        let start = Instant::now();
+        let fade = SleepFade::to_sleep(start, Duration::from_millis(1000));
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert_eq!(fade.frame(at(0)), SleepFadeFrame::Slideshow { black: 0.0 });
+        assert_eq!(
+            fade.frame(at(250)),
+            SleepFadeFrame::Slideshow { black: 0.5 }
+        );
+        assert_eq!(
+            fade.frame(at(500)),
+            SleepFadeFrame::SleepScreen { opacity: 0.0 }
+        );
+        assert!(!fade.is_complete(at(999)));
+        assert!(fade.is_complete(at(1000)));
+        assert_eq!(
+            fade.frame(at(5000)),
+            SleepFadeFrame::SleepScreen { opacity: 1.0 }
+        );
+    }
+
+    #[test]
+    fn sleep_fade_reverses_from_its_current_level() {
+        let start = Instant::now();
+        let mut fade = SleepFade::to_sleep(start, Duration::from_millis(1000));
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // Woken 700ms in: the sleep screen is partly visible and must fade back
+        // out, through black, to the slideshow over the same 700ms.
+        fade.reverse(at(700));
+        assert_eq!(fade.direction(), SleepFadeDirection::ToWake);
+        match fade.frame(at(700)) {
+            SleepFadeFrame::SleepScreen { opacity } => assert!((opacity - 0.4).abs() < 1e-4),
+            other => panic!("expected sleep screen, got {other:?}"),
+        }
+        match fade.frame(at(1100)) {
+            SleepFadeFrame::Slideshow { black } => assert!((black - 0.6).abs() < 1e-4),
+            other => panic!("expected slideshow, got {other:?}"),
+        }
+        assert!(!fade.is_complete(at(1399)));
+        assert!(fade.is_complete(at(1400)));
+        assert_eq!(
+            fade.frame(at(1400)),
+            SleepFadeFrame::Slideshow { black: 0.0 }
+        );
+    }
+
+    #[test]
+    fn waking_retraces_the_sleep_fade_and_reveal_skips_the_sleep_screen() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        let wake = SleepFade::to_wake(start, Duration::from_millis(1000));
+        assert_eq!(wake.direction(), SleepFadeDirection::ToWake);
+        assert_eq!(
+            wake.frame(at(0)),
+            SleepFadeFrame::SleepScreen { opacity: 1.0 }
+        );
+        assert_eq!(
+            wake.frame(at(250)),
+            SleepFadeFrame::SleepScreen { opacity: 0.5 }
+        );
+        // The photo returns over the second half: black 1 → 0.
+        assert_eq!(
+            wake.frame(at(500)),
+            SleepFadeFrame::SleepScreen { opacity: 0.0 }
+        );
+        assert_eq!(
+            wake.frame(at(750)),
+            SleepFadeFrame::Slideshow { black: 0.5 }
+        );
+        assert!(wake.is_complete(at(1000)));
+
+        let reveal = SleepFade::reveal(start, Duration::from_millis(1000));
+        assert_eq!(reveal.direction(), SleepFadeDirection::ToWake);
+        let blacks: Vec<f32> = [0, 125, 250, 375, 500]
+            .into_iter()
+            .map(|ms| match reveal.frame(at(ms)) {
+                SleepFadeFrame::Slideshow { black } => black,
+                other => panic!("reveal never shows the sleep screen, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(blacks, vec![1.0, 0.75, 0.5, 0.25, 0.0]);
+        assert!(!reveal.is_complete(at(499)));
+        assert!(reveal.is_complete(at(500)));
+
+        // Sent back to sleep mid-reveal, it heads for the sleep screen as usual.
+        let mut reveal = SleepFade::reveal(start, Duration::from_millis(1000));
+        reveal.reverse(at(100));
+        assert_eq!(reveal.direction(), SleepFadeDirection::ToSleep);
+        match reveal.frame(at(300)) {
+            SleepFadeFrame::SleepScreen { opacity } => assert!((opacity - 0.2).abs() < 1e-4),
+            other => panic!("expected sleep screen, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn overlay_fade_alpha_eases_in_and_out() {
+        let start = Instant::now();
+        let clock = FadeClock::start(start, Duration::from_millis(400), true, 0.0);
+        let alpha = |ms| overlay_fade_alpha(clock.level(start + Duration::from_millis(ms)));
+
+        assert_eq!(alpha(0), 0.0);
+        assert_eq!(alpha(200), 0.5);
+        assert_eq!(alpha(400), 1.0);
+        assert_eq!(alpha(1000), 1.0);
+        // Eased: slow at both ends, fast through the middle.
+        assert!(alpha(40) < 0.05);
+        assert!(alpha(360) > 0.95);
+        let samples: Vec<f32> = (0..=40).map(|i| alpha(i * 10)).collect();
+        assert!(samples.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn overlay_fade_out_reverses_from_current_alpha() {
+        let start = Instant::now();
+        let mut clock = FadeClock::start(start, Duration::from_millis(400), true, 0.0);
+        let at = |ms| start + Duration::from_millis(ms);
+
+        // Asked to leave 100ms into the fade-in: it takes 100ms to get back out.
+        clock.head(at(100), false);
+        assert!(!clock.is_rising());
+        assert_eq!(clock.level(at(100)), 0.25);
+        assert!(!clock.is_complete(at(199)));
+        assert!(clock.is_complete(at(200)));
+        assert_eq!(overlay_fade_alpha(clock.level(at(200))), 0.0);
+
+        let settled = FadeClock::settled(1.0, start);
+        assert!(settled.is_complete(start));
+        assert_eq!(settled.level(at(5000)), 1.0);
+    }
+
+    #[test]
+    fn message_throttle_limits_layout_rebuilds() {
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        let mut throttle = MessageThrottle::new(Duration::from_millis(250));
+
+        throttle.record("Scanning: 0 photos", at(0));
+        assert!(!throttle.offer("Scanning: 0 photos", at(300)));
+        // The count ticks every frame during a scan, but only one change per
+        // interval is laid out.
+        let applied = (1..=200u64)
+            .filter(|frame| throttle.offer(&format!("Scanning: {frame} photos"), at(frame * 4)))
+            .count();
+        assert_eq!(applied, 3);
+        // Once the count settles, the final value is applied on the next pass.
+        assert!(throttle.offer("Ready: 200 photos", at(1100)));
+        assert!(!throttle.offer("Ready: 200 photos", at(5000)));
+    }
+
+    #[test]
+    fn zero_length_sleep_fade_completes_immediately() {
+        let start = Instant::now();
+        let fade = SleepFade::to_sleep(start, Duration::ZERO);
+        assert!(fade.is_complete(start));
+        assert_eq!(
+            fade.frame(start),
+            SleepFadeFrame::SleepScreen { opacity: 1.0 }
+        );
+    }
+
+    #[test]
+    fn pause_holds_the_photo_until_resume() {
+        let dwell = Duration::from_secs(10);
+        let (early, past_dwell) = (Duration::from_secs(3), Duration::from_secs(30));
+        assert!(ready_to_advance(past_dwell, dwell, false, false));
+        assert!(!ready_to_advance(early, dwell, false, false));
+
+        // Paused, the dwell running out does not start a transition.
+        assert!(!ready_to_advance(past_dwell, dwell, true, false));
+        assert!(ready_to_advance(past_dwell, dwell, false, false));
+    }
+
+    #[test]
+    fn resume_finishes_the_dwell_left_at_pause() {
+        let mut wake = WakeScene::new(10_000, TransitionConfig::default());
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        wake.set_displayed_at(Some(start));
+
+        wake.set_paused(true, at(4));
+        assert_eq!(wake.shown_for(at(60)), Some(Duration::from_secs(4)));
+        wake.set_paused(false, at(60));
+        assert_eq!(wake.shown_for(at(60)), Some(Duration::from_secs(4)));
+        // Six seconds of dwell were left, so the photo moves on at 66s.
+        assert!(wake.shown_for(at(65)).unwrap() < wake.dwell());
+        assert_eq!(wake.shown_for(at(66)), Some(wake.dwell()));
+    }
+
+    #[test]
+    fn a_photo_shown_while_paused_starts_its_dwell_on_resume() {
+        let mut wake = WakeScene::new(10_000, TransitionConfig::default());
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        wake.set_displayed_at(Some(start));
+        wake.set_paused(true, at(4));
+
+        // `next` while paused brings on a new photo at 30s.
+        wake.start_dwell(at(30));
+        wake.set_paused(false, at(60));
+        assert_eq!(wake.shown_for(at(60)), Some(Duration::ZERO));
+        assert_eq!(wake.shown_for(at(70)), Some(wake.dwell()));
+    }
+
+    #[test]
+    fn a_suspend_between_ticks_restarts_the_dwell() {
+        let mut wake = WakeScene::new(10_000, TransitionConfig::default());
+        let start = Instant::now();
+        let wall = SystemTime::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let wall_at = |secs| wall + Duration::from_secs(secs);
+        wake.set_displayed_at(Some(start));
+        wake.note_tick(at(0), wall_at(0));
+        wake.note_tick(at(8), wall_at(8));
+        assert_eq!(wake.shown_for(at(8)), Some(Duration::from_secs(8)));
+
+        // Two hours asleep: the monotonic clock moved a second, the wall
+        // clock two hours. The photo starts its dwell over.
+        wake.note_tick(at(9), wall_at(9 + 2 * 3600));
+        assert_eq!(wake.shown_for(at(9)), Some(Duration::ZERO));
+        assert_eq!(wake.shown_for(at(19)), Some(wake.dwell()));
+
+        // A stall the monotonic clock does see counts too.
+        wake.note_tick(at(30), wall_at(30 + 2 * 3600));
+        assert_eq!(wake.shown_for(at(30)), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn explicit_steps_advance_even_while_paused() {
+        let dwell = Duration::from_secs(10);
+        assert!(ready_to_advance(Duration::ZERO, dwell, true, true));
+        assert!(ready_to_advance(Duration::ZERO, dwell, false, true));
+    }
+
+    #[test]
+    fn reconfigure_changes_the_dwell_used_for_advancing() {
+        let mut wake = WakeScene::new(30_000, TransitionConfig::default());
+        let shown_for = Duration::from_secs(12);
+        assert!(!ready_to_advance(shown_for, wake.dwell(), false, false));
+
+        wake.reconfigure(10_000, TransitionConfig::default());
+
+        assert_eq!(wake.dwell(), Duration::from_secs(10));
+        assert!(ready_to_advance(shown_for, wake.dwell(), false, false));
+    }
+
     fn try_device() -> Option<(wgpu::Device, wgpu::Queue)> {
         let instance = wgpu::Instance::default();
         let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
@@ -0,0 +1,378 @@
+//! Remote control over HTTP, enabled by the `web-control` config block: a
+//! single page at `/` for a phone on the LAN, backed by a small JSON API that
+//! feeds the same viewer command channel as the control socket.
+
+use super::{ControlStatus, ManualOverride};
+use crate::events::{StateSource, ViewerCommand, ViewerState};
+use anyhow::{Context, Result};
+use axum::Router;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::{Html, IntoResponse, Json, Response};
+use axum::routing::{get, post};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+const INDEX_HTML: &str = include_str!("web_control/index.html");
+
+/// What the HTTP handlers share with the control socket.
+#[derive(Clone)]
+pub(crate) struct WebControl {
+    pub(crate) control: mpsc::Sender<ViewerCommand>,
+    pub(crate) status: ControlStatus,
+    pub(crate) manual_override: Arc<Mutex<ManualOverride>>,
+}
+
+pub(crate) async fn run(
+    cancel: CancellationToken,
+    bind_address: SocketAddr,
+    remote: WebControl,
+) -> Result<()> {
+    let listener = TcpListener::bind(bind_address)
+        .await
+        .with_context(|| format!("failed to bind web control on {bind_address}"))?;
+    tracing::info!(address = %bind_address, "web control listening");
+    axum::serve(listener, router(remote))
+        .with_graceful_shutdown(async move { cancel.cancelled().await })
+        .await
+        .context("web control server exited")
+}
+
+fn router(remote: WebControl) -> Router {
+    Router::new()
+        .route("/", get(index))
+        .route("/api/sleep", post(sleep))
+        .route("/api/wake", post(wake))
+        .route("/api/next", post(next))
+        .route("/api/status", get(status).post(status))
+        .route("/api/history", get(history))
+        .route("/photo/{id}", get(photo))
+        .with_state(remote)
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+async fn sleep(State(remote): State<WebControl>, headers: HeaderMap) -> Response {
+    set_state(&remote, &headers, ViewerState::Asleep).await
+}
+
+async fn wake(State(remote): State<WebControl>, headers: HeaderMap) -> Response {
+    set_state(&remote, &headers, ViewerState::Awake).await
+}
+
+async fn next(State(remote): State<WebControl>, headers: HeaderMap) -> Response {
+    if !is_same_origin(&headers) {
+        return cross_origin_rejected();
+    }
+    tracing::info!(command = "next", "received web control command");
+    forward(&remote, ViewerCommand::Next).await
+}
+
+async fn status(State(remote): State<WebControl>) -> Json<serde_json::Value> {
+    Json(remote.status.to_json())
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HistoryQuery {
+    limit: Option<usize>,
+}
+
+/// The same reply as the control socket's `history` command.
+async fn history(
+    State(remote): State<WebControl>,
+    Query(query): Query<HistoryQuery>,
+) -> Json<serde_json::Value> {
+    Json(remote.status.history_json(query.limit))
+}
+
+/// The photo a `share-qr` code links to. Only the photo on screen and those
+/// in the recent history are served.
+async fn photo(State(remote): State<WebControl>, Path(id): Path<String>) -> Response {
+    let Some(path) = remote.status.shared_photo(&id) else {
+        return error_reply(StatusCode::NOT_FOUND, "no such photo");
+    };
+    let read = {
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || std::fs::read(path)).await
+    };
+    match read {
+        Ok(Ok(bytes)) => {
+            let content_type = match path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map(str::to_ascii_lowercase)
+                .as_deref()
+            {
+                Some("jpg" | "jpeg") => "image/jpeg",
+                Some("png") => "image/png",
+                Some("webp") => "image/webp",
+                Some("gif") => "image/gif",
+                Some("heic" | "heif") => "image/heif",
+                _ => "application/octet-stream",
+            };
+            ([(header::CONTENT_TYPE, content_type)], bytes).into_response()
+        }
+        _ => {
+            tracing::warn!(path = %path.display(), "failed to read shared photo");
+            error_reply(StatusCode::NOT_FOUND, "no such photo")
+        }
+    }
+}
+
+/// A press on the page is a manual change, so it holds off the schedule just
+/// like a button press or a `set-state` from the command line.
+async fn set_state(remote: &WebControl, headers: &HeaderMap, state: ViewerState) -> Response {
+    if !is_same_origin(headers) {
+        return cross_origin_rejected();
+    }
+    tracing::info!(
+        command = "set-state",
+        ?state,
+        "received web control command"
+    );
+    remote
+        .manual_override
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .record_manual(state, Instant::now());
+    let command = ViewerCommand::SetState {
+        state,
+        source: StateSource::Manual,
+    };
+    forward(remote, command).await
+}
+
+async fn forward(remote: &WebControl, command: ViewerCommand) -> Response {
+    match remote.control.send(command).await {
+        Ok(()) => Json(serde_json::json!({ "status": "ok" })).into_response(),
+        Err(_) => error_reply(StatusCode::SERVICE_UNAVAILABLE, "viewer is not running"),
+    }
+}
+
+/// Reject cross-origin POSTs so a web page open on a phone cannot drive the
+/// frame behind the user's back. A browser sends `Origin` on a cross-origin
+/// fetch or form post; when present its authority must match `Host`. Clients
+/// sending neither `Origin` nor `Referer` (curl, scripts) are let through.
+fn is_same_origin(headers: &HeaderMap) -> bool {
+    let Some(host) = headers.get(header::HOST).and_then(|v| v.to_str().ok()) else {
+        return true;
+    };
+    let authority = |url: &str| {
+        url.split_once("://")
+            .and_then(|(_, rest)| rest.split('/').next())
+            .map(str::to_owned)
+    };
+    if let Some(origin) = headers.get(header::ORIGIN).and_then(|v| v.to_str().ok()) {
+        return authority(origin).as_deref() == Some(host);
+    }
+    if let Some(referer) = headers.get(header::REFERER).and_then(|v| v.to_str().ok()) {
+        return authority(referer).as_deref() == Some(host);
+    }
+    true
+}
+
+fn cross_origin_rejected() -> Response {
+    tracing::warn!("rejected cross-origin web control request");
+    error_reply(StatusCode::FORBIDDEN, "cross-origin request rejected")
+}
+
+fn error_reply(code: StatusCode, message: &str) -> Response {
+    let body = serde_json::json!({ "status": "error", "error": message });
+    (code, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::QuarantineConfig;
+    use crate::events::ViewerStatus;
+    use crate::metrics::RuntimeMetrics;
+    use crate::tasks::history::{HistoryEntry, PhotoHistory};
+    use crate::tasks::quarantine::Quarantine;
+    use axum::body::{Body, to_bytes};
+    use axum::http::Request;
+    use std::time::Duration;
+    use tokio::sync::watch;
+    use tower::ServiceExt;
+
+    fn remote(
+        viewer: ViewerStatus,
+        ttl: Option<Duration>,
+    ) -> (WebControl, mpsc::Receiver<ViewerCommand>) {
+        let (control, rx) = mpsc::channel(4);
+        let remote = WebControl {
+            control,
+            status: ControlStatus {
+                viewer: Arc::new(Mutex::new(viewer)),
+                started_at: Instant::now(),
+                history: Arc::new(Mutex::new(PhotoHistory::new(10))),
+                metrics: RuntimeMetrics::shared(),
+                quarantine: Quarantine::shared(&QuarantineConfig::default()),
+                library_hold: Arc::new(watch::channel(None).0),
+            },
+            manual_override: Arc::new(Mutex::new(ManualOverride::new(ttl))),
+        };
+        (remote, rx)
+    }
+
+    async fn send(remote: &WebControl, request: Request<Body>) -> (StatusCode, String) {
+        let response = router(remote.clone()).oneshot(request).await.unwrap();
+        let code = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (code, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn post(uri: &str) -> Request<Body> {
+        Request::post(uri)
+            .header(header::HOST, "frame.local:8080")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn sleep_and_wake_are_manual_set_state_commands() {
+        let (remote, mut rx) = remote(ViewerStatus::default(), Some(Duration::from_secs(600)));
+        let (code, body) = send(&remote, post("/api/sleep")).await;
+        assert_eq!(code, StatusCode::OK);
+        assert_eq!(body, r#"{"status":"ok"}"#);
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(ViewerCommand::SetState {
+                state: ViewerState::Asleep,
+                source: StateSource::Manual,
+            })
+        ));
+        // The press holds off a scheduled wake like any other manual change.
+        let held = remote
+            .manual_override
+            .lock()
+            .unwrap()
+            .admit_scheduled(ViewerState::Awake, Instant::now());
+        assert!(held.is_err());
+
+        send(&remote, post("/api/wake")).await;
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(ViewerCommand::SetState {
+                state: ViewerState::Awake,
+                source: StateSource::Manual,
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn next_skips_to_the_next_photo() {
+        let (remote, mut rx) = remote(ViewerStatus::default(), None);
+        let (code, _) = send(&remote, post("/api/next")).await;
+        assert_eq!(code, StatusCode::OK);
+        assert!(matches!(rx.try_recv(), Ok(ViewerCommand::Next)));
+    }
+
+    #[tokio::test]
+    async fn status_reports_the_viewer_snapshot() {
+        let viewer = ViewerStatus {
+            state: ViewerState::Awake,
+            current_photo: Some("/photos/beach.jpg".into()),
+            queue_depth: 2,
+            paused: false,
+        };
+        let (remote, _rx) = remote(viewer, None);
+        let request = Request::get("/api/status").body(Body::empty()).unwrap();
+        let (code, body) = send(&remote, request).await;
+        assert_eq!(code, StatusCode::OK);
+        let reply: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(reply["state"], "awake");
+        assert_eq!(reply["current_photo"], "/photos/beach.jpg");
+        assert_eq!(reply["queue_depth"], 2);
+    }
+
+    #[tokio::test]
+    async fn history_serves_the_recent_photos() {
+        let (remote, _rx) = remote(ViewerStatus::default(), None);
+        for name in ["a.jpg", "b.jpg"] {
+            remote
+                .status
+                .history
+                .lock()
+                .unwrap()
+                .push(HistoryEntry::new(
+                    format!("/photos/{name}").into(),
+                    std::time::SystemTime::UNIX_EPOCH,
+                ));
+        }
+        let request = Request::get("/api/history?limit=1")
+            .body(Body::empty())
+            .unwrap();
+        let (code, body) = send(&remote, request).await;
+        assert_eq!(code, StatusCode::OK);
+        let reply: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(reply["history"][0]["path"], "/photos/b.jpg");
+        assert_eq!(reply["history"].as_array().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn photo_serves_only_recently_shown_photos() {
+        let dir = tempfile::tempdir().unwrap();
+        let shown = dir.path().join("beach.jpg");
+        std::fs::write(&shown, b"jpeg bytes").unwrap();
+        let viewer = ViewerStatus {
+            current_photo: Some(shown.clone()),
+            ..ViewerStatus::default()
+        };
+        let (remote, _rx) = remote(viewer, None);
+        let uri = format!("/photo/{}", crate::share::photo_id(&shown));
+        let (code, body) = send(&remote, Request::get(uri).body(Body::empty()).unwrap()).await;
+        assert_eq!(code, StatusCode::OK);
+        assert_eq!(body, "jpeg bytes");
+
+        let other = dir.path().join("private.jpg");
+        std::fs::write(&other, b"not shared").unwrap();
+        let uri = format!("/photo/{}", crate::share::photo_id(&other));
+        let (code, _) = send(&remote, Request::get(uri).body(Body::empty()).unwrap()).await;
+        assert_eq!(code, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn cross_origin_posts_are_rejected() {
+        let (remote, mut rx) = remote(ViewerStatus::default(), None);
+        let request = Request::post("/api/sleep")
+            .header(header::HOST, "frame.local:8080")
+            .header(header::ORIGIN, "https://evil.example")
+            .body(Body::empty())
+            .unwrap();
+        let (code, _) = send(&remote, request).await;
+        assert_eq!(code, StatusCode::FORBIDDEN);
+        assert!(rx.try_recv().is_err());
+
+        let request = Request::post("/api/next")
+            .header(header::HOST, "frame.local:8080")
+            .header(header::ORIGIN, "http://frame.local:8080")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(send(&remote, request).await.0, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn index_serves_the_remote_page() {
+        let (remote, _rx) = remote(ViewerStatus::default(), None);
+        let request = Request::get("/").body(Body::empty()).unwrap();
+        let (code, body) = send(&remote, request).await;
+        assert_eq!(code, StatusCode::OK);
+        assert!(body.contains("/api/status"));
+    }
+
+    #[tokio::test]
+    async fn commands_fail_once_the_viewer_is_gone() {
+        let (remote, rx) = remote(ViewerStatus::default(), None);
+        drop(rx);
+        let (code, body) = send(&remote, post("/api/next")).await;
+        assert_eq!(code, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body.contains("viewer is not running"));
+    }
+}
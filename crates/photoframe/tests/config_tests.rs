@@ -1,8 +1,10 @@
 use photoframe::config::{
-    Configuration, FillWhenFits, GlobalPhotoSettings, GradientDirection, MattingKind, MattingMode,
-    MattingSelection, PhotoEffectOptions, RadialShape, StudioMatColor, TransitionKind,
-    TransitionMode, TransitionSelection,
+    CaptionCorner, ColorTargetSpace, Configuration, DedupMode, DisplayMode, FillWhenFits,
+    GlobalPhotoSettings, GradientDirection, IrisDirection, LibraryRoot, LogFormat, MattingKind,
+    MattingMode, MattingSelection, MotionConfig, PhotoEffectOptions, PresentMode, RadialShape,
+    StudioMatColor, ToneMode, TransitionKind, TransitionMode, TransitionSelection, ZoomDirection,
 };
+use photoframe::processing::color::PhotoColors;
 use rand::{SeedableRng, rngs::StdRng};
 use std::path::PathBuf;
 
@@ -12,10 +14,87 @@ fn parse_kebab_case_config() {
 photo-library-path: "/photos"
 "#;
     let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
-    assert_eq!(cfg.photo_library_path, PathBuf::from("/photos"));
+    assert_eq!(cfg.library_paths(), vec![PathBuf::from("/photos")]);
     assert!((cfg.global_photo_settings.oversample - 1.0).abs() < f32::EPSILON);
 }
 
+#[test]
+fn parse_multiple_photo_library_paths() {
+    let yaml = r#"
+photo-library-path:
+  - "/photos/family"
+  - "/mnt/nas/photos"
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(
+        cfg.library_paths(),
+        vec![
+            PathBuf::from("/photos/family"),
+            PathBuf::from("/mnt/nas/photos")
+        ]
+    );
+    cfg.validated().unwrap();
+}
+
+#[test]
+fn photo_library_entries_mix_paths_and_maps() {
+    let yaml = r#"
+photo-library-path:
+  - "/photos/family"
+  - path: "/mnt/nas/photos"
+    weight: 2
+    watch: false
+    optional: true
+  - "/photos/family"
+"#;
+    let cfg = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap();
+    // The repeated directory keeps only its first entry.
+    assert_eq!(
+        cfg.library_paths(),
+        vec![
+            PathBuf::from("/photos/family"),
+            PathBuf::from("/mnt/nas/photos")
+        ]
+    );
+    // A bare path is waited for when missing, as before per-root settings.
+    let family = LibraryRoot {
+        optional: true,
+        ..LibraryRoot::new("/photos/family")
+    };
+    assert_eq!(cfg.photo_library_paths[0], family);
+    let nas = &cfg.photo_library_paths[1];
+    assert_eq!(nas.weight, 2.0);
+    assert!(!nas.watch);
+    assert!(nas.optional);
+
+    // A single map needs no list around it, and as the sole root it is
+    // optional unless it says otherwise.
+    let single: Configuration =
+        serde_yaml::from_str("photo-library-path: { path: /photos, weight: 2 }\n").unwrap();
+    assert!(single.photo_library_paths[0].optional);
+    assert!(single.photo_library_paths[0].watch);
+    let required: Configuration =
+        serde_yaml::from_str("photo-library-path: [{ path: /photos, optional: false }]\n").unwrap();
+    assert!(!required.photo_library_paths[0].optional);
+    // Among several roots a map entry must be marked to be optional.
+    let several: Configuration =
+        serde_yaml::from_str("photo-library-path: [/photos, { path: /mnt/nas }]\n").unwrap();
+    assert!(several.photo_library_paths[0].optional);
+    assert!(!several.photo_library_paths[1].optional);
+
+    let light =
+        Configuration::from_yaml_str("photo-library-path:\n  - path: /photos\n    weight: 0.5\n")
+            .unwrap();
+    assert!(light.validated().is_err());
+    assert!(
+        Configuration::from_yaml_str("photo-library-path:\n  - path: /photos\n    wieght: 2\n")
+            .is_err()
+    );
+}
+
 #[test]
 fn parse_with_oversample() {
     let yaml = r#"
@@ -24,7 +103,7 @@ global-photo-settings:
   oversample: 1.5
 "#;
     let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
-    assert_eq!(cfg.photo_library_path, PathBuf::from("/photos"));
+    assert_eq!(cfg.library_paths(), vec![PathBuf::from("/photos")]);
     assert!((cfg.global_photo_settings.oversample - 1.5).abs() < f32::EPSILON);
 }
 
@@ -75,7 +154,7 @@ matting:
         } => {
             assert_eq!(colors.as_slice(), &[StudioMatColor::PhotoAverage]);
             assert!((*bevel_width_px - 5.0).abs() < f32::EPSILON);
-            assert_eq!(*bevel_color, [200, 210, 220]);
+            assert_eq!(*bevel_color, StudioMatColor::Rgb([200, 210, 220]));
             assert!((*texture_strength - 1.0).abs() < f32::EPSILON);
             assert!((*warp_period_px - 5.6).abs() < f32::EPSILON);
             assert!((*weft_period_px - 5.2).abs() < f32::EPSILON);
@@ -142,6 +221,70 @@ matting:
     }
 }
 
+#[test]
+fn parse_studio_with_texture_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let linen = dir.path().join("linen.png");
+    image::RgbaImage::from_pixel(4, 4, image::Rgba([214, 206, 188, 255]))
+        .save(&linen)
+        .unwrap();
+
+    let yaml = format!(
+        r#"
+photo-library-path: "/photos"
+matting:
+  selection: fixed
+  active:
+    - kind: studio
+      texture-path: "{linen}"
+"#,
+        linen = linen.display()
+    );
+
+    let cfg: Configuration = serde_yaml::from_str(&yaml).unwrap();
+    let selected = cfg
+        .matting
+        .primary_selected()
+        .expect("expected primary matting option");
+    match &selected.option.style {
+        MattingMode::Studio { texture_path, .. } => {
+            assert_eq!(texture_path.as_deref(), Some(linen.as_path()));
+        }
+        _ => panic!("expected studio matting"),
+    }
+
+    let mut mat = selected.option.clone();
+    mat.prepare_runtime().unwrap();
+    let texture = mat.runtime.studio_texture().expect("expected mat texture");
+    assert_eq!(texture.path(), linen.as_path());
+}
+
+#[test]
+fn studio_texture_path_must_exist() {
+    let yaml = r#"
+photo-library-path: "/photos"
+matting:
+  selection: fixed
+  active:
+    - kind: studio
+      texture-path: "/nonexistent/linen.png"
+"#;
+
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let mut mat = cfg
+        .matting
+        .primary_selected()
+        .expect("expected studio matting")
+        .option
+        .clone();
+    let err = mat.prepare_runtime().unwrap_err();
+    let message = format!("{err:#}");
+    assert!(
+        message.contains("texture-path") && message.contains("/nonexistent/linen.png"),
+        "{message}"
+    );
+}
+
 #[test]
 fn parse_random_matting_configuration() {
     let yaml = r#"
@@ -192,6 +335,41 @@ matting:
     }
 }
 
+#[test]
+fn random_matting_is_reproducible_with_a_seed() {
+    let yaml = r#"
+photo-library-path: "/photos"
+matting:
+  selection: random
+  active:
+    - kind: fixed-color
+      color: [10, 20, 30]
+    - kind: blur
+    - kind: studio
+"#;
+
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let kinds = |seed: u64| -> Vec<MattingKind> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..24)
+            .map(|_| cfg.matting.select_active(&mut rng).entry.kind)
+            .collect()
+    };
+    let first = kinds(7);
+    assert_eq!(first, kinds(7));
+    assert!(
+        [
+            MattingKind::FixedColor,
+            MattingKind::Blur,
+            MattingKind::Studio
+        ]
+        .iter()
+        .all(|kind| first.contains(kind)),
+        "{first:?}"
+    );
+    assert_ne!(first, kinds(8));
+}
+
 #[test]
 fn multiple_fixed_color_entries_preserve_all_options() {
     let yaml = r#"
@@ -412,17 +590,165 @@ photo-effect:
         PhotoEffectOptions::PrintSimulation(options) => {
             assert!((options.light_angle_degrees - 45.0).abs() < f32::EPSILON);
         }
+        other => panic!("expected print-simulation, got {other:?}"),
     }
     match second {
         PhotoEffectOptions::PrintSimulation(options) => {
             assert!((options.light_angle_degrees - 135.0).abs() < f32::EPSILON);
         }
+        other => panic!("expected print-simulation, got {other:?}"),
     }
     match third {
         PhotoEffectOptions::PrintSimulation(options) => {
             assert!((options.light_angle_degrees - 45.0).abs() < f32::EPSILON);
         }
+        other => panic!("expected print-simulation, got {other:?}"),
+    }
+}
+
+#[test]
+fn photo_effect_sequential_alternates_print_simulation_and_film_grain() {
+    let yaml = r#"
+photo-library-path: "/photos"
+photo-effect:
+  selection: sequential
+  active:
+    - kind: print-simulation
+    - kind: film-grain
+      grain-strength: 0.08
+      vignette-strength: 0.0
+"#;
+
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let cfg = cfg.validated().unwrap();
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let kinds: Vec<_> = (0..4)
+        .map(|_| cfg.photo_effect.choose_option(&mut rng).unwrap())
+        .collect();
+    assert!(matches!(kinds[0], PhotoEffectOptions::PrintSimulation(_)));
+    assert!(matches!(kinds[2], PhotoEffectOptions::PrintSimulation(_)));
+    for grain in [&kinds[1], &kinds[3]] {
+        let PhotoEffectOptions::FilmGrain(options) = grain else {
+            panic!("expected film-grain, got {grain:?}");
+        };
+        assert!((options.grain_strength - 0.08).abs() < f32::EPSILON);
+        assert!((options.grain_size_px - 1.5).abs() < f32::EPSILON);
+        assert_eq!(options.vignette_strength, 0.0);
+    }
+}
+
+#[test]
+fn photo_effect_chain_returns_every_effect_in_order() {
+    let yaml = r#"
+photo-library-path: "/photos"
+photo-effect:
+  selection: chain
+  active:
+    - kind: tone
+      mode: grayscale
+    - kind: film-grain
+"#;
+
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let cfg = cfg.validated().unwrap();
+
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..3 {
+        let effects = cfg.photo_effect.choose_effects(&mut rng);
+        assert_eq!(effects.len(), 2);
+        assert!(matches!(effects[0], PhotoEffectOptions::Tone(_)));
+        assert!(matches!(effects[1], PhotoEffectOptions::FilmGrain(_)));
     }
+
+    // `chain` belongs to photo-effect; transitions still pick one at a time.
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  selection: chain
+  active:
+    - kind: fade
+"#;
+    assert!(serde_yaml::from_str::<Configuration>(yaml).is_err());
+}
+
+#[test]
+fn parse_tone_effect_modes() {
+    let yaml = r#"
+photo-library-path: "/photos"
+photo-effect:
+  selection: sequential
+  active:
+    - kind: tone
+    - kind: tone
+      mode: grayscale
+      strength: 0.5
+    - kind: tone
+      mode: duotone
+      shadow-color: [10, 20, 30]
+      highlight-color: [250, 240, 230]
+"#;
+
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let cfg = cfg.validated().unwrap();
+
+    let mut rng = StdRng::seed_from_u64(1);
+    let tones: Vec<_> = (0..3)
+        .map(
+            |_| match cfg.photo_effect.choose_option(&mut rng).unwrap() {
+                PhotoEffectOptions::Tone(options) => options,
+                other => panic!("expected tone, got {other:?}"),
+            },
+        )
+        .collect();
+    assert_eq!(tones[0].mode, ToneMode::Sepia);
+    assert_eq!(tones[0].strength, 1.0);
+    assert_eq!(tones[1].mode, ToneMode::Grayscale);
+    assert!((tones[1].strength - 0.5).abs() < f32::EPSILON);
+    assert_eq!(tones[2].mode, ToneMode::Duotone);
+    assert_eq!(tones[2].shadow_color, [10, 20, 30]);
+    assert_eq!(tones[2].highlight_color, [250, 240, 230]);
+}
+
+#[test]
+fn tone_rejects_unknown_modes_and_out_of_range_strength() {
+    let yaml = r#"
+photo-library-path: "/photos"
+photo-effect:
+  active:
+    - kind: tone
+      mode: cyanotype
+"#;
+    assert!(serde_yaml::from_str::<Configuration>(yaml).is_err());
+
+    let yaml = r#"
+photo-library-path: "/photos"
+photo-effect:
+  active:
+    - kind: tone
+      strength: 1.5
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let err = cfg.validated().unwrap_err();
+    assert!(format!("{err:#}").contains("tone.strength"), "{err:#}");
+}
+
+#[test]
+fn film_grain_rejects_negative_strength() {
+    let yaml = r#"
+photo-library-path: "/photos"
+photo-effect:
+  active:
+    - kind: film-grain
+      grain-strength: -0.1
+"#;
+
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let err = cfg.validated().unwrap_err();
+    assert!(
+        format!("{err:#}").contains("grain-strength"),
+        "unexpected error: {err:#}"
+    );
 }
 
 #[test]
@@ -581,6 +907,87 @@ matting:
     assert!(mat.runtime.fixed_image().is_none());
 }
 
+#[test]
+fn unusable_fixed_images_are_left_out() {
+    let dir = tempfile::tempdir().unwrap();
+    let good = dir.path().join("linen.png");
+    image::RgbImage::new(4, 4).save(&good).unwrap();
+    let text = dir.path().join("notes.png");
+    std::fs::write(&text, "not an image").unwrap();
+    let missing = dir.path().join("missing.png");
+
+    let yaml = format!(
+        r#"
+photo-library-path: "/photos"
+matting:
+  selection: sequential
+  active:
+    - kind: fixed-image
+      path: ["{missing}", "{good}", "{text}"]
+    - kind: fixed-color
+      colors: [[10, 20, 30]]
+"#,
+        missing = missing.display(),
+        good = good.display(),
+        text = text.display()
+    );
+    let cfg = Configuration::from_yaml_str(&yaml)
+        .unwrap()
+        .validated()
+        .unwrap();
+
+    let kinds: Vec<_> = cfg
+        .matting
+        .iter_selected()
+        .map(|selected| selected.entry.kind)
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![MattingKind::FixedImage, MattingKind::FixedColor]
+    );
+    let background = cfg.matting.options()[0].runtime.fixed_image().unwrap();
+    assert_eq!(background.path(), good.as_path());
+    let mut rng = StdRng::seed_from_u64(1);
+    let cycle: Vec<_> = (0..3)
+        .map(|_| cfg.matting.select_active(&mut rng).entry.index)
+        .collect();
+    assert_eq!(cycle, vec![0, 1, 0]);
+}
+
+#[test]
+fn fixed_image_matting_without_a_usable_background_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let text = dir.path().join("notes.png");
+    std::fs::write(&text, "not an image").unwrap();
+    let missing = dir.path().join("missing.png");
+
+    let yaml = format!(
+        r#"
+photo-library-path: "/photos"
+matting:
+  active:
+    - kind: fixed-image
+      path: ["{missing}", "{text}"]
+"#,
+        missing = missing.display(),
+        text = text.display()
+    );
+    let err = Configuration::from_yaml_str(&yaml)
+        .unwrap()
+        .validated()
+        .unwrap_err();
+    let message = format!("{err:#}");
+    assert!(
+        message.contains("no matting fixed-image background is usable"),
+        "{message}"
+    );
+    assert!(
+        message.contains(&missing.display().to_string()),
+        "{message}"
+    );
+    assert!(message.contains(&text.display().to_string()), "{message}");
+}
+
 #[test]
 fn matting_entry_without_required_fields_is_rejected() {
     let yaml = r#"
@@ -940,40 +1347,279 @@ fn validated_rejects_zero_preload() {
 }
 
 #[test]
-fn validated_rejects_invalid_numeric_ranges() {
-    let cfg = Configuration {
-        loader_max_concurrent_decodes: 0,
-        ..Default::default()
-    };
-    assert!(cfg.validated().is_err());
-
-    let cfg = Configuration {
-        global_photo_settings: GlobalPhotoSettings {
-            oversample: 0.0,
-            ..Default::default()
-        },
-        ..Default::default()
-    };
-    assert!(cfg.validated().is_err());
+fn parse_displays_section() {
+    let yaml = r#"
+photo-library-path: "/photos"
+displays:
+  outputs: ["HDMI-A-1", "HDMI-A-2"]
+  mode: independent
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let cfg = cfg.validated().unwrap();
+    let displays = cfg.displays.expect("displays parsed");
+    assert_eq!(displays.outputs, ["HDMI-A-1", "HDMI-A-2"]);
+    assert_eq!(displays.mode, DisplayMode::Independent);
+
+    let mirrored: Configuration = serde_yaml::from_str(
+        "photo-library-path: /photos
+displays: { outputs: [DP-1] }
+",
+    )
+    .unwrap();
+    assert_eq!(mirrored.displays.unwrap().mode, DisplayMode::Mirror);
+
+    let defaults: Configuration = serde_yaml::from_str(
+        "photo-library-path: /photos
+",
+    )
+    .unwrap();
+    assert!(defaults.displays.is_none());
+
+    for bad in [
+        "displays: { outputs: [] }",
+        "displays: { outputs: [HDMI-A-1, HDMI-A-1] }",
+        "displays: { outputs: [\"\"] }",
+    ] {
+        let cfg: Configuration =
+            serde_yaml::from_str(&format!("photo-library-path: /photos\n{bad}\n")).unwrap();
+        assert!(cfg.validated().is_err(), "{bad} should be rejected");
+    }
+    assert!(
+        Configuration::from_yaml_str(
+            "photo-library-path: /photos\ndisplays: { outputs: [HDMI-A-1], mode: spread }\n"
+        )
+        .is_err()
+    );
 }
 
 #[test]
-fn wipe_transition_rejects_negative_jitter() {
+fn parse_thumbnail_cache_section() {
     let yaml = r#"
 photo-library-path: "/photos"
-transition:
-  selection: fixed
-  active:
-    - kind: wipe
-      angle-jitter: -15.0
+thumbnail-cache:
+  directory: /var/lib/photoframe/thumbnails
 "#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let cache = cfg
+        .validated()
+        .unwrap()
+        .thumbnail_cache
+        .expect("cache parsed");
+    assert_eq!(
+        cache.directory,
+        PathBuf::from("/var/lib/photoframe/thumbnails")
+    );
+    assert_eq!(cache.max_dimension_px, 3840);
 
-    let err = serde_yaml::from_str::<Configuration>(yaml).unwrap_err();
-    assert!(err.to_string().contains("requires angle-jitter >= 0"));
+    let cfg: Configuration = serde_yaml::from_str(
+        "photo-library-path: /photos\nthumbnail-cache: { directory: /tmp/t, max-dimension-px: 16 }\n",
+    )
+    .unwrap();
+    assert!(cfg.validated().is_err());
 }
 
 #[test]
-fn wipe_transition_requires_angles() {
+fn parse_decode_cache_section() {
+    let yaml = r#"
+photo-library-path: "/photos"
+decode-cache:
+  directory: /var/lib/photoframe/decoded
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let cache = cfg.validated().unwrap().decode_cache.expect("cache parsed");
+    assert_eq!(
+        cache.directory,
+        PathBuf::from("/var/lib/photoframe/decoded")
+    );
+    assert_eq!(cache.max_size_mb, 2048);
+
+    let cfg: Configuration = serde_yaml::from_str(
+        "photo-library-path: /photos\ndecode-cache: { directory: /tmp/d, max-size-mb: 8 }\n",
+    )
+    .unwrap();
+    assert!(cfg.validated().is_err());
+}
+
+#[test]
+fn parse_playlist_new_photo_boost() {
+    let yaml = r#"
+photo-library-path: "/photos"
+playlist:
+  new-photo-boost:
+    displays: 20
+    max-age: 7 days
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let boost = cfg
+        .validated()
+        .unwrap()
+        .playlist
+        .new_photo_boost
+        .expect("boost parsed");
+    assert_eq!(boost.displays, 20);
+    assert_eq!(boost.max_age, std::time::Duration::from_secs(7 * 86_400));
+
+    let cfg: Configuration = serde_yaml::from_str(
+        "photo-library-path: /photos\nplaylist: { new-photo-boost: { displays: 0, max-age: 1d } }\n",
+    )
+    .unwrap();
+    assert!(cfg.validated().is_err());
+}
+
+#[test]
+fn parse_playlist_favorites() {
+    let yaml = r#"
+photo-library-path: "/photos"
+playlist:
+  favorites:
+    file: /var/lib/photoframe/favorites.txt
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let favorites = cfg
+        .validated()
+        .unwrap()
+        .playlist
+        .favorites
+        .expect("favorites parsed");
+    assert_eq!(
+        favorites.file,
+        PathBuf::from("/var/lib/photoframe/favorites.txt")
+    );
+    assert_eq!(favorites.factor, 3.0);
+    // A missing file simply lists nothing.
+    assert!(favorites.load().is_empty());
+
+    let cfg: Configuration = serde_yaml::from_str(
+        "photo-library-path: /photos\nplaylist: { favorites: { file: f.txt, factor: 0.5 } }\n",
+    )
+    .unwrap();
+    assert!(cfg.validated().is_err());
+}
+
+#[test]
+fn parse_motion_section() {
+    let yaml = r#"
+photo-library-path: "/photos"
+motion:
+  type: ken-burns
+  zoom-range: [1.0, 1.12]
+  pan-amplitude: 0.4
+  direction: in
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let cfg = cfg.validated().unwrap();
+    assert_eq!(
+        cfg.motion,
+        Some(MotionConfig::KenBurns {
+            zoom_range: [1.0, 1.12],
+            pan_amplitude: 0.4,
+            direction: ZoomDirection::In,
+        })
+    );
+
+    let defaults: Configuration = serde_yaml::from_str("photo-library-path: /photos\n").unwrap();
+    assert!(defaults.motion.is_none());
+    let bare: Configuration =
+        serde_yaml::from_str("photo-library-path: /photos\nmotion: { type: ken-burns }\n").unwrap();
+    assert_eq!(
+        bare.validated().unwrap().motion,
+        Some(MotionConfig::KenBurns {
+            zoom_range: [1.0, 1.08],
+            pan_amplitude: 1.0,
+            direction: ZoomDirection::Random,
+        })
+    );
+}
+
+#[test]
+fn motion_rejects_zoom_below_one() {
+    for bad in [
+        "motion: { type: ken-burns, zoom-range: [0.9, 1.08] }",
+        "motion: { type: ken-burns, zoom-range: [1.1, 1.0] }",
+        "motion: { type: ken-burns, pan-amplitude: 1.5 }",
+    ] {
+        let cfg: Configuration =
+            serde_yaml::from_str(&format!("photo-library-path: /photos\n{bad}\n")).unwrap();
+        assert!(cfg.validated().is_err(), "{bad} should be rejected");
+    }
+    let unknown = serde_yaml::from_str::<Configuration>(
+        "photo-library-path: /photos\nmotion: { type: ken-burns, speed: 2 }\n",
+    );
+    assert!(unknown.is_err());
+    let bad_direction = serde_yaml::from_str::<Configuration>(
+        "photo-library-path: /photos\nmotion: { type: ken-burns, direction: sideways }\n",
+    );
+    assert!(bad_direction.is_err());
+}
+
+#[test]
+fn control_socket_peer_lists_default_to_unset() {
+    let yaml = r#"
+photo-library-path: "/photos"
+control-socket-allowed-uids: [1000, 1001]
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let cfg = cfg.validated().unwrap();
+    assert_eq!(cfg.control_socket_allowed_uids, Some(vec![1000, 1001]));
+    assert_eq!(cfg.control_socket_allowed_gids, None);
+
+    let cfg = Configuration::default();
+    assert!(cfg.control_socket_allowed_uids.is_none());
+    assert!(cfg.control_socket_allowed_gids.is_none());
+}
+
+#[test]
+fn control_socket_empty_peer_list_is_rejected() {
+    let yaml = r#"
+photo-library-path: "/photos"
+control-socket-allowed-gids: []
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let err = cfg.validated().unwrap_err();
+    assert!(err.to_string().contains("control-socket-allowed-gids"));
+}
+
+#[test]
+fn validated_rejects_invalid_numeric_ranges() {
+    let cfg = Configuration {
+        loader_max_concurrent_decodes: 0,
+        ..Default::default()
+    };
+    assert!(cfg.validated().is_err());
+
+    let cfg = Configuration {
+        loader_decode_timeout_ms: 0,
+        ..Default::default()
+    };
+    assert!(cfg.validated().is_err());
+
+    let cfg = Configuration {
+        global_photo_settings: GlobalPhotoSettings {
+            oversample: 0.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    assert!(cfg.validated().is_err());
+}
+
+#[test]
+fn wipe_transition_rejects_negative_jitter() {
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  selection: fixed
+  active:
+    - kind: wipe
+      angle-jitter: -15.0
+"#;
+
+    let err = serde_yaml::from_str::<Configuration>(yaml).unwrap_err();
+    assert!(err.to_string().contains("requires angle-jitter >= 0"));
+}
+
+#[test]
+fn wipe_transition_requires_angles() {
     let yaml = r#"
 photo-library-path: "/photos"
 transition:
@@ -1042,6 +1688,80 @@ transition:
     }
 }
 
+#[test]
+fn push_transition_accepts_named_directions() {
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  selection: sequential
+  active:
+    - kind: push
+      direction: left
+    - kind: push
+      direction: up
+      angle-jitter: 5.0
+"#;
+
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let angles: Vec<(f32, f32)> = cfg
+        .transition
+        .iter_selected()
+        .map(|selected| match selected.option.mode() {
+            TransitionMode::Push(push) => {
+                assert!(!push.random_direction);
+                (push.angles.base_deg, push.angles.jitter_deg)
+            }
+            _ => panic!("expected push transition"),
+        })
+        .collect();
+    assert_eq!(angles, vec![(180.0, 0.0), (270.0, 5.0)]);
+
+    let both = r#"
+photo-library-path: "/photos"
+transition:
+  active:
+    - kind: push
+      direction: down
+      angles: [90.0]
+"#;
+    let err = serde_yaml::from_str::<Configuration>(both).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("accepts direction or angles, not both")
+    );
+}
+
+#[test]
+fn push_random_direction_is_reproducible_with_a_seed() {
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  active:
+    - kind: push
+      direction: random
+"#;
+
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let option = cfg.transition.primary_option().expect("one push option");
+    let TransitionMode::Push(push) = option.mode() else {
+        panic!("expected push transition");
+    };
+    assert!(push.random_direction);
+
+    let draw = |seed| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..32)
+            .map(|_| push.pick_angle_deg(&mut rng))
+            .collect::<Vec<_>>()
+    };
+    let first = draw(7);
+    assert_eq!(first, draw(7));
+    for angle in [0.0, 90.0, 180.0, 270.0] {
+        assert!(first.contains(&angle), "{angle} never drawn in {first:?}");
+    }
+    assert!(first.iter().all(|a| [0.0, 90.0, 180.0, 270.0].contains(a)));
+}
+
 #[test]
 fn parse_matting_fill_when_fits() {
     let yaml = r#"
@@ -1254,6 +1974,28 @@ matting:
     }
 }
 
+#[test]
+fn gradient_angle_must_stay_within_a_turn() {
+    let config = |angle: &str| {
+        format!(
+            "photo-library-path: /photos\nmatting:\n  active:\n    - kind: gradient\n      angle-degrees: {angle}\n"
+        )
+    };
+    for angle in ["-360", "-90", "0", "135.5", "360"] {
+        let cfg: Configuration = serde_yaml::from_str(&config(angle)).unwrap();
+        cfg.validated()
+            .unwrap_or_else(|err| panic!("angle {angle} should validate: {err:#}"));
+    }
+    for angle in ["400", "-360.5", ".nan", ".inf"] {
+        let cfg: Configuration = serde_yaml::from_str(&config(angle)).unwrap();
+        let err = format!("{:#}", cfg.validated().unwrap_err());
+        assert!(
+            err.contains("matting.gradient.angle-degrees must be between -360 and 360"),
+            "angle {angle}: {err}"
+        );
+    }
+}
+
 #[test]
 fn parse_vignette_matting_defaults() {
     let yaml = r#"
@@ -1317,6 +2059,34 @@ matting:
     }
 }
 
+#[test]
+fn parse_gpu_blur_backend() {
+    let yaml = r#"
+photo-library-path: "/photos"
+matting:
+  selection: fixed
+  active:
+    - kind: blur
+      backend: gpu
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let selected = cfg
+        .matting
+        .iter_selected()
+        .next()
+        .expect("expected blur mat");
+    assert!(matches!(
+        selected.option.style,
+        MattingMode::Blur {
+            backend: photoframe::config::BlurBackend::Gpu,
+            ..
+        }
+    ));
+
+    let yaml = yaml.replace("backend: gpu", "backend: opencl");
+    assert!(serde_yaml::from_str::<Configuration>(&yaml).is_err());
+}
+
 #[test]
 fn parse_passe_partout_matting_defaults() {
     let yaml = r#"
@@ -1342,7 +2112,7 @@ matting:
             assert_eq!(colors.len(), 1);
             assert!(matches!(colors[0], StudioMatColor::PhotoAverage));
             assert!((bevel_width_px - 3.0).abs() < 1e-5);
-            assert_eq!(*bevel_color, [255, 255, 255]);
+            assert_eq!(*bevel_color, StudioMatColor::Rgb([255, 255, 255]));
         }
         _ => panic!("expected passe-partout matting"),
     }
@@ -1485,6 +2255,96 @@ transition:
     }
 }
 
+#[test]
+fn parse_crossfade_blur_transition() {
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  active:
+    - kind: crossfade-blur
+      duration-ms: 1200
+      strength: 24
+    - kind: crossfade-blur
+      strength: 500
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let strengths: Vec<(TransitionKind, u128, f32)> = cfg
+        .transition
+        .iter_selected()
+        .map(|selected| match selected.option.mode() {
+            TransitionMode::CrossfadeBlur(cb) => (
+                selected.entry.kind,
+                selected.option.duration().as_millis(),
+                cb.strength,
+            ),
+            _ => panic!("expected crossfade-blur"),
+        })
+        .collect();
+    assert_eq!(
+        strengths,
+        vec![
+            (TransitionKind::CrossfadeBlur, 1200, 24.0),
+            // Out-of-range strengths are clamped.
+            (TransitionKind::CrossfadeBlur, 900, 64.0),
+        ]
+    );
+
+    let err = serde_yaml::from_str::<Configuration>(
+        "photo-library-path: /photos\ntransition:\n  active:\n    - kind: crossfade-zoom\n      strength: 4\n",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("strength"), "{err}");
+}
+
+#[test]
+fn random_transition_draws_follow_configured_weights() {
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  selection: random
+  active:
+    - kind: fade
+      weight: 6
+    - kind: wipe
+      weight: 3
+    - kind: dissolve
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let cfg = cfg.validated().unwrap();
+    let weights: Vec<f32> = cfg
+        .transition
+        .iter_selected()
+        .map(|selected| selected.option.weight())
+        .collect();
+    assert_eq!(weights, vec![6.0, 3.0, 1.0]);
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let draws = 20_000;
+    let mut counts = [0usize; 3];
+    for _ in 0..draws {
+        let slot = match cfg.transition.choose_option(&mut rng).kind() {
+            TransitionKind::Fade => 0,
+            TransitionKind::Wipe => 1,
+            TransitionKind::Dissolve => 2,
+            other => panic!("unexpected transition {other}"),
+        };
+        counts[slot] += 1;
+    }
+    for (count, expected) in counts.iter().zip([0.6, 0.3, 0.1]) {
+        let observed = *count as f64 / draws as f64;
+        assert!(
+            (observed - expected).abs() < 0.02,
+            "observed {observed:.3}, expected {expected}"
+        );
+    }
+
+    let cfg: Configuration = serde_yaml::from_str(
+        "photo-library-path: /photos\ntransition: { active: [{ kind: fade, weight: 0 }] }\n",
+    )
+    .unwrap();
+    assert!(cfg.validated().is_err());
+}
+
 #[test]
 fn parse_showcase_enabled() {
     let yaml = r#"
@@ -1537,11 +2397,11 @@ showcase:
         8,
         "showcase should have 8 mat options (all 9 kinds minus fixed-image which needs a path)"
     );
-    // 9 transition kinds
+    // 10 transition kinds
     assert_eq!(
         validated.transition.options().len(),
-        9,
-        "showcase should have 9 transition options (all TransitionKind::ALL)"
+        10,
+        "showcase should have 10 transition options (all TransitionKind::ALL)"
     );
     // Selection should be sequential.
     assert!(matches!(
@@ -1560,20 +2420,33 @@ showcase:
 
 #[test]
 fn showcase_with_fixed_image_path_includes_all_mats() {
-    let yaml = r#"
-photo-library-path: "/photos"
-showcase:
-  enabled: true
-  fixed-image-path: "/var/lib/photoframe/backgrounds/backdrop.jpg"
-"#;
-    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
-    let validated = cfg.validated().expect("showcase config should be valid");
+    let dir = tempfile::tempdir().unwrap();
+    let backdrop = dir.path().join("backdrop.png");
+    image::RgbImage::new(4, 4).save(&backdrop).unwrap();
+    let showcase = |path: &std::path::Path| {
+        let yaml = format!(
+            "photo-library-path: /photos\nshowcase:\n  enabled: true\n  fixed-image-path: {}\n",
+            path.display()
+        );
+        serde_yaml::from_str::<Configuration>(&yaml)
+            .unwrap()
+            .validated()
+            .expect("showcase config should be valid")
+    };
     // With fixed-image-path provided, all 9 mat kinds should appear.
     assert_eq!(
-        validated.matting.options().len(),
+        showcase(&backdrop).matting.options().len(),
         9,
         "all 9 mat kinds should appear when fixed-image-path is provided"
     );
+    // A backdrop that is not there leaves the fixed-image mat out.
+    assert_eq!(
+        showcase(&dir.path().join("missing.jpg"))
+            .matting
+            .options()
+            .len(),
+        8
+    );
 }
 
 #[test]
@@ -1632,39 +2505,128 @@ transition:
 }
 
 #[test]
-fn iris_defaults_apply_when_fields_omitted() {
+fn iris_rejects_geometry_keys_and_bad_blade_counts() {
+    // Petal geometry is derived from the screen and blade count, so radius is
+    // not part of the schema.
     let yaml = r#"
 photo-library-path: "/photos"
 transition:
   active:
     - kind: iris
+      radius: 0.8
 "#;
-    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
-    let opts: Vec<_> = cfg.transition.iter_selected().collect();
-    assert_eq!(opts.len(), 1);
-    assert_eq!(opts[0].option.duration().as_millis(), 2600);
-    match opts[0].option.mode() {
-        TransitionMode::Iris(iris) => {
-            assert_eq!(iris.blades, 9);
-            assert_eq!(iris.color, [42, 42, 49]);
-            assert_eq!(iris.petal_sheen, 0.45);
-            assert_eq!(iris.overlap_shadow, 0.6);
-            assert_eq!(iris.min_aperture, 0.05);
-            assert_eq!(iris.swirl, -0.45);
-        }
-        other => panic!("expected iris, got {other:?}"),
-    }
-}
+    let err = serde_yaml::from_str::<Configuration>(yaml).unwrap_err();
+    assert!(err.to_string().contains("radius"), "{err}");
 
-#[test]
-fn radial_wipe_shapes_expand_to_slots() {
     let yaml = r#"
 photo-library-path: "/photos"
 transition:
-  selection: random
   active:
-    - kind: radial-wipe
-      shapes: [circle, diamond]
+    - kind: iris
+      blades: -3
+"#;
+    assert!(serde_yaml::from_str::<Configuration>(yaml).is_err());
+
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  active:
+    - kind: iris
+      blades: 0
+"#;
+    let err = serde_yaml::from_str::<Configuration>(yaml).unwrap_err();
+    assert!(err.to_string().contains("blade"), "{err}");
+
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  active:
+    - kind: iris
+      stroke-width: .nan
+"#;
+    let err = serde_yaml::from_str::<Configuration>(yaml).unwrap_err();
+    assert!(err.to_string().contains("stroke-width"), "{err}");
+}
+
+#[test]
+fn iris_defaults_apply_when_fields_omitted() {
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  active:
+    - kind: iris
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let opts: Vec<_> = cfg.transition.iter_selected().collect();
+    assert_eq!(opts.len(), 1);
+    assert_eq!(opts[0].option.duration().as_millis(), 2600);
+    match opts[0].option.mode() {
+        TransitionMode::Iris(iris) => {
+            assert_eq!(iris.blades, 9);
+            assert_eq!(iris.color, [42, 42, 49]);
+            assert_eq!(iris.petal_sheen, 0.45);
+            assert_eq!(iris.overlap_shadow, 0.6);
+            assert_eq!(iris.min_aperture, 0.05);
+            assert_eq!(iris.swirl, -0.45);
+            assert_eq!(iris.stroke_width, 0.0);
+            assert_eq!(iris.direction, IrisDirection::CloseOpen);
+        }
+        other => panic!("expected iris, got {other:?}"),
+    }
+}
+
+#[test]
+fn random_selection_draws_iris_with_its_options() {
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  selection: random
+  active:
+    - kind: fade
+    - kind: iris
+      weight: 3
+      duration-ms: 1800
+      blades: 7
+      stroke-width: 2.5
+      stroke-color: [200, 180, 40]
+      direction: open
+"#;
+    let cfg = Configuration::from_yaml_str(yaml).unwrap();
+    let mut rng = StdRng::seed_from_u64(7);
+    let kinds: Vec<_> = (0..8)
+        .map(|_| cfg.transition.select_active(&mut rng))
+        .map(|selected| {
+            if let TransitionMode::Iris(iris) = selected.option.mode() {
+                assert_eq!(selected.option.duration().as_millis(), 1800);
+                assert_eq!(iris.blades, 7);
+                assert_eq!(iris.stroke_width, 2.5);
+                assert_eq!(iris.stroke_color, [200, 180, 40]);
+                assert_eq!(iris.direction, IrisDirection::Open);
+            }
+            selected.entry.kind
+        })
+        .collect();
+    assert!(kinds.contains(&TransitionKind::Fade), "{kinds:?}");
+    assert!(kinds.contains(&TransitionKind::Iris), "{kinds:?}");
+
+    let again: Vec<_> = {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..8)
+            .map(|_| cfg.transition.select_active(&mut rng).entry.kind)
+            .collect()
+    };
+    assert_eq!(kinds, again);
+}
+
+#[test]
+fn radial_wipe_shapes_expand_to_slots() {
+    let yaml = r#"
+photo-library-path: "/photos"
+transition:
+  selection: random
+  active:
+    - kind: radial-wipe
+      shapes: [circle, diamond]
 "#;
     let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
     let opts: Vec<_> = cfg.transition.iter_selected().collect();
@@ -1733,6 +2695,119 @@ matting:
     }
 }
 
+#[test]
+fn studio_photo_dominant_resolves_to_the_most_common_color() {
+    let yaml = r#"
+photo-library-path: "/photos"
+matting:
+  selection: fixed
+  active:
+    - kind: studio
+      colors: [photo-dominant]
+"#;
+    let mut cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    cfg.matting.prepare_runtime().unwrap();
+    let selected = cfg.matting.primary_selected().expect("expected studio");
+    match &selected.option.style {
+        MattingMode::Studio { colors, .. } => {
+            assert_eq!(colors.as_slice(), &[StudioMatColor::PhotoDominant]);
+        }
+        _ => panic!("expected studio"),
+    }
+
+    // Three quarters red, one quarter blue: the average is a purple that
+    // appears nowhere in the photo; the dominant color is the red.
+    let photo = image::RgbaImage::from_fn(64, 64, |x, _| {
+        if x < 48 {
+            image::Rgba([200, 20, 20, 255])
+        } else {
+            image::Rgba([20, 20, 200, 255])
+        }
+    });
+    let colors = PhotoColors::new(&photo);
+    let resolved = selected
+        .option
+        .runtime
+        .studio_color(&colors)
+        .expect("studio color resolved");
+    let expected = [200.0 / 255.0, 20.0 / 255.0, 20.0 / 255.0];
+    for (got, want) in resolved.iter().zip(expected) {
+        assert!((got - want).abs() < 1e-3, "{resolved:?}");
+    }
+    assert!((colors.average()[2] - expected[2]).abs() > 0.1);
+}
+
+#[test]
+fn studio_bevel_color_takes_photo_colors_and_a_tint() {
+    let yaml = r#"
+photo-library-path: "/photos"
+matting:
+  selection: sequential
+  active:
+    - kind: studio
+      colors: [[30, 30, 30]]
+      bevel-color: [255, 255, 255]
+      bevel-tint-strength: 0.5
+    - kind: studio
+      bevel-color: photo-average
+"#;
+    let mut cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    cfg.matting.prepare_runtime().unwrap();
+    let options = cfg.matting.options();
+    let MattingMode::Studio {
+        bevel_color,
+        bevel_tint_strength,
+        ..
+    } = &options[0].style
+    else {
+        panic!("expected studio");
+    };
+    assert_eq!(*bevel_color, StudioMatColor::Rgb([255, 255, 255]));
+    assert!((bevel_tint_strength - 0.5).abs() < f32::EPSILON);
+
+    // A dark photo: half the tint pulls the white bevel halfway to its average.
+    let photo = image::RgbaImage::from_pixel(16, 16, image::Rgba([51, 51, 51, 255]));
+    let colors = PhotoColors::new(&photo);
+    let average = colors.average()[0];
+    let tinted = bevel_color.resolve_bevel(*bevel_tint_strength, &colors);
+    assert!(
+        (tinted[0] - (1.0 + average) / 2.0).abs() < 1e-3,
+        "{tinted:?}"
+    );
+    assert_eq!(bevel_color.resolve_bevel(0.0, &colors), [1.0, 1.0, 1.0]);
+
+    let MattingMode::Studio {
+        bevel_color,
+        bevel_tint_strength,
+        ..
+    } = &options[1].style
+    else {
+        panic!("expected studio");
+    };
+    assert_eq!(*bevel_color, StudioMatColor::PhotoAverage);
+    assert_eq!(*bevel_tint_strength, 0.0);
+    let resolved = bevel_color.resolve_bevel(*bevel_tint_strength, &colors);
+    assert!((resolved[0] - average).abs() < 1e-3, "{resolved:?}");
+}
+
+#[test]
+fn studio_bevel_tint_strength_must_be_a_fraction() {
+    let yaml = r#"
+photo-library-path: "/photos"
+matting:
+  selection: fixed
+  active:
+    - kind: studio
+      bevel-tint-strength: 1.5
+"#;
+    let mut cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let err = cfg.matting.prepare_runtime().unwrap_err();
+    assert!(
+        format!("{err:#}").contains("bevel-tint-strength"),
+        "{err:#}"
+    );
+}
+
 #[test]
 fn studio_color_singular_alias_accepts_photo_average() {
     let yaml = r#"
@@ -1853,3 +2928,812 @@ fn shipped_showcase_yaml_validates() {
     let cfg: Configuration = serde_yaml::from_str(raw).expect("showcase.yaml should parse");
     cfg.validated().expect("showcase.yaml should validate");
 }
+
+#[test]
+fn reload_takes_live_sections_and_reports_restart_only_keys() {
+    let parse = |yaml: &str| -> Configuration {
+        serde_yaml::from_str::<Configuration>(yaml)
+            .unwrap()
+            .validated()
+            .unwrap()
+    };
+    let running = parse(
+        r#"
+photo-library-path: "/photos"
+global-photo-settings:
+  dwell-ms: 30000
+greeting-screen:
+  message: "Hello"
+"#,
+    );
+    let edited = parse(
+        r#"
+photo-library-path: "/other-photos"
+viewer-preload-count: 5
+global-photo-settings:
+  dwell-ms: 8000
+greeting-screen:
+  message: "Welcome home"
+"#,
+    );
+
+    let (merged, ignored) = running.reloaded_from(&edited);
+
+    assert_eq!(merged.global_photo_settings.dwell_ms, 8000);
+    assert_eq!(
+        merged.greeting_screen.screen.message.as_deref(),
+        Some("Welcome home")
+    );
+    assert_eq!(merged.library_paths(), vec![PathBuf::from("/photos")]);
+    assert_eq!(merged.viewer_preload_count, 3);
+    assert_eq!(ignored, ["photo-library-path", "viewer-preload-count"]);
+
+    let (_, ignored) = running.reloaded_from(&running.clone());
+    assert!(ignored.is_empty());
+}
+
+#[test]
+fn web_control_is_off_by_default_and_stays_on_loopback() {
+    let cfg: Configuration = serde_yaml::from_str("photo-library-path: /photos\n").unwrap();
+    let web = &cfg.validated().unwrap().web_control;
+    assert!(!web.enabled);
+    assert_eq!(web.bind_address, "127.0.0.1:8080".parse().unwrap());
+
+    let yaml = r#"
+photo-library-path: "/photos"
+web-control:
+  enabled: true
+  bind-address: "0.0.0.0:8080"
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let err = cfg.validated().unwrap_err();
+    assert!(format!("{err:#}").contains("allow-lan"), "{err:#}");
+
+    let cfg: Configuration = serde_yaml::from_str(&format!("{yaml}  allow-lan: true\n")).unwrap();
+    let web = cfg.validated().unwrap().web_control;
+    assert!(web.enabled && web.allow_lan);
+}
+
+#[test]
+fn health_server_is_off_by_default_and_needs_its_own_port() {
+    let cfg: Configuration = serde_yaml::from_str("photo-library-path: /photos\n").unwrap();
+    let health = &cfg.validated().unwrap().health_server;
+    assert!(!health.enabled);
+    assert_eq!(health.bind_address, "127.0.0.1:8081".parse().unwrap());
+
+    let yaml = r#"
+photo-library-path: "/photos"
+web-control:
+  enabled: true
+health-server:
+  enabled: true
+  bind-address: "127.0.0.1:8080"
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let err = cfg.validated().unwrap_err();
+    assert!(format!("{err:#}").contains("already taken"), "{err:#}");
+
+    let cfg: Configuration =
+        serde_yaml::from_str(&yaml.replace("127.0.0.1:8080", "0.0.0.0:8081")).unwrap();
+    let health = cfg.validated().unwrap().health_server;
+    assert!(health.enabled);
+}
+
+#[test]
+fn control_tcp_allows_loopback_and_listed_peers() {
+    let yaml = r#"
+photo-library-path: "/photos"
+control-tcp:
+  enabled: true
+  bind-address: "0.0.0.0:8082"
+  allowed-peers: ["192.168.1.20", "fd00::20"]
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let tcp = cfg.validated().unwrap().control_tcp;
+    for peer in [
+        "127.0.0.1",
+        "::1",
+        "192.168.1.20",
+        "::ffff:192.168.1.20",
+        "fd00::20",
+    ] {
+        assert!(tcp.permits(peer.parse().unwrap()), "{peer} is allowed");
+    }
+    for peer in ["192.168.1.21", "10.0.0.20"] {
+        assert!(!tcp.permits(peer.parse().unwrap()), "{peer} is refused");
+    }
+
+    let cfg: Configuration = serde_yaml::from_str(&format!(
+        "{yaml}health-server:\n  enabled: true\n  bind-address: \"0.0.0.0:8082\"\n"
+    ))
+    .unwrap();
+    let err = cfg.validated().unwrap_err();
+    assert!(format!("{err:#}").contains("already taken"), "{err:#}");
+}
+
+#[test]
+fn mqtt_broker_and_topics_are_checked_when_enabled() {
+    let yaml = r#"
+photo-library-path: "/photos"
+mqtt:
+  enabled: true
+  broker: "mqtt://homeassistant.local"
+  username: frame
+  password: hunter2
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let mqtt = cfg.validated().unwrap().mqtt;
+    assert_eq!(
+        mqtt.broker_address().unwrap(),
+        ("homeassistant.local".to_owned(), 1883)
+    );
+    assert_eq!(mqtt.command_topic, "photoframe/command");
+    assert!(
+        !format!("{mqtt:?}").contains("hunter2"),
+        "the password stays out of the logged config"
+    );
+
+    for (from, to, message) in [
+        ("mqtt://homeassistant.local", "tcp://broker:1883", "mqtt://"),
+        (
+            "  password: hunter2\n",
+            "  state-topic: frames/#\n",
+            "wildcards",
+        ),
+        ("  username: frame\n", "", "needs mqtt.username"),
+    ] {
+        let cfg: Configuration = serde_yaml::from_str(&yaml.replace(from, to)).unwrap();
+        let err = cfg.validated().unwrap_err();
+        assert!(format!("{err:#}").contains(message), "{err:#}");
+    }
+}
+
+#[test]
+fn share_qr_needs_a_base_url_and_web_control() {
+    let cfg: Configuration = serde_yaml::from_str("photo-library-path: /photos\n").unwrap();
+    let share = cfg.validated().unwrap().share_qr;
+    assert!(!share.enabled);
+    assert_eq!(share.corner, CaptionCorner::BottomRight);
+
+    let yaml = r#"
+photo-library-path: "/photos"
+share-qr:
+  enabled: true
+  base-url: "http://frame.local:8080"
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let err = cfg.validated().unwrap_err();
+    assert!(
+        format!("{err:#}").contains("web-control.enabled"),
+        "{err:#}"
+    );
+
+    let loopback_only = format!("{yaml}web-control:\n  enabled: true\n");
+    let cfg: Configuration = serde_yaml::from_str(&loopback_only).unwrap();
+    let err = cfg.validated().unwrap_err();
+    assert!(
+        format!("{err:#}").contains("web-control.allow-lan"),
+        "{err:#}"
+    );
+
+    let with_remote = format!(
+        "{yaml}web-control:\n  enabled: true\n  bind-address: 0.0.0.0:8080\n  allow-lan: true\n"
+    );
+    let cfg: Configuration = serde_yaml::from_str(&with_remote).unwrap();
+    let share = cfg.validated().unwrap().share_qr;
+    assert_eq!(share.base_url, "http://frame.local:8080");
+    assert_eq!(share.size, 160);
+
+    let bad_url = with_remote.replace("http://frame.local:8080", "frame.local");
+    let cfg: Configuration = serde_yaml::from_str(&bad_url).unwrap();
+    let err = cfg.validated().unwrap_err();
+    assert!(format!("{err:#}").contains("base-url"), "{err:#}");
+}
+
+#[test]
+fn parse_caption_section() {
+    let cfg: Configuration = serde_yaml::from_str("photo-library-path: /photos\n").unwrap();
+    let caption = cfg.validated().unwrap().caption;
+    assert!(!caption.enabled);
+    assert_eq!(caption.active_template(), None);
+
+    let yaml = r#"
+photo-library-path: "/photos"
+caption:
+  enabled: true
+  template: "{description} ({date})"
+  corner: top-right
+  font: Inter
+  size: 36
+"#;
+    let cfg: Configuration = serde_yaml::from_str(yaml).unwrap();
+    let caption = cfg.validated().unwrap().caption;
+    assert_eq!(caption.active_template(), Some("{description} ({date})"));
+    assert_eq!(caption.corner, CaptionCorner::TopRight);
+    assert_eq!(caption.font.as_deref(), Some("Inter"));
+    assert!((caption.size - 36.0).abs() < f32::EPSILON);
+
+    let cfg: Configuration = serde_yaml::from_str(
+        "photo-library-path: /photos\ncaption: { enabled: true, template: \"{date} {camera}\" }\n",
+    )
+    .unwrap();
+    let err = cfg.validated().unwrap_err();
+    assert!(format!("{err:#}").contains("{camera}"), "{err:#}");
+}
+
+#[test]
+fn parse_debug_overlay_section() {
+    let cfg: Configuration = serde_yaml::from_str("photo-library-path: /photos\n").unwrap();
+    assert!(!cfg.debug_overlay.enabled);
+    assert_eq!(cfg.debug_overlay.corner, CaptionCorner::BottomRight);
+
+    let cfg: Configuration = serde_yaml::from_str(
+        "photo-library-path: /photos\ndebug-overlay: { enabled: true, corner: top-left }\n",
+    )
+    .unwrap();
+    assert!(cfg.debug_overlay.enabled);
+    assert_eq!(cfg.debug_overlay.corner, CaptionCorner::TopLeft);
+}
+
+#[test]
+fn default_config_dump_parses_back_to_the_defaults() {
+    let dumped = Configuration::from_yaml_str(Configuration::DEFAULT_YAML)
+        .expect("dump parses")
+        .validated()
+        .expect("dump validates");
+    let minimal = Configuration::from_yaml_str("photo-library-path: /var/lib/photoframe/photos\n")
+        .unwrap()
+        .validated()
+        .unwrap();
+    // Most sections have no `PartialEq`; `Debug` covers every field.
+    assert_eq!(format!("{dumped:#?}"), format!("{minimal:#?}"));
+}
+
+/// Every key path in `value`, dotted, with list items folded into their
+/// list's path.
+fn yaml_key_paths(value: &serde_yaml::Value, prefix: &str, paths: &mut Vec<String>) {
+    match value {
+        serde_yaml::Value::Mapping(map) => {
+            for (key, value) in map {
+                let key = key.as_str().expect("string keys");
+                let path = if prefix.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                yaml_key_paths(value, &path, paths);
+                paths.push(path);
+            }
+        }
+        serde_yaml::Value::Sequence(items) => {
+            for item in items {
+                yaml_key_paths(item, prefix, paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn default_config_dump_lists_every_key() {
+    // Commented-out keys count: uncomment every `# key:` line, keeping its
+    // indentation, and read the dump as if they were all set. A `{}` block
+    // lists its keys commented out below it, so it is opened up too.
+    let uncommented: String = Configuration::DEFAULT_YAML
+        .lines()
+        .map(|line| {
+            let value = line.split(" #").next().unwrap().trim_end();
+            let line = match value.strip_suffix(" {}") {
+                Some(key) => key.to_string(),
+                None => line.to_string(),
+            };
+            let indent = line.len() - line.trim_start().len();
+            let Some(rest) = line.trim_start().strip_prefix("# ") else {
+                return format!("{line}\n");
+            };
+            let key = rest.trim_start().split_once(':').map(|(key, _)| key);
+            let is_key = key.is_some_and(|key| {
+                key.starts_with(|c: char| c.is_ascii_lowercase())
+                    && key.chars().all(|c| c.is_ascii_lowercase() || c == '-')
+            });
+            if is_key {
+                format!("{}{rest}\n", " ".repeat(indent))
+            } else {
+                format!("{line}\n")
+            }
+        })
+        .collect();
+    let dump: serde_yaml::Value =
+        serde_yaml::from_str(&uncommented).expect("uncommented dump parses");
+    let mut listed = Vec::new();
+    yaml_key_paths(&dump, "", &mut listed);
+
+    let defaults = serde_yaml::to_value(Configuration::default()).unwrap();
+    let mut expected = Vec::new();
+    yaml_key_paths(&defaults, "", &mut expected);
+    let missing: Vec<&String> = expected
+        .iter()
+        .filter(|path| !listed.contains(path))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "keys missing from config/defaults.yaml: {missing:?}"
+    );
+}
+
+#[test]
+fn misspelled_keys_report_their_path_and_a_suggestion() {
+    let yaml = r#"
+photo-library-path: "/photos"
+matting:
+  selection: fixed
+  active:
+    - kind: studio
+      bevle-width-px: 4.0
+"#;
+    let message = Configuration::from_yaml_str(yaml).unwrap_err().to_string();
+    assert!(
+        message.contains("matting.active[0].bevle-width-px"),
+        "{message}"
+    );
+    assert!(
+        message.contains("did you mean 'bevel-width-px'?"),
+        "{message}"
+    );
+
+    let message =
+        Configuration::from_yaml_str("photo-library-path: /photos\nhistory:\n  max-entires: 5\n")
+            .unwrap_err()
+            .to_string();
+    assert!(
+        message.starts_with(
+            "history.max-entires: unknown field `max-entires`; did you mean 'max-entries'?"
+        ),
+        "{message}"
+    );
+
+    let yaml = r#"
+photo-library-path: "/photos"
+matting:
+  selection: fixed
+  active:
+    - kind: studo
+"#;
+    let message = Configuration::from_yaml_str(yaml).unwrap_err().to_string();
+    assert!(message.contains("did you mean 'studio'?"), "{message}");
+}
+
+#[test]
+fn unrelated_unknown_keys_list_the_accepted_ones() {
+    let message =
+        Configuration::from_yaml_str("photo-library-path: /photos\nhistory:\n  retention: 5\n")
+            .unwrap_err()
+            .to_string();
+    assert!(!message.contains("did you mean"), "{message}");
+    assert!(
+        message.contains(
+            "history.retention: unknown field `retention`; expected one of: file, max-entries"
+        ),
+        "{message}"
+    );
+}
+
+#[test]
+fn animations_default_on_and_reject_a_zero_loop_count() {
+    let cfg = Configuration::from_yaml_str("photo-library-path: /photos\n")
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert!(cfg.animations.enabled);
+    assert_eq!(cfg.animations.loop_count, None);
+
+    let yaml = "photo-library-path: /photos\nanimations:\n  loop-count: 2\n  max-frames: 120\n";
+    let cfg = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert_eq!(cfg.animations.loop_count, Some(2));
+    assert_eq!(cfg.animations.max_frames, 120);
+
+    let yaml = "photo-library-path: /photos\nanimations:\n  loop-count: 0\n";
+    let err = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("loop-count"), "{err:#}");
+}
+
+#[test]
+fn ambient_screen_defaults_to_a_dim_card_and_rejects_zero_brightness() {
+    let cfg = Configuration::from_yaml_str("photo-library-path: /photos\n")
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert_eq!(cfg.ambient_screen.brightness, 0.2);
+    assert_eq!(cfg.ambient_screen.photo, None);
+
+    let yaml = "photo-library-path: /photos\nambient-screen:\n  brightness: 0.35\n  photo: /photos/night.jpg\n";
+    let cfg = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert_eq!(cfg.ambient_screen.brightness, 0.35);
+    assert_eq!(
+        cfg.ambient_screen.photo.as_deref(),
+        Some(std::path::Path::new("/photos/night.jpg"))
+    );
+
+    let yaml = "photo-library-path: /photos\nambient-screen:\n  brightness: 0.0\n";
+    let err = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("brightness"), "{err:#}");
+}
+
+#[test]
+fn logging_block_is_accepted_and_bad_module_levels_fail_validation() {
+    let yaml = "photo-library-path: /photos\nlogging:\n  format: json\n  modules:\n    photoframe::tasks::viewer: debug\n";
+    let cfg = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert_eq!(cfg.logging.format, LogFormat::Json);
+    assert_eq!(
+        cfg.logging.directives(),
+        "info,photoframe::tasks::viewer=debug"
+    );
+
+    let yaml = "photo-library-path: /photos\nlogging:\n  modules:\n    photoframe: noisy\n";
+    let err = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("logging"), "{err:#}");
+}
+
+#[test]
+fn playlist_quarantine_defaults_and_rejects_a_zero_failure_limit() {
+    let cfg = Configuration::from_yaml_str("photo-library-path: /photos\n")
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert_eq!(cfg.playlist.quarantine.max_failures, 3);
+    assert_eq!(
+        cfg.playlist.quarantine.cooldown,
+        std::time::Duration::from_secs(24 * 60 * 60)
+    );
+
+    let yaml = "photo-library-path: /photos\nplaylist:\n  quarantine:\n    max-failures: 5\n    cooldown: 2h\n";
+    let cfg = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert_eq!(cfg.playlist.quarantine.max_failures, 5);
+    assert_eq!(
+        cfg.playlist.quarantine.cooldown,
+        std::time::Duration::from_secs(2 * 60 * 60)
+    );
+
+    let yaml = "photo-library-path: /photos\nplaylist:\n  quarantine:\n    max-failures: 0\n";
+    let err = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("playlist.quarantine.max-failures"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn frame_pacing_caps_the_redraw_rate_and_rejects_unknown_present_modes() {
+    let yaml = "photo-library-path: /photos\nframe-pacing:\n  max-fps: 30\n  present-mode: fifo\n";
+    let cfg = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert_eq!(cfg.frame_pacing.present_mode, PresentMode::Fifo);
+    assert_eq!(
+        cfg.frame_pacing.min_frame_interval(),
+        std::time::Duration::from_secs(1) / 30
+    );
+    assert_eq!(
+        Configuration::default().frame_pacing.min_frame_interval(),
+        std::time::Duration::ZERO
+    );
+
+    let yaml = "photo-library-path: /photos\nframe-pacing:\n  present-mode: vsync\n";
+    let err = Configuration::from_yaml_str(yaml)
+        .and_then(Configuration::validated)
+        .unwrap_err();
+    let msg = format!("{err:#}");
+    assert!(msg.contains("present-mode"), "{msg}");
+    assert!(msg.contains("mailbox"), "{msg}");
+
+    let yaml = "photo-library-path: /photos\nframe-pacing:\n  max-fps: 0\n";
+    let err = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("frame-pacing.max-fps"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn color_management_is_on_by_default_and_targets_srgb() {
+    let cfg = Configuration::from_yaml_str("photo-library-path: /photos\n")
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert!(cfg.color.enabled);
+    assert_eq!(cfg.color.target_space, ColorTargetSpace::Srgb);
+
+    let yaml =
+        "photo-library-path: /photos\ncolor:\n  enabled: false\n  target-space: display-p3\n";
+    let cfg = Configuration::from_yaml_str(yaml).unwrap();
+    assert!(!cfg.color.enabled);
+    assert_eq!(cfg.color.target_space, ColorTargetSpace::DisplayP3);
+
+    let yaml = "photo-library-path: /photos\ncolor:\n  target-space: adobe-rgb\n";
+    assert!(Configuration::from_yaml_str(yaml).is_err());
+}
+
+#[test]
+fn playlist_no_repeat_window_defaults_to_back_to_back_only() {
+    let cfg = Configuration::from_yaml_str("photo-library-path: /photos\n").unwrap();
+    assert_eq!(cfg.playlist.no_repeat_window, 1);
+
+    let yaml = "photo-library-path: /photos\nplaylist:\n  no-repeat-window: 25\n";
+    let cfg = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert_eq!(cfg.playlist.no_repeat_window, 25);
+
+    let yaml = "photo-library-path: /photos\nplaylist:\n  no-repeat-window: 0\n";
+    let err = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap_err();
+    assert!(err.to_string().contains("no-repeat-window"), "{err}");
+}
+
+#[test]
+fn playlist_dedup_is_off_by_default() {
+    let cfg = Configuration::from_yaml_str("photo-library-path: /photos\n").unwrap();
+    assert_eq!(cfg.playlist.dedup.mode, DedupMode::Off);
+    assert_eq!(cfg.playlist.dedup.window, 10);
+
+    let yaml =
+        "photo-library-path: /photos\nplaylist:\n  dedup:\n    mode: spread\n    window: 20\n";
+    let cfg = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap();
+    assert_eq!(cfg.playlist.dedup.mode, DedupMode::Spread);
+    assert_eq!(cfg.playlist.dedup.window, 20);
+
+    let yaml = "photo-library-path: /photos\nplaylist:\n  dedup:\n    mode: collapse\n";
+    let cfg = Configuration::from_yaml_str(yaml).unwrap();
+    assert_eq!(cfg.playlist.dedup.mode, DedupMode::Collapse);
+
+    let yaml =
+        "photo-library-path: /photos\nplaylist:\n  dedup:\n    mode: spread\n    window: 65\n";
+    let err = Configuration::from_yaml_str(yaml)
+        .unwrap()
+        .validated()
+        .unwrap_err();
+    assert!(err.to_string().contains("dedup.window"), "{err}");
+
+    let yaml = "photo-library-path: /photos\nplaylist:\n  dedup:\n    mode: merge\n";
+    assert!(Configuration::from_yaml_str(yaml).is_err());
+}
+
+#[test]
+fn config_file_paths_expand_tilde_and_variables() {
+    let home = PathBuf::from(std::env::var("HOME").expect("HOME is set"));
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("config.yaml");
+    let yaml = r#"
+photo-library-path: ["~/Pictures", "${HOME}/more", /srv/photos]
+control-socket-path: ~/photoframe.sock
+matting:
+  active:
+    - kind: fixed-image
+      path: ~/mats/linen.png
+"#;
+    std::fs::write(&file, yaml).unwrap();
+
+    let cfg = Configuration::from_yaml_file(&file).unwrap();
+    assert_eq!(
+        cfg.library_paths(),
+        [
+            home.join("Pictures"),
+            home.join("more"),
+            PathBuf::from("/srv/photos")
+        ]
+    );
+    assert_eq!(cfg.control_socket_path, home.join("photoframe.sock"));
+    match &cfg.matting.primary_selected().unwrap().option.style {
+        MattingMode::FixedImage { paths, .. } => {
+            assert_eq!(paths, &[home.join("mats/linen.png")]);
+        }
+        other => panic!("expected fixed-image matting, got {other:?}"),
+    }
+
+    // Parsing a string leaves paths as written.
+    let cfg = Configuration::from_yaml_str(yaml).unwrap();
+    assert_eq!(cfg.photo_library_paths[0].path, PathBuf::from("~/Pictures"));
+}
+
+#[test]
+fn config_file_with_an_unset_variable_is_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("config.yaml");
+    std::fs::write(
+        &file,
+        "photo-library-path: ${PHOTOFRAME_TEST_UNSET_LIBRARY}/photos\n",
+    )
+    .unwrap();
+
+    let err = Configuration::from_yaml_file(&file).unwrap_err();
+    let message = format!("{err:#}");
+    assert!(message.contains("photo-library-path"), "{message}");
+    assert!(
+        message.contains("`PHOTOFRAME_TEST_UNSET_LIBRARY`") && message.contains("not set"),
+        "{message}"
+    );
+
+    std::fs::write(&file, "photo-library-path: /photos/${HOME\n").unwrap();
+    let err = Configuration::from_yaml_file(&file).unwrap_err();
+    assert!(format!("{err:#}").contains("closing"), "{err:#}");
+}
+
+#[test]
+fn included_config_files_override_the_base() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("conf.d")).unwrap();
+    std::fs::write(
+        dir.path().join("config.yaml"),
+        "include:\n  - conf.d/greeting.yaml\n  - conf.d/later.yaml\nphoto-library-path: /photos\n\
+         greeting-screen:\n  message: \"Hello\"\n  duration-seconds: 5\n  colors:\n    background: \"#000000\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("conf.d/greeting.yaml"),
+        "greeting-screen:\n  message: \"Welcome home\"\n  colors:\n    font: \"#ffffff\"\n\
+         viewer-preload-count: 5\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("conf.d/later.yaml"),
+        "viewer-preload-count: 7\n",
+    )
+    .unwrap();
+
+    let cfg = Configuration::from_yaml_file(dir.path().join("config.yaml")).unwrap();
+    let greeting = &cfg.greeting_screen;
+    assert_eq!(greeting.screen.message.as_deref(), Some("Welcome home"));
+    assert_eq!(greeting.duration_seconds, Some(5.0));
+    assert!(greeting.screen.colors.background.is_some());
+    assert!(greeting.screen.colors.font.is_some());
+    assert_eq!(cfg.viewer_preload_count, 7);
+    assert_eq!(cfg.library_paths(), vec![PathBuf::from("/photos")]);
+}
+
+#[test]
+fn config_files_that_include_each_other_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("config.yaml"),
+        "include: a.yaml\nphoto-library-path: /photos\n",
+    )
+    .unwrap();
+    std::fs::write(dir.path().join("a.yaml"), "include: [b.yaml]\n").unwrap();
+    std::fs::write(dir.path().join("b.yaml"), "include: a.yaml\n").unwrap();
+
+    let err = Configuration::from_yaml_file(dir.path().join("config.yaml")).unwrap_err();
+    let message = format!("{err:#}");
+    assert!(message.contains("include each other"), "{message}");
+    assert!(message.contains("a.yaml -> "), "{message}");
+}
+
+#[test]
+fn library_index_is_off_by_default() {
+    let cfg = Configuration::from_yaml_str("photo-library-path: /photos\n").unwrap();
+    assert!(!cfg.library_index.enabled);
+
+    let yaml = "photo-library-path: /photos\nlibrary-index:\n  enabled: true\n";
+    assert!(
+        Configuration::from_yaml_str(yaml)
+            .unwrap()
+            .library_index
+            .enabled
+    );
+
+    let yaml = "photo-library-path: /photos\nlibrary-index:\n  path: /var/index\n";
+    assert!(Configuration::from_yaml_str(yaml).is_err());
+}
+
+#[test]
+fn configuration_writes_back_the_yaml_it_reads() {
+    let yaml = r#"
+photo-library-path:
+  - /photos
+  - path: /mnt/nas
+    weight: 2
+transition:
+  selection: sequential
+  active:
+    - kind: fade
+      through-black: true
+    - kind: wipe
+      angles: [0, 90]
+      angle-jitter: 5
+    - kind: push
+      direction: random
+    - kind: e-ink
+    - kind: dissolve
+    - kind: radial-wipe
+      shapes: [circle, diamond]
+    - kind: venetian-blinds
+      orientations: [vertical]
+    - kind: crossfade-zoom
+    - kind: crossfade-blur
+    - kind: iris
+      direction: open
+matting:
+  active:
+    - kind: fixed-color
+      colors: [[0, 0, 0], [255, 255, 255]]
+    - kind: studio
+      colors: [photo-average, [10, 20, 30]]
+    - kind: fixed-image
+      path: [/mats/a.png, /mats/b.png]
+    - kind: blur
+    - kind: gradient
+    - kind: vignette
+    - kind: cinematic-blur
+    - kind: passe-partout
+    - kind: drop-shadow
+  fill-when-fits: {}
+photo-effect:
+  selection: chain
+  active:
+    - kind: tone
+      mode: duotone
+    - kind: film-grain
+    - kind: print-simulation
+display:
+  rotation: 90
+motion:
+  type: ken-burns
+mqtt:
+  username: frame
+  password: hunter2
+awake-schedule:
+  timezone: Europe/Paris
+  awake-scheduled:
+    daily:
+      - ["07:00", "22:30:15"]
+playlist:
+  half-life: 12h
+"#;
+    let cfg = Configuration::from_yaml_str(yaml).unwrap();
+
+    let written = serde_yaml::to_string(&cfg).unwrap();
+    let reread = Configuration::from_yaml_str(&written)
+        .unwrap_or_else(|err| panic!("{err:#} in\n{written}"));
+
+    assert_eq!(serde_yaml::to_string(&reread).unwrap(), written);
+    assert_eq!(reread.matting.options().len(), cfg.matting.options().len());
+    assert_eq!(
+        reread.transition.options().len(),
+        cfg.transition.options().len()
+    );
+    assert!(written.contains("password: <redacted>"), "{written}");
+    assert!(!written.contains("hunter2"), "{written}");
+}
@@ -0,0 +1,95 @@
+//! The output rotation pass samples the upright frame through
+//! `DisplayRotation::uv_transform`. Running the same lookup on the CPU at each
+//! panel pixel's center must reproduce the picture the `image` crate rotates,
+//! for every allowed rotation.
+
+use image::{Rgba, RgbaImage, imageops};
+use photoframe::config::{Configuration, DisplayRotation};
+
+/// Every pixel distinct, so a wrong lookup can't land on a matching color.
+fn upright(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        Rgba([(x * 40) as u8, (y * 40) as u8, (x * 7 + y) as u8, 255])
+    })
+}
+
+/// What the rotation pass draws onto a panel of `panel` size: each fragment
+/// maps its UV into the upright frame and takes the nearest texel there.
+fn draw_rotated(frame: &RgbaImage, rotation: DisplayRotation, panel: (u32, u32)) -> RgbaImage {
+    let [row_u, row_v] = rotation.uv_transform();
+    RgbaImage::from_fn(panel.0, panel.1, |x, y| {
+        let u = (x as f32 + 0.5) / panel.0 as f32;
+        let v = (y as f32 + 0.5) / panel.1 as f32;
+        let su = row_u[0] * u + row_u[1] * v + row_u[2];
+        let sv = row_v[0] * u + row_v[1] * v + row_v[2];
+        let sx = ((su * frame.width() as f32) as u32).min(frame.width() - 1);
+        let sy = ((sv * frame.height() as f32) as u32).min(frame.height() - 1);
+        *frame.get_pixel(sx, sy)
+    })
+}
+
+#[test]
+fn rotated_output_matches_the_rotated_picture() {
+    // A 6x4 landscape panel; the frame lays out for the rotated size.
+    let panel = (6, 4);
+    let cases = [
+        (0, DisplayRotation::None),
+        (90, DisplayRotation::Clockwise90),
+        (180, DisplayRotation::Clockwise180),
+        (270, DisplayRotation::Clockwise270),
+    ];
+    for (degrees, rotation) in cases {
+        let (width, height) = rotation.logical_size(panel.0, panel.1);
+        let frame = upright(width, height);
+        let expected = match degrees {
+            0 => frame.clone(),
+            90 => imageops::rotate90(&frame),
+            180 => imageops::rotate180(&frame),
+            _ => imageops::rotate270(&frame),
+        };
+        assert_eq!(expected.dimensions(), panel, "{degrees}°");
+        let drawn = draw_rotated(&frame, rotation, panel);
+        assert!(drawn == expected, "{degrees}° output differs");
+    }
+}
+
+#[test]
+fn quarter_turns_lay_out_for_a_portrait_frame() {
+    assert_eq!(DisplayRotation::None.logical_size(1920, 1080), (1920, 1080));
+    assert_eq!(
+        DisplayRotation::Clockwise90.logical_size(1920, 1080),
+        (1080, 1920)
+    );
+    assert_eq!(
+        DisplayRotation::Clockwise180.logical_size(1920, 1080),
+        (1920, 1080)
+    );
+    assert_eq!(
+        DisplayRotation::Clockwise270.logical_size(1920, 1080),
+        (1080, 1920)
+    );
+}
+
+#[test]
+fn rotation_parses_from_degrees() {
+    let cfg: Configuration = serde_yaml::from_str(
+        r#"
+photo-library-path: "/photos"
+display:
+  rotation: 270
+"#,
+    )
+    .unwrap();
+    assert_eq!(cfg.display.rotation, DisplayRotation::Clockwise270);
+    assert_eq!(cfg.display.rotation.degrees(), 270);
+
+    let err = serde_yaml::from_str::<Configuration>(
+        r#"
+photo-library-path: "/photos"
+display:
+  rotation: 45
+"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("0, 90, 180, or 270"), "{err}");
+}
@@ -1,11 +1,17 @@
-use photoframe::config::{Configuration, GlobalPhotoSettings};
-use photoframe::events::{InvalidPhoto, InventoryEvent};
+use photoframe::config::{
+    Configuration, DedupConfig, DedupMode, GlobalPhotoSettings, LibraryIndexConfig, LibraryRoot,
+    PlaylistFavorites, PlaylistOptions,
+};
+use photoframe::events::{InvalidPhoto, InventoryEvent, ViewerCommand};
+use photoframe::tasks::dedup::Fingerprint;
 use photoframe::tasks::files;
 use rand::{SeedableRng, seq::SliceRandom};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tempfile::tempdir;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_util::sync::CancellationToken;
 use walkdir::WalkDir;
 
@@ -21,7 +27,7 @@ async fn startup_recursive_scan_emits_photo_added() {
     fs::write(lib.join("c.txt"), b"x").unwrap();
 
     let cfg = Configuration {
-        photo_library_path: lib.clone(),
+        photo_library_paths: vec![lib.clone().into()],
         global_photo_settings: GlobalPhotoSettings {
             oversample: 1.0,
             ..Default::default()
@@ -31,9 +37,17 @@ async fn startup_recursive_scan_emits_photo_added() {
 
     let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
     let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (progress_tx, mut progress_rx) = mpsc::channel::<ViewerCommand>(16);
     let cancel = CancellationToken::new();
 
-    let handle = tokio::spawn(files::run(cfg.into(), inv_tx, invalid_rx, cancel.clone()));
+    let handle = tokio::spawn(files::run(
+        cfg.into(),
+        inv_tx,
+        invalid_rx,
+        progress_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
 
     // Collect two PhotoAdded events (for a.jpg, nested/b.jpeg)
     let mut added: Vec<PathBuf> = Vec::new();
@@ -58,12 +72,27 @@ async fn startup_recursive_scan_emits_photo_added() {
         vec!["a.jpg".to_string(), "nested/b.jpeg".to_string()]
     );
 
+    // The greeting hears about the scan, ending with the final count.
+    loop {
+        let progress = tokio::time::timeout(std::time::Duration::from_secs(5), progress_rx.recv())
+            .await
+            .expect("timeout waiting for scan progress");
+        if let Some(ViewerCommand::GreetingProgress {
+            discovered,
+            complete: true,
+        }) = progress
+        {
+            assert_eq!(discovered, 2);
+            break;
+        }
+    }
+
     cancel.cancel();
     let _ = handle.await;
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-async fn invalid_photo_is_kept_and_emits_removed() {
+async fn invalid_photo_is_kept_and_emits_failed() {
     let tmp = tempdir().unwrap();
     let lib = tmp.path().join("lib");
     fs::create_dir_all(&lib).unwrap();
@@ -72,7 +101,7 @@ async fn invalid_photo_is_kept_and_emits_removed() {
     fs::write(&bad, b"x").unwrap();
 
     let cfg = Configuration {
-        photo_library_path: lib.clone(),
+        photo_library_paths: vec![lib.clone().into()],
         global_photo_settings: GlobalPhotoSettings {
             oversample: 1.0,
             ..Default::default()
@@ -82,9 +111,17 @@ async fn invalid_photo_is_kept_and_emits_removed() {
 
     let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
     let (invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (progress_tx, _progress_rx) = mpsc::channel::<ViewerCommand>(16);
     let cancel = CancellationToken::new();
 
-    let handle = tokio::spawn(files::run(cfg.into(), inv_tx, invalid_rx, cancel.clone()));
+    let handle = tokio::spawn(files::run(
+        cfg.into(),
+        inv_tx,
+        invalid_rx,
+        progress_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
 
     // Wait for startup scan to pick up the file
     let mut saw_added = false;
@@ -102,19 +139,19 @@ async fn invalid_photo_is_kept_and_emits_removed() {
     // Send InvalidPhoto (simulate Manager/Loader decision)
     invalid_tx.send(InvalidPhoto(bad.clone())).await.unwrap();
 
-    // Expect at least one PhotoRemoved for the same path
-    let mut saw_removed = false;
+    // Expect a PhotoFailed for the same path, so the manager can count it
+    let mut saw_failed = false;
     let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
     while std::time::Instant::now() < deadline {
-        if let Ok(Some(InventoryEvent::PhotoRemoved(p))) =
+        if let Ok(Some(InventoryEvent::PhotoFailed(p))) =
             tokio::time::timeout(std::time::Duration::from_millis(200), inv_rx.recv()).await
             && p == bad
         {
-            saw_removed = true;
+            saw_failed = true;
             break;
         }
     }
-    assert!(saw_removed, "did not see PhotoRemoved for skipped file");
+    assert!(saw_failed, "did not see PhotoFailed for skipped file");
 
     // The file must NOT be deleted: a decode failure can be transient, so the
     // photo is only dropped from rotation and left on disk for a later retry.
@@ -127,6 +164,572 @@ async fn invalid_photo_is_kept_and_emits_removed() {
     let _ = handle.await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn changes_wait_out_a_library_hold() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    fs::create_dir_all(&lib).unwrap();
+    let bad = lib.join("bad.jpg");
+    fs::write(&bad, b"x").unwrap();
+
+    let cfg = Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        ..Default::default()
+    };
+
+    let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (viewer_tx, _viewer_rx) = mpsc::channel::<ViewerCommand>(16);
+    let (hold_tx, hold_rx) = watch::channel(None);
+    let cancel = CancellationToken::new();
+
+    let handle = tokio::spawn(files::run(
+        cfg.into(),
+        inv_tx,
+        invalid_rx,
+        viewer_tx,
+        cancel.clone(),
+        hold_rx,
+    ));
+
+    let first = tokio::time::timeout(Duration::from_secs(5), inv_rx.recv())
+        .await
+        .expect("timeout waiting for the startup scan");
+    assert!(matches!(first, Some(InventoryEvent::PhotoAdded(_))));
+
+    hold_tx.send_replace(Some("sync".into()));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    invalid_tx.send(InvalidPhoto(bad.clone())).await.unwrap();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), inv_rx.recv())
+            .await
+            .is_err(),
+        "nothing reaches the manager while the library is held"
+    );
+
+    hold_tx.send_replace(None);
+    let released = tokio::time::timeout(Duration::from_secs(5), inv_rx.recv())
+        .await
+        .expect("timeout waiting for the held change");
+    assert!(matches!(released, Some(InventoryEvent::PhotoFailed(path)) if path == bad));
+
+    cancel.cancel();
+    let _ = handle.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn empty_library_is_reported_until_a_photo_arrives() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    fs::create_dir_all(&lib).unwrap();
+    fs::write(lib.join("notes.txt"), b"x").unwrap();
+
+    let cfg = Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        ..Default::default()
+    };
+    assert!(files::discover_startup_photos(&cfg).unwrap().is_empty());
+
+    let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (viewer_tx, mut viewer_rx) = mpsc::channel::<ViewerCommand>(16);
+    let cancel = CancellationToken::new();
+
+    let handle = tokio::spawn(files::run(
+        cfg.into(),
+        inv_tx,
+        invalid_rx,
+        viewer_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
+
+    // An empty startup scan sends the viewer to the empty-library card.
+    loop {
+        let cmd = tokio::time::timeout(std::time::Duration::from_secs(5), viewer_rx.recv())
+            .await
+            .expect("timeout waiting for the empty-library signal");
+        if let Some(ViewerCommand::LibraryEmpty(empty)) = cmd {
+            assert!(empty);
+            break;
+        }
+    }
+
+    // The watcher starts just after the signal, so keep dropping photos in until
+    // one is noticed.
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let mut attempt = 0;
+    let refilled = loop {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "library never reported non-empty"
+        );
+        fs::write(lib.join(format!("new-{attempt}.jpg")), b"x").unwrap();
+        attempt += 1;
+        if let Ok(Some(cmd)) =
+            tokio::time::timeout(std::time::Duration::from_millis(500), viewer_rx.recv()).await
+        {
+            break cmd;
+        }
+    };
+    assert!(matches!(refilled, ViewerCommand::LibraryEmpty(false)));
+    while inv_rx.try_recv().is_ok() {}
+
+    cancel.cancel();
+    let _ = handle.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn photos_added_and_deleted_after_startup_are_reported() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    fs::create_dir_all(lib.join("nested")).unwrap();
+    fs::write(lib.join("a.jpg"), b"x").unwrap();
+
+    let cfg = Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        ..Default::default()
+    };
+
+    let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (viewer_tx, _viewer_rx) = mpsc::channel::<ViewerCommand>(16);
+    let cancel = CancellationToken::new();
+
+    let handle = tokio::spawn(files::run(
+        cfg.into(),
+        inv_tx,
+        invalid_rx,
+        viewer_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
+
+    let first = tokio::time::timeout(std::time::Duration::from_secs(5), inv_rx.recv())
+        .await
+        .expect("timeout waiting for the startup scan");
+    assert!(matches!(first, Some(InventoryEvent::PhotoAdded(_))));
+
+    // The watcher starts just after the scan, so keep dropping photos into the
+    // nested directory until one is noticed.
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    let mut attempt = 0;
+    let added = loop {
+        assert!(
+            std::time::Instant::now() < deadline,
+            "new photo was never reported"
+        );
+        fs::write(lib.join("nested").join(format!("new-{attempt}.jpg")), b"x").unwrap();
+        attempt += 1;
+        if let Ok(Some(InventoryEvent::PhotoAdded(info))) =
+            tokio::time::timeout(std::time::Duration::from_millis(500), inv_rx.recv()).await
+        {
+            break info.path;
+        }
+    };
+    assert!(added.starts_with(lib.join("nested")));
+
+    fs::remove_file(lib.join("a.jpg")).unwrap();
+    let removed = loop {
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), inv_rx.recv())
+            .await
+            .expect("timeout waiting for the removal");
+        if let Some(InventoryEvent::PhotoRemoved(path)) = event {
+            break path;
+        }
+    };
+    assert_eq!(removed, lib.join("a.jpg"));
+
+    cancel.cancel();
+    let _ = handle.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn missing_library_is_waited_for_instead_of_failing() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("not-mounted-yet");
+
+    let cfg = Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        ..Default::default()
+    };
+
+    let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (viewer_tx, mut viewer_rx) = mpsc::channel::<ViewerCommand>(16);
+    let cancel = CancellationToken::new();
+
+    let handle = tokio::spawn(files::run(
+        cfg.into(),
+        inv_tx,
+        invalid_rx,
+        viewer_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
+
+    loop {
+        let cmd = tokio::time::timeout(std::time::Duration::from_secs(5), viewer_rx.recv())
+            .await
+            .expect("timeout waiting for the empty-library signal");
+        if let Some(ViewerCommand::LibraryEmpty(empty)) = cmd {
+            assert!(empty);
+            break;
+        }
+    }
+    assert!(!handle.is_finished(), "files task must keep running");
+
+    // Move the library into place whole so the scan cannot race the write.
+    let staging = tmp.path().join("staging");
+    fs::create_dir_all(&staging).unwrap();
+    fs::write(staging.join("a.jpg"), b"x").unwrap();
+    fs::rename(&staging, &lib).unwrap();
+
+    let added = tokio::time::timeout(std::time::Duration::from_secs(10), inv_rx.recv())
+        .await
+        .expect("timeout waiting for the library to be scanned");
+    match added {
+        Some(InventoryEvent::PhotoAdded(info)) => assert_eq!(info.path, lib.join("a.jpg")),
+        other => panic!("expected PhotoAdded, got {other:?}"),
+    }
+    let refilled = tokio::time::timeout(std::time::Duration::from_secs(5), viewer_rx.recv())
+        .await
+        .expect("timeout waiting for the non-empty signal");
+    assert!(matches!(refilled, Some(ViewerCommand::LibraryEmpty(false))));
+
+    cancel.cancel();
+    let _ = handle.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn optional_root_absent_at_boot_joins_with_its_weight() {
+    let tmp = tempdir().unwrap();
+    let home = tmp.path().join("home");
+    let nas = tmp.path().join("nas");
+    fs::create_dir_all(&home).unwrap();
+    fs::write(home.join("a.jpg"), b"x").unwrap();
+
+    let mut optional = LibraryRoot::new(&nas);
+    optional.optional = true;
+    optional.weight = 3.0;
+    let cfg = Configuration {
+        photo_library_paths: vec![home.clone().into(), optional],
+        ..Default::default()
+    };
+    files::check_required_roots(&cfg).expect("an optional root may be missing");
+    let required = Configuration {
+        photo_library_paths: vec![home.clone().into(), nas.clone().into()],
+        ..Default::default()
+    };
+    let err = files::check_required_roots(&required).unwrap_err();
+    assert!(err.to_string().contains("optional: true"), "{err}");
+
+    let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (viewer_tx, _viewer_rx) = mpsc::channel::<ViewerCommand>(16);
+    let cancel = CancellationToken::new();
+
+    let handle = tokio::spawn(files::run(
+        cfg.into(),
+        inv_tx,
+        invalid_rx,
+        viewer_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
+
+    let mut next_added = async || loop {
+        let event = tokio::time::timeout(Duration::from_secs(10), inv_rx.recv())
+            .await
+            .expect("timeout waiting for PhotoAdded");
+        if let Some(InventoryEvent::PhotoAdded(info)) = event {
+            return info;
+        }
+    };
+    let first = next_added().await;
+    assert_eq!(first.path, home.join("a.jpg"));
+    assert_eq!(first.root_weight, 1.0);
+
+    // Move the share into place whole so the scan cannot race the write.
+    let staging = tmp.path().join("staging");
+    fs::create_dir_all(&staging).unwrap();
+    fs::write(staging.join("b.jpg"), b"x").unwrap();
+    fs::rename(&staging, &nas).unwrap();
+
+    let late = next_added().await;
+    assert_eq!(late.path, nas.join("b.jpg"));
+    assert_eq!(late.root_weight, 3.0);
+
+    cancel.cancel();
+    let _ = handle.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn favorites_file_edits_are_sent_to_the_manager() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    fs::create_dir_all(&lib).unwrap();
+    fs::write(lib.join("a.jpg"), b"x").unwrap();
+    let state = tmp.path().join("state");
+    fs::create_dir_all(&state).unwrap();
+    let file = state.join("favorites.txt");
+
+    let mut cfg = Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        ..Default::default()
+    };
+    cfg.playlist.favorites = Some(PlaylistFavorites {
+        file: file.clone(),
+        factor: 2.0,
+    });
+
+    let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (viewer_tx, _viewer_rx) = mpsc::channel::<ViewerCommand>(16);
+    let cancel = CancellationToken::new();
+
+    let handle = tokio::spawn(files::run(
+        cfg.into(),
+        inv_tx,
+        invalid_rx,
+        viewer_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
+
+    let first = tokio::time::timeout(std::time::Duration::from_secs(5), inv_rx.recv())
+        .await
+        .expect("timeout waiting for the startup scan");
+    assert!(matches!(first, Some(InventoryEvent::PhotoAdded(_))));
+    // Give the watchers a moment to start after the scan.
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let mut next_favorites = async || loop {
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), inv_rx.recv())
+            .await
+            .expect("timeout waiting for the favorites update");
+        if let Some(InventoryEvent::FavoritesChanged(list)) = event {
+            return list;
+        }
+    };
+
+    fs::write(&file, "a.jpg\n").unwrap();
+    assert_eq!(next_favorites().await, vec![PathBuf::from("a.jpg")]);
+
+    fs::remove_file(&file).unwrap();
+    assert!(next_favorites().await.is_empty());
+
+    cancel.cancel();
+    let _ = handle.await;
+}
+
+#[test]
+fn frameignore_patterns_exclude_matching_photos() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    fs::create_dir_all(lib.join("Screenshots")).unwrap();
+    fs::create_dir_all(lib.join("keep")).unwrap();
+    for name in [
+        "a.jpg",
+        "b.png",
+        "Screenshots/s1.jpg",
+        "keep/c.jpg",
+        "keep/d.png",
+    ] {
+        fs::write(lib.join(name), b"x").unwrap();
+    }
+    fs::write(
+        lib.join(files::IGNORE_FILE_NAME),
+        "# hide screenshots and PNGs\nScreenshots/\n*.png\n!keep/d.png\n",
+    )
+    .unwrap();
+
+    let cfg = Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        ..Default::default()
+    };
+    let mut names: Vec<String> = files::discover_startup_photos(&cfg)
+        .unwrap()
+        .into_iter()
+        .map(|info| {
+            let rel = info.path.strip_prefix(&lib).unwrap();
+            rel.to_string_lossy().to_string()
+        })
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["a.jpg", "keep/c.jpg", "keep/d.png"]);
+}
+
+#[test]
+fn nested_ignore_files_override_shallower_ones() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    for dir in ["#recycle", "albums/deep", "old"] {
+        fs::create_dir_all(lib.join(dir)).unwrap();
+    }
+    for name in [
+        "top.jpg",
+        "top.png",
+        "#recycle/gone.jpg",
+        "albums/a.jpg",
+        "albums/b.png",
+        "albums/c.jpg",
+        "albums/deep/d.png",
+        "albums/deep/e.png",
+        "old/keep.jpg",
+        "old/skip.jpg",
+    ] {
+        fs::write(lib.join(name), b"x").unwrap();
+    }
+    let ignore = |dir: &str, name: &str, patterns: &str| {
+        fs::write(lib.join(dir).join(name), patterns).unwrap();
+    };
+    ignore("", files::IGNORE_FILE_NAME, "*.png\n\\#recycle/\n");
+    // The unparsable pattern is skipped; the lines around it still apply.
+    ignore("albums", files::IGNORE_FILE_NAME, "!b.png\nbad[\nc.jpg\n");
+    ignore("albums/deep", files::IGNORE_FILE_NAME, "!d.png\n");
+    ignore("old", files::LEGACY_IGNORE_FILE_NAME, "skip.jpg\n");
+
+    let cfg = Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        ..Default::default()
+    };
+    let mut names: Vec<String> = files::discover_startup_photos(&cfg)
+        .unwrap()
+        .into_iter()
+        .map(|info| {
+            let rel = info.path.strip_prefix(&lib).unwrap();
+            rel.to_string_lossy().to_string()
+        })
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "albums/a.jpg",
+            "albums/b.png",
+            "albums/deep/d.png",
+            "old/keep.jpg",
+            "top.jpg",
+        ]
+    );
+}
+
+#[test]
+fn multiple_library_roots_are_merged() {
+    let tmp = tempdir().unwrap();
+    let family = tmp.path().join("family");
+    let nas = tmp.path().join("nas");
+    fs::create_dir_all(family.join("2024")).unwrap();
+    fs::create_dir_all(&nas).unwrap();
+    fs::write(family.join("a.jpg"), b"x").unwrap();
+    fs::write(family.join("2024/b.png"), b"x").unwrap();
+    fs::write(nas.join("c.jpg"), b"x").unwrap();
+
+    let cfg = Configuration {
+        photo_library_paths: vec![family.clone().into(), nas.clone().into()],
+        ..Default::default()
+    };
+    let mut names: Vec<String> = files::discover_startup_photos(&cfg)
+        .unwrap()
+        .into_iter()
+        .map(|info| {
+            let rel = info.path.strip_prefix(tmp.path()).unwrap();
+            rel.to_string_lossy().to_string()
+        })
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec!["family/2024/b.png", "family/a.jpg", "nas/c.jpg"]
+    );
+}
+
+#[test]
+fn photos_under_overlapping_roots_are_listed_once() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    fs::create_dir_all(lib.join("albums")).unwrap();
+    fs::write(lib.join("a.jpg"), b"x").unwrap();
+    fs::write(lib.join("albums/b.jpg"), b"x").unwrap();
+    // A second root nested in the first, and a third reaching it via a symlink.
+    let link = tmp.path().join("link");
+    std::os::unix::fs::symlink(lib.join("albums"), &link).unwrap();
+
+    let cfg = Configuration {
+        photo_library_paths: vec![lib.clone().into(), lib.join("albums").into(), link.into()],
+        ..Default::default()
+    };
+    let mut names: Vec<String> = files::discover_startup_photos(&cfg)
+        .unwrap()
+        .into_iter()
+        .map(|info| {
+            let rel = info.path.strip_prefix(&lib).unwrap();
+            rel.to_string_lossy().to_string()
+        })
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["a.jpg", "albums/b.jpg"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn editing_frameignore_hides_and_restores_photos() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    fs::create_dir_all(&lib).unwrap();
+    fs::write(lib.join("a.jpg"), b"x").unwrap();
+    fs::write(lib.join("b.jpg"), b"x").unwrap();
+
+    let cfg = Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        ..Default::default()
+    };
+
+    let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (viewer_tx, _viewer_rx) = mpsc::channel::<ViewerCommand>(16);
+    let cancel = CancellationToken::new();
+
+    let handle = tokio::spawn(files::run(
+        cfg.into(),
+        inv_tx,
+        invalid_rx,
+        viewer_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
+
+    for _ in 0..2 {
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), inv_rx.recv())
+            .await
+            .expect("timeout waiting for the startup scan");
+        assert!(matches!(event, Some(InventoryEvent::PhotoAdded(_))));
+    }
+    // Give the watcher a moment to start after the scan.
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    fs::write(lib.join(files::IGNORE_FILE_NAME), "b.jpg\n").unwrap();
+    let hidden = tokio::time::timeout(std::time::Duration::from_secs(5), inv_rx.recv())
+        .await
+        .expect("timeout waiting for the ignored photo to be removed");
+    match hidden {
+        Some(InventoryEvent::PhotoRemoved(path)) => assert_eq!(path, lib.join("b.jpg")),
+        other => panic!("expected PhotoRemoved, got {other:?}"),
+    }
+
+    fs::remove_file(lib.join(files::IGNORE_FILE_NAME)).unwrap();
+    let restored = tokio::time::timeout(std::time::Duration::from_secs(5), inv_rx.recv())
+        .await
+        .expect("timeout waiting for the photo to come back");
+    match restored {
+        Some(InventoryEvent::PhotoAdded(info)) => assert_eq!(info.path, lib.join("b.jpg")),
+        other => panic!("expected PhotoAdded, got {other:?}"),
+    }
+
+    cancel.cancel();
+    let _ = handle.await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn startup_shuffle_is_deterministic_with_seed() {
     let tmp = tempdir().unwrap();
@@ -138,7 +741,7 @@ async fn startup_shuffle_is_deterministic_with_seed() {
     fs::write(lib.join("nested").join("b.jpeg"), b"x").unwrap();
 
     let cfg = Configuration {
-        photo_library_path: lib.clone(),
+        photo_library_paths: vec![lib.clone().into()],
         startup_shuffle_seed: Some(42),
         ..Default::default()
     };
@@ -165,9 +768,17 @@ async fn startup_shuffle_is_deterministic_with_seed() {
 
     let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
     let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (progress_tx, _progress_rx) = mpsc::channel::<ViewerCommand>(16);
     let cancel = CancellationToken::new();
 
-    let handle = tokio::spawn(files::run(cfg.into(), inv_tx, invalid_rx, cancel.clone()));
+    let handle = tokio::spawn(files::run(
+        cfg.into(),
+        inv_tx,
+        invalid_rx,
+        progress_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
 
     let mut actual: Vec<PathBuf> = Vec::new();
     while actual.len() < 2 {
@@ -185,3 +796,208 @@ async fn startup_shuffle_is_deterministic_with_seed() {
     cancel.cancel();
     let _ = handle.await;
 }
+
+/// HEIC/HEIF files are listed with or without the `heif` feature; without it
+/// the loader reports them invalid, naming the feature.
+#[test]
+fn heic_is_scanned_with_or_without_heif_feature() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    fs::create_dir_all(&lib).unwrap();
+    fs::write(lib.join("a.jpg"), b"x").unwrap();
+    fs::write(lib.join("IMG_0001.HEIC"), b"x").unwrap();
+    fs::write(lib.join("b.heif"), b"x").unwrap();
+
+    let cfg = Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        ..Default::default()
+    };
+
+    let mut names: Vec<String> = files::discover_startup_photos(&cfg)
+        .unwrap()
+        .into_iter()
+        .map(|info| info.path.file_name().unwrap().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["IMG_0001.HEIC", "a.jpg", "b.heif"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn library_index_supplies_capture_dates_from_the_second_start() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    fs::create_dir_all(&lib).unwrap();
+    let dated = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/exif/dated.png");
+    fs::copy(&dated, lib.join("dated.png")).unwrap();
+    let taken = UNIX_EPOCH + Duration::from_secs(1_556_964_000);
+
+    let cfg = Arc::new(Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        library_index: LibraryIndexConfig { enabled: true },
+        ..Default::default()
+    });
+
+    // First start: the file time, then the capture date once read.
+    let first = collect_photo_added(cfg.clone(), 2).await;
+    assert_ne!(first[0], taken);
+    assert_eq!(first[1], taken);
+    // Written by the background pass after it sent the capture date.
+    let index = lib.join(files::INDEX_FILE_NAME);
+    for _ in 0..50 {
+        if index.exists() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert!(index.exists(), "index written");
+
+    // Second start: straight from the index.
+    let second = collect_photo_added(cfg, 1).await;
+    assert_eq!(second, [taken]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn dedup_fingerprints_photos_and_keeps_them_in_the_index() {
+    let tmp = tempdir().unwrap();
+    let lib = tmp.path().join("lib");
+    fs::create_dir_all(&lib).unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/dedup");
+    fs::copy(fixtures.join("scene.png"), lib.join("scene.png")).unwrap();
+    fs::copy(fixtures.join("pattern.png"), lib.join("pattern.png")).unwrap();
+
+    let cfg = Arc::new(Configuration {
+        photo_library_paths: vec![lib.clone().into()],
+        library_index: LibraryIndexConfig { enabled: true },
+        playlist: PlaylistOptions {
+            dedup: DedupConfig {
+                mode: DedupMode::Collapse,
+                ..DedupConfig::default()
+            },
+            ..PlaylistOptions::default()
+        },
+        ..Default::default()
+    });
+
+    let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (progress_tx, _progress_rx) = mpsc::channel::<ViewerCommand>(16);
+    let cancel = CancellationToken::new();
+    let handle = tokio::spawn(files::run(
+        cfg.clone(),
+        inv_tx,
+        invalid_rx,
+        progress_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
+    let mut fingerprinted = collect_fingerprints(&mut inv_rx, 2).await;
+    // Found after startup, it is fingerprinted as it arrives.
+    fs::copy(fixtures.join("scene.png"), lib.join("copy.png")).unwrap();
+    fingerprinted.extend(collect_fingerprints(&mut inv_rx, 1).await);
+    cancel.cancel();
+    let _ = handle.await;
+
+    fingerprinted.sort_by(|a, b| a.0.cmp(&b.0));
+    let names: Vec<_> = fingerprinted
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(names, ["copy.png", "pattern.png", "scene.png"]);
+    assert_eq!(fingerprinted[0].1, fingerprinted[2].1);
+    assert_ne!(fingerprinted[1].1, fingerprinted[2].1);
+
+    // Written by the background pass after the startup photos; the next
+    // start takes their fingerprints from it.
+    let index = lib.join(files::INDEX_FILE_NAME);
+    let fingerprints_kept = || {
+        fs::read_to_string(&index)
+            .unwrap_or_default()
+            .matches("\"fingerprint\"")
+            .count()
+    };
+    for _ in 0..50 {
+        if fingerprints_kept() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(fingerprints_kept(), 2);
+    let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (progress_tx, _progress_rx) = mpsc::channel::<ViewerCommand>(16);
+    let cancel = CancellationToken::new();
+    let handle = tokio::spawn(files::run(
+        cfg,
+        inv_tx,
+        invalid_rx,
+        progress_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
+    let mut again = collect_fingerprints(&mut inv_rx, 3).await;
+    again.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(again, fingerprinted);
+    cancel.cancel();
+    let _ = handle.await;
+}
+
+/// Waits for `count` `PhotoFingerprinted` events, by file name.
+async fn collect_fingerprints(
+    inv_rx: &mut mpsc::Receiver<InventoryEvent>,
+    count: usize,
+) -> Vec<(String, Fingerprint)> {
+    let mut fingerprints = Vec::new();
+    while fingerprints.len() < count {
+        if let Some(InventoryEvent::PhotoFingerprinted(path, fingerprint)) =
+            tokio::time::timeout(Duration::from_secs(5), inv_rx.recv())
+                .await
+                .expect("timeout waiting for a fingerprint")
+        {
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            fingerprints.push((name, fingerprint));
+        }
+    }
+    fingerprints
+}
+
+/// Runs the files task until it has sent `count` `PhotoAdded` events and then
+/// gone quiet, and returns their `created_at`.
+async fn collect_photo_added(cfg: Arc<Configuration>, count: usize) -> Vec<SystemTime> {
+    let (inv_tx, mut inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_invalid_tx, invalid_rx) = mpsc::channel::<InvalidPhoto>(16);
+    let (progress_tx, _progress_rx) = mpsc::channel::<ViewerCommand>(16);
+    let cancel = CancellationToken::new();
+    let handle = tokio::spawn(files::run(
+        cfg,
+        inv_tx,
+        invalid_rx,
+        progress_tx,
+        cancel.clone(),
+        no_library_hold(),
+    ));
+
+    let mut created = Vec::new();
+    while created.len() < count {
+        if let Some(InventoryEvent::PhotoAdded(info)) =
+            tokio::time::timeout(Duration::from_secs(5), inv_rx.recv())
+                .await
+                .expect("timeout waiting for inventory event")
+        {
+            created.push(info.created_at);
+        }
+    }
+    assert!(
+        tokio::time::timeout(Duration::from_millis(300), inv_rx.recv())
+            .await
+            .is_err(),
+        "no further events"
+    );
+    cancel.cancel();
+    let _ = handle.await;
+    created
+}
+
+/// A hold that never engages, for tests that don't pause the library.
+fn no_library_hold() -> watch::Receiver<Option<String>> {
+    watch::channel(None).1
+}
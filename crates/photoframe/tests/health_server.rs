@@ -0,0 +1,55 @@
+use std::sync::atomic::AtomicUsize;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use photoframe::events::ViewerStatus;
+use photoframe::health::{self, HealthStatus};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::sync::CancellationToken;
+
+async fn get(address: std::net::SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(address).await.expect("connect");
+    let request = format!("GET {path} HTTP/1.1\r\nHost: frame\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).await.expect("send");
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply).await.expect("read");
+    reply
+}
+
+#[tokio::test]
+async fn healthz_and_status_answer_over_http() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let viewer = Arc::new(Mutex::new(ViewerStatus {
+        queue_depth: 2,
+        current_photo: Some("/photos/local/beach.jpg".into()),
+        ..ViewerStatus::default()
+    }));
+    let status = HealthStatus {
+        viewer,
+        library_size: Arc::new(AtomicUsize::new(42)),
+        started_at: Instant::now(),
+    };
+    let cancel = CancellationToken::new();
+    let server = tokio::spawn(health::serve(listener, cancel.clone(), status));
+
+    let reply = get(address, "/healthz").await;
+    assert!(reply.starts_with("HTTP/1.1 200"), "{reply}");
+    assert!(reply.ends_with(r#"{"status":"ok"}"#), "{reply}");
+
+    let reply = get(address, "/status").await;
+    assert!(reply.starts_with("HTTP/1.1 200"), "{reply}");
+    let body = reply.split("\r\n\r\n").nth(1).expect("body");
+    let json: serde_json::Value = serde_json::from_str(body).expect("json body");
+    assert_eq!(json["state"], "awake");
+    assert_eq!(json["photo_count"], 42);
+    assert_eq!(json["queue_depth"], 2);
+    assert!(
+        json.get("current_photo").is_none(),
+        "paths stay off the network"
+    );
+
+    cancel.cancel();
+    server.await.unwrap().expect("clean shutdown");
+}
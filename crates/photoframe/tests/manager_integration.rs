@@ -1,10 +1,18 @@
-use photoframe::config::PlaylistOptions;
+use photoframe::config::{
+    DedupConfig, DedupMode, HistoryConfig, NewPhotoBoost, PlaylistFavorites, PlaylistOptions,
+    QuarantineConfig,
+};
 use photoframe::events::{Displayed, InventoryEvent, LoadPhoto, PhotoInfo};
+use photoframe::tasks::dedup::Fingerprint;
+use photoframe::tasks::history::HistoryRecorder;
 use photoframe::tasks::manager;
+use photoframe::tasks::quarantine::Quarantine;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, SystemTime};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_util::sync::CancellationToken;
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -22,6 +30,10 @@ async fn manager_ignores_spurious_remove_and_sends_load_on_add() {
         PlaylistOptions::default(),
         None,
         Some(42),
+        Arc::default(),
+        HistoryRecorder::start(&HistoryConfig::default()),
+        Quarantine::shared(&QuarantineConfig::default()),
+        no_library_hold(),
     ));
 
     // Spurious remove for path never added
@@ -40,21 +52,26 @@ async fn manager_ignores_spurious_remove_and_sends_load_on_add() {
 
     // Now add a real file and expect a load
     let real = PathBuf::from("/real/a.jpg");
+    let taken = SystemTime::now();
     inv_tx
-        .send(InventoryEvent::PhotoAdded(photo_info(
-            real.clone(),
-            SystemTime::now(),
-        )))
+        .send(InventoryEvent::PhotoAdded(photo_info(real.clone(), taken)))
         .await
         .unwrap();
 
-    let LoadPhoto { path: p, priority } =
-        tokio::time::timeout(std::time::Duration::from_secs(5), to_load_rx.recv())
-            .await
-            .expect("timeout waiting for LoadPhoto")
-            .expect("channel closed");
+    let LoadPhoto {
+        path: p,
+        priority,
+        created_at,
+    } = tokio::time::timeout(std::time::Duration::from_secs(5), to_load_rx.recv())
+        .await
+        .expect("timeout waiting for LoadPhoto")
+        .expect("channel closed");
     assert!(priority, "first load for new photo should be prioritized");
     assert_eq!(p, real);
+    assert_eq!(
+        created_at, taken,
+        "the load carries the photo's capture time"
+    );
 
     cancel.cancel();
     let _ = handle.await;
@@ -75,6 +92,10 @@ async fn manager_rotates_actual_sent_item() {
         PlaylistOptions::default(),
         None,
         Some(42),
+        Arc::default(),
+        HistoryRecorder::start(&HistoryConfig::default()),
+        Quarantine::shared(&QuarantineConfig::default()),
+        no_library_hold(),
     ));
 
     let initial_a = PathBuf::from("/photos/a.jpg");
@@ -141,6 +162,266 @@ async fn manager_rotates_actual_sent_item() {
     let _ = handle.await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn manager_records_displayed_photos_in_the_history() {
+    let dir = tempfile::tempdir().unwrap();
+    let (_inv_tx, inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (displayed_tx, displayed_rx) = mpsc::channel::<Displayed>(16);
+    let (to_load_tx, _to_load_rx) = mpsc::channel::<LoadPhoto>(1);
+    let cancel = CancellationToken::new();
+    let log = dir.path().join("history.jsonl");
+    let recorder = HistoryRecorder::start(&HistoryConfig {
+        file: Some(log.clone()),
+        max_entries: 10,
+    });
+    let history = recorder.shared();
+
+    let handle = tokio::spawn(manager::run(
+        inv_rx,
+        displayed_rx,
+        to_load_tx,
+        cancel.clone(),
+        PlaylistOptions::default(),
+        None,
+        Some(42),
+        Arc::default(),
+        recorder,
+        Quarantine::shared(&QuarantineConfig::default()),
+        no_library_hold(),
+    ));
+
+    // The second output catching up on a.jpg while the first still shows it
+    // is the same showing, so only two entries land in the history.
+    for (name, output) in [
+        ("a.jpg", "HDMI-A-1"),
+        ("a.jpg", "HDMI-A-2"),
+        ("b.jpg", "HDMI-A-2"),
+    ] {
+        displayed_tx
+            .send(Displayed {
+                path: PathBuf::from("/photos").join(name),
+                output: Some(output.to_string()),
+            })
+            .await
+            .unwrap();
+    }
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while history.lock().unwrap().recent(10).len() < 2 {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "history never filled"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let recent = history.lock().unwrap().recent(10);
+    assert_eq!(recent.len(), 2);
+    assert_eq!(recent[0].path, PathBuf::from("/photos/b.jpg"));
+    assert_eq!(recent[1].path, PathBuf::from("/photos/a.jpg"));
+
+    cancel.cancel();
+    let _ = handle.await;
+    // The disk writer drains once the manager drops its queue.
+    while std::fs::read_to_string(&log)
+        .unwrap_or_default()
+        .lines()
+        .count()
+        < 2
+    {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "history log never written"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn manager_collapses_copies_to_the_largest() {
+    let dir = tempfile::tempdir().unwrap();
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/dedup");
+    let scene = image::open(fixtures.join("scene.png")).unwrap();
+    std::fs::create_dir_all(dir.path().join("album")).unwrap();
+    std::fs::create_dir_all(dir.path().join("export")).unwrap();
+    let large = dir.path().join("album/scene.png");
+    let small = dir.path().join("export/scene.png");
+    scene.save(&large).unwrap();
+    scene
+        .resize_exact(120, 90, image::imageops::FilterType::Triangle)
+        .save(&small)
+        .unwrap();
+    let unrelated = fixtures.join("pattern.png");
+
+    let (inv_tx, inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_displayed_tx, displayed_rx) = mpsc::channel::<Displayed>(16);
+    let (to_load_tx, mut to_load_rx) = mpsc::channel::<LoadPhoto>(1);
+    let cancel = CancellationToken::new();
+    let library_size = Arc::new(AtomicUsize::new(0));
+    let handle = tokio::spawn(manager::run(
+        inv_rx,
+        displayed_rx,
+        to_load_tx,
+        cancel.clone(),
+        dedup_options(DedupMode::Collapse),
+        None,
+        Some(42),
+        Arc::clone(&library_size),
+        HistoryRecorder::start(&HistoryConfig::default()),
+        Quarantine::shared(&QuarantineConfig::default()),
+        no_library_hold(),
+    ));
+
+    let photos = [small.clone(), large.clone(), unrelated.clone()];
+    for path in &photos {
+        inv_tx
+            .send(InventoryEvent::PhotoAdded(photo_info(
+                path.clone(),
+                SystemTime::now(),
+            )))
+            .await
+            .unwrap();
+    }
+    for path in &photos {
+        let fingerprint = Fingerprint::read(path).unwrap();
+        inv_tx
+            .send(InventoryEvent::PhotoFingerprinted(
+                path.clone(),
+                fingerprint,
+            ))
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(library_size.load(Ordering::Relaxed), 2);
+    // Queued before the fingerprints arrived.
+    receive_with_timeout(&mut to_load_rx).await;
+    let mut shown = HashSet::new();
+    for _ in 0..20 {
+        shown.insert(receive_with_timeout(&mut to_load_rx).await);
+    }
+    assert_eq!(shown, HashSet::from([large.clone(), unrelated.clone()]));
+
+    // Without the larger copy, the smaller one is shown again.
+    inv_tx
+        .send(InventoryEvent::PhotoRemoved(large.clone()))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    receive_with_timeout(&mut to_load_rx).await;
+    let mut shown = HashSet::new();
+    for _ in 0..20 {
+        shown.insert(receive_with_timeout(&mut to_load_rx).await);
+    }
+    assert_eq!(shown, HashSet::from([small, unrelated]));
+
+    cancel.cancel();
+    let _ = handle.await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn manager_spreads_copies_apart() {
+    let (inv_tx, inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_displayed_tx, displayed_rx) = mpsc::channel::<Displayed>(16);
+    let (to_load_tx, mut to_load_rx) = mpsc::channel::<LoadPhoto>(1);
+    let cancel = CancellationToken::new();
+    let handle = tokio::spawn(manager::run(
+        inv_rx,
+        displayed_rx,
+        to_load_tx,
+        cancel.clone(),
+        PlaylistOptions {
+            dedup: DedupConfig {
+                mode: DedupMode::Spread,
+                window: 3,
+            },
+            ..PlaylistOptions::default()
+        },
+        None,
+        Some(7),
+        Arc::default(),
+        HistoryRecorder::start(&HistoryConfig::default()),
+        Quarantine::shared(&QuarantineConfig::default()),
+        no_library_hold(),
+    ));
+
+    // Two copies of one photo, and four photos of other shapes.
+    let copies = [
+        PathBuf::from("/photos/album/a.jpg"),
+        PathBuf::from("/photos/export/a.jpg"),
+    ];
+    let mut photos: Vec<(PathBuf, Fingerprint)> = copies
+        .iter()
+        .zip([1200, 600])
+        .map(|(path, width)| (path.clone(), fingerprint(width, width * 3 / 4)))
+        .collect();
+    for i in 1..=4 {
+        photos.push((
+            PathBuf::from(format!("/photos/{i}.jpg")),
+            fingerprint(1000 + 100 * i, 1000),
+        ));
+    }
+    for (path, _) in &photos {
+        inv_tx
+            .send(InventoryEvent::PhotoAdded(photo_info(
+                path.clone(),
+                SystemTime::now() - Duration::from_secs(86_400 * 30),
+            )))
+            .await
+            .unwrap();
+    }
+    for (path, fingerprint) in photos {
+        inv_tx
+            .send(InventoryEvent::PhotoFingerprinted(path, fingerprint))
+            .await
+            .unwrap();
+    }
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    // Queued before the fingerprints arrived.
+    receive_with_timeout(&mut to_load_rx).await;
+    let mut last_copy: Option<(usize, PathBuf)> = None;
+    let mut copies_shown = 0;
+    for position in 0..60 {
+        let path = receive_with_timeout(&mut to_load_rx).await;
+        if !copies.contains(&path) {
+            continue;
+        }
+        copies_shown += 1;
+        if let Some((at, previous)) = &last_copy
+            && *previous != path
+        {
+            assert!(
+                position - at > 3,
+                "{} at {position} follows {} at {at}",
+                path.display(),
+                previous.display()
+            );
+        }
+        last_copy = Some((position, path));
+    }
+    assert!(copies_shown >= 10, "copies shown {copies_shown} times");
+
+    cancel.cancel();
+    let _ = handle.await;
+}
+
+fn dedup_options(mode: DedupMode) -> PlaylistOptions {
+    PlaylistOptions {
+        dedup: DedupConfig {
+            mode,
+            ..DedupConfig::default()
+        },
+        ..PlaylistOptions::default()
+    }
+}
+
+/// A fingerprint that only matches others of the same shape.
+fn fingerprint(width: u32, height: u32) -> Fingerprint {
+    Fingerprint {
+        hash: 0x5A5A_5A5A_5A5A_5A5A,
+        width,
+        height,
+    }
+}
+
 async fn receive_with_timeout(rx: &mut mpsc::Receiver<LoadPhoto>) -> PathBuf {
     // Generous timeout: these async tests run alongside the rest of the suite,
     // and under heavy parallel load the manager task can be slow to get
@@ -152,8 +433,17 @@ async fn receive_with_timeout(rx: &mut mpsc::Receiver<LoadPhoto>) -> PathBuf {
     path
 }
 
+/// A library hold nobody engages.
+fn no_library_hold() -> watch::Receiver<Option<String>> {
+    watch::channel(None).1
+}
+
 fn photo_info(path: PathBuf, created_at: SystemTime) -> PhotoInfo {
-    PhotoInfo { path, created_at }
+    PhotoInfo {
+        path,
+        created_at,
+        root_weight: 1.0,
+    }
 }
 
 #[test]
@@ -161,6 +451,7 @@ fn simulate_playlist_respects_seed_and_weights() {
     let options = PlaylistOptions {
         new_multiplicity: 3,
         half_life: Duration::from_secs(86_400),
+        ..PlaylistOptions::default()
     };
     let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
     let fresh_path = PathBuf::from("fresh.jpg");
@@ -204,6 +495,7 @@ fn simulate_playlist_has_no_back_to_back_repeats() {
     let options = PlaylistOptions {
         new_multiplicity: 3,
         half_life: Duration::from_secs(86_400),
+        ..PlaylistOptions::default()
     };
     let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
     // A small library is the worst case for back-to-back repeats.
@@ -223,6 +515,50 @@ fn simulate_playlist_has_no_back_to_back_repeats() {
     }
 }
 
+#[test]
+fn simulate_playlist_keeps_photos_out_of_the_no_repeat_window() {
+    let window = 8;
+    let options = PlaylistOptions {
+        new_multiplicity: 5,
+        no_repeat_window: window,
+        ..PlaylistOptions::default()
+    };
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    // New photos weigh five times as much and would otherwise come back
+    // within a handful of slides.
+    let photos: Vec<PhotoInfo> = (0..20)
+        .map(|i| {
+            let age = if i < 5 { 0 } else { 86_400 * 30 };
+            photo_info(
+                PathBuf::from(format!("p_{i}.jpg")),
+                now - Duration::from_secs(age),
+            )
+        })
+        .collect();
+
+    for seed in [1u64, 7, 42, 1000] {
+        let plan =
+            manager::simulate_playlist(photos.clone(), options.clone(), now, 300, Some(seed));
+        assert_eq!(plan.len(), 300);
+        for (i, path) in plan.iter().enumerate() {
+            let recent = &plan[i.saturating_sub(window)..i];
+            assert!(
+                !recent.contains(path),
+                "{} repeated within {window} slides at {i} (seed {seed})",
+                path.display()
+            );
+        }
+    }
+
+    // A library no bigger than the window shows every photo in turn.
+    let small: Vec<PhotoInfo> = photos.into_iter().take(5).collect();
+    let plan = manager::simulate_playlist(small, options, now, 50, Some(3));
+    assert_eq!(plan.len(), 50);
+    for (i, path) in plan.iter().enumerate().skip(4) {
+        assert!(!plan[i - 4..i].contains(path), "repeat at {i}");
+    }
+}
+
 #[test]
 fn simulate_playlist_single_photo_repeats() {
     // With only one photo there is nothing else to show, so it must keep
@@ -251,6 +587,7 @@ fn bulk_import_does_not_starve_old_photos() {
     let options = PlaylistOptions {
         new_multiplicity: 3,
         half_life: Duration::from_secs(86_400),
+        ..PlaylistOptions::default()
     };
 
     let old_paths: Vec<PathBuf> = (0..10)
@@ -276,6 +613,216 @@ fn bulk_import_does_not_starve_old_photos() {
     );
 }
 
+/// With `new-photo-boost`, photos from the last few days lead the rotation newest-first,
+/// then the weighted schedule takes over and the boosted photos keep their place in it.
+#[test]
+fn simulate_playlist_boosts_recent_photos_first() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(10_000_000);
+    let day = Duration::from_secs(86_400);
+    let recent: Vec<PathBuf> = (0..3)
+        .map(|i| PathBuf::from(format!("recent_{i}.jpg")))
+        .collect();
+    let mut photos: Vec<PhotoInfo> = recent
+        .iter()
+        .enumerate()
+        .map(|(i, p)| photo_info(p.clone(), now - day * (i as u32 + 1)))
+        .collect();
+    photos.extend(
+        (0..20).map(|i| photo_info(PathBuf::from(format!("old_{i}.jpg")), now - day * (60 + i))),
+    );
+    let boosted = PlaylistOptions {
+        new_photo_boost: Some(NewPhotoBoost {
+            displays: 10,
+            max_age: day * 7,
+        }),
+        ..PlaylistOptions::default()
+    };
+
+    let plan = manager::simulate_playlist(photos.clone(), boosted, now, 40, Some(3));
+    assert_eq!(plan[..3], recent[..], "recent photos lead, newest first");
+    assert!(
+        plan[3..].iter().any(|p| recent.contains(p)),
+        "boosted photos return later"
+    );
+
+    // Without the boost, the same seed does not front-load them.
+    let plain = manager::simulate_playlist(photos, PlaylistOptions::default(), now, 40, Some(3));
+    assert_ne!(plain[..3], recent[..]);
+}
+
+/// Photos listed in the favorites file come up about `factor` times as often as the rest.
+#[test]
+fn simulate_playlist_favors_listed_photos() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("favorites.txt");
+    std::fs::write(&file, "# keepers\n\nalbum/fav.jpg\n").unwrap();
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(10_000_000);
+    let old = now - Duration::from_secs(86_400 * 60);
+    let favorite = PathBuf::from("/photos/album/fav.jpg");
+    let others: Vec<PathBuf> = (0..9)
+        .map(|i| PathBuf::from(format!("/photos/album/p_{i}.jpg")))
+        .collect();
+    let mut photos = vec![photo_info(favorite.clone(), old)];
+    photos.extend(others.iter().map(|p| photo_info(p.clone(), old)));
+    let options = PlaylistOptions {
+        favorites: Some(PlaylistFavorites { file, factor: 4.0 }),
+        ..PlaylistOptions::default()
+    };
+
+    let plan = manager::simulate_playlist(photos.clone(), options, now, 600, Some(11));
+    let count = |path: &PathBuf| plan.iter().filter(|p| *p == path).count();
+    let favorite_count = count(&favorite);
+    let average_other = others.iter().map(count).sum::<usize>() as f64 / others.len() as f64;
+    // Weight 4 against nine photos of weight 1: about 4/13 of the plan.
+    assert!(
+        favorite_count as f64 > 2.5 * average_other,
+        "favorite shown {favorite_count} times vs {average_other:.1} on average"
+    );
+
+    // Without the list it is just one of ten.
+    let plain = manager::simulate_playlist(photos, PlaylistOptions::default(), now, 600, Some(11));
+    let plain_count = plain.iter().filter(|p| **p == favorite).count();
+    assert!(
+        favorite_count > 2 * plain_count,
+        "{favorite_count} vs {plain_count}"
+    );
+}
+
+/// Only the first `displays` showings are boosted; recent photos beyond that wait their turn.
+#[test]
+fn simulate_playlist_boost_stops_after_its_displays() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(10_000_000);
+    let photos: Vec<PhotoInfo> = (0..6)
+        .map(|i| {
+            photo_info(
+                PathBuf::from(format!("p_{i}.jpg")),
+                now - Duration::from_secs(i),
+            )
+        })
+        .collect();
+    let options = PlaylistOptions {
+        new_photo_boost: Some(NewPhotoBoost {
+            displays: 2,
+            max_age: Duration::from_secs(3_600),
+        }),
+        ..PlaylistOptions::default()
+    };
+
+    let plan = manager::simulate_playlist(photos, options, now, 6, Some(11));
+    assert_eq!(
+        plan[..2],
+        [PathBuf::from("p_0.jpg"), PathBuf::from("p_1.jpg")]
+    );
+    let newest_first: Vec<PathBuf> = (2..6)
+        .map(|i| PathBuf::from(format!("p_{i}.jpg")))
+        .collect();
+    assert_ne!(plan[2..], newest_first[..], "the schedule takes over");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn manager_sends_no_loads_while_the_library_is_held() {
+    let (inv_tx, inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_displayed_tx, displayed_rx) = mpsc::channel::<Displayed>(16);
+    let (to_load_tx, mut to_load_rx) = mpsc::channel::<LoadPhoto>(2);
+    let (hold_tx, hold_rx) = watch::channel(Some("sync".to_string()));
+    let cancel = CancellationToken::new();
+
+    let handle = tokio::spawn(manager::run(
+        inv_rx,
+        displayed_rx,
+        to_load_tx,
+        cancel.clone(),
+        PlaylistOptions::default(),
+        None,
+        Some(42),
+        Arc::default(),
+        HistoryRecorder::start(&HistoryConfig::default()),
+        Quarantine::shared(&QuarantineConfig::default()),
+        hold_rx,
+    ));
+
+    let photo = PathBuf::from("/photos/a.jpg");
+    inv_tx
+        .send(InventoryEvent::PhotoAdded(photo_info(
+            photo.clone(),
+            SystemTime::now(),
+        )))
+        .await
+        .unwrap();
+    let held = tokio::time::timeout(Duration::from_millis(300), to_load_rx.recv()).await;
+    assert!(held.is_err(), "no load is sent while the library is held");
+
+    hold_tx.send_replace(None);
+    assert_eq!(receive_with_timeout(&mut to_load_rx).await, photo);
+
+    cancel.cancel();
+    handle.await.unwrap().unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn manager_stops_loading_a_photo_that_keeps_failing() {
+    let dir = tempfile::tempdir().unwrap();
+    let broken = dir.path().join("broken.jpg");
+    std::fs::write(&broken, b"not a jpeg").unwrap();
+    let (inv_tx, inv_rx) = mpsc::channel::<InventoryEvent>(16);
+    let (_displayed_tx, displayed_rx) = mpsc::channel::<Displayed>(16);
+    let (to_load_tx, mut to_load_rx) = mpsc::channel::<LoadPhoto>(1);
+    let cancel = CancellationToken::new();
+    let quarantine = Quarantine::shared(&QuarantineConfig::default());
+
+    let handle = tokio::spawn(manager::run(
+        inv_rx,
+        displayed_rx,
+        to_load_tx,
+        cancel.clone(),
+        PlaylistOptions::default(),
+        None,
+        Some(42),
+        Arc::default(),
+        HistoryRecorder::start(&HistoryConfig::default()),
+        Arc::clone(&quarantine),
+        no_library_hold(),
+    ));
+
+    // The loader rejects the photo each time; the file watcher re-adds it as a
+    // sync job keeps restoring the same broken file.
+    for attempt in 1..=3 {
+        inv_tx
+            .send(InventoryEvent::PhotoAdded(photo_info(
+                broken.clone(),
+                SystemTime::now(),
+            )))
+            .await
+            .unwrap();
+        assert_eq!(receive_with_timeout(&mut to_load_rx).await, broken);
+        inv_tx
+            .send(InventoryEvent::PhotoFailed(broken.clone()))
+            .await
+            .unwrap();
+        // Drain resends that raced the failure, until the manager goes quiet,
+        // so none is mistaken for a load after the quarantine.
+        while tokio::time::timeout(Duration::from_millis(200), to_load_rx.recv())
+            .await
+            .is_ok()
+        {}
+        let listed = quarantine.lock().unwrap().list(SystemTime::now()).len();
+        assert_eq!(listed, usize::from(attempt == 3), "after failure {attempt}");
+    }
+
+    inv_tx
+        .send(InventoryEvent::PhotoAdded(photo_info(
+            broken.clone(),
+            SystemTime::now(),
+        )))
+        .await
+        .unwrap();
+    let none = tokio::time::timeout(Duration::from_millis(500), to_load_rx.recv()).await;
+    assert!(none.is_err(), "a quarantined photo is not loaded again");
+
+    cancel.cancel();
+    let _ = handle.await;
+}
+
 /// Tombstone and generation: remove a photo mid-run, verify it eventually disappears;
 /// re-add and verify it returns. Covers the lazy-skip and generation-bump code paths.
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
@@ -293,6 +840,10 @@ async fn manager_churn_tombstone_and_generation() {
         PlaylistOptions::default(),
         None,
         Some(42),
+        Arc::default(),
+        HistoryRecorder::start(&HistoryConfig::default()),
+        Quarantine::shared(&QuarantineConfig::default()),
+        no_library_hold(),
     ));
 
     let path_a = PathBuf::from("/photos/a.jpg");
@@ -364,3 +915,88 @@ async fn manager_churn_tombstone_and_generation() {
     cancel.cancel();
     let _ = handle.await;
 }
+
+fn library(now: SystemTime, count: usize) -> Vec<PhotoInfo> {
+    (0..count)
+        .map(|i| {
+            photo_info(
+                PathBuf::from(format!("p_{i}.jpg")),
+                now - Duration::from_secs(3_600 * i as u64),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn simulate_playlist_resumes_from_state_file() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let photos = library(now, 8);
+    let dir = tempfile::tempdir().unwrap();
+    let options = PlaylistOptions {
+        state_file: Some(dir.path().join("playlist-state.json")),
+        ..PlaylistOptions::default()
+    };
+
+    let uninterrupted =
+        manager::simulate_playlist(photos.clone(), PlaylistOptions::default(), now, 40, Some(7));
+
+    let before = manager::simulate_playlist(photos.clone(), options.clone(), now, 20, Some(7));
+    // A different seed after the "restart" must not matter: the saved RNG wins.
+    let after = manager::simulate_playlist(photos, options, now, 20, Some(1234));
+
+    assert_eq!(before, uninterrupted[..20]);
+    assert_eq!(
+        after,
+        uninterrupted[20..],
+        "restored playlist should continue the rotation, not restart it"
+    );
+}
+
+#[test]
+fn simulate_playlist_skips_photos_missing_since_snapshot() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let photos = library(now, 6);
+    let dir = tempfile::tempdir().unwrap();
+    let options = PlaylistOptions {
+        state_file: Some(dir.path().join("playlist-state.json")),
+        ..PlaylistOptions::default()
+    };
+
+    manager::simulate_playlist(photos.clone(), options.clone(), now, 12, Some(3));
+
+    let gone = photos[0].path.clone();
+    let mut remaining: Vec<PhotoInfo> = photos[1..].to_vec();
+    let newcomer = PathBuf::from("new.jpg");
+    remaining.push(photo_info(newcomer.clone(), now));
+    let plan = manager::simulate_playlist(remaining, options, now, 30, Some(3));
+
+    assert_eq!(plan.len(), 30);
+    assert!(!plan.contains(&gone), "removed photo must not be scheduled");
+    assert!(
+        plan.contains(&newcomer),
+        "new photo joins the restored rotation"
+    );
+}
+
+#[test]
+fn simulate_playlist_ignores_corrupt_state_file() {
+    let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    let photos = library(now, 5);
+    let dir = tempfile::tempdir().unwrap();
+    let state_file = dir.path().join("playlist-state.json");
+    std::fs::write(&state_file, b"{ not json").unwrap();
+    let options = PlaylistOptions {
+        state_file: Some(state_file.clone()),
+        ..PlaylistOptions::default()
+    };
+
+    let plan = manager::simulate_playlist(photos.clone(), options, now, 10, Some(5));
+    let fresh = manager::simulate_playlist(photos, PlaylistOptions::default(), now, 10, Some(5));
+    assert_eq!(plan, fresh, "a corrupt state file starts a fresh rotation");
+
+    let rewritten = std::fs::read_to_string(&state_file).unwrap();
+    assert!(
+        rewritten.contains("\"version\":1"),
+        "state file is replaced"
+    );
+}
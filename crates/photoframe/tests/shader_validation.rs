@@ -44,3 +44,11 @@ fn caption_composite_wgsl_validates() {
         include_str!("../src/tasks/viewer/scenes/caption_composite.wgsl"),
     );
 }
+
+#[test]
+fn output_rotate_wgsl_validates() {
+    validate(
+        "output_rotate.wgsl",
+        include_str!("../src/tasks/shaders/output_rotate.wgsl"),
+    );
+}
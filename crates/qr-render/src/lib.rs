@@ -0,0 +1,54 @@
+//! QR code rendering shared by the photo frame binaries. `wifi-manager`
+//! saves the codes for its overlay as PNG files; `photoframe` uploads the
+//! `share-qr` code straight into a texture.
+
+use anyhow::{Context, Result};
+use image::{GrayImage, Luma};
+use qrcode::QrCode;
+
+/// Draws `text` as a black-on-white QR code at least `size` pixels across,
+/// quiet zone included. Modules stay whole, so the image may be slightly
+/// larger than asked.
+pub fn render(text: &str, size: u32) -> Result<GrayImage> {
+    let code = QrCode::new(text.as_bytes()).context("text does not fit in a QR code")?;
+    Ok(code.render::<Luma<u8>>().min_dimensions(size, size).build())
+}
+
+/// [`render`] as opaque RGBA rows, for uploading as a texture.
+pub fn render_rgba(text: &str, size: u32) -> Result<(u32, Vec<u8>)> {
+    let image = render(text, size)?;
+    let rgba = image
+        .pixels()
+        .flat_map(|Luma([level])| [*level, *level, *level, 255])
+        .collect();
+    Ok((image.width(), rgba))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_code_is_a_square_at_least_the_asked_size() {
+        let image = render("http://192.168.4.1:8080/", 256).unwrap();
+        assert!(image.width() >= 256);
+        assert_eq!(image.width(), image.height());
+        // The quiet zone keeps the corner white.
+        assert_eq!(image.get_pixel(0, 0), &Luma([255]));
+    }
+
+    #[test]
+    fn rgba_rows_match_the_gray_image() {
+        let text = "WIFI:T:WPA;S:Frame;P:secret;;";
+        let (size, rgba) = render_rgba(text, 160).unwrap();
+        assert_eq!(size, render(text, 160).unwrap().width());
+        assert_eq!(rgba.len(), (size * size * 4) as usize);
+        assert_eq!(&rgba[..4], &[255, 255, 255, 255]);
+        assert!(rgba.chunks(4).any(|pixel| pixel == [0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn oversized_text_is_an_error() {
+        assert!(render(&"x".repeat(8000), 256).is_err());
+    }
+}
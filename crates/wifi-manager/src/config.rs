@@ -1,4 +1,4 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, ensure};
 use serde::Deserialize;
 use std::fs;
 use std::net::Ipv4Addr;
@@ -13,6 +13,12 @@ pub struct Config {
     pub check_interval_sec: u64,
     #[serde(default = "default_offline_grace")]
     pub offline_grace_sec: u64,
+    /// Consecutive failed connectivity polls before the link counts as lost.
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+    /// Consecutive good polls before an active recovery hotspot is torn down.
+    #[serde(default = "default_success_threshold")]
+    pub success_threshold: u32,
     #[serde(default = "default_recovery_mode")]
     pub recovery_mode: RecoveryMode,
     #[serde(default = "default_recovery_reconnect_probe")]
@@ -23,6 +29,9 @@ pub struct Config {
     pub wordlist_path: PathBuf,
     #[serde(default = "default_var_dir")]
     pub var_dir: PathBuf,
+    /// Connectivity summary (`wifi-status.json`) other services can read.
+    #[serde(default = "default_status_file")]
+    pub status_file: PathBuf,
     #[serde(default)]
     pub hotspot: HotspotConfig,
     #[serde(default)]
@@ -84,6 +93,36 @@ pub struct OverlayConfig {
     pub overlay_app_id: String,
     #[serde(default)]
     pub sway_socket: Option<PathBuf>,
+    /// QR codes shown on the overlay, left to right.
+    #[serde(default = "default_overlay_qr_codes")]
+    pub qr_codes: Vec<QrCodeKind>,
+}
+
+/// A QR code the recovery overlay can show.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum QrCodeKind {
+    /// `WIFI:` join string for the hotspot, so phones join with one scan.
+    WifiJoin,
+    /// URL of the provisioning UI.
+    Portal,
+}
+
+impl QrCodeKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::WifiJoin => "wifi-join",
+            Self::Portal => "portal",
+        }
+    }
+
+    /// Image file name under `var-dir`.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Self::WifiJoin => "wifi-qr.png",
+            Self::Portal => "portal-qr.png",
+        }
+    }
 }
 
 impl Config {
@@ -92,8 +131,34 @@ impl Config {
             .with_context(|| format!("failed to read config at {}", path.display()))?;
         let cfg: Config = serde_yaml::from_slice(&data)
             .with_context(|| format!("failed to parse config at {}", path.display()))?;
+        cfg.validate()
+            .with_context(|| format!("invalid config at {}", path.display()))?;
         Ok(cfg)
     }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.failure_threshold >= 1,
+            "failure-threshold must be at least 1"
+        );
+        ensure!(
+            self.success_threshold >= 1,
+            "success-threshold must be at least 1"
+        );
+        let codes = &self.overlay.qr_codes;
+        ensure!(
+            !codes.is_empty(),
+            "overlay.qr-codes must list at least one code"
+        );
+        ensure!(
+            !codes
+                .iter()
+                .enumerate()
+                .any(|(i, code)| codes[..i].contains(code)),
+            "overlay.qr-codes must not list a code twice"
+        );
+        Ok(())
+    }
 }
 
 impl Default for HotspotConfig {
@@ -131,6 +196,7 @@ impl Default for OverlayConfig {
             photo_app_id: default_photo_app_id(),
             overlay_app_id: default_overlay_app_id(),
             sway_socket: None,
+            qr_codes: default_overlay_qr_codes(),
         }
     }
 }
@@ -147,6 +213,14 @@ fn default_offline_grace() -> u64 {
     30
 }
 
+fn default_failure_threshold() -> u32 {
+    3
+}
+
+fn default_success_threshold() -> u32 {
+    2
+}
+
 fn default_recovery_mode() -> RecoveryMode {
     RecoveryMode::AppHandoff
 }
@@ -170,6 +244,10 @@ fn default_var_dir() -> PathBuf {
     PathBuf::from("/var/lib/photoframe")
 }
 
+fn default_status_file() -> PathBuf {
+    PathBuf::from("/run/photo-frame/wifi-status.json")
+}
+
 fn default_hotspot_connection_id() -> String {
     "pf-hotspot".to_string()
 }
@@ -199,6 +277,10 @@ fn default_overlay_command() -> Vec<String> {
     vec!["swaymsg".to_string()]
 }
 
+fn default_overlay_qr_codes() -> Vec<QrCodeKind> {
+    vec![QrCodeKind::WifiJoin, QrCodeKind::Portal]
+}
+
 fn default_photo_app_id() -> String {
     "photoframe".to_string()
 }
@@ -209,7 +291,7 @@ fn default_overlay_app_id() -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, RecoveryMode};
+    use super::{Config, QrCodeKind, RecoveryMode};
 
     #[test]
     fn defaults_include_recovery_settings() {
@@ -217,6 +299,12 @@ mod tests {
         assert_eq!(cfg.recovery_mode, RecoveryMode::AppHandoff);
         assert_eq!(cfg.recovery_reconnect_probe_sec, 300);
         assert_eq!(cfg.recovery_connect_timeout_sec, 20);
+        assert_eq!(cfg.failure_threshold, 3);
+        assert_eq!(cfg.success_threshold, 2);
+        assert_eq!(
+            cfg.status_file,
+            std::path::Path::new("/run/photo-frame/wifi-status.json")
+        );
         assert_eq!(cfg.photo_app.app_id, "photoframe");
         assert_eq!(
             cfg.photo_app.launch_command,
@@ -254,4 +342,38 @@ photo-app:
             ]
         );
     }
+
+    #[test]
+    fn rejects_zero_thresholds() {
+        for yaml in ["failure-threshold: 0", "success-threshold: 0"] {
+            let cfg: Config = serde_yaml::from_str(yaml).expect("parse config");
+            assert!(cfg.validate().is_err(), "{yaml} should be rejected");
+        }
+        let cfg: Config =
+            serde_yaml::from_str("failure-threshold: 5\nsuccess-threshold: 1").expect("parse");
+        assert!(cfg.validate().is_ok());
+        assert_eq!((cfg.failure_threshold, cfg.success_threshold), (5, 1));
+    }
+
+    #[test]
+    fn overlay_qr_codes_default_to_both_and_can_be_narrowed() {
+        let cfg: Config = serde_yaml::from_str("{}").expect("parse config");
+        assert_eq!(
+            cfg.overlay.qr_codes,
+            vec![QrCodeKind::WifiJoin, QrCodeKind::Portal]
+        );
+
+        let cfg: Config =
+            serde_yaml::from_str("overlay:\n  qr-codes: [portal]").expect("parse config");
+        assert_eq!(cfg.overlay.qr_codes, vec![QrCodeKind::Portal]);
+        assert!(cfg.validate().is_ok());
+
+        for yaml in [
+            "overlay:\n  qr-codes: []",
+            "overlay:\n  qr-codes: [portal, portal]",
+        ] {
+            let cfg: Config = serde_yaml::from_str(yaml).expect("parse config");
+            assert!(cfg.validate().is_err(), "{yaml} should be rejected");
+        }
+    }
 }
@@ -0,0 +1,222 @@
+use crate::status::{AttemptRecord, ProvisionRequest};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How many recent join attempts the UI remembers.
+const MAX_TRACKED_JOINS: usize = 4;
+
+/// How long an attempt's status stays available after it was submitted.
+const JOIN_EXPIRY: Duration = Duration::from_secs(15 * 60);
+
+/// Where a join attempt stands, as `GET /join/<token>/status` reports it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "lowercase")]
+pub enum JoinState {
+    Connecting,
+    Connected,
+    Failed { reason: String },
+}
+
+impl JoinState {
+    /// Reads the watcher's progress report.
+    fn from_record(record: &AttemptRecord) -> Self {
+        match record.status.as_str() {
+            "connected" => JoinState::Connected,
+            "error" => JoinState::Failed {
+                reason: record.message.clone(),
+            },
+            _ => JoinState::Connecting,
+        }
+    }
+}
+
+/// The NetworkManager side of a join: the watcher applies submitted
+/// credentials and reports back through the last-attempt record.
+pub trait JoinBackend: Send + Sync {
+    /// Hands the credentials over to be applied.
+    fn submit(&self, request: ProvisionRequest) -> Result<()>;
+
+    /// The latest progress report for any attempt.
+    fn last_attempt(&self) -> Result<Option<AttemptRecord>>;
+}
+
+#[derive(Debug)]
+struct TrackedJoin {
+    token: String,
+    submitted_at: Instant,
+    state: JoinState,
+}
+
+/// Join attempts started from this UI, newest last.
+#[derive(Clone)]
+pub struct JoinTracker {
+    backend: Arc<dyn JoinBackend>,
+    joins: Arc<Mutex<VecDeque<TrackedJoin>>>,
+}
+
+impl JoinTracker {
+    pub fn new(backend: Arc<dyn JoinBackend>) -> Self {
+        Self {
+            backend,
+            joins: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Submits `request` and returns the token to poll its status with.
+    pub fn start(&self, request: ProvisionRequest, now: Instant) -> Result<String> {
+        let token = request.attempt_id.clone();
+        self.backend.submit(request)?;
+        let mut joins = self.joins.lock().expect("join tracker poisoned");
+        while joins.len() >= MAX_TRACKED_JOINS {
+            joins.pop_front();
+        }
+        joins.push_back(TrackedJoin {
+            token: token.clone(),
+            submitted_at: now,
+            state: JoinState::Connecting,
+        });
+        Ok(token)
+    }
+
+    /// Where the attempt `token` stands, or `None` when it is unknown or has
+    /// expired. Once connected or failed an attempt keeps that answer, even
+    /// after a later attempt overwrites the watcher's report.
+    pub fn status(&self, token: &str, now: Instant) -> Option<JoinState> {
+        let record = match self.backend.last_attempt() {
+            Ok(record) => record,
+            Err(err) => {
+                tracing::warn!(error = ?err, "failed to read join progress");
+                None
+            }
+        };
+        let mut joins = self.joins.lock().expect("join tracker poisoned");
+        joins.retain(|join| now.duration_since(join.submitted_at) < JOIN_EXPIRY);
+        let join = joins.iter_mut().find(|join| join.token == token)?;
+        if join.state == JoinState::Connecting
+            && let Some(record) = record
+            && record.attempt_id.as_deref() == Some(token)
+        {
+            join.state = JoinState::from_record(&record);
+        }
+        Some(join.state.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stands in for the watcher and nmcli: keeps what was submitted and
+    /// reports whatever record the test sets.
+    #[derive(Default)]
+    struct ScriptedBackend {
+        submitted: Mutex<Vec<ProvisionRequest>>,
+        report: Mutex<Option<AttemptRecord>>,
+    }
+
+    impl ScriptedBackend {
+        fn report(&self, attempt_id: &str, status: &str, message: &str) {
+            *self.report.lock().unwrap() = Some(AttemptRecord {
+                timestamp: "2024-01-01T00:00:00Z".into(),
+                status: status.into(),
+                message: message.into(),
+                ssid: "Ho***et".into(),
+                attempt_id: Some(attempt_id.into()),
+                error: None,
+            });
+        }
+    }
+
+    impl JoinBackend for ScriptedBackend {
+        fn submit(&self, request: ProvisionRequest) -> Result<()> {
+            self.submitted.lock().unwrap().push(request);
+            Ok(())
+        }
+
+        fn last_attempt(&self) -> Result<Option<AttemptRecord>> {
+            Ok(self.report.lock().unwrap().clone())
+        }
+    }
+
+    fn request(attempt_id: &str) -> ProvisionRequest {
+        ProvisionRequest {
+            attempt_id: attempt_id.into(),
+            timestamp: "2024-01-01T00:00:00Z".into(),
+            ssid: "HomeNet".into(),
+            password: "correct horse".into(),
+        }
+    }
+
+    fn tracker() -> (Arc<ScriptedBackend>, JoinTracker) {
+        let backend = Arc::new(ScriptedBackend::default());
+        (backend.clone(), JoinTracker::new(backend))
+    }
+
+    #[test]
+    fn a_join_moves_from_connecting_to_connected() {
+        let (backend, joins) = tracker();
+        let now = Instant::now();
+        let token = joins.start(request("attempt-a"), now).unwrap();
+        assert_eq!(backend.submitted.lock().unwrap()[0].ssid, "HomeNet");
+        assert_eq!(joins.status(&token, now), Some(JoinState::Connecting));
+
+        backend.report("attempt-a", "connecting", "Attempting connection…");
+        assert_eq!(joins.status(&token, now), Some(JoinState::Connecting));
+        backend.report("attempt-a", "connected", "Frame is back online.");
+        assert_eq!(joins.status(&token, now), Some(JoinState::Connected));
+    }
+
+    #[test]
+    fn a_failed_join_keeps_its_reason() {
+        let (backend, joins) = tracker();
+        let now = Instant::now();
+        let first = joins.start(request("attempt-a"), now).unwrap();
+        backend.report("attempt-a", "error", "The Wi-Fi password was not accepted.");
+        let failed = JoinState::Failed {
+            reason: "The Wi-Fi password was not accepted.".into(),
+        };
+        assert_eq!(joins.status(&first, now), Some(failed.clone()));
+
+        // A retry overwrites the watcher's report; the first answer stands.
+        let second = joins.start(request("attempt-b"), now).unwrap();
+        backend.report("attempt-b", "connecting", "Attempting connection…");
+        assert_eq!(joins.status(&first, now), Some(failed));
+        assert_eq!(joins.status(&second, now), Some(JoinState::Connecting));
+    }
+
+    #[test]
+    fn old_and_unknown_joins_are_forgotten() {
+        let (_, joins) = tracker();
+        let now = Instant::now();
+        assert_eq!(joins.status("attempt-unknown", now), None);
+
+        let tokens: Vec<String> = (0..=MAX_TRACKED_JOINS)
+            .map(|n| joins.start(request(&format!("attempt-{n}")), now).unwrap())
+            .collect();
+        assert_eq!(
+            joins.status(&tokens[0], now),
+            None,
+            "only the last few are kept"
+        );
+        assert!(joins.status(&tokens[1], now).is_some());
+        assert_eq!(joins.status(&tokens[1], now + JOIN_EXPIRY), None);
+    }
+
+    #[test]
+    fn states_serialize_for_the_status_poll() {
+        let failed = JoinState::Failed {
+            reason: "No network with that name is in range.".into(),
+        };
+        assert_eq!(
+            serde_json::to_value(&failed).unwrap(),
+            serde_json::json!({"state": "failed", "reason": "No network with that name is in range."})
+        );
+        assert_eq!(
+            serde_json::to_value(JoinState::Connecting).unwrap(),
+            serde_json::json!({"state": "connecting"})
+        );
+    }
+}
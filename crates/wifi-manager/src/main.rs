@@ -1,11 +1,13 @@
 mod config;
 mod hotspot;
-mod logging;
+mod join;
 mod nm;
 mod overlay;
 mod password;
+mod process;
 mod qr;
 mod status;
+mod systemd;
 mod watch;
 mod web;
 
@@ -36,7 +38,7 @@ enum Commands {
     Watch,
     /// Run only the provisioning UI server.
     Ui,
-    /// Generate the hotspot QR code asset.
+    /// Generate the overlay's QR code assets.
     Qr,
     /// Low level NetworkManager helper subcommands.
     Nm {
@@ -45,6 +47,8 @@ enum Commands {
     },
     /// Launch the on-device recovery overlay window.
     Overlay(overlay::ui::OverlayCli),
+    /// Print the connectivity status the watcher last published.
+    Status,
 }
 
 #[tokio::main]
@@ -60,7 +64,7 @@ async fn try_main() -> Result<()> {
 
     guard_root_usage()?;
 
-    logging::init();
+    log_setup::init(&log_setup::LoggingConfig::default())?;
 
     let config_path = cli
         .config
@@ -81,11 +85,24 @@ async fn try_main() -> Result<()> {
         Commands::Qr => qr::generate(&config)?,
         Commands::Nm { command } => nm::handle_cli(command, &config).await?,
         Commands::Overlay(args) => overlay::ui::run(args)?,
+        Commands::Status => print_status(&config)?,
     }
 
     Ok(())
 }
 
+fn print_status(config: &Config) -> Result<()> {
+    let path = &config.status_file;
+    let Some(status) = status::read_wifi_status(path)? else {
+        anyhow::bail!(
+            "no Wi-Fi status at {}; is `wifi-manager watch` running?",
+            path.display()
+        );
+    };
+    println!("{}", status::format_wifi_status(&status));
+    Ok(())
+}
+
 fn guard_root_usage() -> Result<()> {
     let uid = unsafe { libc::getuid() };
     if uid == 0 {
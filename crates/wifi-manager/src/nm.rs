@@ -1,8 +1,10 @@
 use crate::config::{Config, HotspotConfig};
+use crate::process;
 use anyhow::{Context, Result, anyhow};
 use clap::{Args, Subcommand};
-use std::collections::HashSet;
-use std::process::Stdio;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io;
 use std::time::Duration;
 use tokio::process::Command;
 use tracing::{debug, info, warn};
@@ -90,19 +92,52 @@ pub async fn connected_to_infrastructure(
     }
 }
 
+/// SSID of the network `interface` is associated with, if any.
+pub async fn active_ssid(interface: &str) -> Result<Option<String>> {
+    let output = nmcli(&[
+        "-t",
+        "-f",
+        "ACTIVE,SSID",
+        "device",
+        "wifi",
+        "list",
+        "ifname",
+        interface,
+        "--rescan",
+        "no",
+    ])
+    .await?;
+    Ok(parse_active_ssid(&output))
+}
+
+/// First IPv4 address on `interface`, without the prefix length.
+pub async fn ipv4_address(interface: &str) -> Result<Option<String>> {
+    let output = nmcli(&["-t", "-f", "IP4.ADDRESS", "device", "show", interface]).await?;
+    Ok(output
+        .lines()
+        .find_map(parse_nmcli_value)
+        .map(|value| value.split('/').next().unwrap_or_default().to_string()))
+}
+
+fn parse_active_ssid(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let fields = split_terse_fields(line);
+        match fields.as_slice() {
+            [active, ssid] if active == "yes" && !ssid.is_empty() => Some(ssid.clone()),
+            _ => None,
+        }
+    })
+}
+
 pub async fn gateway_reachable(interface: &str) -> Result<bool> {
     let gw = default_gateway(interface).await?;
     if let Some(gw) = gw {
-        let status = Command::new("ping")
-            .arg("-c")
-            .arg("1")
-            .arg("-W")
-            .arg("2")
-            .arg(&gw)
-            .status()
+        let mut ping = Command::new("ping");
+        ping.args(["-c", "1", "-W", "2"]).arg(&gw);
+        let output = process::output_with_timeout(&mut ping, PROBE_TIMEOUT)
             .await
-            .with_context(|| format!("failed to spawn ping for gateway {gw}"))?;
-        return Ok(status.success());
+            .with_context(|| format!("failed to ping gateway {gw}"))?;
+        return Ok(output.status.success());
     }
     Ok(false)
 }
@@ -464,6 +499,91 @@ pub async fn delete_connection(connection_id: &str) -> Result<()> {
     Ok(())
 }
 
+/// A Wi-Fi network visible from the frame, as offered in the provisioning form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VisibleNetwork {
+    pub ssid: String,
+    pub signal: u8,
+    pub security: String,
+}
+
+/// Scan for nearby networks on `interface`. Entries are deduplicated by SSID
+/// (keeping the strongest access point) and sorted by descending signal.
+/// Hidden networks (empty SSID) are omitted; the form offers manual entry.
+pub async fn scan_networks(interface: &str, rescan: bool) -> Result<Vec<VisibleNetwork>> {
+    let output = nmcli(&[
+        "-t",
+        "-f",
+        "SSID,SIGNAL,SECURITY",
+        "device",
+        "wifi",
+        "list",
+        "ifname",
+        interface,
+        "--rescan",
+        if rescan { "yes" } else { "auto" },
+    ])
+    .await?;
+    Ok(parse_wifi_list(&output))
+}
+
+fn parse_wifi_list(output: &str) -> Vec<VisibleNetwork> {
+    let mut strongest: HashMap<String, VisibleNetwork> = HashMap::new();
+    for line in output.lines() {
+        let fields = split_terse_fields(line);
+        let [ssid, signal, security] = fields.as_slice() else {
+            continue;
+        };
+        if ssid.trim().is_empty() {
+            continue;
+        }
+        let Ok(signal) = signal.trim().parse::<u8>() else {
+            continue;
+        };
+        let security = match security.trim() {
+            "" | "--" => "open".to_string(),
+            other => other.to_string(),
+        };
+        match strongest.get(ssid.as_str()) {
+            Some(existing) if existing.signal >= signal => {}
+            _ => {
+                strongest.insert(
+                    ssid.clone(),
+                    VisibleNetwork {
+                        ssid: ssid.clone(),
+                        signal,
+                        security,
+                    },
+                );
+            }
+        }
+    }
+    let mut networks: Vec<VisibleNetwork> = strongest.into_values().collect();
+    networks.sort_by(|a, b| b.signal.cmp(&a.signal).then_with(|| a.ssid.cmp(&b.ssid)));
+    networks
+}
+
+/// Split one line of `nmcli -t` output into fields. Terse mode escapes `:` and
+/// `\` inside values with a backslash.
+fn split_terse_fields(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            ':' => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
 async fn list_connection_names() -> Result<HashSet<String>> {
     let output = nmcli(&["-t", "-f", "NAME", "connection", "show"]).await?;
     Ok(output
@@ -504,22 +624,24 @@ fn ensure_psk_rules(psk: &str) -> Result<()> {
 /// whole watcher loop indefinitely; a timeout turns that into a transient error.
 const NMCLI_TIMEOUT: Duration = Duration::from_secs(20);
 
+/// Hard cap on the quick probes, `ping` and `iw`, which answer in a second or two.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 async fn nmcli(args: &[&str]) -> Result<String> {
     debug!(command = %display_args(args), "running nmcli");
     let mut cmd = Command::new("nmcli");
     cmd.args(args);
-    cmd.stdout(Stdio::piped());
-    cmd.stderr(Stdio::piped());
-    let output = tokio::time::timeout(NMCLI_TIMEOUT, cmd.output())
-        .await
-        .map_err(|_| {
-            anyhow!(
+    let output = match process::output_with_timeout(&mut cmd, NMCLI_TIMEOUT).await {
+        Ok(output) => output,
+        Err(err) if err.kind() == io::ErrorKind::TimedOut => {
+            return Err(anyhow!(
                 "nmcli {} timed out after {}s",
                 display_args(args),
                 NMCLI_TIMEOUT.as_secs()
-            )
-        })?
-        .context("failed to execute nmcli")?;
+            ));
+        }
+        Err(err) => return Err(err).context("failed to execute nmcli"),
+    };
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     } else {
@@ -532,6 +654,26 @@ async fn nmcli(args: &[&str]) -> Result<String> {
     }
 }
 
+/// What went wrong with a failed join, in words for the person holding the
+/// phone. `None` when the nmcli error is not one of the common ones.
+pub fn describe_join_error(error: &str) -> Option<&'static str> {
+    let error = error.to_lowercase();
+    if error.contains("secrets were required")
+        || error.contains("802-1x supplicant")
+        || error.contains("wrong password")
+    {
+        Some("The Wi-Fi password was not accepted. Check it and try again.")
+    } else if error.contains("no network with ssid") {
+        Some("No network with that name is in range of the frame. Check the name and try again.")
+    } else if error.contains("timed out") || error.contains("timeout") {
+        Some(
+            "The network did not answer in time. Try again, or move the frame closer to the router.",
+        )
+    } else {
+        None
+    }
+}
+
 /// Returns `true` if at least one wireless client is currently associated with
 /// the AP on `interface`.
 ///
@@ -540,11 +682,9 @@ async fn nmcli(args: &[&str]) -> Result<String> {
 /// decisions fail-safe: we would rather probe unnecessarily than permanently
 /// suppress probing because `iw` is unavailable.
 pub async fn has_ap_clients(interface: &str) -> bool {
-    match Command::new("iw")
-        .args(["dev", interface, "station", "dump"])
-        .output()
-        .await
-    {
+    let mut iw = Command::new("iw");
+    iw.args(["dev", interface, "station", "dump"]);
+    match process::output_with_timeout(&mut iw, PROBE_TIMEOUT).await {
         Ok(output) => !output.stdout.is_empty(),
         Err(err) => {
             debug!(error = ?err, "iw station dump failed; assuming no AP clients");
@@ -571,3 +711,77 @@ fn display_args(args: &[&str]) -> String {
     }
     masked.join(" ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        VisibleNetwork, describe_join_error, parse_active_ssid, parse_wifi_list, split_terse_fields,
+    };
+
+    #[test]
+    fn terse_fields_unescape_colons_and_backslashes() {
+        assert_eq!(
+            split_terse_fields(r"Cafe\:Guest:72:WPA2"),
+            vec!["Cafe:Guest", "72", "WPA2"]
+        );
+        assert_eq!(
+            split_terse_fields(r"back\\slash:40:"),
+            vec![r"back\slash", "40", ""]
+        );
+    }
+
+    #[test]
+    fn wifi_list_dedupes_and_sorts_by_signal() {
+        let output = "\
+HomeNet:54:WPA2
+:80:WPA2
+Cafe\\:Guest:72:
+HomeNet:81:WPA2
+Neighbor:33:WPA1 WPA2
+HomeNet:12:WPA2
+";
+        let networks = parse_wifi_list(output);
+        assert_eq!(
+            networks,
+            vec![
+                VisibleNetwork {
+                    ssid: "HomeNet".into(),
+                    signal: 81,
+                    security: "WPA2".into(),
+                },
+                VisibleNetwork {
+                    ssid: "Cafe:Guest".into(),
+                    signal: 72,
+                    security: "open".into(),
+                },
+                VisibleNetwork {
+                    ssid: "Neighbor".into(),
+                    signal: 33,
+                    security: "WPA1 WPA2".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn active_ssid_is_the_associated_network() {
+        let output = "no:Neighbor\nyes:Cafe\\:Guest\nno:HomeNet\n";
+        assert_eq!(parse_active_ssid(output).as_deref(), Some("Cafe:Guest"));
+        assert_eq!(parse_active_ssid("no:HomeNet\n"), None);
+    }
+
+    #[test]
+    fn common_join_errors_get_friendly_descriptions() {
+        let wrong_password = "nmcli connection up pf-wifi-home failed: Error: Connection activation failed: (7) Secrets were required, but not provided.";
+        assert!(
+            describe_join_error(wrong_password)
+                .unwrap()
+                .contains("password")
+        );
+        let missing = "nmcli device wifi connect failed: Error: No network with SSID 'Home' found.";
+        assert!(describe_join_error(missing).unwrap().contains("in range"));
+        let slow = "nmcli connection up pf-wifi-home timed out after 20s";
+        assert!(describe_join_error(slow).unwrap().contains("in time"));
+        assert_eq!(describe_join_error("nmcli failed: permission denied"), None);
+    }
+}
@@ -1,6 +1,6 @@
 pub mod ui;
 
-use crate::config::{Config, OverlayConfig};
+use crate::config::{Config, OverlayConfig, QrCodeKind};
 use crate::hotspot;
 use anyhow::{Context, Result, bail};
 use std::ffi::OsStr;
@@ -22,6 +22,7 @@ pub struct OverlayRequest {
     pub password_file: PathBuf,
     pub ui_url: String,
     pub title: Option<String>,
+    pub qr_codes: Vec<QrCodeKind>,
 }
 
 impl OverlayRequest {
@@ -32,6 +33,7 @@ impl OverlayRequest {
             password_file: hotspot::hotspot_password_path(config),
             ui_url,
             title: None,
+            qr_codes: config.overlay.qr_codes.clone(),
         }
     }
 }
@@ -92,6 +94,10 @@ impl OverlayController {
                 parts.push("--title".to_string());
                 parts.push(title.clone());
             }
+            for code in &request.qr_codes {
+                parts.push("--qr".to_string());
+                parts.push(code.as_str().to_string());
+            }
             let cmdline = parts
                 .into_iter()
                 .map(|s| shell_escape(&s))
@@ -109,6 +115,9 @@ impl OverlayController {
             if let Some(title) = &request.title {
                 command.arg("--title").arg(title);
             }
+            for code in &request.qr_codes {
+                command.arg("--qr").arg(code.as_str());
+            }
             command.env("WINIT_APP_ID", &self.config.overlay_app_id);
             // Default to wayland-0 if WAYLAND_DISPLAY is not set
             if std::env::var_os("WAYLAND_DISPLAY").is_none() {
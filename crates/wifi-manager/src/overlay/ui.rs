@@ -19,6 +19,8 @@ use winit::event::WindowEvent;
 use winit::event_loop::{ActiveEventLoop, EventLoop};
 use winit::window::{Fullscreen, Window, WindowAttributes, WindowButtons, WindowId};
 
+use crate::config::QrCodeKind;
+
 #[derive(Args, Debug)]
 #[command(
     name = "overlay",
@@ -37,6 +39,9 @@ pub struct OverlayCli {
     /// Optional headline override.
     #[arg(long)]
     pub title: Option<String>,
+    /// QR code to show, left to right; repeat for more. Defaults to both.
+    #[arg(long = "qr", value_enum)]
+    pub qr_codes: Vec<QrCodeKind>,
 }
 
 pub fn run(args: OverlayCli) -> Result<()> {
@@ -45,9 +50,19 @@ pub fn run(args: OverlayCli) -> Result<()> {
         .password_file
         .parent()
         .unwrap_or_else(|| Path::new("."));
-    let wifi_qr_asset = load_qr_asset(&var_dir.join("wifi-qr.png"));
-    let portal_qr_asset = load_qr_asset(&var_dir.join("portal-qr.png"));
-    let content = OverlayContent::new(args, password, wifi_qr_asset, portal_qr_asset);
+    let kinds = if args.qr_codes.is_empty() {
+        vec![QrCodeKind::WifiJoin, QrCodeKind::Portal]
+    } else {
+        args.qr_codes.clone()
+    };
+    let qr_cards = kinds
+        .into_iter()
+        .map(|kind| QrCard {
+            kind,
+            asset: load_qr_asset(&var_dir.join(kind.file_name())),
+        })
+        .collect();
+    let content = OverlayContent::new(args, password, qr_cards);
     let font = load_font()?;
     let event_loop = EventLoop::new()?;
     let mut app = OverlayApp::new(font, content);
@@ -107,6 +122,12 @@ impl QrAsset {
     }
 }
 
+/// One QR code on the overlay; `asset` is `None` when its image is missing.
+struct QrCard {
+    kind: QrCodeKind,
+    asset: Option<QrAsset>,
+}
+
 fn load_qr_asset(path: &Path) -> Option<QrAsset> {
     let reader = ImageReader::open(path).ok()?;
     let image = reader.decode().ok()?;
@@ -184,19 +205,12 @@ struct OverlayContent {
     password: String,
     ui_url: String,
     footer: String,
-    /// QR code encoding `WIFI:T:WPA;S:<ssid>;P:<password>;;` — tap to join hotspot.
-    wifi_qr_asset: Option<QrAsset>,
-    /// QR code encoding the portal URL — tap to open setup page after joining.
-    portal_qr_asset: Option<QrAsset>,
+    /// QR codes in display order: the `WIFI:` join string and/or the portal URL.
+    qr_cards: Vec<QrCard>,
 }
 
 impl OverlayContent {
-    fn new(
-        cli: OverlayCli,
-        password: String,
-        wifi_qr_asset: Option<QrAsset>,
-        portal_qr_asset: Option<QrAsset>,
-    ) -> Self {
+    fn new(cli: OverlayCli, password: String, qr_cards: Vec<QrCard>) -> Self {
         let title = cli
             .title
             .unwrap_or_else(|| "Reconnect the photo frame to Wi-Fi".to_string());
@@ -209,8 +223,7 @@ impl OverlayContent {
             password,
             ui_url: cli.ui_url,
             footer,
-            wifi_qr_asset,
-            portal_qr_asset,
+            qr_cards,
         }
     }
 }
@@ -442,7 +455,7 @@ impl Renderer {
             layout.content_width,
             layout.typography_scale,
         );
-        cursor_y = self.draw_qr_pair_section(
+        cursor_y = self.draw_qr_section(
             &mut buffer,
             width,
             height,
@@ -552,10 +565,11 @@ impl Renderer {
         )
     }
 
-    /// Draw both QR codes side by side: left = Wi-Fi join (`WIFI:` URI),
-    /// right = portal URL.  Below the portal QR the URL is shown as plain text
-    /// so users who cannot scan QR codes can still type it manually.
-    fn draw_qr_pair_section(
+    /// Draw the configured QR codes side by side, each under a numbered
+    /// caption: the Wi-Fi join code (`WIFI:` URI) and/or the portal URL. The
+    /// URL is also shown as plain text below the cards so users who cannot
+    /// scan QR codes can still type it manually.
+    fn draw_qr_section(
         &self,
         buffer: &mut [u32],
         width: u32,
@@ -575,104 +589,70 @@ impl Renderer {
         let card_side = qr_side + 2.0 * card_pad;
         let card_radius = (14.0 * scale).clamp(8.0, 22.0);
 
-        let left_x = margin;
-        let right_x = margin + col_width + col_gap;
-
         let label_size = (22.0 * scale).clamp(15.0, 38.0);
         let label_gap = (8.0 * scale).clamp(5.0, 14.0);
 
-        // Draw step 3 label above the left (Wi-Fi join) QR.
-        let left_label_bottom = draw_paragraph(
-            buffer,
-            width,
-            height,
-            &self.font,
-            "3. Scan to join the hotspot:",
-            label_size,
-            Color::from_rgb(0xe8eef7),
-            left_x,
-            top,
-            col_width,
-            label_gap,
-        );
+        let cards = &self.content.qr_cards;
+        let column_x = |index: usize| margin + index as f32 * (col_width + col_gap);
 
-        // Draw step 4 label above the right (portal URL) QR.
-        let right_label_bottom = draw_paragraph(
-            buffer,
-            width,
-            height,
-            &self.font,
-            "4. Then scan to open setup:",
-            label_size,
-            Color::from_rgb(0xe8eef7),
-            right_x,
-            top,
-            col_width,
-            label_gap,
-        );
-
-        // Both QR cards start at the same y so they are vertically aligned.
-        let qr_top = left_label_bottom.max(right_label_bottom);
-
-        // ── Left card: Wi-Fi join QR ──────────────────────────────────────────
-        draw_rounded_rect(
-            buffer,
-            width,
-            height,
-            left_x,
-            qr_top,
-            left_x + card_side,
-            qr_top + card_side,
-            card_radius,
-            Color::from_rgb(0xffffff),
-        );
-        if let Some(qr) = &self.content.wifi_qr_asset {
-            draw_qr_asset(
+        // Captions first, so every card can start at the same y.
+        let mut qr_top = top;
+        for (index, card) in cards.iter().enumerate() {
+            let caption = qr_caption(card.kind, index);
+            let bottom = draw_paragraph(
                 buffer,
                 width,
                 height,
-                qr,
-                left_x + card_pad,
-                qr_top + card_pad,
-                qr_side,
-            );
-        } else {
-            draw_qr_fallback(
-                buffer, width, height, &self.font, left_x, qr_top, card_side, card_pad, scale,
+                &self.font,
+                &caption,
+                label_size,
+                Color::from_rgb(0xe8eef7),
+                column_x(index),
+                top,
+                col_width,
+                label_gap,
             );
+            qr_top = qr_top.max(bottom);
         }
 
-        // ── Right card: portal URL QR ─────────────────────────────────────────
-        draw_rounded_rect(
-            buffer,
-            width,
-            height,
-            right_x,
-            qr_top,
-            right_x + card_side,
-            qr_top + card_side,
-            card_radius,
-            Color::from_rgb(0xffffff),
-        );
-        if let Some(qr) = &self.content.portal_qr_asset {
-            draw_qr_asset(
+        for (index, card) in cards.iter().enumerate() {
+            let x = column_x(index);
+            draw_rounded_rect(
                 buffer,
                 width,
                 height,
-                qr,
-                right_x + card_pad,
-                qr_top + card_pad,
-                qr_side,
-            );
-        } else {
-            draw_qr_fallback(
-                buffer, width, height, &self.font, right_x, qr_top, card_side, card_pad, scale,
+                x,
+                qr_top,
+                x + card_side,
+                qr_top + card_side,
+                card_radius,
+                Color::from_rgb(0xffffff),
             );
+            if let Some(qr) = &card.asset {
+                draw_qr_asset(
+                    buffer,
+                    width,
+                    height,
+                    qr,
+                    x + card_pad,
+                    qr_top + card_pad,
+                    qr_side,
+                );
+            } else {
+                draw_qr_fallback(
+                    buffer, width, height, &self.font, x, qr_top, card_side, card_pad, scale,
+                );
+            }
         }
 
         let after_cards = qr_top + card_side + (14.0 * scale).clamp(8.0, 24.0);
 
-        // URL text below the right QR as a fallback for non-camera users.
+        // URL text below the portal QR (or the last card without one) as a
+        // fallback for non-camera users.
+        let url_column = cards
+            .iter()
+            .position(|card| card.kind == QrCodeKind::Portal)
+            .unwrap_or(cards.len().saturating_sub(1));
         let url_bottom = draw_paragraph(
             buffer,
             width,
@@ -681,7 +661,7 @@ impl Renderer {
             &format!("Or type: {}", self.content.ui_url),
             (18.0 * scale).clamp(12.0, 30.0),
             Color::from_rgb(0x7a8fa5),
-            right_x,
+            column_x(url_column),
             after_cards,
             col_width,
             (6.0 * scale).clamp(4.0, 12.0),
@@ -895,6 +875,16 @@ fn draw_highlight(
 
 /// Draw a "QR unavailable" placeholder inside a white card when the QR image
 /// could not be loaded from disk.
+/// Numbered caption above a QR card; steps 1 and 2 are the manual join.
+fn qr_caption(kind: QrCodeKind, index: usize) -> String {
+    let step = index + 3;
+    match (kind, index) {
+        (QrCodeKind::WifiJoin, _) => format!("{step}. Scan to join the hotspot:"),
+        (QrCodeKind::Portal, 0) => format!("{step}. After joining, scan to open setup:"),
+        (QrCodeKind::Portal, _) => format!("{step}. Then scan to open setup:"),
+    }
+}
+
 fn draw_qr_fallback(
     buffer: &mut [u32],
     width: u32,
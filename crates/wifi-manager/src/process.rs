@@ -0,0 +1,79 @@
+use std::io;
+use std::process::{Output, Stdio};
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Runs `command` to completion and collects its output like
+/// [`Command::output`], but kills it once it has run for `limit`.
+///
+/// NetworkManager's D-Bus can wedge and a ping can hang on a dead route;
+/// without a limit either would stall the watcher past its systemd watchdog.
+/// A timeout is reported as [`io::ErrorKind::TimedOut`].
+pub async fn output_with_timeout(command: &mut Command, limit: Duration) -> io::Result<Output> {
+    command.stdin(Stdio::null());
+    command.stdout(Stdio::piped());
+    command.stderr(Stdio::piped());
+    // Dropping the unfinished wait below sends SIGKILL; tokio reaps the child.
+    command.kill_on_drop(true);
+    let child = command.spawn()?;
+    match tokio::time::timeout(limit, child.wait_with_output()).await {
+        Ok(output) => output,
+        Err(_) => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("timed out after {:.1}s", limit.as_secs_f32()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn output_is_collected_within_the_limit() {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg("echo out; echo err >&2; exit 3");
+        let output = output_with_timeout(&mut command, Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(output.stdout, b"out\n");
+        assert_eq!(output.stderr, b"err\n");
+        assert_eq!(output.status.code(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn a_hung_child_is_killed_at_the_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("pid");
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(format!("echo $$ > '{}'; exec sleep 30", pid_file.display()));
+
+        let started = Instant::now();
+        let err = output_with_timeout(&mut command, Duration::from_millis(500))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(10));
+
+        // The kill is immediate; reaping happens in the background, so a
+        // zombie counts as gone.
+        let pid = fs::read_to_string(&pid_file).unwrap();
+        let stat = format!("/proc/{}/stat", pid.trim());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let running = fs::read_to_string(&stat).is_ok_and(|stat| {
+                let state = stat.rsplit(')').next().unwrap_or_default().trim_start();
+                !state.starts_with('Z')
+            });
+            if !running {
+                break;
+            }
+            assert!(Instant::now() < deadline, "sleeping child still running");
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+}
@@ -1,45 +1,106 @@
-use crate::config::Config;
+use crate::config::{Config, QrCodeKind};
 use crate::hotspot::hotspot_password_path;
 use anyhow::{Context, Result};
-use image::Luma;
-use qrcode::QrCode;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tracing::warn;
 
-/// Generate a QR code that encodes Wi-Fi join credentials in the `WIFI:` URI
-/// format recognised by iOS 11+ (camera app) and Android 10+ (camera/settings).
-///
-/// Scanning pops up a one-tap "Join Network" prompt — no manual password
-/// entry needed.  The portal URL QR is shown separately so the user can scan
-/// to join the hotspot and then scan to open the setup page.
-///
-/// Format: `WIFI:T:WPA;S:<ssid>;P:<password>;;`. The SSID and password are
-/// backslash-escaped (`\ ; , : "`) so a custom hotspot SSID or password
-/// containing those characters can't corrupt the encoded URI.
-pub fn generate(config: &Config) -> Result<()> {
-    let password_path = hotspot_password_path(config);
-    let password = fs::read_to_string(&password_path).with_context(|| {
-        format!(
-            "failed to read hotspot password from {}",
-            password_path.display()
-        )
-    })?;
-    let password = password.trim();
+/// What a generated QR code encodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QrPayload {
+    /// Wi-Fi join credentials in the `WIFI:` URI format recognised by iOS 11+
+    /// (camera app) and Android 10+ (camera/settings). Scanning pops up a
+    /// one-tap "Join Network" prompt — no manual password entry needed.
+    WifiJoin { ssid: String, password: String },
+    /// The portal setup URL, so users can open the Wi-Fi configuration page
+    /// without typing the address manually.
+    Portal { url: String },
+}
+
+impl QrPayload {
+    /// Builds the payload for `kind` from the config and the current hotspot
+    /// password on disk.
+    pub fn for_kind(config: &Config, kind: QrCodeKind) -> Result<Self> {
+        match kind {
+            QrCodeKind::WifiJoin => {
+                let password_path = hotspot_password_path(config);
+                let password = fs::read_to_string(&password_path).with_context(|| {
+                    format!(
+                        "failed to read hotspot password from {}",
+                        password_path.display()
+                    )
+                })?;
+                Ok(Self::WifiJoin {
+                    ssid: config.hotspot.ssid.clone(),
+                    password: password.trim().to_string(),
+                })
+            }
+            QrCodeKind::Portal => Ok(Self::Portal {
+                url: format!("http://{}:{}/", config.hotspot.ipv4_addr, config.ui.port),
+            }),
+        }
+    }
+
+    /// The text the QR code carries.
+    ///
+    /// Wi-Fi join format: `WIFI:T:WPA;S:<ssid>;P:<password>;;`. The SSID and
+    /// password are backslash-escaped (`\ ; , : "`) so a custom hotspot SSID or
+    /// password containing those characters can't corrupt the encoded URI.
+    pub fn encode(&self) -> String {
+        match self {
+            Self::WifiJoin { ssid, password } => format!(
+                "WIFI:T:WPA;S:{ssid};P:{password};;",
+                ssid = escape_wifi_uri_field(ssid),
+                password = escape_wifi_uri_field(password),
+            ),
+            Self::Portal { url } => url.clone(),
+        }
+    }
 
-    let wifi_uri = format!(
-        "WIFI:T:WPA;S:{ssid};P:{password};;",
-        ssid = escape_wifi_uri_field(&config.hotspot.ssid),
-        password = escape_wifi_uri_field(password),
-    );
+    fn describe(&self) -> &'static str {
+        match self {
+            Self::WifiJoin { .. } => "Wi-Fi join",
+            Self::Portal { .. } => "portal URL",
+        }
+    }
+}
 
-    let code = QrCode::new(wifi_uri.as_bytes()).context("failed to generate Wi-Fi join QR code")?;
-    let image = code.render::<Luma<u8>>().min_dimensions(256, 256).build();
-    let path = wifi_qr_path(config);
+/// Writes the QR codes listed in `overlay.qr-codes` and removes the images of
+/// the ones left out, so the overlay never shows a stale code. Every code is
+/// attempted; the first failure is returned.
+pub fn generate(config: &Config) -> Result<()> {
+    let mut result = Ok(());
+    for kind in [QrCodeKind::WifiJoin, QrCodeKind::Portal] {
+        let path = qr_path_for(config, kind);
+        if !config.overlay.qr_codes.contains(&kind) {
+            if let Err(err) = fs::remove_file(&path)
+                && err.kind() != std::io::ErrorKind::NotFound
+            {
+                warn!(path = %path.display(), error = %err, "failed to remove unused QR code");
+            }
+            continue;
+        }
+        let written = QrPayload::for_kind(config, kind)
+            .and_then(|payload| write_qr_png(config, &payload, &path));
+        if let Err(err) = written {
+            warn!(code = kind.as_str(), error = ?err, "failed to write QR code asset");
+            if result.is_ok() {
+                result = Err(err);
+            }
+        }
+    }
+    result
+}
+
+fn write_qr_png(config: &Config, payload: &QrPayload, path: &Path) -> Result<()> {
+    let what = payload.describe();
+    let image = qr_render::render(&payload.encode(), 256)
+        .with_context(|| format!("failed to generate {what} QR code"))?;
     fs::create_dir_all(&config.var_dir)
         .with_context(|| format!("failed to create var dir at {}", config.var_dir.display()))?;
     image
-        .save(&path)
-        .with_context(|| format!("failed to write Wi-Fi join QR code to {}", path.display()))?;
+        .save(path)
+        .with_context(|| format!("failed to write {what} QR code to {}", path.display()))?;
     Ok(())
 }
 
@@ -57,27 +118,13 @@ fn escape_wifi_uri_field(value: &str) -> String {
     out
 }
 
-/// Generate a QR code that encodes the portal setup URL so users can scan to
-/// open the Wi-Fi configuration page without typing the address manually.
-pub fn generate_portal_qr(config: &Config) -> Result<()> {
-    let url = format!("http://{}:{}/", config.hotspot.ipv4_addr, config.ui.port);
-    let code = QrCode::new(url.as_bytes()).context("failed to generate portal URL QR code")?;
-    let image = code.render::<Luma<u8>>().min_dimensions(256, 256).build();
-    let path = portal_qr_path(config);
-    fs::create_dir_all(&config.var_dir)
-        .with_context(|| format!("failed to create var dir at {}", config.var_dir.display()))?;
-    image
-        .save(&path)
-        .with_context(|| format!("failed to write portal QR code to {}", path.display()))?;
-    Ok(())
+/// Where the image for `kind` is written; the overlay reads it from here.
+pub fn qr_path_for(config: &Config, kind: QrCodeKind) -> PathBuf {
+    config.var_dir.join(kind.file_name())
 }
 
 pub fn wifi_qr_path(config: &Config) -> PathBuf {
-    config.var_dir.join("wifi-qr.png")
-}
-
-pub fn portal_qr_path(config: &Config) -> PathBuf {
-    config.var_dir.join("portal-qr.png")
+    qr_path_for(config, QrCodeKind::WifiJoin)
 }
 
 /// Kept for callers that previously used `qr_path` — now an alias for
@@ -86,3 +133,37 @@ pub fn portal_qr_path(config: &Config) -> PathBuf {
 pub fn qr_path(config: &Config) -> PathBuf {
     wifi_qr_path(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{QrPayload, escape_wifi_uri_field};
+
+    #[test]
+    fn escapes_special_characters_in_wifi_fields() {
+        assert_eq!(escape_wifi_uri_field("plain-words"), "plain-words");
+        assert_eq!(escape_wifi_uri_field("a;b,c:d"), r"a\;b\,c\:d");
+        assert_eq!(escape_wifi_uri_field(r"back\slash"), r"back\\slash");
+        assert_eq!(escape_wifi_uri_field(r#"say "hi""#), r#"say \"hi\""#);
+        assert_eq!(escape_wifi_uri_field(r"\;"), r"\\\;");
+    }
+
+    #[test]
+    fn wifi_join_payload_uses_the_standard_format() {
+        let payload = QrPayload::WifiJoin {
+            ssid: "Frame;Setup".to_string(),
+            password: "pass:word,1\\".to_string(),
+        };
+        assert_eq!(
+            payload.encode(),
+            r"WIFI:T:WPA;S:Frame\;Setup;P:pass\:word\,1\\;;"
+        );
+    }
+
+    #[test]
+    fn portal_payload_is_the_url() {
+        let payload = QrPayload::Portal {
+            url: "http://192.168.4.1:8080/".to_string(),
+        };
+        assert_eq!(payload.encode(), "http://192.168.4.1:8080/");
+    }
+}
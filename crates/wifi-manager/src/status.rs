@@ -1,5 +1,5 @@
 use crate::config::Config;
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, ensure};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::fs::OpenOptions;
@@ -31,6 +31,57 @@ pub struct AttemptRecord {
     pub error: Option<String>,
 }
 
+/// Version of the [`WifiStatus`] document. Bump when a field changes meaning
+/// or disappears; readers refuse documents newer than they understand.
+pub const WIFI_STATUS_SCHEMA_VERSION: u32 = 1;
+
+/// Connectivity summary published for other services (buttond, dashboards)
+/// on every watcher state change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WifiStatus {
+    pub schema_version: u32,
+    pub state: Connectivity,
+    /// Network the frame is joined to while online.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ssid: Option<String>,
+    /// The frame's address: the LAN address while online, the hotspot
+    /// address while the hotspot is up.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hotspot: Option<HotspotStatus>,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Connectivity {
+    /// Joined to the home network.
+    Online,
+    /// Link lost, but the recovery hotspot is not up (grace period or retry
+    /// backoff).
+    Degraded,
+    /// Recovery hotspot is up and waiting for credentials.
+    Hotspot,
+}
+
+impl Connectivity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Online => "online",
+            Self::Degraded => "degraded",
+            Self::Hotspot => "hotspot",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotspotStatus {
+    pub ssid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimeStateRecord {
     pub timestamp: String,
@@ -86,6 +137,45 @@ pub fn write_runtime_state(config: &Config, record: &RuntimeStateRecord) -> Resu
     write_json_with_mode(&runtime_state_path(config), record, 0o644)
 }
 
+/// The status document holds the hotspot password, so it is readable by the
+/// kiosk group but not by everyone.
+pub fn write_wifi_status(config: &Config, status: &WifiStatus) -> Result<()> {
+    write_json_with_mode(&config.status_file, status, 0o640)
+}
+
+pub fn read_wifi_status(path: &Path) -> Result<Option<WifiStatus>> {
+    let Some(status) = read_json_optional::<WifiStatus>(path)? else {
+        return Ok(None);
+    };
+    ensure!(
+        status.schema_version <= WIFI_STATUS_SCHEMA_VERSION,
+        "{} uses schema version {}; this build understands up to {}",
+        path.display(),
+        status.schema_version,
+        WIFI_STATUS_SCHEMA_VERSION
+    );
+    Ok(Some(status))
+}
+
+/// Human-readable rendering for `wifi-manager status`.
+pub fn format_wifi_status(status: &WifiStatus) -> String {
+    let mut lines = vec![format!("state:     {}", status.state.as_str())];
+    if let Some(ssid) = &status.ssid {
+        lines.push(format!("ssid:      {ssid}"));
+    }
+    if let Some(ip) = &status.ip_address {
+        lines.push(format!("ip:        {ip}"));
+    }
+    if let Some(hotspot) = &status.hotspot {
+        lines.push(format!("hotspot:   {}", hotspot.ssid));
+        if let Some(password) = &hotspot.password {
+            lines.push(format!("password:  {password}"));
+        }
+    }
+    lines.push(format!("updated:   {}", status.timestamp));
+    lines.join("\n")
+}
+
 pub fn last_ssid_path(config: &Config) -> PathBuf {
     config.var_dir.join("last-ssid.txt")
 }
@@ -203,9 +293,10 @@ fn read_json_optional<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Optio
 #[cfg(test)]
 mod tests {
     use super::{
-        AttemptRecord, ProvisionRequest, RuntimeStateRecord, read_last_attempt, read_request,
-        read_runtime_state, redact_ssid, remove_request, write_last_attempt, write_request,
-        write_runtime_state,
+        AttemptRecord, Connectivity, HotspotStatus, ProvisionRequest, RuntimeStateRecord,
+        WIFI_STATUS_SCHEMA_VERSION, WifiStatus, format_wifi_status, read_last_attempt,
+        read_request, read_runtime_state, read_wifi_status, redact_ssid, remove_request,
+        write_last_attempt, write_request, write_runtime_state, write_wifi_status,
     };
     use crate::config::Config;
     use tempfile::tempdir;
@@ -272,4 +363,90 @@ mod tests {
             "RecoveryHotspotActive"
         );
     }
+
+    fn hotspot_status() -> WifiStatus {
+        WifiStatus {
+            schema_version: WIFI_STATUS_SCHEMA_VERSION,
+            state: Connectivity::Hotspot,
+            ssid: None,
+            ip_address: Some("192.168.4.1".to_string()),
+            hotspot: Some(HotspotStatus {
+                ssid: "PhotoFrame-Setup".to_string(),
+                password: Some("apple-river-stone".to_string()),
+            }),
+            timestamp: "2026-01-01T00:00:02Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn wifi_status_is_replaced_atomically() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempdir().expect("tempdir");
+        let mut cfg: Config = serde_yaml::from_str("{}").expect("parse");
+        cfg.status_file = tmp.path().join("run/wifi-status.json");
+
+        write_wifi_status(&cfg, &hotspot_status()).expect("write hotspot");
+        let online = WifiStatus {
+            state: Connectivity::Online,
+            ssid: Some("HomeNet".to_string()),
+            ip_address: Some("192.168.1.20".to_string()),
+            hotspot: None,
+            ..hotspot_status()
+        };
+        write_wifi_status(&cfg, &online).expect("write online");
+
+        let read_back = read_wifi_status(&cfg.status_file)
+            .expect("read")
+            .expect("present");
+        assert_eq!(read_back, online);
+        // The temp file was renamed over the target; nothing is left behind.
+        let entries: Vec<_> = std::fs::read_dir(tmp.path().join("run"))
+            .expect("list")
+            .map(|entry| entry.expect("entry").file_name())
+            .collect();
+        assert_eq!(entries, vec!["wifi-status.json"]);
+        let mode = std::fs::metadata(&cfg.status_file)
+            .expect("metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+
+    #[test]
+    fn wifi_status_parses_the_published_schema() {
+        let tmp = tempdir().expect("tempdir");
+        let path = tmp.path().join("wifi-status.json");
+        assert!(read_wifi_status(&path).expect("missing is fine").is_none());
+
+        std::fs::write(
+            &path,
+            r#"{
+  "schema_version": 1,
+  "state": "hotspot",
+  "ip_address": "192.168.4.1",
+  "hotspot": { "ssid": "PhotoFrame-Setup", "password": "apple-river-stone" },
+  "timestamp": "2026-01-01T00:00:02Z"
+}"#,
+        )
+        .expect("write");
+        let status = read_wifi_status(&path).expect("read").expect("present");
+        assert_eq!(status, hotspot_status());
+        assert_eq!(
+            format_wifi_status(&status),
+            "state:     hotspot\n\
+             ip:        192.168.4.1\n\
+             hotspot:   PhotoFrame-Setup\n\
+             password:  apple-river-stone\n\
+             updated:   2026-01-01T00:00:02Z"
+        );
+
+        std::fs::write(
+            &path,
+            r#"{"schema_version": 2, "state": "online", "timestamp": "x"}"#,
+        )
+        .expect("write");
+        let err = read_wifi_status(&path).unwrap_err();
+        assert!(err.to_string().contains("schema version 2"), "{err}");
+    }
 }
@@ -0,0 +1,122 @@
+use sd_notify::NotifyState;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::debug;
+
+/// Service notifications for `wifi-manager watch` under `Type=notify`:
+/// readiness once the first connectivity probe is done, watchdog pings while
+/// the loop keeps turning, and a stopping notice on shutdown. Outside systemd
+/// (`NOTIFY_SOCKET` unset) every call is a no-op.
+#[derive(Debug, Clone, Copy)]
+pub struct Notifier {
+    enabled: bool,
+    /// Half of `WatchdogSec`, when systemd asked this process for pings.
+    ping_interval: Option<Duration>,
+}
+
+impl Notifier {
+    pub fn from_env() -> Self {
+        Self::from_vars(
+            std::env::var_os("NOTIFY_SOCKET").is_some_and(|socket| !socket.is_empty()),
+            std::env::var("WATCHDOG_USEC").ok().as_deref(),
+            std::env::var("WATCHDOG_PID").ok().as_deref(),
+            std::process::id(),
+        )
+    }
+
+    fn from_vars(
+        notify_socket: bool,
+        watchdog_usec: Option<&str>,
+        watchdog_pid: Option<&str>,
+        pid: u32,
+    ) -> Self {
+        // A watchdog set up for another process (say, a parent shell) isn't ours.
+        let ours = watchdog_pid.is_none_or(|watchdog_pid| watchdog_pid.parse() == Ok(pid));
+        let ping_interval = watchdog_usec
+            .filter(|_| notify_socket && ours)
+            .and_then(|usec| usec.parse::<u64>().ok())
+            .filter(|usec| *usec > 0)
+            .map(|usec| Duration::from_micros(usec) / 2);
+        Self {
+            enabled: notify_socket,
+            ping_interval,
+        }
+    }
+
+    pub fn ping_interval(&self) -> Option<Duration> {
+        self.ping_interval
+    }
+
+    /// `READY=1`; returns whether it was sent.
+    pub fn ready(&self) -> bool {
+        self.send(NotifyState::Ready)
+    }
+
+    /// `STOPPING=1`; returns whether it was sent.
+    pub fn stopping(&self) -> bool {
+        self.send(NotifyState::Stopping)
+    }
+
+    /// `WATCHDOG=1` when the watchdog is on; returns whether it was sent.
+    pub fn ping(&self) -> bool {
+        self.ping_interval.is_some() && self.send(NotifyState::Watchdog)
+    }
+
+    /// Sleeps for `duration`, pinging the watchdog along the way so a long
+    /// gap between polls doesn't look like a hang.
+    pub async fn idle(&self, duration: Duration) {
+        let Some(interval) = self.ping_interval else {
+            sleep(duration).await;
+            return;
+        };
+        let deadline = Instant::now() + duration;
+        loop {
+            self.ping();
+            let now = Instant::now();
+            if now >= deadline {
+                return;
+            }
+            sleep((deadline - now).min(interval)).await;
+        }
+    }
+
+    fn send(&self, state: NotifyState) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        match sd_notify::notify(false, &[state]) {
+            Ok(()) => true,
+            Err(err) => {
+                debug!(error = ?err, "systemd notification failed");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notify_calls_are_skipped_without_notify_socket() {
+        let notifier = Notifier::from_vars(false, Some("30000000"), None, 42);
+        assert_eq!(notifier.ping_interval(), None);
+        assert!(!notifier.ready());
+        assert!(!notifier.ping());
+        assert!(!notifier.stopping());
+    }
+
+    #[test]
+    fn pings_come_at_half_the_watchdog_interval() {
+        let notifier = Notifier::from_vars(true, Some("30000000"), Some("42"), 42);
+        assert_eq!(notifier.ping_interval(), Some(Duration::from_secs(15)));
+
+        let elsewhere = Notifier::from_vars(true, Some("30000000"), Some("7"), 42);
+        assert_eq!(elsewhere.ping_interval(), None, "watchdog of another pid");
+        let unset = Notifier::from_vars(true, None, None, 42);
+        assert_eq!(unset.ping_interval(), None);
+        let zero = Notifier::from_vars(true, Some("0"), None, 42);
+        assert_eq!(zero.ping_interval(), None);
+    }
+}
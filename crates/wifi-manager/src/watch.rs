@@ -4,9 +4,11 @@ use crate::nm;
 use crate::overlay::{OverlayController, overlay_request};
 use crate::qr;
 use crate::status::{
-    AttemptRecord, ProvisionRequest, RuntimeStateRecord, now_rfc3339, read_request, redact_ssid,
-    remove_request, write_last_attempt, write_last_ssid, write_runtime_state,
+    AttemptRecord, Connectivity, HotspotStatus, ProvisionRequest, RuntimeStateRecord,
+    WIFI_STATUS_SCHEMA_VERSION, WifiStatus, now_rfc3339, read_request, redact_ssid, remove_request,
+    write_last_attempt, write_last_ssid, write_runtime_state, write_wifi_status,
 };
+use crate::systemd::Notifier;
 use anyhow::{Context, Result};
 use rand::Rng;
 use std::fs;
@@ -38,6 +40,16 @@ pub async fn run(config: Config, config_path: PathBuf) -> Result<()> {
     let mut backoff_until: Option<Instant> = None;
     let mut recovery: Option<ActiveRecovery> = None;
     let mut overlay = OverlayController::new(config.overlay.clone());
+    let mut link = LinkHysteresis::new(config.failure_threshold, config.success_threshold);
+    let mut published: Option<Connectivity> = None;
+    let notifier = Notifier::from_env();
+    let mut ready = false;
+    if let Some(interval) = notifier.ping_interval() {
+        info!(
+            ping_interval_ms = interval.as_millis() as u64,
+            "systemd watchdog enabled"
+        );
+    }
 
     if config.photo_app.app_id != config.overlay.photo_app_id {
         warn!(
@@ -58,11 +70,13 @@ pub async fn run(config: Config, config_path: PathBuf) -> Result<()> {
         tokio::select! {
             _ = sigterm.recv() => {
                 info!("received SIGTERM; shutting down");
+                notifier.stopping();
                 shutdown_recovery(&config, &mut recovery, &mut overlay).await;
                 return Ok(());
             }
             _ = sigint.recv() => {
                 info!("received SIGINT; shutting down");
+                notifier.stopping();
                 shutdown_recovery(&config, &mut recovery, &mut overlay).await;
                 return Ok(());
             }
@@ -74,10 +88,22 @@ pub async fn run(config: Config, config_path: PathBuf) -> Result<()> {
                         false
                     }
                 };
+                link.observe(online);
+                if !ready {
+                    // Startup is done once the first probe has an answer.
+                    notifier.ready();
+                    ready = true;
+                }
 
                 match state {
                     WatchState::Online => {
-                        if !online {
+                        if !online && !link.confirmed_offline() {
+                            info!(
+                                failures = link.consecutive_failures(),
+                                threshold = config.failure_threshold,
+                                "connectivity poll failed; waiting for consecutive failures"
+                            );
+                        } else if !online {
                             offline_since = Some(Instant::now());
                             transition_state(
                                 &config,
@@ -156,7 +182,7 @@ pub async fn run(config: Config, config_path: PathBuf) -> Result<()> {
                                     );
                                 }
                             }
-                        } else if online {
+                        } else if link.confirmed_online() {
                             finalize_recovery(
                                 &config,
                                 &mut recovery,
@@ -175,6 +201,13 @@ pub async fn run(config: Config, config_path: PathBuf) -> Result<()> {
                                 None,
                             );
                         } else {
+                            if online {
+                                info!(
+                                    successes = link.consecutive_successes(),
+                                    threshold = config.success_threshold,
+                                    "link seen during recovery; waiting for consecutive successes"
+                                );
+                            }
                             let request = match read_request(&config) {
                                 Ok(value) => value,
                                 Err(err) => {
@@ -289,7 +322,7 @@ pub async fn run(config: Config, config_path: PathBuf) -> Result<()> {
                         );
                     }
                     WatchState::RecoveryBackoff => {
-                        if online {
+                        if link.confirmed_online() {
                             finalize_recovery(
                                 &config,
                                 &mut recovery,
@@ -352,9 +385,17 @@ pub async fn run(config: Config, config_path: PathBuf) -> Result<()> {
                     }
                 }
 
+                let connectivity = connectivity_for(state, recovery.is_some());
+                if published != Some(connectivity) {
+                    publish_status(&config, connectivity).await;
+                    published = Some(connectivity);
+                }
+
                 let jitter_ms: u64 = rand::rng().random_range(0..500);
                 let base = Duration::from_secs(config.check_interval_sec);
-                sleep(base + Duration::from_millis(jitter_ms)).await;
+                // Pings only between polls: a poll stuck on a subprocess
+                // stops them and lets systemd restart the watcher.
+                notifier.idle(base + Duration::from_millis(jitter_ms)).await;
             } => {}
         }
     }
@@ -381,6 +422,58 @@ impl WatchState {
     }
 }
 
+/// Debounces connectivity polls: the link only counts as lost after
+/// `failure_threshold` failed polls in a row, and as back after
+/// `success_threshold` good polls in a row, so a brief router reboot does not
+/// stop the photo app or flap the recovery hotspot.
+#[derive(Debug)]
+struct LinkHysteresis {
+    failure_threshold: u32,
+    success_threshold: u32,
+    consecutive_failures: u32,
+    consecutive_successes: u32,
+}
+
+impl LinkHysteresis {
+    fn new(failure_threshold: u32, success_threshold: u32) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            success_threshold: success_threshold.max(1),
+            consecutive_failures: 0,
+            consecutive_successes: 0,
+        }
+    }
+
+    /// Records one connectivity poll.
+    fn observe(&mut self, online: bool) {
+        if online {
+            self.consecutive_successes = self.consecutive_successes.saturating_add(1);
+            self.consecutive_failures = 0;
+        } else {
+            self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+            self.consecutive_successes = 0;
+        }
+    }
+
+    fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    fn consecutive_successes(&self) -> u32 {
+        self.consecutive_successes
+    }
+
+    /// Enough failed polls in a row to treat the link as lost.
+    fn confirmed_offline(&self) -> bool {
+        self.consecutive_failures >= self.failure_threshold
+    }
+
+    /// Enough good polls in a row to treat the link as restored.
+    fn confirmed_online(&self) -> bool {
+        self.consecutive_successes >= self.success_threshold
+    }
+}
+
 struct ActiveRecovery {
     ui_process: Child,
     last_reconnect_probe: Instant,
@@ -432,6 +525,60 @@ fn transition_state(
     }
 }
 
+/// Collapses the watcher's internal states into what other services care
+/// about: are we online, and if not, is the hotspot up.
+fn connectivity_for(state: WatchState, hotspot_up: bool) -> Connectivity {
+    match state {
+        WatchState::Online => Connectivity::Online,
+        WatchState::RecoveryHotspotActive | WatchState::ProvisioningAttempt => {
+            Connectivity::Hotspot
+        }
+        WatchState::OfflineGrace | WatchState::RecoveryBackoff if hotspot_up => {
+            Connectivity::Hotspot
+        }
+        WatchState::OfflineGrace | WatchState::RecoveryBackoff => Connectivity::Degraded,
+    }
+}
+
+async fn publish_status(config: &Config, connectivity: Connectivity) {
+    let mut status = WifiStatus {
+        schema_version: WIFI_STATUS_SCHEMA_VERSION,
+        state: connectivity,
+        ssid: None,
+        ip_address: None,
+        hotspot: None,
+        timestamp: now_rfc3339().unwrap_or_else(|_| "unknown".to_string()),
+    };
+    match connectivity {
+        Connectivity::Online => {
+            status.ssid = nm::active_ssid(&config.interface)
+                .await
+                .inspect_err(|err| debug!(error = ?err, "failed to read active SSID"))
+                .ok()
+                .flatten();
+            status.ip_address = nm::ipv4_address(&config.interface)
+                .await
+                .inspect_err(|err| debug!(error = ?err, "failed to read IPv4 address"))
+                .ok()
+                .flatten();
+        }
+        Connectivity::Hotspot => {
+            status.ip_address = Some(config.hotspot.ipv4_addr.to_string());
+            status.hotspot = Some(HotspotStatus {
+                ssid: config.hotspot.ssid.clone(),
+                password: fs::read_to_string(hotspot::hotspot_password_path(config))
+                    .ok()
+                    .map(|password| password.trim().to_string())
+                    .filter(|password| !password.is_empty()),
+            });
+        }
+        Connectivity::Degraded => {}
+    }
+    if let Err(err) = write_wifi_status(config, &status) {
+        warn!(error = ?err, "failed to publish wifi status");
+    }
+}
+
 async fn check_online_link(config: &Config) -> Result<bool> {
     let connected =
         nm::connected_to_infrastructure(&config.interface, &config.hotspot.connection_id).await?;
@@ -470,12 +617,9 @@ async fn enter_recovery(
         "hotspot session password generated"
     );
 
-    if let Err(err) = qr::generate(config) {
-        warn!(error = ?err, "failed to write Wi-Fi join QR code asset");
-    }
-    if let Err(err) = qr::generate_portal_qr(config) {
-        warn!(error = ?err, "failed to write portal URL QR code asset");
-    }
+    // Failures are logged per code inside `generate`; the overlay falls back
+    // to text for any code that is missing.
+    let _ = qr::generate(config);
 
     let child = spawn_ui(config_path).await?;
 
@@ -631,12 +775,10 @@ async fn apply_provision_request(
     sleep(Duration::from_secs(NM_SETTLE_SECS)).await;
 
     if let Err(err) = nm::activate_connection(&connection_id).await {
-        record_attempt_error(
-            config,
-            request,
-            "Failed to activate Wi-Fi connection.",
-            err.to_string(),
-        );
+        let error = err.to_string();
+        let message =
+            nm::describe_join_error(&error).unwrap_or("Failed to activate Wi-Fi connection.");
+        record_attempt_error(config, request, message, error);
         if let Err(err) = nm::delete_connection(&connection_id).await {
             warn!(error = ?err, connection = %connection_id, "failed to remove unsuccessful Wi-Fi profile");
         }
@@ -816,10 +958,36 @@ async fn spawn_ui(config_path: &PathBuf) -> Result<Child> {
 
 #[cfg(test)]
 mod tests {
-    use super::{WatchState, should_probe_at};
+    use super::{LinkHysteresis, WatchState, connectivity_for, should_probe_at};
     use crate::config::Config;
+    use crate::status::Connectivity;
     use std::time::{Duration, Instant};
 
+    #[test]
+    fn connectivity_reports_the_hotspot_whenever_it_is_up() {
+        assert_eq!(
+            connectivity_for(WatchState::Online, false),
+            Connectivity::Online
+        );
+        assert_eq!(
+            connectivity_for(WatchState::OfflineGrace, false),
+            Connectivity::Degraded
+        );
+        assert_eq!(
+            connectivity_for(WatchState::ProvisioningAttempt, true),
+            Connectivity::Hotspot
+        );
+        // A failed provisioning attempt backs off with the hotspot still up.
+        assert_eq!(
+            connectivity_for(WatchState::RecoveryBackoff, true),
+            Connectivity::Hotspot
+        );
+        assert_eq!(
+            connectivity_for(WatchState::RecoveryBackoff, false),
+            Connectivity::Degraded
+        );
+    }
+
     #[test]
     fn state_labels_are_stable() {
         assert_eq!(WatchState::Online.as_str(), "Online");
@@ -837,4 +1005,47 @@ mod tests {
         let earlier = Instant::now() - Duration::from_secs(cfg.recovery_reconnect_probe_sec + 1);
         assert!(should_probe_at(&cfg, earlier));
     }
+
+    #[test]
+    fn link_is_lost_only_after_consecutive_failures() {
+        let mut link = LinkHysteresis::new(3, 2);
+        for online in [false, false] {
+            link.observe(online);
+            assert!(!link.confirmed_offline());
+        }
+        // A good poll resets the streak: a 10 s router reboot never trips it.
+        link.observe(true);
+        link.observe(false);
+        link.observe(false);
+        assert!(!link.confirmed_offline());
+        link.observe(false);
+        assert!(link.confirmed_offline());
+        assert_eq!(link.consecutive_failures(), 3);
+    }
+
+    #[test]
+    fn link_is_restored_only_after_consecutive_successes() {
+        let mut link = LinkHysteresis::new(3, 2);
+        let polls = [false, false, false, true, false, true, true, true];
+        let restored: Vec<bool> = polls
+            .iter()
+            .map(|&online| {
+                link.observe(online);
+                link.confirmed_online()
+            })
+            .collect();
+        assert_eq!(
+            restored,
+            [false, false, false, false, false, false, true, true]
+        );
+    }
+
+    #[test]
+    fn thresholds_of_one_react_to_every_poll() {
+        let mut link = LinkHysteresis::new(1, 1);
+        link.observe(false);
+        assert!(link.confirmed_offline() && !link.confirmed_online());
+        link.observe(true);
+        assert!(link.confirmed_online() && !link.confirmed_offline());
+    }
 }
@@ -1,4 +1,6 @@
 use crate::config::Config;
+use crate::join::{JoinBackend, JoinTracker};
+use crate::nm::{self, VisibleNetwork};
 use crate::qr;
 use crate::status::{
     AttemptRecord, ProvisionRequest, now_rfc3339, read_last_attempt, read_last_ssid, redact_ssid,
@@ -6,7 +8,7 @@ use crate::status::{
 };
 use anyhow::{Context, Result};
 use axum::Router;
-use axum::extract::{Form, State};
+use axum::extract::{Form, Path, Query, State};
 use axum::http::{HeaderMap, StatusCode, header};
 use axum::response::{Html, IntoResponse, Json, Response};
 use axum::routing::{get, post};
@@ -16,9 +18,10 @@ use serde::Deserialize;
 use std::fs;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::net::TcpListener;
 use tokio::signal;
+use tokio::sync::Mutex;
 use tokio::time::sleep;
 use tracing::{info, warn};
 
@@ -28,19 +31,57 @@ use tracing::{info, warn};
 /// down the hotspot AP interface.
 const PROVISIONING_DELAY_SECS: u64 = 5;
 
+/// How long a Wi-Fi scan result is reused before `/networks` asks nmcli
+/// again, so page refreshes don't hammer NetworkManager.
+const SCAN_CACHE_SECS: u64 = 10;
+
 #[derive(Clone)]
 struct UiState {
     config: Arc<Config>,
+    scan_cache: Arc<Mutex<Option<(Instant, Vec<VisibleNetwork>)>>>,
+    joins: JoinTracker,
+}
+
+/// Hands credentials to the watcher through the request file and reads its
+/// progress back from the last-attempt record.
+struct WatcherFiles {
+    config: Arc<Config>,
+}
+
+impl JoinBackend for WatcherFiles {
+    fn submit(&self, request: ProvisionRequest) -> Result<()> {
+        // Write the request file after a delay so the response is fully
+        // delivered to the browser before the watcher begins provisioning and
+        // eventually tears down the hotspot AP interface.
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            sleep(Duration::from_secs(PROVISIONING_DELAY_SECS)).await;
+            if let Err(err) = write_request(&config, &request) {
+                warn!(error = ?err, "failed to write provisioning request after response delay");
+            }
+        });
+        Ok(())
+    }
+
+    fn last_attempt(&self) -> Result<Option<AttemptRecord>> {
+        read_last_attempt(&self.config)
+    }
 }
 
 pub async fn run_ui(config: Config) -> Result<()> {
+    let config = Arc::new(config);
     let state = UiState {
-        config: Arc::new(config),
+        config: config.clone(),
+        scan_cache: Arc::new(Mutex::new(None)),
+        joins: JoinTracker::new(Arc::new(WatcherFiles { config })),
     };
 
     let router = Router::new()
         .route("/", get(render_form))
         .route("/submit", post(handle_submit))
+        .route("/join", post(start_join))
+        .route("/join/{token}/status", get(join_status))
+        .route("/networks", get(list_networks))
         .route("/status", get(status_page))
         .route("/status.json", get(status_json))
         .route("/qr.png", get(serve_qr))
@@ -95,14 +136,46 @@ async fn render_form(State(state): State<UiState>) -> Html<String> {
     };
     let body = format!(
         "<!doctype html><html lang='en'><head><meta charset='utf-8'><meta name='viewport' content='width=device-width,initial-scale=1'>\
-<title>Photo Frame Wi-Fi Setup</title><style>{}</style></head><body><main><section class='hero'><h1>Photo Frame Wi-Fi Recovery</h1><p>Connect to the hotspot <strong>{}</strong> using the password shown on the frame, then submit your home Wi-Fi details below.</p></section><section class='form'><form method='post' action='/submit'><label>Wi-Fi Name (SSID)<input name='ssid' required maxlength='32'{}></label><label>Password<input name='password' type='password' minlength='8' maxlength='63' required></label><button type='submit'>Connect</button></form></section></main></body></html>",
+<title>Photo Frame Wi-Fi Setup</title><style>{}</style></head><body><main><section class='hero'><h1>Photo Frame Wi-Fi Recovery</h1><p>Connect to the hotspot <strong>{}</strong> using the password shown on the frame, then submit your home Wi-Fi details below.</p></section><section class='status' id='join-status' hidden><h1 id='join-title'></h1><p id='join-message'></p></section><section class='form' id='join-form'><form id='wifi-form' method='post' action='/submit'><label id='network-picker' hidden>Wi-Fi Network<span class='picker'><select id='network'></select><button type='button' id='rescan' class='secondary'>Rescan</button></span></label><label id='ssid-entry'>Wi-Fi Name (SSID)<input id='ssid' name='ssid' required maxlength='32'{}></label><label>Password<input name='password' type='password' minlength='8' maxlength='63' required></label><button type='submit'>Connect</button></form></section></main><script>{}{}</script></body></html>",
         styles(),
         html_escape(&state.config.hotspot.ssid),
-        ssid_value
+        ssid_value,
+        network_picker_script(),
+        join_script()
     );
     Html(body)
 }
 
+#[derive(Deserialize)]
+struct NetworksQuery {
+    #[serde(default)]
+    rescan: bool,
+}
+
+async fn list_networks(
+    State(state): State<UiState>,
+    Query(query): Query<NetworksQuery>,
+) -> Response {
+    let mut cache = state.scan_cache.lock().await;
+    if !query.rescan
+        && let Some((scanned_at, networks)) = cache.as_ref()
+        && scanned_at.elapsed() < Duration::from_secs(SCAN_CACHE_SECS)
+    {
+        return Json(networks.clone()).into_response();
+    }
+    match nm::scan_networks(&state.config.interface, query.rescan).await {
+        Ok(networks) => {
+            info!(count = networks.len(), "wifi scan completed");
+            *cache = Some((Instant::now(), networks.clone()));
+            Json(networks).into_response()
+        }
+        Err(err) => {
+            warn!(error = ?err, "wifi scan failed");
+            (StatusCode::SERVICE_UNAVAILABLE, "scan unavailable").into_response()
+        }
+    }
+}
+
 /// Reject cross-origin POSTs (CSRF defense). Browsers send `Origin` on a
 /// cross-origin form post; when present, its authority must match the request's
 /// `Host`. We fall back to `Referer`, and allow requests carrying neither header
@@ -136,16 +209,9 @@ async fn handle_submit(
     info!(ssid = %redact_ssid(&ssid), "received provisioning form submission");
     match prepare_submission(&state.config, &form).await {
         Ok((request, message)) => {
-            // Write the request file after a delay so the success page is
-            // fully delivered to the browser before the watcher begins
-            // provisioning and eventually tears down the hotspot AP interface.
-            let config = state.config.clone();
-            tokio::spawn(async move {
-                sleep(Duration::from_secs(PROVISIONING_DELAY_SECS)).await;
-                if let Err(err) = write_request(&config, &request) {
-                    warn!(error = ?err, "failed to write provisioning request after response delay");
-                }
-            });
+            if let Err(err) = state.joins.start(request, Instant::now()) {
+                warn!(error = ?err, "failed to start provisioning");
+            }
             // Connection: close tells the browser to finalize and close the
             // TCP connection rather than keeping it alive.  This prevents iOS
             // from showing a spinner when the AP interface disappears while an
@@ -158,25 +224,72 @@ async fn handle_submit(
             resp
         }
         Err(err) => {
-            warn!(error = ?err, "wifi submission failed");
-            let display =
-                "We could not queue those settings. Check the password and try again.".to_string();
-            let _ = write_last_attempt(
-                &state.config,
-                &AttemptRecord {
-                    timestamp: now_rfc3339().unwrap_or_else(|_| "unknown".to_string()),
-                    status: "error".to_string(),
-                    message: display.clone(),
-                    ssid: redact_ssid(&ssid),
-                    attempt_id: None,
-                    error: Some(err.to_string()),
-                },
-            );
+            let display = record_rejected_submission(&state.config, &ssid, &err);
             Html(error_page(&display)).into_response()
         }
     }
 }
 
+/// Starts a join for the inline script on the setup page, which then polls
+/// `/join/<token>/status` until the frame has joined or given up.
+async fn start_join(
+    State(state): State<UiState>,
+    headers: HeaderMap,
+    Form(form): Form<WifiForm>,
+) -> Response {
+    if !is_same_origin(&headers) {
+        warn!("rejected cross-origin /join request (possible CSRF)");
+        return (StatusCode::FORBIDDEN, "Cross-origin request rejected.").into_response();
+    }
+    info!(ssid = %redact_ssid(&form.ssid), "received join request");
+    let started = match prepare_submission(&state.config, &form).await {
+        Ok((request, _)) => state.joins.start(request, Instant::now()),
+        Err(err) => Err(err),
+    };
+    match started {
+        Ok(token) => (
+            StatusCode::ACCEPTED,
+            Json(serde_json::json!({ "token": token })),
+        )
+            .into_response(),
+        Err(err) => {
+            let display = record_rejected_submission(&state.config, &form.ssid, &err);
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": display })),
+            )
+                .into_response()
+        }
+    }
+}
+
+async fn join_status(State(state): State<UiState>, Path(token): Path<String>) -> Response {
+    match state.joins.status(&token, Instant::now()) {
+        Some(join) => Json(join).into_response(),
+        None => (StatusCode::NOT_FOUND, "unknown join").into_response(),
+    }
+}
+
+/// Records a submission that never reached the watcher and returns the
+/// message to show for it.
+fn record_rejected_submission(config: &Config, ssid: &str, err: &anyhow::Error) -> String {
+    warn!(error = ?err, "wifi submission failed");
+    let display =
+        "We could not queue those settings. Check the password and try again.".to_string();
+    let _ = write_last_attempt(
+        config,
+        &AttemptRecord {
+            timestamp: now_rfc3339().unwrap_or_else(|_| "unknown".to_string()),
+            status: "error".to_string(),
+            message: display.clone(),
+            ssid: redact_ssid(ssid),
+            attempt_id: None,
+            error: Some(err.to_string()),
+        },
+    );
+    display
+}
+
 /// Validate form inputs and record a "queued" attempt status.
 /// Returns the `ProvisionRequest` to be written after the success page is
 /// delivered, and the human-readable message for the success page.
@@ -332,8 +445,29 @@ fn html_escape(s: &str) -> String {
     out
 }
 
+/// Progressive enhancement for the setup form: fills a dropdown from
+/// `/networks` and keeps the free-text SSID field for hidden networks (or when
+/// scripting or the scan is unavailable).
+fn network_picker_script() -> &'static str {
+    "(function(){var picker=document.getElementById('network-picker'),select=document.getElementById('network'),entry=document.getElementById('ssid-entry'),ssid=document.getElementById('ssid'),rescan=document.getElementById('rescan'),OTHER='__other__';\
+function sync(){var manual=select.value===OTHER;entry.hidden=!manual;if(!manual){ssid.value=select.value;}}\
+function load(force){rescan.disabled=true;fetch('/networks'+(force?'?rescan=true':'')).then(function(r){if(!r.ok){throw new Error(r.status);}return r.json();}).then(function(list){var current=ssid.value;select.textContent='';list.forEach(function(n){var o=document.createElement('option');o.value=n.ssid;o.textContent=n.ssid+' ('+n.signal+'%'+(n.security==='open'?', open':'')+')';select.appendChild(o);});var other=document.createElement('option');other.value=OTHER;other.textContent='Other / hidden network\u{2026}';select.appendChild(other);select.value=list.some(function(n){return n.ssid===current;})?current:(current?OTHER:(list.length?list[0].ssid:OTHER));picker.hidden=false;sync();}).catch(function(){picker.hidden=true;entry.hidden=false;}).finally(function(){rescan.disabled=false;});}\
+select.addEventListener('change',sync);rescan.addEventListener('click',function(){load(true);});load(false);})();"
+}
+
+/// Submits the form through `/join` and follows the attempt in place, so the
+/// user sees a wrong password or missing network without reloading. Without
+/// scripting the form posts to `/submit` as before.
+fn join_script() -> &'static str {
+    "(function(){var form=document.getElementById('wifi-form'),formSection=document.getElementById('join-form'),box=document.getElementById('join-status'),title=document.getElementById('join-title'),message=document.getElementById('join-message'),button=form.querySelector('button[type=submit]');\
+function show(heading,text,failed){title.textContent=heading;message.textContent=text;box.hidden=false;box.classList.toggle('error',!!failed);}\
+function gone(){formSection.hidden=true;show('The hotspot has shut down','The frame switched off its hotspot to join your Wi-Fi. If it connects, the recovery screen closes and the slideshow resumes. If it fails, the hotspot comes back and you can try again.');}\
+function poll(token){fetch('/join/'+encodeURIComponent(token)+'/status',{cache:'no-store'}).then(function(r){if(!r.ok){throw new Error(r.status);}return r.json();}).then(function(s){if(s.state==='connected'){formSection.hidden=true;show('Connected!','The frame has joined your Wi-Fi. The hotspot is about to shut down; you can rejoin your home network now.');}else if(s.state==='failed'){formSection.hidden=false;button.disabled=false;show('Could not connect',s.reason,true);}else{setTimeout(function(){poll(token);},2000);}}).catch(gone);}\
+form.addEventListener('submit',function(e){e.preventDefault();button.disabled=true;show('Connecting to your network\u{2026}','The hotspot may drop for a moment while the frame tries your Wi-Fi.');fetch('/join',{method:'POST',body:new URLSearchParams(new FormData(form))}).then(function(r){return r.json().then(function(b){if(!r.ok){throw b.error||'Check the details and try again.';}return b.token;});}).then(poll,function(err){button.disabled=false;show('Check and try again',typeof err==='string'?err:'The frame did not answer. Try again.',true);});});})();"
+}
+
 fn styles() -> &'static str {
-    "body{font-family:'Inter',system-ui,sans-serif;margin:0;background:#0b1d26;color:#f7f9fb;}main{max-width:720px;margin:0 auto;padding:3rem 1.5rem;}section.hero{background:#132b3a;padding:2rem;border-radius:18px;margin-bottom:2rem;box-shadow:0 20px 45px rgba(0,0,0,0.25);}section.hero h1{margin-top:0;font-size:2rem;}section.hero p{line-height:1.6;}section.hero .qr{display:block;margin:1.5rem auto;width:220px;height:220px;background:#fff;padding:12px;border-radius:12px;box-shadow:0 10px 20px rgba(0,0,0,0.2);}section.form{background:#132b3a;padding:2rem;border-radius:18px;box-shadow:0 20px 45px rgba(0,0,0,0.25);}section.form form{display:flex;flex-direction:column;gap:1rem;}label{display:flex;flex-direction:column;font-weight:600;}input{margin-top:0.4rem;padding:0.75rem;border-radius:12px;border:none;background:#0b1d26;color:#f7f9fb;font-size:1rem;}button{padding:0.85rem;border:none;border-radius:14px;font-size:1.05rem;font-weight:700;background:linear-gradient(135deg,#4cc9f0,#4361ee);color:#fff;cursor:pointer;box-shadow:0 14px 28px rgba(67,97,238,0.35);}button:hover{filter:brightness(1.05);}p.status-link{text-align:center;margin-top:1.5rem;}p.status-link a{color:#4cc9f0;text-decoration:none;font-weight:600;}section.status{background:#132b3a;padding:2rem;border-radius:18px;box-shadow:0 20px 45px rgba(0,0,0,0.25);}section.status.error{border:2px solid #ef476f;}section.status h1{margin-top:0;font-size:1.8rem;}section.status p{line-height:1.6;}p.back a{color:#4cc9f0;text-decoration:none;font-weight:600;}@media (max-width:600px){main{padding:2rem 1rem;}section.hero,section.form,section.status{padding:1.5rem;}}"
+    "body{font-family:'Inter',system-ui,sans-serif;margin:0;background:#0b1d26;color:#f7f9fb;}main{max-width:720px;margin:0 auto;padding:3rem 1.5rem;}section.hero{background:#132b3a;padding:2rem;border-radius:18px;margin-bottom:2rem;box-shadow:0 20px 45px rgba(0,0,0,0.25);}section.hero h1{margin-top:0;font-size:2rem;}section.hero p{line-height:1.6;}section.hero .qr{display:block;margin:1.5rem auto;width:220px;height:220px;background:#fff;padding:12px;border-radius:12px;box-shadow:0 10px 20px rgba(0,0,0,0.2);}section.form{background:#132b3a;padding:2rem;border-radius:18px;box-shadow:0 20px 45px rgba(0,0,0,0.25);}section.form form{display:flex;flex-direction:column;gap:1rem;}label{display:flex;flex-direction:column;font-weight:600;}input{margin-top:0.4rem;padding:0.75rem;border-radius:12px;border:none;background:#0b1d26;color:#f7f9fb;font-size:1rem;}button{padding:0.85rem;border:none;border-radius:14px;font-size:1.05rem;font-weight:700;background:linear-gradient(135deg,#4cc9f0,#4361ee);color:#fff;cursor:pointer;box-shadow:0 14px 28px rgba(67,97,238,0.35);}button:hover{filter:brightness(1.05);}select{flex:1;margin-top:0.4rem;padding:0.75rem;border-radius:12px;border:none;background:#0b1d26;color:#f7f9fb;font-size:1rem;}span.picker{display:flex;gap:0.6rem;align-items:flex-end;}button.secondary{padding:0.75rem 1rem;font-size:0.95rem;background:#1f4257;box-shadow:none;}[hidden]{display:none!important;}p.status-link{text-align:center;margin-top:1.5rem;}p.status-link a{color:#4cc9f0;text-decoration:none;font-weight:600;}section.status{background:#132b3a;padding:2rem;border-radius:18px;box-shadow:0 20px 45px rgba(0,0,0,0.25);}section.status.error{border:2px solid #ef476f;}section.status h1{margin-top:0;font-size:1.8rem;}section.status p{line-height:1.6;}p.back a{color:#4cc9f0;text-decoration:none;font-weight:600;}@media (max-width:600px){main{padding:2rem 1rem;}section.hero,section.form,section.status{padding:1.5rem;}}"
 }
 
 #[cfg(test)]
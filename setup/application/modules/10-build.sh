@@ -52,6 +52,19 @@ if [[ -z "${RUSTFLAGS:-}" ]]; then
     log INFO "RUSTFLAGS not set; defaulting to '-C target-cpu=native'"
 fi
 
+# Optional cargo features (e.g. PHOTOFRAME_FEATURES=heif for iPhone photos).
+feature_args=()
+if [[ -n "${PHOTOFRAME_FEATURES:-}" ]]; then
+    feature_args=(--features "photoframe/${PHOTOFRAME_FEATURES//,/,photoframe/}")
+    log INFO "Enabling photoframe features: ${PHOTOFRAME_FEATURES}"
+fi
+# Optional buttond features (e.g. BUTTOND_FEATURES=logind to react to resume
+# from suspend at once).
+if [[ -n "${BUTTOND_FEATURES:-}" ]]; then
+    feature_args+=(--features "buttond/${BUTTOND_FEATURES//,/,buttond/}")
+    log INFO "Enabling buttond features: ${BUTTOND_FEATURES}"
+fi
+
 log INFO "Building workspace binaries with cargo ${profile_flag[*]}"
 cd "${REPO_ROOT}"
 # Constrain parallelism on lower-memory devices to reduce OOM risk.
@@ -83,7 +96,7 @@ else
     fi
 fi
 
-cargo build --workspace --bins "${profile_flag[@]}" "${cargo_jobs_args[@]}"
+cargo build --workspace --bins "${profile_flag[@]}" "${feature_args[@]}" "${cargo_jobs_args[@]}"
 
 if [[ -d "${REPO_ROOT}/target" ]]; then
     if find "${REPO_ROOT}/target" -maxdepth 2 -user root -print -quit | grep -q .; then
@@ -22,6 +22,9 @@ PACKAGES=(
     libclang-dev
     libdrm2
     libgbm1
+    # HEIC/HEIF decoding; only linked when building with
+    # PHOTOFRAME_FEATURES=heif, but cheap enough to always install.
+    libheif-dev
     libinput10
     libssl-dev
     libudev-dev